@@ -0,0 +1,115 @@
+//! Costs of building and serializing a request with a long history, as every send does.
+//!
+//! Run with `cargo bench --bench request_building`.
+
+use criterion::{black_box, criterion_group, criterion_main, Criterion};
+use open_ai_rust_responses_by_sshift::{InputItem, Model, Request, Response, Tool};
+
+const HISTORY_LEN: usize = 50;
+
+fn history() -> Vec<InputItem> {
+    (0..HISTORY_LEN)
+        .map(|turn| {
+            let role = if turn % 2 == 0 { "user" } else { "assistant" };
+            InputItem::message(
+                role,
+                vec![InputItem::content_text(format!(
+                    "Turn {turn}: {}",
+                    "a fairly long message body ".repeat(20)
+                ))],
+            )
+        })
+        .collect()
+}
+
+fn tools() -> Vec<Tool> {
+    (0..8)
+        .map(|i| {
+            Tool::function(
+                format!("tool_{i}"),
+                "Looks something up",
+                serde_json::json!({
+                    "type": "object",
+                    "properties": {
+                        "query": {"type": "string", "description": "What to look up"},
+                        "limit": {"type": "integer", "minimum": 1, "maximum": 100},
+                        "filters": {"type": "array", "items": {"type": "string"}}
+                    },
+                    "required": ["query"]
+                }),
+            )
+        })
+        .collect()
+}
+
+fn request() -> Request {
+    Request::builder()
+        .model(Model::GPT4oMini)
+        .input_items(history())
+        .tools(tools())
+        .build()
+}
+
+fn response_with_calls() -> Response {
+    let output: Vec<_> = (0..20)
+        .map(|i| {
+            serde_json::json!({
+                "type": "function_call", "id": format!("fc_{i}"), "call_id": format!("call_{i}"),
+                "name": "lookup", "arguments": r#"{"query":"weather in Paris","limit":5}"#,
+                "status": "completed"
+            })
+        })
+        .collect();
+    serde_json::from_value(serde_json::json!({
+        "id": "resp_1", "object": "response", "created_at": 1, "model": "gpt-4o-mini",
+        "status": "completed", "output": output
+    }))
+    .expect("valid response")
+}
+
+fn request_building(c: &mut Criterion) {
+    c.bench_function("build_request_50_items", |b| {
+        b.iter_batched(
+            || (history(), tools()),
+            |(history, tools)| {
+                Request::builder()
+                    .model(Model::GPT4oMini)
+                    .input_items(history)
+                    .tools(tools)
+                    .build()
+            },
+            criterion::BatchSize::SmallInput,
+        );
+    });
+
+    let request = request();
+    c.bench_function("serialize_request_50_items", |b| {
+        b.iter(|| black_box(serde_json::to_vec(black_box(&request)).expect("serializable")));
+    });
+    c.bench_function("build_and_serialize_request_50_items", |b| {
+        b.iter_batched(
+            || (history(), tools()),
+            |(history, tools)| {
+                let request = Request::builder()
+                    .model(Model::GPT4oMini)
+                    .input_items(history)
+                    .tools(tools)
+                    .build_validated()
+                    .expect("valid request");
+                serde_json::to_vec(&request).expect("serializable")
+            },
+            criterion::BatchSize::SmallInput,
+        );
+    });
+
+    let response = response_with_calls();
+    let mut calls = c.benchmark_group("tool_calls_20");
+    calls.bench_function("owned", |b| b.iter(|| black_box(response.tool_calls())));
+    calls.bench_function("borrowed", |b| {
+        b.iter(|| black_box(response.tool_call_refs()));
+    });
+    calls.finish();
+}
+
+criterion_group!(benches, request_building);
+criterion_main!(benches);
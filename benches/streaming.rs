@@ -0,0 +1,62 @@
+//! Throughput of parsing a Responses stream body.
+//!
+//! Run with `cargo bench --bench streaming --features stream`. A canned 10k-event transcript,
+//! mostly text and function argument deltas like a real stream, goes through
+//! [`Responses::process_stream_line`]. `json_value_only` only parses each event into a
+//! `serde_json::Value`, which every event paid before the common delta events were
+//! deserialized directly.
+
+use criterion::{black_box, criterion_group, criterion_main, Criterion, Throughput};
+use open_ai_rust_responses_by_sshift::responses::Responses;
+
+const EVENTS: usize = 10_000;
+
+/// Events as the API sends them, `type` first
+fn transcript() -> Vec<String> {
+    let mut lines = vec![
+        r#"data: {"type":"response.created","sequence_number":0,"response":{"id":"resp_1","object":"response","status":"in_progress","output":[]}}"#.to_string(),
+    ];
+    for i in 1..EVENTS - 1 {
+        lines.push(match i % 10 {
+            0 => format!(
+                r#"data: {{"type":"response.function_call_arguments.delta","sequence_number":{i},"item_id":"fc_1","output_index":1,"delta":"{{\"city\": \"Par"}}"#
+            ),
+            5 => format!(
+                r#"data: {{"type":"response.output_text.delta","sequence_number":{i},"item_id":"msg_1","output_index":0,"content_index":0,"delta":"line one\nline \"two\"","logprobs":[]}}"#
+            ),
+            7 => ": ping".to_string(),
+            _ => format!(
+                r#"data: {{"type":"response.output_text.delta","sequence_number":{i},"item_id":"msg_1","output_index":0,"content_index":0,"delta":" token","logprobs":[]}}"#
+            ),
+        });
+    }
+    lines.push(format!(
+        r#"data: {{"type":"response.completed","sequence_number":{EVENTS},"response":{{"id":"resp_1","object":"response","status":"completed","output":[],"usage":{{"input_tokens":12,"output_tokens":9000,"total_tokens":9012}}}}}}"#
+    ));
+    lines
+}
+
+fn streaming(c: &mut Criterion) {
+    let lines = transcript();
+    let mut group = c.benchmark_group("stream_10k_events");
+    group.throughput(Throughput::Elements(EVENTS as u64));
+    group.bench_function("process_stream_line", |b| {
+        b.iter(|| {
+            for line in &lines {
+                black_box(Responses::process_stream_line(line));
+            }
+        });
+    });
+    group.bench_function("json_value_only", |b| {
+        b.iter(|| {
+            for line in &lines {
+                let data = line.strip_prefix("data: ").unwrap_or(line);
+                black_box(serde_json::from_str::<serde_json::Value>(data).ok());
+            }
+        });
+    });
+    group.finish();
+}
+
+criterion_group!(benches, streaming);
+criterion_main!(benches);
@@ -0,0 +1,43 @@
+//! Blocking example: calling the API from a plain `fn main()` without an async runtime
+//!
+//! Run with: `cargo run --example blocking --features blocking,stream`
+//!
+//! Make sure to set your OpenAI API key:
+//! ```bash
+//! export OPENAI_API_KEY=sk-your-api-key-here
+//! ```
+
+use open_ai_rust_responses_by_sshift::blocking::Client;
+use open_ai_rust_responses_by_sshift::{Model, Request};
+
+fn main() -> Result<(), Box<dyn std::error::Error>> {
+    let client = Client::from_env()?;
+
+    let request = Request::builder()
+        .model(Model::GPT4oMini)
+        .input("Tell me a short joke about programming")
+        .max_output_tokens(200)
+        .build();
+    let response = client.responses.create(request)?;
+    println!("📝 {}", response.output_text());
+
+    #[cfg(feature = "stream")]
+    {
+        use std::io::Write;
+
+        let request = Request::builder()
+            .model(Model::GPT4oMini)
+            .input("Count from one to five")
+            .build();
+        print!("🌊 ");
+        for event in client.responses.stream(request) {
+            if let Some(delta) = event?.as_text_delta() {
+                print!("{delta}");
+                std::io::stdout().flush()?;
+            }
+        }
+        println!();
+    }
+
+    Ok(())
+}
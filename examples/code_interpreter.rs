@@ -118,8 +118,11 @@ fn item_type_name(item: &ResponseItem) -> &'static str {
         ResponseItem::ImageGenerationCall { .. } => "ImageGenerationCall",
         ResponseItem::CodeInterpreterCall { .. } => "CodeInterpreterCall",
         ResponseItem::FunctionCall { .. } => "FunctionCall",
+        ResponseItem::FunctionCallOutput { .. } => "FunctionCallOutput",
+        ResponseItem::CustomToolCall { .. } => "CustomToolCall",
         ResponseItem::Text { .. } => "Text",
         #[allow(deprecated)]
         ResponseItem::ToolCall(_) => "ToolCall",
+        ResponseItem::Unknown(_) => "Unknown",
     }
 }
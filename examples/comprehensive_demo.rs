@@ -20,6 +20,9 @@
 //! Setup:
 //! 1. Create a `.env` file in the project root with: OPENAI_API_KEY=sk-your-api-key-here
 //! 2. Run with: `cargo run --example comprehensive_demo --features stream`
+//!
+//! Without an API key, run it against the in-process mock server instead:
+//! `cargo run --example comprehensive_demo --features stream,mock-server`
 
 use base64::Engine;
 use dotenv::dotenv;
@@ -49,7 +52,20 @@ async fn main() -> Result<(), Box<dyn std::error::Error>> {
     println!("🚀 OpenAI Rust Responses API - Comprehensive Demo (Enhanced Edition)");
     println!("====================================================================\n");
 
-    // Create client from environment variable
+    // Create client from environment variable. Without OPENAI_API_KEY, the `mock-server`
+    // feature runs the demo against canned responses instead.
+    #[cfg(feature = "mock-server")]
+    let (client, _mock) = if std::env::var("OPENAI_API_KEY").is_ok() {
+        (Client::from_env()?, None)
+    } else {
+        let mock = open_ai_rust_responses_by_sshift::mock::spawn()?;
+        println!(
+            "🧪 OPENAI_API_KEY not set, using the mock server at {}\n",
+            mock.base_url()
+        );
+        (mock.client()?, Some(mock))
+    };
+    #[cfg(not(feature = "mock-server"))]
     let client = Client::from_env()?;
 
     // 1. BASIC RESPONSE WITH ENHANCED MONITORING
@@ -170,7 +186,7 @@ async fn main() -> Result<(), Box<dyn std::error::Error>> {
                             full_response.push_str(&content);
                             event_count += 1;
                         }
-                        StreamEvent::ImageProgress { url, index } => {
+                        StreamEvent::ImageProgress { url, index, .. } => {
                             image_events += 1;
                             if let Some(progress_url) = url {
                                 println!(
@@ -316,6 +332,7 @@ async fn main() -> Result<(), Box<dyn std::error::Error>> {
     let add_file_request = AddFileToVectorStoreRequest {
         file_id: file.id.clone(),
         attributes: None,
+        chunking_strategy: None,
     };
 
     let _file_result = client
@@ -330,10 +347,8 @@ async fn main() -> Result<(), Box<dyn std::error::Error>> {
 
     // Search vector store (direct API call)
     println!("🔍 Searching vector store directly...");
-    let search_request = SearchVectorStoreRequest {
-        query: "programming principles".to_string(),
-        max_num_results: Some(3),
-    };
+    let search_request =
+        SearchVectorStoreRequest::new("programming principles").with_max_num_results(3);
 
     match client
         .vector_stores
@@ -1010,15 +1025,11 @@ async fn main() -> Result<(), Box<dyn std::error::Error>> {
         .delete_file(&vector_store.id, &file.id)
         .await
     {
-        Ok(delete_response) => {
+        Ok(outcome) => {
             println!("✅ Vector store file deletion API works correctly");
             println!(
-                "   File '{}' removed from vector store successfully",
-                delete_response.id
-            );
-            println!(
-                "   Deletion confirmed: {} | Object type: {}",
-                delete_response.deleted, delete_response.object
+                "   File '{}' removed from vector store: {outcome:?}",
+                file.id
             );
             println!("   Note: File still exists in Files API - only removed from vector store");
         }
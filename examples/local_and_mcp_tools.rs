@@ -112,5 +112,18 @@ async fn main() -> Result<(), Box<dyn std::error::Error>> {
     // Call MCP tool (if one existed with this name)
     // let mcp_result = registry.call_tool("mcp_tool_name", json!({})).await?;
 
+    // 6. Let the model drive the tools
+    // `run_tools` answers function calls until the model is done. With
+    // `parallel_tool_calls(false)` the calls run one at a time, in order.
+    // let openai = Client::from_env()?;
+    // let request = Request::builder()
+    //     .model(Model::GPT4o)
+    //     .input("What is 12 * 7?")
+    //     .tools(registry.list_tools().await?)
+    //     .parallel_tool_calls(false)
+    //     .build();
+    // let (response, report) = registry.run_tools(&openai.responses, request, 5).await?;
+    // println!("{} ({} tool calls)", response.output_text(), report.calls.len());
+
     Ok(())
 }
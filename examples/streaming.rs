@@ -75,30 +75,42 @@ async fn main() -> Result<(), Box<dyn std::error::Error>> {
                         response_id = Some(id.clone());
                         println!("\n📝 Response ID: {id}");
                     }
-                    StreamEvent::TextDelta { content, index: _ } => {
+                    StreamEvent::TextDelta { content, .. } => {
                         print!("{content}");
                         std::io::Write::flush(&mut std::io::stdout())?; // Flush to show immediately
                         total_chunks += 1;
                         total_chars += content.len();
                     }
-                    StreamEvent::TextStop { index } => {
+                    StreamEvent::TextStop { index, .. } => {
                         println!("\n📝 Text stream {index} stopped");
                     }
-                    StreamEvent::ToolCallCreated { id, name, index: _ } => {
+                    StreamEvent::ToolCallCreated { id, name, .. } => {
                         println!("\n🛠️ Tool call created: {name} ({id})");
                         tool_calls += 1;
                     }
-                    StreamEvent::ToolCallDelta {
-                        content,
-                        id: _,
-                        index: _,
-                    } => {
+                    StreamEvent::ToolCallDelta { content, .. } => {
                         print!("{content}");
                         std::io::Write::flush(&mut std::io::stdout())?;
                     }
-                    StreamEvent::ToolCallCompleted { id, index: _ } => {
+                    StreamEvent::ToolCallCompleted { id, .. } => {
                         println!("\n✅ Tool call completed: {id}");
                     }
+                    StreamEvent::McpCallStarted {
+                        server_label, name, ..
+                    } => {
+                        println!("\n🔌 MCP call started: {server_label}/{name}");
+                        tool_calls += 1;
+                    }
+                    StreamEvent::McpCallCompleted { id, .. } => {
+                        println!("\n✅ MCP call completed: {id}");
+                    }
+                    StreamEvent::McpCallFailed { id, error } => {
+                        println!("\n❌ MCP call {id} failed: {error:?}");
+                    }
+                    StreamEvent::McpListToolsCompleted { tools } => {
+                        println!("\n🔌 MCP server lists {} tools", tools.len());
+                    }
+                    StreamEvent::McpCallArgumentsDelta { .. } => {}
                     // Note: ImageProgress event is for the deprecated partials tool.
                     // The new built-in tool returns the full image in an ImageGenerationCall.
                     // This event is kept for backward compatibility tests but won't be triggered by Tool::image_generation().
@@ -110,6 +122,18 @@ async fn main() -> Result<(), Box<dyn std::error::Error>> {
                         println!("\n\n🏁 Stream completed!");
                         break;
                     }
+                    StreamEvent::Failed { error } => {
+                        error_events += 1;
+                        println!("\n❌ Response failed: {} ({})", error.message, error.code);
+                        break;
+                    }
+                    StreamEvent::Incomplete { reason } => {
+                        println!("\n⚠️ Response incomplete: {reason:?}");
+                        break;
+                    }
+                    StreamEvent::SessionReset { message } => {
+                        println!("\n🔄 {message}");
+                    }
                     StreamEvent::Chunk => {
                         // Heartbeat - just continue
                     }
@@ -130,7 +154,7 @@ async fn main() -> Result<(), Box<dyn std::error::Error>> {
                     if e.is_recoverable() {
                         println!("      🔄 This error is recoverable");
                         if let Some(retry_after) = e.retry_after() {
-                            println!("      ⏱️ Suggested retry delay: {retry_after}s");
+                            println!("      ⏱️ Suggested retry delay: {retry_after:?}");
                         }
                     } else {
                         println!("      ❌ This error is not recoverable");
@@ -177,13 +201,18 @@ async fn main() -> Result<(), Box<dyn std::error::Error>> {
         let text_event = StreamEvent::TextDelta {
             content: "Sample text".to_string(),
             index: 0,
+            content_index: 0,
         };
         let image_event = StreamEvent::ImageProgress {
             url: Some("https://example.com/partial-image-1.jpg".to_string()),
             index: 0,
+            partial_image_b64: None,
+            partial_image_index: Some(0),
         };
         let tool_event = StreamEvent::ToolCallDelta {
             id: "call_123".to_string(),
+            call_id: None,
+            name: None,
             content: "tool output".to_string(),
             index: 0,
         };
@@ -0,0 +1,444 @@
+//! Synchronous client for programs without an async runtime.
+//!
+//! [`Client`] wraps an async [`crate::Client`] of its own and drives its futures on a
+//! current-thread tokio runtime that is created with the client and shared by every clone.
+//! Calls can be made from a plain `fn main()`:
+//!
+//! ```rust,no_run
+//! use open_ai_rust_responses_by_sshift::blocking::Client;
+//! use open_ai_rust_responses_by_sshift::{Model, Request};
+//!
+//! fn main() -> Result<(), Box<dyn std::error::Error>> {
+//!     let client = Client::from_env()?;
+//!     let request = Request::builder()
+//!         .model(Model::GPT4oMini)
+//!         .input("Name a prime number")
+//!         .build();
+//!     println!("{}", client.responses.create(request)?.output_text());
+//!     Ok(())
+//! }
+//! ```
+//!
+//! Calling the blocking client from inside an async runtime does not panic: the call
+//! is driven from a helper thread instead. It still blocks the calling task, so async
+//! code should use the async client.
+//!
+//! The wrapped client never shares its connection pool with async code: a connection
+//! opened on one runtime stops working once that runtime is blocked or shut down.
+
+use crate::files::{File, FilePurpose};
+use crate::images::{ImageGenerateRequest, ImageGenerateResponse};
+use crate::types::{DeleteOutcome, PaginatedList, PaginationParams};
+use crate::vector_stores::{SearchVectorStoreRequest, SearchVectorStoreResponse};
+use crate::{ClientBuilder, CreateError, Request, Response, Result};
+use std::future::Future;
+use std::path::Path;
+use std::sync::Arc;
+
+/// Runtime shared by a blocking client and its endpoint handles
+#[derive(Debug, Clone)]
+struct Runtime(Arc<RuntimeCell>);
+
+#[derive(Debug)]
+struct RuntimeCell(Option<tokio::runtime::Runtime>);
+
+impl Drop for RuntimeCell {
+    fn drop(&mut self) {
+        // A plain drop panics if the last clone goes away inside an async runtime
+        if let Some(runtime) = self.0.take() {
+            runtime.shutdown_background();
+        }
+    }
+}
+
+impl Runtime {
+    fn new() -> std::result::Result<Self, CreateError> {
+        let runtime = tokio::runtime::Builder::new_current_thread()
+            .enable_all()
+            .build()
+            .map_err(CreateError::Runtime)?;
+        Ok(Self(Arc::new(RuntimeCell(Some(runtime)))))
+    }
+
+    /// Runs `future` to completion on the shared runtime
+    fn block_on<F>(&self, future: F) -> F::Output
+    where
+        F: Future + Send,
+        F::Output: Send,
+    {
+        // Only `Drop` takes the runtime out, so it is always there while `self` is alive
+        let runtime = (self.0).0.as_ref().expect("runtime taken before drop");
+        if tokio::runtime::Handle::try_current().is_err() {
+            return runtime.block_on(future);
+        }
+        // Starting a runtime inside another one panics, so drive it from a fresh thread
+        std::thread::scope(|scope| {
+            scope
+                .spawn(|| runtime.block_on(future))
+                .join()
+                .unwrap_or_else(|panic| std::panic::resume_unwind(panic))
+        })
+    }
+}
+
+/// Blocking counterpart of [`crate::Client`] for the core operations
+#[derive(Debug, Clone)]
+pub struct Client {
+    /// Responses API endpoints
+    pub responses: Responses,
+
+    /// Files API endpoints
+    pub files: Files,
+
+    /// Images API endpoints
+    pub images: Images,
+
+    /// Vector stores API endpoints
+    pub vector_stores: VectorStores,
+}
+
+impl Client {
+    /// Creates a new client with the given API key
+    ///
+    /// # Errors
+    ///
+    /// Same as [`crate::Client::new`], or `CreateError::Runtime` if the runtime cannot start
+    pub fn new(api_key: &str) -> std::result::Result<Self, CreateError> {
+        Self::wrap(crate::Client::new(api_key)?)
+    }
+
+    /// Creates a new client with the given API key and base URL
+    ///
+    /// # Errors
+    ///
+    /// Same as [`crate::Client::new_with_base_url`], or `CreateError::Runtime` if the
+    /// runtime cannot start
+    pub fn new_with_base_url(
+        api_key: &str,
+        base_url: &str,
+    ) -> std::result::Result<Self, CreateError> {
+        Self::wrap(crate::Client::new_with_base_url(api_key, base_url)?)
+    }
+
+    /// Creates a client from the `OPENAI_API_KEY` environment variable
+    ///
+    /// # Errors
+    ///
+    /// Same as [`crate::Client::from_env`], or `CreateError::Runtime` if the runtime cannot
+    /// start
+    pub fn from_env() -> std::result::Result<Self, CreateError> {
+        Self::wrap(crate::Client::from_env()?)
+    }
+
+    /// Wraps an async client that nothing else uses
+    fn wrap(inner: crate::Client) -> std::result::Result<Self, CreateError> {
+        let runtime = Runtime::new()?;
+        Ok(Self {
+            responses: Responses {
+                inner: inner.responses,
+                runtime: runtime.clone(),
+            },
+            files: Files {
+                inner: inner.files,
+                runtime: runtime.clone(),
+            },
+            images: Images {
+                inner: inner.images,
+                runtime: runtime.clone(),
+            },
+            vector_stores: VectorStores {
+                inner: inner.vector_stores,
+                runtime,
+            },
+        })
+    }
+}
+
+/// Builds a client with any [`crate::Client::builder`] configuration.
+///
+/// The builder is built again here, so the blocking client gets its own HTTP client even
+/// when an async client was built from the same builder. A preconfigured
+/// [`http_client`](ClientBuilder::http_client) is used as given and should not also be
+/// used from async code.
+impl TryFrom<ClientBuilder> for Client {
+    type Error = CreateError;
+
+    fn try_from(builder: ClientBuilder) -> std::result::Result<Self, CreateError> {
+        Self::wrap(builder.build()?)
+    }
+}
+
+/// Blocking counterpart of [`crate::responses::Responses`]
+#[derive(Debug, Clone)]
+pub struct Responses {
+    inner: crate::responses::Responses,
+    runtime: Runtime,
+}
+
+impl Responses {
+    /// Creates a response.
+    ///
+    /// # Errors
+    ///
+    /// Same as [`crate::responses::Responses::create`]
+    pub fn create(&self, request: Request) -> Result<Response> {
+        self.runtime.block_on(self.inner.create(request))
+    }
+
+    /// Retrieves a response by id.
+    ///
+    /// # Errors
+    ///
+    /// Same as [`crate::responses::Responses::retrieve`]
+    pub fn retrieve(&self, id: &str) -> Result<Response> {
+        self.runtime.block_on(self.inner.retrieve(id))
+    }
+
+    /// Deletes a response.
+    ///
+    /// # Errors
+    ///
+    /// Same as [`crate::responses::Responses::delete`]
+    pub fn delete(&self, id: &str) -> Result<DeleteOutcome> {
+        self.runtime.block_on(self.inner.delete(id))
+    }
+
+    /// Creates a streaming response and returns an iterator over its events.
+    ///
+    /// Each call to `next` blocks until the next event arrives.
+    #[cfg(feature = "stream")]
+    #[must_use]
+    pub fn stream(&self, request: Request) -> StreamEvents {
+        StreamEvents {
+            stream: self.inner.stream(request),
+            runtime: self.runtime.clone(),
+        }
+    }
+}
+
+/// Blocking iterator over the events of a streaming response, returned by
+/// [`Responses::stream`]
+#[cfg(feature = "stream")]
+pub struct StreamEvents {
+    stream: crate::types::ResponseStream,
+    runtime: Runtime,
+}
+
+#[cfg(feature = "stream")]
+impl std::fmt::Debug for StreamEvents {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("StreamEvents").finish_non_exhaustive()
+    }
+}
+
+#[cfg(feature = "stream")]
+impl Iterator for StreamEvents {
+    type Item = Result<crate::types::StreamEvent>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        use futures::StreamExt;
+
+        let stream = &mut self.stream;
+        self.runtime.block_on(stream.next())
+    }
+}
+
+/// Blocking counterpart of [`crate::files::Files`]
+#[derive(Debug, Clone)]
+pub struct Files {
+    inner: crate::files::Files,
+    runtime: Runtime,
+}
+
+impl Files {
+    /// Uploads a file from a path.
+    ///
+    /// # Errors
+    ///
+    /// Same as [`crate::files::Files::upload_file`]
+    pub fn upload_file(
+        &self,
+        path: impl AsRef<Path>,
+        purpose: impl Into<FilePurpose>,
+        mime_type: Option<String>,
+    ) -> Result<File> {
+        let (path, purpose) = (path.as_ref(), purpose.into());
+        self.runtime
+            .block_on(self.inner.upload_file(path, purpose, mime_type))
+    }
+
+    /// Downloads a file's content.
+    ///
+    /// # Errors
+    ///
+    /// Same as [`crate::files::Files::download`]
+    pub fn download(&self, file_id: &str) -> Result<Vec<u8>> {
+        self.runtime.block_on(self.inner.download(file_id))
+    }
+
+    /// Lists files.
+    ///
+    /// # Errors
+    ///
+    /// Same as [`crate::files::Files::list`]
+    pub fn list(&self, params: Option<PaginationParams>) -> Result<PaginatedList<File>> {
+        self.runtime.block_on(self.inner.list(params))
+    }
+}
+
+/// Blocking counterpart of [`crate::images::Images`]
+#[derive(Debug, Clone)]
+pub struct Images {
+    inner: crate::images::Images,
+    runtime: Runtime,
+}
+
+impl Images {
+    /// Generates images from a prompt.
+    ///
+    /// # Errors
+    ///
+    /// Same as [`crate::images::Images::generate`]
+    pub fn generate(&self, request: ImageGenerateRequest) -> Result<ImageGenerateResponse> {
+        self.runtime.block_on(self.inner.generate(request))
+    }
+}
+
+/// Blocking counterpart of [`crate::vector_stores::VectorStores`]
+#[derive(Debug, Clone)]
+pub struct VectorStores {
+    inner: crate::vector_stores::VectorStores,
+    runtime: Runtime,
+}
+
+impl VectorStores {
+    /// Searches a vector store.
+    ///
+    /// # Errors
+    ///
+    /// Same as [`crate::vector_stores::VectorStores::search`]
+    pub fn search(
+        &self,
+        vector_store_id: &str,
+        request: SearchVectorStoreRequest,
+    ) -> Result<SearchVectorStoreResponse> {
+        self.runtime
+            .block_on(self.inner.search(vector_store_id, request))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::Model;
+
+    const RESPONSE: &str = r#"{"id":"resp_1","object":"response","created_at":1700000000,"model":"gpt-4o-mini","status":"completed","output":[],"output_text":"Hi!"}"#;
+
+    fn client(server: &mockito::ServerGuard) -> Client {
+        Client::new_with_base_url("sk-blocking-1234567890", &server.url()).unwrap()
+    }
+
+    fn request() -> Request {
+        Request::builder()
+            .model(Model::GPT4oMini)
+            .input("Say hi")
+            .build()
+    }
+
+    #[test]
+    fn create_from_plain_main() {
+        let mut server = mockito::Server::new();
+        let mock = server
+            .mock("POST", "/responses")
+            .with_status(200)
+            .with_body(RESPONSE)
+            .expect(2)
+            .create();
+
+        let client = client(&server);
+        assert_eq!(
+            client.responses.create(request()).unwrap().output_text(),
+            "Hi!"
+        );
+        // Clones share the runtime and keep working from other threads
+        let clone = client.clone();
+        std::thread::spawn(move || clone.responses.create(request()).unwrap())
+            .join()
+            .unwrap();
+        mock.assert();
+
+        server
+            .mock("GET", "/responses/missing")
+            .with_status(404)
+            .with_body(r#"{"error":{"message":"No response found","type":"invalid_request_error","code":null}}"#)
+            .create();
+        assert!(client.responses.retrieve("missing").is_err());
+    }
+
+    #[cfg(feature = "stream")]
+    #[test]
+    fn stream_is_an_iterator_of_events() {
+        let mut server = mockito::Server::new();
+        server
+            .mock("POST", "/responses")
+            .with_status(200)
+            .with_header("content-type", "text/event-stream")
+            .with_body(concat!(
+                "data: {\"type\":\"response.output_text.delta\",\"delta\":\"Hel\"}\n\n",
+                "data: {\"type\":\"response.output_text.delta\",\"delta\":\"lo\"}\n\n",
+                "data: [DONE]\n\n",
+            ))
+            .create();
+
+        let text: String = client(&server)
+            .responses
+            .stream(request())
+            .map(Result::unwrap)
+            .filter_map(|event| event.as_text_delta().map(str::to_string))
+            .collect();
+        assert_eq!(text, "Hello");
+    }
+
+    #[test]
+    fn builder_clients_outlive_the_async_runtime() {
+        let mut server = mockito::Server::new();
+        let mock = server
+            .mock("POST", "/responses")
+            .with_status(200)
+            .with_body(RESPONSE)
+            .expect(3)
+            .create();
+        let builder = crate::Client::builder()
+            .api_key("sk-blocking-1234567890")
+            .base_url(server.url());
+
+        let client = Client::try_from(builder.clone()).unwrap();
+        let runtime = tokio::runtime::Runtime::new().unwrap();
+        let async_client = builder.build().unwrap();
+        runtime
+            .block_on(async_client.responses.create(request()))
+            .unwrap();
+        client.responses.create(request()).unwrap();
+        // Pooled connections of the async client die with its runtime
+        drop(runtime);
+        assert_eq!(
+            client.responses.create(request()).unwrap().output_text(),
+            "Hi!"
+        );
+        mock.assert();
+    }
+
+    #[tokio::test]
+    async fn works_inside_an_async_runtime() {
+        let mut server = mockito::Server::new_async().await;
+        server
+            .mock("POST", "/responses")
+            .with_status(200)
+            .with_body(RESPONSE)
+            .create_async()
+            .await;
+
+        let response = client(&server).responses.create(request()).unwrap();
+        assert_eq!(response.output_text(), "Hi!");
+    }
+}
@@ -0,0 +1,399 @@
+use crate::endpoint::Endpoint;
+use crate::error::Result;
+use crate::http::Transport;
+use crate::types::{DeleteConfirmation, PaginatedList, PaginationParams};
+use chrono::{DateTime, Utc};
+use reqwest::StatusCode;
+use serde::{Deserialize, Serialize};
+
+/// Containers API endpoints, for code interpreter containers managed outside a request
+#[derive(Debug, Clone)]
+pub struct Containers {
+    client: Transport,
+    endpoint: Endpoint,
+}
+
+/// Container object
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct ContainerObject {
+    /// Unique identifier for the container (`cntr_...`)
+    pub id: String,
+
+    /// Type of object (always "container")
+    #[cfg_attr(feature = "lenient", serde(default))]
+    pub object: String,
+
+    /// Name of the container
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub name: Option<String>,
+
+    /// Unix timestamp for when the container was created
+    #[serde(with = "crate::types::lenient::timestamp")]
+    pub created_at: DateTime<Utc>,
+
+    /// Status of the container, such as `running` or `expired`
+    #[serde(deserialize_with = "crate::types::lenient::status")]
+    pub status: String,
+
+    /// Expiration policy of the container
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub expires_after: Option<ContainerExpiresAfter>,
+
+    /// Unix timestamp of the container's last activity
+    #[serde(
+        default,
+        skip_serializing_if = "Option::is_none",
+        with = "chrono::serde::ts_seconds_option"
+    )]
+    pub last_active_at: Option<DateTime<Utc>>,
+}
+
+impl ContainerObject {
+    /// Returns true if the container has expired and can no longer run code
+    #[must_use]
+    pub fn is_expired(&self) -> bool {
+        self.status == "expired"
+    }
+}
+
+/// Anchor timestamp a [`ContainerExpiresAfter`] policy counts from
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum ContainerExpiresAfterAnchor {
+    /// The container's last activity
+    #[default]
+    LastActiveAt,
+}
+
+/// Expiration policy for a container
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub struct ContainerExpiresAfter {
+    /// Timestamp the expiration counts from
+    pub anchor: ContainerExpiresAfterAnchor,
+
+    /// Minutes after the anchor until the container expires
+    pub minutes: u32,
+}
+
+impl ContainerExpiresAfter {
+    /// Expires the container the given number of minutes after its last activity
+    #[must_use]
+    pub fn last_active_at(minutes: u32) -> Self {
+        Self {
+            anchor: ContainerExpiresAfterAnchor::LastActiveAt,
+            minutes,
+        }
+    }
+}
+
+/// Request to create a new container
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct CreateContainerRequest {
+    /// Name of the container
+    pub name: String,
+
+    /// Files copied into the container when it is created
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub file_ids: Option<Vec<String>>,
+
+    /// Expiration policy; the server default applies if unset
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub expires_after: Option<ContainerExpiresAfter>,
+}
+
+impl CreateContainerRequest {
+    /// Creates a request for an empty container with the given name
+    #[must_use]
+    pub fn new(name: impl Into<String>) -> Self {
+        Self {
+            name: name.into(),
+            file_ids: None,
+            expires_after: None,
+        }
+    }
+
+    /// Sets the files copied into the container
+    #[must_use]
+    pub fn with_file_ids<I, S>(mut self, file_ids: I) -> Self
+    where
+        I: IntoIterator<Item = S>,
+        S: Into<String>,
+    {
+        self.file_ids = Some(file_ids.into_iter().map(Into::into).collect());
+        self
+    }
+
+    /// Sets the expiration policy
+    #[must_use]
+    pub fn with_expires_after(mut self, expires_after: ContainerExpiresAfter) -> Self {
+        self.expires_after = Some(expires_after);
+        self
+    }
+}
+
+impl Containers {
+    /// Creates a new Containers API client
+    pub(crate) fn new(client: impl Into<Transport>, endpoint: impl Into<Endpoint>) -> Self {
+        Self {
+            client: client.into(),
+            endpoint: endpoint.into(),
+        }
+    }
+
+    /// Sends this handle's requests with `auth` as the `Authorization` header
+    pub(crate) fn with_auth(mut self, auth: &reqwest::header::HeaderValue) -> Self {
+        self.client = self.client.with_auth(auth.clone());
+        self
+    }
+
+    /// Creates a new container.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the request fails to send or has a non-200 status code.
+    pub async fn create(&self, request: CreateContainerRequest) -> Result<ContainerObject> {
+        let request = self
+            .client
+            .post(self.endpoint.url(&["containers"])?)
+            .json(&request);
+        self.client.send_json(request).await
+    }
+
+    /// Retrieves a container by ID.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the request fails to send or has a non-200 status code.
+    pub async fn retrieve(&self, container_id: &str) -> Result<ContainerObject> {
+        let request = self
+            .client
+            .get(self.endpoint.url(&["containers", container_id])?);
+        self.client.send_json(request).await
+    }
+
+    /// Lists containers.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if `params` has an out-of-range limit, or if the request fails to send
+    /// or has a non-200 status code.
+    pub async fn list(
+        &self,
+        params: Option<PaginationParams>,
+    ) -> Result<PaginatedList<ContainerObject>> {
+        let mut request = self.client.get(self.endpoint.url(&["containers"])?);
+
+        if let Some(params) = params {
+            params.validate()?;
+            request = request.query(&params);
+        }
+
+        self.client.send_json(request).await
+    }
+
+    /// Deletes a container.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the request fails to send or has a non-200 status code.
+    pub async fn delete(&self, container_id: &str) -> Result<()> {
+        let request = self
+            .client
+            .delete(self.endpoint.url(&["containers", container_id])?);
+        self.client.send(request).await?;
+        Ok(())
+    }
+
+    /// Deletes a container and returns the API's confirmation.
+    ///
+    /// Unlike [`Containers::delete`], a 404 is returned as [`DeleteConfirmation::NotFound`].
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the request fails to send, has a non-200 status code other
+    /// than 404, or the confirmation cannot be decoded.
+    pub async fn delete_confirmed(&self, container_id: &str) -> Result<DeleteConfirmation> {
+        let request = self
+            .client
+            .delete(self.endpoint.url(&["containers", container_id])?);
+        let response = self
+            .client
+            .send_allowing(request, &[StatusCode::NOT_FOUND])
+            .await?;
+        self.client.deletion(response).await
+    }
+
+    /// Creates a replacement for an expired container, with the same name and expiration policy.
+    ///
+    /// If the expired container can no longer be retrieved, the replacement is named after
+    /// its ID. Files are not copied over.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if creating the replacement fails.
+    pub async fn recreate(&self, container_id: &str) -> Result<ContainerObject> {
+        let expired = self.retrieve(container_id).await.ok();
+        let name = expired
+            .as_ref()
+            .and_then(|container| container.name.clone())
+            .unwrap_or_else(|| container_id.to_string());
+        let request = CreateContainerRequest {
+            expires_after: expired.and_then(|container| container.expires_after),
+            ..CreateContainerRequest::new(name)
+        };
+        self.create(request).await
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use mockito::Matcher;
+
+    fn container_json(id: &str, status: &str) -> serde_json::Value {
+        serde_json::json!({
+            "id": id,
+            "object": "container",
+            "name": "analysis",
+            "created_at": 1_700_000_000,
+            "status": status,
+            "expires_after": {"anchor": "last_active_at", "minutes": 20},
+            "last_active_at": 1_700_000_600
+        })
+    }
+
+    #[test]
+    fn container_object_round_trips() {
+        let json = container_json("cntr_1", "running");
+        let container: ContainerObject = serde_json::from_value(json.clone()).unwrap();
+        assert_eq!(container.name.as_deref(), Some("analysis"));
+        assert_eq!(
+            container.expires_after,
+            Some(ContainerExpiresAfter::last_active_at(20))
+        );
+        assert_eq!(container.last_active_at.unwrap().timestamp(), 1_700_000_600);
+        assert!(!container.is_expired());
+        assert_eq!(serde_json::to_value(&container).unwrap(), json);
+
+        let minimal: ContainerObject = serde_json::from_value(serde_json::json!({
+            "id": "cntr_2", "object": "container", "created_at": 1_700_000_000, "status": "expired"
+        }))
+        .unwrap();
+        assert!(minimal.is_expired());
+        assert!(minimal.expires_after.is_none() && minimal.last_active_at.is_none());
+    }
+
+    #[tokio::test]
+    async fn container_lifecycle() {
+        let mut server = mockito::Server::new_async().await;
+        let create = server
+            .mock("POST", "/containers")
+            .match_body(Matcher::Json(serde_json::json!({
+                "name": "analysis",
+                "file_ids": ["file_1"],
+                "expires_after": {"anchor": "last_active_at", "minutes": 20}
+            })))
+            .with_status(200)
+            .with_body(container_json("cntr_1", "running").to_string())
+            .create_async()
+            .await;
+        let retrieve = server
+            .mock("GET", "/containers/cntr_1")
+            .with_status(200)
+            .with_body(container_json("cntr_1", "running").to_string())
+            .create_async()
+            .await;
+        let list = server
+            .mock("GET", "/containers")
+            .match_query(Matcher::UrlEncoded("limit".into(), "10".into()))
+            .with_status(200)
+            .with_body(
+                serde_json::json!({
+                    "object": "list",
+                    "data": [container_json("cntr_1", "running")],
+                    "first_id": "cntr_1",
+                    "last_id": "cntr_1",
+                    "has_more": false
+                })
+                .to_string(),
+            )
+            .create_async()
+            .await;
+        let delete = server
+            .mock("DELETE", "/containers/cntr_1")
+            .with_status(200)
+            .with_body(r#"{"id":"cntr_1","object":"container.deleted","deleted":true}"#)
+            .create_async()
+            .await;
+
+        let containers = Containers::new(reqwest::Client::new(), server.url());
+        let created = containers
+            .create(
+                CreateContainerRequest::new("analysis")
+                    .with_file_ids(["file_1"])
+                    .with_expires_after(ContainerExpiresAfter::last_active_at(20)),
+            )
+            .await
+            .unwrap();
+        assert_eq!(created.id, "cntr_1");
+        assert_eq!(containers.retrieve("cntr_1").await.unwrap(), created);
+        let page = containers
+            .list(Some(PaginationParams::new().limit(10)))
+            .await
+            .unwrap();
+        assert_eq!(page.data, [created]);
+        containers.delete("cntr_1").await.unwrap();
+
+        for mock in [create, retrieve, list, delete] {
+            mock.assert_async().await;
+        }
+    }
+
+    #[tokio::test]
+    async fn recreate_copies_name_and_expiry() {
+        let mut server = mockito::Server::new_async().await;
+        server
+            .mock("GET", "/containers/cntr_old")
+            .with_status(200)
+            .with_body(container_json("cntr_old", "expired").to_string())
+            .create_async()
+            .await;
+        server
+            .mock("GET", "/containers/cntr_gone")
+            .with_status(404)
+            .with_body(
+                r#"{"error":{"message":"No container found","type":"invalid_request_error"}}"#,
+            )
+            .create_async()
+            .await;
+        let create = server
+            .mock("POST", "/containers")
+            .match_body(Matcher::Json(serde_json::json!({
+                "name": "analysis",
+                "expires_after": {"anchor": "last_active_at", "minutes": 20}
+            })))
+            .with_status(200)
+            .with_body(container_json("cntr_new", "running").to_string())
+            .create_async()
+            .await;
+        let create_fallback = server
+            .mock("POST", "/containers")
+            .match_body(Matcher::Json(serde_json::json!({"name": "cntr_gone"})))
+            .with_status(200)
+            .with_body(container_json("cntr_new2", "running").to_string())
+            .create_async()
+            .await;
+
+        let containers = Containers::new(reqwest::Client::new(), server.url());
+        assert_eq!(
+            containers.recreate("cntr_old").await.unwrap().id,
+            "cntr_new"
+        );
+        assert_eq!(
+            containers.recreate("cntr_gone").await.unwrap().id,
+            "cntr_new2"
+        );
+        create.assert_async().await;
+        create_fallback.assert_async().await;
+    }
+}
@@ -0,0 +1,388 @@
+//! Multi-turn conversations that summarize themselves before outgrowing the context window.
+//!
+//! A [`ConversationManager`] chains turns through `previous_response_id` and watches the
+//! token usage of each response. Once the context reaches its budget (80% of
+//! [`Model::context_window`](crate::Model::context_window) by default), it asks the model
+//! to summarize the conversation so far and starts a new chain from that summary, sent as a
+//! developer message. Callers keep calling [`ConversationManager::send`] throughout.
+
+use crate::error::Result;
+use crate::responses::Responses;
+use crate::types::RequestTemplate;
+use std::fmt;
+use std::sync::Arc;
+
+/// Prompt used to summarize a conversation unless
+/// [`ConversationManager::with_summary_prompt`] sets another
+pub const DEFAULT_SUMMARY_PROMPT: &str = "Summarize this conversation so far. Keep every fact, \
+     decision, name and open question needed to continue it; leave out pleasantries.";
+
+/// Share of the context window a conversation may fill before it is summarized
+pub const DEFAULT_BUDGET_RATIO: f64 = 0.8;
+
+/// Details of a summarization, passed to [`ConversationManager::on_summarize`] hooks
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct SummarizationEvent {
+    /// Context size in tokens that triggered the summarization
+    pub context_tokens: u32,
+
+    /// Token budget in effect
+    pub budget: u32,
+
+    /// Summary the new chain starts from
+    pub summary: String,
+
+    /// ID of the response holding the summary
+    pub response_id: String,
+}
+
+/// Hook called after every summarization
+pub type SummarizeCallback = dyn Fn(&SummarizationEvent) + Send + Sync;
+
+/// Runs a conversation over [`Responses`], summarizing it when it nears the token budget.
+///
+/// The manager is `Send` and `Sync`, so it can be shared between async handlers behind a
+/// mutex such as `tokio::sync::Mutex`.
+///
+/// ```rust,no_run
+/// # async fn run() -> open_ai_rust_responses_by_sshift::Result<()> {
+/// use open_ai_rust_responses_by_sshift::{Client, Model};
+/// use open_ai_rust_responses_by_sshift::types::RequestTemplate;
+///
+/// let client = Client::from_env().unwrap();
+/// let template = RequestTemplate {
+///     model: Some(Model::GPT4oMini),
+///     instructions: Some("You are a helpful assistant".to_string()),
+///     ..RequestTemplate::default()
+/// };
+/// let mut conversation = client
+///     .conversation(template)
+///     .on_summarize(|event| println!("summarized at {} tokens", event.context_tokens));
+///
+/// let reply = conversation.send("Hi, I'm planning a trip to Japan").await?;
+/// println!("{}", reply.output_text());
+/// # Ok(())
+/// # }
+/// ```
+#[derive(Clone)]
+pub struct ConversationManager {
+    responses: Responses,
+    template: RequestTemplate,
+    token_budget: Option<u32>,
+    budget_ratio: f64,
+    summary_prompt: String,
+    on_summarize: Option<Arc<SummarizeCallback>>,
+    previous_response_id: Option<String>,
+    summary: Option<String>,
+    context_tokens: u32,
+    total_tokens: u64,
+}
+
+impl fmt::Debug for ConversationManager {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("ConversationManager")
+            .field("template", &self.template)
+            .field("token_budget", &self.token_budget())
+            .field("previous_response_id", &self.previous_response_id)
+            .field("summary", &self.summary.is_some())
+            .field("context_tokens", &self.context_tokens)
+            .field("total_tokens", &self.total_tokens)
+            .field("on_summarize", &self.on_summarize.is_some())
+            .finish_non_exhaustive()
+    }
+}
+
+impl ConversationManager {
+    /// Starts an empty conversation; every turn is built from `template`
+    #[must_use]
+    pub fn new(responses: Responses, template: RequestTemplate) -> Self {
+        Self {
+            responses,
+            template,
+            token_budget: None,
+            budget_ratio: DEFAULT_BUDGET_RATIO,
+            summary_prompt: DEFAULT_SUMMARY_PROMPT.to_string(),
+            on_summarize: None,
+            previous_response_id: None,
+            summary: None,
+            context_tokens: 0,
+            total_tokens: 0,
+        }
+    }
+
+    /// Sets a fixed token budget, overriding the share of the context window
+    #[must_use]
+    pub fn with_token_budget(mut self, tokens: u32) -> Self {
+        self.token_budget = Some(tokens);
+        self
+    }
+
+    /// Sets the share of the model's context window to fill before summarizing (clamped to 0..=1)
+    #[must_use]
+    pub fn with_budget_ratio(mut self, ratio: f64) -> Self {
+        self.budget_ratio = ratio.clamp(0.0, 1.0);
+        self
+    }
+
+    /// Sets the prompt that asks the model for a summary
+    #[must_use]
+    pub fn with_summary_prompt(mut self, prompt: impl Into<String>) -> Self {
+        self.summary_prompt = prompt.into();
+        self
+    }
+
+    /// Sets a hook called after every summarization
+    #[must_use]
+    pub fn on_summarize<F>(mut self, hook: F) -> Self
+    where
+        F: Fn(&SummarizationEvent) + Send + Sync + 'static,
+    {
+        self.on_summarize = Some(Arc::new(hook));
+        self
+    }
+
+    /// Returns the token budget, or `None` if there is none and the conversation is never
+    /// summarized automatically (custom models without [`Self::with_token_budget`])
+    #[must_use]
+    // Context windows fit in f64 exactly and the ratio is at most 1
+    #[allow(clippy::cast_possible_truncation, clippy::cast_sign_loss)]
+    pub fn token_budget(&self) -> Option<u32> {
+        self.token_budget.or_else(|| {
+            let model = match &self.template.model {
+                Some(model) => model.clone(),
+                None => crate::Request::default().model,
+            };
+            model
+                .context_window()
+                .map(|tokens| (f64::from(tokens) * self.budget_ratio) as u32)
+        })
+    }
+
+    /// Returns the current context size: the total tokens of the last response in the chain
+    #[must_use]
+    pub fn context_tokens(&self) -> u32 {
+        self.context_tokens
+    }
+
+    /// Returns the tokens used by every request so far, summaries included
+    #[must_use]
+    pub fn total_tokens(&self) -> u64 {
+        self.total_tokens
+    }
+
+    /// Returns the ID of the last response in the chain
+    #[must_use]
+    pub fn previous_response_id(&self) -> Option<&str> {
+        self.previous_response_id.as_deref()
+    }
+
+    /// Returns the summary the next turn will start from, if the chain was just restarted
+    #[must_use]
+    pub fn pending_summary(&self) -> Option<&str> {
+        self.summary.as_deref()
+    }
+
+    /// Forgets the conversation; the next turn starts a new chain
+    pub fn reset(&mut self) {
+        self.previous_response_id = None;
+        self.summary = None;
+        self.context_tokens = 0;
+    }
+
+    /// Sends a user turn and returns the model's response.
+    ///
+    /// If the context has reached the token budget, the conversation is summarized first.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the summarization or the turn itself fails. A failed turn does
+    /// not change the conversation, so it can be retried.
+    pub async fn send(&mut self, user_input: impl Into<String>) -> Result<crate::Response> {
+        if self
+            .token_budget()
+            .is_some_and(|budget| self.context_tokens >= budget)
+        {
+            self.summarize().await?;
+        }
+
+        let mut builder = self.template.request(user_input.into());
+        if let Some(id) = &self.previous_response_id {
+            builder = builder.previous_response_id(id.clone());
+        } else if let Some(summary) = &self.summary {
+            builder = builder
+                .developer_message(format!("Summary of the conversation so far:\n{summary}"));
+        }
+        let response = self.responses.create(builder.build()).await?;
+
+        self.summary = None;
+        self.record(&response);
+        Ok(response)
+    }
+
+    /// Summarizes the conversation now and restarts the chain from the summary, returning it.
+    ///
+    /// Returns `None` without a request if nothing has been sent since the last restart.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the summarization request fails.
+    pub async fn summarize(&mut self) -> Result<Option<String>> {
+        let Some(previous_response_id) = self.previous_response_id.clone() else {
+            return Ok(None);
+        };
+        let request = self
+            .template
+            .request(self.summary_prompt.as_str())
+            .previous_response_id(previous_response_id)
+            .build();
+        let response = self.responses.create(request).await?;
+        let summary = response.output_text();
+
+        let event = SummarizationEvent {
+            context_tokens: self.context_tokens,
+            budget: self.token_budget().unwrap_or(0),
+            summary: summary.clone(),
+            response_id: response.id.clone(),
+        };
+        crate::diag::debug!(
+            "Summarized conversation at {} tokens into response {}",
+            event.context_tokens,
+            event.response_id
+        );
+        self.total_tokens += u64::from(response.total_tokens().unwrap_or(0));
+        self.previous_response_id = None;
+        self.summary = Some(summary.clone());
+        self.context_tokens = 0;
+        if let Some(hook) = &self.on_summarize {
+            hook(&event);
+        }
+        Ok(Some(summary))
+    }
+
+    fn record(&mut self, response: &crate::Response) {
+        let tokens = response.total_tokens().unwrap_or(0);
+        self.total_tokens += u64::from(tokens);
+        self.context_tokens = tokens;
+        self.previous_response_id = Some(response.id.clone());
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use mockito::Matcher;
+    use std::sync::Mutex;
+
+    fn response_body(id: &str, text: &str, total_tokens: u32) -> String {
+        serde_json::json!({
+            "id": id,
+            "object": "response",
+            "created_at": 1_700_000_000,
+            "model": "gpt-4o",
+            "status": "completed",
+            "output": [{
+                "type": "message", "id": format!("msg_{id}"), "role": "assistant", "status": "completed",
+                "content": [{"type": "output_text", "text": text, "annotations": []}]
+            }],
+            "usage": {"input_tokens": total_tokens - 10, "output_tokens": 10, "total_tokens": total_tokens}
+        })
+        .to_string()
+    }
+
+    #[test]
+    fn budget_defaults_to_share_of_context_window() {
+        let responses = Responses::new(reqwest::Client::new(), "http://localhost");
+        let manager = ConversationManager::new(responses.clone(), RequestTemplate::default());
+        assert_eq!(manager.token_budget(), Some(102_400));
+        let manager = manager.with_budget_ratio(0.5);
+        assert_eq!(manager.token_budget(), Some(64_000));
+
+        let custom = RequestTemplate {
+            model: Some(crate::Model::Custom("my-model".to_string())),
+            ..RequestTemplate::default()
+        };
+        let manager = ConversationManager::new(responses, custom);
+        assert_eq!(manager.token_budget(), None);
+        assert_eq!(manager.with_token_budget(500).token_budget(), Some(500));
+    }
+
+    #[tokio::test]
+    async fn summarizes_and_restarts_chain_at_budget() {
+        let mut server = mockito::Server::new_async().await;
+        let first = server
+            .mock("POST", "/responses")
+            .match_body(Matcher::Regex(r#""input":"first""#.to_string()))
+            .with_status(200)
+            .with_body(response_body("resp_1", "Hello", 600))
+            .create_async()
+            .await;
+        let second = server
+            .mock("POST", "/responses")
+            .match_body(Matcher::PartialJson(serde_json::json!({
+                "input": "second", "previous_response_id": "resp_1"
+            })))
+            .with_status(200)
+            .with_body(response_body("resp_2", "Noted", 1000))
+            .create_async()
+            .await;
+        let summarize = server
+            .mock("POST", "/responses")
+            .match_body(Matcher::PartialJson(serde_json::json!({
+                "input": DEFAULT_SUMMARY_PROMPT, "previous_response_id": "resp_2"
+            })))
+            .with_status(200)
+            .with_body(response_body("resp_sum", "User is planning a trip.", 300))
+            .expect(1)
+            .create_async()
+            .await;
+        let third = server
+            .mock("POST", "/responses")
+            .match_body(Matcher::AllOf(vec![
+                Matcher::Regex(
+                    "Summary of the conversation so far:\\\\nUser is planning a trip.".to_string(),
+                ),
+                Matcher::Regex(r#""text":"third""#.to_string()),
+            ]))
+            .with_status(200)
+            .with_body(response_body("resp_3", "Sure", 400))
+            .create_async()
+            .await;
+
+        let events = Arc::new(Mutex::new(Vec::new()));
+        let recorded = events.clone();
+        let manager = ConversationManager::new(
+            Responses::new(reqwest::Client::new(), server.url()),
+            RequestTemplate::default(),
+        )
+        .with_token_budget(1000)
+        .on_summarize(move |event| recorded.lock().unwrap().push(event.clone()));
+
+        // Shared between tasks the way an async handler would hold it
+        let manager = Arc::new(tokio::sync::Mutex::new(manager));
+        for input in ["first", "second", "third"] {
+            let manager = manager.clone();
+            tokio::spawn(async move { manager.lock().await.send(input).await })
+                .await
+                .unwrap()
+                .unwrap();
+        }
+
+        let manager = manager.lock().await;
+        assert_eq!(
+            *events.lock().unwrap(),
+            [SummarizationEvent {
+                context_tokens: 1000,
+                budget: 1000,
+                summary: "User is planning a trip.".to_string(),
+                response_id: "resp_sum".to_string(),
+            }]
+        );
+        assert_eq!(manager.previous_response_id(), Some("resp_3"));
+        assert_eq!(manager.pending_summary(), None);
+        assert_eq!(manager.context_tokens(), 400);
+        assert_eq!(manager.total_tokens(), 600 + 1000 + 300 + 400);
+        for mock in [first, second, summarize, third] {
+            mock.assert_async().await;
+        }
+    }
+}
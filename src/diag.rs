@@ -0,0 +1,62 @@
+//! Diagnostic output for the crate.
+//!
+//! Every log line goes through the macros below. By default they forward to the `log`
+//! crate. With the `tracing` feature they emit `tracing` events instead, so they land
+//! inside the spans opened around requests and streams. `tracing` itself falls back to
+//! `log` records when no subscriber is installed, so a `log`-only application still sees
+//! the same output.
+
+// Types-only builds use just a few of these
+#![allow(unused_imports, unused_macros)]
+
+macro_rules! diag_debug {
+    ($($arg:tt)+) => {{
+        #[cfg(feature = "tracing")]
+        ::tracing::debug!($($arg)+);
+        #[cfg(not(feature = "tracing"))]
+        ::log::debug!($($arg)+);
+    }};
+}
+
+macro_rules! diag_info {
+    ($($arg:tt)+) => {{
+        #[cfg(feature = "tracing")]
+        ::tracing::info!($($arg)+);
+        #[cfg(not(feature = "tracing"))]
+        ::log::info!($($arg)+);
+    }};
+}
+
+macro_rules! diag_warn {
+    ($($arg:tt)+) => {{
+        #[cfg(feature = "tracing")]
+        ::tracing::warn!($($arg)+);
+        #[cfg(not(feature = "tracing"))]
+        ::log::warn!($($arg)+);
+    }};
+}
+
+macro_rules! diag_error {
+    ($($arg:tt)+) => {{
+        #[cfg(feature = "tracing")]
+        ::tracing::error!($($arg)+);
+        #[cfg(not(feature = "tracing"))]
+        ::log::error!($($arg)+);
+    }};
+}
+
+/// Records a field on the current span; compiled out without the `tracing` feature
+macro_rules! diag_record {
+    ($field:literal, $value:expr) => {{
+        #[cfg(feature = "tracing")]
+        ::tracing::Span::current().record($field, $value);
+        #[cfg(not(feature = "tracing"))]
+        let _ = &$value;
+    }};
+}
+
+// Renamed on export: a macro defined as `warn` is ambiguous with the built-in attribute
+pub(crate) use {
+    diag_debug as debug, diag_error as error, diag_info as info, diag_record as record,
+    diag_warn as warn,
+};
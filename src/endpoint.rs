@@ -0,0 +1,428 @@
+//! Base URL handling shared by the endpoint modules.
+//!
+//! URLs are built by appending path segments to the parsed base URL rather than
+//! by string concatenation, so a base with or without a trailing slash, with an
+//! embedded path (`https://proxy.example.com/openai/v1`) or with a query string
+//! all produce well-formed URLs, and ids are percent-encoded as single segments.
+
+use reqwest::Url;
+
+/// Parsed base URL that endpoint URLs are built from
+#[derive(Debug, Clone)]
+pub(crate) struct Endpoint {
+    raw: String,
+    base: Result<Url, String>,
+}
+
+impl Endpoint {
+    /// Parses and validates a base URL.
+    ///
+    /// # Errors
+    ///
+    /// Returns `CreateError::InvalidBaseUrl` if the URL cannot be parsed or is not an
+    /// `http`/`https` URL.
+    pub(crate) fn parse(base_url: &str) -> Result<Self, crate::CreateError> {
+        let endpoint = Self::from(base_url);
+        match &endpoint.base {
+            Ok(_) => Ok(endpoint),
+            Err(reason) => Err(crate::CreateError::InvalidBaseUrl(reason.clone())),
+        }
+    }
+
+    /// Checks that requests to this base URL are encrypted.
+    ///
+    /// Plain `http` is allowed only for loopback hosts (`localhost`, `127.0.0.1`, `::1`).
+    ///
+    /// # Errors
+    ///
+    /// Returns `CreateError::InsecureBaseUrl` for an `http` URL to any other host.
+    pub(crate) fn require_https(&self) -> Result<(), crate::CreateError> {
+        let Ok(url) = &self.base else {
+            return Ok(());
+        };
+        let is_loopback = match url.host() {
+            Some(url::Host::Domain(domain)) => domain.eq_ignore_ascii_case("localhost"),
+            Some(url::Host::Ipv4(ip)) => ip.is_loopback(),
+            Some(url::Host::Ipv6(ip)) => ip.is_loopback(),
+            None => false,
+        };
+        if url.scheme() == "https" || is_loopback {
+            Ok(())
+        } else {
+            Err(crate::CreateError::InsecureBaseUrl(self.raw.clone()))
+        }
+    }
+
+    /// Returns the base URL as given
+    pub(crate) fn as_str(&self) -> &str {
+        &self.raw
+    }
+
+    /// Builds the URL for the given path segments, e.g. `&["files", file_id, "content"]`.
+    ///
+    /// Each segment is percent-encoded, so a `/` inside an id stays part of that id.
+    ///
+    /// # Errors
+    ///
+    /// Returns [`Error::InvalidBaseUrl`](crate::Error::InvalidBaseUrl) if the base URL
+    /// did not parse when the client was created.
+    pub(crate) fn url(&self, segments: &[&str]) -> crate::Result<Url> {
+        let mut url = self.base.clone().map_err(crate::Error::InvalidBaseUrl)?;
+        url.path_segments_mut()
+            .map_err(|()| crate::Error::InvalidBaseUrl(format!("{} cannot be a base", self.raw)))?
+            .pop_if_empty()
+            .extend(segments);
+        Ok(url)
+    }
+
+    /// Resolves a URL the API handed out, such as a background handle's status URL.
+    ///
+    /// Absolute URLs are used as they are and relative ones are resolved like a link on
+    /// the base URL. Either way the result must share the base URL's scheme, host and
+    /// port, so requests carrying the API key never go to another server.
+    ///
+    /// # Errors
+    ///
+    /// Returns [`Error::InvalidBaseUrl`](crate::Error::InvalidBaseUrl) if the base URL did
+    /// not parse, or [`Error::InvalidHandleUrl`](crate::Error::InvalidHandleUrl) if `url`
+    /// does not resolve or points at another origin.
+    pub(crate) fn resolve_same_origin(&self, url: &str) -> crate::Result<Url> {
+        let base = self
+            .base
+            .as_ref()
+            .map_err(|reason| crate::Error::InvalidBaseUrl(reason.clone()))?;
+        let resolved = base
+            .join(url)
+            .map_err(|e| crate::Error::InvalidHandleUrl(format!("{url}: {e}")))?;
+        if resolved.origin() != base.origin() {
+            return Err(crate::Error::InvalidHandleUrl(format!(
+                "{url} is not on the API host {}",
+                base.origin().ascii_serialization()
+            )));
+        }
+        Ok(resolved)
+    }
+
+    /// Builds the URL for a `/`-separated path such as `"/tools/web_search"`
+    ///
+    /// # Errors
+    ///
+    /// Same as [`Endpoint::url`].
+    pub(crate) fn path_url(&self, path: &str) -> crate::Result<Url> {
+        let segments: Vec<&str> = path.split('/').filter(|s| !s.is_empty()).collect();
+        self.url(&segments)
+    }
+}
+
+/// Parses without failing; an invalid base URL is reported by every [`Endpoint::url`] call
+impl From<&str> for Endpoint {
+    fn from(base_url: &str) -> Self {
+        Self {
+            raw: base_url.to_string(),
+            base: parse_base(base_url),
+        }
+    }
+}
+
+impl From<String> for Endpoint {
+    fn from(base_url: String) -> Self {
+        Self::from(base_url.as_str())
+    }
+}
+
+/// Builder for a single endpoint group used without a [`Client`](crate::Client), such as
+/// [`Responses::builder`](crate::responses::Responses::builder).
+///
+/// Useful for services that only call one API, or that reach it through a different
+/// gateway than the others:
+///
+/// ```rust,no_run
+/// use open_ai_rust_responses_by_sshift::responses::Responses;
+///
+/// let responses = Responses::builder()
+///     .api_key("sk-...")
+///     .base_url("https://eu.gateway.example.com/openai/v1")
+///     .build()?;
+/// # Ok::<(), open_ai_rust_responses_by_sshift::CreateError>(())
+/// ```
+#[must_use]
+pub struct EndpointBuilder<T> {
+    api_key: Option<String>,
+    base_url: Option<String>,
+    http_client: Option<reqwest::Client>,
+    recovery_policy: crate::RecoveryPolicy,
+    endpoint_group: std::marker::PhantomData<fn() -> T>,
+}
+
+impl<T> Default for EndpointBuilder<T> {
+    fn default() -> Self {
+        Self {
+            api_key: None,
+            base_url: None,
+            http_client: None,
+            recovery_policy: crate::RecoveryPolicy::default(),
+            endpoint_group: std::marker::PhantomData,
+        }
+    }
+}
+
+impl<T> Clone for EndpointBuilder<T> {
+    fn clone(&self) -> Self {
+        Self {
+            api_key: self.api_key.clone(),
+            base_url: self.base_url.clone(),
+            http_client: self.http_client.clone(),
+            recovery_policy: self.recovery_policy.clone(),
+            endpoint_group: std::marker::PhantomData,
+        }
+    }
+}
+
+impl<T> std::fmt::Debug for EndpointBuilder<T> {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("EndpointBuilder")
+            .field("api_key", &self.api_key.as_ref().map(|_| "<redacted>"))
+            .field("base_url", &self.base_url)
+            // The HTTP client's headers may hold credentials
+            .field("http_client", &self.http_client.is_some())
+            .field("recovery_policy", &self.recovery_policy)
+            .finish()
+    }
+}
+
+impl<T> EndpointBuilder<T> {
+    /// Sets the API key; defaults to the `OPENAI_API_KEY` environment variable
+    pub fn api_key(mut self, api_key: impl Into<String>) -> Self {
+        self.api_key = Some(api_key.into());
+        self
+    }
+
+    /// Sets the base URL; defaults to `https://api.openai.com/v1`
+    pub fn base_url(mut self, base_url: impl Into<String>) -> Self {
+        self.base_url = Some(base_url.into());
+        self
+    }
+
+    /// Uses a preconfigured HTTP client instead of building one from the API key.
+    ///
+    /// The client must already send any authentication headers the server expects.
+    pub fn http_client(mut self, http_client: reqwest::Client) -> Self {
+        self.http_client = Some(http_client);
+        self
+    }
+
+    /// Sets the recovery policy for failed requests
+    pub fn recovery_policy(mut self, recovery_policy: crate::RecoveryPolicy) -> Self {
+        self.recovery_policy = recovery_policy;
+        self
+    }
+
+    /// Validates the settings shared by every endpoint group's `build`
+    pub(crate) fn into_parts(
+        self,
+    ) -> Result<(crate::http::Transport, Endpoint, crate::RecoveryPolicy), crate::CreateError> {
+        let endpoint =
+            Endpoint::parse(self.base_url.as_deref().unwrap_or(crate::DEFAULT_BASE_URL))?;
+        let http_client = if let Some(http_client) = self.http_client {
+            http_client
+        } else {
+            let api_key = match self.api_key {
+                Some(api_key) => api_key,
+                None => std::env::var("OPENAI_API_KEY")
+                    .map_err(|_| crate::CreateError::ApiKeyNotFound)?,
+            };
+            let user_agent = reqwest::header::HeaderValue::from_static(crate::DEFAULT_USER_AGENT);
+            crate::default_http_client(
+                &api_key,
+                crate::HttpOptions {
+                    user_agent: Some(user_agent),
+                    ..crate::HttpOptions::default()
+                },
+            )?
+        };
+        Ok((
+            crate::http::Transport::from(http_client),
+            endpoint,
+            self.recovery_policy,
+        ))
+    }
+}
+
+fn parse_base(base_url: &str) -> Result<Url, String> {
+    let mut url = Url::parse(base_url.trim()).map_err(|e| format!("{base_url}: {e}"))?;
+    if !matches!(url.scheme(), "http" | "https") || url.cannot_be_a_base() {
+        return Err(format!("{base_url}: expected an http or https URL"));
+    }
+    let path = url.path().trim_end_matches('/').to_string();
+    url.set_path(&path);
+    Ok(url)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn url(base: &str, segments: &[&str]) -> String {
+        Endpoint::parse(base)
+            .unwrap()
+            .url(segments)
+            .unwrap()
+            .to_string()
+    }
+
+    #[test]
+    fn joins_segments_onto_any_base_path() {
+        for base in ["https://api.openai.com/v1", "https://api.openai.com/v1/"] {
+            assert_eq!(
+                url(base, &["responses"]),
+                "https://api.openai.com/v1/responses"
+            );
+        }
+        assert_eq!(
+            url("http://localhost:8080", &["files", "file_1", "content"]),
+            "http://localhost:8080/files/file_1/content"
+        );
+        assert_eq!(
+            url("https://proxy.example.com/openai/v1//", &["files"]),
+            "https://proxy.example.com/openai/v1/files"
+        );
+        // The query string stays on the end instead of swallowing the path
+        assert_eq!(
+            url(
+                "https://gw.example.com/openai/v1?api-version=2025-01-01",
+                &["responses"]
+            ),
+            "https://gw.example.com/openai/v1/responses?api-version=2025-01-01"
+        );
+    }
+
+    #[test]
+    fn escapes_ids_and_splits_paths() {
+        assert_eq!(
+            url("https://api.openai.com/v1", &["responses", "resp/../1 ?#"]),
+            "https://api.openai.com/v1/responses/resp%2F..%2F1%20%3F%23"
+        );
+        let endpoint = Endpoint::parse("https://api.openai.com/v1/").unwrap();
+        assert_eq!(
+            endpoint.path_url("/tools/web_search").unwrap().as_str(),
+            "https://api.openai.com/v1/tools/web_search"
+        );
+    }
+
+    #[test]
+    fn rejects_invalid_base_urls() {
+        for base in [
+            "",
+            "api.openai.com/v1",
+            "mailto:ops@example.com",
+            "ftp://x/v1",
+        ] {
+            assert!(
+                matches!(
+                    Endpoint::parse(base),
+                    Err(crate::CreateError::InvalidBaseUrl(_))
+                ),
+                "{base:?} should be rejected"
+            );
+            assert!(matches!(
+                Endpoint::from(base).url(&["responses"]),
+                Err(crate::Error::InvalidBaseUrl(_))
+            ));
+        }
+    }
+
+    #[test]
+    fn require_https_allows_plain_http_only_to_loopback() {
+        for base in [
+            "https://api.openai.com/v1",
+            "http://localhost:8080/v1",
+            "http://LOCALHOST",
+            "http://127.0.0.1:1234",
+            "http://[::1]:8080",
+        ] {
+            assert!(
+                Endpoint::parse(base).unwrap().require_https().is_ok(),
+                "{base:?} should be allowed"
+            );
+        }
+        for base in [
+            "http://api.openai.com/v1",
+            "http://localhost.example.com",
+            "http://10.0.0.1",
+        ] {
+            assert!(
+                matches!(
+                    Endpoint::parse(base).unwrap().require_https(),
+                    Err(crate::CreateError::InsecureBaseUrl(_))
+                ),
+                "{base:?} should be rejected"
+            );
+        }
+    }
+
+    #[test]
+    fn resolve_same_origin_keeps_urls_on_the_api_host() {
+        let endpoint = Endpoint::parse("https://api.openai.com/v1").unwrap();
+        assert_eq!(
+            endpoint
+                .resolve_same_origin("https://api.openai.com/jobs/bg_1/status?x=1")
+                .unwrap()
+                .as_str(),
+            "https://api.openai.com/jobs/bg_1/status?x=1"
+        );
+        assert_eq!(
+            endpoint.resolve_same_origin("/jobs/bg_1").unwrap().as_str(),
+            "https://api.openai.com/jobs/bg_1"
+        );
+        for url in [
+            "https://evil.example.com/jobs/bg_1",
+            "http://api.openai.com/jobs/bg_1",
+            "https://api.openai.com:8443/jobs/bg_1",
+        ] {
+            assert!(
+                matches!(
+                    endpoint.resolve_same_origin(url),
+                    Err(crate::Error::InvalidHandleUrl(_))
+                ),
+                "{url:?} should be rejected"
+            );
+        }
+    }
+
+    #[tokio::test]
+    async fn clients_encode_ids_under_an_embedded_base_path() {
+        let mut server = mockito::Server::new_async().await;
+        let mocks = [
+            server.mock("GET", "/openai/v1/responses/resp%2F1"),
+            server.mock("GET", "/openai/v1/files/file%2Fabc"),
+            server.mock("GET", "/openai/v1/vector_stores/vs%201"),
+        ];
+        let mut created = Vec::new();
+        for mock in mocks {
+            created.push(
+                mock.with_status(404)
+                    .with_body(
+                        r#"{"error":{"message":"not found","type":"invalid_request_error"}}"#,
+                    )
+                    .expect(1)
+                    .create_async()
+                    .await,
+            );
+        }
+
+        let client =
+            crate::Client::new_with_base_url("sk-test", &format!("{}/openai/v1/", server.url()))
+                .unwrap();
+        assert!(client.responses.retrieve("resp/1").await.is_err());
+        assert!(client.files.get("file/abc").await.is_err());
+        assert!(client.vector_stores.get("vs 1").await.is_err());
+
+        for mock in created {
+            mock.assert_async().await;
+        }
+        assert!(matches!(
+            crate::Client::new_with_base_url("sk-test", "localhost:8080"),
+            Err(crate::CreateError::InvalidBaseUrl(_))
+        ));
+    }
+}
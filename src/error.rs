@@ -1,5 +1,6 @@
 use serde::{Deserialize, Serialize};
 use std::fmt;
+use std::time::Duration;
 
 /// High-level classification for errors to drive retry and logging behavior
 #[derive(Debug, Clone, Copy, PartialEq, Eq)]
@@ -9,6 +10,7 @@ pub enum ErrorClass {
     RetryableServer,
     RateLimited,
     ApiContainerExpired,
+    PreviousResponseNotFound,
     NonRecoverable,
 }
 
@@ -22,6 +24,7 @@ impl ErrorClass {
             Self::RetryableServer => "retryable_server",
             Self::RateLimited => "rate_limited",
             Self::ApiContainerExpired => "api_container_expired",
+            Self::PreviousResponseNotFound => "previous_response_not_found",
             Self::NonRecoverable => "non_recoverable",
         }
     }
@@ -34,6 +37,7 @@ impl fmt::Display for ErrorClass {
 }
 
 /// API error response
+#[cfg(feature = "client")]
 #[derive(Debug, Clone, Deserialize, Serialize, Default)]
 pub struct ApiError {
     /// Error message
@@ -92,8 +96,8 @@ pub enum Error {
     /// Bad Gateway error (502)
     #[error("Service temporarily unavailable (Bad Gateway). Please try again in a moment.")]
     BadGateway {
-        /// When to retry (seconds from now)
-        retry_after: Option<u64>,
+        /// How long to wait before retrying
+        retry_after: Option<Duration>,
 
         /// Original status code
         status_code: u16,
@@ -102,8 +106,8 @@ pub enum Error {
     /// Service Unavailable error (503)
     #[error("Service temporarily unavailable. Please try again{retry_message}.")]
     ServiceUnavailable {
-        /// When to retry (seconds from now)
-        retry_after: Option<u64>,
+        /// How long to wait before retrying
+        retry_after: Option<Duration>,
 
         /// Formatted retry message
         retry_message: String,
@@ -112,15 +116,15 @@ pub enum Error {
     /// Gateway Timeout error (504)
     #[error("Request timed out at the gateway. Please try again.")]
     GatewayTimeout {
-        /// When to retry (seconds from now)
-        retry_after: Option<u64>,
+        /// How long to wait before retrying
+        retry_after: Option<Duration>,
     },
 
     /// Rate limiting error (429)
     #[error("Rate limit exceeded. Please try again{retry_message}.")]
     RateLimited {
-        /// When the rate limit resets
-        retry_after: Option<u64>,
+        /// How long until the rate limit resets
+        retry_after: Option<Duration>,
 
         /// Formatted retry message
         retry_message: String,
@@ -165,6 +169,37 @@ pub enum Error {
         suggestion: Option<String>,
     },
 
+    /// Content policy violation (400 with `content_policy_violation` code)
+    #[error("Content policy violation: {message}")]
+    ContentPolicyViolation {
+        /// Error message
+        message: String,
+    },
+
+    /// Context length exceeded (400 with `context_length_exceeded` code)
+    #[error("Context length exceeded: {message}")]
+    ContextLengthExceeded {
+        /// Error message
+        message: String,
+
+        /// Number of tokens requested (parsed from the message when available)
+        requested: Option<u32>,
+
+        /// Maximum context length of the model (parsed from the message when available)
+        max: Option<u32>,
+    },
+
+    /// The response named by `previous_response_id` no longer exists, e.g. because it
+    /// was deleted or is past the retention period
+    #[error("Previous response not found: {message}")]
+    PreviousResponseNotFound {
+        /// ID of the missing response (parsed from the message when available)
+        id: Option<String>,
+
+        /// Error message
+        message: String,
+    },
+
     /// Container expired error (special case of API error)
     #[error("Container expired: {message}")]
     ContainerExpired {
@@ -176,10 +211,12 @@ pub enum Error {
     },
 
     /// HTTP error
+    #[cfg(feature = "client")]
     #[error("HTTP error: {0}")]
     Http(#[from] reqwest::Error),
 
     /// HTTP status error (fallback for unhandled status codes)
+    #[cfg(feature = "client")]
     #[error("HTTP status error: {0}")]
     HttpStatus(reqwest::StatusCode),
 
@@ -187,6 +224,17 @@ pub enum Error {
     #[error("JSON error: {0}")]
     Json(#[from] serde_json::Error),
 
+    /// The body of a successful response does not match the type it is read into
+    #[error("Failed to decode response body at `{path}`: {message}")]
+    JsonDecode {
+        /// Path to the field that failed, such as `output[0].status`; `.` for the body itself
+        path: String,
+        /// Why the field failed to decode
+        message: String,
+        /// Start of the body, up to 2 KB
+        body_snippet: String,
+    },
+
     /// Stream error
     #[error("Stream error: {0}")]
     Stream(String),
@@ -199,6 +247,14 @@ pub enum Error {
     #[error("API key not found in environment")]
     ApiKeyNotFound,
 
+    /// Base URL could not be parsed, so no request URL can be built
+    #[error("Invalid base URL: {0}")]
+    InvalidBaseUrl(String),
+
+    /// A background handle's status or stream URL is missing, invalid, or not on the API host
+    #[error("Invalid background handle URL: {0}")]
+    InvalidHandleUrl(String),
+
     /// Context recovery error
     #[error("Context recovery failed: {0}")]
     ContextRecovery(String),
@@ -210,6 +266,68 @@ pub enum Error {
     /// MCP error
     #[error("MCP error: {0}")]
     Mcp(String),
+
+    /// Request failed client-side validation
+    #[error("Invalid request: {0}")]
+    Validation(#[from] crate::types::ValidationError),
+
+    /// Encoded data returned by the API, or an image passed in, could not be decoded
+    #[error("Decode error: {0}")]
+    Decode(String),
+
+    /// Reading or writing a local file failed
+    #[error("I/O error: {0}")]
+    Io(#[from] std::io::Error),
+
+    /// A function call's arguments are not valid JSON
+    #[error("Invalid arguments for tool {name} (call {call_id}): {source}")]
+    InvalidToolArguments {
+        /// Name of the called function
+        name: String,
+        /// Call ID of the function call
+        call_id: String,
+        /// Why the arguments failed to parse
+        source: serde_json::Error,
+    },
+
+    /// A webhook delivery failed signature or timestamp verification
+    #[error("Webhook verification failed: {0}")]
+    WebhookVerification(String),
+
+    /// Response body is larger than the client's
+    /// [`max_response_body_bytes`](crate::ClientBuilder::max_response_body_bytes)
+    #[error("Response body too large: {actual} bytes (limit {limit})")]
+    ResponseTooLarge {
+        /// Configured limit in bytes
+        limit: u64,
+        /// Size from `Content-Length`, or the bytes received before reading stopped
+        actual: u64,
+    },
+
+    /// Request body is larger than the
+    /// [`reject_above_bytes`](crate::types::RequestSizeLimits::reject_above_bytes) limit,
+    /// so it was not sent
+    #[error(
+        "Request body too large: {actual} bytes (limit {limit}, largest field {largest_field})"
+    )]
+    RequestTooLarge {
+        /// Configured limit in bytes
+        limit: u64,
+        /// Serialized size of the request
+        actual: u64,
+        /// Field taking up the most bytes, as named by
+        /// [`RequestSizeReport::largest_field`](crate::types::RequestSizeReport::largest_field)
+        largest_field: String,
+    },
+
+    /// A resource was still processing when a wait ran out of time or status checks
+    #[error("Timed out waiting for {resource} after {polls} status checks")]
+    PollTimeout {
+        /// What was being waited on
+        resource: String,
+        /// Status checks made before giving up
+        polls: u32,
+    },
 }
 
 impl Error {
@@ -218,6 +336,7 @@ impl Error {
     pub fn classify(&self) -> ErrorClass {
         match self {
             Self::ContainerExpired { .. } => ErrorClass::ContainerExpired,
+            Self::PreviousResponseNotFound { .. } => ErrorClass::PreviousResponseNotFound,
             Self::Api { message, .. } if message_indicates_container_expired(message) => {
                 ErrorClass::ApiContainerExpired
             }
@@ -229,9 +348,10 @@ impl Error {
                 ..
             } => ErrorClass::RetryableServer,
             Self::RateLimited { .. } => ErrorClass::RateLimited,
+            #[cfg(feature = "client")]
             Self::Http(reqwest_error)
                 if reqwest_error.is_timeout()
-                    || reqwest_error.is_connect()
+                    || crate::runtime::is_connect_error(reqwest_error)
                     || reqwest_error.is_request() =>
             {
                 ErrorClass::TransientHttp
@@ -240,6 +360,13 @@ impl Error {
         }
     }
 
+    /// Returns true if the request's `previous_response_id` names a response that no
+    /// longer exists
+    #[must_use]
+    pub fn is_previous_response_not_found(&self) -> bool {
+        matches!(self, Self::PreviousResponseNotFound { .. })
+    }
+
     /// Returns true if this error indicates a container has expired
     #[must_use]
     pub fn is_container_expired(&self) -> bool {
@@ -249,6 +376,32 @@ impl Error {
         )
     }
 
+    /// Returns true if the model is short on capacity: a 429, 503 or 502.
+    ///
+    /// [`Responses::create_with_fallback`](crate::responses::Responses::create_with_fallback)
+    /// moves on to the next model after these.
+    #[must_use]
+    pub fn is_capacity_error(&self) -> bool {
+        matches!(
+            self,
+            Self::RateLimited { .. } | Self::ServiceUnavailable { .. } | Self::BadGateway { .. }
+        )
+    }
+
+    /// Returns true if this is the 429 sent when flex-tier capacity is temporarily exhausted.
+    ///
+    /// These are retried with exponential backoff by the recovery loop.
+    #[must_use]
+    pub fn is_resource_unavailable(&self) -> bool {
+        matches!(
+            self,
+            Self::RateLimited {
+                limit_type: Some(limit_type),
+                ..
+            } if limit_type == RESOURCE_UNAVAILABLE_LIMIT_TYPE
+        )
+    }
+
     /// Returns true if this error can be automatically recovered from
     #[must_use]
     pub fn is_recoverable(&self) -> bool {
@@ -256,14 +409,9 @@ impl Error {
             ErrorClass::ContainerExpired
             | ErrorClass::RetryableServer
             | ErrorClass::RateLimited
-            | ErrorClass::ApiContainerExpired => true,
-            ErrorClass::TransientHttp => matches!(
-                self,
-                Self::Http(reqwest_error)
-                    if reqwest_error.is_timeout()
-                        || reqwest_error.is_connect()
-                        || reqwest_error.is_request()
-            ),
+            | ErrorClass::ApiContainerExpired
+            | ErrorClass::PreviousResponseNotFound
+            | ErrorClass::TransientHttp => true,
             ErrorClass::NonRecoverable => false,
         }
     }
@@ -276,18 +424,29 @@ impl Error {
             | ErrorClass::RetryableServer
             | ErrorClass::RateLimited
             | ErrorClass::ApiContainerExpired => true,
-            ErrorClass::TransientHttp => matches!(
-                self,
-                Self::Http(reqwest_error)
-                    if reqwest_error.is_timeout() || reqwest_error.is_connect()
-            ),
-            ErrorClass::NonRecoverable => false,
+            ErrorClass::TransientHttp => self.is_timeout_or_connect_error(),
+            // Retrying only helps once the request no longer names the missing response
+            ErrorClass::PreviousResponseNotFound | ErrorClass::NonRecoverable => false,
+        }
+    }
+
+    /// Returns true for HTTP timeouts and connection failures
+    fn is_timeout_or_connect_error(&self) -> bool {
+        match self {
+            #[cfg(feature = "client")]
+            Self::Http(reqwest_error) => {
+                reqwest_error.is_timeout() || crate::runtime::is_connect_error(reqwest_error)
+            }
+            _ => false,
         }
     }
 
-    /// Returns the suggested retry delay in seconds
+    /// Returns the suggested retry delay.
+    ///
+    /// Taken from the `retry-after-ms` or `retry-after` header when the API sent one,
+    /// otherwise a default for the kind of error.
     #[must_use]
-    pub fn retry_after(&self) -> Option<u64> {
+    pub fn retry_after(&self) -> Option<Duration> {
         match self {
             Self::BadGateway { retry_after, .. }
             | Self::ServiceUnavailable { retry_after, .. }
@@ -298,15 +457,29 @@ impl Error {
             Self::ServerError {
                 retry_suggested: true,
                 ..
-            } => Some(5), // 5 seconds
-            Self::ContainerExpired { .. } => Some(1), // 1 second
-            Self::Http(reqwest_error) if reqwest_error.is_timeout() => Some(10), // 10 seconds
-            Self::Http(reqwest_error) if reqwest_error.is_connect() => Some(3), // 3 seconds
+            } => Some(Duration::from_secs(5)),
+            Self::ContainerExpired { .. } => Some(Duration::from_secs(1)),
+            #[cfg(feature = "client")]
+            Self::Http(reqwest_error) if reqwest_error.is_timeout() => {
+                Some(Duration::from_secs(10))
+            }
+            #[cfg(feature = "client")]
+            Self::Http(reqwest_error) if crate::runtime::is_connect_error(reqwest_error) => {
+                Some(Duration::from_secs(3))
+            }
 
             _ => None,
         }
     }
 
+    /// Returns the suggested retry delay in whole seconds, rounded up
+    #[deprecated(note = "use `retry_after`, which returns a `Duration` with millisecond precision")]
+    #[must_use]
+    pub fn retry_after_secs(&self) -> Option<u64> {
+        self.retry_after()
+            .map(|delay| delay.as_secs() + u64::from(delay.subsec_nanos() > 0))
+    }
+
     /// Returns a user-friendly error message
     #[must_use]
     pub fn user_message(&self) -> String {
@@ -318,8 +491,11 @@ impl Error {
             }
 
             Self::ServiceUnavailable { retry_after, .. } => {
-                if let Some(seconds) = retry_after {
-                    format!("Service is temporarily unavailable. Please try again in {seconds} seconds.")
+                if let Some(delay) = retry_after {
+                    format!(
+                        "Service is temporarily unavailable. Please try again in {}.",
+                        describe_delay(*delay)
+                    )
                 } else {
                     "Service is temporarily unavailable. Please try again shortly.".to_string()
                 }
@@ -328,8 +504,11 @@ impl Error {
             Self::GatewayTimeout { .. } => "The request timed out. Please try again.".to_string(),
 
             Self::RateLimited { retry_after, .. } => {
-                if let Some(seconds) = retry_after {
-                    format!("Rate limit exceeded. Please try again in {seconds} seconds.")
+                if let Some(delay) = retry_after {
+                    format!(
+                        "Rate limit exceeded. Please try again in {}.",
+                        describe_delay(*delay)
+                    )
                 } else {
                     "Rate limit exceeded. Please try again shortly.".to_string()
                 }
@@ -347,6 +526,23 @@ impl Error {
                 "Session expired. Retrying with a new session...".to_string()
             }
 
+            Self::PreviousResponseNotFound { .. } => {
+                "The earlier conversation is no longer available. Please start a new conversation."
+                    .to_string()
+            }
+
+            Self::ContentPolicyViolation { .. } => {
+                "The request was rejected by the content policy. Please rephrase your input."
+                    .to_string()
+            }
+
+            Self::ContextLengthExceeded { max: Some(max), .. } => format!(
+                "The input is too long for this model (maximum {max} tokens). Please shorten your input."
+            ),
+            Self::ContextLengthExceeded { .. } => {
+                "The input is too long for this model. Please shorten your input.".to_string()
+            }
+
             Self::InvalidApiKey => "Invalid API key. Please check your API key format.".to_string(),
 
             Self::ApiKeyNotFound => {
@@ -391,7 +587,7 @@ impl Error {
 
     /// Creates a bad gateway error
     #[must_use]
-    pub fn bad_gateway(retry_after: Option<u64>) -> Self {
+    pub fn bad_gateway(retry_after: Option<Duration>) -> Self {
         Self::BadGateway {
             retry_after,
             status_code: 502,
@@ -400,9 +596,9 @@ impl Error {
 
     /// Creates a service unavailable error
     #[must_use]
-    pub fn service_unavailable(retry_after: Option<u64>) -> Self {
-        let retry_message = if let Some(seconds) = retry_after {
-            format!(" in {seconds} seconds")
+    pub fn service_unavailable(retry_after: Option<Duration>) -> Self {
+        let retry_message = if let Some(delay) = retry_after {
+            format!(" in {}", describe_delay(delay))
         } else {
             " shortly".to_string()
         };
@@ -415,15 +611,15 @@ impl Error {
 
     /// Creates a gateway timeout error
     #[must_use]
-    pub fn gateway_timeout(retry_after: Option<u64>) -> Self {
+    pub fn gateway_timeout(retry_after: Option<Duration>) -> Self {
         Self::GatewayTimeout { retry_after }
     }
 
     /// Creates a rate limited error
     #[must_use]
-    pub fn rate_limited(retry_after: Option<u64>, limit_type: Option<String>) -> Self {
-        let retry_message = if let Some(seconds) = retry_after {
-            format!(" in {seconds} seconds")
+    pub fn rate_limited(retry_after: Option<Duration>, limit_type: Option<String>) -> Self {
+        let retry_message = if let Some(delay) = retry_after {
+            format!(" in {}", describe_delay(delay))
         } else {
             " shortly".to_string()
         };
@@ -434,6 +630,68 @@ impl Error {
             limit_type,
         }
     }
+
+    /// Creates a context length exceeded error, parsing the token counts from the message
+    #[must_use]
+    pub fn context_length_exceeded(message: impl Into<String>) -> Self {
+        let message = message.into();
+        let max = parse_number_after(&message, "maximum context length is")
+            .or_else(|| parse_number_after(&message, "context window of"));
+        let requested = parse_number_after(&message, "resulted in")
+            .or_else(|| parse_number_after(&message, "requested"));
+
+        Self::ContextLengthExceeded {
+            message,
+            requested,
+            max,
+        }
+    }
+}
+
+/// Maps well-known API error codes, and messages that have no code of their own, to
+/// dedicated error variants
+fn error_from_code(code: Option<&str>, message: &str) -> Option<Error> {
+    if code == Some("previous_response_not_found")
+        || message_indicates_previous_response_not_found(message)
+    {
+        return Some(Error::PreviousResponseNotFound {
+            id: quoted_response_id(message),
+            message: message.to_string(),
+        });
+    }
+    match code? {
+        "content_policy_violation" => Some(Error::ContentPolicyViolation {
+            message: message.to_string(),
+        }),
+        "context_length_exceeded" => Some(Error::context_length_exceeded(message)),
+        _ => None,
+    }
+}
+
+/// Parses the first integer that follows `marker` in `message`
+fn parse_number_after(message: &str, marker: &str) -> Option<u32> {
+    let start = message.find(marker)? + marker.len();
+    let digits: String = message[start..]
+        .trim_start()
+        .chars()
+        .take_while(|c| c.is_ascii_digit() || *c == ',')
+        .filter(char::is_ascii_digit)
+        .collect();
+    digits.parse().ok()
+}
+
+/// Matches messages such as "Previous response with id 'resp_x' not found."
+fn message_indicates_previous_response_not_found(message: &str) -> bool {
+    let normalized = message.to_ascii_lowercase();
+    normalized.contains("previous response") && normalized.contains("not found")
+}
+
+/// Returns the first `resp_` ID in `message`, with any surrounding quotes removed
+fn quoted_response_id(message: &str) -> Option<String> {
+    message
+        .split(|c: char| c.is_whitespace() || matches!(c, '\'' | '"' | '`'))
+        .find(|word| word.starts_with("resp_"))
+        .map(|id| id.trim_end_matches(['.', ',']).to_string())
 }
 
 fn message_indicates_container_expired(message: &str) -> bool {
@@ -445,6 +703,10 @@ fn message_indicates_container_expired(message: &str) -> bool {
 
 impl From<ApiErrorDetails> for Error {
     fn from(error: ApiErrorDetails) -> Self {
+        if let Some(mapped) = error_from_code(error.code.as_deref(), &error.message) {
+            return mapped;
+        }
+
         // Check if this is a container expiration error
         if error
             .message
@@ -467,29 +729,123 @@ impl From<ApiErrorDetails> for Error {
     }
 }
 
+impl From<crate::types::ResponseError> for Error {
+    /// Maps the error of a failed response, such as the one carried by a
+    /// `response.failed` stream event, onto the same variants as HTTP errors
+    fn from(error: crate::types::ResponseError) -> Self {
+        let details = ApiErrorDetails {
+            message: error.message,
+            error_type: "response_failed".to_string(),
+            code: Some(error.code),
+            param: None,
+        };
+
+        if indicates_resource_unavailable(&details) {
+            return Self::rate_limited(
+                Some(RESOURCE_UNAVAILABLE_RETRY),
+                Some(RESOURCE_UNAVAILABLE_LIMIT_TYPE.to_string()),
+            );
+        }
+        match details.code.as_deref() {
+            Some("rate_limit_exceeded") => Self::rate_limited(None, None),
+            Some("server_error" | "internal_error" | "service_unavailable") => {
+                Self::server_error(details.message, None, true)
+            }
+            _ => details.into(),
+        }
+    }
+}
+
 /// Result type for the crate
 pub type Result<T> = std::result::Result<T, Error>;
 
+/// Formats a retry delay for messages: whole seconds as `30 seconds`, anything else as
+/// `250ms` or `1.5s`
+pub(crate) fn describe_delay(delay: Duration) -> String {
+    if delay.subsec_nanos() == 0 {
+        format!("{} seconds", delay.as_secs())
+    } else {
+        format!("{delay:?}")
+    }
+}
+
+/// Reads the retry delay from `retry-after-ms`, falling back to `retry-after` (in seconds)
+#[cfg(feature = "client")]
+fn parse_retry_after(headers: &reqwest::header::HeaderMap) -> Option<Duration> {
+    let header = |name: &str| headers.get(name).and_then(|value| value.to_str().ok());
+    let millis = header("retry-after-ms")
+        .and_then(|value| value.trim().parse::<f64>().ok())
+        .filter(|millis| millis.is_finite() && *millis >= 0.0)
+        .map(|millis| Duration::from_secs_f64(millis / 1000.0));
+    millis.or_else(|| {
+        header("retry-after")
+            .and_then(|value| value.trim().parse::<u64>().ok())
+            .map(Duration::from_secs)
+    })
+}
+
 /// Helper function to handle specific HTTP status codes
-fn handle_http_status_code(status: reqwest::StatusCode, retry_after: Option<u64>) -> Option<Error> {
+#[cfg(feature = "client")]
+fn handle_http_status_code(
+    status: reqwest::StatusCode,
+    retry_after: Option<Duration>,
+) -> Option<Error> {
     match status.as_u16() {
         // Bad Gateway - always transient
-        502 => Some(Error::bad_gateway(retry_after.or(Some(30)))), // Default 30s retry
+        502 => Some(Error::bad_gateway(
+            retry_after.or(Some(Duration::from_secs(30))),
+        )), // Default 30s retry
 
         // Service Unavailable - always transient
-        503 => Some(Error::service_unavailable(retry_after.or(Some(60)))), // Default 60s retry
+        503 => Some(Error::service_unavailable(
+            retry_after.or(Some(Duration::from_secs(60))),
+        )), // Default 60s retry
 
         // Gateway Timeout - always transient
-        504 => Some(Error::gateway_timeout(retry_after.or(Some(45)))), // Default 45s retry
-
-        // Rate Limited - always recoverable
-        429 => Some(Error::rate_limited(retry_after.or(Some(60)), None)), // Default 60s retry
+        504 => Some(Error::gateway_timeout(
+            retry_after.or(Some(Duration::from_secs(45))),
+        )), // Default 45s retry
 
         _ => None,
     }
 }
 
+/// `limit_type` of a [`Error::RateLimited`] raised because flex-tier capacity is exhausted
+const RESOURCE_UNAVAILABLE_LIMIT_TYPE: &str = "resource_unavailable";
+
+/// Default delay before retrying a flex-tier "Resource unavailable" error
+const RESOURCE_UNAVAILABLE_RETRY: Duration = Duration::from_secs(15);
+
+/// Helper function to handle rate limiting (429)
+#[cfg(feature = "client")]
+async fn handle_rate_limited(response: reqwest::Response, retry_after: Option<Duration>) -> Error {
+    let details = response
+        .bytes()
+        .await
+        .ok()
+        .and_then(|bytes| serde_json::from_slice::<ApiError>(&bytes).ok())
+        .map(|api_error| api_error.error);
+
+    if details.as_ref().is_some_and(indicates_resource_unavailable) {
+        return Error::rate_limited(
+            retry_after.or(Some(RESOURCE_UNAVAILABLE_RETRY)),
+            Some(RESOURCE_UNAVAILABLE_LIMIT_TYPE.to_string()),
+        );
+    }
+
+    Error::rate_limited(retry_after.or(Some(Duration::from_secs(60))), None) // Default 60s retry
+}
+
+fn indicates_resource_unavailable(details: &ApiErrorDetails) -> bool {
+    details.code.as_deref() == Some(RESOURCE_UNAVAILABLE_LIMIT_TYPE)
+        || details
+            .message
+            .to_ascii_lowercase()
+            .contains("resource unavailable")
+}
+
 /// Helper function to handle authentication and authorization errors
+#[cfg(feature = "client")]
 fn handle_auth_errors(status: reqwest::StatusCode) -> Option<Error> {
     match status.as_u16() {
         // Authentication errors
@@ -515,11 +871,18 @@ fn handle_auth_errors(status: reqwest::StatusCode) -> Option<Error> {
 }
 
 /// Helper function to handle client errors (400, 422)
+#[cfg(feature = "client")]
 async fn handle_client_errors(response: reqwest::Response) -> Result<Error> {
     let status_code = response.status().as_u16();
     let bytes = response.bytes().await.map_err(Error::Http)?;
 
     if let Ok(api_error) = serde_json::from_slice::<ApiError>(&bytes) {
+        if let Some(mapped) =
+            error_from_code(api_error.error.code.as_deref(), &api_error.error.message)
+        {
+            return Ok(mapped);
+        }
+
         let suggestion = match status_code {
             400 => Some("Please check your request format and parameters.".to_string()),
             422 => Some("Please check your request data for validation errors.".to_string()),
@@ -545,6 +908,7 @@ async fn handle_client_errors(response: reqwest::Response) -> Result<Error> {
 }
 
 /// Helper function to handle server errors (500-599)
+#[cfg(feature = "client")]
 async fn handle_server_errors(
     response: reqwest::Response,
     request_id: Option<String>,
@@ -599,6 +963,7 @@ async fn handle_server_errors(
 }
 
 /// Helper function to try parsing API errors from responses
+#[cfg(feature = "client")]
 pub(crate) async fn try_parse_api_error(response: reqwest::Response) -> Result<reqwest::Response> {
     let status = response.status();
     if status.is_success() {
@@ -606,11 +971,7 @@ pub(crate) async fn try_parse_api_error(response: reqwest::Response) -> Result<r
     }
 
     // Extract useful headers before consuming the response
-    let retry_after = response
-        .headers()
-        .get("retry-after")
-        .and_then(|h| h.to_str().ok())
-        .and_then(|s| s.parse::<u64>().ok());
+    let retry_after = parse_retry_after(response.headers());
 
     let request_id = response
         .headers()
@@ -619,6 +980,11 @@ pub(crate) async fn try_parse_api_error(response: reqwest::Response) -> Result<r
         .and_then(|h| h.to_str().ok())
         .map(std::string::ToString::to_string);
 
+    // Rate Limited - always recoverable, but the body tells flex capacity errors apart
+    if status == reqwest::StatusCode::TOO_MANY_REQUESTS {
+        return Err(handle_rate_limited(response, retry_after).await);
+    }
+
     // Handle specific HTTP status codes first
     if let Some(error) = handle_http_status_code(status, retry_after) {
         return Err(error);
@@ -652,15 +1018,14 @@ pub(crate) async fn try_parse_api_error(response: reqwest::Response) -> Result<r
 #[cfg(test)]
 mod tests {
     use super::*;
-    use std::time::Duration;
 
     #[test]
     fn test_new_error_types() {
         // Test BadGateway error
-        let bad_gateway = Error::bad_gateway(Some(30));
+        let bad_gateway = Error::bad_gateway(Some(Duration::from_secs(30)));
         assert!(bad_gateway.is_recoverable());
         assert!(bad_gateway.is_transient());
-        assert_eq!(bad_gateway.retry_after(), Some(30));
+        assert_eq!(bad_gateway.retry_after(), Some(Duration::from_secs(30)));
         assert_eq!(
             bad_gateway.user_message(),
             "The service is temporarily unavailable. Please try again in a moment."
@@ -671,7 +1036,7 @@ mod tests {
             Error::server_error("Internal server error", Some("req_123".to_string()), true);
         assert!(server_error.is_recoverable());
         assert!(server_error.is_transient());
-        assert_eq!(server_error.retry_after(), Some(5));
+        assert_eq!(server_error.retry_after(), Some(Duration::from_secs(5)));
 
         // Test non-retryable server error
         let non_retryable = Error::server_error("Fatal error", None, false);
@@ -679,22 +1044,29 @@ mod tests {
         assert!(!non_retryable.is_transient());
 
         // Test ServiceUnavailable
-        let service_unavailable = Error::service_unavailable(Some(60));
+        let service_unavailable = Error::service_unavailable(Some(Duration::from_secs(60)));
         assert!(service_unavailable.is_recoverable());
         assert!(service_unavailable.is_transient());
-        assert_eq!(service_unavailable.retry_after(), Some(60));
+        assert_eq!(
+            service_unavailable.retry_after(),
+            Some(Duration::from_secs(60))
+        );
 
         // Test RateLimited
-        let rate_limited = Error::rate_limited(Some(120), Some("requests".to_string()));
+        let rate_limited =
+            Error::rate_limited(Some(Duration::from_secs(120)), Some("requests".to_string()));
         assert!(rate_limited.is_recoverable());
         assert!(rate_limited.is_transient());
-        assert_eq!(rate_limited.retry_after(), Some(120));
+        assert_eq!(rate_limited.retry_after(), Some(Duration::from_secs(120)));
 
         // Test ContainerExpired (existing functionality)
         let container_expired = Error::container_expired("Container expired", false);
         assert!(container_expired.is_recoverable());
         assert!(container_expired.is_container_expired());
-        assert_eq!(container_expired.retry_after(), Some(1));
+        assert_eq!(
+            container_expired.retry_after(),
+            Some(Duration::from_secs(1))
+        );
     }
 
     #[test]
@@ -705,13 +1077,13 @@ mod tests {
             "The service is temporarily unavailable. Please try again in a moment."
         );
 
-        let service_unavailable = Error::service_unavailable(Some(30));
+        let service_unavailable = Error::service_unavailable(Some(Duration::from_secs(30)));
         assert_eq!(
             service_unavailable.user_message(),
             "Service is temporarily unavailable. Please try again in 30 seconds."
         );
 
-        let rate_limited = Error::rate_limited(Some(60), None);
+        let rate_limited = Error::rate_limited(Some(Duration::from_secs(60)), None);
         assert_eq!(
             rate_limited.user_message(),
             "Rate limit exceeded. Please try again in 60 seconds."
@@ -784,37 +1156,37 @@ mod tests {
     }
 
     fn test_bad_gateway_error() {
-        let error = Error::bad_gateway(Some(30));
+        let error = Error::bad_gateway(Some(Duration::from_secs(30)));
         assert!(error.is_transient());
         assert!(error.is_recoverable());
-        assert_eq!(error.retry_after(), Some(30));
+        assert_eq!(error.retry_after(), Some(Duration::from_secs(30)));
         assert!(error.user_message().contains("temporarily unavailable"));
     }
 
     fn test_service_unavailable_error() {
-        let error = Error::service_unavailable(Some(60));
+        let error = Error::service_unavailable(Some(Duration::from_secs(60)));
         assert!(error.is_transient());
         assert!(error.is_recoverable());
-        assert_eq!(error.retry_after(), Some(60));
+        assert_eq!(error.retry_after(), Some(Duration::from_secs(60)));
         assert!(error.user_message().contains("unavailable"));
     }
 
     fn test_gateway_timeout_error() {
-        let error = Error::gateway_timeout(Some(45));
+        let error = Error::gateway_timeout(Some(Duration::from_secs(45)));
         assert!(error.is_transient());
         assert!(error.is_recoverable());
-        assert_eq!(error.retry_after(), Some(45));
+        assert_eq!(error.retry_after(), Some(Duration::from_secs(45)));
         assert!(error.user_message().contains("timed out"));
     }
 
     fn test_rate_limited_error() {
         let error = Error::rate_limited(
-            Some(120),
+            Some(Duration::from_secs(120)),
             Some("You have exceeded your rate limit".to_string()),
         );
         assert!(error.is_transient()); // Rate limiting is transient - it will reset after time
         assert!(error.is_recoverable());
-        assert_eq!(error.retry_after(), Some(120));
+        assert_eq!(error.retry_after(), Some(Duration::from_secs(120)));
         assert!(error.user_message().contains("Rate limit"));
     }
 
@@ -826,7 +1198,7 @@ mod tests {
         );
         assert!(error.is_transient());
         assert!(error.is_recoverable());
-        assert_eq!(error.retry_after(), Some(5)); // Default retry for server errors
+        assert_eq!(error.retry_after(), Some(Duration::from_secs(5))); // Default retry for server errors
         assert!(error.user_message().contains("server encountered an error"));
     }
 
@@ -874,15 +1246,15 @@ mod tests {
                 "The service is temporarily unavailable. Please try again in a moment.",
             ),
             (
-                Error::service_unavailable(Some(60)),
+                Error::service_unavailable(Some(Duration::from_secs(60))),
                 "Service is temporarily unavailable. Please try again in 60 seconds.",
             ),
             (
-                Error::gateway_timeout(Some(45)),
+                Error::gateway_timeout(Some(Duration::from_secs(45))),
                 "The request timed out. Please try again.",
             ),
             (
-                Error::rate_limited(Some(120), None),
+                Error::rate_limited(Some(Duration::from_secs(120)), None),
                 "Rate limit exceeded. Please try again in 120 seconds.",
             ),
             (
@@ -908,38 +1280,39 @@ mod tests {
     fn test_error_factory_methods() {
         // Test all error factory methods work correctly
 
-        let bad_gateway = Error::bad_gateway(Some(30));
+        let bad_gateway = Error::bad_gateway(Some(Duration::from_secs(30)));
         if let Error::BadGateway {
             retry_after,
             status_code,
         } = bad_gateway
         {
-            assert_eq!(retry_after, Some(30));
+            assert_eq!(retry_after, Some(Duration::from_secs(30)));
             assert_eq!(status_code, 502);
         } else {
             panic!("Expected BadGateway error");
         }
 
-        let service_unavailable = Error::service_unavailable(Some(60));
+        let service_unavailable = Error::service_unavailable(Some(Duration::from_secs(60)));
         if let Error::ServiceUnavailable {
             retry_after,
             retry_message,
         } = service_unavailable
         {
-            assert_eq!(retry_after, Some(60));
+            assert_eq!(retry_after, Some(Duration::from_secs(60)));
             assert_eq!(retry_message, " in 60 seconds");
         } else {
             panic!("Expected ServiceUnavailable error");
         }
 
-        let rate_limited = Error::rate_limited(Some(120), Some("tokens".to_string()));
+        let rate_limited =
+            Error::rate_limited(Some(Duration::from_secs(120)), Some("tokens".to_string()));
         if let Error::RateLimited {
             retry_after,
             limit_type,
             ..
         } = rate_limited
         {
-            assert_eq!(retry_after, Some(120));
+            assert_eq!(retry_after, Some(Duration::from_secs(120)));
             assert_eq!(limit_type, Some("tokens".to_string()));
         } else {
             panic!("Expected RateLimited error");
@@ -961,6 +1334,239 @@ mod tests {
         }
     }
 
+    #[test]
+    fn test_content_policy_and_context_length_mapping() {
+        let policy = Error::from(ApiErrorDetails {
+            message: "Your request was rejected as a result of our safety system.".to_string(),
+            error_type: "invalid_request_error".to_string(),
+            code: Some("content_policy_violation".to_string()),
+            param: None,
+        });
+        assert!(matches!(policy, Error::ContentPolicyViolation { .. }));
+        assert!(!policy.is_recoverable());
+        assert_eq!(policy.classify(), ErrorClass::NonRecoverable);
+
+        let context = Error::from(ApiErrorDetails {
+            message: "This model's maximum context length is 128,000 tokens. However, your messages resulted in 130512 tokens.".to_string(),
+            error_type: "invalid_request_error".to_string(),
+            code: Some("context_length_exceeded".to_string()),
+            param: Some("input".to_string()),
+        });
+        if let Error::ContextLengthExceeded { requested, max, .. } = &context {
+            assert_eq!(*requested, Some(130_512));
+            assert_eq!(*max, Some(128_000));
+        } else {
+            panic!("Expected ContextLengthExceeded error, got {context:?}");
+        }
+        assert!(!context.is_recoverable());
+        assert!(!context.is_transient());
+
+        let unparsed = Error::context_length_exceeded("Your input exceeds the context window.");
+        if let Error::ContextLengthExceeded { requested, max, .. } = unparsed {
+            assert_eq!(requested, None);
+            assert_eq!(max, None);
+        } else {
+            panic!("Expected ContextLengthExceeded error");
+        }
+    }
+
+    #[cfg(feature = "client")]
+    #[tokio::test]
+    async fn test_flex_resource_unavailable_is_retryable_rate_limit() {
+        let mut server = mockito::Server::new_async().await;
+        let _flex = server
+            .mock("POST", "/flex")
+            .with_status(429)
+            .with_body(r#"{"error":{"message":"Resource unavailable. Please try again later.","type":"server_error","code":"resource_unavailable","param":null}}"#)
+            .create_async()
+            .await;
+        let _quota = server
+            .mock("POST", "/quota")
+            .with_status(429)
+            .with_header("retry-after", "7")
+            .with_body(r#"{"error":{"message":"Rate limit reached for requests","type":"requests","code":"rate_limit_exceeded","param":null}}"#)
+            .create_async()
+            .await;
+
+        let client = reqwest::Client::new();
+
+        let response = client
+            .post(format!("{}/flex", server.url()))
+            .send()
+            .await
+            .expect("request should succeed");
+        let error = try_parse_api_error(response)
+            .await
+            .expect_err("expected flex error");
+        assert!(error.is_resource_unavailable());
+        assert!(error.is_recoverable());
+        assert_eq!(error.classify(), ErrorClass::RateLimited);
+        assert_eq!(error.retry_after(), Some(RESOURCE_UNAVAILABLE_RETRY));
+
+        let response = client
+            .post(format!("{}/quota", server.url()))
+            .send()
+            .await
+            .expect("request should succeed");
+        let error = try_parse_api_error(response)
+            .await
+            .expect_err("expected rate limit error");
+        assert!(!error.is_resource_unavailable());
+        assert_eq!(error.retry_after(), Some(Duration::from_secs(7)));
+    }
+
+    #[cfg(feature = "client")]
+    #[tokio::test]
+    #[allow(deprecated)]
+    async fn test_retry_after_ms_is_preferred_over_retry_after() {
+        let mut server = mockito::Server::new_async().await;
+        let _both = server
+            .mock("POST", "/both")
+            .with_status(429)
+            .with_header("retry-after-ms", "250")
+            .with_header("retry-after", "1")
+            .create_async()
+            .await;
+        let _fractional = server
+            .mock("POST", "/fractional")
+            .with_status(503)
+            .with_header("retry-after-ms", "1500.5")
+            .create_async()
+            .await;
+        let _invalid = server
+            .mock("POST", "/invalid")
+            .with_status(502)
+            .with_header("retry-after-ms", "soon")
+            .with_header("retry-after", "3")
+            .create_async()
+            .await;
+
+        let client = reqwest::Client::new();
+        let mut errors = Vec::new();
+        for path in ["both", "fractional", "invalid"] {
+            let response = client
+                .post(format!("{}/{path}", server.url()))
+                .send()
+                .await
+                .expect("request should succeed");
+            errors.push(
+                try_parse_api_error(response)
+                    .await
+                    .expect_err("expected error"),
+            );
+        }
+
+        assert_eq!(errors[0].retry_after(), Some(Duration::from_millis(250)));
+        assert_eq!(errors[0].retry_after_secs(), Some(1));
+        assert_eq!(
+            errors[0].user_message(),
+            "Rate limit exceeded. Please try again in 250ms."
+        );
+        assert_eq!(
+            errors[1].retry_after(),
+            Some(Duration::from_micros(1_500_500))
+        );
+        assert_eq!(errors[1].retry_after_secs(), Some(2));
+        assert_eq!(errors[2].retry_after(), Some(Duration::from_secs(3)));
+        assert_eq!(errors[2].retry_after_secs(), Some(3));
+    }
+
+    #[cfg(feature = "client")]
+    #[tokio::test]
+    async fn test_client_error_codes_map_to_dedicated_variants() {
+        let mut server = mockito::Server::new_async().await;
+        let _policy = server
+            .mock("POST", "/policy")
+            .with_status(400)
+            .with_body(r#"{"error":{"message":"Flagged by moderation","type":"invalid_request_error","code":"content_policy_violation","param":null}}"#)
+            .create();
+        let _context = server
+            .mock("POST", "/context")
+            .with_status(400)
+            .with_body(r#"{"error":{"message":"This model's maximum context length is 8192 tokens, however you requested 9000 tokens","type":"invalid_request_error","code":"context_length_exceeded","param":null}}"#)
+            .create();
+
+        let client = reqwest::Client::new();
+
+        let response = client
+            .post(format!("{}/policy", server.url()))
+            .send()
+            .await
+            .expect("request should succeed");
+        let error = try_parse_api_error(response)
+            .await
+            .expect_err("expected policy error");
+        assert!(matches!(error, Error::ContentPolicyViolation { .. }));
+
+        let response = client
+            .post(format!("{}/context", server.url()))
+            .send()
+            .await
+            .expect("request should succeed");
+        let error = try_parse_api_error(response)
+            .await
+            .expect_err("expected context length error");
+        match error {
+            Error::ContextLengthExceeded { requested, max, .. } => {
+                assert_eq!(requested, Some(9000));
+                assert_eq!(max, Some(8192));
+            }
+            other => panic!("Expected ContextLengthExceeded error, got {other:?}"),
+        }
+    }
+
+    #[cfg(feature = "client")]
+    #[tokio::test]
+    async fn test_missing_previous_response_is_detected_for_400_and_404() {
+        let mut server = mockito::Server::new_async().await;
+        let _bad_request = server
+            .mock("POST", "/400")
+            .with_status(400)
+            .with_body(r#"{"error":{"message":"Previous response with id 'resp_gone' not found.","type":"invalid_request_error","code":"previous_response_not_found","param":"previous_response_id"}}"#)
+            .create_async()
+            .await;
+        let _not_found = server
+            .mock("POST", "/404")
+            .with_status(404)
+            .with_body(r#"{"error":{"message":"Previous response with id 'resp_old' not found.","type":"invalid_request_error","code":null,"param":null}}"#)
+            .create_async()
+            .await;
+        let _other = server
+            .mock("POST", "/other")
+            .with_status(404)
+            .with_body(r#"{"error":{"message":"No response found with id 'resp_x'.","type":"invalid_request_error","code":null,"param":null}}"#)
+            .create_async()
+            .await;
+
+        let client = reqwest::Client::new();
+        for (path, expected) in [("400", "resp_gone"), ("404", "resp_old")] {
+            let response = client
+                .post(format!("{}/{path}", server.url()))
+                .send()
+                .await
+                .unwrap();
+            let error = try_parse_api_error(response).await.unwrap_err();
+            match &error {
+                Error::PreviousResponseNotFound { id, .. } => {
+                    assert_eq!(id.as_deref(), Some(expected));
+                }
+                other => panic!("Expected PreviousResponseNotFound, got {other:?}"),
+            }
+            assert_eq!(error.classify(), ErrorClass::PreviousResponseNotFound);
+            assert!(error.is_recoverable());
+            assert!(!error.is_transient());
+        }
+
+        let response = client
+            .post(format!("{}/other", server.url()))
+            .send()
+            .await
+            .unwrap();
+        let error = try_parse_api_error(response).await.unwrap_err();
+        assert!(!error.is_previous_response_not_found(), "{error:?}");
+    }
+
+    #[cfg(feature = "client")]
     #[test]
     fn classify_error_classes() {
         let container = Error::container_expired("Session expired", false);
@@ -980,7 +1586,7 @@ mod tests {
         let retryable_server = Error::server_error("Server hiccup", None, true);
         assert_eq!(retryable_server.classify(), ErrorClass::RetryableServer);
 
-        let rate_limited = Error::rate_limited(Some(1), None);
+        let rate_limited = Error::rate_limited(Some(Duration::from_secs(1)), None);
         assert_eq!(rate_limited.classify(), ErrorClass::RateLimited);
 
         let runtime = tokio::runtime::Runtime::new().expect("runtime");
@@ -1,15 +1,24 @@
-use crate::error::{try_parse_api_error, Result};
-use crate::types::{PaginatedList, PaginationParams};
+use crate::endpoint::Endpoint;
+use crate::error::Result;
+use crate::http::Transport;
+use crate::retry::Retrying;
+use crate::types::{DeleteConfirmation, Order, PaginatedList, PaginationParams, RecoveryPolicy};
 use chrono::{DateTime, Utc};
-use reqwest::Client as HttpClient;
+use reqwest::StatusCode;
 use serde::{Deserialize, Serialize};
-use std::path::Path;
+#[cfg(not(target_arch = "wasm32"))]
+use std::path::{Path, PathBuf};
+#[cfg(not(target_arch = "wasm32"))]
+use std::sync::atomic::{AtomicUsize, Ordering};
+#[cfg(not(target_arch = "wasm32"))]
+use std::sync::Arc;
 
 /// Files API endpoints
 #[derive(Debug, Clone)]
 pub struct Files {
-    client: HttpClient,
-    base_url: String,
+    client: Transport,
+    endpoint: Endpoint,
+    recovery_policy: RecoveryPolicy,
 }
 
 /// File object representing a file in the API
@@ -19,6 +28,7 @@ pub struct File {
     pub id: String,
 
     /// Type of object (always "file")
+    #[cfg_attr(feature = "lenient", serde(default))]
     pub object: String,
 
     /// Name of the file
@@ -31,19 +41,28 @@ pub struct File {
     pub bytes: u64,
 
     /// Unix timestamp for when the file was created
-    #[serde(with = "chrono::serde::ts_seconds")]
+    #[serde(with = "crate::types::lenient::timestamp")]
     pub created_at: DateTime<Utc>,
 
     /// Status of the file
+    #[serde(deserialize_with = "crate::types::lenient::status")]
     pub status: String,
 
     /// Status details if the file is in an error state
     #[serde(skip_serializing_if = "Option::is_none")]
     pub status_details: Option<String>,
+
+    /// Unix timestamp for when the file expires, if it has an expiration policy
+    #[serde(
+        default,
+        skip_serializing_if = "Option::is_none",
+        with = "chrono::serde::ts_seconds_option"
+    )]
+    pub expires_at: Option<DateTime<Utc>>,
 }
 
 /// Purpose of a file
-#[derive(Debug, Clone, Serialize, Deserialize)]
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
 #[serde(rename_all = "snake_case")]
 pub enum FilePurpose {
     /// File for assistants
@@ -57,6 +76,18 @@ pub enum FilePurpose {
     Custom(String),
 }
 
+impl FilePurpose {
+    /// Returns the purpose as sent to the API
+    #[must_use]
+    pub fn as_str(&self) -> &str {
+        match self {
+            Self::Assistants => "assistants",
+            Self::FineTuning => "fine-tuning",
+            Self::Custom(s) => s,
+        }
+    }
+}
+
 impl From<&str> for FilePurpose {
     fn from(s: &str) -> Self {
         match s {
@@ -89,12 +120,296 @@ pub struct CreateFileRequest {
     /// Optional MIME type for the file
     #[serde(skip)]
     pub mime_type: Option<String>,
+
+    /// Optional expiration policy for the file
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub expires_after: Option<ExpiresAfter>,
+}
+
+/// Anchor timestamp an [`ExpiresAfter`] policy counts from
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum ExpiresAfterAnchor {
+    /// The file's creation time
+    #[default]
+    CreatedAt,
+}
+
+impl ExpiresAfterAnchor {
+    /// Returns the anchor as sent to the API
+    #[must_use]
+    pub fn as_str(self) -> &'static str {
+        match self {
+            Self::CreatedAt => "created_at",
+        }
+    }
+}
+
+/// Expiration policy for an uploaded file
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub struct ExpiresAfter {
+    /// Timestamp the expiration counts from
+    pub anchor: ExpiresAfterAnchor,
+
+    /// Seconds after the anchor until the file expires (3600 to 2592000)
+    pub seconds: u64,
+}
+
+impl ExpiresAfter {
+    /// Expires the file the given number of seconds after it was created
+    #[must_use]
+    pub fn created_at(seconds: u64) -> Self {
+        Self {
+            anchor: ExpiresAfterAnchor::CreatedAt,
+            seconds,
+        }
+    }
+}
+
+/// Parameters for [`Files::list`] and [`Files::list_with`]
+///
+/// ```rust
+/// use open_ai_rust_responses_by_sshift::files::{FilePurpose, ListFilesParams};
+/// use open_ai_rust_responses_by_sshift::types::Order;
+///
+/// let params = ListFilesParams::new()
+///     .purpose(FilePurpose::Assistants)
+///     .order(Order::Desc)
+///     .limit(20);
+/// ```
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct ListFilesParams {
+    /// Only return files with this purpose
+    pub purpose: Option<FilePurpose>,
+
+    /// Sort order by creation time
+    pub order: Option<Order>,
+
+    /// Maximum number of files to return
+    pub limit: Option<u32>,
+
+    /// Returns files after the given file ID
+    pub after: Option<String>,
+}
+
+impl ListFilesParams {
+    /// Largest `limit` the files endpoint accepts
+    pub const MAX_LIMIT: u32 = 10_000;
+
+    /// Creates empty list parameters (server defaults)
+    #[must_use]
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Only returns files with the given purpose
+    #[must_use]
+    pub fn purpose(mut self, purpose: impl Into<FilePurpose>) -> Self {
+        self.purpose = Some(purpose.into());
+        self
+    }
+
+    /// Sets the sort order
+    #[must_use]
+    pub fn order(mut self, order: Order) -> Self {
+        self.order = Some(order);
+        self
+    }
+
+    /// Sets the maximum number of files to return, up to [`MAX_LIMIT`](Self::MAX_LIMIT)
+    #[must_use]
+    pub fn limit(mut self, limit: u32) -> Self {
+        self.limit = Some(limit);
+        self
+    }
+
+    /// Returns files after the given file ID
+    #[must_use]
+    pub fn after(mut self, after: impl Into<String>) -> Self {
+        self.after = Some(after.into());
+        self
+    }
+
+    fn pagination(&self) -> PaginationParams {
+        PaginationParams {
+            limit: self.limit,
+            after: self.after.clone(),
+            before: None,
+            order: self.order,
+        }
+    }
+}
+
+impl From<PaginationParams> for ListFilesParams {
+    /// Keeps `limit`, `after` and `order`; the files endpoint has no `before` cursor
+    fn from(params: PaginationParams) -> Self {
+        Self {
+            purpose: None,
+            order: params.order,
+            limit: params.limit,
+            after: params.after,
+        }
+    }
+}
+
+/// Default number of uploads [`Files::upload_many`] runs at once
+#[cfg(not(target_arch = "wasm32"))]
+pub const DEFAULT_UPLOAD_CONCURRENCY: usize = 4;
+
+/// Callback that receives [`Files::upload_many`] progress
+#[cfg(not(target_arch = "wasm32"))]
+pub type UploadProgressCallback = Arc<dyn Fn(UploadProgress) + Send + Sync>;
+
+/// Options for [`Files::upload_many`]
+#[cfg(not(target_arch = "wasm32"))]
+#[derive(Clone)]
+pub struct UploadManyOptions {
+    /// Maximum number of uploads in flight at once; 0 is treated as 1
+    pub concurrency: usize,
+
+    /// MIME type sent for every file instead of the one guessed from its name
+    pub mime_type_override: Option<String>,
+
+    /// Called when an upload starts, completes or fails
+    pub on_progress: Option<UploadProgressCallback>,
+}
+
+#[cfg(not(target_arch = "wasm32"))]
+impl Default for UploadManyOptions {
+    fn default() -> Self {
+        Self {
+            concurrency: DEFAULT_UPLOAD_CONCURRENCY,
+            mime_type_override: None,
+            on_progress: None,
+        }
+    }
+}
+
+#[cfg(not(target_arch = "wasm32"))]
+impl std::fmt::Debug for UploadManyOptions {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("UploadManyOptions")
+            .field("concurrency", &self.concurrency)
+            .field("mime_type_override", &self.mime_type_override)
+            .field("on_progress", &self.on_progress.is_some())
+            .finish()
+    }
+}
+
+#[cfg(not(target_arch = "wasm32"))]
+impl UploadManyOptions {
+    /// Creates options with [`DEFAULT_UPLOAD_CONCURRENCY`] and no progress callback
+    #[must_use]
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Sets the maximum number of uploads in flight at once
+    #[must_use]
+    pub fn with_concurrency(mut self, concurrency: usize) -> Self {
+        self.concurrency = concurrency;
+        self
+    }
+
+    /// Sends `mime_type` for every file
+    #[must_use]
+    pub fn with_mime_type_override(mut self, mime_type: impl Into<String>) -> Self {
+        self.mime_type_override = Some(mime_type.into());
+        self
+    }
+
+    /// Sets the progress callback
+    #[must_use]
+    pub fn with_progress(
+        mut self,
+        on_progress: impl Fn(UploadProgress) + Send + Sync + 'static,
+    ) -> Self {
+        self.on_progress = Some(Arc::new(on_progress));
+        self
+    }
+}
+
+/// What happened to one file of a [`Files::upload_many`] call
+#[cfg(not(target_arch = "wasm32"))]
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum UploadEvent {
+    /// The file is being read and sent
+    Started,
+    /// The file was uploaded
+    Completed {
+        /// ID of the uploaded file
+        file_id: String,
+    },
+    /// The file could not be read or uploaded
+    Failed {
+        /// Why the upload failed
+        error: String,
+    },
+}
+
+/// Progress report passed to the [`UploadManyOptions::on_progress`] callback
+#[cfg(not(target_arch = "wasm32"))]
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct UploadProgress {
+    /// Position of the file in the paths passed to [`Files::upload_many`]
+    pub index: usize,
+
+    /// Path of the file
+    pub path: PathBuf,
+
+    /// What just happened to the file
+    pub event: UploadEvent,
+
+    /// Uploads started so far, this one included
+    pub started: usize,
+
+    /// Uploads completed so far
+    pub completed: usize,
+
+    /// Uploads failed so far
+    pub failed: usize,
+
+    /// Number of files in the call
+    pub total: usize,
+}
+
+#[cfg(not(target_arch = "wasm32"))]
+impl UploadProgress {
+    /// Returns true once every file has completed or failed
+    #[must_use]
+    pub fn is_finished(&self) -> bool {
+        self.completed + self.failed == self.total
+    }
 }
 
 impl Files {
-    /// Creates a new Files API client
-    pub(crate) fn new(client: HttpClient, base_url: String) -> Self {
-        Self { client, base_url }
+    /// Creates a new Files API client that retries failed requests as `recovery_policy` allows
+    pub(crate) fn new(
+        client: impl Into<Transport>,
+        endpoint: impl Into<Endpoint>,
+        recovery_policy: RecoveryPolicy,
+    ) -> Self {
+        Self {
+            client: client.into(),
+            endpoint: endpoint.into(),
+            recovery_policy,
+        }
+    }
+
+    /// Returns a builder for a Files API client that is used on its own, with its own
+    /// base URL and recovery policy
+    pub fn builder() -> crate::EndpointBuilder<Self> {
+        crate::EndpointBuilder::default()
+    }
+
+    /// Sends this handle's requests with `auth` as the `Authorization` header
+    pub(crate) fn with_auth(mut self, auth: &reqwest::header::HeaderValue) -> Self {
+        self.client = self.client.with_auth(auth.clone());
+        self
+    }
+
+    fn retrying(&self) -> Retrying<'_> {
+        Retrying::new(&self.client, &self.recovery_policy)
     }
 
     /// Creates a new file.
@@ -103,47 +418,50 @@ impl Files {
     ///
     /// Returns an error if the request fails to send or has a non-200 status code.
     pub async fn create(&self, request: CreateFileRequest) -> Result<File> {
-        let file_part = if let Some(mime) = &request.mime_type {
-            reqwest::multipart::Part::bytes(request.file)
-                .file_name(request.filename.clone())
-                .mime_str(mime)
-                .map_err(|e| crate::Error::Stream(e.to_string()))?
-        } else {
-            // Infer MIME type from filename
-            let mime = mime_guess::from_path(&request.filename).first_or_octet_stream();
-
-            reqwest::multipart::Part::bytes(request.file)
-                .file_name(request.filename.clone())
-                .mime_str(mime.as_ref())
-                .map_err(|e| crate::Error::Stream(e.to_string()))?
-        };
-
-        let form = reqwest::multipart::Form::new()
-            .text("purpose", request.purpose)
-            .part("file", file_part);
-
-        let response = self
-            .client
-            .post(format!("{}/files", self.base_url))
-            .multipart(form)
-            .send()
+        let url = self.endpoint.url(&["files"])?;
+        // A multipart body is consumed by sending, so each attempt builds its own
+        self.retrying()
+            .send_json(|| {
+                Ok(self
+                    .client
+                    .post(url.clone())
+                    .multipart(upload_form(&request)?))
+            })
             .await
-            .map_err(crate::Error::Http)?;
-
-        let response = try_parse_api_error(response).await?;
-        response.json().await.map_err(crate::Error::Http)
     }
 
     /// Uploads a file from a path.
     ///
+    /// Not available on wasm32, which has no local filesystem; use [`Files::create`] instead.
+    ///
     /// # Errors
     ///
     /// Returns an error if the file cannot be read, the request fails to send, or has a non-200 status code.
+    #[cfg(not(target_arch = "wasm32"))]
     pub async fn upload_file<P: AsRef<Path>>(
         &self,
         path: P,
         purpose: impl Into<FilePurpose>,
         mime_type: Option<String>,
+    ) -> Result<File> {
+        self.upload_file_with_expiration(path, purpose, mime_type, None)
+            .await
+    }
+
+    /// Uploads a file from a path with an optional expiration policy.
+    ///
+    /// Not available on wasm32, which has no local filesystem; use [`Files::create`] instead.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the file cannot be read, the request fails to send, or has a non-200 status code.
+    #[cfg(not(target_arch = "wasm32"))]
+    pub async fn upload_file_with_expiration<P: AsRef<Path>>(
+        &self,
+        path: P,
+        purpose: impl Into<FilePurpose>,
+        mime_type: Option<String>,
+        expires_after: Option<ExpiresAfter>,
     ) -> Result<File> {
         let path = path.as_ref();
         let filename = path
@@ -155,55 +473,146 @@ impl Files {
         let file_data = std::fs::read(path)
             .map_err(|e| crate::Error::Stream(format!("Failed to read file: {e}")))?;
 
-        let purpose_str = match purpose.into() {
-            FilePurpose::Assistants => "assistants".to_string(),
-            FilePurpose::FineTuning => "fine-tuning".to_string(),
-            FilePurpose::Custom(s) => s,
-        };
-
         let request = CreateFileRequest {
-            purpose: purpose_str,
+            purpose: purpose.into().as_str().to_string(),
             file: file_data,
             filename,
             mime_type,
+            expires_after,
         };
 
         self.create(request).await
     }
 
+    /// Uploads many files at once, at most `options.concurrency` at a time.
+    ///
+    /// Results are returned in the order of `paths`, and a file that fails does not stop
+    /// the others. Each file is read into memory just before it is sent, so at most
+    /// `concurrency` files are held at a time. Not available on wasm32.
+    ///
+    /// ```rust,no_run
+    /// # async fn run(client: open_ai_rust_responses_by_sshift::Client) {
+    /// use open_ai_rust_responses_by_sshift::files::{FilePurpose, UploadManyOptions};
+    ///
+    /// let paths = vec!["docs/a.pdf".into(), "docs/b.pdf".into()];
+    /// let options = UploadManyOptions::new()
+    ///     .with_concurrency(8)
+    ///     .with_progress(|progress| {
+    ///         println!("{}/{} done", progress.completed + progress.failed, progress.total);
+    ///     });
+    /// for result in client.files.upload_many(paths, FilePurpose::Assistants, options).await {
+    ///     match result {
+    ///         Ok(file) => println!("uploaded {}", file.id),
+    ///         Err(error) => eprintln!("failed: {error}"),
+    ///     }
+    /// }
+    /// # }
+    /// ```
+    #[cfg(not(target_arch = "wasm32"))]
+    pub async fn upload_many(
+        &self,
+        paths: Vec<PathBuf>,
+        purpose: impl Into<FilePurpose>,
+        options: UploadManyOptions,
+    ) -> Vec<Result<File>> {
+        use futures_util::StreamExt;
+
+        let purpose = purpose.into();
+        let total = paths.len();
+        let counts = [
+            AtomicUsize::new(0),
+            AtomicUsize::new(0),
+            AtomicUsize::new(0),
+        ];
+        let report = |index: usize, path: &Path, event: UploadEvent| {
+            let slot = match event {
+                UploadEvent::Started => 0,
+                UploadEvent::Completed { .. } => 1,
+                UploadEvent::Failed { .. } => 2,
+            };
+            counts[slot].fetch_add(1, Ordering::SeqCst);
+            if let Some(on_progress) = &options.on_progress {
+                on_progress(UploadProgress {
+                    index,
+                    path: path.to_path_buf(),
+                    event,
+                    started: counts[0].load(Ordering::SeqCst),
+                    completed: counts[1].load(Ordering::SeqCst),
+                    failed: counts[2].load(Ordering::SeqCst),
+                    total,
+                });
+            }
+        };
+
+        futures_util::stream::iter(paths.iter().enumerate())
+            .map(|(index, path)| {
+                let (purpose, report) = (purpose.clone(), &report);
+                let mime_type = options.mime_type_override.clone();
+                async move {
+                    report(index, path, UploadEvent::Started);
+                    let result = self.upload_file(path, purpose, mime_type).await;
+                    let event = match &result {
+                        Ok(file) => UploadEvent::Completed {
+                            file_id: file.id.clone(),
+                        },
+                        Err(error) => UploadEvent::Failed {
+                            error: error.to_string(),
+                        },
+                    };
+                    report(index, path, event);
+                    result
+                }
+            })
+            .buffered(options.concurrency.max(1))
+            .collect()
+            .await
+    }
+
     /// Retrieves a file with the given ID.
     ///
     /// # Errors
     ///
     /// Returns an error if the request fails to send or has a non-200 status code.
     pub async fn get(&self, file_id: &str) -> Result<File> {
-        let response = self
-            .client
-            .get(format!("{}/files/{}", self.base_url, file_id))
-            .send()
+        let url = self.endpoint.url(&["files", file_id])?;
+        self.retrying()
+            .send_json(|| Ok(self.client.get(url.clone())))
             .await
-            .map_err(crate::Error::Http)?;
-
-        let response = try_parse_api_error(response).await?;
-        response.json().await.map_err(crate::Error::Http)
     }
 
     /// Lists all files.
     ///
+    /// Use [`Files::list_with`] to filter by purpose.
+    ///
     /// # Errors
     ///
-    /// Returns an error if the request fails to send or has a non-200 status code.
+    /// Returns an error if `params` has a limit outside 1..=10,000, or if the request fails to send
+    /// or has a non-200 status code.
     pub async fn list(&self, params: Option<PaginationParams>) -> Result<PaginatedList<File>> {
-        let mut request = self.client.get(format!("{}/files", self.base_url));
-
-        if let Some(params) = params {
-            request = request.query(&params);
-        }
-
-        let response = request.send().await.map_err(crate::Error::Http)?;
+        self.list_with(params.map(ListFilesParams::from).unwrap_or_default())
+            .await
+    }
 
-        let response = try_parse_api_error(response).await?;
-        response.json().await.map_err(crate::Error::Http)
+    /// Lists files, optionally filtered by purpose.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if `params` has a limit outside 1..=10,000, or if the request fails to send
+    /// or has a non-200 status code.
+    pub async fn list_with(&self, params: ListFilesParams) -> Result<PaginatedList<File>> {
+        let pagination = params.pagination();
+        pagination.validate_max(ListFilesParams::MAX_LIMIT)?;
+
+        let url = self.endpoint.url(&["files"])?;
+        self.retrying()
+            .send_json(|| {
+                let mut request = self.client.get(url.clone()).query(&pagination);
+                if let Some(purpose) = &params.purpose {
+                    request = request.query(&[("purpose", purpose.as_str())]);
+                }
+                Ok(request)
+            })
+            .await
     }
 
     /// Deletes a file with the given ID.
@@ -212,31 +621,44 @@ impl Files {
     ///
     /// Returns an error if the request fails to send or has a non-200 status code.
     pub async fn delete(&self, file_id: &str) -> Result<()> {
-        let response = self
-            .client
-            .delete(format!("{}/files/{}", self.base_url, file_id))
-            .send()
-            .await
-            .map_err(crate::Error::Http)?;
-
-        try_parse_api_error(response).await?;
+        let url = self.endpoint.url(&["files", file_id])?;
+        self.retrying()
+            .send(|| Ok(self.client.delete(url.clone())))
+            .await?;
         Ok(())
     }
 
+    /// Deletes a file and returns the API's confirmation.
+    ///
+    /// Unlike [`Files::delete`], a 404 is returned as [`DeleteConfirmation::NotFound`].
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the request fails to send, has a non-200 status code other
+    /// than 404, or the confirmation cannot be decoded.
+    pub async fn delete_confirmed(&self, file_id: &str) -> Result<DeleteConfirmation> {
+        let url = self.endpoint.url(&["files", file_id])?;
+        let response = self
+            .retrying()
+            .send_allowing(
+                || Ok(self.client.delete(url.clone())),
+                &[StatusCode::NOT_FOUND],
+            )
+            .await?;
+        self.client.deletion(response).await
+    }
+
     /// Downloads the content of a file with the given ID.
     ///
     /// # Errors
     ///
     /// Returns an error if the request fails to send or has a non-200 status code.
     pub async fn download(&self, file_id: &str) -> Result<Vec<u8>> {
+        let url = self.endpoint.url(&["files", file_id, "content"])?;
         let response = self
-            .client
-            .get(format!("{}/files/{}/content", self.base_url, file_id))
-            .send()
-            .await
-            .map_err(crate::Error::Http)?;
-
-        let response = try_parse_api_error(response).await?;
+            .retrying()
+            .send(|| Ok(self.client.get(url.clone())))
+            .await?;
         response
             .bytes()
             .await
@@ -244,3 +666,320 @@ impl Files {
             .map_err(crate::Error::Http)
     }
 }
+
+/// Builds the multipart body for [`Files::create`]
+fn upload_form(request: &CreateFileRequest) -> Result<reqwest::multipart::Form> {
+    let mime = match &request.mime_type {
+        Some(mime) => mime.clone(),
+        // Infer MIME type from filename
+        None => mime_guess::from_path(&request.filename)
+            .first_or_octet_stream()
+            .to_string(),
+    };
+    let file_part = reqwest::multipart::Part::bytes(request.file.clone())
+        .file_name(request.filename.clone())
+        .mime_str(&mime)
+        .map_err(|e| crate::Error::Stream(e.to_string()))?;
+
+    let mut form = reqwest::multipart::Form::new().text("purpose", request.purpose.clone());
+    // Multipart has no nesting, so the object is sent as bracketed fields
+    if let Some(expires_after) = request.expires_after {
+        form = form
+            .text("expires_after[anchor]", expires_after.anchor.as_str())
+            .text("expires_after[seconds]", expires_after.seconds.to_string());
+    }
+    Ok(form.part("file", file_part))
+}
+
+impl crate::EndpointBuilder<Files> {
+    /// Builds a standalone Files API client.
+    ///
+    /// # Errors
+    ///
+    /// Returns `CreateError::InvalidBaseUrl` if the base URL is not a valid http or https
+    /// URL, `CreateError::ApiKeyNotFound` if neither an API key nor an HTTP client was
+    /// given and `OPENAI_API_KEY` is not set, or `CreateError::InvalidApiKey` if the key
+    /// is invalid
+    pub fn build(self) -> std::result::Result<Files, crate::CreateError> {
+        let (client, endpoint, recovery_policy) = self.into_parts()?;
+        Ok(Files::new(client, endpoint, recovery_policy))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use mockito::Matcher;
+
+    fn file_json() -> serde_json::Value {
+        serde_json::json!({
+            "id": "file_1",
+            "object": "file",
+            "filename": "notes.txt",
+            "purpose": "assistants",
+            "bytes": 5,
+            "created_at": 1_700_000_000,
+            "expires_at": 1_700_003_600,
+            "status": "processed"
+        })
+    }
+
+    #[tokio::test]
+    async fn create_sends_expires_after_as_form_fields() {
+        let mut server = mockito::Server::new_async().await;
+        let mock = server
+            .mock("POST", "/files")
+            .match_body(Matcher::AllOf(vec![
+                Matcher::Regex(r#"name="purpose"\r\n\r\nassistants\r\n"#.to_string()),
+                Matcher::Regex(
+                    r#"name="expires_after\[anchor\]"\r\n\r\ncreated_at\r\n"#.to_string(),
+                ),
+                Matcher::Regex(r#"name="expires_after\[seconds\]"\r\n\r\n3600\r\n"#.to_string()),
+                Matcher::Regex(r#"name="file"; filename="notes.txt""#.to_string()),
+            ]))
+            .with_status(200)
+            .with_body(file_json().to_string())
+            .create_async()
+            .await;
+
+        let files = Files::new(
+            reqwest::Client::new(),
+            server.url(),
+            RecoveryPolicy::default(),
+        );
+        let file = files
+            .create(CreateFileRequest {
+                purpose: FilePurpose::Assistants.as_str().to_string(),
+                file: b"hello".to_vec(),
+                filename: "notes.txt".to_string(),
+                mime_type: None,
+                expires_after: Some(ExpiresAfter::created_at(3600)),
+            })
+            .await
+            .unwrap();
+
+        mock.assert_async().await;
+        assert_eq!(
+            file.expires_at.map(|at| at.timestamp()),
+            Some(1_700_003_600)
+        );
+    }
+
+    #[tokio::test]
+    async fn delete_confirmed_reports_the_confirmation_and_missing_files() {
+        let mut server = mockito::Server::new_async().await;
+        server
+            .mock("DELETE", "/files/file-1")
+            .with_status(200)
+            .with_body(r#"{"id":"file-1","object":"file","deleted":false}"#)
+            .create_async()
+            .await;
+        server
+            .mock("DELETE", "/files/file-gone")
+            .with_status(404)
+            .with_body(
+                r#"{"error":{"message":"No such File object","type":"invalid_request_error"}}"#,
+            )
+            .expect(2)
+            .create_async()
+            .await;
+
+        let files = Files::new(
+            reqwest::Client::new(),
+            server.url(),
+            RecoveryPolicy::default(),
+        );
+        let confirmation = files.delete_confirmed("file-1").await.unwrap();
+        assert_eq!(
+            confirmation.status().map(|status| status.id.as_str()),
+            Some("file-1")
+        );
+        assert!(!confirmation.is_deleted());
+        assert_eq!(
+            files.delete_confirmed("file-gone").await.unwrap(),
+            DeleteConfirmation::NotFound
+        );
+        // `delete` still treats a 404 as an error
+        assert!(files.delete("file-gone").await.is_err());
+    }
+
+    #[tokio::test]
+    async fn create_retries_under_the_client_recovery_policy() {
+        let mut server = mockito::Server::new_async().await;
+        let unavailable = server
+            .mock("POST", "/files")
+            .with_status(503)
+            .with_header("retry-after", "0")
+            .expect(1)
+            .create_async()
+            .await;
+        // The retried attempt carries the whole form again
+        let success = server
+            .mock("POST", "/files")
+            .match_body(Matcher::Regex(
+                r#"filename="notes.txt"[\s\S]*hello"#.to_string(),
+            ))
+            .with_status(200)
+            .with_body(file_json().to_string())
+            .expect(1)
+            .create_async()
+            .await;
+
+        let client = crate::Client::builder()
+            .http_client(reqwest::Client::new())
+            .base_url(server.url())
+            .recovery_policy(RecoveryPolicy::aggressive())
+            .build()
+            .unwrap();
+        let file = client
+            .files
+            .create(CreateFileRequest {
+                purpose: FilePurpose::Assistants.as_str().to_string(),
+                file: b"hello".to_vec(),
+                filename: "notes.txt".to_string(),
+                mime_type: None,
+                expires_after: None,
+            })
+            .await
+            .unwrap();
+
+        assert_eq!(file.id, "file_1");
+        unavailable.assert_async().await;
+        success.assert_async().await;
+    }
+
+    #[tokio::test]
+    async fn list_with_sends_purpose_and_pagination() {
+        let mut server = mockito::Server::new_async().await;
+        let mock = server
+            .mock("GET", "/files")
+            .match_query(Matcher::AllOf(vec![
+                Matcher::UrlEncoded("purpose".into(), "assistants".into()),
+                Matcher::UrlEncoded("order".into(), "asc".into()),
+                Matcher::UrlEncoded("limit".into(), "1000".into()),
+                Matcher::UrlEncoded("after".into(), "file_0".into()),
+            ]))
+            .with_status(200)
+            .with_body(
+                serde_json::json!({
+                    "object": "list",
+                    "data": [file_json()],
+                    "has_more": false
+                })
+                .to_string(),
+            )
+            .create_async()
+            .await;
+
+        let files = Files::new(
+            reqwest::Client::new(),
+            server.url(),
+            RecoveryPolicy::default(),
+        );
+        let params = ListFilesParams::new()
+            .purpose("assistants")
+            .order(Order::Asc)
+            .limit(1000)
+            .after("file_0");
+        let list = files.list_with(params).await.unwrap();
+
+        mock.assert_async().await;
+        assert_eq!(list.data[0].id, "file_1");
+        for limit in [0, ListFilesParams::MAX_LIMIT + 1] {
+            assert!(files
+                .list_with(ListFilesParams::new().limit(limit))
+                .await
+                .is_err());
+        }
+    }
+
+    #[tokio::test]
+    async fn upload_many_bounds_concurrency_and_keeps_input_order() {
+        let dir = std::env::temp_dir().join(format!("upload-many-{}", std::process::id()));
+        std::fs::create_dir_all(&dir).unwrap();
+        let mut paths: Vec<PathBuf> = (0..6)
+            .map(|i| {
+                let path = dir.join(format!("doc{i}.txt"));
+                std::fs::write(&path, format!("document {i}")).unwrap();
+                path
+            })
+            .collect();
+        // A missing file fails on its own without stopping the rest
+        paths.insert(3, dir.join("missing.txt"));
+
+        let in_flight = Arc::new(AtomicUsize::new(0));
+        let peak = Arc::new(AtomicUsize::new(0));
+        let mut server = mockito::Server::new_async().await;
+        let mut mocks = Vec::new();
+        for i in 0..6 {
+            let (in_flight, peak) = (in_flight.clone(), peak.clone());
+            let mut body = file_json();
+            body["id"] = format!("file_{i}").into();
+            let body = body.to_string();
+            mocks.push(
+                server
+                    .mock("POST", "/files")
+                    .match_body(Matcher::Regex(format!(r#"filename="doc{i}\.txt""#)))
+                    .with_status(200)
+                    .with_chunked_body(move |writer| {
+                        let now = in_flight.fetch_add(1, Ordering::SeqCst) + 1;
+                        peak.fetch_max(now, Ordering::SeqCst);
+                        std::thread::sleep(std::time::Duration::from_millis(100));
+                        in_flight.fetch_sub(1, Ordering::SeqCst);
+                        writer.write_all(body.as_bytes())
+                    })
+                    .expect(1)
+                    .create_async()
+                    .await,
+            );
+        }
+
+        let events = Arc::new(std::sync::Mutex::new(Vec::new()));
+        let seen = events.clone();
+        let options = UploadManyOptions::new()
+            .with_concurrency(2)
+            .with_progress(move |progress| seen.lock().unwrap().push(progress));
+        let files = Files::new(
+            reqwest::Client::new(),
+            server.url(),
+            RecoveryPolicy::default(),
+        );
+        let results = files
+            .upload_many(paths, FilePurpose::Assistants, options)
+            .await;
+        std::fs::remove_dir_all(&dir).unwrap();
+
+        let ids: Vec<_> = results
+            .iter()
+            .map(|result| result.as_ref().map(|file| file.id.as_str()).ok())
+            .collect();
+        assert_eq!(
+            ids,
+            [
+                Some("file_0"),
+                Some("file_1"),
+                Some("file_2"),
+                None,
+                Some("file_3"),
+                Some("file_4"),
+                Some("file_5")
+            ]
+        );
+        assert_eq!(peak.load(Ordering::SeqCst), 2);
+        for mock in mocks {
+            mock.assert_async().await;
+        }
+
+        let events = events.lock().unwrap();
+        assert_eq!(events.len(), 14);
+        let failed: Vec<_> = events
+            .iter()
+            .filter(|progress| matches!(progress.event, UploadEvent::Failed { .. }))
+            .collect();
+        assert_eq!(failed.len(), 1);
+        assert_eq!(failed[0].index, 3);
+        let last = events.last().unwrap();
+        assert!(last.is_finished());
+        assert_eq!((last.completed, last.failed, last.total), (6, 1, 7));
+    }
+}
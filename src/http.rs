@@ -0,0 +1,280 @@
+//! Shared HTTP plumbing for the endpoint modules.
+//!
+//! Every API call goes through [`Transport::send`] so that error parsing and
+//! [`Interceptor`] hooks are applied uniformly.
+
+use crate::error::{try_parse_api_error, Result};
+use crate::interceptor::{Interceptor, RequestContext, ResponseContext};
+use crate::signing::RequestSigner;
+use crate::types::DeleteConfirmation;
+use bytes::Bytes;
+use futures_util::StreamExt;
+use reqwest::header::{HeaderValue, AUTHORIZATION};
+use reqwest::{Client as HttpClient, IntoUrl, RequestBuilder, StatusCode};
+use serde::de::DeserializeOwned;
+use std::fmt;
+use std::sync::Arc;
+
+/// HTTP client plus the interceptors to notify around each request
+#[derive(Clone)]
+pub(crate) struct Transport {
+    /// Shared by every handle made with [`Transport::with_auth`], so they reuse one
+    /// connection pool
+    client: Arc<HttpClient>,
+    /// Authorization header that replaces the HTTP client's default one
+    auth: Option<HeaderValue>,
+    interceptors: Arc<[Arc<dyn Interceptor>]>,
+    max_body_bytes: Option<u64>,
+    signer: Option<Arc<dyn RequestSigner>>,
+}
+
+impl fmt::Debug for Transport {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        // The HTTP client is left out: its default headers may hold credentials
+        f.debug_struct("Transport")
+            .field("auth_override", &self.auth.is_some())
+            .field("interceptors", &self.interceptors.len())
+            .field("max_body_bytes", &self.max_body_bytes)
+            .field("signer", &self.signer)
+            .finish_non_exhaustive()
+    }
+}
+
+impl From<HttpClient> for Transport {
+    fn from(client: HttpClient) -> Self {
+        Self::new(client, Vec::new())
+    }
+}
+
+impl Transport {
+    pub(crate) fn new(client: HttpClient, interceptors: Vec<Arc<dyn Interceptor>>) -> Self {
+        Self {
+            client: Arc::new(client),
+            auth: None,
+            interceptors: interceptors.into(),
+            max_body_bytes: None,
+            signer: None,
+        }
+    }
+
+    /// Rejects successful responses whose body is larger than `limit` bytes
+    pub(crate) fn with_max_body_bytes(mut self, limit: Option<u64>) -> Self {
+        self.max_body_bytes = limit;
+        self
+    }
+
+    /// Sends every request with `auth` as the `Authorization` header, overriding the
+    /// HTTP client's default headers
+    pub(crate) fn with_auth(mut self, auth: HeaderValue) -> Self {
+        self.auth = Some(auth);
+        self
+    }
+
+    /// Whether both transports send through the same HTTP client
+    #[cfg(test)]
+    pub(crate) fn shares_pool_with(&self, other: &Self) -> bool {
+        Arc::ptr_eq(&self.client, &other.client)
+    }
+
+    pub(crate) fn get(&self, url: impl IntoUrl) -> RequestBuilder {
+        self.authorize(self.client.get(url))
+    }
+
+    pub(crate) fn post(&self, url: impl IntoUrl) -> RequestBuilder {
+        self.authorize(self.client.post(url))
+    }
+
+    pub(crate) fn delete(&self, url: impl IntoUrl) -> RequestBuilder {
+        self.authorize(self.client.delete(url))
+    }
+
+    fn authorize(&self, request: RequestBuilder) -> RequestBuilder {
+        match &self.auth {
+            Some(auth) => request.header(AUTHORIZATION, auth.clone()),
+            None => request,
+        }
+    }
+
+    /// Sends a request as attempt 1 and returns the response if it has a success status
+    pub(crate) async fn send(&self, request: RequestBuilder) -> Result<reqwest::Response> {
+        self.send_attempt(request, 1).await
+    }
+
+    /// Sends a request and deserializes the JSON body of a successful response
+    pub(crate) async fn send_json<T: DeserializeOwned>(
+        &self,
+        request: RequestBuilder,
+    ) -> Result<T> {
+        let response = self.send(request).await?;
+        self.json(response).await
+    }
+
+    /// Signs every request with `signer` just before it is sent
+    pub(crate) fn with_signer(mut self, signer: Option<Arc<dyn RequestSigner>>) -> Self {
+        self.signer = signer;
+        self
+    }
+
+    /// Reads and deserializes the JSON body of a response, within the body size limit
+    pub(crate) async fn json<T: DeserializeOwned>(&self, response: reqwest::Response) -> Result<T> {
+        let body = self.body(response).await?;
+        decode_json(&body)
+    }
+
+    /// Reads the confirmation of a delete, or [`DeleteConfirmation::NotFound`] for a 404
+    /// let through by [`Transport::send_allowing`]
+    pub(crate) async fn deletion(&self, response: reqwest::Response) -> Result<DeleteConfirmation> {
+        if response.status() == StatusCode::NOT_FOUND {
+            return Ok(DeleteConfirmation::NotFound);
+        }
+        Ok(DeleteConfirmation::Confirmed(self.json(response).await?))
+    }
+
+    /// Reads the body of a response, stopping as soon as it exceeds the body size limit
+    pub(crate) async fn body(&self, response: reqwest::Response) -> Result<Bytes> {
+        let Some(limit) = self.max_body_bytes else {
+            return response.bytes().await.map_err(crate::Error::Http);
+        };
+        let mut body = Vec::new();
+        let mut chunks = response.bytes_stream();
+        while let Some(chunk) = chunks.next().await {
+            body.extend_from_slice(&chunk.map_err(crate::Error::Http)?);
+            let actual = u64::try_from(body.len()).unwrap_or(u64::MAX);
+            if actual > limit {
+                return Err(crate::Error::ResponseTooLarge { limit, actual });
+            }
+        }
+        Ok(body.into())
+    }
+
+    /// Sends a request, notifying interceptors with the given attempt number.
+    ///
+    /// Non-success statuses are converted to errors via [`try_parse_api_error`].
+    pub(crate) async fn send_attempt(
+        &self,
+        request: RequestBuilder,
+        attempt: u32,
+    ) -> Result<reqwest::Response> {
+        self.execute(request, attempt, &[]).await
+    }
+
+    /// Like [`Transport::send`], but a response with one of the given statuses is
+    /// returned as-is instead of being converted to an error
+    pub(crate) async fn send_allowing(
+        &self,
+        request: RequestBuilder,
+        allowed: &[StatusCode],
+    ) -> Result<reqwest::Response> {
+        self.send_attempt_allowing(request, 1, allowed).await
+    }
+
+    /// Like [`Transport::send_allowing`], with the given attempt number
+    pub(crate) async fn send_attempt_allowing(
+        &self,
+        request: RequestBuilder,
+        attempt: u32,
+        allowed: &[StatusCode],
+    ) -> Result<reqwest::Response> {
+        self.execute(request, attempt, allowed).await
+    }
+
+    async fn execute(
+        &self,
+        request: RequestBuilder,
+        attempt: u32,
+        allowed: &[StatusCode],
+    ) -> Result<reqwest::Response> {
+        let mut request = request.build().map_err(crate::Error::Http)?;
+        if let Some(signer) = &self.signer {
+            crate::signing::sign_request(signer.as_ref(), &mut request);
+        }
+
+        let method = request.method().clone();
+        let path = request.url().path().to_string();
+        let request_context = RequestContext {
+            method: method.clone(),
+            path: path.clone(),
+            attempt,
+        };
+        for interceptor in self.interceptors.iter() {
+            interceptor.on_request(&request_context);
+        }
+
+        let stopwatch = crate::runtime::Stopwatch::start();
+        let (status, request_id, result) = match self.client.execute(request).await {
+            Ok(response) => {
+                let status = response.status();
+                let request_id = response
+                    .headers()
+                    .get("x-request-id")
+                    .and_then(|h| h.to_str().ok())
+                    .map(ToString::to_string);
+                let result = if allowed.contains(&status) {
+                    Ok(response)
+                } else {
+                    try_parse_api_error(response)
+                        .await
+                        .and_then(|response| self.check_content_length(response))
+                };
+                (Some(status), request_id, result)
+            }
+            Err(e) => (None, None, Err(crate::Error::Http(e))),
+        };
+
+        if !self.interceptors.is_empty() {
+            let response_context = ResponseContext {
+                method,
+                path,
+                status,
+                duration: stopwatch.elapsed(),
+                request_id,
+                error_class: result.as_ref().err().map(crate::Error::classify),
+                attempt,
+            };
+            for interceptor in self.interceptors.iter() {
+                interceptor.on_response(&response_context);
+            }
+        }
+
+        result
+    }
+
+    /// Fails early when `Content-Length` already exceeds the body size limit
+    fn check_content_length(&self, response: reqwest::Response) -> Result<reqwest::Response> {
+        match (self.max_body_bytes, response.content_length()) {
+            (Some(limit), Some(actual)) if actual > limit => {
+                Err(crate::Error::ResponseTooLarge { limit, actual })
+            }
+            _ => Ok(response),
+        }
+    }
+}
+
+/// Longest start of the body kept in [`Error::JsonDecode`](crate::Error::JsonDecode)
+const BODY_SNIPPET_BYTES: usize = 2048;
+
+/// Deserializes a response body, reporting the path of the field that failed as
+/// [`Error::JsonDecode`](crate::Error::JsonDecode)
+pub(crate) fn decode_json<T: DeserializeOwned>(body: &[u8]) -> Result<T> {
+    let decode_error = |path: String, error: &serde_json::Error| {
+        let snippet = String::from_utf8_lossy(&body[..body.len().min(BODY_SNIPPET_BYTES)]);
+        crate::Error::JsonDecode {
+            path,
+            message: error.to_string(),
+            body_snippet: if body.len() > BODY_SNIPPET_BYTES {
+                format!("{snippet}…")
+            } else {
+                snippet.into_owned()
+            },
+        }
+    };
+
+    let mut deserializer = serde_json::Deserializer::from_slice(body);
+    let value = serde_path_to_error::deserialize(&mut deserializer)
+        .map_err(|error| decode_error(error.path().to_string(), error.inner()))?;
+    // Trailing characters after the value
+    deserializer
+        .end()
+        .map_err(|error| decode_error(".".to_string(), &error))?;
+    Ok(value)
+}
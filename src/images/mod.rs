@@ -1,20 +1,44 @@
 mod types;
 pub use types::*;
 
-use crate::error::{try_parse_api_error, Result};
-use reqwest::Client as HttpClient;
+use crate::endpoint::Endpoint;
+use crate::error::Result;
+use crate::http::Transport;
+use crate::retry::Retrying;
+use crate::types::RecoveryPolicy;
 
 /// Images API endpoints
 #[derive(Debug, Clone)]
 pub struct Images {
-    client: HttpClient,
-    base_url: String,
+    client: Transport,
+    endpoint: Endpoint,
+    recovery_policy: RecoveryPolicy,
 }
 
 impl Images {
-    /// Creates a new Images API client
-    pub(crate) fn new(client: HttpClient, base_url: String) -> Self {
-        Self { client, base_url }
+    /// Creates a new Images API client that retries failed requests as `recovery_policy` allows
+    pub(crate) fn new(
+        client: impl Into<Transport>,
+        endpoint: impl Into<Endpoint>,
+        recovery_policy: RecoveryPolicy,
+    ) -> Self {
+        Self {
+            client: client.into(),
+            endpoint: endpoint.into(),
+            recovery_policy,
+        }
+    }
+
+    /// Returns a builder for a Images API client that is used on its own, with its own
+    /// base URL and recovery policy
+    pub fn builder() -> crate::EndpointBuilder<Self> {
+        crate::EndpointBuilder::default()
+    }
+
+    /// Sends this handle's requests with `auth` as the `Authorization` header
+    pub(crate) fn with_auth(mut self, auth: &reqwest::header::HeaderValue) -> Self {
+        self.client = self.client.with_auth(auth.clone());
+        self
     }
 
     /// Generate images using gpt-image-1 model
@@ -23,15 +47,262 @@ impl Images {
     ///
     /// Returns an error if the request fails to send or has a non-200 status code.
     pub async fn generate(&self, request: ImageGenerateRequest) -> Result<ImageGenerateResponse> {
-        let response = self
-            .client
-            .post(format!("{}/images/generations", self.base_url))
-            .json(&request)
-            .send()
+        let url = self.endpoint.url(&["images", "generations"])?;
+        Retrying::new(&self.client, &self.recovery_policy)
+            .send_json(|| Ok(self.client.post(url.clone()).json(&request)))
+            .await
+    }
+
+    /// Generates images, streaming partial previews as they are ready and then the
+    /// final image.
+    ///
+    /// `stream` is set on the request; use [`ImageGenerateRequest::with_stream`] to ask
+    /// for partial images. The request is sent on the first poll and retried under the
+    /// recovery policy until the stream opens. Errors the API reports mid-stream arrive
+    /// as [`ImageStreamEvent::Error`].
+    #[cfg(feature = "stream")]
+    pub fn generate_stream(
+        &self,
+        request: ImageGenerateRequest,
+    ) -> impl futures::Stream<Item = Result<ImageStreamEvent>> {
+        use futures::StreamExt;
+
+        let request = ImageGenerateRequest {
+            stream: Some(true),
+            ..request
+        };
+        let url = self.endpoint.url(&["images", "generations"]);
+        let client = self.client.clone();
+        let recovery_policy = self.recovery_policy.clone();
+        let opened = async move {
+            let url = url?;
+            Retrying::new(&client, &recovery_policy)
+                .send(|| Ok(client.post(url.clone()).json(&request)))
+                .await
+        };
+        futures::stream::once(opened).flat_map(|opened| match opened {
+            Ok(response) => image_stream_events(response).left_stream(),
+            Err(error) => futures::stream::once(async { Err(error) }).right_stream(),
+        })
+    }
+}
+
+/// Parses an image generation body into events, skipping comments and `[DONE]`
+#[cfg(feature = "stream")]
+fn image_stream_events(
+    response: reqwest::Response,
+) -> impl futures::Stream<Item = Result<ImageStreamEvent>> {
+    use futures::StreamExt;
+
+    crate::types::sse::sse_events(Box::pin(response.bytes_stream())).filter_map(
+        |block| async move {
+            let block = match block {
+                Ok(block) => block,
+                Err(error) => {
+                    return Some(Err(crate::Error::Stream(format!(
+                        "Chunk read error: {error}"
+                    ))))
+                }
+            };
+            let text = match std::str::from_utf8(&block) {
+                Ok(text) => text,
+                Err(error) => {
+                    return Some(Err(crate::Error::Stream(format!(
+                        "Invalid UTF-8 in chunk: {error}"
+                    ))))
+                }
+            };
+            let event = crate::types::SseEvent::parse(text)?;
+            if event.data.trim() == "[DONE]" {
+                return None;
+            }
+            Some(ImageStreamEvent::parse(&event))
+        },
+    )
+}
+
+impl crate::EndpointBuilder<Images> {
+    /// Builds a standalone Images API client.
+    ///
+    /// # Errors
+    ///
+    /// Returns `CreateError::InvalidBaseUrl` if the base URL is not a valid http or https
+    /// URL, `CreateError::ApiKeyNotFound` if neither an API key nor an HTTP client was
+    /// given and `OPENAI_API_KEY` is not set, or `CreateError::InvalidApiKey` if the key
+    /// is invalid
+    pub fn build(self) -> std::result::Result<Images, crate::CreateError> {
+        let (client, endpoint, recovery_policy) = self.into_parts()?;
+        Ok(Images::new(client, endpoint, recovery_policy))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[tokio::test]
+    async fn generate_retries_under_the_client_recovery_policy() {
+        let mut server = mockito::Server::new_async().await;
+        let unavailable = server
+            .mock("POST", "/images/generations")
+            .with_status(503)
+            .with_header("retry-after", "0")
+            .expect(1)
+            .create_async()
+            .await;
+        let success = server
+            .mock("POST", "/images/generations")
+            .with_status(200)
+            .with_body(r#"{"created":1700000000,"data":[{"b64_json":"aGVsbG8="}]}"#)
+            .expect(1)
+            .create_async()
+            .await;
+
+        let client = crate::Client::builder()
+            .http_client(reqwest::Client::new())
+            .base_url(server.url())
+            .recovery_policy(RecoveryPolicy::aggressive())
+            .build()
+            .unwrap();
+        let response = client
+            .images
+            .generate(ImageGenerateRequest::new("a lighthouse"))
             .await
-            .map_err(crate::Error::Http)?;
+            .unwrap();
+
+        assert_eq!(response.data.len(), 1);
+        unavailable.assert_async().await;
+        success.assert_async().await;
+    }
+
+    #[cfg(feature = "stream")]
+    fn images_for(server: &mockito::ServerGuard) -> Images {
+        crate::Client::builder()
+            .http_client(reqwest::Client::new())
+            .base_url(server.url())
+            .recovery_policy(RecoveryPolicy::aggressive())
+            .build()
+            .unwrap()
+            .images
+    }
+
+    #[cfg(feature = "stream")]
+    #[tokio::test]
+    async fn generate_stream_yields_partials_then_the_final_image() {
+        use futures::StreamExt;
+        use mockito::Matcher;
+        use serde_json::json;
+
+        let mut server = mockito::Server::new_async().await;
+        let partial = |index: u32, b64: &str| {
+            json!({
+                "type": "image_generation.partial_image",
+                "b64_json": b64,
+                "partial_image_index": index,
+                "created_at": 1_700_000_000,
+                "size": "1024x1024",
+            })
+        };
+        let completed = json!({
+            "type": "image_generation.completed",
+            "b64_json": "ZmluYWw=",
+            "created_at": 1_700_000_001,
+            "usage": {"input_tokens": 12, "output_tokens": 272, "total_tokens": 284},
+        });
+        // The second partial is split over an `event` name and two data lines
+        let body = format!(
+            ": keep-alive\n\ndata: {}\n\nevent: image_generation.partial_image\ndata: {{\"b64_json\":\"c2Vjb25k\",\ndata: \"partial_image_index\":1}}\n\ndata: {}\n\ndata: {completed}\n\ndata: [DONE]\n\n",
+            partial(0, "Zmlyc3Q="),
+            json!({"type": "image_generation.future_event"}),
+        );
+        let mock = server
+            .mock("POST", "/images/generations")
+            .match_body(Matcher::PartialJson(json!({
+                "prompt": "a lighthouse",
+                "stream": true,
+                "partial_images": 2,
+            })))
+            .with_status(200)
+            .with_header("content-type", "text/event-stream")
+            .with_body(body)
+            .expect(1)
+            .create_async()
+            .await;
+
+        let events: Vec<_> = images_for(&server)
+            .generate_stream(ImageGenerateRequest::new("a lighthouse").with_stream(2))
+            .map(Result::unwrap)
+            .collect()
+            .await;
+
+        assert_eq!(events.len(), 4, "{events:?}");
+        assert!(matches!(
+            &events[0],
+            ImageStreamEvent::PartialImage { index: 0, b64 } if b64 == "Zmlyc3Q="
+        ));
+        assert!(matches!(
+            &events[1],
+            ImageStreamEvent::PartialImage { index: 1, b64 } if b64 == "c2Vjb25k"
+        ));
+        assert!(matches!(
+            &events[2],
+            ImageStreamEvent::Unknown { event_type } if event_type == "image_generation.future_event"
+        ));
+        match &events[3] {
+            ImageStreamEvent::Completed { image, usage } => {
+                assert_eq!(image.b64_json.as_deref(), Some("ZmluYWw="));
+                assert_eq!(
+                    usage,
+                    &Some(ImageUsage {
+                        input_tokens: 12,
+                        output_tokens: 272,
+                        total_tokens: 284,
+                    })
+                );
+            }
+            other => panic!("expected the final image, got {other:?}"),
+        }
+        mock.assert_async().await;
+    }
+
+    #[cfg(feature = "stream")]
+    #[tokio::test]
+    async fn generate_stream_reports_error_events_and_failed_opens() {
+        use futures::StreamExt;
+
+        let mut server = mockito::Server::new_async().await;
+        let mock = server
+            .mock("POST", "/images/generations")
+            .with_status(200)
+            .with_body(
+                "data: {\"type\":\"error\",\"error\":{\"message\":\"moderation_blocked\",\"code\":\"content_policy_violation\"}}\n\n",
+            )
+            .create_async()
+            .await;
+        let images = images_for(&server);
+        let events: Vec<_> = images
+            .generate_stream(ImageGenerateRequest::new("a lighthouse"))
+            .collect()
+            .await;
+        assert!(matches!(
+            events.as_slice(),
+            [Ok(ImageStreamEvent::Error { message, code: Some(code) })]
+                if message == "moderation_blocked" && code == "content_policy_violation"
+        ));
+        mock.remove_async().await;
 
-        let response = try_parse_api_error(response).await?;
-        response.json().await.map_err(crate::Error::Http)
+        // The stream opens lazily, and a request the API rejects ends it with the error
+        server
+            .mock("POST", "/images/generations")
+            .with_status(400)
+            .with_body(r#"{"error":{"message":"bad size","type":"invalid_request_error"}}"#)
+            .create_async()
+            .await;
+        let events: Vec<_> = images
+            .generate_stream(ImageGenerateRequest::new("a lighthouse").with_size("1x1"))
+            .collect()
+            .await;
+        assert_eq!(events.len(), 1);
+        assert!(events[0].is_err());
     }
 }
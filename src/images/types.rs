@@ -31,6 +31,12 @@ pub struct ImageGenerateRequest {
     /// User identifier
     #[serde(skip_serializing_if = "Option::is_none")]
     pub user: Option<String>,
+    /// Whether to stream the result as server-sent events
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub stream: Option<bool>,
+    /// Number of partial images to send while streaming (0-3)
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub partial_images: Option<u8>,
 }
 
 /// Response from image generation
@@ -71,6 +77,8 @@ impl ImageGenerateRequest {
             background: None,
             seed: None,
             user: None,
+            stream: None,
+            partial_images: None,
         }
     }
 
@@ -129,4 +137,136 @@ impl ImageGenerateRequest {
         self.user = Some(user.into());
         self
     }
+
+    /// Stream the result, sending up to `partial_images` (0-3) previews before the
+    /// final image
+    #[must_use]
+    pub fn with_stream(mut self, partial_images: u8) -> Self {
+        self.stream = Some(true);
+        self.partial_images = Some(partial_images.min(3));
+        self
+    }
+}
+
+/// Token usage of an image generation
+#[derive(Debug, Clone, Default, Deserialize, PartialEq, Eq)]
+pub struct ImageUsage {
+    /// Tokens in the prompt and any input images
+    #[serde(default)]
+    pub input_tokens: u32,
+    /// Tokens in the generated image
+    #[serde(default)]
+    pub output_tokens: u32,
+    /// Input and output tokens together
+    #[serde(default)]
+    pub total_tokens: u32,
+}
+
+/// Event from [`Images::generate_stream`](super::Images::generate_stream)
+#[cfg(feature = "stream")]
+#[derive(Debug, Clone)]
+pub enum ImageStreamEvent {
+    /// A preview of the image, sent before the final one
+    PartialImage {
+        /// Position of this partial among the previews, starting at 0
+        index: u32,
+        /// Base64 encoded image data
+        b64: String,
+    },
+
+    /// The final image
+    Completed {
+        /// The generated image
+        image: ImageData,
+        /// Token usage, when the API reports it
+        usage: Option<ImageUsage>,
+    },
+
+    /// The API reported an error while generating; no further events follow
+    Error {
+        /// Description of the error
+        message: String,
+        /// Error code, when given
+        code: Option<String>,
+    },
+
+    /// An event type this version does not know
+    Unknown {
+        /// The event's `type`
+        event_type: String,
+    },
+}
+
+/// One image stream event as sent
+#[cfg(feature = "stream")]
+#[derive(Deserialize)]
+struct ImageStreamWire {
+    #[serde(rename = "type", default)]
+    event_type: Option<String>,
+    #[serde(default)]
+    b64_json: Option<String>,
+    #[serde(default)]
+    partial_image_index: Option<u32>,
+    #[serde(default)]
+    revised_prompt: Option<String>,
+    #[serde(default)]
+    usage: Option<ImageUsage>,
+    #[serde(default)]
+    message: Option<String>,
+    #[serde(default)]
+    code: Option<String>,
+    #[serde(default)]
+    error: Option<ImageStreamErrorWire>,
+}
+
+/// Error details, nested under `error` in an error event
+#[cfg(feature = "stream")]
+#[derive(Deserialize)]
+struct ImageStreamErrorWire {
+    #[serde(default)]
+    message: Option<String>,
+    #[serde(default)]
+    code: Option<String>,
+}
+
+#[cfg(feature = "stream")]
+impl ImageStreamEvent {
+    /// Reads one server-sent event; the SSE `event` name stands in for a missing `type`
+    pub(crate) fn parse(event: &crate::types::SseEvent) -> crate::Result<Self> {
+        let wire: ImageStreamWire = serde_json::from_str(&event.data).map_err(|error| {
+            crate::Error::Stream(format!("Invalid image stream event: {error}"))
+        })?;
+        let event_type = wire
+            .event_type
+            .or_else(|| event.event.clone())
+            .unwrap_or_default();
+        Ok(match event_type.as_str() {
+            "image_generation.partial_image" => Self::PartialImage {
+                index: wire.partial_image_index.unwrap_or_default(),
+                b64: wire.b64_json.unwrap_or_default(),
+            },
+            "image_generation.completed" => Self::Completed {
+                image: ImageData {
+                    url: None,
+                    b64_json: wire.b64_json,
+                    revised_prompt: wire.revised_prompt,
+                },
+                usage: wire.usage,
+            },
+            "error" => {
+                let nested = wire.error.unwrap_or(ImageStreamErrorWire {
+                    message: None,
+                    code: None,
+                });
+                Self::Error {
+                    message: nested
+                        .message
+                        .or(wire.message)
+                        .unwrap_or_else(|| "image generation failed".to_string()),
+                    code: nested.code.or(wire.code),
+                }
+            }
+            _ => Self::Unknown { event_type },
+        })
+    }
 }
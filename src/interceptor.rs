@@ -0,0 +1,129 @@
+//! Request/response hooks for observability.
+//!
+//! Interceptors are registered on the [`ClientBuilder`](crate::ClientBuilder) and are
+//! called around every HTTP request the endpoint modules send, including each retry
+//! attempt made by the recovery loop. They only observe traffic; they cannot modify
+//! or cancel requests.
+//!
+//! ```rust
+//! use open_ai_rust_responses_by_sshift::interceptor::{Interceptor, ResponseContext};
+//!
+//! #[derive(Debug)]
+//! struct LatencyRecorder;
+//!
+//! impl Interceptor for LatencyRecorder {
+//!     fn on_response(&self, context: &ResponseContext) {
+//!         println!("{} {} took {:?}", context.method, context.path, context.duration);
+//!     }
+//! }
+//! ```
+
+use crate::error::ErrorClass;
+use reqwest::{Method, StatusCode};
+use std::fmt::Debug;
+use std::time::Duration;
+
+/// Details of a request about to be sent
+#[derive(Debug, Clone)]
+pub struct RequestContext {
+    /// HTTP method
+    pub method: Method,
+
+    /// URL path, without the query string (e.g. `/v1/responses`)
+    pub path: String,
+
+    /// Attempt number, starting at 1; retries made by the recovery loop increment it
+    pub attempt: u32,
+}
+
+/// Outcome of a request
+#[derive(Debug, Clone)]
+pub struct ResponseContext {
+    /// HTTP method
+    pub method: Method,
+
+    /// URL path, without the query string (e.g. `/v1/responses`)
+    pub path: String,
+
+    /// HTTP status, or `None` if no response was received (connection error, timeout)
+    pub status: Option<StatusCode>,
+
+    /// Time from sending the request until the response headers were parsed
+    pub duration: Duration,
+
+    /// Value of the `x-request-id` response header, if present
+    pub request_id: Option<String>,
+
+    /// Classification of the error, if the request failed
+    pub error_class: Option<ErrorClass>,
+
+    /// Attempt number, starting at 1; retries made by the recovery loop increment it
+    pub attempt: u32,
+}
+
+impl ResponseContext {
+    /// Returns true if the request completed with a success status
+    #[must_use]
+    pub fn is_success(&self) -> bool {
+        self.error_class.is_none() && self.status.is_some_and(|s| s.is_success())
+    }
+}
+
+/// Hooks invoked around every API request.
+///
+/// Both methods default to doing nothing, so implementors only override what they need.
+/// Hooks run inline on the request path and should return quickly.
+pub trait Interceptor: Debug + Send + Sync {
+    /// Called before a request is sent
+    fn on_request(&self, context: &RequestContext) {
+        let _ = context;
+    }
+
+    /// Called once the request has completed, successfully or not
+    fn on_response(&self, context: &ResponseContext) {
+        let _ = context;
+    }
+}
+
+/// Interceptor that logs every request through the `log` crate.
+///
+/// Requests are logged at `debug`, successful responses at `info` and failures at `warn`.
+#[derive(Debug, Clone, Copy, Default)]
+pub struct LogInterceptor;
+
+impl Interceptor for LogInterceptor {
+    fn on_request(&self, context: &RequestContext) {
+        crate::diag::debug!(
+            "--> {} {} (attempt {})",
+            context.method,
+            context.path,
+            context.attempt
+        );
+    }
+
+    fn on_response(&self, context: &ResponseContext) {
+        let status = context
+            .status
+            .map_or_else(|| "no response".to_string(), |s| s.to_string());
+        let request_id = context.request_id.as_deref().unwrap_or("-");
+
+        if let Some(class) = context.error_class {
+            crate::diag::warn!(
+                "<-- {} {} {status} in {:?} (attempt {}, request_id={request_id}, error_class={})",
+                context.method,
+                context.path,
+                context.duration,
+                context.attempt,
+                class.as_str()
+            );
+        } else {
+            crate::diag::info!(
+                "<-- {} {} {status} in {:?} (attempt {}, request_id={request_id})",
+                context.method,
+                context.path,
+                context.duration,
+                context.attempt
+            );
+        }
+    }
+}
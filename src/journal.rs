@@ -0,0 +1,576 @@
+//! Client-side journal of API traffic, for replaying incidents offline.
+//!
+//! A [`JournalSink`] registered with [`ClientBuilder::journal`](crate::ClientBuilder::journal)
+//! receives a [`JournalEntry`] for every `responses` create attempt (retries included),
+//! every opened stream and each of its events, and every retrieve. Requests and
+//! responses are redacted with [`RedactionConfig`] before they reach the sink, and API
+//! keys quoted in error messages are shortened to `sk-...` hints; set
+//! [`ClientBuilder::journal_redaction`](crate::ClientBuilder::journal_redaction) to
+//! adjust what is stripped.
+//!
+//! [`JsonlJournal`] appends entries to a file, one JSON object per line, and
+//! [`Journal::replay_reader`] reads them back as typed entries so a test harness can
+//! re-drive application logic against recorded responses without network access.
+//!
+//! ```rust,no_run
+//! use open_ai_rust_responses_by_sshift::{Client, Journal, JsonlJournal};
+//! use std::sync::Arc;
+//!
+//! # fn run() -> Result<(), Box<dyn std::error::Error>> {
+//! let client = Client::builder()
+//!     .journal(Arc::new(JsonlJournal::create("session.jsonl")?))
+//!     .build()?;
+//!
+//! // Later, offline
+//! for entry in Journal::replay_reader("session.jsonl")? {
+//!     let entry = entry?;
+//!     if let Some(response) = entry.response()? {
+//!         println!("{:?} attempt {}: {}", entry.kind, entry.attempt, response.output_text());
+//!     }
+//! }
+//! # Ok(())
+//! # }
+//! ```
+
+use crate::types::{RedactionConfig, StreamEvent};
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+use std::fmt::Debug;
+use std::sync::Arc;
+
+/// Receives journal entries as requests complete.
+///
+/// Called inline on the request path, so implementations should return quickly and
+/// must not panic; a sink that fails to store an entry should log and carry on.
+pub trait JournalSink: Debug + Send + Sync {
+    /// Stores one entry
+    fn record(&self, entry: &JournalEntry);
+}
+
+/// Which call produced a [`JournalEntry`]
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum JournalKind {
+    /// One attempt of `responses.create`
+    Create,
+    /// Opening a response stream
+    Stream,
+    /// One item yielded by a stream: an event, or an error (including a failed open)
+    StreamEvent,
+    /// `responses.retrieve`
+    Retrieve,
+}
+
+/// What a journaled call returned
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum JournalOutcome {
+    /// A response body, redacted
+    Response(Value),
+    /// The stream was opened; its events follow as separate entries
+    Opened,
+    /// A stream event, redacted
+    Event(Value),
+    /// The call failed
+    Error(JournalError),
+}
+
+/// An error recorded in the journal
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct JournalError {
+    /// [`ErrorClass`](crate::ErrorClass) label, e.g. `retryable_server`
+    pub class: String,
+
+    /// Error message, with API keys shortened
+    pub message: String,
+}
+
+/// One recorded call
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct JournalEntry {
+    /// When the call completed
+    pub timestamp: DateTime<Utc>,
+
+    /// Which call this is
+    pub kind: JournalKind,
+
+    /// Hex SHA-256 of the request's canonical JSON (see
+    /// [`Request::fingerprint`](crate::Request::fingerprint)); stream events carry their
+    /// stream's fingerprint
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub request_fingerprint: Option<String>,
+
+    /// The request, redacted; for a retrieve, `{"id": ...}`. Left out on stream events
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub request: Option<Value>,
+
+    /// What the call returned
+    #[serde(rename = "response_or_error")]
+    pub outcome: JournalOutcome,
+
+    /// Attempt number, starting at 1; retries made by the recovery loop increment it
+    pub attempt: u32,
+}
+
+impl JournalEntry {
+    /// Decodes the recorded response, if this entry holds one.
+    ///
+    /// Inline data over the redaction limit comes back as a length marker rather than
+    /// the original payload.
+    ///
+    /// # Errors
+    ///
+    /// Returns [`Error::Json`](crate::Error::Json) if the recorded body is not a response.
+    pub fn response(&self) -> crate::Result<Option<crate::Response>> {
+        match &self.outcome {
+            JournalOutcome::Response(body) => Ok(Some(serde_json::from_value(body.clone())?)),
+            _ => Ok(None),
+        }
+    }
+
+    /// Decodes the recorded stream event, if this entry holds one
+    ///
+    /// # Errors
+    ///
+    /// Returns [`Error::Json`](crate::Error::Json) if the recorded event is not a
+    /// [`StreamEvent`].
+    pub fn event(&self) -> crate::Result<Option<StreamEvent>> {
+        match &self.outcome {
+            JournalOutcome::Event(event) => Ok(Some(serde_json::from_value(event.clone())?)),
+            _ => Ok(None),
+        }
+    }
+
+    /// The recorded error, if the call failed
+    #[must_use]
+    pub fn error(&self) -> Option<&JournalError> {
+        match &self.outcome {
+            JournalOutcome::Error(error) => Some(error),
+            _ => None,
+        }
+    }
+}
+
+/// A [`JournalSink`] with the redaction applied to what is sent to it
+#[derive(Debug, Clone)]
+pub struct Journal {
+    sink: Arc<dyn JournalSink>,
+    redaction: RedactionConfig,
+}
+
+impl Journal {
+    /// Journals to `sink` with the default [`RedactionConfig`]
+    #[must_use]
+    pub fn new(sink: Arc<dyn JournalSink>) -> Self {
+        Self {
+            sink,
+            redaction: RedactionConfig::default(),
+        }
+    }
+
+    /// Sets what is stripped from requests, responses and events before they are recorded
+    #[must_use]
+    pub fn with_redaction(mut self, redaction: RedactionConfig) -> Self {
+        self.redaction = redaction;
+        self
+    }
+
+    /// Opens a journal written by [`JsonlJournal`] and reads its entries in order
+    ///
+    /// # Errors
+    ///
+    /// Returns [`Error::Io`](crate::Error::Io) if the file cannot be opened.
+    #[cfg(not(target_arch = "wasm32"))]
+    pub fn replay_reader(path: impl AsRef<std::path::Path>) -> crate::Result<JournalReader> {
+        let file = std::fs::File::open(path)?;
+        Ok(JournalReader {
+            lines: std::io::BufRead::lines(std::io::BufReader::new(file)),
+        })
+    }
+
+    /// Fingerprint and redacted JSON of `request`
+    pub(crate) fn request_fields(&self, request: &crate::Request) -> (String, Value) {
+        (
+            crate::types::canonical::to_hex(&request.fingerprint()),
+            request.to_audit_json_with(&self.redaction),
+        )
+    }
+
+    /// Records the outcome of one create attempt
+    pub(crate) fn record_create(
+        &self,
+        request: &crate::Request,
+        attempt: u32,
+        result: &crate::Result<crate::Response>,
+    ) {
+        let (fingerprint, request) = self.request_fields(request);
+        self.record(
+            JournalKind::Create,
+            Some(fingerprint),
+            Some(request),
+            attempt,
+            self.response_outcome(result),
+        );
+    }
+
+    /// Records the outcome of a retrieve
+    pub(crate) fn record_retrieve(&self, id: &str, result: &crate::Result<crate::Response>) {
+        self.record(
+            JournalKind::Retrieve,
+            None,
+            Some(serde_json::json!({ "id": id })),
+            1,
+            self.response_outcome(result),
+        );
+    }
+
+    /// Records an event or error read from a stream
+    #[cfg(feature = "stream")]
+    pub(crate) fn record_stream_item(
+        &self,
+        fingerprint: Option<&str>,
+        item: &crate::Result<StreamEvent>,
+    ) {
+        let outcome = match item {
+            Ok(event) => JournalOutcome::Event(self.redaction.audit(event)),
+            Err(error) => JournalOutcome::Error(journal_error(error)),
+        };
+        self.record(
+            JournalKind::StreamEvent,
+            fingerprint.map(str::to_string),
+            None,
+            1,
+            outcome,
+        );
+    }
+
+    /// Records the opening of a stream
+    #[cfg(feature = "stream")]
+    pub(crate) fn record_stream_open(
+        &self,
+        fingerprint: Option<String>,
+        request: Value,
+        result: std::result::Result<(), &crate::Error>,
+    ) {
+        let outcome = match result {
+            Ok(()) => JournalOutcome::Opened,
+            Err(error) => JournalOutcome::Error(journal_error(error)),
+        };
+        self.record(JournalKind::Stream, fingerprint, Some(request), 1, outcome);
+    }
+
+    fn response_outcome(&self, result: &crate::Result<crate::Response>) -> JournalOutcome {
+        match result {
+            Ok(response) => JournalOutcome::Response(response.to_audit_json_with(&self.redaction)),
+            Err(error) => JournalOutcome::Error(journal_error(error)),
+        }
+    }
+
+    fn record(
+        &self,
+        kind: JournalKind,
+        request_fingerprint: Option<String>,
+        request: Option<Value>,
+        attempt: u32,
+        outcome: JournalOutcome,
+    ) {
+        self.sink.record(&JournalEntry {
+            timestamp: Utc::now(),
+            kind,
+            request_fingerprint,
+            request,
+            outcome,
+            attempt,
+        });
+    }
+}
+
+fn journal_error(error: &crate::Error) -> JournalError {
+    JournalError {
+        class: error.classify().as_str().to_string(),
+        message: redact_api_keys(&error.to_string()),
+    }
+}
+
+/// Replaces every `sk-` token in `text` with its [`redact_api_key`](crate::types::redact_api_key) hint
+fn redact_api_keys(text: &str) -> String {
+    let is_key_char = |c: char| c.is_ascii_alphanumeric() || c == '-' || c == '_';
+    let mut redacted = String::with_capacity(text.len());
+    let mut rest = text;
+    while let Some(start) = rest.find("sk-") {
+        let at_word_start = !rest[..start].chars().next_back().is_some_and(is_key_char);
+        let end = rest[start..]
+            .find(|c: char| !is_key_char(c))
+            .map_or(rest.len(), |len| start + len);
+        redacted.push_str(&rest[..start]);
+        if at_word_start {
+            redacted.push_str(&crate::types::redact_api_key(&rest[start..end]));
+        } else {
+            redacted.push_str(&rest[start..end]);
+        }
+        rest = &rest[end..];
+    }
+    redacted.push_str(rest);
+    redacted
+}
+
+/// Appends entries to a file as JSON lines.
+///
+/// Each entry is written and flushed as one line when it is recorded, so a crash loses
+/// at most the entry being written. Write failures are logged and otherwise ignored.
+#[cfg(not(target_arch = "wasm32"))]
+#[derive(Debug)]
+pub struct JsonlJournal {
+    path: std::path::PathBuf,
+    file: std::sync::Mutex<std::fs::File>,
+}
+
+#[cfg(not(target_arch = "wasm32"))]
+impl JsonlJournal {
+    /// Opens `path` for appending, creating it if needed
+    ///
+    /// # Errors
+    ///
+    /// Returns [`Error::Io`](crate::Error::Io) if the file cannot be opened.
+    pub fn create(path: impl Into<std::path::PathBuf>) -> crate::Result<Self> {
+        let path = path.into();
+        let file = std::fs::OpenOptions::new()
+            .create(true)
+            .append(true)
+            .open(&path)?;
+        Ok(Self {
+            path,
+            file: std::sync::Mutex::new(file),
+        })
+    }
+
+    /// Path of the journal file
+    #[must_use]
+    pub fn path(&self) -> &std::path::Path {
+        &self.path
+    }
+}
+
+#[cfg(not(target_arch = "wasm32"))]
+impl JournalSink for JsonlJournal {
+    fn record(&self, entry: &JournalEntry) {
+        use std::io::Write as _;
+
+        let mut line = match serde_json::to_vec(entry) {
+            Ok(line) => line,
+            Err(error) => {
+                crate::diag::warn!("Failed to serialize journal entry: {error}");
+                return;
+            }
+        };
+        line.push(b'\n');
+        let mut file = self
+            .file
+            .lock()
+            .unwrap_or_else(std::sync::PoisonError::into_inner);
+        if let Err(error) = file.write_all(&line).and_then(|()| file.flush()) {
+            crate::diag::warn!(
+                "Failed to write journal entry to {}: {error}",
+                self.path.display()
+            );
+        }
+    }
+}
+
+/// Reads the entries of a [`JsonlJournal`] file, from [`Journal::replay_reader`]
+#[cfg(not(target_arch = "wasm32"))]
+#[derive(Debug)]
+pub struct JournalReader {
+    lines: std::io::Lines<std::io::BufReader<std::fs::File>>,
+}
+
+#[cfg(not(target_arch = "wasm32"))]
+impl Iterator for JournalReader {
+    type Item = crate::Result<JournalEntry>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        loop {
+            let line = match self.lines.next()? {
+                Ok(line) => line,
+                Err(error) => return Some(Err(error.into())),
+            };
+            if !line.trim().is_empty() {
+                return Some(serde_json::from_str(&line).map_err(Into::into));
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn api_keys_in_messages_are_shortened() {
+        assert_eq!(
+            redact_api_keys("Incorrect API key provided: sk-proj-abcdefghijklmnop1234. Check it."),
+            "Incorrect API key provided: sk-...1234. Check it."
+        );
+        assert_eq!(redact_api_keys("sk-short"), "sk-...");
+        // Only whole tokens are keys
+        assert_eq!(
+            redact_api_keys("risk-free task-sk-1"),
+            "risk-free task-sk-1"
+        );
+    }
+
+    #[tokio::test]
+    async fn conversation_with_a_retry_is_journaled_in_order() {
+        const API_KEY: &str = "sk-journal-test-secret-key-0001";
+
+        let mut server = mockito::Server::new_async().await;
+        let unavailable = server
+            .mock("POST", "/responses")
+            .with_status(503)
+            .with_header("retry-after", "0")
+            .expect(1)
+            .create_async()
+            .await;
+        let created = server
+            .mock("POST", "/responses")
+            .with_status(200)
+            .with_body(r#"{"id":"resp_1","object":"response","created_at":1700000000,"model":"gpt-4o","status":"completed","output":[{"type":"message","id":"msg_1","role":"assistant","status":"completed","content":[{"type":"output_text","text":"Hi there","annotations":[]}]}]}"#)
+            .expect(1)
+            .create_async()
+            .await;
+        server
+            .mock("GET", "/responses/resp_1")
+            .with_status(400)
+            .with_body(format!(
+                r#"{{"error":{{"message":"Project of {API_KEY} cannot read resp_1","type":"invalid_request_error","code":null}}}}"#
+            ))
+            .create_async()
+            .await;
+
+        let path = std::env::temp_dir().join(format!("journal-{}.jsonl", std::process::id()));
+        let _ = std::fs::remove_file(&path);
+        let client = crate::Client::builder()
+            .api_key(API_KEY)
+            .base_url(server.url())
+            .journal(Arc::new(JsonlJournal::create(&path).unwrap()))
+            .build()
+            .unwrap();
+
+        let request = crate::Request::builder()
+            .model("gpt-4o")
+            .input("hello")
+            .tools(vec![crate::Tool::mcp(
+                "docs",
+                "https://mcp.example.com",
+                Some(
+                    [("Authorization".to_string(), format!("Bearer {API_KEY}"))]
+                        .into_iter()
+                        .collect(),
+                ),
+            )])
+            .build();
+        let response = client.responses.create(request.clone()).await.unwrap();
+        assert!(client.responses.retrieve("resp_1").await.is_err());
+        unavailable.assert_async().await;
+        created.assert_async().await;
+
+        let text = std::fs::read_to_string(&path).unwrap();
+        assert!(!text.contains("journal-test-secret"), "{text}");
+
+        let entries = Journal::replay_reader(&path)
+            .unwrap()
+            .collect::<crate::Result<Vec<_>>>()
+            .unwrap();
+        let shape: Vec<_> = entries
+            .iter()
+            .map(|entry| (entry.kind, entry.attempt))
+            .collect();
+        assert_eq!(
+            shape,
+            [
+                (JournalKind::Create, 1),
+                (JournalKind::Create, 2),
+                (JournalKind::Retrieve, 1)
+            ]
+        );
+
+        let fingerprint = crate::types::canonical::to_hex(&request.fingerprint());
+        assert_eq!(
+            entries[0].request_fingerprint.as_deref(),
+            Some(&*fingerprint)
+        );
+        assert_eq!(entries[0].error().unwrap().class, "retryable_server");
+        assert_eq!(entries[1].request, entries[0].request);
+        assert_eq!(
+            entries[1].request.as_ref().unwrap()["tools"][0]["headers"]["Authorization"],
+            crate::types::audit::REDACTED
+        );
+        assert_eq!(entries[1].response().unwrap(), Some(response));
+
+        assert_eq!(
+            entries[2].request,
+            Some(serde_json::json!({"id": "resp_1"}))
+        );
+        let error = entries[2].error().unwrap();
+        assert!(error.message.contains("sk-...0001"), "{}", error.message);
+        std::fs::remove_file(&path).unwrap();
+    }
+
+    #[cfg(feature = "stream")]
+    #[tokio::test]
+    async fn stream_events_are_journaled_after_the_open() {
+        use futures::StreamExt;
+
+        #[derive(Debug, Default)]
+        struct Memory(std::sync::Mutex<Vec<JournalEntry>>);
+
+        impl JournalSink for Memory {
+            fn record(&self, entry: &JournalEntry) {
+                self.0.lock().unwrap().push(entry.clone());
+            }
+        }
+
+        let mut server = mockito::Server::new_async().await;
+        server
+            .mock("POST", "/responses")
+            .with_status(200)
+            .with_header("content-type", "text/event-stream")
+            .with_body(concat!(
+                "data: {\"type\":\"response.output_text.delta\",\"delta\":\"Hi\",\"output_index\":0}\n\n",
+                "data: {\"type\":\"response.completed\",\"response\":{\"id\":\"resp_1\"}}\n\n",
+            ))
+            .create_async()
+            .await;
+
+        let memory = Arc::new(Memory::default());
+        let client = crate::Client::builder()
+            .api_key("sk-test")
+            .base_url(server.url())
+            .journal(memory.clone())
+            .build()
+            .unwrap();
+        let events: Vec<_> = client
+            .responses
+            .stream(
+                crate::Request::builder()
+                    .model("gpt-4o")
+                    .input("hi")
+                    .build(),
+            )
+            .collect()
+            .await;
+
+        let entries = memory.0.lock().unwrap();
+        assert_eq!(entries.len(), events.len() + 1);
+        assert_eq!(entries[0].kind, JournalKind::Stream);
+        assert_eq!(entries[0].outcome, JournalOutcome::Opened);
+        assert_eq!(entries[0].request.as_ref().unwrap()["stream"], true);
+        for (entry, event) in entries[1..].iter().zip(&events) {
+            assert_eq!(entry.kind, JournalKind::StreamEvent);
+            assert_eq!(entry.request_fingerprint, entries[0].request_fingerprint);
+            assert!(entry.request.is_none());
+            assert_eq!(&entry.event().unwrap(), &event.as_ref().ok().cloned());
+        }
+    }
+}
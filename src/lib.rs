@@ -15,55 +15,148 @@
 //! - Full support for the Responses API endpoints
 //! - Conversation continuity through response IDs
 //! - Message history retrieval
-//! - File and vector store operations
+//! - File, vector store and code interpreter container operations
 //! - Streaming responses via Server-Sent Events (SSE)
 //! - Built-in tools support (web search, file search)
 //! - Function calling capabilities
 //! - **Model Context Protocol (MCP)** integration
 //! - **Realtime API** support via WebSockets
+//! - **Interceptors** for request/response telemetry
+//! - **Journal** of requests, responses and stream events for offline replay
+//! - **WebAssembly** support (`wasm` feature, `wasm32-unknown-unknown` target)
+//! - **Lenient parsing** of off-spec gateway payloads (`lenient` feature)
+//! - **Response caching** of identical non-streaming creates (`cache` feature)
+//! - **Tracing** spans around recovery and streaming (`tracing` feature)
+//! - **Mock server** for running examples and tests without an API key (`mock-server` feature)
+//! - **Image downscaling** of raw uploads before sending them (`image` feature)
+//! - **Blocking client** for synchronous programs (`blocking` feature)
+//! - **Conversations** that summarize themselves before filling the context window
+//! - **Types only** builds without the HTTP client (`--no-default-features --features types-only`)
 
+#[cfg(all(feature = "blocking", not(target_arch = "wasm32")))]
+pub mod blocking;
+#[cfg(feature = "client")]
+pub mod containers;
+#[cfg(feature = "client")]
+pub mod conversation;
+mod diag;
+#[cfg(feature = "client")]
+mod endpoint;
 mod error;
+#[cfg(feature = "client")]
 pub mod files;
+#[cfg(feature = "client")]
+mod http;
+#[cfg(feature = "client")]
 pub mod images;
+#[cfg(feature = "client")]
+pub mod interceptor;
+#[cfg(feature = "client")]
+pub mod journal;
+#[cfg(feature = "client")]
 pub mod mcp;
+#[cfg(feature = "client")]
 pub mod messages;
+#[cfg(all(feature = "mock-server", not(target_arch = "wasm32")))]
+pub mod mock;
+#[cfg(all(feature = "client", not(target_arch = "wasm32")))]
 pub mod realtime;
+#[cfg(feature = "client")]
 pub mod responses;
+#[cfg(feature = "client")]
+mod retry;
+#[cfg(feature = "client")]
+mod runtime;
+#[cfg(feature = "client")]
+pub mod signing;
 #[cfg(test)]
 mod tests;
+#[cfg(feature = "client")]
 pub mod tools;
 pub mod types;
+#[cfg(feature = "client")]
 pub mod vector_stores;
+#[cfg(feature = "client")]
+pub mod webhooks;
 
 // Re-export types from the types module
+#[allow(deprecated)]
+pub use types::ToolCall;
 pub use types::{
-    FunctionCallInfo, Input, InputItem, MessageContent, Model, PaginatedList, PaginationParams,
-    ReasoningEffort, Request, RequestBuilder, Response, ResponseItem, StreamEvent, Tool, ToolCall,
-    ToolChoice, Verbosity,
+    FunctionCallInfo, Input, InputItem, MessageContent, Metadata, Model, PaginatedList,
+    PaginationParams, ReasoningEffort, Request, RequestBuilder, Response, ResponseItem,
+    StreamEvent, Tool, ToolChoice, Verbosity,
 };
 
 // Re-export container and tool types
-pub use types::{Container, RecoveryCallback, RecoveryPolicy, RetryScope};
+pub use types::{
+    Container, RecoveryCallback, RecoveryPolicy, RetryJitter, RetryScope, RetryTransform,
+};
 
 // Re-export recovery types
-pub use responses::{RecoveryInfo, ResponseWithRecovery};
+#[cfg(feature = "cache")]
+pub use responses::cache::{CacheConfig, CachedOr};
+#[cfg(feature = "stream")]
+pub use responses::metrics::{MetricsHandle, StreamMetrics};
+#[cfg(feature = "client")]
+pub use responses::rate_limit::RateLimitConfig;
+#[cfg(feature = "client")]
+pub use responses::{RecoveryEvent, RecoveryEventCallback, RecoveryInfo, ResponseWithRecovery};
 
 // Re-export image types
-pub use images::{ImageData, ImageGenerateRequest, ImageGenerateResponse};
+#[cfg(all(feature = "client", feature = "stream"))]
+pub use images::ImageStreamEvent;
+#[cfg(feature = "client")]
+pub use images::{ImageData, ImageGenerateRequest, ImageGenerateResponse, ImageUsage};
+
+// Re-export container management types
+#[cfg(feature = "client")]
+pub use containers::{ContainerObject, CreateContainerRequest};
 
 // Re-export vector store types
+#[cfg(feature = "client")]
 pub use vector_stores::{
-    AddFileToVectorStoreRequest, CreateVectorStoreRequest, SearchVectorStoreRequest,
-    SearchVectorStoreResponse, VectorStore, VectorStoreFileDeleteResponse,
+    AddFileToVectorStoreRequest, AttachOptions, AttachedFile, ChunkingStrategy,
+    CreateVectorStoreRequest, SearchVectorStoreRequest, SearchVectorStoreResponse, VectorStore,
+    VectorStoreFileAssociation, VectorStoreFileDeleteResponse, VectorStoreFileStatus,
 };
 
 // Re-export error types
 pub use error::{Error, ErrorClass, Result};
 
+// Re-export interceptor types
+#[cfg(feature = "client")]
+pub use endpoint::EndpointBuilder;
+#[cfg(feature = "client")]
+pub use interceptor::{Interceptor, LogInterceptor};
+
+// Re-export journal types
+#[cfg(all(feature = "client", not(target_arch = "wasm32")))]
+pub use journal::JsonlJournal;
+#[cfg(feature = "client")]
+pub use journal::{Journal, JournalEntry, JournalSink};
+
+#[cfg(feature = "client")]
 use reqwest::{header, Client as HttpClient};
+#[cfg(feature = "client")]
 use std::env;
+#[cfg(feature = "client")]
+use std::fmt;
+#[cfg(feature = "client")]
+use std::sync::Arc;
+
+#[cfg(feature = "client")]
+const DEFAULT_BASE_URL: &str = "https://api.openai.com/v1";
+
+/// User agent sent by clients built from an API key, unless the builder changes it
+#[cfg(feature = "client")]
+pub const DEFAULT_USER_AGENT: &str = concat!(
+    "open-ai-rust-responses-by-sshift/",
+    env!("CARGO_PKG_VERSION")
+);
 
 /// Error that can occur when creating a client
+#[cfg(feature = "client")]
 #[derive(Debug, thiserror::Error)]
 pub enum CreateError {
     /// API key is invalid
@@ -77,9 +170,44 @@ pub enum CreateError {
     /// HTTP client creation error
     #[error("Failed to create HTTP client: {0}")]
     HttpClient(#[from] reqwest::Error),
+
+    /// Base URL is not a valid http or https URL
+    #[error("Invalid base URL: {0}")]
+    InvalidBaseUrl(String),
+
+    /// Base URL is plain http to a non-loopback host while strict security is on
+    #[error("Insecure base URL: {0} (strict security requires https)")]
+    InsecureBaseUrl(String),
+
+    /// User agent is not a valid header value
+    #[error("Invalid user agent: {0:?}")]
+    InvalidUserAgent(String),
+
+    /// Organization ID is not a valid header value
+    #[error("Invalid organization ID: {0:?}")]
+    InvalidOrganization(String),
+
+    /// Project ID is not a valid header value
+    #[error("Invalid project ID: {0:?}")]
+    InvalidProject(String),
+
+    /// An environment variable read by [`ClientBuilder::load_env`] has an invalid value
+    #[error("Invalid environment variable {name}: {reason}")]
+    InvalidEnvVar {
+        /// Name of the variable
+        name: &'static str,
+        /// Why the value was rejected
+        reason: String,
+    },
+
+    /// The blocking client's runtime could not be started
+    #[cfg(all(feature = "blocking", not(target_arch = "wasm32")))]
+    #[error("Failed to start the blocking client's runtime: {0}")]
+    Runtime(std::io::Error),
 }
 
 /// Client for the OpenAI Responses API
+#[cfg(feature = "client")]
 #[derive(Debug, Clone)]
 pub struct Client {
     /// Responses API endpoints
@@ -99,8 +227,18 @@ pub struct Client {
 
     /// Images API endpoints
     pub images: images::Images,
+
+    /// Containers API endpoints
+    pub containers: containers::Containers,
+
+    /// The API key as returned by [`Client::redacted_key`]
+    redacted_key: Option<String>,
+
+    /// The user agent as returned by [`Client::user_agent`]
+    user_agent: Option<String>,
 }
 
+#[cfg(feature = "client")]
 impl Client {
     /// Creates a new client with the given API key
     ///
@@ -108,39 +246,20 @@ impl Client {
     ///
     /// Returns `CreateError::InvalidApiKey` if the API key is empty or doesn't start with "sk-"
     pub fn new(api_key: &str) -> std::result::Result<Self, CreateError> {
-        Self::new_with_base_url(api_key, "https://api.openai.com/v1")
+        Self::new_with_base_url(api_key, DEFAULT_BASE_URL)
     }
 
     /// Creates a new client with the given API key and base URL
     ///
     /// # Errors
     ///
-    /// Returns `CreateError::InvalidApiKey` if the API key is empty, doesn't start with "sk-", or contains invalid characters
+    /// Returns `CreateError::InvalidApiKey` if the API key is empty, doesn't start with "sk-", or contains invalid characters,
+    /// or `CreateError::InvalidBaseUrl` if the base URL is not a valid http or https URL
     pub fn new_with_base_url(
         api_key: &str,
         base_url: &str,
     ) -> std::result::Result<Self, CreateError> {
-        if api_key.is_empty() || !api_key.starts_with("sk-") {
-            return Err(CreateError::InvalidApiKey);
-        }
-
-        let mut headers = header::HeaderMap::new();
-        let auth_value = format!("Bearer {api_key}");
-        let auth_header =
-            header::HeaderValue::from_str(&auth_value).map_err(|_| CreateError::InvalidApiKey)?;
-        headers.insert(header::AUTHORIZATION, auth_header);
-
-        let user_agent = format!(
-            "open-ai-rust-responses-by-sshift/{}",
-            env!("CARGO_PKG_VERSION")
-        );
-
-        let http_client = HttpClient::builder()
-            .default_headers(headers)
-            .user_agent(user_agent)
-            .build()?;
-
-        Ok(Self::new_with_http_client(&http_client, base_url))
+        Self::new_with_base_url_and_recovery(api_key, base_url, RecoveryPolicy::default())
     }
 
     /// Creates a client from the `OPENAI_API_KEY` environment variable
@@ -149,7 +268,20 @@ impl Client {
     ///
     /// Returns `CreateError::InvalidApiKey` if the environment variable is not set or invalid
     pub fn from_env() -> std::result::Result<Self, CreateError> {
-        Self::from_env_with_base_url("https://api.openai.com/v1")
+        Self::from_env_with_base_url(DEFAULT_BASE_URL)
+    }
+
+    /// Creates a client from every environment variable [`ClientBuilder::load_env`] reads:
+    /// API key, base URL, organization, project, timeout and the `OAI_RECOVERY_*`
+    /// recovery settings.
+    ///
+    /// # Errors
+    ///
+    /// Returns `CreateError::InvalidEnvVar` naming the variable that failed to parse,
+    /// `CreateError::ApiKeyNotFound` if `OPENAI_API_KEY` is not set, or any error from
+    /// [`ClientBuilder::build`].
+    pub fn from_env_full() -> std::result::Result<Self, CreateError> {
+        ClientBuilder::new().load_env()?.build()
     }
 
     /// Creates a client from the environment, loading both API key and recovery policy.
@@ -170,37 +302,131 @@ impl Client {
     ///
     /// # Errors
     ///
-    /// Returns `CreateError::InvalidApiKey` if the environment variable is not set or invalid
+    /// Returns `CreateError::InvalidApiKey` if the environment variable is not set or invalid,
+    /// or `CreateError::InvalidBaseUrl` if the base URL is not a valid http or https URL
     pub fn from_env_with_base_url(base_url: &str) -> std::result::Result<Self, CreateError> {
         let api_key = std::env::var("OPENAI_API_KEY").map_err(|_| CreateError::InvalidApiKey)?;
         Self::new_with_base_url(&api_key, base_url)
     }
 
-    /// Creates a new client with the given HTTP client and base URL
+    /// Creates a new client with the given HTTP client and base URL.
+    ///
+    /// The base URL is not validated here; if it is invalid, every request fails with
+    /// [`Error::InvalidBaseUrl`].
     #[must_use]
     pub fn new_with_http_client(http_client: &HttpClient, base_url: &str) -> Self {
         Self::new_with_http_client_and_recovery(http_client, base_url, RecoveryPolicy::default())
     }
 
-    /// Creates a new client with the given HTTP client, base URL, and recovery policy
+    /// Creates a new client with the given HTTP client, base URL, and recovery policy.
+    ///
+    /// The base URL is not validated here; if it is invalid, every request fails with
+    /// [`Error::InvalidBaseUrl`].
     #[must_use]
     pub fn new_with_http_client_and_recovery(
         http_client: &HttpClient,
         base_url: &str,
         recovery_policy: RecoveryPolicy,
     ) -> Self {
-        let base_url = base_url.trim_end_matches('/').to_string();
+        Self::from_transport(
+            &http::Transport::from(http_client.clone()),
+            &endpoint::Endpoint::from(base_url),
+            recovery_policy,
+        )
+    }
+
+    /// Returns a builder for configuring a client
+    #[must_use]
+    pub fn builder() -> ClientBuilder {
+        ClientBuilder::new()
+    }
+
+    /// Returns the API key in a form that is safe to log, such as `sk-...x7Qz`.
+    ///
+    /// `None` when the client was given a preconfigured HTTP client rather than a key.
+    /// See [`types::redact_api_key`].
+    #[must_use]
+    pub fn redacted_key(&self) -> Option<&str> {
+        self.redacted_key.as_deref()
+    }
+
+    /// Returns the `User-Agent` header this client sends, for logging.
+    ///
+    /// `None` when the client was given a preconfigured HTTP client, or when
+    /// [`ClientBuilder::user_agent_override`] removed the header.
+    #[must_use]
+    pub fn user_agent(&self) -> Option<&str> {
+        self.user_agent.as_deref()
+    }
 
+    /// Returns a handle that authenticates with `api_key` instead of this client's key.
+    ///
+    /// The handle shares this client's connection pool, interceptors and recovery policy,
+    /// so a server holding many API keys can keep one client and derive a cheap handle
+    /// per key. The response cache is not shared: enable it on the handle if needed. A
+    /// rate limiter is not shared either: the handle gets its own with the same settings,
+    /// because the server limits each key separately.
+    ///
+    /// ```rust,no_run
+    /// use open_ai_rust_responses_by_sshift::Client;
+    ///
+    /// let shared = Client::from_env()?;
+    /// let tenant = shared.with_api_key_override("sk-tenant-key")?;
+    /// # Ok::<(), open_ai_rust_responses_by_sshift::CreateError>(())
+    /// ```
+    ///
+    /// # Errors
+    ///
+    /// Returns `CreateError::InvalidApiKey` if the API key is empty, doesn't start with "sk-", or contains invalid characters
+    pub fn with_api_key_override(&self, api_key: &str) -> std::result::Result<Self, CreateError> {
+        let auth = auth_header(api_key)?;
+        Ok(Self {
+            responses: self.responses.clone().with_auth(&auth),
+            messages: self.messages.clone().with_auth(&auth),
+            files: self.files.clone().with_auth(&auth),
+            vector_stores: self.vector_stores.clone().with_auth(&auth),
+            tools: self.tools.clone().with_auth(&auth),
+            images: self.images.clone().with_auth(&auth),
+            containers: self.containers.clone().with_auth(&auth),
+            redacted_key: None,
+            user_agent: self.user_agent.clone(),
+        }
+        .with_api_key_hint(api_key))
+    }
+
+    fn with_api_key_hint(mut self, api_key: &str) -> Self {
+        self.redacted_key = Some(types::redact_api_key(api_key));
+        self
+    }
+
+    fn with_user_agent(mut self, user_agent: Option<&header::HeaderValue>) -> Self {
+        self.user_agent = user_agent
+            .and_then(|value| value.to_str().ok())
+            .map(str::to_string);
+        self
+    }
+
+    fn from_transport(
+        transport: &http::Transport,
+        endpoint: &endpoint::Endpoint,
+        recovery_policy: RecoveryPolicy,
+    ) -> Self {
+        let messages = messages::Messages::new(transport.clone(), endpoint.clone());
+        let files = files::Files::new(transport.clone(), endpoint.clone(), recovery_policy.clone());
+        let vector_stores = vector_stores::VectorStores::new(
+            transport.clone(),
+            endpoint.clone(),
+            recovery_policy.clone(),
+        );
+        let tools = tools::Tools::new(transport.clone(), endpoint.clone(), recovery_policy.clone());
+        let images =
+            images::Images::new(transport.clone(), endpoint.clone(), recovery_policy.clone());
+        let containers = containers::Containers::new(transport.clone(), endpoint.clone());
         let responses = responses::Responses::new_with_recovery(
-            http_client.clone(),
-            base_url.clone(),
+            transport.clone(),
+            endpoint.clone(),
             recovery_policy,
         );
-        let messages = messages::Messages::new(http_client.clone(), base_url.clone());
-        let files = files::Files::new(http_client.clone(), base_url.clone());
-        let vector_stores = vector_stores::VectorStores::new(http_client.clone(), base_url.clone());
-        let tools = tools::Tools::new(http_client.clone(), base_url.clone());
-        let images = images::Images::new(http_client.clone(), base_url.clone());
 
         Self {
             responses,
@@ -209,9 +435,165 @@ impl Client {
             vector_stores,
             tools,
             images,
+            containers,
+            redacted_key: None,
+            user_agent: None,
         }
     }
 
+    /// Starts a [`ConversationManager`](conversation::ConversationManager) over `responses`,
+    /// building every turn from `template`
+    #[must_use]
+    pub fn conversation(
+        &self,
+        template: types::RequestTemplate,
+    ) -> conversation::ConversationManager {
+        conversation::ConversationManager::new(self.responses.clone(), template)
+    }
+
+    /// Sends `prompt` to `model` and returns the output text.
+    ///
+    /// A one-shot shortcut for `responses.create` that goes through the client's
+    /// recovery policy. On failure, [`Error::user_message`] gives text suitable for
+    /// end users.
+    ///
+    /// # Errors
+    ///
+    /// Returns the error from [`Responses::create`](responses::Responses::create)
+    pub async fn ask(&self, model: impl Into<Model>, prompt: impl Into<String>) -> Result<String> {
+        let request = Request::builder().model(model).input(prompt).build();
+        self.ask_request(request).await
+    }
+
+    /// Sends `prompt` with the images at `image_urls` to `model` and returns the output text.
+    ///
+    /// The prompt is sent ahead of the images in a single user message.
+    ///
+    /// # Errors
+    ///
+    /// Returns the error from [`Responses::create`](responses::Responses::create)
+    pub async fn ask_with_images(
+        &self,
+        model: impl Into<Model>,
+        prompt: impl Into<String>,
+        image_urls: &[&str],
+    ) -> Result<String> {
+        let request = image_urls
+            .iter()
+            .fold(
+                Request::builder().model(model).input(prompt),
+                |builder, url| builder.push_image_url(*url),
+            )
+            .build();
+        self.ask_request(request).await
+    }
+
+    async fn ask_request(&self, request: Request) -> Result<String> {
+        Ok(self.responses.create(request).await?.output_text())
+    }
+
+    /// Streams the answer to `prompt` from `model` as text deltas.
+    ///
+    /// Other events are skipped. A [`StreamEvent::Failed`] event is yielded as an
+    /// error, and the stream ends after it or after the response completes.
+    #[cfg(feature = "stream")]
+    pub fn ask_streaming(
+        &self,
+        model: impl Into<Model>,
+        prompt: impl Into<String>,
+    ) -> impl futures::Stream<Item = Result<String>> {
+        use futures::StreamExt;
+
+        let request = Request::builder().model(model).input(prompt).build();
+        self.responses.stream(request).filter_map(|event| {
+            futures::future::ready(match event {
+                Ok(StreamEvent::TextDelta { content, .. }) => Some(Ok(content)),
+                Ok(StreamEvent::Failed { error }) => Some(Err(error.into())),
+                Ok(_) => None,
+                Err(error) => Some(Err(error)),
+            })
+        })
+    }
+
+    /// Reports server-side stream failures as `Err` items instead of
+    /// [`StreamEvent::Failed`] events.
+    ///
+    /// See [`Responses::with_stream_failures_as_errors`](responses::Responses::with_stream_failures_as_errors).
+    #[must_use]
+    pub fn with_stream_failures_as_errors(mut self, enabled: bool) -> Self {
+        self.responses = self.responses.with_stream_failures_as_errors(enabled);
+        self
+    }
+
+    /// Returns 404s from `responses.delete` and `vector_stores.delete_file` as errors
+    /// instead of [`DeleteOutcome::NotFound`](types::DeleteOutcome::NotFound).
+    ///
+    /// See [`Responses::with_strict_deletes`](responses::Responses::with_strict_deletes).
+    #[must_use]
+    pub fn with_strict_deletes(mut self, enabled: bool) -> Self {
+        self.responses = self.responses.with_strict_deletes(enabled);
+        self.vector_stores = self.vector_stores.with_strict_deletes(enabled);
+        self
+    }
+
+    /// Logs a warning after each `responses.create` for every sent parameter the server
+    /// ignored or changed.
+    ///
+    /// See [`Responses::with_warn_on_discrepancy`](responses::Responses::with_warn_on_discrepancy).
+    #[must_use]
+    pub fn with_warn_on_discrepancy(mut self, enabled: bool) -> Self {
+        self.responses = self.responses.with_warn_on_discrepancy(enabled);
+        self
+    }
+
+    /// Sets soft limits on the size of requests sent by `responses.create`.
+    ///
+    /// See [`Responses::with_request_size_limits`](responses::Responses::with_request_size_limits).
+    #[must_use]
+    pub fn with_request_size_limits(mut self, limits: types::RequestSizeLimits) -> Self {
+        self.responses = self.responses.with_request_size_limits(limits);
+        self
+    }
+
+    /// Drops base64 image results while deserializing `responses.create` responses.
+    ///
+    /// See [`Responses::with_lite_responses`](responses::Responses::with_lite_responses).
+    #[must_use]
+    pub fn with_lite_responses(mut self, enabled: bool) -> Self {
+        self.responses = self.responses.with_lite_responses(enabled);
+        self
+    }
+
+    /// Sets idle and overall timeouts for `responses.stream`.
+    ///
+    /// See [`Responses::with_stream_options`](responses::Responses::with_stream_options).
+    #[must_use]
+    pub fn with_stream_options(mut self, options: types::StreamOptions) -> Self {
+        self.responses = self.responses.with_stream_options(options);
+        self
+    }
+
+    /// Paces `responses.create` and `responses.stream` with a rate limiter shared by
+    /// every clone of this client.
+    ///
+    /// See [`Responses::with_rate_limiter`](responses::Responses::with_rate_limiter).
+    #[must_use]
+    pub fn with_rate_limiter(mut self, config: RateLimitConfig) -> Self {
+        self.responses = self.responses.with_rate_limiter(config);
+        self
+    }
+
+    /// Enables the client-side response cache for `responses.create`.
+    ///
+    /// See [`Responses::create_cached`](responses::Responses::create_cached) for which
+    /// requests are cached.
+    #[cfg(feature = "cache")]
+    #[must_use]
+    pub fn with_response_cache(mut self, config: responses::cache::CacheConfig) -> Self {
+        self.responses = self.responses.with_response_cache(config);
+        self
+    }
+
     /// Creates a new client with recovery policy from the given API key
     ///
     /// # Errors
@@ -221,44 +603,37 @@ impl Client {
         api_key: &str,
         recovery_policy: RecoveryPolicy,
     ) -> std::result::Result<Self, CreateError> {
-        Self::new_with_base_url_and_recovery(api_key, "https://api.openai.com/v1", recovery_policy)
+        Self::new_with_base_url_and_recovery(api_key, DEFAULT_BASE_URL, recovery_policy)
     }
 
     /// Creates a new client with recovery policy from the given API key and base URL
     ///
     /// # Errors
     ///
-    /// Returns `CreateError::InvalidApiKey` if the API key is empty, doesn't start with "sk-", or contains invalid characters
+    /// Returns `CreateError::InvalidApiKey` if the API key is empty, doesn't start with "sk-", or contains invalid characters,
+    /// or `CreateError::InvalidBaseUrl` if the base URL is not a valid http or https URL
     pub fn new_with_base_url_and_recovery(
         api_key: &str,
         base_url: &str,
         recovery_policy: RecoveryPolicy,
     ) -> std::result::Result<Self, CreateError> {
-        if api_key.is_empty() || !api_key.starts_with("sk-") {
-            return Err(CreateError::InvalidApiKey);
-        }
-
-        let mut headers = header::HeaderMap::new();
-        let auth_value = format!("Bearer {api_key}");
-        let auth_header =
-            header::HeaderValue::from_str(&auth_value).map_err(|_| CreateError::InvalidApiKey)?;
-        headers.insert(header::AUTHORIZATION, auth_header);
+        let user_agent = header::HeaderValue::from_static(DEFAULT_USER_AGENT);
+        let http_client = default_http_client(
+            api_key,
+            HttpOptions {
+                user_agent: Some(user_agent.clone()),
+                ..HttpOptions::default()
+            },
+        )?;
+        let endpoint = endpoint::Endpoint::parse(base_url)?;
 
-        let user_agent = format!(
-            "open-ai-rust-responses-by-sshift/{}",
-            env!("CARGO_PKG_VERSION")
-        );
-
-        let http_client = HttpClient::builder()
-            .default_headers(headers)
-            .user_agent(user_agent)
-            .build()?;
-
-        Ok(Self::new_with_http_client_and_recovery(
-            &http_client,
-            base_url,
+        Ok(Self::from_transport(
+            &http::Transport::from(http_client),
+            &endpoint,
             recovery_policy,
-        ))
+        )
+        .with_api_key_hint(api_key)
+        .with_user_agent(Some(&user_agent)))
     }
 
     /// Creates a client with recovery policy from the `OPENAI_API_KEY` environment variable
@@ -269,7 +644,7 @@ impl Client {
     pub fn from_env_with_recovery(
         recovery_policy: RecoveryPolicy,
     ) -> std::result::Result<Self, CreateError> {
-        Self::from_env_with_base_url_and_recovery("https://api.openai.com/v1", recovery_policy)
+        Self::from_env_with_base_url_and_recovery(DEFAULT_BASE_URL, recovery_policy)
     }
 
     /// Creates a client with recovery policy from the `OPENAI_API_KEY` environment variable with a custom base URL
@@ -285,3 +660,530 @@ impl Client {
         Self::new_with_base_url_and_recovery(&api_key, base_url, recovery_policy)
     }
 }
+
+/// Optional settings for the HTTP client built from an API key
+#[cfg(feature = "client")]
+#[derive(Default)]
+struct HttpOptions {
+    user_agent: Option<header::HeaderValue>,
+    organization: Option<header::HeaderValue>,
+    project: Option<header::HeaderValue>,
+    timeout: Option<std::time::Duration>,
+}
+
+/// Builds the default HTTP client, authenticating with the given API key and applying
+/// `options`
+#[cfg(feature = "client")]
+fn default_http_client(
+    api_key: &str,
+    options: HttpOptions,
+) -> std::result::Result<HttpClient, CreateError> {
+    let mut headers = header::HeaderMap::new();
+    headers.insert(header::AUTHORIZATION, auth_header(api_key)?);
+    if let Some(user_agent) = options.user_agent {
+        headers.insert(header::USER_AGENT, user_agent);
+    }
+    if let Some(organization) = options.organization {
+        headers.insert("OpenAI-Organization", organization);
+    }
+    if let Some(project) = options.project {
+        headers.insert("OpenAI-Project", project);
+    }
+
+    let builder = HttpClient::builder().default_headers(headers);
+    // wasm fetch has no client-wide timeout
+    #[cfg(not(target_arch = "wasm32"))]
+    let builder = match options.timeout {
+        Some(timeout) => builder.timeout(timeout),
+        None => builder,
+    };
+    Ok(builder.build()?)
+}
+
+/// Validates `api_key` and builds the `Authorization` header for it
+#[cfg(feature = "client")]
+fn auth_header(api_key: &str) -> std::result::Result<header::HeaderValue, CreateError> {
+    if api_key.is_empty() || !api_key.starts_with("sk-") {
+        return Err(CreateError::InvalidApiKey);
+    }
+
+    let mut auth_header = header::HeaderValue::from_str(&format!("Bearer {api_key}"))
+        .map_err(|_| CreateError::InvalidApiKey)?;
+    // Keeps the key out of `Debug` output
+    auth_header.set_sensitive(true);
+    Ok(auth_header)
+}
+
+/// Builder for [`Client`].
+///
+/// ```rust,no_run
+/// use open_ai_rust_responses_by_sshift::{Client, LogInterceptor};
+/// use std::sync::Arc;
+///
+/// let client = Client::builder()
+///     .api_key("sk-...")
+///     .interceptor(Arc::new(LogInterceptor))
+///     .build()?;
+/// # Ok::<(), open_ai_rust_responses_by_sshift::CreateError>(())
+/// ```
+#[cfg(feature = "client")]
+#[derive(Clone, Default)]
+pub struct ClientBuilder {
+    api_key: Option<String>,
+    base_url: Option<String>,
+    responses_base_url: Option<String>,
+    files_base_url: Option<String>,
+    images_base_url: Option<String>,
+    vector_stores_base_url: Option<String>,
+    http_client: Option<HttpClient>,
+    recovery_policy: Option<RecoveryPolicy>,
+    organization: Option<String>,
+    project: Option<String>,
+    timeout: Option<std::time::Duration>,
+    env: EnvSettings,
+    interceptors: Vec<Arc<dyn Interceptor>>,
+    max_response_body_bytes: Option<u64>,
+    request_signer: Option<Arc<dyn signing::RequestSigner>>,
+    strict_security: bool,
+    user_agent_suffix: Option<String>,
+    user_agent_override: Option<String>,
+    journal: Option<Arc<dyn journal::JournalSink>>,
+    journal_redaction: types::RedactionConfig,
+}
+
+#[cfg(feature = "client")]
+impl fmt::Debug for ClientBuilder {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("ClientBuilder")
+            .field("api_key", &self.api_key.as_ref().map(|_| "<redacted>"))
+            .field("base_url", &self.base_url)
+            .field("responses_base_url", &self.responses_base_url)
+            .field("files_base_url", &self.files_base_url)
+            .field("images_base_url", &self.images_base_url)
+            .field("vector_stores_base_url", &self.vector_stores_base_url)
+            // The HTTP client's headers may hold credentials
+            .field("http_client", &self.http_client.is_some())
+            .field("recovery_policy", &self.recovery_policy)
+            .field("organization", &self.organization)
+            .field("project", &self.project)
+            .field("timeout", &self.timeout)
+            .field("env", &self.env)
+            .field("interceptors", &self.interceptors)
+            .field("max_response_body_bytes", &self.max_response_body_bytes)
+            .field("request_signer", &self.request_signer)
+            .field("strict_security", &self.strict_security)
+            .field("user_agent_suffix", &self.user_agent_suffix)
+            .field("user_agent_override", &self.user_agent_override)
+            .field("journal", &self.journal)
+            .field("journal_redaction", &self.journal_redaction)
+            .finish()
+    }
+}
+
+#[cfg(feature = "client")]
+impl ClientBuilder {
+    /// Creates a builder with default settings
+    #[must_use]
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Sets the API key; defaults to the `OPENAI_API_KEY` environment variable
+    #[must_use]
+    pub fn api_key(mut self, api_key: impl Into<String>) -> Self {
+        self.api_key = Some(api_key.into());
+        self
+    }
+
+    /// Sets the base URL; defaults to `https://api.openai.com/v1`
+    #[must_use]
+    pub fn base_url(mut self, base_url: impl Into<String>) -> Self {
+        self.base_url = Some(base_url.into());
+        self
+    }
+
+    /// Sends `/responses` requests to `base_url` instead of the client's base URL, e.g.
+    /// through a regional gateway
+    #[must_use]
+    pub fn responses_base_url(mut self, base_url: impl Into<String>) -> Self {
+        self.responses_base_url = Some(base_url.into());
+        self
+    }
+
+    /// Sends `/files` requests to `base_url` instead of the client's base URL
+    #[must_use]
+    pub fn files_base_url(mut self, base_url: impl Into<String>) -> Self {
+        self.files_base_url = Some(base_url.into());
+        self
+    }
+
+    /// Sends `/images` requests to `base_url` instead of the client's base URL
+    #[must_use]
+    pub fn images_base_url(mut self, base_url: impl Into<String>) -> Self {
+        self.images_base_url = Some(base_url.into());
+        self
+    }
+
+    /// Sends `/vector_stores` requests to `base_url` instead of the client's base URL
+    #[must_use]
+    pub fn vector_stores_base_url(mut self, base_url: impl Into<String>) -> Self {
+        self.vector_stores_base_url = Some(base_url.into());
+        self
+    }
+
+    /// Uses a preconfigured HTTP client instead of building one from the API key.
+    ///
+    /// The client must already send any authentication headers the server expects.
+    #[must_use]
+    pub fn http_client(mut self, http_client: HttpClient) -> Self {
+        self.http_client = Some(http_client);
+        self
+    }
+
+    /// Sets the recovery policy used by [`Responses::create`](responses::Responses::create)
+    #[must_use]
+    pub fn recovery_policy(mut self, recovery_policy: RecoveryPolicy) -> Self {
+        self.recovery_policy = Some(recovery_policy);
+        self
+    }
+
+    /// Sends `organization` as the `OpenAI-Organization` header.
+    ///
+    /// Ignored when a preconfigured [`http_client`](Self::http_client) is given.
+    #[must_use]
+    pub fn organization(mut self, organization: impl Into<String>) -> Self {
+        self.organization = Some(organization.into());
+        self
+    }
+
+    /// Sends `project` as the `OpenAI-Project` header.
+    ///
+    /// Ignored when a preconfigured [`http_client`](Self::http_client) is given.
+    #[must_use]
+    pub fn project(mut self, project: impl Into<String>) -> Self {
+        self.project = Some(project.into());
+        self
+    }
+
+    /// Sets a timeout for each request, from sending it until the body is read.
+    ///
+    /// Ignored when a preconfigured [`http_client`](Self::http_client) is given, and on
+    /// wasm32, where fetch has no client-wide timeout.
+    #[must_use]
+    pub fn timeout(mut self, timeout: std::time::Duration) -> Self {
+        self.timeout = Some(timeout);
+        self
+    }
+
+    /// Reads settings from the environment.
+    ///
+    /// | Variable | Setting |
+    /// |---|---|
+    /// | `OPENAI_API_KEY` | [`api_key`](Self::api_key) |
+    /// | `OPENAI_BASE_URL` | [`base_url`](Self::base_url) |
+    /// | `OPENAI_ORG_ID` | [`organization`](Self::organization) |
+    /// | `OPENAI_PROJECT_ID` | [`project`](Self::project) |
+    /// | `OPENAI_TIMEOUT_SECS` | [`timeout`](Self::timeout), in whole seconds |
+    /// | `OAI_RECOVERY_*` | [`recovery_policy`](Self::recovery_policy), via [`RecoveryPolicy::from_env`] |
+    ///
+    /// Explicit setters take precedence over the environment whether they are called
+    /// before or after this, and the environment over the defaults. Unset or empty
+    /// variables are skipped. Values are checked here as they would be by
+    /// [`build`](Self::build), so a bad value is reported with its variable's name.
+    ///
+    /// # Errors
+    ///
+    /// Returns `CreateError::InvalidEnvVar` naming the first variable with an invalid
+    /// value. Invalid `OAI_RECOVERY_*` values are logged and keep their defaults.
+    pub fn load_env(mut self) -> std::result::Result<Self, CreateError> {
+        self.env = EnvSettings::load()?;
+        Ok(self)
+    }
+
+    /// Adds an interceptor notified around every API request
+    #[must_use]
+    pub fn interceptor(mut self, interceptor: Arc<dyn Interceptor>) -> Self {
+        self.interceptors.push(interceptor);
+        self
+    }
+
+    /// Replaces the interceptors notified around every API request
+    #[must_use]
+    pub fn interceptors(mut self, interceptors: Vec<Arc<dyn Interceptor>>) -> Self {
+        self.interceptors = interceptors;
+        self
+    }
+
+    /// Records every `responses` create attempt, stream and retrieve to `sink`.
+    ///
+    /// Requests and responses are redacted before they are recorded; see the
+    /// [`journal`] module.
+    #[must_use]
+    pub fn journal(mut self, sink: Arc<dyn journal::JournalSink>) -> Self {
+        self.journal = Some(sink);
+        self
+    }
+
+    /// Sets what the [`journal`](Self::journal) strips from requests, responses and
+    /// stream events; defaults to [`RedactionConfig::default`](types::RedactionConfig::default)
+    #[must_use]
+    pub fn journal_redaction(mut self, redaction: types::RedactionConfig) -> Self {
+        self.journal_redaction = redaction;
+        self
+    }
+
+    /// Signs every request with `signer` after its body is serialized.
+    ///
+    /// See the [`signing`] module for which requests can be signed.
+    #[must_use]
+    pub fn request_signer(mut self, signer: Arc<dyn signing::RequestSigner>) -> Self {
+        self.request_signer = Some(signer);
+        self
+    }
+
+    /// Rejects successful responses with a body larger than `bytes`.
+    ///
+    /// A `Content-Length` over the limit fails the request before the body is read;
+    /// otherwise reading stops once the limit is passed. Either way the error is
+    /// [`Error::ResponseTooLarge`]. Unlimited by default.
+    #[must_use]
+    pub fn max_response_body_bytes(mut self, bytes: u64) -> Self {
+        self.max_response_body_bytes = Some(bytes);
+        self
+    }
+
+    /// Rejects a plain `http` base URL unless it points at a loopback host.
+    ///
+    /// Off by default, so a typo'd `http://` URL would send the API key unencrypted.
+    /// With it on, [`build`](Self::build) fails with `CreateError::InsecureBaseUrl`
+    /// instead. `localhost`, `127.0.0.1` and `::1` stay allowed for local servers.
+    #[must_use]
+    pub fn strict_security(mut self, enabled: bool) -> Self {
+        self.strict_security = enabled;
+        self
+    }
+
+    /// Appends `suffix` to the user agent, separated by a space, e.g. `myapp/2.3 (team-billing)`
+    /// for gateways that attribute requests by application.
+    ///
+    /// Ignored when a preconfigured [`http_client`](Self::http_client) is given.
+    #[must_use]
+    pub fn user_agent_suffix(mut self, suffix: impl Into<String>) -> Self {
+        self.user_agent_suffix = Some(suffix.into());
+        self
+    }
+
+    /// Replaces [`DEFAULT_USER_AGENT`]; an empty string sends no `User-Agent` header.
+    ///
+    /// A [`user_agent_suffix`](Self::user_agent_suffix) is still appended. Ignored when a
+    /// preconfigured [`http_client`](Self::http_client) is given.
+    #[must_use]
+    pub fn user_agent_override(mut self, user_agent: impl Into<String>) -> Self {
+        self.user_agent_override = Some(user_agent.into());
+        self
+    }
+
+    /// The `User-Agent` header to send, or `None` to send none
+    fn user_agent_header(&self) -> std::result::Result<Option<header::HeaderValue>, CreateError> {
+        let base = self
+            .user_agent_override
+            .as_deref()
+            .unwrap_or(DEFAULT_USER_AGENT);
+        let user_agent = match self.user_agent_suffix.as_deref() {
+            Some(suffix) if !base.is_empty() => format!("{base} {suffix}"),
+            Some(suffix) => suffix.to_string(),
+            None => base.to_string(),
+        };
+        if user_agent.is_empty() {
+            return Ok(None);
+        }
+        header::HeaderValue::from_str(&user_agent)
+            .map(Some)
+            .map_err(|_| CreateError::InvalidUserAgent(user_agent))
+    }
+
+    /// Builds the client.
+    ///
+    /// # Errors
+    ///
+    /// Returns `CreateError::ApiKeyNotFound` if no HTTP client or API key was given and
+    /// `OPENAI_API_KEY` is not set, `CreateError::InvalidApiKey` if the API key is invalid,
+    /// `CreateError::InvalidBaseUrl` if a base URL is not a valid http or https URL,
+    /// `CreateError::InsecureBaseUrl` if [`strict_security`](Self::strict_security) is on
+    /// and a base URL is plain http to a non-loopback host, `CreateError::InvalidUserAgent`
+    /// if the user agent is not a valid header value, or `CreateError::HttpClient` if the
+    /// HTTP client cannot be created.
+    pub fn build(mut self) -> std::result::Result<Client, CreateError> {
+        let env = std::mem::take(&mut self.env);
+        let recovery_policy = self
+            .recovery_policy
+            .take()
+            .or(env.recovery_policy)
+            .unwrap_or_default();
+        let base_url = self.base_url.take().or(env.base_url);
+        let parse = |base_url: &str| -> std::result::Result<_, CreateError> {
+            let endpoint = endpoint::Endpoint::parse(base_url)?;
+            if self.strict_security {
+                endpoint.require_https()?;
+            }
+            Ok(endpoint)
+        };
+        let endpoint = parse(base_url.as_deref().unwrap_or(DEFAULT_BASE_URL))?;
+        let responses_endpoint = self.responses_base_url.as_deref().map(parse).transpose()?;
+        let files_endpoint = self.files_base_url.as_deref().map(parse).transpose()?;
+        let images_endpoint = self.images_base_url.as_deref().map(parse).transpose()?;
+        let vector_stores_endpoint = self
+            .vector_stores_base_url
+            .as_deref()
+            .map(parse)
+            .transpose()?;
+
+        let (http_client, api_key, user_agent) = if let Some(http_client) = self.http_client {
+            (http_client, None, None)
+        } else {
+            let user_agent = self.user_agent_header()?;
+            let header = |value: Option<String>, error: fn(String) -> CreateError| {
+                value
+                    .map(|value| header::HeaderValue::from_str(&value).map_err(|_| error(value)))
+                    .transpose()
+            };
+            let options = HttpOptions {
+                user_agent: user_agent.clone(),
+                organization: header(
+                    self.organization.or(env.organization),
+                    CreateError::InvalidOrganization,
+                )?,
+                project: header(self.project.or(env.project), CreateError::InvalidProject)?,
+                timeout: self.timeout.or(env.timeout),
+            };
+            let api_key = match self.api_key.or(env.api_key) {
+                Some(api_key) => api_key,
+                None => env::var("OPENAI_API_KEY").map_err(|_| CreateError::ApiKeyNotFound)?,
+            };
+            (
+                default_http_client(&api_key, options)?,
+                Some(api_key),
+                user_agent,
+            )
+        };
+
+        let transport = http::Transport::new(http_client, self.interceptors)
+            .with_max_body_bytes(self.max_response_body_bytes)
+            .with_signer(self.request_signer);
+        let mut client = Client::from_transport(&transport, &endpoint, recovery_policy.clone())
+            .with_user_agent(user_agent.as_ref());
+        if let Some(endpoint) = responses_endpoint {
+            client.responses = responses::Responses::new_with_recovery(
+                transport.clone(),
+                endpoint,
+                recovery_policy.clone(),
+            );
+        }
+        if let Some(endpoint) = files_endpoint {
+            client.files = files::Files::new(transport.clone(), endpoint, recovery_policy.clone());
+        }
+        if let Some(endpoint) = images_endpoint {
+            client.images =
+                images::Images::new(transport.clone(), endpoint, recovery_policy.clone());
+        }
+        if let Some(endpoint) = vector_stores_endpoint {
+            client.vector_stores =
+                vector_stores::VectorStores::new(transport, endpoint, recovery_policy);
+        }
+        if let Some(sink) = self.journal {
+            client.responses = client
+                .responses
+                .with_journal(journal::Journal::new(sink).with_redaction(self.journal_redaction));
+        }
+        Ok(match api_key {
+            Some(api_key) => client.with_api_key_hint(&api_key),
+            None => client,
+        })
+    }
+}
+
+/// Settings read by [`ClientBuilder::load_env`], used where no explicit setter was called
+#[cfg(feature = "client")]
+#[derive(Clone, Default)]
+struct EnvSettings {
+    api_key: Option<String>,
+    base_url: Option<String>,
+    organization: Option<String>,
+    project: Option<String>,
+    timeout: Option<std::time::Duration>,
+    recovery_policy: Option<RecoveryPolicy>,
+}
+
+#[cfg(feature = "client")]
+impl fmt::Debug for EnvSettings {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("EnvSettings")
+            .field("api_key", &self.api_key.as_ref().map(|_| "<redacted>"))
+            .field("base_url", &self.base_url)
+            .field("organization", &self.organization)
+            .field("project", &self.project)
+            .field("timeout", &self.timeout)
+            .field("recovery_policy", &self.recovery_policy)
+            .finish()
+    }
+}
+
+#[cfg(feature = "client")]
+impl EnvSettings {
+    fn load() -> std::result::Result<Self, CreateError> {
+        let invalid =
+            |name: &'static str, reason: String| CreateError::InvalidEnvVar { name, reason };
+
+        let api_key = env_var("OPENAI_API_KEY");
+        if let Some(api_key) = &api_key {
+            auth_header(api_key).map_err(|_| {
+                invalid(
+                    "OPENAI_API_KEY",
+                    "expected a key starting with `sk-`".to_string(),
+                )
+            })?;
+        }
+        let base_url = env_var("OPENAI_BASE_URL");
+        if let Some(base_url) = &base_url {
+            endpoint::Endpoint::parse(base_url)
+                .map_err(|error| invalid("OPENAI_BASE_URL", error.to_string()))?;
+        }
+        let header = |name: &'static str| {
+            let value = env_var(name);
+            if let Some(value) = &value {
+                header::HeaderValue::from_str(value)
+                    .map_err(|_| invalid(name, format!("{value:?} is not a valid header value")))?;
+            }
+            Ok::<_, CreateError>(value)
+        };
+        let organization = header("OPENAI_ORG_ID")?;
+        let project = header("OPENAI_PROJECT_ID")?;
+        let timeout = env_var("OPENAI_TIMEOUT_SECS")
+            .map(|value| match value.parse::<u64>() {
+                Ok(secs) if secs > 0 => Ok(std::time::Duration::from_secs(secs)),
+                _ => Err(invalid(
+                    "OPENAI_TIMEOUT_SECS",
+                    format!("{value:?} is not a positive number of seconds"),
+                )),
+            })
+            .transpose()?;
+
+        Ok(Self {
+            api_key,
+            base_url,
+            organization,
+            project,
+            timeout,
+            recovery_policy: Some(RecoveryPolicy::from_env()),
+        })
+    }
+}
+
+/// The trimmed value of `name`, or `None` if it is unset or empty
+#[cfg(feature = "client")]
+fn env_var(name: &str) -> Option<String> {
+    env::var(name)
+        .ok()
+        .map(|value| value.trim().to_string())
+        .filter(|value| !value.is_empty())
+}
@@ -1,10 +1,12 @@
 pub mod adapter;
 pub mod client;
 pub mod registry;
+pub mod runner;
 pub mod transport;
 pub mod types;
 
 pub use client::McpClient;
 pub use registry::{LocalTool, ToolRegistry};
+pub use runner::{CallRecord, ToolRunReport};
 pub use transport::HttpTransport;
 pub use types::*;
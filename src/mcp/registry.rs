@@ -7,7 +7,8 @@ use std::collections::HashMap;
 use std::sync::Arc;
 
 /// Trait for local tools
-#[async_trait]
+#[cfg_attr(not(target_arch = "wasm32"), async_trait)]
+#[cfg_attr(target_arch = "wasm32", async_trait(?Send))]
 pub trait LocalTool: Send + Sync {
     /// Returns the name of the tool
     fn name(&self) -> &str;
@@ -78,7 +79,8 @@ impl ToolRegistry {
     /// 1. Collects all registered local tools.
     /// 2. Fetches available tools from the configured MCP server (if any).
     /// 3. Converts MCP tools to the OpenAI `Tool` format using `mcp_tool_to_openai_tool`.
-    /// 4. Returns a unified vector ready to be sent in an OpenAI API request.
+    /// 4. Skips MCP tools shadowed by a local tool of the same name.
+    /// 5. Returns a unified vector ready to be sent in an OpenAI API request.
     ///
     /// # Errors
     /// Returns an error if the MCP client fails to list tools from the remote server.
@@ -98,7 +100,14 @@ impl ToolRegistry {
         if let Some(client) = &self.mcp_client {
             let mcp_tools = client.list_tools().await?;
             for mcp_tool in mcp_tools {
-                tools.push(super::adapter::mcp_tool_to_openai_tool(mcp_tool));
+                let tool = super::adapter::mcp_tool_to_openai_tool(mcp_tool);
+                // A local tool of the same name shadows it, as in `call_tool`
+                if !tools
+                    .iter()
+                    .any(|t| t.identity_key() == tool.identity_key())
+                {
+                    tools.push(tool);
+                }
             }
         }
 
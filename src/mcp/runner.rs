@@ -0,0 +1,398 @@
+//! Run loop that executes the model's function calls through a [`ToolRegistry`] until
+//! it stops asking for them.
+//!
+//! How calls within one turn are executed follows the request's `parallel_tool_calls`
+//! flag: with `Some(false)` the model expects one call per turn, so calls run one after
+//! another in the order they were emitted; otherwise all calls of a turn run
+//! concurrently. Either way every call's output is submitted before the next turn.
+
+use crate::error::Result;
+use crate::mcp::ToolRegistry;
+use crate::responses::Responses;
+use crate::runtime::Stopwatch;
+use crate::types::{FunctionCallInfo, Input, InputItem};
+use futures_util::FutureExt;
+use std::panic::AssertUnwindSafe;
+use std::time::Duration;
+
+/// One executed tool call
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct CallRecord {
+    /// Name of the tool
+    pub name: String,
+
+    /// Call ID the output was submitted under
+    pub call_id: String,
+
+    /// Time spent in the handler
+    pub duration: Duration,
+
+    /// Whether the handler returned a result; `false` if it failed, panicked or was
+    /// given arguments that are not JSON
+    pub ok: bool,
+
+    /// The failure, as submitted to the model
+    pub error: Option<String>,
+}
+
+/// What a [`ToolRegistry::run_tools`] call did
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct ToolRunReport {
+    /// Number of turns whose tool calls were executed and submitted
+    pub iterations: usize,
+
+    /// Every executed call, in submission order
+    pub calls: Vec<CallRecord>,
+}
+
+impl ToolRunReport {
+    /// Returns the calls that failed
+    pub fn failures(&self) -> impl Iterator<Item = &CallRecord> {
+        self.calls.iter().filter(|call| !call.ok)
+    }
+}
+
+impl ToolRegistry {
+    /// Sends `request` and keeps answering the model's function calls with this
+    /// registry's tools, until a response has no calls left or `max_iterations` turns
+    /// of calls have been answered.
+    ///
+    /// Follow-up requests reuse `request` with `previous_response_id` set and the
+    /// call outputs as input. A failing or panicking handler does not stop the loop:
+    /// its error is submitted to the model as the call's output, as
+    /// `{"error": "..."}`, and recorded in the report.
+    ///
+    /// Returns the last response with the report. If the limit was reached, that
+    /// response still has unanswered calls.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if a request to the API fails.
+    pub async fn run_tools(
+        &self,
+        responses: &Responses,
+        request: crate::Request,
+        max_iterations: usize,
+    ) -> Result<(crate::Response, ToolRunReport)> {
+        let sequential = request.parallel_tool_calls == Some(false);
+        let mut report = ToolRunReport::default();
+        let mut response = responses.create(request.clone()).await?;
+
+        while report.iterations < max_iterations {
+            let calls = response.tool_calls();
+            if calls.is_empty() {
+                break;
+            }
+            report.iterations += 1;
+
+            let records = if sequential {
+                let mut records = Vec::with_capacity(calls.len());
+                for call in &calls {
+                    records.push(self.execute_call(call).await);
+                }
+                records
+            } else {
+                futures_util::future::join_all(calls.iter().map(|call| self.execute_call(call)))
+                    .await
+            };
+
+            let mut outputs = Vec::with_capacity(records.len());
+            for (record, output) in records {
+                outputs.push(InputItem::function_call_output(
+                    record.call_id.clone(),
+                    output,
+                ));
+                report.calls.push(record);
+            }
+
+            let mut next = request.clone();
+            next.previous_response_id = Some(response.id.clone());
+            next.input = Input::Items(outputs);
+            response = responses.create(next).await?;
+        }
+
+        Ok((response, report))
+    }
+
+    /// Runs one call, turning every failure into an output for the model
+    async fn execute_call(&self, call: &FunctionCallInfo) -> (CallRecord, String) {
+        let stopwatch = Stopwatch::start();
+        let result = match call.arguments_json() {
+            Ok(args) => AssertUnwindSafe(self.call_tool(&call.name, args))
+                .catch_unwind()
+                .await
+                .unwrap_or_else(|panic| {
+                    let message = panic
+                        .downcast_ref::<&str>()
+                        .map(ToString::to_string)
+                        .or_else(|| panic.downcast_ref::<String>().cloned())
+                        .unwrap_or_else(|| "unknown panic".to_string());
+                    Err(crate::Error::Mcp(format!("tool panicked: {message}")))
+                }),
+            Err(error) => Err(error),
+        };
+        let duration = stopwatch.elapsed();
+
+        let (output, error) = match result {
+            Ok(serde_json::Value::String(text)) => (text, None),
+            Ok(value) => (value.to_string(), None),
+            Err(error) => {
+                let message = error.to_string();
+                crate::diag::warn!("Tool {} failed: {message}", call.name);
+                (
+                    serde_json::json!({ "error": message }).to_string(),
+                    Some(message),
+                )
+            }
+        };
+        let record = CallRecord {
+            name: call.name.clone(),
+            call_id: call.call_id.clone(),
+            duration,
+            ok: error.is_none(),
+            error,
+        };
+        (record, output)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::mcp::LocalTool;
+    use async_trait::async_trait;
+    use serde_json::{json, Value};
+    use std::sync::{Arc, Mutex};
+
+    /// Logs the start and end of each call, waiting for the shared barrier if any
+    struct Step {
+        name: &'static str,
+        log: Arc<Mutex<Vec<String>>>,
+        barrier: Option<Arc<tokio::sync::Barrier>>,
+    }
+
+    #[async_trait]
+    impl LocalTool for Step {
+        fn name(&self) -> &str {
+            self.name
+        }
+        fn description(&self) -> &'static str {
+            "Test step"
+        }
+        fn schema(&self) -> Value {
+            json!({"type": "object"})
+        }
+        async fn call(&self, args: Value) -> crate::Result<Value> {
+            let entry = format!("{}:{}", self.name, args["n"]);
+            self.log.lock().unwrap().push(format!(">{entry}"));
+            tokio::task::yield_now().await;
+            if let Some(barrier) = &self.barrier {
+                barrier.wait().await;
+            }
+            self.log.lock().unwrap().push(format!("<{entry}"));
+            match self.name {
+                "fails" => Err(crate::Error::Mcp("disk full".to_string())),
+                "panics" => panic!("handler bug"),
+                _ => Ok(json!({"step": args["n"]})),
+            }
+        }
+    }
+
+    fn registry(log: &Arc<Mutex<Vec<String>>>, barrier: Option<usize>) -> ToolRegistry {
+        let barrier = barrier.map(|n| Arc::new(tokio::sync::Barrier::new(n)));
+        let mut registry = ToolRegistry::new();
+        for name in ["step", "fails", "panics"] {
+            registry.register_local_tool(Box::new(Step {
+                name,
+                log: log.clone(),
+                barrier: barrier.clone(),
+            }));
+        }
+        registry
+    }
+
+    fn response_with(id: &str, calls: &[(&str, &str, u32)]) -> String {
+        let output: Vec<Value> = if calls.is_empty() {
+            vec![json!({
+                "type": "message",
+                "id": "msg_1",
+                "role": "assistant",
+                "status": "completed",
+                "content": [{"type": "output_text", "text": "All done", "annotations": []}]
+            })]
+        } else {
+            calls
+                .iter()
+                .map(|(name, call_id, n)| {
+                    json!({
+                        "type": "function_call",
+                        "id": format!("fc_{call_id}"),
+                        "call_id": call_id,
+                        "name": name,
+                        "arguments": json!({"n": n}).to_string(),
+                        "status": "completed"
+                    })
+                })
+                .collect()
+        };
+        json!({
+            "id": id,
+            "object": "response",
+            "created_at": 1_700_000_000,
+            "model": "gpt-4o",
+            "status": "completed",
+            "output": output
+        })
+        .to_string()
+    }
+
+    /// Expects one request answering `previous` with outputs matching the `submitted` regex
+    async fn turn(
+        server: &mut mockito::ServerGuard,
+        previous: Option<(&str, &str)>,
+        body: String,
+    ) -> mockito::Mock {
+        let matcher = match previous {
+            Some((id, submitted)) => mockito::Matcher::AllOf(vec![
+                mockito::Matcher::PartialJson(json!({"previous_response_id": id})),
+                mockito::Matcher::Regex(submitted.to_string()),
+            ]),
+            None => mockito::Matcher::PartialJson(json!({"input": "go"})),
+        };
+        server
+            .mock("POST", "/responses")
+            .match_body(matcher)
+            .with_status(200)
+            .with_body(body)
+            .expect(1)
+            .create_async()
+            .await
+    }
+
+    #[tokio::test]
+    async fn sequential_run_answers_one_call_per_turn_and_survives_failures() {
+        let mut server = mockito::Server::new_async().await;
+        // The failures reach the model as error outputs
+        let mocks = vec![
+            turn(
+                &mut server,
+                None,
+                // The model ignored the flag; the calls still run one at a time
+                response_with("resp_1", &[("step", "call_1", 1), ("step", "call_4", 4)]),
+            )
+            .await,
+            turn(
+                &mut server,
+                Some(("resp_1", r#"\\"step\\":1"#)),
+                response_with("resp_2", &[("fails", "call_2", 2)]),
+            )
+            .await,
+            turn(
+                &mut server,
+                Some(("resp_2", "disk full")),
+                response_with("resp_3", &[("panics", "call_3", 3)]),
+            )
+            .await,
+            turn(
+                &mut server,
+                Some(("resp_3", "tool panicked: handler bug")),
+                response_with("resp_4", &[]),
+            )
+            .await,
+        ];
+
+        let log = Arc::new(Mutex::new(Vec::new()));
+        let registry = registry(&log, None);
+        let responses = Responses::new(reqwest::Client::new(), server.url());
+        let request = crate::Request::builder()
+            .model("gpt-4o")
+            .input("go")
+            .parallel_tool_calls(false)
+            .build();
+        let (response, report) = registry.run_tools(&responses, request, 10).await.unwrap();
+
+        assert_eq!(response.output_text(), "All done");
+        assert_eq!(report.iterations, 3);
+        assert_eq!(
+            *log.lock().unwrap(),
+            [
+                ">step:1",
+                "<step:1",
+                ">step:4",
+                "<step:4",
+                ">fails:2",
+                "<fails:2",
+                ">panics:3",
+                "<panics:3"
+            ]
+        );
+        let outcomes: Vec<_> = report
+            .calls
+            .iter()
+            .map(|call| (call.name.as_str(), call.ok))
+            .collect();
+        assert_eq!(
+            outcomes,
+            [
+                ("step", true),
+                ("step", true),
+                ("fails", false),
+                ("panics", false)
+            ]
+        );
+        assert!(report.calls[3]
+            .error
+            .as_deref()
+            .unwrap()
+            .contains("tool panicked: handler bug"));
+        assert_eq!(report.failures().count(), 2);
+        for mock in mocks {
+            mock.assert_async().await;
+        }
+    }
+
+    #[tokio::test]
+    async fn parallel_run_executes_a_turn_concurrently() {
+        let mut server = mockito::Server::new_async().await;
+        let mocks = [
+            turn(
+                &mut server,
+                None,
+                response_with("resp_1", &[("step", "call_a", 1), ("step", "call_b", 2)]),
+            )
+            .await,
+            turn(
+                &mut server,
+                Some(("resp_1", "call_b")),
+                response_with("resp_2", &[("step", "call_c", 3)]),
+            )
+            .await,
+        ];
+
+        // Two calls that each wait for the other only finish when run concurrently
+        let log = Arc::new(Mutex::new(Vec::new()));
+        let registry = registry(&log, Some(2));
+        let responses = Responses::new(reqwest::Client::new(), server.url());
+        let request = crate::Request::builder()
+            .model("gpt-4o")
+            .input("go")
+            .build();
+        let first_turn = tokio::time::timeout(
+            Duration::from_secs(5),
+            registry.run_tools(&responses, request, 1),
+        )
+        .await
+        .expect("calls of one turn ran one after another");
+        let (response, report) = first_turn.unwrap();
+
+        // The limit stops the loop with the next call unanswered
+        assert_eq!(report.iterations, 1);
+        assert_eq!(response.id, "resp_2");
+        assert_eq!(response.tool_calls().len(), 1);
+        let call_ids: Vec<_> = report.calls.iter().map(|c| c.call_id.as_str()).collect();
+        assert_eq!(call_ids, ["call_a", "call_b"]);
+        assert!(report.calls.iter().all(|call| call.ok));
+        mocks[0].assert_async().await;
+        mocks[1].assert_async().await;
+    }
+}
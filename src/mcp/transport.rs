@@ -4,7 +4,8 @@ use async_trait::async_trait;
 use reqwest::header::{HeaderMap, HeaderName, HeaderValue};
 use reqwest::Client;
 
-#[async_trait]
+#[cfg_attr(not(target_arch = "wasm32"), async_trait)]
+#[cfg_attr(target_arch = "wasm32", async_trait(?Send))]
 pub trait McpTransport: Send + Sync {
     async fn send(&self, message: &JsonRpcRequest) -> Result<JsonRpcResponse>;
 }
@@ -73,7 +74,8 @@ impl HttpTransport {
     }
 }
 
-#[async_trait]
+#[cfg_attr(not(target_arch = "wasm32"), async_trait)]
+#[cfg_attr(target_arch = "wasm32", async_trait(?Send))]
 impl McpTransport for HttpTransport {
     async fn send(&self, message: &JsonRpcRequest) -> Result<JsonRpcResponse> {
         let response = self
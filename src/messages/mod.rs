@@ -1,14 +1,15 @@
-use crate::error::{try_parse_api_error, Result};
+use crate::endpoint::Endpoint;
+use crate::error::Result;
+use crate::http::Transport;
 use crate::types::PaginationParams;
 use chrono::{DateTime, Utc};
-use reqwest::Client as HttpClient;
 use serde::{Deserialize, Serialize};
 
 /// Messages API endpoints
 #[derive(Debug, Clone)]
 pub struct Messages {
-    client: HttpClient,
-    base_url: String,
+    client: Transport,
+    endpoint: Endpoint,
 }
 
 /// Message object representing a message in a thread
@@ -18,6 +19,7 @@ pub struct Message {
     pub id: String,
 
     /// Type of object (always "message")
+    #[cfg_attr(feature = "lenient", serde(default))]
     pub object: String,
 
     /// Thread ID that this message belongs to
@@ -30,12 +32,12 @@ pub struct Message {
     pub content: String,
 
     /// Unix timestamp for when the message was created
-    #[serde(with = "chrono::serde::ts_seconds")]
+    #[serde(with = "crate::types::lenient::timestamp")]
     pub created_at: DateTime<Utc>,
 
     /// Optional metadata associated with the message
     #[serde(skip_serializing_if = "Option::is_none")]
-    pub metadata: Option<serde_json::Value>,
+    pub metadata: Option<crate::types::Metadata>,
 }
 
 /// Request to create a message
@@ -49,7 +51,7 @@ pub struct CreateMessageRequest {
 
     /// Optional metadata to associate with the message
     #[serde(skip_serializing_if = "Option::is_none")]
-    pub metadata: Option<serde_json::Value>,
+    pub metadata: Option<crate::types::Metadata>,
 }
 
 /// Response containing a list of messages
@@ -84,8 +86,17 @@ impl ListMessagesResponse {
 
 impl Messages {
     /// Creates a new Messages API client
-    pub(crate) fn new(client: HttpClient, base_url: String) -> Self {
-        Self { client, base_url }
+    pub(crate) fn new(client: impl Into<Transport>, endpoint: impl Into<Endpoint>) -> Self {
+        Self {
+            client: client.into(),
+            endpoint: endpoint.into(),
+        }
+    }
+
+    /// Sends this handle's requests with `auth` as the `Authorization` header
+    pub(crate) fn with_auth(mut self, auth: &reqwest::header::HeaderValue) -> Self {
+        self.client = self.client.with_auth(auth.clone());
+        self
     }
 
     /// Creates a message in a thread.
@@ -94,16 +105,11 @@ impl Messages {
     ///
     /// Returns an error if the request fails to send or has a non-200 status code.
     pub async fn create(&self, thread_id: &str, request: CreateMessageRequest) -> Result<Message> {
-        let response = self
+        let request = self
             .client
-            .post(format!("{}/threads/{}/messages", self.base_url, thread_id))
-            .json(&request)
-            .send()
-            .await
-            .map_err(crate::Error::Http)?;
-
-        let response = try_parse_api_error(response).await?;
-        response.json().await.map_err(crate::Error::Http)
+            .post(self.endpoint.url(&["threads", thread_id, "messages"])?)
+            .json(&request);
+        self.client.send_json(request).await
     }
 
     /// Retrieves a message by ID.
@@ -112,25 +118,19 @@ impl Messages {
     ///
     /// Returns an error if the request fails to send or has a non-200 status code.
     pub async fn retrieve(&self, thread_id: &str, message_id: &str) -> Result<Message> {
-        let response = self
-            .client
-            .get(format!(
-                "{}/threads/{}/messages/{}",
-                self.base_url, thread_id, message_id
-            ))
-            .send()
-            .await
-            .map_err(crate::Error::Http)?;
-
-        let response = try_parse_api_error(response).await?;
-        response.json().await.map_err(crate::Error::Http)
+        let request = self.client.get(
+            self.endpoint
+                .url(&["threads", thread_id, "messages", message_id])?,
+        );
+        self.client.send_json(request).await
     }
 
     /// Lists messages in a thread.
     ///
     /// # Errors
     ///
-    /// Returns an error if the request fails to send or has a non-200 status code.
+    /// Returns an error if `params` has an out-of-range limit, or if the request fails to send
+    /// or has a non-200 status code.
     pub async fn list(
         &self,
         thread_id: &str,
@@ -138,16 +138,14 @@ impl Messages {
     ) -> Result<ListMessagesResponse> {
         let mut request = self
             .client
-            .get(format!("{}/threads/{}/messages", self.base_url, thread_id));
+            .get(self.endpoint.url(&["threads", thread_id, "messages"])?);
 
         if let Some(params) = params {
+            params.validate()?;
             request = request.query(&params);
         }
 
-        let response = request.send().await.map_err(crate::Error::Http)?;
-
-        let response = try_parse_api_error(response).await?;
-        response.json().await.map_err(crate::Error::Http)
+        self.client.send_json(request).await
     }
 
     /// Creates a message in a conversation using response IDs.
@@ -170,7 +168,7 @@ impl Messages {
             ..Default::default()
         };
 
-        let response = crate::responses::Responses::new(self.client.clone(), self.base_url.clone())
+        let response = crate::responses::Responses::new(self.client.clone(), self.endpoint.clone())
             .create(response_request)
             .await?;
 
@@ -216,7 +214,7 @@ impl Messages {
             }
 
             let response =
-                crate::responses::Responses::new(self.client.clone(), self.base_url.clone())
+                crate::responses::Responses::new(self.client.clone(), self.endpoint.clone())
                     .retrieve(&id)
                     .await?;
 
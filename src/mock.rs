@@ -0,0 +1,1006 @@
+//! In-process stand-in for the OpenAI API, for running examples and tests without a key.
+//!
+//! [`spawn`] starts an HTTP server on a local port that implements enough of `/responses`,
+//! `/files` and `/vector_stores` for the examples to run end to end. Its answers are
+//! canned and deterministic, and they echo the request's model, instructions and sampling
+//! parameters, so tests can check what reached the API:
+//!
+//! ```rust,no_run
+//! # async fn demo() -> Result<(), Box<dyn std::error::Error>> {
+//! use open_ai_rust_responses_by_sshift::{mock, Request};
+//!
+//! let server = mock::spawn()?;
+//! let client = server.client()?;
+//!
+//! let response = client
+//!     .responses
+//!     .create(Request::builder().model("gpt-4o").input("Hello").build())
+//!     .await?;
+//! assert!(response.output_text().contains("gpt-4o"));
+//! assert_eq!(server.requests()[0].path, "/responses");
+//! # Ok(())
+//! # }
+//! ```
+//!
+//! Requests are answered as follows:
+//! - a create with function tools calls each of them with `{}` as arguments, unless the
+//!   input already holds function call outputs; otherwise it answers with a message
+//! - web search, file search and image generation tools add a completed call of their
+//!   kind before the message; image generation returns a 1x1 PNG
+//! - `"stream": true` streams the same response as server-sent events, one word per delta
+//! - files and vector stores live in memory; a vector store search returns the start of
+//!   each of its files
+//! - anything else is a 404 in the API's error format
+
+use hyper::service::{make_service_fn, service_fn};
+use hyper::{Body, Method, StatusCode};
+use serde_json::{json, Value};
+use std::collections::{BTreeMap, HashMap};
+use std::fmt::Write as _;
+use std::sync::{Arc, Mutex, MutexGuard};
+
+/// API key used by [`MockServerHandle::client`]; the server accepts any key
+pub const MOCK_API_KEY: &str = "sk-mock";
+
+/// Creation time reported for every object, so outputs stay deterministic
+const CREATED_AT: i64 = 1_700_000_000;
+
+/// Request fields copied into each canned response
+const ECHOED_FIELDS: &[&str] = &[
+    "instructions",
+    "previous_response_id",
+    "temperature",
+    "top_p",
+    "max_output_tokens",
+    "parallel_tool_calls",
+    "tool_choice",
+    "tools",
+    "metadata",
+    "user",
+    "store",
+    "background",
+    "reasoning",
+    "text",
+];
+
+/// 1x1 transparent PNG returned by the image generation tool
+const PIXEL_PNG_B64: &str =
+    "iVBORw0KGgoAAAANSUhEUgAAAAEAAAABCAQAAAC1HAwCAAAAC0lEQVR42mNkYAAAAAYAAjCB0C8AAAAASUVORK5CYII=";
+
+/// A request the mock server received
+#[derive(Debug, Clone, PartialEq)]
+pub struct RecordedRequest {
+    /// HTTP method, e.g. `"POST"`
+    pub method: String,
+    /// Path below the base URL, without the query string, e.g. `"/responses"`
+    pub path: String,
+    /// JSON body, if the request had one
+    pub body: Option<Value>,
+}
+
+/// A running mock server; it shuts down when dropped
+#[derive(Debug)]
+pub struct MockServerHandle {
+    base_url: String,
+    state: Arc<Mutex<State>>,
+    shutdown: Option<tokio::sync::oneshot::Sender<()>>,
+}
+
+impl MockServerHandle {
+    /// Base URL to pass to [`Client::new_with_base_url`](crate::Client::new_with_base_url)
+    #[must_use]
+    pub fn base_url(&self) -> &str {
+        &self.base_url
+    }
+
+    /// Creates a client that talks to this server with [`MOCK_API_KEY`]
+    ///
+    /// # Errors
+    ///
+    /// Returns a [`CreateError`](crate::CreateError) if the client cannot be built.
+    pub fn client(&self) -> std::result::Result<crate::Client, crate::CreateError> {
+        crate::Client::new_with_base_url(MOCK_API_KEY, &self.base_url)
+    }
+
+    /// Returns the requests received so far, oldest first
+    #[must_use]
+    pub fn requests(&self) -> Vec<RecordedRequest> {
+        lock(&self.state).requests.clone()
+    }
+}
+
+impl Drop for MockServerHandle {
+    fn drop(&mut self) {
+        if let Some(shutdown) = self.shutdown.take() {
+            let _ = shutdown.send(());
+        }
+    }
+}
+
+/// Starts a mock server on a free local port.
+///
+/// Must be called from within a Tokio runtime, which serves the requests.
+///
+/// # Panics
+///
+/// Panics if called outside a Tokio runtime.
+///
+/// # Errors
+///
+/// Returns [`Error::Io`](crate::Error::Io) if no local port can be bound.
+pub fn spawn() -> crate::Result<MockServerHandle> {
+    let listener = std::net::TcpListener::bind(("127.0.0.1", 0))?;
+    let address = listener.local_addr()?;
+    let state = Arc::new(Mutex::new(State::default()));
+    let service_state = Arc::clone(&state);
+    let make_service = make_service_fn(move |_| {
+        let state = Arc::clone(&service_state);
+        async move {
+            Ok::<_, std::convert::Infallible>(service_fn(move |request| {
+                handle(Arc::clone(&state), request)
+            }))
+        }
+    });
+    let server = hyper::Server::from_tcp(listener)
+        .map_err(std::io::Error::other)?
+        .serve(make_service);
+    let (shutdown, stopped) = tokio::sync::oneshot::channel::<()>();
+    tokio::spawn(server.with_graceful_shutdown(async {
+        let _ = stopped.await;
+    }));
+    crate::diag::debug!("Mock OpenAI server listening on {address}");
+
+    Ok(MockServerHandle {
+        base_url: format!("http://{address}/v1"),
+        state,
+        shutdown: Some(shutdown),
+    })
+}
+
+#[derive(Debug, Default)]
+struct State {
+    next_id: u64,
+    requests: Vec<RecordedRequest>,
+    responses: HashMap<String, StoredResponse>,
+    files: BTreeMap<String, StoredFile>,
+    vector_stores: BTreeMap<String, StoredVectorStore>,
+}
+
+#[derive(Debug)]
+struct StoredResponse {
+    response: Value,
+    input_items: Vec<Value>,
+}
+
+#[derive(Debug)]
+struct StoredFile {
+    file: Value,
+    content: Vec<u8>,
+}
+
+#[derive(Debug)]
+struct StoredVectorStore {
+    vector_store: Value,
+    file_ids: Vec<String>,
+}
+
+impl State {
+    /// Returns a new ID with the given prefix; IDs sort in creation order
+    fn id(&mut self, prefix: &str) -> String {
+        self.next_id += 1;
+        format!("{prefix}mock{:06}", self.next_id)
+    }
+}
+
+fn lock(state: &Mutex<State>) -> MutexGuard<'_, State> {
+    // A panicking handler leaves the state usable, so poisoning is ignored
+    state
+        .lock()
+        .unwrap_or_else(std::sync::PoisonError::into_inner)
+}
+
+/// What a route answers with
+enum Reply {
+    Json(StatusCode, Value),
+    Bytes(Vec<u8>),
+    Events(Vec<Value>),
+}
+
+impl Reply {
+    fn ok(body: Value) -> Self {
+        Self::Json(StatusCode::OK, body)
+    }
+
+    fn not_found(message: impl Into<String>) -> Self {
+        Self::Json(
+            StatusCode::NOT_FOUND,
+            json!({"error": {
+                "message": message.into(),
+                "type": "invalid_request_error",
+                "code": "not_found",
+            }}),
+        )
+    }
+
+    fn into_response(self) -> hyper::Response<Body> {
+        let (status, content_type, body) = match self {
+            Self::Json(status, body) => (status, "application/json", Body::from(body.to_string())),
+            Self::Bytes(bytes) => (
+                StatusCode::OK,
+                "application/octet-stream",
+                Body::from(bytes),
+            ),
+            Self::Events(events) => {
+                let body = events.iter().fold(String::new(), |mut body, event| {
+                    let _ = write!(body, "data: {event}\n\n");
+                    body
+                });
+                (StatusCode::OK, "text/event-stream", Body::from(body))
+            }
+        };
+        let mut response = hyper::Response::new(body);
+        *response.status_mut() = status;
+        response.headers_mut().insert(
+            hyper::header::CONTENT_TYPE,
+            hyper::header::HeaderValue::from_static(content_type),
+        );
+        response
+    }
+}
+
+async fn handle(
+    state: Arc<Mutex<State>>,
+    request: hyper::Request<Body>,
+) -> Result<hyper::Response<Body>, std::convert::Infallible> {
+    let method = request.method().clone();
+    let path = request.uri().path();
+    let path = path.strip_prefix("/v1").unwrap_or(path).to_string();
+    let content_type = request
+        .headers()
+        .get(hyper::header::CONTENT_TYPE)
+        .and_then(|value| value.to_str().ok())
+        .unwrap_or_default()
+        .to_string();
+    let body = hyper::body::to_bytes(request.into_body())
+        .await
+        .unwrap_or_default();
+    let json = serde_json::from_slice::<Value>(&body).ok();
+
+    let mut state = lock(&state);
+    state.requests.push(RecordedRequest {
+        method: method.to_string(),
+        path: path.clone(),
+        body: json.clone(),
+    });
+    let segments: Vec<&str> = path.split('/').filter(|s| !s.is_empty()).collect();
+    let json = json.unwrap_or(Value::Null);
+    let reply = match segments.split_first() {
+        Some((&"responses", rest)) => responses_route(&mut state, &method, rest, &json),
+        Some((&"files", rest)) => files_route(&mut state, &method, rest, &content_type, &body),
+        Some((&"vector_stores", rest)) => vector_stores_route(&mut state, &method, rest, &json),
+        _ => None,
+    }
+    .unwrap_or_else(|| {
+        Reply::not_found(format!("The mock server has no route for {method} {path}"))
+    });
+    Ok(reply.into_response())
+}
+
+fn responses_route(
+    state: &mut State,
+    method: &Method,
+    segments: &[&str],
+    request: &Value,
+) -> Option<Reply> {
+    let reply = match (method, segments) {
+        (&Method::POST, []) => create_response(state, request),
+        (&Method::GET, [id]) => match state.responses.get(*id) {
+            Some(stored) => Reply::ok(stored.response.clone()),
+            None => Reply::not_found(format!("No response found with id '{id}'")),
+        },
+        (&Method::DELETE, [id]) => match state.responses.remove(*id) {
+            Some(_) => Reply::ok(json!({"id": id, "object": "response", "deleted": true})),
+            None => Reply::not_found(format!("No response found with id '{id}'")),
+        },
+        (&Method::POST, [id, "cancel"]) => match state.responses.get_mut(*id) {
+            Some(stored) => {
+                stored.response["status"] = json!("cancelled");
+                Reply::ok(stored.response.clone())
+            }
+            None => Reply::not_found(format!("No response found with id '{id}'")),
+        },
+        (&Method::GET, [id, "input_items"]) => match state.responses.get(*id) {
+            Some(stored) => Reply::ok(list(&stored.input_items)),
+            None => Reply::not_found(format!("No response found with id '{id}'")),
+        },
+        _ => return None,
+    };
+    Some(reply)
+}
+
+fn files_route(
+    state: &mut State,
+    method: &Method,
+    segments: &[&str],
+    content_type: &str,
+    body: &[u8],
+) -> Option<Reply> {
+    let reply = match (method, segments) {
+        (&Method::POST, []) => upload_file(state, content_type, body),
+        (&Method::GET, []) => Reply::ok(list(
+            &state
+                .files
+                .values()
+                .map(|stored| stored.file.clone())
+                .collect::<Vec<_>>(),
+        )),
+        (&Method::GET, [id]) => match state.files.get(*id) {
+            Some(stored) => Reply::ok(stored.file.clone()),
+            None => Reply::not_found(format!("No such File object: {id}")),
+        },
+        (&Method::GET, [id, "content"]) => match state.files.get(*id) {
+            Some(stored) => Reply::Bytes(stored.content.clone()),
+            None => Reply::not_found(format!("No such File object: {id}")),
+        },
+        (&Method::DELETE, [id]) => match state.files.remove(*id) {
+            Some(_) => Reply::ok(json!({"id": id, "object": "file", "deleted": true})),
+            None => Reply::not_found(format!("No such File object: {id}")),
+        },
+        _ => return None,
+    };
+    Some(reply)
+}
+
+fn vector_stores_route(
+    state: &mut State,
+    method: &Method,
+    segments: &[&str],
+    request: &Value,
+) -> Option<Reply> {
+    let (id, rest) = match segments.split_first() {
+        None => {
+            return Some(match *method {
+                Method::POST => create_vector_store(state, request),
+                Method::GET => Reply::ok(list(
+                    &state
+                        .vector_stores
+                        .values()
+                        .map(StoredVectorStore::to_json)
+                        .collect::<Vec<_>>(),
+                )),
+                _ => return None,
+            })
+        }
+        Some((id, rest)) => (*id, rest),
+    };
+    if !state.vector_stores.contains_key(id) {
+        return Some(Reply::not_found(format!(
+            "No vector store found with id '{id}'"
+        )));
+    }
+    let reply = match (method, rest) {
+        (&Method::GET, []) => Reply::ok(state.vector_stores[id].to_json()),
+        (&Method::DELETE, []) => {
+            state.vector_stores.remove(id);
+            Reply::ok(json!({"id": id, "object": "vector_store.deleted", "deleted": true}))
+        }
+        (&Method::POST, ["files"]) => {
+            let file_id = request["file_id"].as_str().unwrap_or_default().to_string();
+            let store = state.vector_stores.get_mut(id)?;
+            if !store.file_ids.contains(&file_id) {
+                store.file_ids.push(file_id.clone());
+            }
+            Reply::ok(vector_store_file(id, &file_id))
+        }
+        (&Method::GET, ["files"]) => Reply::ok(list(
+            &state.vector_stores[id]
+                .file_ids
+                .iter()
+                .map(|file_id| vector_store_file(id, file_id))
+                .collect::<Vec<_>>(),
+        )),
+        (&Method::GET, ["files", file_id]) => {
+            if state.vector_stores[id]
+                .file_ids
+                .iter()
+                .any(|existing| existing == file_id)
+            {
+                Reply::ok(vector_store_file(id, file_id))
+            } else {
+                Reply::not_found(format!("No file found with id '{file_id}'"))
+            }
+        }
+        (&Method::DELETE, ["files", file_id]) => {
+            let store = state.vector_stores.get_mut(id)?;
+            let before = store.file_ids.len();
+            store.file_ids.retain(|existing| existing != file_id);
+            Reply::ok(json!({
+                "id": file_id,
+                "object": "vector_store.file.deleted",
+                "deleted": store.file_ids.len() < before,
+            }))
+        }
+        (&Method::POST, ["search"]) => search_vector_store(state, id, request),
+        _ => return None,
+    };
+    Some(reply)
+}
+
+/// Wraps items in the API's list envelope
+fn list(data: &[Value]) -> Value {
+    let id = |item: Option<&Value>| item.and_then(|item| item.get("id")).cloned();
+    json!({
+        "object": "list",
+        "first_id": id(data.first()),
+        "last_id": id(data.last()),
+        "has_more": false,
+        "data": data,
+    })
+}
+
+/// Returns the text of the last user turn, or `""`
+fn input_text(request: &Value) -> String {
+    match &request["input"] {
+        Value::String(text) => text.clone(),
+        Value::Array(items) => items
+            .iter()
+            .rev()
+            .find(|item| item["role"] == "user")
+            .map(|item| match &item["content"] {
+                Value::String(text) => text.clone(),
+                Value::Array(parts) => parts
+                    .iter()
+                    .filter_map(|part| part["text"].as_str())
+                    .collect::<Vec<_>>()
+                    .join(" "),
+                _ => String::new(),
+            })
+            .unwrap_or_default(),
+        _ => String::new(),
+    }
+}
+
+/// The request's input as stored items, with IDs added where missing
+fn input_items(state: &mut State, request: &Value) -> Vec<Value> {
+    let mut items = match &request["input"] {
+        Value::String(text) => vec![json!({
+            "type": "message",
+            "role": "user",
+            "content": [{"type": "input_text", "text": text}],
+        })],
+        Value::Array(items) => items.clone(),
+        _ => Vec::new(),
+    };
+    for item in &mut items {
+        if item.get("id").is_none() {
+            if let Some(object) = item.as_object_mut() {
+                let prefix = if object.get("type") == Some(&json!("message")) {
+                    "msg_"
+                } else {
+                    "item_"
+                };
+                object.insert("id".to_string(), json!(state.id(prefix)));
+            }
+        }
+        if item.get("type").is_none() && item.get("role").is_some() {
+            item["type"] = json!("message");
+        }
+    }
+    items
+}
+
+fn create_response(state: &mut State, request: &Value) -> Reply {
+    let model = request["model"]
+        .as_str()
+        .unwrap_or("gpt-4o-mini")
+        .to_string();
+    let tools = request["tools"].as_array().cloned().unwrap_or_default();
+    let answered_tools = request["input"].as_array().is_some_and(|items| {
+        items
+            .iter()
+            .any(|item| item["type"] == "function_call_output")
+    });
+    let text = input_text(request);
+
+    let mut output = Vec::new();
+    for tool in &tools {
+        match tool["type"].as_str().unwrap_or_default() {
+            "function" if !answered_tools => output.push(json!({
+                "type": "function_call",
+                "id": state.id("fc_"),
+                "call_id": state.id("call_"),
+                "name": tool["name"],
+                "arguments": "{}",
+                "status": "completed",
+            })),
+            "web_search" | "web_search_preview" => output.push(json!({
+                "type": "web_search_call",
+                "id": state.id("ws_"),
+                "status": "completed",
+            })),
+            "file_search" => output.push(json!({
+                "type": "file_search_call",
+                "id": state.id("fs_"),
+                "status": "completed",
+            })),
+            "image_generation" => output.push(json!({
+                "type": "image_generation_call",
+                "id": state.id("ig_"),
+                "status": "completed",
+                "result": PIXEL_PNG_B64,
+                "output_format": "png",
+                "size": "1024x1024",
+                "revised_prompt": text,
+            })),
+            _ => {}
+        }
+    }
+    if !output.iter().any(|item| item["type"] == "function_call") {
+        output.push(json!({
+            "type": "message",
+            "id": state.id("msg_"),
+            "role": "assistant",
+            "status": "completed",
+            "content": [{"type": "output_text", "text": answer(&model, request, &text), "annotations": []}],
+        }));
+    }
+
+    let id = state.id("resp_");
+    let mut response = json!({
+        "id": id,
+        "object": "response",
+        "created_at": CREATED_AT,
+        "model": model,
+        "status": "completed",
+        "output": output,
+        "usage": usage(&text, &output),
+    });
+    for field in ECHOED_FIELDS {
+        if let Some(value) = request.get(*field).filter(|value| !value.is_null()) {
+            response[*field] = value.clone();
+        }
+    }
+    let input_items = input_items(state, request);
+    state.responses.insert(
+        id,
+        StoredResponse {
+            response: response.clone(),
+            input_items,
+        },
+    );
+
+    if request["stream"] == true {
+        Reply::Events(stream_events(&response))
+    } else {
+        Reply::ok(response)
+    }
+}
+
+/// The canned answer, echoing the model, instructions and input
+fn answer(model: &str, request: &Value, text: &str) -> String {
+    let mut answer = format!("Mock response from {model}.");
+    if let Some(instructions) = request["instructions"].as_str() {
+        let _ = write!(answer, " Instructions: {instructions}.");
+    }
+    if !text.is_empty() {
+        let _ = write!(answer, " You said: {text}");
+    }
+    answer
+}
+
+/// Counts words as tokens
+fn usage(input: &str, output: &[Value]) -> Value {
+    let words = |text: &str| text.split_whitespace().count();
+    let input_tokens = words(input);
+    let output_tokens: usize = output
+        .iter()
+        .flat_map(|item| item["content"].as_array().into_iter().flatten())
+        .filter_map(|part| part["text"].as_str())
+        .map(words)
+        .sum();
+    json!({
+        "input_tokens": input_tokens,
+        "output_tokens": output_tokens,
+        "total_tokens": input_tokens + output_tokens,
+    })
+}
+
+/// Server-sent events that build up `response`, one word per text delta
+fn stream_events(response: &Value) -> Vec<Value> {
+    let mut in_progress = response.clone();
+    in_progress["status"] = json!("in_progress");
+    in_progress["output"] = json!([]);
+    in_progress["usage"] = Value::Null;
+    let mut events = vec![json!({"type": "response.created", "response": in_progress})];
+
+    let output = response["output"].as_array().cloned().unwrap_or_default();
+    for (index, item) in output.iter().enumerate() {
+        let item_id = &item["id"];
+        if item["type"] == "message" {
+            let mut added = item.clone();
+            added["content"] = json!([]);
+            added["status"] = json!("in_progress");
+            events.push(
+                json!({"type": "response.output_item.added", "output_index": index, "item": added}),
+            );
+            let text = item["content"][0]["text"].as_str().unwrap_or_default();
+            for word in text.split_inclusive(' ') {
+                events.push(json!({
+                    "type": "response.output_text.delta",
+                    "item_id": item_id,
+                    "output_index": index,
+                    "content_index": 0,
+                    "delta": word,
+                }));
+            }
+            events.push(json!({
+                "type": "response.output_text.done",
+                "item_id": item_id,
+                "output_index": index,
+                "content_index": 0,
+                "text": text,
+            }));
+        } else {
+            events.push(
+                json!({"type": "response.output_item.added", "output_index": index, "item": item}),
+            );
+            if item["type"] == "function_call" {
+                events.push(json!({
+                    "type": "response.function_call_arguments.done",
+                    "item_id": item_id,
+                    "output_index": index,
+                    "arguments": item["arguments"],
+                }));
+            }
+        }
+        events.push(
+            json!({"type": "response.output_item.done", "output_index": index, "item": item}),
+        );
+    }
+    events.push(json!({"type": "response.completed", "response": response}));
+    events
+}
+
+fn upload_file(state: &mut State, content_type: &str, body: &[u8]) -> Reply {
+    let mut purpose = String::from("assistants");
+    let mut filename = String::from("upload");
+    let mut content = Vec::new();
+    for part in multipart_parts(content_type, body) {
+        match part.name.as_str() {
+            "purpose" => purpose = String::from_utf8_lossy(part.data).into_owned(),
+            "file" => {
+                if let Some(name) = part.filename {
+                    filename = name;
+                }
+                content = part.data.to_vec();
+            }
+            _ => {}
+        }
+    }
+    let id = state.id("file-");
+    let file = json!({
+        "id": id,
+        "object": "file",
+        "filename": filename,
+        "purpose": purpose,
+        "bytes": content.len(),
+        "created_at": CREATED_AT,
+        "status": "processed",
+    });
+    state.files.insert(
+        id,
+        StoredFile {
+            file: file.clone(),
+            content,
+        },
+    );
+    Reply::ok(file)
+}
+
+struct MultipartPart<'a> {
+    name: String,
+    filename: Option<String>,
+    data: &'a [u8],
+}
+
+/// Splits a `multipart/form-data` body into its parts
+fn multipart_parts<'a>(content_type: &str, body: &'a [u8]) -> Vec<MultipartPart<'a>> {
+    let Some(boundary) = content_type
+        .split("boundary=")
+        .nth(1)
+        .map(|boundary| boundary.trim_matches('"'))
+    else {
+        return Vec::new();
+    };
+    let delimiter = format!("--{boundary}");
+    let mut parts = Vec::new();
+    let mut rest = body;
+    while let Some(start) = find(rest, delimiter.as_bytes()) {
+        rest = &rest[start + delimiter.len()..];
+        if rest.starts_with(b"--") {
+            break;
+        }
+        let end = find(rest, delimiter.as_bytes()).unwrap_or(rest.len());
+        let part = rest[..end].strip_prefix(b"\r\n").unwrap_or(&rest[..end]);
+        let part = part.strip_suffix(b"\r\n").unwrap_or(part);
+        let Some(header_end) = find(part, b"\r\n\r\n") else {
+            continue;
+        };
+        let headers = String::from_utf8_lossy(&part[..header_end]);
+        let param = |key: &str| {
+            let start = headers.find(key)? + key.len();
+            let value = &headers[start..];
+            Some(value[..value.find('"')?].to_string())
+        };
+        parts.push(MultipartPart {
+            name: param("; name=\"").unwrap_or_default(),
+            filename: param("filename=\""),
+            data: &part[header_end + 4..],
+        });
+    }
+    parts
+}
+
+fn find(haystack: &[u8], needle: &[u8]) -> Option<usize> {
+    haystack
+        .windows(needle.len())
+        .position(|window| window == needle)
+}
+
+impl StoredVectorStore {
+    fn to_json(&self) -> Value {
+        let mut vector_store = self.vector_store.clone();
+        vector_store["file_ids"] = json!(self.file_ids);
+        vector_store["file_counts"] = json!({
+            "completed": self.file_ids.len(),
+            "total": self.file_ids.len(),
+        });
+        vector_store
+    }
+}
+
+fn create_vector_store(state: &mut State, request: &Value) -> Reply {
+    let id = state.id("vs_");
+    let file_ids: Vec<String> = request["file_ids"]
+        .as_array()
+        .into_iter()
+        .flatten()
+        .filter_map(|id| id.as_str().map(ToString::to_string))
+        .collect();
+    let store = StoredVectorStore {
+        vector_store: json!({
+            "id": id,
+            "object": "vector_store",
+            "name": request["name"].as_str().unwrap_or_default(),
+            "created_at": CREATED_AT,
+            "status": "completed",
+        }),
+        file_ids,
+    };
+    let vector_store = store.to_json();
+    state.vector_stores.insert(id, store);
+    Reply::ok(vector_store)
+}
+
+fn vector_store_file(vector_store_id: &str, file_id: &str) -> Value {
+    json!({
+        "id": file_id,
+        "object": "vector_store.file",
+        "vector_store_id": vector_store_id,
+        "created_at": CREATED_AT,
+        "status": "completed",
+    })
+}
+
+fn search_vector_store(state: &State, id: &str, request: &Value) -> Reply {
+    let limit = request["max_num_results"]
+        .as_u64()
+        .and_then(|limit| usize::try_from(limit).ok())
+        .unwrap_or(10);
+    let data: Vec<Value> = state.vector_stores[id]
+        .file_ids
+        .iter()
+        .filter_map(|file_id| Some((file_id, state.files.get(file_id)?)))
+        .take(limit)
+        .map(|(file_id, stored)| {
+            let text: String = String::from_utf8_lossy(&stored.content)
+                .chars()
+                .take(500)
+                .collect();
+            json!({
+                "file_id": file_id,
+                "filename": stored.file["filename"],
+                "score": 1.0,
+                "attributes": {},
+                "content": [{"type": "text", "text": text}],
+            })
+        })
+        .collect();
+    Reply::ok(json!({
+        "object": "vector_store.search_results.page",
+        "search_query": request["query"],
+        "data": data,
+        "has_more": false,
+        "next_page": null,
+    }))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::files::FilePurpose;
+    use crate::types::Tool;
+    use crate::vector_stores::{
+        AddFileToVectorStoreRequest, CreateVectorStoreRequest, SearchVectorStoreRequest,
+    };
+    use crate::Request;
+
+    #[tokio::test]
+    async fn responses_echo_the_request_and_run_tool_rounds() {
+        let server = spawn().unwrap();
+        let client = server.client().unwrap();
+
+        let request = Request::builder()
+            .model("gpt-4o")
+            .instructions("Be brief")
+            .input("Hello there")
+            .temperature(0.3)
+            .build();
+        let response = client.responses.create(request).await.unwrap();
+        assert_eq!(
+            response.output_text(),
+            "Mock response from gpt-4o. Instructions: Be brief. You said: Hello there"
+        );
+        assert_eq!(response.temperature, Some(0.3));
+        assert_eq!(
+            response.usage.as_ref().map(|usage| usage.input_tokens),
+            Some(2)
+        );
+
+        let calculator = Tool::function("calculate", "Arithmetic", json!({"type": "object"}));
+        let first = client
+            .responses
+            .create(
+                Request::builder()
+                    .model("gpt-4o-mini")
+                    .input("What is 2 + 2?")
+                    .tools(vec![calculator.clone()])
+                    .build(),
+            )
+            .await
+            .unwrap();
+        let calls = first.tool_calls();
+        assert_eq!(calls.len(), 1);
+        assert_eq!(
+            (calls[0].name.as_str(), calls[0].arguments.as_str()),
+            ("calculate", "{}")
+        );
+
+        let second = client
+            .responses
+            .create(
+                Request::builder()
+                    .model("gpt-4o-mini")
+                    .with_function_outputs(
+                        first.id(),
+                        vec![(calls[0].call_id.clone(), "4".to_string())],
+                    )
+                    .tools(vec![calculator])
+                    .build(),
+            )
+            .await
+            .unwrap();
+        assert!(second.tool_calls().is_empty());
+        assert_eq!(second.previous_response_id.as_deref(), Some(first.id()));
+
+        let retrieved = client.responses.retrieve(second.id()).await.unwrap();
+        assert_eq!(retrieved.id, second.id);
+        let paths: Vec<_> = server
+            .requests()
+            .into_iter()
+            .map(|request| format!("{} {}", request.method, request.path))
+            .collect();
+        assert_eq!(
+            paths,
+            [
+                "POST /responses",
+                "POST /responses",
+                "POST /responses",
+                format!("GET /responses/{}", second.id).as_str(),
+            ]
+        );
+    }
+
+    #[cfg(feature = "stream")]
+    #[tokio::test]
+    async fn streams_the_canned_text_word_by_word() {
+        use crate::types::StreamEvent;
+        use futures::StreamExt;
+
+        let server = spawn().unwrap();
+        let client = server.client().unwrap();
+        let mut stream = client
+            .responses
+            .stream(Request::builder().model("gpt-4o").input("Hi").build());
+
+        let mut text = String::new();
+        let mut deltas = 0;
+        while let Some(event) = stream.next().await {
+            if let StreamEvent::TextDelta { content, .. } = event.unwrap() {
+                deltas += 1;
+                text.push_str(&content);
+            }
+        }
+        assert_eq!(text, "Mock response from gpt-4o. You said: Hi");
+        assert_eq!(deltas, 7);
+    }
+
+    #[tokio::test]
+    async fn files_and_vector_stores_round_trip() {
+        let server = spawn().unwrap();
+        let client = server.client().unwrap();
+
+        let file = client
+            .files
+            .create(crate::files::CreateFileRequest {
+                purpose: FilePurpose::Assistants.as_str().to_string(),
+                file: b"Keep functions small".to_vec(),
+                filename: "guide.md".to_string(),
+                mime_type: Some("text/markdown".to_string()),
+                expires_after: None,
+            })
+            .await
+            .unwrap();
+        assert_eq!((file.filename.as_str(), file.bytes), ("guide.md", 20));
+        assert_eq!(
+            client.files.download(&file.id).await.unwrap(),
+            b"Keep functions small"
+        );
+        assert_eq!(client.files.list(None).await.unwrap().data.len(), 1);
+
+        let store = client
+            .vector_stores
+            .create(CreateVectorStoreRequest {
+                name: "Guides".to_string(),
+                file_ids: vec![],
+            })
+            .await
+            .unwrap();
+        client
+            .vector_stores
+            .add_file(
+                &store.id,
+                AddFileToVectorStoreRequest {
+                    file_id: file.id.clone(),
+                    attributes: None,
+                    chunking_strategy: None,
+                },
+            )
+            .await
+            .unwrap();
+        let attached = client
+            .vector_stores
+            .get_file(&store.id, &file.id)
+            .await
+            .unwrap();
+        assert_eq!(
+            attached.status,
+            crate::vector_stores::VectorStoreFileStatus::Completed
+        );
+        let results = client
+            .vector_stores
+            .search(
+                &store.id,
+                SearchVectorStoreRequest::new("functions").with_max_num_results(3),
+            )
+            .await
+            .unwrap();
+        assert_eq!(results.data[0].filename, "guide.md");
+        assert_eq!(results.data[0].content[0].text, "Keep functions small");
+
+        client.vector_stores.delete(&store.id).await.unwrap();
+        client.files.delete(&file.id).await.unwrap();
+        assert!(client.vector_stores.get(&store.id).await.is_err());
+        assert!(client.files.get(&file.id).await.is_err());
+    }
+}
@@ -0,0 +1,319 @@
+//! Opt-in client-side cache for identical non-streaming creates.
+
+use crate::runtime::Stopwatch;
+use lru::LruCache;
+use sha2::{Digest, Sha256};
+use std::num::NonZeroUsize;
+use std::ops::Deref;
+use std::sync::{Arc, Mutex};
+use std::time::Duration;
+
+/// Request fields left out of the cache key by default
+pub const DEFAULT_CACHE_EXCLUDED_FIELDS: &[&str] = &["user", "metadata", "previous_response_id"];
+
+/// Tool types whose calls may have side effects, so their requests are not cached by default
+const SIDE_EFFECT_TOOL_TYPES: &[&str] = &["mcp", "function", "custom"];
+
+/// Settings for the response cache enabled by [`Client::with_response_cache`](crate::Client::with_response_cache)
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct CacheConfig {
+    /// How long a cached response stays fresh
+    pub ttl: Duration,
+
+    /// Maximum number of cached responses; the least recently used is evicted first
+    pub max_entries: usize,
+
+    /// Top-level request fields ignored when comparing requests
+    pub excluded_fields: Vec<String>,
+
+    /// Also cache requests that offer MCP, function or custom tools
+    pub allow_side_effect_tools: bool,
+}
+
+impl Default for CacheConfig {
+    fn default() -> Self {
+        Self {
+            ttl: Duration::from_secs(60),
+            max_entries: 256,
+            excluded_fields: DEFAULT_CACHE_EXCLUDED_FIELDS
+                .iter()
+                .map(ToString::to_string)
+                .collect(),
+            allow_side_effect_tools: false,
+        }
+    }
+}
+
+impl CacheConfig {
+    /// Creates a config with the given freshness window and size
+    #[must_use]
+    pub fn new(ttl: Duration, max_entries: usize) -> Self {
+        Self {
+            ttl,
+            max_entries,
+            ..Self::default()
+        }
+    }
+
+    /// Sets the top-level request fields ignored when comparing requests
+    #[must_use]
+    pub fn with_excluded_fields<I, S>(mut self, fields: I) -> Self
+    where
+        I: IntoIterator<Item = S>,
+        S: Into<String>,
+    {
+        self.excluded_fields = fields.into_iter().map(Into::into).collect();
+        self
+    }
+
+    /// Sets whether requests offering MCP, function or custom tools may be cached
+    #[must_use]
+    pub fn with_side_effect_tools(mut self, allow: bool) -> Self {
+        self.allow_side_effect_tools = allow;
+        self
+    }
+}
+
+/// A response that either came from the cache or from the API
+#[derive(Debug, Clone)]
+pub enum CachedOr<T> {
+    /// Served from the client-side cache without an API call
+    Cached(T),
+    /// Fetched from the API
+    Fresh(T),
+}
+
+impl<T> CachedOr<T> {
+    /// Returns true if the value was served from the cache
+    #[must_use]
+    pub fn is_cached(&self) -> bool {
+        matches!(self, Self::Cached(_))
+    }
+
+    /// Returns the wrapped value
+    #[must_use]
+    pub fn into_inner(self) -> T {
+        match self {
+            Self::Cached(value) | Self::Fresh(value) => value,
+        }
+    }
+}
+
+impl<T> Deref for CachedOr<T> {
+    type Target = T;
+
+    fn deref(&self) -> &T {
+        match self {
+            Self::Cached(value) | Self::Fresh(value) => value,
+        }
+    }
+}
+
+/// LRU of responses keyed by a hash of the request, shared between clones
+#[derive(Clone)]
+pub(crate) struct ResponseCache {
+    config: Arc<CacheConfig>,
+    entries: Arc<Mutex<LruCache<String, (Stopwatch, crate::Response)>>>,
+}
+
+impl std::fmt::Debug for ResponseCache {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("ResponseCache")
+            .field("config", &self.config)
+            .finish_non_exhaustive()
+    }
+}
+
+impl ResponseCache {
+    pub(crate) fn new(config: CacheConfig) -> Self {
+        let capacity = NonZeroUsize::new(config.max_entries).unwrap_or(NonZeroUsize::MIN);
+        Self {
+            config: Arc::new(config),
+            entries: Arc::new(Mutex::new(LruCache::new(capacity))),
+        }
+    }
+
+    /// Returns the cache key for a request, or `None` if the request must not be cached
+    pub(crate) fn key(&self, request: &crate::Request) -> Option<String> {
+        if request.stream == Some(true)
+            || request.store == Some(true)
+            || request.background == Some(true)
+        {
+            return None;
+        }
+        if !self.config.allow_side_effect_tools
+            && request
+                .tools
+                .iter()
+                .flatten()
+                .any(|tool| SIDE_EFFECT_TOOL_TYPES.contains(&tool.tool_type.as_str()))
+        {
+            return None;
+        }
+
+        let mut json = serde_json::to_value(request).ok()?;
+        let fields = json.as_object_mut()?;
+        for field in &self.config.excluded_fields {
+            fields.remove(field);
+        }
+        let canonical = crate::types::canonical::to_canonical_string(&json);
+        Some(format!("{:x}", Sha256::digest(canonical.as_bytes())))
+    }
+
+    /// Returns a fresh cached response, dropping it if it has expired
+    pub(crate) fn get(&self, key: &str) -> Option<crate::Response> {
+        let mut entries = self.entries.lock().ok()?;
+        match entries.get(key) {
+            Some((stored, response)) if stored.elapsed() < self.config.ttl => {
+                Some(response.clone())
+            }
+            Some(_) => {
+                entries.pop(key);
+                None
+            }
+            None => None,
+        }
+    }
+
+    pub(crate) fn insert(&self, key: String, response: crate::Response) {
+        if let Ok(mut entries) = self.entries.lock() {
+            entries.put(key, (Stopwatch::start(), response));
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::responses::Responses;
+
+    const BODY: &str = r#"{"id":"resp_1","object":"response","created_at":1700000000,"model":"gpt-4o","status":"completed","output":[]}"#;
+
+    async fn server_expecting(hits: usize) -> (mockito::ServerGuard, mockito::Mock) {
+        let mut server = mockito::Server::new_async().await;
+        let mock = server
+            .mock("POST", "/responses")
+            .with_status(200)
+            .with_body(BODY)
+            .expect(hits)
+            .create_async()
+            .await;
+        (server, mock)
+    }
+
+    fn request(user: &str) -> crate::Request {
+        crate::Request::builder()
+            .model("gpt-4o")
+            .input("What is our refund policy?")
+            .user(user)
+            .build()
+    }
+
+    #[tokio::test]
+    async fn identical_requests_hit_the_cache() {
+        let (server, mock) = server_expecting(2).await;
+        let responses = Responses::new(reqwest::Client::new(), server.url())
+            .with_response_cache(CacheConfig::default());
+
+        let first = responses.create_cached(request("alice")).await.unwrap();
+        assert!(!first.is_cached());
+        // `user` is excluded from the key by default
+        let second = responses.create_cached(request("bob")).await.unwrap();
+        assert!(second.is_cached());
+        assert_eq!(second.id, "resp_1");
+        // Clones share the cache, and plain `create` uses it too
+        responses.clone().create(request("carol")).await.unwrap();
+
+        let different = crate::Request::builder()
+            .model("gpt-4o")
+            .input("What is our shipping policy?")
+            .build();
+        assert!(!responses
+            .create_cached(different)
+            .await
+            .unwrap()
+            .is_cached());
+        mock.assert_async().await;
+    }
+
+    #[tokio::test]
+    async fn expired_and_uncacheable_requests_miss() {
+        let (server, mock) = server_expecting(5).await;
+        let expired = Responses::new(reqwest::Client::new(), server.url())
+            .with_response_cache(CacheConfig::new(Duration::ZERO, 8));
+        assert!(!expired
+            .create_cached(request("a"))
+            .await
+            .unwrap()
+            .is_cached());
+        assert!(!expired
+            .create_cached(request("a"))
+            .await
+            .unwrap()
+            .is_cached());
+
+        let responses = Responses::new(reqwest::Client::new(), server.url())
+            .with_response_cache(CacheConfig::default().with_excluded_fields(["metadata"]));
+        let stored = crate::Request {
+            store: Some(true),
+            ..request("a")
+        };
+        let with_function = crate::Request::builder()
+            .model("gpt-4o")
+            .input("hi")
+            .tools(vec![crate::types::Tool::function(
+                "refund",
+                "Issue a refund",
+                serde_json::json!({"type": "object"}),
+            )])
+            .build();
+        for request in [stored, with_function] {
+            assert!(responses.create_cached(request.clone()).await.is_ok());
+            assert!(responses.cache.as_ref().unwrap().key(&request).is_none());
+        }
+        // `user` now counts towards the key
+        responses.create_cached(request("a")).await.unwrap();
+        let cache = responses.cache.as_ref().unwrap();
+        assert_ne!(cache.key(&request("a")), cache.key(&request("b")));
+        mock.assert_async().await;
+    }
+
+    #[cfg(feature = "stream")]
+    #[tokio::test]
+    async fn streaming_bypasses_the_cache() {
+        use futures::StreamExt;
+
+        let mut server = mockito::Server::new_async().await;
+        let create = server
+            .mock("POST", "/responses")
+            .match_body(mockito::Matcher::PartialJson(
+                serde_json::json!({"stream": true}),
+            ))
+            .with_status(200)
+            .with_header("content-type", "text/event-stream")
+            .with_body("data: [DONE]\n\n")
+            .expect(1)
+            .create_async()
+            .await;
+        let plain = server
+            .mock("POST", "/responses")
+            .with_status(200)
+            .with_body(BODY)
+            .expect(1)
+            .create_async()
+            .await;
+
+        let responses = Responses::new(reqwest::Client::new(), server.url())
+            .with_response_cache(CacheConfig::default());
+        responses.create_cached(request("a")).await.unwrap();
+        let mut stream = responses.stream(request("a"));
+        while let Some(event) = stream.next().await {
+            if matches!(event, Ok(crate::types::StreamEvent::Done)) {
+                break;
+            }
+        }
+
+        create.assert_async().await;
+        plain.assert_async().await;
+    }
+}
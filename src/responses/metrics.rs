@@ -0,0 +1,238 @@
+//! Opt-in latency and throughput metrics for streamed responses.
+//!
+//! [`Responses::stream_with_metrics`](super::Responses::stream_with_metrics) returns the
+//! usual event stream together with a [`MetricsHandle`]. The handle fills in as events
+//! pass through, and is complete once the stream has ended.
+
+use crate::runtime::Stopwatch;
+use crate::types::{ResponseStream, StreamEvent};
+use std::collections::HashMap;
+use std::sync::{Arc, Mutex};
+use std::time::Duration;
+
+/// Rough number of characters per token, for [`StreamMetrics::approx_tokens_per_second`]
+const CHARS_PER_TOKEN: f64 = 4.0;
+
+/// Timings and counts for one streamed response
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct StreamMetrics {
+    /// Time from sending the request to the first output delta (text, tool call
+    /// arguments or MCP call arguments); `None` if no delta arrived
+    pub ttft: Option<Duration>,
+
+    /// Time from sending the request to the terminal event, the first error or the end
+    /// of the stream; `None` while the stream is still running
+    pub total_duration: Option<Duration>,
+
+    /// Number of `TextDelta` events
+    pub text_delta_count: u64,
+
+    /// Characters of text and argument deltas received
+    pub approx_chars: u64,
+
+    /// Number of events of each [`StreamEvent::kind`], e.g. `"text_delta"`
+    pub events_by_type: HashMap<String, u64>,
+}
+
+impl StreamMetrics {
+    /// Approximate output speed between the first delta and the end of the stream,
+    /// counting four characters per token.
+    ///
+    /// Returns `None` until the stream has ended, or if no time passed after the first
+    /// delta.
+    #[must_use]
+    pub fn approx_tokens_per_second(&self) -> Option<f64> {
+        let generating = self.total_duration?.checked_sub(self.ttft?)?;
+        if generating.is_zero() {
+            return None;
+        }
+        #[allow(clippy::cast_precision_loss)]
+        let tokens = self.approx_chars as f64 / CHARS_PER_TOKEN;
+        Some(tokens / generating.as_secs_f64())
+    }
+
+    fn record(&mut self, event: &StreamEvent, elapsed: Duration) {
+        *self
+            .events_by_type
+            .entry(event.kind().to_string())
+            .or_default() += 1;
+
+        let delta = match event {
+            StreamEvent::TextDelta { content, .. } => {
+                self.text_delta_count += 1;
+                Some(content)
+            }
+            StreamEvent::ToolCallDelta { content, .. } => Some(content),
+            StreamEvent::McpCallArgumentsDelta { delta, .. } => Some(delta),
+            _ => None,
+        };
+        if let Some(delta) = delta {
+            self.ttft.get_or_insert(elapsed);
+            self.approx_chars += delta.chars().count() as u64;
+        }
+        if event.is_terminal() {
+            self.total_duration = Some(elapsed);
+        }
+    }
+}
+
+/// Shared view of a stream's [`StreamMetrics`], updated as the stream is polled
+#[derive(Debug, Clone, Default)]
+pub struct MetricsHandle {
+    inner: Arc<Mutex<StreamMetrics>>,
+}
+
+impl MetricsHandle {
+    /// Returns the metrics collected so far
+    #[must_use]
+    pub fn snapshot(&self) -> StreamMetrics {
+        self.lock().clone()
+    }
+
+    /// Returns true once the stream has ended and the metrics are final
+    #[must_use]
+    pub fn is_finished(&self) -> bool {
+        self.lock().total_duration.is_some()
+    }
+
+    /// Time to the first output delta, if one has arrived
+    #[must_use]
+    pub fn ttft(&self) -> Option<Duration> {
+        self.lock().ttft
+    }
+
+    /// Total stream duration, once the stream has ended
+    #[must_use]
+    pub fn total_duration(&self) -> Option<Duration> {
+        self.lock().total_duration
+    }
+
+    fn lock(&self) -> std::sync::MutexGuard<'_, StreamMetrics> {
+        // The metrics stay consistent even if a holder panicked
+        self.inner
+            .lock()
+            .unwrap_or_else(std::sync::PoisonError::into_inner)
+    }
+}
+
+/// Wraps `stream` so every event is recorded in `handle`.
+///
+/// The clock starts on the first poll, which is when the request is sent.
+pub(crate) fn instrument(stream: ResponseStream, handle: MetricsHandle) -> ResponseStream {
+    use futures::StreamExt;
+
+    Box::pin(futures::stream::unfold(
+        (stream, None::<Stopwatch>, handle),
+        |(mut stream, stopwatch, handle)| async move {
+            let stopwatch = stopwatch.unwrap_or_else(Stopwatch::start);
+            let item = stream.next().await;
+            let elapsed = stopwatch.elapsed();
+            {
+                let mut metrics = handle.lock();
+                match &item {
+                    Some(Ok(event)) => metrics.record(event, elapsed),
+                    Some(Err(_)) | None => {
+                        metrics.total_duration.get_or_insert(elapsed);
+                    }
+                }
+            }
+            let item = item?;
+            Some((item, (stream, Some(stopwatch), handle)))
+        },
+    ))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use futures::StreamExt;
+
+    fn text(content: &str) -> StreamEvent {
+        StreamEvent::TextDelta {
+            content: content.to_string(),
+            index: 0,
+            content_index: 0,
+        }
+    }
+
+    /// Yields each event after its delay, like a server streaming at that pace
+    fn paced(events: Vec<(u64, StreamEvent)>) -> ResponseStream {
+        Box::pin(
+            futures::stream::iter(events).then(|(delay_ms, event)| async move {
+                tokio::time::sleep(Duration::from_millis(delay_ms)).await;
+                Ok(event)
+            }),
+        )
+    }
+
+    #[tokio::test(start_paused = true)]
+    async fn metrics_time_first_delta_and_terminal_event() {
+        let handle = MetricsHandle::default();
+        let stream = instrument(
+            paced(vec![
+                (
+                    100,
+                    StreamEvent::ResponseCreated {
+                        id: "resp_1".to_string(),
+                    },
+                ),
+                (250, text("Hello")),
+                (50, StreamEvent::Chunk),
+                (50, text(", wörld")),
+                (
+                    100,
+                    StreamEvent::ToolCallDelta {
+                        id: "call_1".to_string(),
+                        call_id: None,
+                        name: None,
+                        content: "{}".to_string(),
+                        index: 1,
+                    },
+                ),
+                (50, StreamEvent::Done),
+            ]),
+            handle.clone(),
+        );
+        tokio::pin!(stream);
+
+        assert!(stream.next().await.is_some());
+        assert_eq!(handle.ttft(), None);
+        assert!(!handle.is_finished());
+        while stream.next().await.is_some() {}
+
+        let metrics = handle.snapshot();
+        assert!(handle.is_finished());
+        assert_eq!(metrics.ttft, Some(Duration::from_millis(350)));
+        assert_eq!(metrics.total_duration, Some(Duration::from_millis(600)));
+        assert_eq!(metrics.text_delta_count, 2);
+        assert_eq!(metrics.approx_chars, 14);
+        assert_eq!(metrics.events_by_type["text_delta"], 2);
+        assert_eq!(metrics.events_by_type["chunk"], 1);
+        assert_eq!(metrics.events_by_type["response_created"], 1);
+        assert_eq!(metrics.events_by_type["done"], 1);
+        // 14 chars ≈ 3.5 tokens over the 250ms after the first delta
+        let rate = metrics.approx_tokens_per_second().unwrap();
+        assert!((rate - 14.0).abs() < 1e-9, "{rate}");
+    }
+
+    #[tokio::test(start_paused = true)]
+    async fn metrics_finish_when_stream_ends_without_output() {
+        let handle = MetricsHandle::default();
+        let stream = instrument(
+            paced(vec![(
+                400,
+                StreamEvent::Incomplete {
+                    reason: Some("max_output_tokens".to_string()),
+                },
+            )]),
+            handle.clone(),
+        );
+        assert_eq!(stream.count().await, 1);
+
+        let metrics = handle.snapshot();
+        assert_eq!(metrics.ttft, None);
+        assert_eq!(metrics.total_duration, Some(Duration::from_millis(400)));
+        assert_eq!(metrics.approx_tokens_per_second(), None);
+        assert_eq!(metrics.events_by_type["incomplete"], 1);
+    }
+}
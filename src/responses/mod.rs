@@ -1,17 +1,113 @@
-use crate::error::{try_parse_api_error, Result};
-use crate::types::{RecoveryCallback, RecoveryPolicy, RetryScope};
-use reqwest::Client as HttpClient;
+#[cfg(feature = "cache")]
+pub mod cache;
+#[cfg(feature = "stream")]
+pub mod metrics;
+pub mod rate_limit;
+
+use crate::endpoint::Endpoint;
+use crate::error::Result;
+use crate::http::Transport;
+use crate::retry;
+use crate::types::{
+    CancelOutcome, CreateOutcome, DeleteConfirmation, DeleteOutcome, RecoveryCallback,
+    RecoveryPolicy,
+};
+use reqwest::StatusCode;
 use std::fmt;
 use std::sync::Arc;
 
 /// Decision for retry logic
 enum RetryDecision {
-    /// Continue retrying
-    Continue,
+    /// Continue retrying after waiting the given delay
+    Continue { retry_delay: std::time::Duration },
     /// Return error
     Error(crate::Error),
 }
 
+/// Where a stream's events come from
+#[cfg(feature = "stream")]
+enum StreamSource {
+    /// A new response, created by POSTing the request
+    Create {
+        url: reqwest::Url,
+        request: Box<crate::Request>,
+        rate_limiter: Option<rate_limit::RateLimiter>,
+    },
+    /// A background response, read from its handle's stream URL
+    Background { url: reqwest::Url, id: String },
+    /// A response whose stream the recovery loop has already opened
+    Opened {
+        request: Box<crate::Request>,
+        response: std::sync::Mutex<Option<reqwest::Response>>,
+    },
+}
+
+#[cfg(feature = "stream")]
+impl StreamSource {
+    /// Sends the request that opens the event stream
+    async fn open(&self, client: &Transport) -> Result<reqwest::Response> {
+        match self {
+            Self::Create {
+                url,
+                request,
+                rate_limiter,
+            } => {
+                if let Some(limiter) = rate_limiter {
+                    limiter.acquire(rate_limit::estimate_tokens(request)).await;
+                }
+                let response = client.send(client.post(url.clone()).json(request)).await?;
+                if let Some(limiter) = rate_limiter {
+                    limiter.observe_headers(response.headers());
+                }
+                Ok(response)
+            }
+            Self::Background { url, id } => {
+                crate::diag::debug!("Streaming background response {id}");
+                let request = client
+                    .get(url.clone())
+                    .header(reqwest::header::ACCEPT, "text/event-stream");
+                client.send(request).await
+            }
+            Self::Opened { response, .. } => response
+                .lock()
+                .unwrap_or_else(std::sync::PoisonError::into_inner)
+                .take()
+                .ok_or_else(|| crate::Error::Stream("Stream was already opened".to_string())),
+        }
+    }
+
+    /// Fingerprint and redacted request to journal when the stream opens
+    fn journal_fields(
+        &self,
+        journal: &crate::journal::Journal,
+    ) -> (Option<String>, serde_json::Value) {
+        match self {
+            Self::Create { request, .. } | Self::Opened { request, .. } => {
+                let (fingerprint, request) = journal.request_fields(request);
+                (Some(fingerprint), request)
+            }
+            Self::Background { id, .. } => (None, serde_json::json!({ "id": id })),
+        }
+    }
+
+    #[cfg(feature = "tracing")]
+    fn span(&self) -> tracing::Span {
+        match self {
+            Self::Create { request, .. } | Self::Opened { request, .. } => tracing::info_span!(
+                "stream",
+                model = %request.model,
+                error_class = tracing::field::Empty,
+                response_id = tracing::field::Empty,
+            ),
+            Self::Background { id, .. } => tracing::info_span!(
+                "background_stream",
+                error_class = tracing::field::Empty,
+                response_id = %id,
+            ),
+        }
+    }
+}
+
 /// Lightweight formatter for recovery policy snapshots
 struct FormattedRecoveryPolicy<'a> {
     policy: &'a RecoveryPolicy,
@@ -26,14 +122,18 @@ impl fmt::Display for FormattedRecoveryPolicy<'_> {
         write!(
             f,
             "auto_retry_on_expired_container={}, notify_on_reset={}, max_retries={}, \
-auto_prune_expired_containers={}, log_recovery_attempts={}, reset_message={}, retry_scope={}",
+auto_prune_expired_containers={}, reset_missing_previous_response={}, log_recovery_attempts={}, \
+reset_message={}, retry_scope={}, use_idempotency_key={}, retry_transform={}",
             policy.auto_retry_on_expired_container,
             policy.notify_on_reset,
             policy.max_retries,
             policy.auto_prune_expired_containers,
+            policy.reset_missing_previous_response,
             policy.log_recovery_attempts,
             reset_message,
-            retry_scope
+            retry_scope,
+            policy.use_idempotency_key,
+            policy.on_retry_transform.is_some()
         )
     }
 }
@@ -42,7 +142,55 @@ fn policy_retry_scope(policy: &RecoveryPolicy) -> &str {
     policy.retry_scope.as_str()
 }
 
+/// Logs when the server reports a different `max_output_tokens` than was requested,
+/// e.g. because a proxy dropped the field or the model clamped it
+fn warn_on_max_output_tokens_mismatch(request: &crate::Request, response: &crate::Response) {
+    if let (Some(requested), Some(applied)) =
+        (request.max_output_tokens, response.max_output_tokens)
+    {
+        if requested != applied {
+            crate::diag::warn!(
+                "Requested max_output_tokens {requested} but the server applied {applied} for {}",
+                response.id
+            );
+        }
+    }
+}
+
+/// Reads the body of a 202, which some gateways cut down to the queued response's
+/// `id` and `status`; missing fields are filled in from the request
+fn decode_accepted(body: &[u8], request: &crate::Request) -> Result<crate::Response> {
+    let mut value: serde_json::Value = crate::http::decode_json(body)?;
+    if let Some(fields) = value.as_object_mut() {
+        fields.entry("object").or_insert_with(|| "response".into());
+        fields
+            .entry("created_at")
+            .or_insert_with(|| chrono::Utc::now().timestamp().into());
+        fields
+            .entry("model")
+            .or_insert_with(|| request.model.to_string().into());
+        fields.entry("status").or_insert_with(|| "queued".into());
+        fields
+            .entry("output")
+            .or_insert_with(|| serde_json::Value::Array(Vec::new()));
+    }
+    crate::http::decode_json(&serde_json::to_vec(&value)?)
+}
+
+/// Logs every parameter the response reports as ignored or changed, and at debug level
+/// the ones the server filled in
+fn log_discrepancies(request: &crate::Request, response: &crate::Response) {
+    for discrepancy in response.diff_request(request) {
+        if discrepancy.is_dropped() {
+            crate::diag::warn!("Response {}: {discrepancy}", response.id);
+        } else {
+            crate::diag::debug!("Response {}: {discrepancy}", response.id);
+        }
+    }
+}
+
 /// Recovery result information
+#[allow(clippy::struct_excessive_bools)]
 #[derive(Debug, Clone)]
 pub struct RecoveryInfo {
     /// Whether recovery was attempted
@@ -54,11 +202,42 @@ pub struct RecoveryInfo {
     /// Whether the recovery was successful
     pub successful: bool,
 
-    /// User-friendly message about the recovery
+    /// User-friendly message to show when recovery reset the session; set from
+    /// [`RecoveryPolicy::get_reset_message`] when
+    /// [`notify_on_reset`](RecoveryPolicy::notify_on_reset) is on
     pub message: Option<String>,
 
     /// Original error that triggered recovery
     pub original_error: Option<String>,
+
+    /// Idempotency key sent with the last attempt, for reconciling duplicates server-side.
+    /// Attempts share a key while the body is unchanged; recovery rewriting the body
+    /// starts a new one.
+    pub idempotency_key: Option<String>,
+
+    /// Model that served the request; differs from the requested model after a
+    /// [`Responses::create_with_fallback`] fallback
+    pub final_model: Option<crate::Model>,
+
+    /// Whether recovery pruned expired context from the request, which it does after a
+    /// container expiry when
+    /// [`auto_prune_expired_containers`](RecoveryPolicy::auto_prune_expired_containers) is set
+    pub context_pruned: bool,
+
+    /// `previous_response_id` that recovery removed from the request. The retried request
+    /// no longer sees that conversation, so send its history again if it is still needed.
+    pub cleared_previous_response_id: Option<String>,
+
+    /// Whether recovery dropped `previous_response_id` because the response it named no
+    /// longer exists, so the answer starts a new conversation; see
+    /// [`reset_missing_previous_response`](RecoveryPolicy::reset_missing_previous_response)
+    pub chain_reset: bool,
+
+    /// Classification of the error that triggered the last retry
+    pub error_class: Option<crate::ErrorClass>,
+
+    /// Total time spent waiting between attempts
+    pub total_delay: std::time::Duration,
 }
 
 impl RecoveryInfo {
@@ -71,6 +250,13 @@ impl RecoveryInfo {
             successful: false,
             message: None,
             original_error: None,
+            idempotency_key: None,
+            final_model: None,
+            context_pruned: false,
+            cleared_previous_response_id: None,
+            chain_reset: false,
+            error_class: None,
+            total_delay: std::time::Duration::ZERO,
         }
     }
 
@@ -87,6 +273,7 @@ impl RecoveryInfo {
             successful: true,
             message,
             original_error,
+            ..Self::none()
         }
     }
 
@@ -96,11 +283,73 @@ impl RecoveryInfo {
         Self {
             attempted: true,
             retry_count,
-            successful: false,
-            message: None,
             original_error,
+            ..Self::none()
         }
     }
+
+    /// Sets the idempotency key that was sent with the request
+    #[must_use]
+    pub fn with_idempotency_key(mut self, key: Option<String>) -> Self {
+        self.idempotency_key = key;
+        self
+    }
+
+    /// Whether recovery started the conversation over: it cleared
+    /// `previous_response_id` or pruned expired context, so the model no longer sees
+    /// earlier turns
+    #[must_use]
+    pub fn is_session_reset(&self) -> bool {
+        self.context_pruned || self.chain_reset || self.cleared_previous_response_id.is_some()
+    }
+
+    /// Copies what the recovery loop did to the request
+    fn with_trace(mut self, trace: &RecoveryTrace) -> Self {
+        self.context_pruned = trace.context_pruned;
+        self.cleared_previous_response_id
+            .clone_from(&trace.cleared_previous_response_id);
+        self.chain_reset = trace.chain_reset;
+        self.error_class = trace.last_error.as_ref().map(crate::Error::classify);
+        self.total_delay = trace.total_delay;
+        self
+    }
+}
+
+/// A retry about to be made by the recovery loop, passed to the callback set with
+/// [`Responses::with_recovery_event_callback`]
+#[derive(Debug)]
+pub struct RecoveryEvent<'a> {
+    /// Error that triggered the retry
+    pub error: &'a crate::Error,
+
+    /// Classification of `error`
+    pub error_class: crate::ErrorClass,
+
+    /// Number of this retry, starting at 1
+    pub retry_count: u32,
+
+    /// Time the loop waits before sending the retry
+    pub delay: std::time::Duration,
+
+    /// Whether this retry pruned expired context from the request
+    pub context_pruned: bool,
+
+    /// `previous_response_id` this retry removed from the request
+    pub cleared_previous_response_id: Option<&'a str>,
+}
+
+/// Callback for [`Responses::with_recovery_event_callback`]
+pub type RecoveryEventCallback = Box<dyn Fn(&RecoveryEvent<'_>) + Send + Sync>;
+
+/// What the recovery loop has done so far for one request
+#[derive(Debug, Default)]
+struct RecoveryTrace {
+    retry_count: u32,
+    last_error: Option<crate::Error>,
+    context_pruned: bool,
+    cleared_previous_response_id: Option<String>,
+    chain_reset: bool,
+    total_delay: std::time::Duration,
 }
 
 /// Enhanced response with recovery information
@@ -149,53 +398,137 @@ impl ResponseWithRecovery {
     pub fn recovery_message(&self) -> Option<&str> {
         self.recovery_info.message.as_deref()
     }
+
+    /// Returns the idempotency key sent with the request, if any
+    #[must_use]
+    pub fn idempotency_key(&self) -> Option<&str> {
+        self.recovery_info.idempotency_key.as_deref()
+    }
 }
 
 /// Responses API endpoints
 #[derive(Clone)]
+#[allow(clippy::struct_excessive_bools)]
 pub struct Responses {
-    client: HttpClient,
-    base_url: String,
+    client: Transport,
+    endpoint: Endpoint,
     recovery_policy: RecoveryPolicy,
     recovery_callback: Option<Arc<RecoveryCallback>>,
+    recovery_event_callback: Option<Arc<RecoveryEventCallback>>,
+    stream_failures_as_errors: bool,
+    strict_deletes: bool,
+    warn_on_discrepancy: bool,
+    size_limits: crate::types::RequestSizeLimits,
+    lite: bool,
+    stream_options: crate::types::StreamOptions,
+    rate_limiter: Option<rate_limit::RateLimiter>,
+    journal: Option<crate::journal::Journal>,
+    last_recovery: Arc<std::sync::Mutex<Option<RecoveryInfo>>>,
+    #[cfg(feature = "stream")]
+    last_event_id: Arc<std::sync::Mutex<Option<String>>>,
+    #[cfg(feature = "cache")]
+    cache: Option<cache::ResponseCache>,
 }
 
 impl std::fmt::Debug for Responses {
     fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
         f.debug_struct("Responses")
             .field("client", &self.client)
-            .field("base_url", &self.base_url)
+            .field("base_url", &self.endpoint.as_str())
             .field("recovery_policy", &self.recovery_policy)
             .field("recovery_callback", &self.recovery_callback.is_some())
-            .finish()
+            .field(
+                "recovery_event_callback",
+                &self.recovery_event_callback.is_some(),
+            )
+            .field("rate_limiter", &self.rate_limiter.is_some())
+            .field("journal", &self.journal.is_some())
+            .finish_non_exhaustive()
     }
 }
 
 impl Responses {
     /// Creates a new Responses API client
-    pub(crate) fn new(client: HttpClient, base_url: String) -> Self {
+    pub(crate) fn new(client: impl Into<Transport>, endpoint: impl Into<Endpoint>) -> Self {
         Self {
-            client,
-            base_url,
+            client: client.into(),
+            endpoint: endpoint.into(),
             recovery_policy: RecoveryPolicy::default(),
             recovery_callback: None,
+            recovery_event_callback: None,
+            stream_failures_as_errors: false,
+            strict_deletes: false,
+            warn_on_discrepancy: false,
+            size_limits: crate::types::RequestSizeLimits::default(),
+            lite: false,
+            stream_options: crate::types::StreamOptions::default(),
+            rate_limiter: None,
+            journal: None,
+            last_recovery: Arc::default(),
+            #[cfg(feature = "stream")]
+            last_event_id: Arc::default(),
+            #[cfg(feature = "cache")]
+            cache: None,
+        }
+    }
+
+    /// Sends this handle's requests with `auth` as the `Authorization` header.
+    ///
+    /// The response cache is not carried over, so handles for different API keys never
+    /// serve each other's cached responses. The rate limiter starts over from the same
+    /// settings, since every API key has its own limits on the server.
+    pub(crate) fn with_auth(mut self, auth: &reqwest::header::HeaderValue) -> Self {
+        self.client = self.client.with_auth(auth.clone());
+        self.rate_limiter = self
+            .rate_limiter
+            .as_ref()
+            .map(rate_limit::RateLimiter::fresh);
+        #[cfg(feature = "cache")]
+        {
+            self.cache = None;
         }
+        self
+    }
+
+    #[cfg(test)]
+    pub(crate) fn transport(&self) -> &Transport {
+        &self.client
     }
 
     /// Creates a new Responses API client with recovery policy
     pub(crate) fn new_with_recovery(
-        client: HttpClient,
-        base_url: String,
+        client: impl Into<Transport>,
+        endpoint: impl Into<Endpoint>,
         recovery_policy: RecoveryPolicy,
     ) -> Self {
         Self {
-            client,
-            base_url,
+            client: client.into(),
+            endpoint: endpoint.into(),
             recovery_policy,
             recovery_callback: None,
+            recovery_event_callback: None,
+            stream_failures_as_errors: false,
+            strict_deletes: false,
+            warn_on_discrepancy: false,
+            size_limits: crate::types::RequestSizeLimits::default(),
+            lite: false,
+            stream_options: crate::types::StreamOptions::default(),
+            rate_limiter: None,
+            journal: None,
+            last_recovery: Arc::default(),
+            #[cfg(feature = "stream")]
+            last_event_id: Arc::default(),
+            #[cfg(feature = "cache")]
+            cache: None,
         }
     }
 
+    /// Returns a builder for a Responses API client that is used on its own, with its own
+    /// base URL and recovery policy
+    pub fn builder() -> crate::EndpointBuilder<Self> {
+        crate::EndpointBuilder::default()
+    }
+
     /// Sets a callback function to be called when recovery occurs
     #[must_use]
     pub fn with_recovery_callback(mut self, callback: RecoveryCallback) -> Self {
@@ -203,6 +536,133 @@ impl Responses {
         self
     }
 
+    /// Sets a callback that receives a [`RecoveryEvent`] before each retry.
+    ///
+    /// Unlike [`Responses::with_recovery_callback`], the event says how the request was
+    /// changed for the retry, such as a cleared `previous_response_id`. It runs after
+    /// the plain recovery callback.
+    #[must_use]
+    pub fn with_recovery_event_callback(mut self, callback: RecoveryEventCallback) -> Self {
+        self.recovery_event_callback = Some(Arc::new(callback));
+        self
+    }
+
+    /// Recovery information from the most recent request that went through the recovery
+    /// loop: [`Responses::create`] with recovery enabled, the `create_with_*` methods and
+    /// [`Responses::stream_with_recovery`].
+    ///
+    /// This is how callers of plain `create` learn that the session was reset; see
+    /// [`RecoveryInfo::is_session_reset`]. `None` before the first such request and after
+    /// one that failed. Clones of this handle share the slot, so with several requests in
+    /// flight it describes whichever finished last; use
+    /// [`Responses::create_with_recovery`] when each call needs its own.
+    #[must_use]
+    pub fn last_recovery(&self) -> Option<RecoveryInfo> {
+        self.last_recovery
+            .lock()
+            .unwrap_or_else(std::sync::PoisonError::into_inner)
+            .clone()
+    }
+
+    fn set_last_recovery(&self, info: Option<RecoveryInfo>) {
+        *self
+            .last_recovery
+            .lock()
+            .unwrap_or_else(std::sync::PoisonError::into_inner) = info;
+    }
+
+    /// The SSE `id:` of the latest event read by the most recent stream from this handle,
+    /// to send as `Last-Event-ID` when resuming it.
+    ///
+    /// Events without an `id:` keep the previous one, as the SSE spec says. `None` before
+    /// any event carried an id; reset when a new stream opens. Clones of this handle share
+    /// the slot, like [`Responses::last_recovery`].
+    #[cfg(feature = "stream")]
+    #[must_use]
+    pub fn last_event_id(&self) -> Option<String> {
+        self.last_event_id
+            .lock()
+            .unwrap_or_else(std::sync::PoisonError::into_inner)
+            .clone()
+    }
+
+    /// Sets whether [`Responses::stream`] reports a server-side failure as an `Err` item
+    /// instead of a [`StreamEvent::Failed`](crate::types::StreamEvent::Failed) event.
+    ///
+    /// The error is mapped like an HTTP error, so rate limits and server errors can be
+    /// told apart with [`Error::classify`](crate::Error::classify). Off by default.
+    #[must_use]
+    pub fn with_stream_failures_as_errors(mut self, enabled: bool) -> Self {
+        self.stream_failures_as_errors = enabled;
+        self
+    }
+
+    /// Sets whether [`Responses::delete`] returns a 404 as an error instead of
+    /// [`DeleteOutcome::NotFound`]. Off by default.
+    #[must_use]
+    pub fn with_strict_deletes(mut self, enabled: bool) -> Self {
+        self.strict_deletes = enabled;
+        self
+    }
+
+    /// Sets whether the non-streaming `create` methods log a warning for every sent
+    /// parameter the response reports as ignored or changed, as found by
+    /// [`Response::diff_request`](crate::Response::diff_request). Parameters the server
+    /// filled in with a default are logged at debug level. Off by default, in which case
+    /// only a changed `max_output_tokens` is logged.
+    #[must_use]
+    pub fn with_warn_on_discrepancy(mut self, enabled: bool) -> Self {
+        self.warn_on_discrepancy = enabled;
+        self
+    }
+
+    /// Sets soft limits on the size of request bodies sent by the `create` methods.
+    ///
+    /// A request above `warn_above_bytes` is logged with its largest field; one above
+    /// `reject_above_bytes` fails with [`Error::RequestTooLarge`](crate::Error::RequestTooLarge)
+    /// without being sent. Streams are not checked; use
+    /// [`RequestSizeLimits::check`](crate::types::RequestSizeLimits::check) for those.
+    #[must_use]
+    pub fn with_request_size_limits(mut self, limits: crate::types::RequestSizeLimits) -> Self {
+        self.size_limits = limits;
+        self
+    }
+
+    /// Sets whether `create` drops the base64 images from image generation results while
+    /// deserializing, as [`Response::from_slice_lite`](crate::Response::from_slice_lite) does.
+    ///
+    /// Useful when only the text of a response is needed. Off by default.
+    #[must_use]
+    pub fn with_lite_responses(mut self, enabled: bool) -> Self {
+        self.lite = enabled;
+        self
+    }
+
+    /// Sets the timeouts [`Responses::stream`] applies to every stream.
+    ///
+    /// Use [`Responses::stream_with_options`] to override them for a single call.
+    #[must_use]
+    pub fn with_stream_options(mut self, options: crate::types::StreamOptions) -> Self {
+        self.stream_options = options;
+        self
+    }
+
+    /// Paces [`Responses::create`] and [`Responses::stream`] with a client-side rate limiter.
+    ///
+    /// Clones made afterwards share the limiter, so concurrent requests from all of them
+    /// are spaced out instead of retrying in lockstep after a 429.
+    #[must_use]
+    pub fn with_rate_limiter(mut self, config: rate_limit::RateLimitConfig) -> Self {
+        self.rate_limiter = Some(rate_limit::RateLimiter::new(&config));
+        self
+    }
+
+    /// Records create attempts, streams and retrieves to `journal`
+    pub(crate) fn with_journal(mut self, journal: crate::journal::Journal) -> Self {
+        self.journal = Some(journal);
+        self
+    }
+
     /// Returns the currently configured recovery policy.
     ///
     /// Defaults remain unchanged; this accessor simply exposes a shared
@@ -226,86 +686,248 @@ impl Responses {
     ///
     /// # Errors
     ///
-    /// Returns an error if the request fails to send, has a non-200 status code,
-    /// and recovery attempts (if any) also fail.
+    /// Returns a validation error if `request.stream` is `Some(true)`. Otherwise returns an
+    /// error if the request fails to send, has a non-200 status code, and recovery
+    /// attempts (if any) also fail.
     pub async fn create_with_recovery(
+        &self,
+        mut request: crate::Request,
+    ) -> Result<ResponseWithRecovery> {
+        self.recover(&mut request).await.0
+    }
+
+    /// Creates a response, falling back to the next model in `fallbacks` whenever the
+    /// current one stays short on capacity.
+    ///
+    /// The request's own model is tried first, with the usual recovery policy. Once its
+    /// retries are used up on a 429, 503 or 502 ([`Error::is_capacity_error`](crate::Error::is_capacity_error)),
+    /// `request.model` is replaced by the next candidate and the request is sent again.
+    /// [`RecoveryInfo::final_model`] names the model that answered. Every model starts from
+    /// the original request, so changes made while recovering on one model, such as a
+    /// halved `max_output_tokens` or a cleared `previous_response_id`, are not carried over.
+    ///
+    /// # Errors
+    ///
+    /// Returns the first error that is not a capacity error, without trying further
+    /// models, or the last model's error once every candidate is exhausted.
+    pub async fn create_with_fallback(
         &self,
         request: crate::Request,
+        fallbacks: &[crate::Model],
     ) -> Result<ResponseWithRecovery> {
+        let mut fallbacks = fallbacks.iter();
+        let mut model = request.model.clone();
+        let mut retry_count: u32 = 0;
+        let mut original_error: Option<String> = None;
+
+        loop {
+            let mut current_request = crate::Request {
+                model: model.clone(),
+                ..request.clone()
+            };
+            match self.recover(&mut current_request).await {
+                (Ok(mut result), _) => {
+                    let info = &mut result.recovery_info;
+                    if retry_count > 0 {
+                        info.attempted = true;
+                        info.successful = true;
+                        info.retry_count = info.retry_count.saturating_add(retry_count);
+                        if info.original_error.is_none() {
+                            info.original_error = original_error;
+                        }
+                    }
+                    return Ok(result);
+                }
+                (Err(error), true) => {
+                    let Some(next) = fallbacks.next() else {
+                        return Err(error);
+                    };
+                    crate::diag::warn!(
+                        "Model {model} is short on capacity ({error}); falling back to {next}"
+                    );
+                    // Switching models counts as one more attempt
+                    let attempts = match error {
+                        crate::Error::MaxRetriesExceeded { attempts } => attempts,
+                        _ => 0,
+                    };
+                    retry_count = retry_count.saturating_add(attempts).saturating_add(1);
+                    original_error.get_or_insert_with(|| error.to_string());
+                    model = next.clone();
+                }
+                (Err(error), false) => return Err(error),
+            }
+        }
+    }
+
+    /// Runs the recovery loop for one request, modifying it in place between attempts.
+    ///
+    /// Alongside the result, reports whether the last error returned by the server was a
+    /// capacity error, which a `MaxRetriesExceeded` result no longer shows. With the
+    /// `tracing` feature the loop runs inside a `create_with_recovery` span carrying
+    /// `model`, `retry_count`, `error_class` and `response_id`.
+    async fn recover(
+        &self,
+        current_request: &mut crate::Request,
+    ) -> (Result<ResponseWithRecovery>, bool) {
+        #[cfg(feature = "tracing")]
+        let span = tracing::info_span!(
+            "create_with_recovery",
+            model = %current_request.model,
+            retry_count = 0_u32,
+            error_class = tracing::field::Empty,
+            response_id = tracing::field::Empty,
+        );
+        let attempts = self.recover_attempts(current_request);
+        #[cfg(feature = "tracing")]
+        let attempts = tracing::Instrument::instrument(attempts, span);
+        let result = attempts.await;
+        self.set_last_recovery(
+            result
+                .0
+                .as_ref()
+                .ok()
+                .map(|result| result.recovery_info.clone()),
+        );
+        result
+    }
+
+    async fn recover_attempts(
+        &self,
+        current_request: &mut crate::Request,
+    ) -> (Result<ResponseWithRecovery>, bool) {
         if let Some(snapshot) = self.policy_snapshot() {
-            log::debug!("Starting recovery-enabled request with policy: {snapshot}");
+            crate::diag::debug!("Starting recovery-enabled request with policy: {snapshot}");
         }
 
-        let mut current_request = request;
-        let mut retry_count: u32 = 0;
-        let mut last_error: Option<crate::Error> = None;
+        if let Err(error) = Self::sanitize_non_streaming(current_request)
+            .and_then(|()| self.size_limits.check(current_request))
+        {
+            return (Err(error), false);
+        }
+        let mut trace = RecoveryTrace::default();
+        // One key per request body, reused on every attempt that sends the same body so the
+        // server can de-duplicate, and replaced once recovery rewrites the body
+        let mut keyed_body: Option<([u8; 32], String)> = None;
 
         loop {
+            let idempotency_key = self.idempotency_key_for(current_request, &mut keyed_body);
             if self.recovery_policy.log_recovery_attempts {
+                let retry_count = trace.retry_count;
                 let attempt_number = retry_count.saturating_add(1);
-                let has_last_error = last_error.is_some();
-                log::debug!(
+                let has_last_error = trace.last_error.is_some();
+                crate::diag::debug!(
                     "Preparing to send attempt {attempt_number} (retry_count={retry_count}, has_last_error={has_last_error})"
                 );
             }
 
-            match self.create_internal(&current_request).await {
+            match self
+                .create_internal(
+                    current_request,
+                    trace.retry_count.saturating_add(1),
+                    idempotency_key,
+                )
+                .await
+            {
                 Ok(response) => {
-                    return Ok(self.handle_successful_response(
-                        response,
-                        retry_count,
-                        last_error.as_ref(),
-                    ));
+                    crate::diag::record!("response_id", response.id());
+                    let mut result = self.handle_successful_response(response, &trace);
+                    result.recovery_info.idempotency_key = idempotency_key.map(str::to_string);
+                    result.recovery_info.final_model = Some(current_request.model.clone());
+                    return (Ok(result), false);
                 }
                 Err(error) => {
-                    match self.handle_error_with_retry(
-                        error,
-                        &mut current_request,
-                        &mut retry_count,
-                        &mut last_error,
-                    ) {
-                        RetryDecision::Error(err) => return Err(err),
-                        RetryDecision::Continue => {}
+                    let capacity = error.is_capacity_error();
+                    match self.handle_error_with_retry(error, current_request, &mut trace) {
+                        RetryDecision::Error(err) => return (Err(err), capacity),
+                        RetryDecision::Continue { retry_delay } => {
+                            if let Some(error) = trace.last_error.as_ref() {
+                                if error.is_container_expired()
+                                    && self.recovery_policy.recreate_expired_containers
+                                {
+                                    self.recreate_expired_containers(current_request).await;
+                                }
+                                retry::wait(error, retry_delay).await;
+                            }
+                        }
                     }
                 }
             }
         }
     }
 
+    /// Idempotency key for `request`'s current body, if the policy sends one.
+    ///
+    /// The key in `keyed_body` is kept while the body is unchanged. A body rewritten by
+    /// recovery (pruned context, a cleared `previous_response_id`, a retry transform or a
+    /// recreated container) gets a new key, so the server neither replays the result of
+    /// the old body nor rejects the new one as a key conflict.
+    fn idempotency_key_for<'a>(
+        &self,
+        request: &crate::Request,
+        keyed_body: &'a mut Option<([u8; 32], String)>,
+    ) -> Option<&'a str> {
+        if !self.recovery_policy.use_idempotency_key {
+            return None;
+        }
+        let fingerprint = request.fingerprint();
+        if keyed_body
+            .as_ref()
+            .is_none_or(|(keyed, _)| *keyed != fingerprint)
+        {
+            let key = if self.recovery_policy.idempotency_key_from_fingerprint {
+                crate::types::canonical::to_hex(&fingerprint)
+            } else {
+                uuid::Uuid::new_v4().to_string()
+            };
+            *keyed_body = Some((fingerprint, key));
+        }
+        keyed_body.as_ref().map(|(_, key)| key.as_str())
+    }
+
     /// Handles successful response, creating recovery info if needed
     fn handle_successful_response(
         &self,
         response: crate::Response,
-        retry_count: u32,
-        last_error: Option<&crate::Error>,
+        trace: &RecoveryTrace,
     ) -> ResponseWithRecovery {
-        if retry_count > 0 {
-            // We had to recover, create recovery info
-            let recovery_info = RecoveryInfo::success(
-                retry_count,
-                if self.recovery_policy.notify_on_reset {
-                    Some(self.recovery_policy.get_reset_message())
-                } else {
-                    None
-                },
-                last_error.map(std::string::ToString::to_string),
-            );
+        match self.recovery_info(trace) {
+            Some(recovery_info) => ResponseWithRecovery::with_recovery(response, recovery_info),
+            // No recovery needed
+            None => ResponseWithRecovery::new(response),
+        }
+    }
 
-            if self.recovery_policy.log_recovery_attempts {
-                if let Some(error) = last_error {
-                    log::info!(
-                        "Successfully recovered after {retry_count} attempt(s) (classification={})",
-                        error.classify()
-                    );
-                } else {
-                    log::info!("Successfully recovered after {retry_count} attempt(s)");
-                }
-            }
+    /// Describes what the recovery loop did, or `None` if the first attempt succeeded.
+    ///
+    /// The reset message is attached only when the session was actually reset, not
+    /// after a plain retry of a transient error.
+    fn recovery_info(&self, trace: &RecoveryTrace) -> Option<RecoveryInfo> {
+        let retry_count = trace.retry_count;
+        if retry_count == 0 {
+            return None;
+        }
+        let last_error = trace.last_error.as_ref();
+        let mut recovery_info = RecoveryInfo::success(
+            retry_count,
+            None,
+            last_error.map(std::string::ToString::to_string),
+        )
+        .with_trace(trace);
+        if self.recovery_policy.notify_on_reset && recovery_info.is_session_reset() {
+            recovery_info.message = Some(self.recovery_policy.get_reset_message());
+        }
 
-            return ResponseWithRecovery::with_recovery(response, recovery_info);
+        if self.recovery_policy.log_recovery_attempts {
+            if let Some(error) = last_error {
+                crate::diag::info!(
+                    "Successfully recovered after {retry_count} attempt(s) (classification={})",
+                    error.classify()
+                );
+            } else {
+                crate::diag::info!("Successfully recovered after {retry_count} attempt(s)");
+            }
         }
-        // No recovery needed
-        ResponseWithRecovery::new(response)
+        Some(recovery_info)
     }
 
     /// Handles error with retry logic
@@ -313,317 +935,240 @@ impl Responses {
         &self,
         error: crate::Error,
         current_request: &mut crate::Request,
-        retry_count: &mut u32,
-        last_error: &mut Option<crate::Error>,
+        trace: &mut RecoveryTrace,
     ) -> RetryDecision {
-        let logging_enabled = self.recovery_policy.log_recovery_attempts;
-        let classification = error.classify();
-        let suggested_retry_after = error.retry_after();
-        let current_retry_count = *retry_count;
-        let scope = self.recovery_policy.retry_scope;
-        let scope_label = scope.as_str();
-        let scope_allows_retry = match scope {
-            RetryScope::AllRecoverable => error.is_recoverable(),
-            RetryScope::ContainerOnly => matches!(
-                classification,
-                crate::error::ErrorClass::ContainerExpired
-                    | crate::error::ErrorClass::ApiContainerExpired
-            ),
-            RetryScope::TransientOnly => matches!(
-                classification,
-                crate::error::ErrorClass::TransientHttp | crate::error::ErrorClass::RetryableServer
-            ),
+        let policy = &self.recovery_policy;
+        let retry::Verdict::Retry { delay } = retry::decide(policy, &error, trace.retry_count)
+        else {
+            return RetryDecision::Error(retry::give_up(policy, error, trace.retry_count));
         };
-        let within_retry_limit = *retry_count < self.recovery_policy.max_retries;
-        let auto_retry_enabled = self.recovery_policy.auto_retry_on_expired_container;
-        let is_recoverable = error.is_recoverable();
-        let can_retry =
-            is_recoverable && auto_retry_enabled && scope_allows_retry && within_retry_limit;
-
-        if can_retry {
-            let before_retry_count = current_retry_count;
-            let next_retry_count = retry_count.saturating_add(1);
-            let retry_delay = suggested_retry_after.unwrap_or(1);
-
-            if logging_enabled {
-                log::debug!(
-                    "handle_error_with_retry: classification={classification}, scope={scope_label}, retry_count={before_retry_count}->{next_retry_count}, retry_after={retry_delay}s, decision=Continue"
-                );
-            }
 
-            *retry_count = next_retry_count;
-            self.log_retry_attempt(&error, *retry_count, retry_delay);
-            *last_error = Some(error);
+        trace.retry_count = trace.retry_count.saturating_add(1);
+        retry::log_retry(policy, &error, trace.retry_count, delay);
+        let delay = retry::effective_delay(&error, delay);
+        trace.total_delay = trace.total_delay.saturating_add(delay);
+
+        // Notify callback if set
+        if let Some(callback) = &self.recovery_callback {
+            callback(&error, trace.retry_count);
+        }
+
+        let (context_pruned, cleared) = self.modify_request_for_retry(current_request, &error);
+        if let Some(transform) = &policy.on_retry_transform {
+            transform.apply(current_request, trace.retry_count.saturating_add(1));
+        }
+        if let Some(callback) = &self.recovery_event_callback {
+            callback(&RecoveryEvent {
+                error: &error,
+                error_class: error.classify(),
+                retry_count: trace.retry_count,
+                delay,
+                context_pruned,
+                cleared_previous_response_id: cleared.as_deref(),
+            });
+        }
+        trace.context_pruned |= context_pruned;
+        trace.chain_reset |= error.is_previous_response_not_found();
+        if trace.cleared_previous_response_id.is_none() {
+            trace.cleared_previous_response_id = cleared;
+        }
+        trace.last_error = Some(error);
+
+        RetryDecision::Continue { retry_delay: delay }
+    }
 
-            // Notify callback if set
-            if let Some(callback) = &self.recovery_callback {
-                if let Some(ref error) = last_error {
-                    callback(error, *retry_count);
+    /// Modifies request for retry based on error type.
+    ///
+    /// Returns whether expired context was pruned, and the `previous_response_id` that
+    /// was cleared, if any.
+    fn modify_request_for_retry(
+        &self,
+        current_request: &mut crate::Request,
+        error: &crate::Error,
+    ) -> (bool, Option<String>) {
+        match error {
+            crate::Error::ContainerExpired { .. } => {
+                // Prune expired containers from context if enabled
+                if self.recovery_policy.auto_prune_expired_containers {
+                    (true, self.prune_expired_context(current_request))
+                } else {
+                    // Just clear the previous_response_id to start fresh
+                    (false, current_request.previous_response_id.take())
                 }
             }
+            crate::Error::PreviousResponseNotFound { .. } => {
+                // The chain is gone; continue as a new conversation
+                (false, current_request.previous_response_id.take())
+            }
+            crate::Error::BadGateway { .. }
+            | crate::Error::ServiceUnavailable { .. }
+            | crate::Error::GatewayTimeout { .. }
+            | crate::Error::ServerError { .. }
+            | crate::Error::RateLimited { .. } => {
+                // For these errors, we don't need to modify the request
+                // Just retry as-is after the delay
+                (false, None)
+            }
+            _ => {
+                // For other recoverable errors, clear context as fallback
+                (false, current_request.previous_response_id.take())
+            }
+        }
+    }
 
-            Self::handle_retry_delay(last_error.as_ref().unwrap(), retry_delay);
-            self.modify_request_for_retry(current_request, last_error.as_ref().unwrap());
-
-            RetryDecision::Continue
-        } else {
-            // Can't recover or max retries exceeded
-            if *retry_count > 0 {
-                if self.recovery_policy.log_recovery_attempts {
-                    if logging_enabled {
-                        let reason = if !within_retry_limit {
-                            "max_retries_reached"
-                        } else if !scope_allows_retry {
-                            "scope_restricted"
-                        } else if !auto_retry_enabled {
-                            "auto_retry_disabled"
-                        } else if !is_recoverable {
-                            "non_recoverable"
-                        } else {
-                            "unknown"
-                        };
-                        log::debug!(
-                            "handle_error_with_retry: classification={classification}, scope={scope_label}, retry_count={current_retry_count}->{current_retry_count}, retry_after={suggested_retry_after:?}, decision=MaxRetriesExceeded, reason={reason}"
+    /// Points every code interpreter tool that names a container by ID at a fresh
+    /// replacement; a container that cannot be recreated is left as-is
+    async fn recreate_expired_containers(&self, request: &mut crate::Request) {
+        let containers =
+            crate::containers::Containers::new(self.client.clone(), self.endpoint.clone());
+        for tool in request.tools.iter_mut().flatten() {
+            let Some(container_id) = tool.container.as_mut().and_then(|c| c.id.as_mut()) else {
+                continue;
+            };
+            match containers.recreate(container_id).await {
+                Ok(replacement) => {
+                    if self.recovery_policy.log_recovery_attempts {
+                        crate::diag::info!(
+                            "Recreated expired container {container_id} as {}",
+                            replacement.id
                         );
                     }
-                    log::error!("Recovery failed after {} attempts: {error}", *retry_count);
+                    *container_id = replacement.id;
                 }
-                RetryDecision::Error(crate::Error::MaxRetriesExceeded {
-                    attempts: *retry_count,
-                })
-            } else {
-                if logging_enabled {
-                    let reason = if !scope_allows_retry {
-                        "scope_restricted"
-                    } else if !auto_retry_enabled {
-                        "auto_retry_disabled"
-                    } else if !is_recoverable {
-                        "non_recoverable"
-                    } else {
-                        "unknown"
-                    };
-                    log::debug!(
-                        "handle_error_with_retry: classification={classification}, scope={scope_label}, retry_count={current_retry_count}, retry_after={suggested_retry_after:?}, decision=Propagate, reason={reason}"
+                Err(error) => {
+                    crate::diag::warn!(
+                        "Failed to recreate expired container {container_id}: {error}"
                     );
                 }
-                RetryDecision::Error(error)
             }
         }
     }
 
-    /// Logs retry attempt based on error type
-    fn log_retry_attempt(&self, error: &crate::Error, retry_count: u32, retry_delay: u64) {
-        if !self.recovery_policy.log_recovery_attempts {
-            return;
+    /// Prepares a request for the non-streaming create path.
+    ///
+    /// `stream: Some(false)` is dropped so it is never sent; `stream: Some(true)` is
+    /// rejected because the non-streaming path cannot parse an SSE body.
+    fn sanitize_non_streaming(request: &mut crate::Request) -> Result<()> {
+        if request.stream == Some(true) {
+            return Err(crate::types::ValidationError::StreamingNotSupported.into());
         }
+        request.stream = None;
+        request.reconcile_max_tokens();
+        Ok(())
+    }
 
-        let classification = error.classify();
+    /// Creates a response (internal method without recovery), journaling the attempt.
+    async fn create_internal(
+        &self,
+        request: &crate::Request,
+        attempt: u32,
+        idempotency_key: Option<&str>,
+    ) -> Result<crate::Response> {
+        let result = self.create_attempt(request, attempt, idempotency_key).await;
+        if let Some(journal) = &self.journal {
+            journal.record_create(request, attempt, &result);
+        }
+        result
+    }
 
-        match classification {
-            crate::error::ErrorClass::ContainerExpired
-            | crate::error::ErrorClass::ApiContainerExpired => {
-                Self::log_container_expired_retry(retry_count, self.recovery_policy.max_retries);
-            }
-            crate::error::ErrorClass::RetryableServer => {
-                Self::log_retryable_server_retry(
-                    error,
-                    retry_count,
-                    retry_delay,
-                    self.recovery_policy.max_retries,
-                );
-            }
-            crate::error::ErrorClass::RateLimited => {
-                Self::log_rate_limited_retry(
-                    retry_count,
-                    retry_delay,
-                    self.recovery_policy.max_retries,
-                );
-            }
-            crate::error::ErrorClass::TransientHttp => {
-                Self::log_transient_http_retry(
-                    error,
-                    retry_count,
-                    retry_delay,
-                    self.recovery_policy.max_retries,
-                );
-            }
-            crate::error::ErrorClass::NonRecoverable => {
-                Self::log_non_recoverable_retry(
-                    error,
-                    classification,
-                    retry_count,
-                    self.recovery_policy.max_retries,
-                );
-            }
+    async fn create_attempt(
+        &self,
+        request: &crate::Request,
+        attempt: u32,
+        idempotency_key: Option<&str>,
+    ) -> Result<crate::Response> {
+        let (response, estimated_tokens) = self
+            .send_internal(request, attempt, idempotency_key)
+            .await?;
+        if response.status() == StatusCode::ACCEPTED {
+            // Queued for background processing: no usage or echoed parameters yet
+            let body = self.client.body(response).await?;
+            return decode_accepted(&body, request);
         }
-    }
-
-    /// Logs container expired retry attempt
-    fn log_container_expired_retry(retry_count: u32, max_retries: u32) {
-        log::warn!("Container expired, attempting recovery (attempt {retry_count}/{max_retries})");
-    }
-
-    /// Logs retryable server error retry attempt
-    fn log_retryable_server_retry(
-        error: &crate::Error,
-        retry_count: u32,
-        retry_delay: u64,
-        max_retries: u32,
-    ) {
-        match error {
-            crate::Error::BadGateway { .. } => {
-                log::warn!(
-                    "Bad Gateway error, retrying in {retry_delay}s (attempt {retry_count}/{max_retries})"
-                );
-            }
-            crate::Error::ServiceUnavailable { .. } => {
-                log::warn!(
-                    "Service unavailable, retrying in {retry_delay}s (attempt {retry_count}/{max_retries})"
-                );
-            }
-            crate::Error::GatewayTimeout { .. } => {
-                log::warn!(
-                    "Gateway timeout, retrying in {retry_delay}s (attempt {retry_count}/{max_retries})"
-                );
-            }
-            crate::Error::ServerError {
-                retry_suggested: true,
-                ..
-            } => {
-                log::warn!(
-                    "Server error (retryable), retrying in {retry_delay}s (attempt {retry_count}/{max_retries})"
-                );
-            }
-            _ => {
-                log::warn!(
-                    "Recoverable error, attempting recovery (attempt {}/{}): {}",
-                    retry_count,
-                    max_retries,
-                    error.user_message()
-                );
-            }
+        let response = self.decode_response(response).await?;
+        if let (Some(limiter), Some(estimated), Some(used)) = (
+            &self.rate_limiter,
+            estimated_tokens,
+            response.total_tokens(),
+        ) {
+            limiter.record_usage(estimated, used);
         }
-    }
-
-    /// Logs rate limited retry attempt
-    fn log_rate_limited_retry(retry_count: u32, retry_delay: u64, max_retries: u32) {
-        log::warn!(
-            "Rate limited, retrying in {retry_delay}s (attempt {retry_count}/{max_retries})"
-        );
-    }
-
-    /// Logs transient HTTP error retry attempt
-    fn log_transient_http_retry(
-        error: &crate::Error,
-        retry_count: u32,
-        retry_delay: u64,
-        max_retries: u32,
-    ) {
-        if let crate::Error::Http(reqwest_error) = error {
-            if reqwest_error.is_timeout() {
-                log::warn!(
-                    "HTTP timeout, retrying in {retry_delay}s (attempt {retry_count}/{max_retries})"
-                );
-            } else if reqwest_error.is_connect() {
-                log::warn!(
-                    "HTTP connection error, retrying in {retry_delay}s (attempt {retry_count}/{max_retries})"
-                );
-            } else if reqwest_error.is_request() {
-                log::warn!(
-                    "HTTP request error, attempting recovery (attempt {retry_count}/{max_retries})"
-                );
-            } else {
-                log::warn!(
-                    "Recoverable HTTP error, attempting recovery (attempt {retry_count}/{max_retries}): {reqwest_error}"
-                );
-            }
+        if self.warn_on_discrepancy {
+            log_discrepancies(request, &response);
         } else {
-            log::warn!(
-                "Recoverable error, attempting recovery (attempt {}/{}): {}",
-                retry_count,
-                max_retries,
-                error.user_message()
-            );
+            warn_on_max_output_tokens_mismatch(request, &response);
         }
+        Ok(response)
     }
 
-    /// Logs non-recoverable error retry attempt
-    fn log_non_recoverable_retry(
-        error: &crate::Error,
-        classification: crate::error::ErrorClass,
-        retry_count: u32,
-        max_retries: u32,
-    ) {
-        log::warn!(
-            "Retrying after unexpected classification ({classification}) (attempt {}/{}): {}",
-            retry_count,
-            max_retries,
-            error.user_message()
-        );
-    }
-
-    /// Handles retry delay based on error type
-    fn handle_retry_delay(error: &crate::Error, retry_delay: u64) {
-        // Add delay for transient errors (but not for container expiration)
-        if error.is_transient() && !error.is_container_expired() && retry_delay > 0 {
-            // Use std::thread::sleep for simple delay (blocking is acceptable here)
-            std::thread::sleep(std::time::Duration::from_secs(retry_delay));
+    /// Sends a create request, returning the successful HTTP response and, with a rate
+    /// limiter, the tokens reserved for it
+    async fn send_internal(
+        &self,
+        request: &crate::Request,
+        attempt: u32,
+        idempotency_key: Option<&str>,
+    ) -> Result<(reqwest::Response, Option<u32>)> {
+        let mut http_request = self
+            .client
+            .post(self.endpoint.url(&["responses"])?)
+            .json(request);
+        if let Some(key) = idempotency_key {
+            http_request =
+                http_request.header(self.recovery_policy.idempotency_header.as_str(), key);
         }
-    }
+        // wasm fetch has no per-request timeout
+        #[cfg(not(target_arch = "wasm32"))]
+        if let Some(timeout) = request
+            .service_tier
+            .as_ref()
+            .and_then(crate::types::ServiceTier::default_timeout)
+        {
+            http_request = http_request.timeout(timeout);
+        }
+        let Some(limiter) = &self.rate_limiter else {
+            let response = self.client.send_attempt(http_request, attempt).await?;
+            return Ok((response, None));
+        };
 
-    /// Modifies request for retry based on error type
-    fn modify_request_for_retry(&self, current_request: &mut crate::Request, error: &crate::Error) {
-        match error {
-            crate::Error::ContainerExpired { .. } => {
-                // Prune expired containers from context if enabled
-                if self.recovery_policy.auto_prune_expired_containers {
-                    *current_request = self.prune_expired_context(current_request.clone());
-                } else {
-                    // Just clear the previous_response_id to start fresh
-                    current_request.previous_response_id = None;
+        let estimated_tokens = rate_limit::estimate_tokens(request);
+        limiter.acquire(estimated_tokens).await;
+        let response = match self.client.send_attempt(http_request, attempt).await {
+            Ok(response) => response,
+            Err(error) => {
+                if let (crate::Error::RateLimited { .. }, Some(delay)) =
+                    (&error, error.retry_after())
+                {
+                    limiter.pause(delay);
                 }
+                return Err(error);
             }
-            crate::Error::BadGateway { .. }
-            | crate::Error::ServiceUnavailable { .. }
-            | crate::Error::GatewayTimeout { .. }
-            | crate::Error::ServerError { .. }
-            | crate::Error::RateLimited { .. } => {
-                // For these errors, we don't need to modify the request
-                // Just retry as-is after the delay
-            }
-            _ => {
-                // For other recoverable errors, clear context as fallback
-                current_request.previous_response_id = None;
-            }
-        }
+        };
+        limiter.observe_headers(response.headers());
+        Ok((response, Some(estimated_tokens)))
     }
 
-    /// Creates a response (internal method without recovery).
-    async fn create_internal(&self, request: &crate::Request) -> Result<crate::Response> {
-        let response = self
-            .client
-            .post(format!("{}/responses", self.base_url))
-            .json(request)
-            .send()
-            .await
-            .map_err(crate::Error::Http)?;
-
-        let response = try_parse_api_error(response).await?;
-        response.json().await.map_err(crate::Error::Http)
+    async fn decode_response(&self, response: reqwest::Response) -> Result<crate::Response> {
+        if self.lite {
+            let body = self.client.body(response).await?;
+            crate::types::lite::lite(|| crate::http::decode_json(&body))
+        } else {
+            self.client.json(response).await
+        }
     }
 
-    /// Prunes expired containers from the request context
-    fn prune_expired_context(&self, mut request: crate::Request) -> crate::Request {
+    /// Prunes expired containers from the request context, returning the cleared
+    /// `previous_response_id`
+    fn prune_expired_context(&self, request: &mut crate::Request) -> Option<String> {
         // For now, we'll implement a simple strategy: clear the previous_response_id
         // In a more sophisticated implementation, we could track container lifecycles
         // and selectively prune only expired ones while preserving fresh context
-        request.previous_response_id = None;
+        let cleared = request.previous_response_id.take();
 
         if self.recovery_policy.log_recovery_attempts {
-            log::debug!("Pruned expired context from request");
+            crate::diag::debug!("Pruned expired context from request");
         }
-
-        request
+        cleared
     }
 
     /// Manually prunes expired containers from a request
@@ -631,8 +1176,9 @@ impl Responses {
     /// This method can be called by applications that want to proactively
     /// clean up their context before making requests.
     #[must_use]
-    pub fn prune_expired_context_manual(&self, request: crate::Request) -> crate::Request {
-        self.prune_expired_context(request)
+    pub fn prune_expired_context_manual(&self, mut request: crate::Request) -> crate::Request {
+        self.prune_expired_context(&mut request);
+        request
     }
 
     /// Creates a response without applying any recovery policy.
@@ -643,18 +1189,47 @@ impl Responses {
     ///
     /// # Errors
     ///
-    /// Returns an error if the request fails to send or has a non-200 status code.
-    pub async fn create_no_recovery(&self, request: crate::Request) -> Result<crate::Response> {
-        self.create_internal(&request).await
+    /// Returns a validation error if `request.stream` is `Some(true)`, or an error if the
+    /// request fails to send or has a non-200 status code.
+    pub async fn create_no_recovery(&self, mut request: crate::Request) -> Result<crate::Response> {
+        Self::sanitize_non_streaming(&mut request)?;
+        self.size_limits.check(&request)?;
+        self.create_internal(&request, 1, None).await
     }
 
-    /// Creates a response (legacy method for backward compatibility).
+    /// Creates a response and returns its undecoded JSON body.
+    ///
+    /// For callers that deserialize selectively, e.g. with
+    /// [`Response::from_slice_lite`](crate::Response::from_slice_lite) or their own
+    /// visitor that skips image payloads. Like [`Responses::create_no_recovery`], no
+    /// recovery policy or cache is applied.
     ///
     /// # Errors
     ///
-    /// Returns an error if the request fails to send or has a non-200 status code.
+    /// Returns a validation error if `request.stream` is `Some(true)`, an error if the
+    /// request fails to send or has a non-200 status code, or
+    /// [`Error::ResponseTooLarge`](crate::Error::ResponseTooLarge) if the body exceeds the
+    /// client's size limit.
+    pub async fn create_raw(&self, mut request: crate::Request) -> Result<bytes::Bytes> {
+        Self::sanitize_non_streaming(&mut request)?;
+        self.size_limits.check(&request)?;
+        let (response, _) = self.send_internal(&request, 1, None).await?;
+        self.client.body(response).await
+    }
+
+    /// Creates a response, applying the client's recovery policy.
+    ///
+    /// Goes through the same retry loop as [`Responses::create_with_recovery`] whenever
+    /// [`RecoveryPolicy::enabled`] is true, so a policy that retries transient errors but
+    /// not container expirations still retries here. Which errors are retried is decided
+    /// per error class. Use [`Responses::create_no_recovery`] to bypass recovery entirely.
+    ///
+    /// # Errors
+    ///
+    /// Returns a validation error if `request.stream` is `Some(true)`, or an error if the
+    /// request fails to send or has a non-200 status code.
     pub async fn create(&self, request: crate::Request) -> Result<crate::Response> {
-        let use_recovery = self.recovery_policy.auto_retry_on_expired_container;
+        let use_recovery = self.recovery_policy.enabled();
 
         if let Some(snapshot) = self.policy_snapshot() {
             let branch = if use_recovery {
@@ -662,16 +1237,112 @@ impl Responses {
             } else {
                 "direct"
             };
-            log::debug!("create() delegating via {branch} branch; active policy: {snapshot}");
+            crate::diag::debug!(
+                "create() delegating via {branch} branch; active policy: {snapshot}"
+            );
         }
 
-        if use_recovery {
-            // Use the recovery-enabled version and extract just the response
-            self.create_with_recovery(request).await.map(|r| r.response)
+        #[cfg(feature = "cache")]
+        if self.cache.is_some() {
+            return self
+                .create_cached(request)
+                .await
+                .map(cache::CachedOr::into_inner);
+        }
+
+        self.create_uncached(request).await
+    }
+
+    async fn create_uncached(&self, request: crate::Request) -> Result<crate::Response> {
+        if self.recovery_policy.enabled() {
+            // Use the recovery-enabled version and extract just the response; the
+            // recovery info stays available through `last_recovery`
+            self.create_with_recovery(request).await.map(|result| {
+                if let Some(message) = result.recovery_message() {
+                    crate::diag::info!("Session reset during recovery: {message}");
+                }
+                result.response
+            })
         } else {
             // Use the direct version without recovery
-            self.create_internal(&request).await
+            self.create_no_recovery(request).await
+        }
+    }
+
+    /// Enables the client-side response cache for [`Responses::create`] and
+    /// [`Responses::create_cached`]. Clones made afterwards share the cache.
+    #[cfg(feature = "cache")]
+    #[must_use]
+    pub fn with_response_cache(mut self, config: cache::CacheConfig) -> Self {
+        self.cache = Some(cache::ResponseCache::new(config));
+        self
+    }
+
+    /// Creates a response, serving an identical recent request from the cache.
+    ///
+    /// Only requests without `stream`, `background` or `store` set to true, and without
+    /// MCP, function or custom tools (unless allowed by the [`CacheConfig`](cache::CacheConfig)),
+    /// are cached. Without a cache configured this always returns [`cache::CachedOr::Fresh`].
+    ///
+    /// # Errors
+    ///
+    /// Returns the same errors as [`Responses::create`].
+    #[cfg(feature = "cache")]
+    pub async fn create_cached(
+        &self,
+        request: crate::Request,
+    ) -> Result<cache::CachedOr<crate::Response>> {
+        let cached = self
+            .cache
+            .as_ref()
+            .and_then(|cache| Some((cache, cache.key(&request)?)));
+        let Some((cache, key)) = cached else {
+            return self
+                .create_uncached(request)
+                .await
+                .map(cache::CachedOr::Fresh);
+        };
+
+        if let Some(response) = cache.get(&key) {
+            crate::diag::debug!(
+                "Serving response {} from the client-side cache",
+                response.id
+            );
+            return Ok(cache::CachedOr::Cached(response));
         }
+
+        let response = self.create_uncached(request).await?;
+        cache.insert(key, response.clone());
+        Ok(cache::CachedOr::Fresh(response))
+    }
+
+    /// Creates a response with `background` set, returning a handle while it runs.
+    ///
+    /// [`Responses::create`] also accepts background requests: an HTTP 202 comes back
+    /// as a [`Response`](crate::Response) with status
+    /// [`Queued`](crate::types::ResponseStatus::Queued), to be polled with
+    /// [`Responses::retrieve`]. Use this method instead to get a
+    /// [`BackgroundHandle`](crate::types::BackgroundHandle) for
+    /// [`Responses::background_status`] and [`Responses::background_cancel`]. A 202 is
+    /// a success, so it is never retried.
+    ///
+    /// # Errors
+    ///
+    /// Returns the same errors as [`Responses::create`].
+    pub async fn create_background(&self, mut request: crate::Request) -> Result<CreateOutcome> {
+        use crate::types::{BackgroundHandle, BackgroundStatus, ResponseStatus};
+
+        request.background = Some(true);
+        let response = self.create_uncached(request).await?;
+        let status = match response.status {
+            ResponseStatus::Queued => BackgroundStatus::Queued,
+            ResponseStatus::InProgress => BackgroundStatus::Running,
+            _ => return Ok(CreateOutcome::Completed(Box::new(response))),
+        };
+        let status_url = self.endpoint.url(&["responses", &response.id])?;
+        let mut handle = BackgroundHandle::new(response.id, status_url.into());
+        handle.status = status;
+        Ok(CreateOutcome::Accepted(handle))
     }
 
     /// Retrieves a response by ID.
@@ -680,49 +1351,240 @@ impl Responses {
     ///
     /// Returns an error if the request fails to send or has a non-200 status code.
     pub async fn retrieve(&self, id: &str) -> Result<crate::Response> {
-        let response = self
+        let request = self.client.get(self.endpoint.url(&["responses", id])?);
+        let result = self.client.send_json(request).await;
+        if let Some(journal) = &self.journal {
+            journal.record_retrieve(id, &result);
+        }
+        result
+    }
+
+    /// Lists the input items that produced a stored response.
+    ///
+    /// Image URLs are omitted unless requested with
+    /// [`list_input_items_with`](Self::list_input_items_with) and
+    /// [`Include::MessageInputImageUrl`](crate::types::Include::MessageInputImageUrl).
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if `params` has an out-of-range limit, or if the request fails to send
+    /// or has a non-200 status code.
+    pub async fn list_input_items(
+        &self,
+        id: &str,
+        params: Option<crate::types::PaginationParams>,
+    ) -> Result<crate::types::PaginatedList<crate::ResponseItem>> {
+        self.list_input_items_with(id, params, &[]).await
+    }
+
+    /// Lists the input items that produced a stored response, with additional fields.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if `params` has an out-of-range limit, or if the request fails to send
+    /// or has a non-200 status code.
+    pub async fn list_input_items_with(
+        &self,
+        id: &str,
+        params: Option<crate::types::PaginationParams>,
+        include: &[crate::types::Include],
+    ) -> Result<crate::types::PaginatedList<crate::ResponseItem>> {
+        let mut request = self
             .client
-            .get(format!("{}/responses/{}", self.base_url, id))
-            .send()
-            .await
-            .map_err(crate::Error::Http)?;
+            .get(self.endpoint.url(&["responses", id, "input_items"])?);
+        if let Some(params) = params {
+            params.validate()?;
+            request = request.query(&params);
+        }
+        for field in include {
+            request = request.query(&[("include[]", field.as_str())]);
+        }
+        self.client.send_json(request).await
+    }
+
+    /// Retrieves `last_id` and every response before it through `previous_response_id`,
+    /// oldest first.
+    ///
+    /// # Errors
+    ///
+    /// Returns the first error from [`retrieve`](Self::retrieve).
+    pub async fn fetch_chain(&self, last_id: &str) -> Result<Vec<crate::Response>> {
+        let mut chain = self.fetch_chain_back(last_id, usize::MAX).await?;
+        chain.reverse();
+        Ok(chain)
+    }
+
+    /// Retrieves up to `max_depth` responses back from `last_id`, newest first
+    async fn fetch_chain_back(
+        &self,
+        last_id: &str,
+        max_depth: usize,
+    ) -> Result<Vec<crate::Response>> {
+        let mut chain = Vec::new();
+        let mut seen = std::collections::HashSet::new();
+        let mut next = Some(last_id.to_string());
+        while chain.len() < max_depth {
+            let Some(id) = next.take().filter(|id| seen.insert(id.clone())) else {
+                break;
+            };
+            let response = self.retrieve(&id).await?;
+            next.clone_from(&response.previous_response_id);
+            chain.push(response);
+        }
+        Ok(chain)
+    }
 
-        let response = try_parse_api_error(response).await?;
-        response.json().await.map_err(crate::Error::Http)
+    /// Sums the usage of `last_id` and the responses before it through
+    /// `previous_response_id`, for billing a whole conversation.
+    ///
+    /// At most `max_depth` responses are retrieved, newest first, so a very long chain
+    /// is only partly counted; compare [`UsageAggregate::responses`](crate::types::UsageAggregate::responses) with `max_depth` to
+    /// tell. Responses without usage are counted in
+    /// [`UsageAggregate::responses_without_usage`](crate::types::UsageAggregate::responses_without_usage).
+    ///
+    /// # Errors
+    ///
+    /// Returns the first error from [`retrieve`](Self::retrieve).
+    pub async fn aggregate_chain_usage(
+        &self,
+        last_id: &str,
+        max_depth: usize,
+    ) -> Result<crate::types::UsageAggregate> {
+        let chain = self.fetch_chain_back(last_id, max_depth).await?;
+        Ok(crate::types::UsageAggregate::from_responses(&chain))
+    }
+
+    /// Starts a stateless request for `model` that replays the chain ending at `last_id`.
+    ///
+    /// The returned builder has the model and the replayed history as input, without a
+    /// `previous_response_id`; append the next turn with
+    /// [`push_input_item`](crate::RequestBuilder::push_input_item). See
+    /// [`Input::from_responses`](crate::types::Input::from_responses) for what is replayed.
+    ///
+    /// # Errors
+    ///
+    /// Returns the first error from fetching the chain.
+    pub async fn replay_chain(
+        &self,
+        last_id: &str,
+        model: impl Into<crate::Model>,
+    ) -> Result<crate::RequestBuilder> {
+        let history = self.fetch_chain(last_id).await?;
+        Ok(crate::Request::builder()
+            .model(model)
+            .input_from_responses(&history))
     }
 
     /// Cancels a response that is being generated.
     ///
+    /// A 409 or 423 from the API means the response can no longer be cancelled; the
+    /// response is then retrieved and returned as [`CancelOutcome::AlreadyCompleted`].
+    /// A 404 is returned as [`CancelOutcome::NotFound`].
+    ///
     /// # Errors
     ///
-    /// Returns an error if the request fails to send or has a non-200 status code.
-    pub async fn cancel(&self, id: &str) -> Result<crate::Response> {
+    /// Returns an error if a request fails to send or has any other non-200 status code.
+    pub async fn cancel(&self, id: &str) -> Result<CancelOutcome> {
+        let request = self
+            .client
+            .post(self.endpoint.url(&["responses", id, "cancel"])?);
         let response = self
             .client
-            .post(format!("{}/responses/{}/cancel", self.base_url, id))
-            .send()
-            .await
-            .map_err(crate::Error::Http)?;
-
-        let response = try_parse_api_error(response).await?;
-        response.json().await.map_err(crate::Error::Http)
+            .send_allowing(
+                request,
+                &[
+                    StatusCode::NOT_FOUND,
+                    StatusCode::CONFLICT,
+                    StatusCode::LOCKED,
+                ],
+            )
+            .await?;
+        match response.status() {
+            StatusCode::NOT_FOUND => Ok(CancelOutcome::NotFound),
+            StatusCode::CONFLICT | StatusCode::LOCKED => {
+                crate::diag::debug!("Response {id} already finished, retrieving it");
+                Ok(CancelOutcome::AlreadyCompleted(self.retrieve(id).await?))
+            }
+            _ => Ok(CancelOutcome::Cancelled(self.client.json(response).await?)),
+        }
     }
 
     /// Deletes a response.
     ///
+    /// A 404, or a confirmation reporting `deleted: false`, is returned as
+    /// [`DeleteOutcome::NotFound`]; with [`Responses::with_strict_deletes`] a 404 is an
+    /// error instead. See [`Responses::delete_confirmed`] to keep the confirmation.
+    ///
     /// # Errors
     ///
     /// Returns an error if the request fails to send or has a non-200 status code.
-    pub async fn delete(&self, id: &str) -> Result<()> {
-        let response = self
-            .client
-            .delete(format!("{}/responses/{}", self.base_url, id))
-            .send()
-            .await
-            .map_err(crate::Error::Http)?;
+    pub async fn delete(&self, id: &str) -> Result<DeleteOutcome> {
+        Ok(self.delete_confirmed(id).await?.outcome())
+    }
 
-        try_parse_api_error(response).await?;
-        Ok(())
+    /// Deletes a response and returns the API's confirmation.
+    ///
+    /// A 404 is returned as [`DeleteConfirmation::NotFound`], or as an error with
+    /// [`Responses::with_strict_deletes`].
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the request fails to send, has a non-200 status code, or the
+    /// confirmation cannot be decoded.
+    pub async fn delete_confirmed(&self, id: &str) -> Result<DeleteConfirmation> {
+        let request = self.client.delete(self.endpoint.url(&["responses", id])?);
+        let allowed: &[StatusCode] = if self.strict_deletes {
+            &[]
+        } else {
+            &[StatusCode::NOT_FOUND]
+        };
+        let response = self.client.send_allowing(request, allowed).await?;
+        self.client.deletion(response).await
+    }
+
+    /// Polls the status of a background response at its handle's `status_url`.
+    ///
+    /// The URL may be absolute or relative to the base URL, but must be on the API host
+    /// so the API key is never sent elsewhere. Use [`BackgroundHandle::apply`] to update
+    /// the handle from the result.
+    ///
+    /// [`BackgroundHandle::apply`]: crate::types::BackgroundHandle::apply
+    ///
+    /// # Errors
+    ///
+    /// Returns [`Error::InvalidHandleUrl`](crate::Error::InvalidHandleUrl) if the status URL
+    /// is invalid or on another host, or an error if the request fails to send or has a
+    /// non-200 status code.
+    pub async fn background_status(
+        &self,
+        handle: &crate::types::BackgroundHandle,
+    ) -> Result<crate::types::BackgroundStatusResponse> {
+        let url = self.endpoint.resolve_same_origin(&handle.status_url)?;
+        self.client.send_json(self.client.get(url)).await
+    }
+
+    /// Retrieves the response behind a background handle, as [`Responses::retrieve`] does.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the request fails to send or has a non-200 status code.
+    pub async fn background_retrieve(
+        &self,
+        handle: &crate::types::BackgroundHandle,
+    ) -> Result<crate::Response> {
+        self.retrieve(&handle.id).await
+    }
+
+    /// Cancels a background response, as [`Responses::cancel`] does.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if a request fails to send or has an unexpected status code.
+    pub async fn background_cancel(
+        &self,
+        handle: &crate::types::BackgroundHandle,
+    ) -> Result<CancelOutcome> {
+        self.cancel(&handle.id).await
     }
 
     /// Creates a streaming response.
@@ -736,8 +1598,8 @@ impl Responses {
     fn convert_to_stream_error(error: &crate::Error) -> crate::Error {
         match error {
             crate::Error::BadGateway { retry_after, .. } => {
-                let retry_msg = if let Some(seconds) = retry_after {
-                    format!(" (retry in {seconds}s)")
+                let retry_msg = if let Some(delay) = retry_after {
+                    format!(" (retry in {delay:?})")
                 } else {
                     String::new()
                 };
@@ -746,24 +1608,24 @@ impl Responses {
                 ))
             }
             crate::Error::ServiceUnavailable { retry_after, .. } => {
-                let retry_msg = if let Some(seconds) = retry_after {
-                    format!(" (retry in {seconds}s)")
+                let retry_msg = if let Some(delay) = retry_after {
+                    format!(" (retry in {delay:?})")
                 } else {
                     String::new()
                 };
                 crate::Error::Stream(format!("Streaming failed: Service unavailable{retry_msg}"))
             }
             crate::Error::GatewayTimeout { retry_after, .. } => {
-                let retry_msg = if let Some(seconds) = retry_after {
-                    format!(" (retry in {seconds}s)")
+                let retry_msg = if let Some(delay) = retry_after {
+                    format!(" (retry in {delay:?})")
                 } else {
                     String::new()
                 };
                 crate::Error::Stream(format!("Streaming failed: Gateway timeout{retry_msg}"))
             }
             crate::Error::RateLimited { retry_after, .. } => {
-                let retry_msg = if let Some(seconds) = retry_after {
-                    format!(" (retry in {seconds}s)")
+                let retry_msg = if let Some(delay) = retry_after {
+                    format!(" (retry in {delay:?})")
                 } else {
                     String::new()
                 };
@@ -793,11 +1655,18 @@ impl Responses {
         }
     }
 
-    /// Processes a single line of streaming data
+    /// Parses one line of a stream body: `data: {...}`, a bare JSON line, or `data: [DONE]`.
+    ///
+    /// `None` for blank lines, SSE comments and lines that are not valid JSON. Useful for
+    /// proxies that read the body line by line; [`Responses::process_sse_event`] also
+    /// handles events whose data spans several lines.
     #[cfg(feature = "stream")]
-    fn process_stream_line(line: &str) -> Option<Result<crate::types::StreamEvent>> {
+    #[must_use]
+    pub fn process_stream_line(line: &str) -> Option<Result<crate::types::StreamEvent>> {
         let line = line.trim();
-        if line.is_empty() {
+        // Blank lines separate events; lines starting with `:` are SSE comments such as
+        // `: ping` keep-alives
+        if line.is_empty() || line.starts_with(':') {
             return None;
         }
 
@@ -812,95 +1681,350 @@ impl Responses {
         Self::parse_json_event(line)
     }
 
-    /// Parses JSON event data and returns stream event
+    /// Parses one whole SSE event, as separated by blank lines.
+    ///
+    /// Multi-line `data` fields are joined before parsing, and the `event` name is used
+    /// as the type when the payload has no `type` field. A block without `data` lines is
+    /// read as JSON lines instead, returning the first event found. [`Responses::stream`]
+    /// parses every event this way.
     #[cfg(feature = "stream")]
-    fn parse_json_event(data: &str) -> Option<Result<crate::types::StreamEvent>> {
-        match serde_json::from_str::<serde_json::Value>(data) {
-            Ok(event) => {
-                if let Some(result) = Self::parse_stream_event(&event) {
-                    return Some(Ok(result));
-                }
-                // Check if this was an error event and handle it appropriately
-                if let Some(event_type) = event.get("type").and_then(|t| t.as_str()) {
-                    if event_type == "response.error" {
-                        let error_msg = event
-                            .get("error")
-                            .and_then(|e| e.get("message"))
-                            .and_then(|m| m.as_str())
-                            .unwrap_or("Unknown streaming error");
-                        return Some(Err(crate::Error::Stream(format!(
-                            "Server-side streaming error: {error_msg}"
-                        ))));
+    #[must_use]
+    pub fn process_sse_event(block: &str) -> Option<Result<crate::types::StreamEvent>> {
+        Self::process_parsed_sse_event(crate::types::SseEvent::parse(block), block)
+    }
+
+    /// [`Responses::process_sse_event`] for a block whose event has already been parsed
+    #[cfg(feature = "stream")]
+    fn process_parsed_sse_event(
+        event: Option<crate::types::SseEvent>,
+        block: &str,
+    ) -> Option<Result<crate::types::StreamEvent>> {
+        let Some(event) = event else {
+            return block.lines().find_map(Self::process_stream_line);
+        };
+        let data = event.data.trim();
+        if data == "[DONE]" {
+            return Some(Ok(crate::types::StreamEvent::Done));
+        }
+        match event.event.as_deref() {
+            Some(name) if leading_event_type(data).is_none() => {
+                let mut value = match serde_json::from_str::<serde_json::Value>(data) {
+                    Ok(value) => value,
+                    Err(json_err) => {
+                        crate::diag::debug!(
+                            "Failed to parse JSON data: {data} (error: {json_err})"
+                        );
+                        return None;
                     }
+                };
+                if let Some(fields) = value.as_object_mut() {
+                    fields
+                        .entry("type")
+                        .or_insert_with(|| serde_json::Value::from(name));
                 }
-                None
+                Self::parse_stream_event(&value).map(Ok)
             }
+            _ => Self::parse_json_event(data),
+        }
+    }
+
+    /// Parses JSON event data and returns stream event
+    #[cfg(feature = "stream")]
+    fn parse_json_event(data: &str) -> Option<Result<crate::types::StreamEvent>> {
+        if let Some(event) = leading_event_type(data).and_then(|t| Self::parse_delta_event(t, data))
+        {
+            return Some(Ok(event));
+        }
+        match serde_json::from_str::<serde_json::Value>(data) {
+            Ok(event) => Self::parse_stream_event(&event).map(Ok),
             Err(json_err) => {
                 // Log JSON parsing errors but continue processing
-                log::debug!("Failed to parse JSON data: {data} (error: {json_err})");
+                crate::diag::debug!("Failed to parse JSON data: {data} (error: {json_err})");
                 None
             }
         }
     }
 
-    /// Creates a streaming response
+    /// Parses the delta events that make up most of a stream straight into their
+    /// variant, without building a `serde_json::Value`.
+    ///
+    /// `None` for other event types, or when the event does not have the expected shape,
+    /// in which case [`parse_stream_event`](Self::parse_stream_event) handles it.
+    #[cfg(feature = "stream")]
+    fn parse_delta_event(event_type: &str, data: &str) -> Option<crate::types::StreamEvent> {
+        use std::borrow::Cow;
+
+        #[derive(serde::Deserialize)]
+        struct DeltaEvent<'a> {
+            #[serde(borrow)]
+            delta: Option<Cow<'a, str>>,
+            #[serde(borrow)]
+            item_id: Option<Cow<'a, str>>,
+            #[serde(borrow)]
+            call_id: Option<Cow<'a, str>>,
+            #[serde(borrow)]
+            name: Option<Cow<'a, str>>,
+            output_index: Option<u64>,
+            content_index: Option<u64>,
+        }
+
+        if !matches!(
+            event_type,
+            "response.output_text.delta"
+                | "response.function_call_arguments.delta"
+                | "response.mcp_call_arguments.delta"
+        ) {
+            return None;
+        }
+        let event: DeltaEvent = serde_json::from_str(data).ok()?;
+        let index = |value: Option<u64>| value.and_then(|v| u32::try_from(v).ok()).unwrap_or(0);
+        let delta = event.delta?.into_owned();
+        Some(match event_type {
+            "response.output_text.delta" => crate::types::StreamEvent::TextDelta {
+                content: delta,
+                index: index(event.output_index),
+                content_index: index(event.content_index),
+            },
+            "response.function_call_arguments.delta" => crate::types::StreamEvent::ToolCallDelta {
+                id: event.item_id?.into_owned(),
+                call_id: event.call_id.map(Cow::into_owned),
+                name: event.name.map(Cow::into_owned),
+                content: delta,
+                index: index(event.output_index),
+            },
+            _ => crate::types::StreamEvent::McpCallArgumentsDelta {
+                id: event.item_id?.into_owned(),
+                delta,
+            },
+        })
+    }
+
+    /// Creates a streaming response, with the timeouts set by [`Responses::with_stream_options`]
     #[cfg(feature = "stream")]
     #[must_use]
-    #[allow(clippy::too_many_lines)]
-    pub fn stream(
+    pub fn stream(&self, request: crate::Request) -> crate::types::ResponseStream {
+        self.stream_with_options(request, self.stream_options)
+    }
+
+    /// Creates a streaming response and collects latency metrics while it is consumed.
+    ///
+    /// The returned [`MetricsHandle`](metrics::MetricsHandle) reports the time to the first
+    /// output delta, the total duration, delta counts and the number of events of each
+    /// type. The clock starts when the stream is first polled, which sends the request.
+    #[cfg(feature = "stream")]
+    #[must_use]
+    pub fn stream_with_metrics(
+        &self,
+        request: crate::Request,
+    ) -> (crate::types::ResponseStream, metrics::MetricsHandle) {
+        let handle = metrics::MetricsHandle::default();
+        let stream = metrics::instrument(self.stream(request), handle.clone());
+        (stream, handle)
+    }
+
+    /// Creates a streaming response with the given timeouts.
+    ///
+    /// If no data arrives within `idle_timeout`, or the stream runs past
+    /// `overall_timeout`, the stream yields an [`Error::Stream`](crate::Error::Stream)
+    /// and ends.
+    #[cfg(feature = "stream")]
+    #[must_use]
+    pub fn stream_with_options(
         &self,
         mut request: crate::Request,
-    ) -> std::pin::Pin<Box<dyn futures::Stream<Item = Result<crate::types::StreamEvent>> + Send>>
-    {
+        options: crate::types::StreamOptions,
+    ) -> crate::types::ResponseStream {
         // Ensure stream is set to true
         request.stream = Some(true);
+        request.reconcile_max_tokens();
+        match self.endpoint.url(&["responses"]) {
+            Ok(url) => self.stream_from(
+                StreamSource::Create {
+                    url,
+                    request: Box::new(request),
+                    rate_limiter: self.rate_limiter.clone(),
+                },
+                options,
+            ),
+            Err(error) => Box::pin(futures::stream::once(async { Err(error) })),
+        }
+    }
 
-        let url = format!("{}/responses", self.base_url);
+    /// Creates a streaming response, retrying the request that opens the stream under
+    /// the client's recovery policy.
+    ///
+    /// Errors before the first event are handled as in [`Responses::create_with_recovery`],
+    /// including clearing a missing `previous_response_id`. If that reset the session and
+    /// [`RecoveryPolicy::notify_on_reset`] is set, the stream starts with a
+    /// [`StreamEvent::SessionReset`](crate::types::StreamEvent::SessionReset) carrying the
+    /// reset message. [`Responses::last_recovery`] reports the full recovery info once
+    /// the stream has opened. Errors after that point are not retried.
+    #[cfg(feature = "stream")]
+    #[must_use]
+    pub fn stream_with_recovery(&self, request: crate::Request) -> crate::types::ResponseStream {
+        use futures::StreamExt;
+
+        let responses = self.clone();
+        let opened = async move {
+            let opened = responses.open_stream_with_recovery(request).await;
+            (responses, opened)
+        };
+        Box::pin(
+            futures::stream::once(opened).flat_map(|(responses, opened)| match opened {
+                Ok((source, recovery_info)) => {
+                    let notice = recovery_info
+                        .message
+                        .map(|message| Ok(crate::types::StreamEvent::SessionReset { message }));
+                    futures::stream::iter(notice)
+                        .chain(responses.stream_from(source, responses.stream_options))
+                        .left_stream()
+                }
+                Err(error) => futures::stream::once(async { Err(error) }).right_stream(),
+            }),
+        )
+    }
+
+    /// Runs the recovery loop until the request that opens a stream succeeds
+    #[cfg(feature = "stream")]
+    async fn open_stream_with_recovery(
+        &self,
+        mut request: crate::Request,
+    ) -> Result<(StreamSource, RecoveryInfo)> {
+        request.stream = Some(true);
+        request.reconcile_max_tokens();
+        let url = self.endpoint.url(&["responses"])?;
+        let mut trace = RecoveryTrace::default();
+        loop {
+            let source = StreamSource::Create {
+                url: url.clone(),
+                request: Box::new(request.clone()),
+                rate_limiter: self.rate_limiter.clone(),
+            };
+            let error = match source.open(&self.client).await {
+                Ok(response) => {
+                    let recovery_info = self
+                        .recovery_info(&trace)
+                        .unwrap_or_else(RecoveryInfo::none);
+                    self.set_last_recovery(Some(recovery_info.clone()));
+                    let source = StreamSource::Opened {
+                        request: Box::new(request),
+                        response: std::sync::Mutex::new(Some(response)),
+                    };
+                    return Ok((source, recovery_info));
+                }
+                Err(error) => error,
+            };
+            match self.handle_error_with_retry(error, &mut request, &mut trace) {
+                RetryDecision::Error(error) => {
+                    self.set_last_recovery(None);
+                    return Err(error);
+                }
+                RetryDecision::Continue { retry_delay } => {
+                    if let Some(error) = trace.last_error.as_ref() {
+                        if error.is_container_expired()
+                            && self.recovery_policy.recreate_expired_containers
+                        {
+                            self.recreate_expired_containers(&mut request).await;
+                        }
+                        retry::wait(error, retry_delay).await;
+                    }
+                }
+            }
+        }
+    }
+
+    /// Streams the events of a background response from its handle's `stream_url`.
+    ///
+    /// The stream goes through the same pipeline as [`Responses::stream`], including the
+    /// timeouts set by [`Responses::with_stream_options`]. If the handle has no
+    /// `stream_url`, or it is not on the API host, the stream yields a single error.
+    #[cfg(feature = "stream")]
+    #[must_use]
+    pub fn background_stream(
+        &self,
+        handle: &crate::types::BackgroundHandle,
+    ) -> crate::types::ResponseStream {
+        let url = handle
+            .stream_url
+            .as_deref()
+            .ok_or_else(|| {
+                crate::Error::InvalidHandleUrl(format!(
+                    "background response {} has no stream URL",
+                    handle.id
+                ))
+            })
+            .and_then(|url| self.endpoint.resolve_same_origin(url));
+        match url {
+            Ok(url) => self.stream_from(
+                StreamSource::Background {
+                    url,
+                    id: handle.id.clone(),
+                },
+                self.stream_options,
+            ),
+            Err(error) => Box::pin(futures::stream::once(async { Err(error) })),
+        }
+    }
+
+    /// Opens `source` on the first poll and turns its body into stream events
+    #[cfg(feature = "stream")]
+    #[allow(clippy::too_many_lines)]
+    fn stream_from(
+        &self,
+        source: StreamSource,
+        options: crate::types::StreamOptions,
+    ) -> crate::types::ResponseStream {
+        use futures::StreamExt;
+
+        #[cfg(feature = "tracing")]
+        let span = source.span();
         let client = self.client.clone();
+        let journal = self.journal.clone().map(|journal| {
+            let (fingerprint, request) = source.journal_fields(&journal);
+            Arc::new((journal, fingerprint, request))
+        });
+        // Shared by every poll of the stream below, which only needs it for the first one
+        let source = std::sync::Arc::new(source);
+        let last_event_id = self.last_event_id.clone();
+        let open_journal = journal.clone();
 
         // Create stream that handles the actual OpenAI Responses API streaming format
         // We use a tuple to track the response, response ID, and whether we've emitted the ResponseCreated event
         let stream = futures::stream::unfold((None, None, false), move |state| {
             let (mut response_opt, mut response_id_opt, mut response_id_emitted) = state;
-            let url = url.clone();
             let client = client.clone();
-            let request = request.clone();
+            let source = source.clone();
+            let journal = open_journal.clone();
+            let last_event_id = last_event_id.clone();
 
             async move {
                 if response_opt.is_none() {
+                    *last_event_id
+                        .lock()
+                        .unwrap_or_else(std::sync::PoisonError::into_inner) = None;
                     // Make the initial request
-                    let response = match client.post(&url).json(&request).send().await {
+                    let opened = source.open(&client).await;
+                    if let Some(journal) = journal.as_deref() {
+                        let (journal, fingerprint, request) = journal;
+                        journal.record_stream_open(
+                            fingerprint.clone(),
+                            request.clone(),
+                            opened.as_ref().map(|_| ()),
+                        );
+                    }
+                    let response = match opened {
                         Ok(response) => response,
-                        Err(e) => {
+                        Err(crate::Error::Http(e)) => {
                             return Some((
                                 Err(crate::Error::Stream(format!("Failed to send request: {e}"))),
                                 (None, None, false),
                             ));
                         }
-                    };
-
-                    // Check if response is OK
-                    if !response.status().is_success() {
-                        let status = response.status();
-
-                        // Use our enhanced error parsing for streaming responses
-                        match crate::error::try_parse_api_error(response).await {
-                            Ok(_) => {
-                                // This shouldn't happen since we already checked !is_success()
-                                return Some((
-                                    Err(crate::Error::Stream(format!(
-                                        "Unexpected success status after failure check: {status}"
-                                    ))),
-                                    (None, None, false),
-                                ));
-                            }
-                            Err(error) => {
-                                let stream_error = Self::convert_to_stream_error(&error);
-                                return Some((Err(stream_error), (None, None, false)));
-                            }
+                        Err(error) => {
+                            let stream_error = Self::convert_to_stream_error(&error);
+                            return Some((Err(stream_error), (None, None, false)));
                         }
-                    }
-
+                    };
                     // Extract response ID from HTTP headers
                     // OpenAI may provide it in headers like "openai-response-id" or "x-response-id"
                     let response_id = response
@@ -912,7 +2036,10 @@ impl Responses {
                         .map(ToString::to_string);
 
                     response_id_opt = response_id;
-                    response_opt = Some(response);
+                    // Read the body as a byte stream; unlike `Response::chunk` this is
+                    // also available on the wasm32 fetch backend
+                    let body = crate::types::sse::sse_events(Box::pin(response.bytes_stream()));
+                    response_opt = Some(body);
                 }
 
                 let Some(mut response) = response_opt.take() else {
@@ -939,13 +2066,13 @@ impl Responses {
                 }
 
                 // Read chunks from the response
-                let chunk_result = response.chunk().await;
+                let chunk_result = response.next().await;
 
                 // Put response back in state immediately after reading chunk
                 response_opt = Some(response);
 
                 match chunk_result {
-                    Ok(Some(chunk)) => {
+                    Some(Ok(chunk)) => {
                         // Convert chunk to string
                         let chunk_str = match std::str::from_utf8(&chunk) {
                             Ok(s) => s,
@@ -959,32 +2086,36 @@ impl Responses {
                             }
                         };
 
-                        // Process each line in the chunk
-                        for line in chunk_str.lines() {
-                            if let Some(result) = Self::process_stream_line(line) {
-                                match result {
-                                    Ok(event) => {
-                                        // Check if this event contains response ID (from response.created event)
-                                        if let crate::types::StreamEvent::ResponseCreated { id } =
-                                            &event
-                                        {
-                                            // Update our stored response ID if we got it from the stream
-                                            if response_id_opt.is_none() {
-                                                response_id_opt = Some(id.clone());
-                                            }
-                                            response_id_emitted = true;
+                        // Each chunk holds one whole event
+                        let sse_event = crate::types::SseEvent::parse(chunk_str);
+                        if let Some(id) = sse_event.as_ref().and_then(|event| event.id.clone()) {
+                            *last_event_id
+                                .lock()
+                                .unwrap_or_else(std::sync::PoisonError::into_inner) = Some(id);
+                        }
+                        if let Some(result) = Self::process_parsed_sse_event(sse_event, chunk_str) {
+                            match result {
+                                Ok(event) => {
+                                    // Check if this event contains response ID (from response.created event)
+                                    if let crate::types::StreamEvent::ResponseCreated { id } =
+                                        &event
+                                    {
+                                        // Update our stored response ID if we got it from the stream
+                                        if response_id_opt.is_none() {
+                                            response_id_opt = Some(id.clone());
                                         }
-                                        return Some((
-                                            Ok(event),
-                                            (
-                                                response_opt,
-                                                response_id_opt.clone(),
-                                                response_id_emitted,
-                                            ),
-                                        ));
+                                        response_id_emitted = true;
                                     }
-                                    Err(error) => return Some((Err(error), (None, None, false))),
+                                    return Some((
+                                        Ok(event),
+                                        (
+                                            response_opt,
+                                            response_id_opt.clone(),
+                                            response_id_emitted,
+                                        ),
+                                    ));
                                 }
+                                Err(error) => return Some((Err(error), (None, None, false))),
                             }
                         }
 
@@ -994,11 +2125,11 @@ impl Responses {
                             (response_opt, response_id_opt.clone(), response_id_emitted),
                         ))
                     }
-                    Ok(None) => {
+                    None => {
                         // End of stream
                         Some((Ok(crate::types::StreamEvent::Done), (None, None, false)))
                     }
-                    Err(e) => Some((
+                    Some(Err(e)) => Some((
                         Err(crate::Error::Stream(format!("Chunk read error: {e}"))),
                         (None, None, false),
                     )),
@@ -1006,23 +2137,184 @@ impl Responses {
             }
         });
 
-        Box::pin(stream)
+        let stream = Self::with_stream_timeouts(Box::pin(stream), options);
+
+        // Deltas and completions carry only the item ID; give them the call ID and name
+        let mut tool_call_ids = crate::types::stream::ToolCallIds::default();
+        let stream = stream.map(move |item| {
+            item.map(|mut event| {
+                tool_call_ids.fill(&mut event);
+                event
+            })
+        });
+
+        // End the stream after a terminal event instead of polling the exhausted body
+        // again, which would send the request a second time
+        let failures_as_errors = self.stream_failures_as_errors;
+        let stream = futures::stream::unfold(Some(stream), move |stream| {
+            let next = async move {
+                let mut stream = stream?;
+                let item = match stream.next().await? {
+                    Ok(event) if event.is_terminal() => {
+                        let item = match event {
+                            crate::types::StreamEvent::Failed { error } if failures_as_errors => {
+                                Err(error.into())
+                            }
+                            event => Ok(event),
+                        };
+                        return Some((item, None));
+                    }
+                    Ok(crate::types::StreamEvent::ResponseCreated { id }) => {
+                        crate::diag::record!("response_id", id.as_str());
+                        Ok(crate::types::StreamEvent::ResponseCreated { id })
+                    }
+                    Err(error) => {
+                        crate::diag::record!("error_class", error.classify().as_str());
+                        Err(error)
+                    }
+                    item => item,
+                };
+                Some((item, Some(stream)))
+            };
+            // Every poll runs inside the span, including the one that sends the request
+            #[cfg(feature = "tracing")]
+            let next = tracing::Instrument::instrument(next, span.clone());
+            next
+        });
+
+        let Some(journal) = journal else {
+            return Box::pin(stream);
+        };
+        Box::pin(stream.inspect(move |item| {
+            let (journal, fingerprint, _) = &*journal;
+            journal.record_stream_item(fingerprint.as_deref(), item);
+        }))
+    }
+
+    /// Cuts the stream short with an error once an idle or overall timeout passes
+    #[cfg(feature = "stream")]
+    fn with_stream_timeouts(
+        stream: crate::types::ResponseStream,
+        options: crate::types::StreamOptions,
+    ) -> crate::types::ResponseStream {
+        use futures::StreamExt;
+
+        if options.idle_timeout.is_none() && options.overall_timeout.is_none() {
+            return stream;
+        }
+        let started = crate::runtime::Stopwatch::start();
+        Box::pin(futures::stream::unfold(
+            Some(stream),
+            move |stream| async move {
+                let mut stream = stream?;
+                let overall_left = options
+                    .overall_timeout
+                    .map(|overall| overall.saturating_sub(started.elapsed()));
+                let Some(wait) = options.idle_timeout.into_iter().chain(overall_left).min() else {
+                    return stream.next().await.map(|item| (item, Some(stream)));
+                };
+
+                let Some(item) = crate::runtime::timeout(wait, stream.next()).await else {
+                    let message = match (options.idle_timeout, options.overall_timeout) {
+                        (Some(idle), _) if idle == wait => {
+                            format!("idle timeout after {}s", idle.as_secs_f64())
+                        }
+                        (_, Some(overall)) => {
+                            format!("overall timeout after {}s", overall.as_secs_f64())
+                        }
+                        (_, None) => format!("idle timeout after {}s", wait.as_secs_f64()),
+                    };
+                    return Some((Err(crate::Error::Stream(message)), None));
+                };
+                item.map(|item| (item, Some(stream)))
+            },
+        ))
+    }
+
+    /// Extracts the error of a `response.failed`, `response.error` or `error` event.
+    ///
+    /// The error object sits under `response.error`, under `error`, or (for `error`
+    /// events) at the top level of the event.
+    #[cfg(feature = "stream")]
+    fn parse_stream_failure(event: &serde_json::Value) -> crate::types::ResponseError {
+        let details = event
+            .pointer("/response/error")
+            .or_else(|| event.get("error"))
+            .filter(|details| details.is_object())
+            .unwrap_or(event);
+        let field = |name: &str| {
+            details
+                .get(name)
+                .and_then(|value| value.as_str())
+                .map(std::string::ToString::to_string)
+        };
+
+        crate::types::ResponseError {
+            code: field("code").unwrap_or_else(|| "unknown_error".to_string()),
+            message: field("message").unwrap_or_else(|| "Unknown streaming error".to_string()),
+            metadata: None,
+        }
+    }
+
+    /// Reads an index field such as `output_index`, defaulting to 0 when absent
+    #[cfg(feature = "stream")]
+    fn event_index(event: &serde_json::Value, field: &str) -> u32 {
+        Self::event_u32(event, field).unwrap_or(0)
+    }
+
+    /// Reads an optional `u32` field of an event
+    #[cfg(feature = "stream")]
+    fn event_u32(event: &serde_json::Value, field: &str) -> Option<u32> {
+        event
+            .get(field)
+            .and_then(serde_json::Value::as_u64)
+            .and_then(|value| u32::try_from(value).ok())
+    }
+
+    /// Reads an optional string field of an event or item
+    #[cfg(feature = "stream")]
+    fn event_str(value: &serde_json::Value, field: &str) -> Option<String> {
+        value
+            .get(field)
+            .and_then(|v| v.as_str())
+            .map(ToString::to_string)
+    }
+
+    /// Reads the call ID and function name of a legacy `tool_call` object.
+    ///
+    /// Legacy calls are answered under their `id` unless they carry a separate `call_id`.
+    #[cfg(feature = "stream")]
+    fn legacy_tool_call_ids(tool_call: &serde_json::Value) -> (Option<String>, Option<String>) {
+        let call_id =
+            Self::event_str(tool_call, "call_id").or_else(|| Self::event_str(tool_call, "id"));
+        let name = tool_call
+            .get("function")
+            .and_then(|f| Self::event_str(f, "name"));
+        (call_id, name)
     }
 
     #[cfg(feature = "stream")]
     #[allow(clippy::too_many_lines)]
     fn parse_stream_event(event: &serde_json::Value) -> Option<crate::types::StreamEvent> {
         if let Some(event_type) = event.get("type").and_then(|t| t.as_str()) {
+            let index = Self::event_index(event, "output_index");
             match event_type {
                 "response.output_text.delta" => {
                     if let Some(delta) = event.get("delta").and_then(|d| d.as_str()) {
                         let text_event = crate::types::StreamEvent::TextDelta {
                             content: delta.to_string(),
-                            index: 0, // Default index
+                            index,
+                            content_index: Self::event_index(event, "content_index"),
                         };
                         return Some(text_event);
                     }
                 }
+                "response.output_text.done" => {
+                    return Some(crate::types::StreamEvent::TextStop {
+                        index,
+                        content_index: Self::event_index(event, "content_index"),
+                    });
+                }
                 "response.created" => {
                     // Parse response.created event which contains the response ID
                     if let Some(response_data) = event.get("response") {
@@ -1042,29 +2334,29 @@ impl Responses {
                 "response.done" => {
                     return Some(crate::types::StreamEvent::Done);
                 }
-                "response.error" => {
-                    // Handle errors by logging them and returning None
-                    // The caller should handle this by checking for None and potentially stopping the stream
-                    if let Some(error_details) = event.get("error") {
-                        log::error!("Stream error event received: {error_details}");
-                    } else {
-                        log::error!("Stream error event received without details");
-                    }
-                    return None;
+                "response.failed" | "response.error" | "error" => {
+                    let error = Self::parse_stream_failure(event);
+                    crate::diag::error!("Stream failed with {}: {}", error.code, error.message);
+                    return Some(crate::types::StreamEvent::Failed { error });
+                }
+                "response.incomplete" => {
+                    let reason = event
+                        .pointer("/response/incomplete_details/reason")
+                        .and_then(|r| r.as_str())
+                        .map(std::string::ToString::to_string);
+                    return Some(crate::types::StreamEvent::Incomplete { reason });
                 }
                 "response.tool_call.created" => {
                     if let Some(tool_call) = event.get("tool_call") {
-                        if let (Some(id), Some(name)) = (
-                            tool_call.get("id").and_then(|i| i.as_str()),
-                            tool_call
-                                .get("function")
-                                .and_then(|f| f.get("name"))
-                                .and_then(|n| n.as_str()),
-                        ) {
+                        let (call_id, name) = Self::legacy_tool_call_ids(tool_call);
+                        if let (Some(id), Some(name)) =
+                            (tool_call.get("id").and_then(|i| i.as_str()), name)
+                        {
                             return Some(crate::types::StreamEvent::ToolCallCreated {
                                 id: id.to_string(),
-                                name: name.to_string(),
-                                index: 0, // Default index
+                                call_id,
+                                name,
+                                index,
                             });
                         }
                     }
@@ -1075,10 +2367,13 @@ impl Responses {
                             tool_call.get("id").and_then(|i| i.as_str()),
                             event.get("delta").and_then(|d| d.as_str()),
                         ) {
+                            let (call_id, name) = Self::legacy_tool_call_ids(tool_call);
                             return Some(crate::types::StreamEvent::ToolCallDelta {
                                 id: id.to_string(),
+                                call_id,
+                                name,
                                 content: delta.to_string(),
-                                index: 0, // Default index
+                                index,
                             });
                         }
                     }
@@ -1086,12 +2381,123 @@ impl Responses {
                 "response.tool_call.completed" => {
                     if let Some(tool_call) = event.get("tool_call") {
                         if let Some(id) = tool_call.get("id").and_then(|i| i.as_str()) {
+                            let (call_id, name) = Self::legacy_tool_call_ids(tool_call);
                             return Some(crate::types::StreamEvent::ToolCallCompleted {
                                 id: id.to_string(),
-                                index: 0, // Default index
+                                call_id,
+                                name,
+                                index,
+                            });
+                        }
+                    }
+                }
+                "response.function_call_arguments.delta" => {
+                    if let (Some(id), Some(delta)) = (
+                        event.get("item_id").and_then(|i| i.as_str()),
+                        event.get("delta").and_then(|d| d.as_str()),
+                    ) {
+                        return Some(crate::types::StreamEvent::ToolCallDelta {
+                            id: id.to_string(),
+                            call_id: Self::event_str(event, "call_id"),
+                            name: Self::event_str(event, "name"),
+                            content: delta.to_string(),
+                            index,
+                        });
+                    }
+                }
+                "response.function_call_arguments.done" => {
+                    if let Some(id) = event.get("item_id").and_then(|i| i.as_str()) {
+                        return Some(crate::types::StreamEvent::ToolCallCompleted {
+                            id: id.to_string(),
+                            call_id: Self::event_str(event, "call_id"),
+                            name: Self::event_str(event, "name"),
+                            index,
+                        });
+                    }
+                }
+                "response.output_item.added" | "response.mcp_call.in_progress" => {
+                    let item = event.get("item").unwrap_or(event);
+                    if item.get("type").and_then(|t| t.as_str()) == Some("function_call") {
+                        if let (Some(id), Some(name)) = (
+                            item.get("id").and_then(|i| i.as_str()),
+                            item.get("name").and_then(|n| n.as_str()),
+                        ) {
+                            return Some(crate::types::StreamEvent::ToolCallCreated {
+                                id: id.to_string(),
+                                call_id: Self::event_str(item, "call_id"),
+                                name: name.to_string(),
+                                index,
                             });
                         }
                     }
+                    let is_mcp_call = event_type == "response.mcp_call.in_progress"
+                        || item.get("type").and_then(|t| t.as_str()) == Some("mcp_call");
+                    let id = item.get("id").or_else(|| event.get("item_id"));
+                    if let (true, Some(id), Some(server_label), Some(name)) = (
+                        is_mcp_call,
+                        id.and_then(|i| i.as_str()),
+                        item.get("server_label").and_then(|l| l.as_str()),
+                        item.get("name").and_then(|n| n.as_str()),
+                    ) {
+                        return Some(crate::types::StreamEvent::McpCallStarted {
+                            id: id.to_string(),
+                            server_label: server_label.to_string(),
+                            name: name.to_string(),
+                        });
+                    }
+                    return Some(crate::types::StreamEvent::Unknown);
+                }
+                "response.mcp_call_arguments.delta" => {
+                    if let (Some(id), Some(delta)) = (
+                        event.get("item_id").and_then(|i| i.as_str()),
+                        event.get("delta").and_then(|d| d.as_str()),
+                    ) {
+                        return Some(crate::types::StreamEvent::McpCallArgumentsDelta {
+                            id: id.to_string(),
+                            delta: delta.to_string(),
+                        });
+                    }
+                }
+                "response.mcp_call.completed" | "response.mcp_call.failed" => {
+                    if let Some(id) = event.get("item_id").and_then(|i| i.as_str()) {
+                        let field = |name: &str| {
+                            let value = event
+                                .get(name)
+                                .or_else(|| event.get("item").and_then(|item| item.get(name)))?;
+                            value
+                                .as_str()
+                                .or_else(|| value.get("message").and_then(|m| m.as_str()))
+                                .map(std::string::ToString::to_string)
+                        };
+                        let id = id.to_string();
+                        return Some(if event_type == "response.mcp_call.completed" {
+                            crate::types::StreamEvent::McpCallCompleted {
+                                id,
+                                output: field("output"),
+                            }
+                        } else {
+                            crate::types::StreamEvent::McpCallFailed {
+                                id,
+                                error: field("error"),
+                            }
+                        });
+                    }
+                }
+                "response.output_item.done" | "response.mcp_list_tools.completed" => {
+                    let item = event.get("item").unwrap_or(event);
+                    let is_list_tools = event_type == "response.mcp_list_tools.completed"
+                        || item.get("type").and_then(|t| t.as_str()) == Some("mcp_list_tools");
+                    if let (true, Some(tools)) = (is_list_tools, item.get("tools")) {
+                        match serde_json::from_value(tools.clone()) {
+                            Ok(tools) => {
+                                return Some(crate::types::StreamEvent::McpListToolsCompleted {
+                                    tools,
+                                });
+                            }
+                            Err(error) => crate::diag::debug!("Failed to parse MCP tools: {error}"),
+                        }
+                    }
+                    return Some(crate::types::StreamEvent::Unknown);
                 }
                 "response.image.progress" => {
                     if let Some(image_data) = event.get("image") {
@@ -1099,30 +2505,74 @@ impl Responses {
                             .get("url")
                             .and_then(|u| u.as_str())
                             .map(std::string::ToString::to_string);
-                        let index = u32::try_from(
-                            image_data
-                                .get("index")
-                                .and_then(serde_json::Value::as_u64)
-                                .unwrap_or(0),
-                        )
-                        .unwrap_or(0);
-                        return Some(crate::types::StreamEvent::ImageProgress { url, index });
+                        let index = Self::event_index(image_data, "index");
+                        return Some(crate::types::StreamEvent::ImageProgress {
+                            url,
+                            index,
+                            partial_image_b64: None,
+                            partial_image_index: Self::event_u32(image_data, "partial_image_index"),
+                        });
+                    }
+                }
+                "response.image_generation_call.partial_image" => {
+                    if let Some(b64) = Self::event_str(event, "partial_image_b64") {
+                        return Some(crate::types::StreamEvent::ImageProgress {
+                            url: Self::event_str(event, "url"),
+                            index,
+                            partial_image_b64: Some(b64),
+                            partial_image_index: Self::event_u32(event, "partial_image_index"),
+                        });
                     }
                 }
                 _ => {
                     // Log unknown event types for debugging
-                    log::debug!("Unknown stream event type: {event_type}");
+                    crate::diag::debug!("Unknown stream event type: {event_type}");
                     return Some(crate::types::StreamEvent::Unknown);
                 }
             }
         }
 
         // If we can't parse the event, log it for debugging
-        log::debug!("Failed to parse stream event: {event}");
+        crate::diag::debug!("Failed to parse stream event: {event}");
         None
     }
 }
 
+/// Reads the `type` of a JSON event when it is the first field, as the API sends it,
+/// without parsing the rest of the event
+#[cfg(feature = "stream")]
+fn leading_event_type(data: &str) -> Option<&str> {
+    let rest = data
+        .strip_prefix('{')?
+        .trim_start()
+        .strip_prefix("\"type\"")?;
+    let rest = rest
+        .trim_start()
+        .strip_prefix(':')?
+        .trim_start()
+        .strip_prefix('"')?;
+    rest.split_once('"').map(|(event_type, _)| event_type)
+}
+
+impl crate::EndpointBuilder<Responses> {
+    /// Builds a standalone Responses API client.
+    ///
+    /// # Errors
+    ///
+    /// Returns `CreateError::InvalidBaseUrl` if the base URL is not a valid http or https
+    /// URL, `CreateError::ApiKeyNotFound` if neither an API key nor an HTTP client was
+    /// given and `OPENAI_API_KEY` is not set, or `CreateError::InvalidApiKey` if the key
+    /// is invalid
+    pub fn build(self) -> std::result::Result<Responses, crate::CreateError> {
+        let (client, endpoint, recovery_policy) = self.into_parts()?;
+        Ok(Responses::new_with_recovery(
+            client,
+            endpoint,
+            recovery_policy,
+        ))
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -1200,31 +2650,2457 @@ mod tests {
     }
 
     #[tokio::test]
-    async fn container_only_scope_does_not_retry_transient_http_errors() {
-        let client = reqwest::Client::builder()
-            .connect_timeout(Duration::from_millis(100))
-            .build()
-            .expect("failed to construct client");
+    async fn create_retries_transient_errors_with_container_retry_disabled() {
+        let mut server = mockito::Server::new_async().await;
+        let bad_gateway = server
+            .mock("POST", "/responses")
+            .with_status(502)
+            .with_header("retry-after", "0")
+            .expect(1)
+            .create_async()
+            .await;
+        let success = server
+            .mock("POST", "/responses")
+            .with_status(200)
+            .with_body(response_body("resp_retried", "completed"))
+            .expect(1)
+            .create_async()
+            .await;
 
-        let policy = RecoveryPolicy::aggressive()
-            .with_logging(false)
-            .with_retry_scope(RetryScope::ContainerOnly);
+        let policy = RecoveryPolicy::new()
+            .with_auto_retry(false)
+            .with_retry_scope(RetryScope::TransientOnly)
+            .with_max_retries(2)
+            .with_logging(false);
+        let responses = Responses::new_with_recovery(reqwest::Client::new(), server.url(), policy);
 
-        let responses =
-            Responses::new_with_recovery(client, "http://127.0.0.1:9".to_string(), policy);
+        let response = responses
+            .create(crate::Request::default())
+            .await
+            .expect("expected the 502 to be retried");
+        assert_eq!(response.id, "resp_retried");
+        bad_gateway.assert_async().await;
+        success.assert_async().await;
+    }
 
-        let error = responses
+    #[derive(Debug, Default)]
+    struct RecordingInterceptor {
+        requests: std::sync::Mutex<Vec<crate::interceptor::RequestContext>>,
+        responses: std::sync::Mutex<Vec<crate::interceptor::ResponseContext>>,
+    }
+
+    impl crate::Interceptor for RecordingInterceptor {
+        fn on_request(&self, context: &crate::interceptor::RequestContext) {
+            self.requests.lock().unwrap().push(context.clone());
+        }
+
+        fn on_response(&self, context: &crate::interceptor::ResponseContext) {
+            self.responses.lock().unwrap().push(context.clone());
+        }
+    }
+
+    #[tokio::test]
+    async fn interceptors_observe_each_retry_attempt() {
+        let mut server = mockito::Server::new_async().await;
+        let bad_gateway = server
+            .mock("POST", "/responses")
+            .with_status(502)
+            .with_header("retry-after", "0")
+            .with_header("x-request-id", "req_first")
+            .expect(1)
+            .create_async()
+            .await;
+        let success = server
+            .mock("POST", "/responses")
+            .with_status(200)
+            .with_header("x-request-id", "req_second")
+            .with_body(
+                r#"{"id":"resp_1","object":"response","created_at":1700000000,"model":"gpt-4o","status":"completed","output":[]}"#,
+            )
+            .expect(1)
+            .create_async()
+            .await;
+
+        let recorder = Arc::new(RecordingInterceptor::default());
+        let client = crate::Client::builder()
+            .http_client(reqwest::Client::new())
+            .base_url(server.url())
+            .recovery_policy(RecoveryPolicy::default().with_logging(false))
+            .interceptor(recorder.clone())
+            .build()
+            .expect("failed to build client");
+
+        let response = client
+            .responses
             .create(crate::Request::default())
             .await
-            .expect_err("expected transient http error");
+            .expect("expected retry to succeed");
+        assert_eq!(response.id, "resp_1");
+        bad_gateway.assert_async().await;
+        success.assert_async().await;
 
-        if let crate::Error::Http(ref http_error) = error {
-            assert!(
-                http_error.is_connect() || http_error.is_timeout(),
-                "unexpected http error variant: {http_error:?}"
-            );
-        } else {
-            panic!("expected http error, got {error:?}");
+        let requests = recorder.requests.lock().unwrap();
+        let attempts: Vec<u32> = requests.iter().map(|r| r.attempt).collect();
+        assert_eq!(attempts, vec![1, 2]);
+        assert!(requests
+            .iter()
+            .all(|r| r.method == reqwest::Method::POST && r.path == "/responses"));
+
+        let responses = recorder.responses.lock().unwrap();
+        assert_eq!(responses.len(), 2);
+        assert_eq!(responses[0].attempt, 1);
+        assert_eq!(responses[0].status, Some(reqwest::StatusCode::BAD_GATEWAY));
+        assert_eq!(responses[0].request_id.as_deref(), Some("req_first"));
+        assert_eq!(
+            responses[0].error_class,
+            Some(crate::ErrorClass::RetryableServer)
+        );
+        assert!(!responses[0].is_success());
+        assert_eq!(responses[1].attempt, 2);
+        assert_eq!(responses[1].status, Some(reqwest::StatusCode::OK));
+        assert_eq!(responses[1].request_id.as_deref(), Some("req_second"));
+        assert_eq!(responses[1].error_class, None);
+        assert!(responses[1].is_success());
+    }
+
+    #[tokio::test]
+    async fn service_tier_is_sent_and_echoed() {
+        let mut server = mockito::Server::new_async().await;
+        let mock = server
+            .mock("POST", "/responses")
+            .match_body(mockito::Matcher::PartialJson(serde_json::json!({
+                "service_tier": "flex"
+            })))
+            .with_status(200)
+            .with_body(
+                r#"{"id":"resp_1","object":"response","created_at":1700000000,"model":"o3","status":"completed","output":[],"service_tier":"flex"}"#,
+            )
+            .expect(1)
+            .create_async()
+            .await;
+
+        let responses = Responses::new(reqwest::Client::new(), server.url());
+        let request = crate::Request::builder()
+            .model("o3")
+            .input("hi")
+            .service_tier(crate::types::ServiceTier::Flex)
+            .build();
+        let response = responses.create_no_recovery(request).await.unwrap();
+        assert_eq!(response.service_tier, Some(crate::types::ServiceTier::Flex));
+        mock.assert_async().await;
+    }
+
+    #[tokio::test]
+    async fn non_streaming_create_strips_or_rejects_stream_flag() {
+        let mut server = mockito::Server::new_async().await;
+        let mock = server
+            .mock("POST", "/responses")
+            .match_request(|request| {
+                let body: serde_json::Value =
+                    serde_json::from_slice(request.body().unwrap()).unwrap();
+                body.get("stream").is_none()
+            })
+            .with_status(200)
+            .with_body(
+                r#"{"id":"resp_1","object":"response","created_at":1700000000,"model":"gpt-4o","status":"completed","output":[]}"#,
+            )
+            .expect(2)
+            .create_async()
+            .await;
+
+        let responses = Responses::new(reqwest::Client::new(), server.url());
+        let request = crate::Request::builder()
+            .model("gpt-4o")
+            .input("hi")
+            .stream(false)
+            .build();
+        responses
+            .create_with_recovery(request.clone())
+            .await
+            .unwrap();
+        responses.create_no_recovery(request).await.unwrap();
+        mock.assert_async().await;
+
+        let streaming = crate::Request::builder()
+            .model("gpt-4o")
+            .input("hi")
+            .stream(true)
+            .build();
+        let error = responses.create(streaming).await.unwrap_err();
+        assert!(
+            matches!(
+                error,
+                crate::Error::Validation(crate::types::ValidationError::StreamingNotSupported)
+            ),
+            "{error:?}"
+        );
+    }
+
+    #[tokio::test]
+    async fn retry_transform_adjusts_request_per_attempt() {
+        let keys = Arc::new(std::sync::Mutex::new(Vec::new()));
+        let record_key = |keys: &Arc<std::sync::Mutex<Vec<String>>>| {
+            let keys = keys.clone();
+            move |request: &mockito::Request| {
+                // Matchers may run more than once per request, so keep each key once
+                let key = request.header("idempotency-key")[0].to_str().unwrap();
+                let mut keys = keys.lock().unwrap();
+                if !keys.iter().any(|seen| seen == key) {
+                    keys.push(key.to_string());
+                }
+                true
+            }
+        };
+        let mut server = mockito::Server::new_async().await;
+        let first = server
+            .mock("POST", "/responses")
+            .match_body(mockito::Matcher::PartialJson(serde_json::json!({
+                "max_output_tokens": 400,
+                "metadata": {"attempt": "1"}
+            })))
+            .match_request(record_key(&keys))
+            .with_status(503)
+            .with_header("retry-after", "0")
+            .expect(1)
+            .create_async()
+            .await;
+        let second = server
+            .mock("POST", "/responses")
+            .match_body(mockito::Matcher::PartialJson(serde_json::json!({
+                "max_output_tokens": 200,
+                "metadata": {"attempt": "2"}
+            })))
+            .match_request(record_key(&keys))
+            .with_status(200)
+            .with_body(
+                r#"{"id":"resp_1","object":"response","created_at":1700000000,"model":"gpt-4o","status":"completed","output":[]}"#,
+            )
+            .expect(1)
+            .create_async()
+            .await;
+
+        let policy = RecoveryPolicy::default()
+            .with_logging(false)
+            .with_retry_transform(|request, attempt| {
+                request.max_output_tokens = request.max_output_tokens.map(|tokens| tokens / 2);
+                request
+                    .metadata
+                    .get_or_insert_with(crate::types::Metadata::new)
+                    .insert("attempt", attempt.to_string())
+                    .unwrap();
+            });
+        let responses = Responses::new_with_recovery(reqwest::Client::new(), server.url(), policy);
+        let request = crate::Request::builder()
+            .model("gpt-4o")
+            .input("hi")
+            .max_output_tokens(400)
+            .metadata_entry("attempt", "1")
+            .build();
+
+        let result = responses.create_with_recovery(request).await.unwrap();
+        assert_eq!(result.recovery_info.retry_count, 1);
+        first.assert_async().await;
+        second.assert_async().await;
+
+        // The transformed body is a different request, so it must not reuse the first key
+        let keys = keys.lock().unwrap().clone();
+        let [first_key, second_key] = keys.as_slice() else {
+            panic!("expected a distinct key per attempt, got {keys:?}");
+        };
+        assert_ne!(first_key, second_key);
+        assert_eq!(result.idempotency_key(), Some(second_key.as_str()));
+    }
+
+    #[tokio::test]
+    async fn retried_create_reuses_idempotency_key() {
+        fn echo_key(request: &mockito::Request) -> String {
+            request.header("idempotency-key")[0]
+                .to_str()
+                .unwrap()
+                .to_string()
         }
+
+        let mut server = mockito::Server::new_async().await;
+        let uuid = mockito::Matcher::Regex(
+            "^[0-9a-f]{8}-[0-9a-f]{4}-4[0-9a-f]{3}-[89ab][0-9a-f]{3}-[0-9a-f]{12}$".to_string(),
+        );
+        // The first attempt times out at the gateway; echo its key back via x-request-id
+        let timeout = server
+            .mock("POST", "/responses")
+            .match_header("idempotency-key", uuid.clone())
+            .with_status(504)
+            .with_header("retry-after", "0")
+            .with_header_from_request("x-request-id", echo_key)
+            .expect(1)
+            .create_async()
+            .await;
+        // The retry succeeds; echo its key back as the response id
+        let success = server
+            .mock("POST", "/responses")
+            .match_header("idempotency-key", uuid)
+            .with_status(200)
+            .with_body_from_request(|request| {
+                format!(
+                    r#"{{"id":"{}","object":"response","created_at":1700000000,"model":"gpt-4o","status":"completed","output":[]}}"#,
+                    echo_key(request)
+                )
+                .into_bytes()
+            })
+            .expect(1)
+            .create_async()
+            .await;
+
+        let recorder = Arc::new(RecordingInterceptor::default());
+        let client = crate::Client::builder()
+            .http_client(reqwest::Client::new())
+            .base_url(server.url())
+            .recovery_policy(RecoveryPolicy::default().with_logging(false))
+            .interceptor(recorder.clone())
+            .build()
+            .expect("failed to build client");
+
+        let result = client
+            .responses
+            .create_with_recovery(crate::Request::default())
+            .await
+            .expect("expected retry to succeed");
+        timeout.assert_async().await;
+        success.assert_async().await;
+
+        let key = result.idempotency_key().expect("key should be exposed");
+        assert_eq!(result.recovery_info.retry_count, 1);
+        assert_eq!(result.response.id, key);
+        let first_attempt_key = recorder.responses.lock().unwrap()[0].request_id.clone();
+        assert_eq!(first_attempt_key.as_deref(), Some(key));
+    }
+
+    #[tokio::test]
+    async fn fingerprint_idempotency_keys_repeat_for_identical_requests() {
+        let request = crate::Request::builder()
+            .model("gpt-4o")
+            .input("Hi")
+            .build();
+        let expected = crate::types::canonical::to_hex(&request.fingerprint());
+
+        let mut server = mockito::Server::new_async().await;
+        let mock = server
+            .mock("POST", "/responses")
+            .match_header("idempotency-key", expected.as_str())
+            .with_status(200)
+            .with_body(r#"{"id":"resp_1","object":"response","created_at":1700000000,"model":"gpt-4o","status":"completed","output":[]}"#)
+            .expect(2)
+            .create_async()
+            .await;
+        let responses = Responses::new_with_recovery(
+            reqwest::Client::new(),
+            server.url(),
+            RecoveryPolicy::default().with_fingerprint_idempotency_key(true),
+        );
+
+        for _ in 0..2 {
+            let result = responses
+                .create_with_recovery(request.clone())
+                .await
+                .unwrap();
+            assert_eq!(result.idempotency_key(), Some(expected.as_str()));
+        }
+        mock.assert_async().await;
+    }
+
+    fn image_response_body() -> String {
+        let image = "A".repeat(4096);
+        format!(
+            r#"{{"id":"resp_img","object":"response","created_at":1700000000,"model":"gpt-4o","status":"completed","output":[{{"type":"image_generation_call","id":"ig_1","result":"{image}","status":"completed"}},{{"type":"message","id":"msg_1","role":"assistant","status":"completed","content":[{{"type":"output_text","text":"Here it is","annotations":[]}}]}}]}}"#
+        )
+    }
+
+    #[tokio::test]
+    async fn oversized_response_bodies_are_rejected() {
+        let body = image_response_body();
+        let body_len = body.len() as u64;
+        let mut server = mockito::Server::new_async().await;
+        server
+            .mock("POST", "/responses")
+            .match_body(mockito::Matcher::PartialJson(
+                serde_json::json!({"input": "sized"}),
+            ))
+            .with_status(200)
+            .with_body(&body)
+            .create_async()
+            .await;
+        server
+            .mock("POST", "/responses")
+            .match_body(mockito::Matcher::PartialJson(
+                serde_json::json!({"input": "chunked"}),
+            ))
+            .with_status(200)
+            .with_chunked_body(move |writer| {
+                for chunk in body.as_bytes().chunks(1024) {
+                    writer.write_all(chunk)?;
+                }
+                Ok(())
+            })
+            .create_async()
+            .await;
+
+        let client = |limit| {
+            crate::Client::builder()
+                .http_client(reqwest::Client::new())
+                .base_url(server.url())
+                .max_response_body_bytes(limit)
+                .build()
+                .unwrap()
+        };
+        let request = |input: &str| {
+            crate::Request::builder()
+                .model("gpt-4o")
+                .input(input)
+                .build()
+        };
+
+        // Content-Length over the limit fails before the body is read
+        let error = client(1000)
+            .responses
+            .create_no_recovery(request("sized"))
+            .await
+            .unwrap_err();
+        assert!(
+            matches!(error, crate::Error::ResponseTooLarge { limit: 1000, actual } if actual == body_len),
+            "{error:?}"
+        );
+
+        // Without Content-Length, reading stops once the limit is passed
+        let error = client(2000)
+            .responses
+            .create_raw(request("chunked"))
+            .await
+            .unwrap_err();
+        assert!(
+            matches!(error, crate::Error::ResponseTooLarge { limit: 2000, actual } if actual > 2000 && actual < body_len),
+            "{error:?}"
+        );
+
+        let response = client(body_len)
+            .responses
+            .create_no_recovery(request("chunked"))
+            .await
+            .unwrap();
+        assert_eq!(response.output_text(), "Here it is");
+    }
+
+    #[tokio::test]
+    async fn lite_responses_skip_image_results() {
+        let body = image_response_body();
+        let mut server = mockito::Server::new_async().await;
+        server
+            .mock("POST", "/responses")
+            .with_status(200)
+            .with_body(&body)
+            .expect(2)
+            .create_async()
+            .await;
+
+        let responses = Responses::new(reqwest::Client::new(), server.url());
+        let request = crate::Request::builder()
+            .model("gpt-4o")
+            .input("Draw a cat")
+            .build();
+        let raw = responses.create_raw(request.clone()).await.unwrap();
+        assert_eq!(raw, body.as_bytes());
+
+        let full: crate::Response = serde_json::from_slice(&raw).unwrap();
+        let lite = crate::Response::from_slice_lite(&raw).unwrap();
+        let image_result = |response: &crate::Response| match &response.output[0] {
+            crate::ResponseItem::ImageGenerationCall { result, .. } => result.len(),
+            other => panic!("unexpected item {other:?}"),
+        };
+        assert_eq!(image_result(&full), 4096);
+        assert_eq!(image_result(&lite), 0);
+        assert_eq!(lite.output_text(), "Here it is");
+        assert_eq!(lite.output[1], full.output[1]);
+        // The flag does not leak into later deserializations
+        let again: crate::Response = serde_json::from_slice(&raw).unwrap();
+        assert_eq!(again, full);
+
+        let created = responses
+            .with_lite_responses(true)
+            .create(request)
+            .await
+            .unwrap();
+        assert_eq!(image_result(&created), 0);
+    }
+
+    #[tokio::test]
+    async fn idempotency_key_can_be_disabled() {
+        let mut server = mockito::Server::new_async().await;
+        let mock = server
+            .mock("POST", "/responses")
+            .match_header("idempotency-key", mockito::Matcher::Missing)
+            .with_status(200)
+            .with_body(
+                r#"{"id":"resp_1","object":"response","created_at":1700000000,"model":"gpt-4o","status":"completed","output":[]}"#,
+            )
+            .expect(1)
+            .create_async()
+            .await;
+
+        let policy = RecoveryPolicy::default()
+            .with_logging(false)
+            .with_idempotency_key(false);
+        let responses = Responses::new_with_recovery(reqwest::Client::new(), server.url(), policy);
+
+        let result = responses
+            .create_with_recovery(crate::Request::default())
+            .await
+            .expect("expected success");
+        assert_eq!(result.idempotency_key(), None);
+        mock.assert_async().await;
+    }
+
+    #[tokio::test]
+    async fn container_only_scope_does_not_retry_transient_http_errors() {
+        let client = reqwest::Client::builder()
+            .connect_timeout(Duration::from_millis(100))
+            .build()
+            .expect("failed to construct client");
+
+        let policy = RecoveryPolicy::aggressive()
+            .with_logging(false)
+            .with_retry_scope(RetryScope::ContainerOnly);
+
+        let responses =
+            Responses::new_with_recovery(client, "http://127.0.0.1:9".to_string(), policy);
+
+        let error = responses
+            .create(crate::Request::default())
+            .await
+            .expect_err("expected transient http error");
+
+        if let crate::Error::Http(ref http_error) = error {
+            assert!(
+                http_error.is_connect() || http_error.is_timeout(),
+                "unexpected http error variant: {http_error:?}"
+            );
+        } else {
+            panic!("expected http error, got {error:?}");
+        }
+    }
+
+    #[tokio::test(start_paused = true)]
+    async fn expired_named_container_is_recreated_before_retry() {
+        use mockito::Matcher;
+
+        let mut server = mockito::Server::new_async().await;
+        let expired = server
+            .mock("POST", "/responses")
+            .match_body(Matcher::PartialJson(serde_json::json!({
+                "tools": [{"type": "code_interpreter", "container": "cntr_old"}]
+            })))
+            .with_status(404)
+            .with_body(
+                r#"{"error":{"message":"Container is expired","type":"invalid_request_error"}}"#,
+            )
+            .expect(1)
+            .create_async()
+            .await;
+        server
+            .mock("GET", "/containers/cntr_old")
+            .with_status(200)
+            .with_body(
+                r#"{"id":"cntr_old","object":"container","name":"analysis","created_at":1700000000,"status":"expired"}"#,
+            )
+            .create_async()
+            .await;
+        let recreate = server
+            .mock("POST", "/containers")
+            .match_body(Matcher::Json(serde_json::json!({"name": "analysis"})))
+            .with_status(200)
+            .with_body(
+                r#"{"id":"cntr_new","object":"container","name":"analysis","created_at":1700000100,"status":"running"}"#,
+            )
+            .expect(1)
+            .create_async()
+            .await;
+        let retried = server
+            .mock("POST", "/responses")
+            .match_body(Matcher::PartialJson(serde_json::json!({
+                "tools": [{"type": "code_interpreter", "container": "cntr_new"}]
+            })))
+            .with_status(200)
+            .with_body(r#"{"id":"resp_1","object":"response","created_at":1700000000,"model":"gpt-4o","status":"completed","output":[]}"#)
+            .expect(1)
+            .create_async()
+            .await;
+
+        let policy = RecoveryPolicy::new().with_recreate_expired_containers(true);
+        let responses = Responses::new_with_recovery(reqwest::Client::new(), server.url(), policy);
+        let request = crate::Request::builder()
+            .model("gpt-4o")
+            .input("Plot the data")
+            .tools(vec![crate::types::Tool::code_interpreter(Some(
+                crate::types::Container::id("cntr_old"),
+            ))])
+            .build();
+        let result = responses.create_with_recovery(request).await.unwrap();
+        assert_eq!(result.response.id, "resp_1");
+        assert_eq!(result.recovery_info.retry_count, 1);
+
+        for mock in [expired, recreate, retried] {
+            mock.assert_async().await;
+        }
+    }
+
+    /// Creates a request that fails with an expired container, then succeeds once
+    /// `previous_response_id` is gone, reporting every recovery event it sees
+    async fn recover_from_container_expiry(
+        auto_prune: bool,
+    ) -> (RecoveryInfo, Vec<(crate::ErrorClass, bool, Option<String>)>) {
+        use mockito::Matcher;
+
+        let mut server = mockito::Server::new_async().await;
+        let expired = server
+            .mock("POST", "/responses")
+            .match_body(Matcher::PartialJson(
+                serde_json::json!({"previous_response_id": "resp_old"}),
+            ))
+            .with_status(404)
+            .with_body(
+                r#"{"error":{"message":"Container is expired","type":"invalid_request_error"}}"#,
+            )
+            .expect(1)
+            .create_async()
+            .await;
+        let retried = server
+            .mock("POST", "/responses")
+            .with_status(200)
+            .with_body(r#"{"id":"resp_new","object":"response","created_at":1700000000,"model":"gpt-4o","status":"completed","output":[]}"#)
+            .expect(1)
+            .create_async()
+            .await;
+
+        let events = Arc::new(std::sync::Mutex::new(Vec::new()));
+        let seen = events.clone();
+        let policy = RecoveryPolicy::new().with_auto_prune(auto_prune);
+        let responses = Responses::new_with_recovery(reqwest::Client::new(), server.url(), policy)
+            .with_recovery_event_callback(Box::new(move |event| {
+                assert_eq!(event.retry_count, 1);
+                assert!(event.error.is_container_expired());
+                seen.lock().unwrap().push((
+                    event.error_class,
+                    event.context_pruned,
+                    event.cleared_previous_response_id.map(ToString::to_string),
+                ));
+            }));
+        let request = crate::Request::builder()
+            .model("gpt-4o")
+            .input("Continue the analysis")
+            .previous_response_id("resp_old")
+            .build();
+        let result = responses.create_with_recovery(request).await.unwrap();
+        assert_eq!(result.response.id, "resp_new");
+        expired.assert_async().await;
+        retried.assert_async().await;
+
+        let events = events.lock().unwrap().clone();
+        (result.recovery_info, events)
+    }
+
+    #[tokio::test(start_paused = true)]
+    async fn recovery_info_reports_pruned_context_after_container_expiry() {
+        let (info, events) = recover_from_container_expiry(true).await;
+        assert!(info.successful);
+        assert!(info.context_pruned);
+        assert_eq!(
+            info.cleared_previous_response_id.as_deref(),
+            Some("resp_old")
+        );
+        assert_eq!(info.error_class, Some(crate::ErrorClass::ContainerExpired));
+        // Container expiry is retried without waiting
+        assert_eq!(info.total_delay, Duration::ZERO);
+        assert_eq!(
+            events,
+            [(
+                crate::ErrorClass::ContainerExpired,
+                true,
+                Some("resp_old".to_string())
+            )]
+        );
+
+        // Without auto-prune the ID is still cleared, but no context is pruned
+        let (info, events) = recover_from_container_expiry(false).await;
+        assert!(!info.context_pruned);
+        assert_eq!(
+            info.cleared_previous_response_id.as_deref(),
+            Some("resp_old")
+        );
+        assert_eq!(
+            events,
+            [(
+                crate::ErrorClass::ContainerExpired,
+                false,
+                Some("resp_old".to_string())
+            )]
+        );
+    }
+
+    #[tokio::test(start_paused = true)]
+    async fn recovery_info_sums_delays_and_keeps_context_for_transient_errors() {
+        let mut server = mockito::Server::new_async().await;
+        server
+            .mock("POST", "/responses")
+            .with_status(503)
+            .with_header("retry-after", "2")
+            .expect(1)
+            .create_async()
+            .await;
+        server
+            .mock("POST", "/responses")
+            .with_status(200)
+            .with_body(r#"{"id":"resp_1","object":"response","created_at":1700000000,"model":"gpt-4o","status":"completed","output":[]}"#)
+            .create_async()
+            .await;
+
+        let responses = Responses::new_with_recovery(
+            reqwest::Client::new(),
+            server.url(),
+            RecoveryPolicy::default(),
+        );
+        let request = crate::Request::builder()
+            .model("gpt-4o")
+            .input("hi")
+            .previous_response_id("resp_old")
+            .build();
+        let info = responses
+            .create_with_recovery(request)
+            .await
+            .unwrap()
+            .recovery_info;
+        assert_eq!(info.error_class, Some(crate::ErrorClass::RetryableServer));
+        assert_eq!(info.total_delay, Duration::from_secs(2));
+        assert!(!info.context_pruned);
+        assert_eq!(info.cleared_previous_response_id, None);
+    }
+
+    /// Mocks a response chain whose `previous_response_id` is gone: the chained request
+    /// fails, and the same request without it gets `body`, expected `retries` times
+    async fn mock_missing_previous_response(
+        server: &mut mockito::ServerGuard,
+        body: &str,
+        retries: usize,
+    ) -> (mockito::Mock, mockito::Mock) {
+        use mockito::Matcher;
+
+        let missing = server
+            .mock("POST", "/responses")
+            .match_body(Matcher::PartialJson(
+                serde_json::json!({"previous_response_id": "resp_deleted"}),
+            ))
+            .with_status(400)
+            .with_body(r#"{"error":{"message":"Previous response with id 'resp_deleted' not found.","type":"invalid_request_error","code":"previous_response_not_found","param":"previous_response_id"}}"#)
+            .expect(1)
+            .create_async()
+            .await;
+        let restarted = server
+            .mock("POST", "/responses")
+            .match_request(|request| {
+                !request
+                    .utf8_lossy_body()
+                    .unwrap()
+                    .contains("previous_response_id")
+            })
+            .with_status(200)
+            .with_body(body)
+            .expect(retries)
+            .create_async()
+            .await;
+        (missing, restarted)
+    }
+
+    fn notifying_reset_policy() -> RecoveryPolicy {
+        RecoveryPolicy::default()
+            .with_reset_missing_previous_response(true)
+            .with_notify_on_reset(true)
+            .with_reset_message("Starting over: the earlier chat has expired.")
+    }
+
+    fn chained_request() -> crate::Request {
+        crate::Request::builder()
+            .model("gpt-4o")
+            .input("And then?")
+            .previous_response_id("resp_deleted")
+            .build()
+    }
+
+    /// Sends a request chained to a deleted response and returns the outcome, asserting
+    /// how many times each mock was hit
+    async fn create_after_missing_previous_response(
+        policy: RecoveryPolicy,
+        retries: usize,
+    ) -> Result<ResponseWithRecovery> {
+        let mut server = mockito::Server::new_async().await;
+        let (missing, restarted) = mock_missing_previous_response(
+            &mut server,
+            r#"{"id":"resp_fresh","object":"response","created_at":1700000000,"model":"gpt-4o","status":"completed","output":[]}"#,
+            retries,
+        )
+        .await;
+
+        let responses = Responses::new_with_recovery(reqwest::Client::new(), server.url(), policy);
+        let result = responses.create_with_recovery(chained_request()).await;
+        missing.assert_async().await;
+        restarted.assert_async().await;
+        result
+    }
+
+    #[tokio::test]
+    async fn missing_previous_response_resets_the_chain_when_allowed() {
+        let policy = RecoveryPolicy::default().with_reset_missing_previous_response(true);
+        let result = create_after_missing_previous_response(policy, 1)
+            .await
+            .unwrap();
+        assert_eq!(result.response.id, "resp_fresh");
+        let info = result.recovery_info;
+        assert!(info.chain_reset);
+        assert!(!info.context_pruned);
+        assert_eq!(
+            info.cleared_previous_response_id.as_deref(),
+            Some("resp_deleted")
+        );
+        assert_eq!(
+            info.error_class,
+            Some(crate::ErrorClass::PreviousResponseNotFound)
+        );
+        assert_eq!(info.total_delay, Duration::ZERO);
+    }
+
+    #[tokio::test]
+    async fn missing_previous_response_is_returned_when_reset_is_off() {
+        let error = create_after_missing_previous_response(RecoveryPolicy::default(), 0)
+            .await
+            .unwrap_err();
+        match error {
+            crate::Error::PreviousResponseNotFound { id, .. } => {
+                assert_eq!(id.as_deref(), Some("resp_deleted"));
+            }
+            other => panic!("Expected PreviousResponseNotFound, got {other:?}"),
+        }
+    }
+
+    #[tokio::test(start_paused = true)]
+    async fn plain_create_reports_session_reset_through_last_recovery() {
+        let mut server = mockito::Server::new_async().await;
+        let (missing, restarted) = mock_missing_previous_response(
+            &mut server,
+            r#"{"id":"resp_fresh","object":"response","created_at":1700000000,"model":"gpt-4o","status":"completed","output":[]}"#,
+            1,
+        )
+        .await;
+        let responses = Responses::new_with_recovery(
+            reqwest::Client::new(),
+            server.url(),
+            notifying_reset_policy(),
+        );
+        assert!(responses.last_recovery().is_none());
+
+        let response = responses.create(chained_request()).await.unwrap();
+        assert_eq!(response.id, "resp_fresh");
+        missing.assert_async().await;
+        restarted.assert_async().await;
+
+        // Clones share the slot, as the client's handles do
+        let info = responses.clone().last_recovery().unwrap();
+        assert!(info.is_session_reset());
+        assert_eq!(
+            info.message.as_deref(),
+            Some("Starting over: the earlier chat has expired.")
+        );
+
+        // A retried outage keeps the conversation, so there is nothing to tell the user
+        server.reset();
+        server
+            .mock("POST", "/responses")
+            .with_status(503)
+            .with_header("retry-after", "0")
+            .expect(1)
+            .create_async()
+            .await;
+        let recovered = server
+            .mock("POST", "/responses")
+            .with_status(200)
+            .with_body(r#"{"id":"resp_next","object":"response","created_at":1700000000,"model":"gpt-4o","status":"completed","output":[]}"#)
+            .create_async()
+            .await;
+        let result = responses
+            .create_with_recovery(
+                crate::Request::builder()
+                    .model("gpt-4o")
+                    .input("Hi")
+                    .build(),
+            )
+            .await
+            .unwrap();
+        recovered.assert_async().await;
+        assert_eq!(result.recovery_info.retry_count, 1);
+        assert!(!result.recovery_info.is_session_reset());
+        assert_eq!(result.recovery_message(), None);
+        assert_eq!(responses.last_recovery().unwrap().retry_count, 1);
+    }
+
+    #[cfg(feature = "stream")]
+    #[tokio::test]
+    async fn stream_with_recovery_starts_with_the_reset_notice() {
+        use crate::types::StreamEvent;
+        use futures::StreamExt;
+
+        let mut server = mockito::Server::new_async().await;
+        let (missing, restarted) = mock_missing_previous_response(
+            &mut server,
+            "data: {\"type\":\"response.output_text.delta\",\"delta\":\"Hello\"}\n\ndata: [DONE]\n\n",
+            1,
+        )
+        .await;
+        let responses = Responses::new_with_recovery(
+            reqwest::Client::new(),
+            server.url(),
+            notifying_reset_policy(),
+        );
+
+        let events: Vec<_> = responses
+            .stream_with_recovery(chained_request())
+            .map(Result::unwrap)
+            .collect()
+            .await;
+        missing.assert_async().await;
+        restarted.assert_async().await;
+        assert_eq!(
+            events.first(),
+            Some(&StreamEvent::SessionReset {
+                message: "Starting over: the earlier chat has expired.".to_string(),
+            })
+        );
+        assert!(events
+            .iter()
+            .any(|event| event.as_text_delta() == Some("Hello")));
+        assert_eq!(events.last(), Some(&StreamEvent::Done));
+        let info = responses.last_recovery().unwrap();
+        assert!(info.chain_reset);
+        assert_eq!(
+            info.cleared_previous_response_id.as_deref(),
+            Some("resp_deleted")
+        );
+
+        // Without notify_on_reset the reset is only visible through last_recovery
+        let mut server = mockito::Server::new_async().await;
+        mock_missing_previous_response(&mut server, "data: [DONE]\n\n", 1).await;
+        let responses = Responses::new_with_recovery(
+            reqwest::Client::new(),
+            server.url(),
+            notifying_reset_policy().with_notify_on_reset(false),
+        );
+        let events: Vec<_> = responses
+            .stream_with_recovery(chained_request())
+            .map(Result::unwrap)
+            .collect()
+            .await;
+        assert_eq!(events, [StreamEvent::Done]);
+        assert!(responses.last_recovery().unwrap().is_session_reset());
+    }
+
+    #[tokio::test(start_paused = true)]
+    async fn fallback_model_serves_request_after_capacity_errors() {
+        use mockito::Matcher;
+
+        let mut server = mockito::Server::new_async().await;
+        let overloaded = server
+            .mock("POST", "/responses")
+            .match_body(Matcher::PartialJson(serde_json::json!({"model": "gpt-5"})))
+            .with_status(429)
+            .with_body(r#"{"error":{"message":"Rate limit reached","type":"requests"}}"#)
+            .expect(2)
+            .create_async()
+            .await;
+        // The retry on gpt-5 halves the output cap; the next model starts from the original
+        let fallback = server
+            .mock("POST", "/responses")
+            .match_body(Matcher::PartialJson(
+                serde_json::json!({"model": "gpt-5-mini", "max_output_tokens": 400}),
+            ))
+            .with_status(200)
+            .with_body(r#"{"id":"resp_1","object":"response","created_at":1700000000,"model":"gpt-5-mini","status":"completed","output":[]}"#)
+            .expect(1)
+            .create_async()
+            .await;
+
+        let policy = RecoveryPolicy::default()
+            .with_logging(false)
+            .with_retry_transform(|request, _attempt| {
+                request.max_output_tokens = request.max_output_tokens.map(|tokens| tokens / 2);
+            });
+        let responses = Responses::new_with_recovery(reqwest::Client::new(), server.url(), policy);
+        let request = crate::Request::builder()
+            .model(crate::Model::GPT5)
+            .input("hello")
+            .max_output_tokens(400)
+            .build();
+        let result = responses
+            .create_with_fallback(request, &[crate::Model::GPT5Mini, crate::Model::GPT5Nano])
+            .await
+            .unwrap();
+
+        assert_eq!(result.response.id, "resp_1");
+        let info = &result.recovery_info;
+        assert_eq!(info.final_model, Some(crate::Model::GPT5Mini));
+        assert!(info.attempted && info.successful);
+        // One retry on gpt-5, then the switch to gpt-5-mini
+        assert_eq!(info.retry_count, 2);
+        assert!(info.original_error.is_some());
+        overloaded.assert_async().await;
+        fallback.assert_async().await;
+    }
+
+    #[tokio::test]
+    async fn client_errors_do_not_fall_back() {
+        use mockito::Matcher;
+
+        let mut server = mockito::Server::new_async().await;
+        let invalid = server
+            .mock("POST", "/responses")
+            .match_body(Matcher::PartialJson(serde_json::json!({"model": "gpt-5"})))
+            .with_status(400)
+            .with_body(r#"{"error":{"message":"Invalid input","type":"invalid_request_error"}}"#)
+            .expect(1)
+            .create_async()
+            .await;
+        let fallback = server
+            .mock("POST", "/responses")
+            .match_body(Matcher::PartialJson(
+                serde_json::json!({"model": "gpt-5-mini"}),
+            ))
+            .expect(0)
+            .create_async()
+            .await;
+
+        let responses = Responses::new(reqwest::Client::new(), server.url());
+        let request = crate::Request::builder()
+            .model(crate::Model::GPT5)
+            .input("hello")
+            .build();
+        let error = responses
+            .create_with_fallback(request, &[crate::Model::GPT5Mini])
+            .await
+            .unwrap_err();
+
+        assert!(!error.is_capacity_error());
+        invalid.assert_async().await;
+        fallback.assert_async().await;
+    }
+
+    #[tokio::test]
+    async fn replay_chain_fetches_history_oldest_first() {
+        let mut server = mockito::Server::new_async().await;
+        for (id, previous, text) in [
+            ("resp_3", Some("resp_2"), "three"),
+            ("resp_2", Some("resp_1"), "two"),
+            ("resp_1", None, "one"),
+            // A response pointing at itself must not loop forever
+            ("resp_loop", Some("resp_loop"), "loop"),
+        ] {
+            let body = serde_json::json!({
+                "id": id, "object": "response", "created_at": 1, "model": "gpt-4o",
+                "status": "completed", "previous_response_id": previous,
+                "output": [{"type": "message", "id": format!("msg_{id}"), "role": "assistant",
+                    "content": [{"type": "output_text", "text": text, "annotations": []}]}]
+            });
+            server
+                .mock("GET", format!("/responses/{id}").as_str())
+                .with_status(200)
+                .with_body(body.to_string())
+                .create_async()
+                .await;
+        }
+        let responses = Responses::new(reqwest::Client::new(), server.url());
+
+        let chain = responses.fetch_chain("resp_3").await.unwrap();
+        let ids: Vec<_> = chain.iter().map(|r| r.id.as_str()).collect();
+        assert_eq!(ids, ["resp_1", "resp_2", "resp_3"]);
+        assert_eq!(responses.fetch_chain("resp_loop").await.unwrap().len(), 1);
+
+        let request = responses
+            .replay_chain("resp_3", crate::Model::GPT4oMini)
+            .await
+            .unwrap()
+            .build();
+        assert_eq!(request.model, crate::Model::GPT4oMini);
+        assert!(request.previous_response_id.is_none());
+        let crate::types::Input::Items(items) = &request.input else {
+            panic!("expected items");
+        };
+        let texts: Vec<_> = items
+            .iter()
+            .map(|item| item.content.as_ref().unwrap()[0]["text"].clone())
+            .collect();
+        assert_eq!(texts, ["one", "two", "three"]);
+    }
+
+    #[tokio::test]
+    async fn aggregate_chain_usage_walks_and_sums_the_chain() {
+        let mut server = mockito::Server::new_async().await;
+        let mut mocks = Vec::new();
+        for (id, previous, usage, expected_calls) in [
+            (
+                "resp_3",
+                Some("resp_2"),
+                serde_json::json!({"input_tokens": 300, "output_tokens": 30, "total_tokens": 330,
+                    "output_tokens_details": {"reasoning_tokens": 12}}),
+                2,
+            ),
+            ("resp_2", Some("resp_1"), serde_json::Value::Null, 2),
+            (
+                "resp_1",
+                None,
+                serde_json::json!({"input_tokens": 100, "output_tokens": 10, "total_tokens": 110,
+                    "input_tokens_details": {"cached_tokens": 50}}),
+                1,
+            ),
+        ] {
+            let body = serde_json::json!({
+                "id": id, "object": "response", "created_at": 1, "model": "gpt-5",
+                "status": "completed", "previous_response_id": previous, "usage": usage,
+                "output": [{"type": "web_search_call", "id": format!("ws_{id}"), "status": "completed"}]
+            });
+            mocks.push(
+                server
+                    .mock("GET", format!("/responses/{id}").as_str())
+                    .with_status(200)
+                    .with_body(body.to_string())
+                    .expect(expected_calls)
+                    .create_async()
+                    .await,
+            );
+        }
+        let responses = Responses::new(reqwest::Client::new(), server.url());
+
+        let total = responses.aggregate_chain_usage("resp_3", 10).await.unwrap();
+        assert_eq!(total.responses, 3);
+        assert_eq!(total.responses_without_usage, 1);
+        assert_eq!(
+            (total.input_tokens, total.output_tokens, total.total_tokens),
+            (400, 40, 440)
+        );
+        assert_eq!((total.reasoning_tokens, total.cached_tokens), (12, 50));
+        assert_eq!(total.web_search, 3);
+
+        // The depth limit stops the walk before the oldest response
+        let partial = responses.aggregate_chain_usage("resp_3", 2).await.unwrap();
+        assert_eq!((partial.responses, partial.total_tokens), (2, 330));
+
+        for mock in mocks {
+            mock.assert_async().await;
+        }
+    }
+
+    fn background_handle(server: &mockito::ServerGuard) -> crate::types::BackgroundHandle {
+        serde_json::from_value(serde_json::json!({
+            "id": "bg_1",
+            "status_url": format!("{}/bg/status/bg_1", server.url()),
+            "stream_url": format!("{}/bg/stream/bg_1", server.url()),
+            "status": "queued"
+        }))
+        .unwrap()
+    }
+
+    #[tokio::test]
+    async fn background_status_follows_the_handle_through_its_lifecycle() {
+        use crate::types::BackgroundStatus;
+
+        let mut server = mockito::Server::new_async().await;
+        let responses = Responses::new(reqwest::Client::new(), format!("{}/v1", server.url()));
+        let mut handle = background_handle(&server);
+
+        let polls = [
+            (
+                serde_json::json!({"id": "bg_1", "status": "queued", "queue_position": 3}),
+                BackgroundStatus::Queued,
+            ),
+            (
+                serde_json::json!({"id": "bg_1", "status": "in_progress", "progress": 40,
+                    "estimated_completion": "2026-01-01T00:00:00Z"}),
+                BackgroundStatus::Running,
+            ),
+            (
+                serde_json::json!({"id": "bg_1", "status": "completed", "progress": 100}),
+                BackgroundStatus::Completed,
+            ),
+        ];
+        for (body, expected) in polls {
+            let mock = server
+                .mock("GET", "/bg/status/bg_1")
+                .with_status(200)
+                .with_body(body.to_string())
+                .expect(1)
+                .create_async()
+                .await;
+            let status = responses.background_status(&handle).await.unwrap();
+            mock.assert_async().await;
+            mock.remove_async().await;
+
+            assert_eq!(status.status, expected);
+            handle.apply(&status);
+            match expected {
+                BackgroundStatus::Queued => assert_eq!(status.queue_position, Some(3)),
+                BackgroundStatus::Running => {
+                    assert_eq!(status.progress, Some(40));
+                    assert!(handle.is_running());
+                    assert_eq!(
+                        handle.estimated_completion.as_deref(),
+                        Some("2026-01-01T00:00:00Z")
+                    );
+                }
+                _ => assert!(handle.is_done()),
+            }
+        }
+
+        // A failure's error object is reduced to its message
+        let failed: crate::types::BackgroundStatusResponse =
+            serde_json::from_value(serde_json::json!({"id": "bg_1", "status": "failed",
+                "error": {"code": "server_error", "message": "boom"}}))
+            .unwrap();
+        assert_eq!(failed.error.as_deref(), Some("boom"));
+
+        // The key is never sent to a status URL on another host
+        handle.status_url = "https://elsewhere.example.com/bg/status/bg_1".to_string();
+        assert!(matches!(
+            responses.background_status(&handle).await,
+            Err(crate::Error::InvalidHandleUrl(_))
+        ));
+    }
+
+    #[tokio::test]
+    async fn accepted_background_creates_are_queued_not_decode_errors() {
+        use crate::types::{BackgroundStatus, ResponseStatus};
+
+        let mut server = mockito::Server::new_async().await;
+        let mock = server
+            .mock("POST", "/v1/responses")
+            .match_body(mockito::Matcher::PartialJson(
+                serde_json::json!({"background": true}),
+            ))
+            .with_status(202)
+            .with_body(r#"{"id":"resp_bg","status":"queued"}"#)
+            .expect(2)
+            .create_async()
+            .await;
+        // Recovery is on, and still nothing is retried
+        let responses = Responses::new_with_recovery(
+            reqwest::Client::new(),
+            format!("{}/v1", server.url()),
+            RecoveryPolicy::aggressive(),
+        );
+        let request = crate::Request::builder()
+            .model("gpt-4o")
+            .input("Write a long report")
+            .background(true)
+            .build();
+
+        let response = responses.create(request.clone()).await.unwrap();
+        assert_eq!(response.id, "resp_bg");
+        assert_eq!(response.status, ResponseStatus::Queued);
+        assert_eq!(response.model, "gpt-4o");
+        assert!(response.output.is_empty());
+
+        let outcome = responses.create_background(request).await.unwrap();
+        let handle = outcome.handle().expect("a queued response is accepted");
+        assert_eq!(handle.id, "resp_bg");
+        assert_eq!(handle.status, BackgroundStatus::Queued);
+        assert_eq!(
+            handle.status_url,
+            format!("{}/v1/responses/resp_bg", server.url())
+        );
+        mock.assert_async().await;
+
+        // A gateway that ignores `background` answers with the finished response
+        server
+            .mock("POST", "/v1/responses")
+            .with_status(200)
+            .with_body(
+                r#"{"id":"resp_fg","object":"response","created_at":1700000000,"model":"gpt-4o","status":"completed","output":[]}"#,
+            )
+            .create_async()
+            .await;
+        let outcome = responses
+            .create_background(
+                crate::Request::builder()
+                    .model("gpt-4o")
+                    .input("Hi")
+                    .build(),
+            )
+            .await
+            .unwrap();
+        assert_eq!(outcome.response().map(|r| r.id.as_str()), Some("resp_fg"));
+    }
+
+    #[tokio::test]
+    async fn background_cancel_cancels_the_handles_response() {
+        let mut server = mockito::Server::new_async().await;
+        let mock = server
+            .mock("POST", "/v1/responses/bg_1/cancel")
+            .with_status(200)
+            .with_body(
+                serde_json::json!({
+                    "id": "bg_1", "object": "response", "created_at": 1,
+                    "model": "gpt-5", "status": "cancelled", "output": []
+                })
+                .to_string(),
+            )
+            .create_async()
+            .await;
+        let responses = Responses::new(reqwest::Client::new(), format!("{}/v1", server.url()));
+
+        let outcome = responses
+            .background_cancel(&background_handle(&server))
+            .await
+            .unwrap();
+        assert!(matches!(outcome, CancelOutcome::Cancelled(response) if response.id == "bg_1"));
+        mock.assert_async().await;
+    }
+
+    #[cfg(feature = "stream")]
+    #[tokio::test]
+    async fn background_stream_reads_the_handles_stream_url() {
+        use crate::types::StreamEvent;
+        use futures::StreamExt;
+
+        let mut server = mockito::Server::new_async().await;
+        let completed = serde_json::json!({"type": "response.completed",
+            "response": {"id": "bg_1", "object": "response", "created_at": 1,
+                "model": "gpt-5", "status": "completed", "output": []}});
+        let mock = server
+            .mock("GET", "/bg/stream/bg_1")
+            .match_header("accept", "text/event-stream")
+            .with_status(200)
+            .with_header("content-type", "text/event-stream")
+            .with_body(format!(
+                "data: {}\n\ndata: {completed}\n\n",
+                serde_json::json!({"type": "response.output_text.delta", "delta": "Hi"})
+            ))
+            .expect(1)
+            .create_async()
+            .await;
+        let responses = Responses::new(reqwest::Client::new(), format!("{}/v1", server.url()));
+        let mut handle = background_handle(&server);
+
+        let events: Vec<_> = tokio::time::timeout(
+            Duration::from_secs(5),
+            responses.background_stream(&handle).collect::<Vec<_>>(),
+        )
+        .await
+        .expect("stream should end after a terminal event");
+        assert!(events.iter().any(
+            |event| matches!(event, Ok(StreamEvent::TextDelta { content, .. }) if content == "Hi")
+        ));
+        assert!(matches!(events.last(), Some(Ok(event)) if event.is_terminal()));
+        mock.assert_async().await;
+
+        handle.stream_url = None;
+        let events: Vec<_> = responses.background_stream(&handle).collect().await;
+        assert!(matches!(
+            events.as_slice(),
+            [Err(crate::Error::InvalidHandleUrl(_))]
+        ));
+    }
+
+    #[tokio::test]
+    async fn list_input_items_parses_input_side_items() {
+        use crate::types::{Include, MessageContent, Order, PaginationParams};
+        use mockito::Matcher;
+
+        let mut server = mockito::Server::new_async().await;
+        let list = server
+            .mock("GET", "/responses/resp_1/input_items")
+            .match_query(Matcher::AllOf(vec![
+                Matcher::UrlEncoded("limit".into(), "3".into()),
+                Matcher::UrlEncoded("order".into(), "desc".into()),
+                Matcher::UrlEncoded("include[]".into(), "message.input_image.image_url".into()),
+            ]))
+            .with_status(200)
+            .with_body(include_str!("../tests/fixtures/response_input_items.json"))
+            .create_async()
+            .await;
+        let responses = Responses::new(reqwest::Client::new(), server.url());
+
+        let params = PaginationParams::new().limit(3).order(Order::Desc);
+        let page = responses
+            .list_input_items_with(
+                "resp_1",
+                Some(params.clone()),
+                &[Include::MessageInputImageUrl],
+            )
+            .await
+            .unwrap();
+        list.assert_async().await;
+        assert_eq!(page.next_params(&params), None);
+
+        let types: Vec<_> = page
+            .data
+            .iter()
+            .map(crate::ResponseItem::item_type)
+            .collect();
+        assert_eq!(types, ["function_call_output", "function_call", "message"]);
+        let crate::ResponseItem::Message { role, content, .. } = &page.data[2] else {
+            panic!("expected a message, got {:?}", page.data[2]);
+        };
+        assert_eq!(role, "user");
+        assert_eq!(
+            content,
+            &[
+                MessageContent::InputText {
+                    text: "What's the weather here?".to_string()
+                },
+                MessageContent::InputImage {
+                    image_url: Some("https://example.com/paris.jpg".to_string()),
+                    file_id: None,
+                    detail: Some("auto".to_string()),
+                },
+                MessageContent::InputFile {
+                    file_id: Some("file-abc".to_string()),
+                    filename: Some("itinerary.pdf".to_string()),
+                },
+            ]
+        );
+
+        let invalid = responses
+            .list_input_items("resp_1", Some(PaginationParams::new().limit(0)))
+            .await;
+        assert!(matches!(invalid, Err(crate::Error::Validation(_))));
+    }
+
+    #[cfg(feature = "stream")]
+    async fn collect_stream(
+        responses: &Responses,
+        server: &mut mockito::ServerGuard,
+        event: serde_json::Value,
+    ) -> Vec<Result<crate::types::StreamEvent>> {
+        use futures::StreamExt;
+
+        server
+            .mock("POST", "/responses")
+            .with_status(200)
+            .with_header("content-type", "text/event-stream")
+            .with_body(format!("data: {event}\n\n"))
+            .create_async()
+            .await;
+
+        let stream = responses.stream(crate::Request::default());
+        tokio::time::timeout(Duration::from_secs(5), stream.collect())
+            .await
+            .expect("stream should end after a terminal event")
+    }
+
+    #[cfg(feature = "stream")]
+    #[tokio::test]
+    async fn stream_ends_after_failed_and_incomplete_events() {
+        let mut server = mockito::Server::new_async().await;
+        let responses = Responses::new(reqwest::Client::new(), server.url());
+
+        let failed = serde_json::json!({
+            "type": "response.failed",
+            "response": {
+                "id": "resp_1",
+                "status": "failed",
+                "error": {"code": "server_error", "message": "The model crashed"}
+            }
+        });
+        let events = collect_stream(&responses, &mut server, failed).await;
+        assert_eq!(events.len(), 1);
+        let error = events[0].as_ref().unwrap().as_failure().unwrap();
+        assert_eq!(error.code, "server_error");
+        assert_eq!(error.message, "The model crashed");
+
+        let incomplete = serde_json::json!({
+            "type": "response.incomplete",
+            "response": {
+                "id": "resp_1",
+                "status": "incomplete",
+                "incomplete_details": {"reason": "max_output_tokens"}
+            }
+        });
+        let events = collect_stream(&responses, &mut server, incomplete).await;
+        assert_eq!(events.len(), 1);
+        match events[0].as_ref().unwrap() {
+            crate::types::StreamEvent::Incomplete { reason } => {
+                assert_eq!(reason.as_deref(), Some("max_output_tokens"));
+            }
+            other => panic!("expected incomplete event, got {other:?}"),
+        }
+    }
+
+    #[cfg(feature = "stream")]
+    #[tokio::test]
+    async fn stream_failures_can_be_reported_as_errors() {
+        let mut server = mockito::Server::new_async().await;
+        let responses = Responses::new(reqwest::Client::new(), server.url())
+            .with_stream_failures_as_errors(true);
+
+        let rate_limited = serde_json::json!({
+            "type": "error",
+            "code": "rate_limit_exceeded",
+            "message": "Slow down",
+            "param": null
+        });
+        let events = collect_stream(&responses, &mut server, rate_limited).await;
+        assert_eq!(events.len(), 1);
+        let error = events[0].as_ref().unwrap_err();
+        assert_eq!(error.classify(), crate::error::ErrorClass::RateLimited);
+
+        let server_error = serde_json::json!({
+            "type": "response.failed",
+            "response": {"error": {"code": "server_error", "message": "boom"}}
+        });
+        let events = collect_stream(&responses, &mut server, server_error).await;
+        assert_eq!(events.len(), 1);
+        assert_eq!(
+            events[0].as_ref().unwrap_err().classify(),
+            crate::error::ErrorClass::RetryableServer
+        );
+    }
+
+    /// Collects an SSE byte stream into one string
+    #[cfg(feature = "stream")]
+    async fn sse_text(stream: impl futures::Stream<Item = Result<bytes::Bytes>>) -> String {
+        use futures::StreamExt;
+
+        let frames: Vec<_> = stream.collect().await;
+        frames
+            .into_iter()
+            .map(|frame| String::from_utf8(frame.unwrap().to_vec()).unwrap())
+            .collect()
+    }
+
+    #[cfg(feature = "stream")]
+    #[tokio::test]
+    async fn sse_bytes_round_trip_through_the_parser() {
+        use crate::types::{StreamEvent, StreamExt2};
+        fn assert_send<T: Send>(_: &T) {}
+
+        let wire = [
+            r#"data: {"type":"response.created","response":{"id":"resp_1","status":"in_progress"}}"#,
+            r#"data: {"type":"response.output_text.delta","delta":"Hel","output_index":0}"#,
+            r#"data: {"type":"response.output_text.delta","delta":"lo \"world\"\n","output_index":0}"#,
+            r#"data: {"type":"response.output_text.done","output_index":0,"content_index":1}"#,
+            r#"data: {"type":"response.tool_call.created","tool_call":{"id":"call_1","function":{"name":"get_weather"}},"output_index":1}"#,
+            r#"data: {"type":"response.tool_call.delta","tool_call":{"id":"call_1"},"delta":"{\"city\":","output_index":1}"#,
+            r#"data: {"type":"response.tool_call.completed","tool_call":{"id":"call_1"},"output_index":1}"#,
+            r#"data: {"type":"response.image.progress","image":{"url":"https://img/1","index":2}}"#,
+            r#"data: {"type":"response.image_generation_call.partial_image","item_id":"ig_1","output_index":3,"partial_image_index":1,"partial_image_b64":"iVBORw0KGgo="}"#,
+            r#"data: {"type":"response.output_item.added","item":{"type":"mcp_call","id":"mcp_1","server_label":"wiki","name":"ask"}}"#,
+            r#"data: {"type":"response.mcp_call_arguments.delta","item_id":"mcp_1","delta":"{}"}"#,
+            r#"data: {"type":"response.mcp_call.completed","item_id":"mcp_1","output":"42"}"#,
+            r#"data: {"type":"response.mcp_call.failed","item_id":"mcp_2","error":null}"#,
+            r#"data: {"type":"response.mcp_list_tools.completed","tools":[{"name":"ask"}]}"#,
+            r#"data: {"type":"response.in_progress"}"#,
+            r#"data: {"type":"response.failed","response":{"error":{"code":"server_error","message":"boom"}}}"#,
+        ];
+        let events: Vec<StreamEvent> = wire
+            .iter()
+            .filter_map(|line| Responses::process_stream_line(line))
+            .map(Result::unwrap)
+            .collect();
+        assert_eq!(events.len(), wire.len());
+        assert_eq!(
+            events[8],
+            StreamEvent::ImageProgress {
+                url: None,
+                index: 3,
+                partial_image_b64: Some("iVBORw0KGgo=".to_string()),
+                partial_image_index: Some(1),
+            }
+        );
+
+        let source = futures::stream::iter(events.clone().into_iter().map(Ok));
+        let sse = source.into_sse_bytes();
+        assert_send(&sse);
+        let text = sse_text(sse).await;
+        assert!(text.ends_with("data: [DONE]\n\n"));
+
+        let reparsed: Vec<StreamEvent> = text
+            .split("\n\n")
+            .filter_map(Responses::process_stream_line)
+            .map(Result::unwrap)
+            .collect();
+        // `Unknown` has no wire form, and `[DONE]` follows the terminal event
+        let mut expected: Vec<StreamEvent> = events
+            .into_iter()
+            .filter(|event| *event != StreamEvent::Unknown)
+            .collect();
+        expected.push(StreamEvent::Done);
+        assert_eq!(reparsed, expected);
+
+        // A source ending without a terminal event still gets one `[DONE]`
+        let text = sse_text(futures::stream::iter([Ok(StreamEvent::Done)]).into_sse_bytes()).await;
+        assert_eq!(text, "data: [DONE]\n\n");
+        let text = sse_text(futures::stream::empty().into_sse_bytes()).await;
+        assert_eq!(text, "data: [DONE]\n\n");
+    }
+
+    #[cfg(feature = "stream")]
+    #[test]
+    fn delta_events_parse_the_same_without_an_intermediate_value() {
+        let lines = [
+            r#"{"type":"response.output_text.delta","item_id":"msg_1","output_index":2,"content_index":1,"delta":"line\n\"quoted\" é"}"#,
+            r#"{ "type" : "response.output_text.delta", "delta": "spaced" }"#,
+            r#"{"delta":"type not first","type":"response.output_text.delta","output_index":1}"#,
+            r#"{"type":"response.output_text.delta","delta":"huge index","output_index":99999999999}"#,
+            r#"{"type":"response.output_text.delta","delta":"bad index","output_index":-1}"#,
+            r#"{"type":"response.output_text.delta","delta":null}"#,
+            r#"{"type":"response.output_text.delta","delta":{"not":"text"}}"#,
+            r#"{"type":"response.function_call_arguments.delta","item_id":"fc_1","call_id":"call_1","name":"f","output_index":1,"delta":"{\"a\":"}"#,
+            r#"{"type":"response.function_call_arguments.delta","delta":"no item id"}"#,
+            r#"{"type":"response.mcp_call_arguments.delta","item_id":"mcp_1","delta":"{}"}"#,
+            r#"{"type":"response.output_text.done","output_index":0}"#,
+        ];
+        for line in lines {
+            let value: serde_json::Value = serde_json::from_str(line).unwrap();
+            let expected = Responses::parse_stream_event(&value);
+            let parsed =
+                Responses::process_stream_line(&format!("data: {line}")).map(Result::unwrap);
+            assert_eq!(parsed, expected, "{line}");
+        }
+        assert_eq!(leading_event_type(r#"{"type":"a","delta":"x"}"#), Some("a"));
+        assert_eq!(leading_event_type(r#"{"delta":"x","type":"a"}"#), None);
+    }
+
+    #[cfg(feature = "stream")]
+    #[test]
+    fn stream_events_carry_output_and_content_indices() {
+        use crate::types::{CollectedOutput, StreamCollector, StreamEvent};
+
+        let wire = [
+            r#"data: {"type":"response.output_text.delta","delta":"A","output_index":0,"content_index":0}"#,
+            r#"data: {"type":"response.tool_call.created","tool_call":{"id":"call_2","function":{"name":"second"}},"output_index":2}"#,
+            r#"data: {"type":"response.tool_call.created","tool_call":{"id":"call_1","function":{"name":"first"}},"output_index":1}"#,
+            r#"data: {"type":"response.tool_call.delta","tool_call":{"id":"call_2"},"delta":"{\"b\":2}","output_index":2}"#,
+            r#"data: {"type":"response.output_text.delta","delta":"C","output_index":0,"content_index":1}"#,
+            r#"data: {"type":"response.tool_call.delta","tool_call":{"id":"call_1"},"delta":"{\"a\":1}","output_index":1}"#,
+            r#"data: {"type":"response.output_text.delta","delta":"B","output_index":0,"content_index":0}"#,
+            r#"data: {"type":"response.output_text.done","output_index":0,"content_index":1}"#,
+        ];
+        let events: Vec<StreamEvent> = wire
+            .iter()
+            .filter_map(|line| Responses::process_stream_line(line))
+            .map(Result::unwrap)
+            .collect();
+        assert_eq!(
+            events[4],
+            StreamEvent::TextDelta {
+                content: "C".to_string(),
+                index: 0,
+                content_index: 1,
+            }
+        );
+        assert_eq!(
+            events[7],
+            StreamEvent::TextStop {
+                index: 0,
+                content_index: 1,
+            }
+        );
+
+        let mut collector = StreamCollector::new();
+        for event in &events {
+            collector.push_event(event);
+        }
+        let outputs = collector.outputs();
+        assert_eq!(
+            outputs[0],
+            CollectedOutput::Text(vec!["AB".to_string(), "C".to_string()])
+        );
+        let calls: Vec<_> = collector
+            .tool_calls()
+            .into_iter()
+            .map(|call| (call.output_index, call.name, call.arguments))
+            .collect();
+        assert_eq!(
+            calls,
+            [
+                (1, "first".to_string(), r#"{"a":1}"#.to_string()),
+                (2, "second".to_string(), r#"{"b":2}"#.to_string()),
+            ]
+        );
+    }
+
+    #[cfg(feature = "stream")]
+    #[tokio::test]
+    async fn multi_line_sse_events_are_joined_and_named() {
+        use crate::types::{SseEvent, StreamEvent};
+        use futures::StreamExt;
+
+        const FIXTURE: &str = include_str!("../tests/fixtures/multiline_sse_stream.sse");
+
+        // Split the body mid-event so the framing has to reassemble it
+        let chunks: Vec<reqwest::Result<bytes::Bytes>> = FIXTURE
+            .as_bytes()
+            .chunks(37)
+            .map(|chunk| Ok(bytes::Bytes::copy_from_slice(chunk)))
+            .collect();
+        let blocks: Vec<_> = crate::types::sse::sse_events(Box::pin(futures::stream::iter(chunks)))
+            .map(|block| String::from_utf8(block.unwrap().to_vec()).unwrap())
+            .collect()
+            .await;
+        let ids: Vec<_> = blocks
+            .iter()
+            .filter_map(|block| SseEvent::parse(block)?.id)
+            .collect();
+        assert_eq!(ids, ["evt_1", "evt_2", "evt_3", "evt_4", "evt_5"]);
+
+        let mut server = mockito::Server::new_async().await;
+        server
+            .mock("POST", "/responses")
+            .with_status(200)
+            .with_header("content-type", "text/event-stream")
+            .with_body(FIXTURE)
+            .create_async()
+            .await;
+        let responses = Responses::new(reqwest::Client::new(), server.url());
+        let mut stream = responses.stream(crate::Request::default());
+        let (mut events, mut event_ids) = (Vec::new(), Vec::new());
+        while let Some(event) = stream.next().await {
+            let event = event.unwrap();
+            if event != StreamEvent::Chunk {
+                events.push(event);
+                event_ids.push(responses.last_event_id().unwrap());
+            }
+        }
+
+        // Each event's id is readable as it arrives, ready to resume from
+        assert_eq!(event_ids, ["evt_1", "evt_2", "evt_3", "evt_4", "evt_5"]);
+        assert_eq!(
+            events,
+            [
+                // Named by `event:` only
+                StreamEvent::ResponseCreated {
+                    id: "resp_sse1".to_string()
+                },
+                StreamEvent::TextDelta {
+                    content: "Hello".to_string(),
+                    index: 0,
+                    content_index: 0,
+                },
+                StreamEvent::ToolCallCreated {
+                    id: "fc_1".to_string(),
+                    call_id: Some("call_1".to_string()),
+                    name: "get_weather".to_string(),
+                    index: 1,
+                },
+                StreamEvent::ToolCallDelta {
+                    id: "fc_1".to_string(),
+                    call_id: Some("call_1".to_string()),
+                    name: Some("get_weather".to_string()),
+                    content: r#"{"city":"Paris"}"#.to_string(),
+                    index: 1,
+                },
+                StreamEvent::Done,
+            ]
+        );
+    }
+
+    #[cfg(feature = "stream")]
+    #[tokio::test]
+    async fn streamed_function_calls_carry_their_call_ids() {
+        use crate::types::{StreamCollector, StreamEvent};
+        use futures::StreamExt;
+
+        let mut server = mockito::Server::new_async().await;
+        server
+            .mock("POST", "/responses")
+            .with_status(200)
+            .with_header("content-type", "text/event-stream")
+            .with_body(include_str!("../tests/fixtures/function_call_stream.sse"))
+            .create_async()
+            .await;
+        let responses = Responses::new(reqwest::Client::new(), server.url());
+
+        let events: Vec<StreamEvent> = responses
+            .stream(crate::Request::default())
+            .map(Result::unwrap)
+            .collect()
+            .await;
+        // The argument deltas name only the item; the call ID comes from `output_item.added`
+        assert_eq!(
+            events[3],
+            StreamEvent::ToolCallDelta {
+                id: "fc_68b0a".to_string(),
+                call_id: Some("call_Xk1".to_string()),
+                name: Some("get_weather".to_string()),
+                content: r#"{"city":"#.to_string(),
+                index: 0,
+            }
+        );
+        assert_eq!(
+            events[6],
+            StreamEvent::ToolCallCompleted {
+                id: "fc_68b0a".to_string(),
+                call_id: Some("call_Xk1".to_string()),
+                name: Some("get_weather".to_string()),
+                index: 0,
+            }
+        );
+
+        let mut collector = StreamCollector::new();
+        for event in &events {
+            collector.push_event(event);
+        }
+        let calls = collector.function_calls();
+        assert_eq!(
+            calls
+                .iter()
+                .map(|call| (
+                    call.call_id.as_str(),
+                    call.name.as_str(),
+                    call.arguments.as_str()
+                ))
+                .collect::<Vec<_>>(),
+            [
+                ("call_Xk1", "get_weather", r#"{"city":"Paris"}"#),
+                ("call_Ym2", "get_time", r#"{"tz":"CET"}"#),
+            ]
+        );
+        assert!(calls
+            .iter()
+            .all(crate::types::FunctionCallInfo::is_completed));
+        assert_eq!(calls[0].id.as_deref(), Some("fc_68b0a"));
+    }
+
+    #[cfg(feature = "stream")]
+    #[test]
+    fn mcp_stream_events_are_typed() {
+        use crate::types::StreamEvent;
+
+        let events: Vec<StreamEvent> = include_str!("../tests/fixtures/mcp_stream_events.sse")
+            .split("\n\n")
+            .filter_map(Responses::process_stream_line)
+            .map(Result::unwrap)
+            .collect();
+        assert_eq!(events.len(), 11);
+
+        // List-tools progress without the tools stays unknown
+        assert_eq!(
+            events[..3],
+            [
+                StreamEvent::Unknown,
+                StreamEvent::Unknown,
+                StreamEvent::Unknown
+            ]
+        );
+        let StreamEvent::McpListToolsCompleted { tools } = &events[3] else {
+            panic!("expected listed tools, got {:?}", events[3]);
+        };
+        assert_eq!(tools.len(), 2);
+        assert_eq!(tools[0].name, "read_wiki_structure");
+        assert_eq!(
+            tools[0].input_schema.as_ref().unwrap()["required"][0],
+            "repoName"
+        );
+        assert_eq!(tools[1].description, None);
+
+        assert_eq!(
+            events[4],
+            StreamEvent::McpCallStarted {
+                id: "mcp_68a2".to_string(),
+                server_label: "deepwiki".to_string(),
+                name: "ask_question".to_string(),
+            }
+        );
+        // The in-progress event carries neither label nor name
+        assert_eq!(events[5], StreamEvent::Unknown);
+        let arguments: String = events[6..8]
+            .iter()
+            .filter_map(StreamEvent::as_mcp_arguments_delta)
+            .collect();
+        assert_eq!(
+            serde_json::from_str::<serde_json::Value>(&arguments).unwrap()["repoName"],
+            "openai/codex"
+        );
+        assert_eq!(
+            events[8],
+            StreamEvent::McpCallCompleted {
+                id: "mcp_68a2".to_string(),
+                output: None,
+            }
+        );
+        assert_eq!(events[9].as_mcp_call_id(), Some("mcp_68a3"));
+        assert_eq!(
+            events[10],
+            StreamEvent::McpCallFailed {
+                id: "mcp_68a3".to_string(),
+                error: Some("Repository not found".to_string()),
+            }
+        );
+        assert!(events.iter().all(|event| !event.is_terminal()));
+    }
+
+    #[cfg(feature = "stream")]
+    #[tokio::test]
+    async fn sse_bytes_propagate_errors_without_done() {
+        use crate::types::{StreamEvent, StreamExt2};
+        use futures::StreamExt;
+
+        let source = futures::stream::iter([
+            Ok(StreamEvent::TextDelta {
+                content: "Hi".to_string(),
+                index: 0,
+                content_index: 0,
+            }),
+            Err(crate::Error::Stream("connection reset".to_string())),
+            Ok(StreamEvent::Done),
+        ]);
+        let frames: Vec<_> = source.into_sse_bytes().collect().await;
+        assert_eq!(frames.len(), 2);
+        assert!(frames[0].as_ref().unwrap().starts_with(b"data: {"));
+        assert!(
+            matches!(&frames[1], Err(crate::Error::Stream(message)) if message == "connection reset")
+        );
+    }
+
+    #[cfg(feature = "stream")]
+    #[tokio::test]
+    async fn forward_to_channel_stops_after_terminal_events() {
+        use crate::types::{StreamEvent, StreamExt2};
+
+        let pause = Duration::from_millis(20);
+        let url = stalling_server(vec![
+            (
+                Duration::ZERO,
+                "data: {\"type\":\"response.created\",\"response\":{\"id\":\"resp_1\"}}\n\n",
+            ),
+            (
+                pause,
+                "data: {\"type\":\"response.output_text.delta\",\"delta\":\"Hi\"}\n\n",
+            ),
+            (pause, "data: [DONE]\n\n"),
+            // Never forwarded: the task stops at `[DONE]`
+            (
+                pause,
+                "data: {\"type\":\"response.output_text.delta\",\"delta\":\"!\"}\n\n",
+            ),
+        ])
+        .await;
+        let responses = Responses::new(reqwest::Client::new(), url);
+
+        let (tx, mut rx) = tokio::sync::mpsc::channel(8);
+        let handle = responses
+            .stream(crate::Request::default())
+            .forward_to_channel(tx);
+        let mut received = Vec::new();
+        while let Some(event) = rx.recv().await {
+            received.push(event);
+        }
+        handle.await.unwrap().unwrap();
+        assert_eq!(received.len(), 3, "{received:?}");
+        assert_eq!(received[0].as_response_id(), Some("resp_1"));
+        assert_eq!(received[2], StreamEvent::Done);
+
+        // Errors come back through the handle
+        let (tx, mut rx) = tokio::sync::mpsc::channel(8);
+        let failing = futures::stream::iter([
+            Ok(StreamEvent::Chunk),
+            Err(crate::Error::Stream("idle timeout after 1s".to_string())),
+        ]);
+        let handle = failing.forward_to_channel(tx);
+        assert_eq!(rx.recv().await, Some(StreamEvent::Chunk));
+        assert!(rx.recv().await.is_none());
+        assert!(matches!(
+            handle.await.unwrap(),
+            Err(crate::Error::Stream(message)) if message == "idle timeout after 1s"
+        ));
+
+        // Dropping the receiver stops forwarding without an error
+        let (tx, rx) = tokio::sync::mpsc::channel(1);
+        drop(rx);
+        let endless = futures::stream::repeat_with(|| Ok(StreamEvent::Chunk));
+        handle_ok(endless.forward_to_channel(tx)).await;
+    }
+
+    #[cfg(feature = "stream")]
+    async fn handle_ok(handle: tokio::task::JoinHandle<Result<()>>) {
+        tokio::time::timeout(Duration::from_secs(5), handle)
+            .await
+            .expect("forwarding should stop")
+            .unwrap()
+            .unwrap();
+    }
+
+    /// Serves one SSE response that sends `chunks` with the given pauses, then stalls
+    #[cfg(feature = "stream")]
+    async fn stalling_server(chunks: Vec<(Duration, &'static str)>) -> String {
+        use tokio::io::{AsyncReadExt, AsyncWriteExt};
+
+        let listener = tokio::net::TcpListener::bind("127.0.0.1:0").await.unwrap();
+        let url = format!("http://{}", listener.local_addr().unwrap());
+        tokio::spawn(async move {
+            let (mut socket, _) = listener.accept().await.unwrap();
+            let mut request = vec![0; 64 * 1024];
+            let _ = socket.read(&mut request).await;
+            socket
+                .write_all(b"HTTP/1.1 200 OK\r\ncontent-type: text/event-stream\r\ntransfer-encoding: chunked\r\n\r\n")
+                .await
+                .unwrap();
+            for (pause, chunk) in chunks {
+                tokio::time::sleep(pause).await;
+                let framed = format!("{:x}\r\n{chunk}\r\n", chunk.len());
+                if socket.write_all(framed.as_bytes()).await.is_err() {
+                    return;
+                }
+            }
+            // Keep the connection open without sending anything
+            tokio::time::sleep(Duration::from_secs(60)).await;
+        });
+        url
+    }
+
+    #[cfg(feature = "stream")]
+    #[tokio::test]
+    async fn stream_idle_timeout_ends_a_stalled_stream() {
+        use futures::StreamExt;
+
+        let url = stalling_server(vec![(
+            Duration::ZERO,
+            "data: {\"type\":\"response.output_text.delta\",\"delta\":\"Hel\"}\n\n",
+        )])
+        .await;
+        let options =
+            crate::types::StreamOptions::new().with_idle_timeout(Duration::from_millis(200));
+        let responses = Responses::new(reqwest::Client::new(), url).with_stream_options(options);
+
+        let events: Vec<_> = tokio::time::timeout(
+            Duration::from_secs(5),
+            responses.stream(crate::Request::default()).collect(),
+        )
+        .await
+        .expect("the idle timeout should end the stream");
+
+        assert_eq!(events.len(), 2, "{events:?}");
+        assert_eq!(events[0].as_ref().unwrap().as_text_delta(), Some("Hel"));
+        match &events[1] {
+            Err(crate::Error::Stream(message)) => assert_eq!(message, "idle timeout after 0.2s"),
+            other => panic!("expected an idle timeout, got {other:?}"),
+        }
+    }
+
+    #[cfg(feature = "stream")]
+    #[tokio::test]
+    async fn stream_keep_alives_count_as_activity() {
+        use futures::StreamExt;
+
+        let ping = (Duration::from_millis(100), ": ping\n\n");
+        let url = stalling_server(vec![ping, ping, ping, ping, ping, ping]).await;
+        let options = crate::types::StreamOptions::new()
+            .with_idle_timeout(Duration::from_millis(300))
+            .with_overall_timeout(Duration::from_millis(450));
+        let responses = Responses::new(reqwest::Client::new(), url);
+
+        let events: Vec<_> = tokio::time::timeout(
+            Duration::from_secs(5),
+            responses
+                .stream_with_options(crate::Request::default(), options)
+                .collect(),
+        )
+        .await
+        .expect("the overall timeout should end the stream");
+
+        // Pings only produce heartbeats, and keep the idle timeout from firing
+        let (last, heartbeats) = events.split_last().unwrap();
+        assert!(!heartbeats.is_empty());
+        assert!(heartbeats
+            .iter()
+            .all(|event| matches!(event, Ok(crate::types::StreamEvent::Chunk))));
+        match last {
+            Err(crate::Error::Stream(message)) => {
+                assert_eq!(message, "overall timeout after 0.45s");
+            }
+            other => panic!("expected an overall timeout, got {other:?}"),
+        }
+    }
+
+    /// Collects span fields and events so tests can inspect what the crate emits
+    #[cfg(feature = "tracing")]
+    #[derive(Clone, Default)]
+    struct CaptureLayer {
+        closed_spans: Captured<String>,
+        events: Captured<Option<String>>,
+    }
+
+    /// Span names paired with fields, shared between the layer and the test
+    #[cfg(feature = "tracing")]
+    type Captured<T> = std::sync::Arc<std::sync::Mutex<Vec<(T, SpanFields)>>>;
+
+    #[cfg(feature = "tracing")]
+    #[derive(Clone, Debug, Default)]
+    struct SpanFields(std::collections::BTreeMap<String, String>);
+
+    #[cfg(feature = "tracing")]
+    impl tracing::field::Visit for SpanFields {
+        fn record_str(&mut self, field: &tracing::field::Field, value: &str) {
+            self.0.insert(field.name().to_string(), value.to_string());
+        }
+
+        fn record_debug(&mut self, field: &tracing::field::Field, value: &dyn std::fmt::Debug) {
+            self.0
+                .insert(field.name().to_string(), format!("{value:?}"));
+        }
+    }
+
+    #[cfg(feature = "tracing")]
+    impl<S> tracing_subscriber::Layer<S> for CaptureLayer
+    where
+        S: tracing::Subscriber + for<'a> tracing_subscriber::registry::LookupSpan<'a>,
+    {
+        fn on_new_span(
+            &self,
+            attrs: &tracing::span::Attributes<'_>,
+            id: &tracing::span::Id,
+            ctx: tracing_subscriber::layer::Context<'_, S>,
+        ) {
+            let mut fields = SpanFields::default();
+            attrs.record(&mut fields);
+            ctx.span(id).unwrap().extensions_mut().insert(fields);
+        }
+
+        fn on_record(
+            &self,
+            id: &tracing::span::Id,
+            values: &tracing::span::Record<'_>,
+            ctx: tracing_subscriber::layer::Context<'_, S>,
+        ) {
+            let span = ctx.span(id).unwrap();
+            let mut extensions = span.extensions_mut();
+            values.record(extensions.get_mut::<SpanFields>().unwrap());
+        }
+
+        fn on_event(
+            &self,
+            event: &tracing::Event<'_>,
+            ctx: tracing_subscriber::layer::Context<'_, S>,
+        ) {
+            let mut fields = SpanFields::default();
+            event.record(&mut fields);
+            let span = ctx.event_span(event).map(|span| span.name().to_string());
+            self.events.lock().unwrap().push((span, fields));
+        }
+
+        fn on_close(&self, id: tracing::span::Id, ctx: tracing_subscriber::layer::Context<'_, S>) {
+            let span = ctx.span(&id).unwrap();
+            let fields = span
+                .extensions()
+                .get::<SpanFields>()
+                .cloned()
+                .unwrap_or_default();
+            self.closed_spans
+                .lock()
+                .unwrap()
+                .push((span.name().to_string(), fields));
+        }
+    }
+
+    #[cfg(feature = "tracing")]
+    #[tokio::test]
+    async fn retried_create_is_traced_with_span_fields() {
+        use tracing_subscriber::layer::SubscriberExt;
+
+        let mut server = mockito::Server::new_async().await;
+        let unavailable = server
+            .mock("POST", "/responses")
+            .with_status(503)
+            .with_header("retry-after", "0")
+            .expect(1)
+            .create_async()
+            .await;
+        let ok = server
+            .mock("POST", "/responses")
+            .with_body(
+                r#"{"id":"resp_traced","object":"response","created_at":1700000000,"model":"gpt-4o","status":"completed","output":[]}"#,
+            )
+            .expect(1)
+            .create_async()
+            .await;
+
+        let capture = CaptureLayer::default();
+        let _guard =
+            tracing::subscriber::set_default(tracing_subscriber::registry().with(capture.clone()));
+        let policy = RecoveryPolicy::default().with_logging(false);
+        let responses = Responses::new_with_recovery(reqwest::Client::new(), server.url(), policy);
+        let request = crate::Request::builder()
+            .model("gpt-4o")
+            .input("hi")
+            .build();
+        let result = responses.create_with_recovery(request).await.unwrap();
+        assert_eq!(result.recovery_info.retry_count, 1);
+        unavailable.assert_async().await;
+        ok.assert_async().await;
+
+        let events = capture.events.lock().unwrap();
+        let (span, retry) = events
+            .iter()
+            .find(|(_, fields)| {
+                fields.0.get("message").map(String::as_str)
+                    == Some("Retrying after recoverable error")
+            })
+            .expect("a retry event");
+        assert_eq!(span.as_deref(), Some("create_with_recovery"));
+        assert_eq!(retry.0["retry_count"], "1");
+        assert_eq!(retry.0["error_class"], "retryable_server");
+        assert_eq!(retry.0["delay_ms"], "0");
+
+        let spans = capture.closed_spans.lock().unwrap();
+        let (_, fields) = spans
+            .iter()
+            .find(|(name, _)| name == "create_with_recovery")
+            .expect("a closed recovery span");
+        assert_eq!(fields.0["model"], "gpt-4o");
+        assert_eq!(fields.0["retry_count"], "1");
+        assert_eq!(fields.0["error_class"], "retryable_server");
+        assert_eq!(fields.0["response_id"], "resp_traced");
+    }
+
+    fn response_body(id: &str, status: &str) -> String {
+        format!(
+            r#"{{"id":"{id}","object":"response","created_at":1700000000,"model":"gpt-4o","status":"{status}","output":[]}}"#
+        )
+    }
+
+    #[tokio::test]
+    async fn cancel_maps_statuses_to_outcomes() {
+        let mut server = mockito::Server::new_async().await;
+        let cancelled = server
+            .mock("POST", "/responses/resp_running/cancel")
+            .with_status(200)
+            .with_body(response_body("resp_running", "cancelled"))
+            .expect(1)
+            .create_async()
+            .await;
+        let missing = server
+            .mock("POST", "/responses/resp_missing/cancel")
+            .with_status(404)
+            .with_body(
+                r#"{"error":{"message":"Response not found","type":"invalid_request_error"}}"#,
+            )
+            .expect(1)
+            .create_async()
+            .await;
+        let mut finished = Vec::new();
+        for (id, status) in [("resp_done", 409), ("resp_locked", 423)] {
+            finished.push(
+                server
+                    .mock("POST", format!("/responses/{id}/cancel").as_str())
+                    .with_status(status)
+                    .with_body(r#"{"error":{"message":"Cannot cancel a completed response","type":"invalid_request_error"}}"#)
+                    .expect(1)
+                    .create_async()
+                    .await,
+            );
+            finished.push(
+                server
+                    .mock("GET", format!("/responses/{id}").as_str())
+                    .with_status(200)
+                    .with_body(response_body(id, "completed"))
+                    .expect(1)
+                    .create_async()
+                    .await,
+            );
+        }
+
+        let responses = Responses::new(reqwest::Client::new(), server.url());
+        let outcome = responses.cancel("resp_running").await.unwrap();
+        assert!(outcome.is_cancelled());
+        assert_eq!(
+            outcome.response().unwrap().status,
+            crate::types::ResponseStatus::Cancelled
+        );
+        assert_eq!(
+            responses.cancel("resp_missing").await.unwrap(),
+            CancelOutcome::NotFound
+        );
+        for id in ["resp_done", "resp_locked"] {
+            match responses.cancel(id).await.unwrap() {
+                CancelOutcome::AlreadyCompleted(response) => {
+                    assert_eq!(response.id, id);
+                    assert_eq!(response.status, crate::types::ResponseStatus::Completed);
+                }
+                other => panic!("expected AlreadyCompleted, got {other:?}"),
+            }
+        }
+        cancelled.assert_async().await;
+        missing.assert_async().await;
+        for mock in finished {
+            mock.assert_async().await;
+        }
+    }
+
+    #[tokio::test]
+    async fn cancel_still_errors_on_other_statuses() {
+        let mut server = mockito::Server::new_async().await;
+        let mock = server
+            .mock("POST", "/responses/resp_1/cancel")
+            .with_status(400)
+            .with_body(r#"{"error":{"message":"Only background responses can be cancelled","type":"invalid_request_error"}}"#)
+            .expect(1)
+            .create_async()
+            .await;
+
+        let responses = Responses::new(reqwest::Client::new(), server.url());
+        let error = responses.cancel("resp_1").await.unwrap_err();
+        assert!(
+            matches!(
+                error,
+                crate::Error::ClientError {
+                    status_code: 400,
+                    ..
+                }
+            ),
+            "{error:?}"
+        );
+        mock.assert_async().await;
+    }
+
+    #[tokio::test]
+    async fn delete_reports_not_found_unless_strict() {
+        let mut server = mockito::Server::new_async().await;
+        let deleted = server
+            .mock("DELETE", "/responses/resp_1")
+            .with_status(200)
+            .with_body(r#"{"id":"resp_1","object":"response.deleted","deleted":true}"#)
+            .expect(1)
+            .create_async()
+            .await;
+        let missing = server
+            .mock("DELETE", "/responses/resp_gone")
+            .with_status(404)
+            .with_body(
+                r#"{"error":{"message":"Response not found","type":"invalid_request_error"}}"#,
+            )
+            .expect(2)
+            .create_async()
+            .await;
+
+        let responses = Responses::new(reqwest::Client::new(), server.url());
+        assert_eq!(
+            responses.delete("resp_1").await.unwrap(),
+            DeleteOutcome::Deleted
+        );
+        assert_eq!(
+            responses.delete("resp_gone").await.unwrap(),
+            DeleteOutcome::NotFound
+        );
+
+        let strict = responses.with_strict_deletes(true);
+        let error = strict.delete("resp_gone").await.unwrap_err();
+        assert!(matches!(error, crate::Error::Api { .. }), "{error:?}");
+        deleted.assert_async().await;
+        missing.assert_async().await;
+    }
+
+    #[tokio::test]
+    async fn delete_confirmed_keeps_the_api_confirmation() {
+        use crate::types::{DeleteConfirmation, DeletionStatus};
+
+        let mut server = mockito::Server::new_async().await;
+        for (id, deleted) in [("resp_1", true), ("resp_kept", false)] {
+            server
+                .mock("DELETE", format!("/responses/{id}").as_str())
+                .with_status(200)
+                .with_body(format!(
+                    r#"{{"id":"{id}","object":"response.deleted","deleted":{deleted}}}"#
+                ))
+                .expect(2)
+                .create_async()
+                .await;
+        }
+        server
+            .mock("DELETE", "/responses/resp_gone")
+            .with_status(404)
+            .with_body(
+                r#"{"error":{"message":"Response not found","type":"invalid_request_error"}}"#,
+            )
+            .create_async()
+            .await;
+
+        let responses = Responses::new(reqwest::Client::new(), server.url());
+        let confirmation = responses.delete_confirmed("resp_1").await.unwrap();
+        assert_eq!(
+            confirmation,
+            DeleteConfirmation::Confirmed(DeletionStatus {
+                id: "resp_1".to_string(),
+                object: "response.deleted".to_string(),
+                deleted: true,
+            })
+        );
+        assert!(confirmation.is_deleted());
+
+        let kept = responses.delete_confirmed("resp_kept").await.unwrap();
+        assert_eq!(kept.status().map(|status| status.deleted), Some(false));
+        assert!(!kept.is_deleted());
+        assert_eq!(
+            responses.delete_confirmed("resp_gone").await.unwrap(),
+            DeleteConfirmation::NotFound
+        );
+
+        // `delete` reduces the confirmation; `deleted: false` is not a deletion
+        assert_eq!(
+            responses.delete("resp_1").await.unwrap(),
+            DeleteOutcome::Deleted
+        );
+        assert_eq!(
+            responses.delete("resp_kept").await.unwrap(),
+            DeleteOutcome::NotFound
+        );
+    }
+
+    #[tokio::test]
+    async fn oversized_requests_are_rejected_before_sending() {
+        let mut server = mockito::Server::new_async().await;
+        let mock = server
+            .mock("POST", "/responses")
+            .with_status(200)
+            .with_body(response_body("resp_small", "completed"))
+            .expect(2)
+            .create_async()
+            .await;
+
+        let responses = Responses::new(reqwest::Client::new(), server.url())
+            .with_request_size_limits(
+                crate::types::RequestSizeLimits::new()
+                    .with_warn_above_bytes(100)
+                    .with_reject_above_bytes(10_000),
+            );
+        let oversized = crate::Request::builder()
+            .model("gpt-4o")
+            .instructions("x".repeat(20_000))
+            .input("hi")
+            .build();
+        for error in [
+            responses
+                .create_no_recovery(oversized.clone())
+                .await
+                .unwrap_err(),
+            responses.create_with_recovery(oversized).await.unwrap_err(),
+        ] {
+            assert!(
+                matches!(
+                    &error,
+                    crate::Error::RequestTooLarge { limit: 10_000, actual, largest_field }
+                        if *actual > 20_000 && largest_field == "instructions"
+                ),
+                "{error:?}"
+            );
+        }
+
+        // Above the warning threshold only: logged and sent
+        let warned = crate::Request::builder()
+            .model("gpt-4o")
+            .instructions("x".repeat(500))
+            .input("hi")
+            .build();
+        responses.create_no_recovery(warned.clone()).await.unwrap();
+        responses.create_with_recovery(warned).await.unwrap();
+        mock.assert_async().await;
     }
 }
@@ -0,0 +1,435 @@
+//! Client-side rate limiting shared by every clone of a client.
+//!
+//! Each limit is a token bucket that refills continuously. A caller reserves its
+//! share under a lock and then sleeps outside it, so concurrent requests queue up
+//! one behind another instead of all firing at once and retrying in lockstep.
+
+use crate::runtime::Stopwatch;
+use reqwest::header::HeaderMap;
+use std::sync::{Arc, Mutex};
+use std::time::Duration;
+
+/// Settings for the limiter enabled by [`Client::with_rate_limiter`](crate::Client::with_rate_limiter)
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct RateLimitConfig {
+    /// Requests allowed per minute; `None` leaves requests unlimited
+    pub requests_per_minute: Option<u32>,
+
+    /// Tokens allowed per minute; `None` leaves tokens unlimited
+    pub tokens_per_minute: Option<u32>,
+
+    /// Requests that may be sent back to back before pacing starts
+    pub burst: u32,
+
+    /// Adjust to the `x-ratelimit-remaining-*` and `x-ratelimit-reset-*` response headers
+    pub follow_headers: bool,
+}
+
+impl Default for RateLimitConfig {
+    fn default() -> Self {
+        Self {
+            requests_per_minute: None,
+            tokens_per_minute: None,
+            burst: 1,
+            follow_headers: true,
+        }
+    }
+}
+
+impl RateLimitConfig {
+    /// Creates a config with the given request and token budgets per minute
+    #[must_use]
+    pub fn new(requests_per_minute: u32, tokens_per_minute: u32) -> Self {
+        Self {
+            requests_per_minute: Some(requests_per_minute),
+            tokens_per_minute: Some(tokens_per_minute),
+            ..Self::default()
+        }
+    }
+
+    /// Creates a config that only limits requests per minute
+    #[must_use]
+    pub fn requests_per_minute(requests_per_minute: u32) -> Self {
+        Self {
+            requests_per_minute: Some(requests_per_minute),
+            ..Self::default()
+        }
+    }
+
+    /// Sets how many requests may be sent back to back before pacing starts (at least 1)
+    #[must_use]
+    pub fn with_burst(mut self, burst: u32) -> Self {
+        self.burst = burst.max(1);
+        self
+    }
+
+    /// Sets whether rate limit response headers tighten the limiter
+    #[must_use]
+    pub fn with_follow_headers(mut self, follow: bool) -> Self {
+        self.follow_headers = follow;
+        self
+    }
+}
+
+/// A continuously refilling budget. `available` may go negative while callers wait.
+#[derive(Debug)]
+struct Bucket {
+    capacity: f64,
+    per_second: f64,
+    available: f64,
+    updated: Duration,
+}
+
+impl Bucket {
+    fn new(per_minute: u32, capacity: f64) -> Self {
+        Self {
+            capacity,
+            per_second: f64::from(per_minute) / 60.0,
+            available: capacity,
+            updated: Duration::ZERO,
+        }
+    }
+
+    fn refill(&mut self, now: Duration) {
+        let elapsed = now.saturating_sub(self.updated).as_secs_f64();
+        self.available = (self.available + elapsed * self.per_second).min(self.capacity);
+        self.updated = now;
+    }
+
+    /// Caps a single request's share at the capacity, so one oversized request waits
+    /// for a full bucket instead of pushing everyone behind it back by minutes
+    fn share(&self, amount: u32) -> f64 {
+        f64::from(amount).min(self.capacity)
+    }
+
+    /// Takes `amount` and returns how long until it is covered
+    fn reserve(&mut self, amount: f64, now: Duration) -> Duration {
+        self.refill(now);
+        self.available -= amount;
+        self.wait()
+    }
+
+    fn wait(&self) -> Duration {
+        if self.available >= 0.0 || self.per_second <= 0.0 {
+            Duration::ZERO
+        } else {
+            Duration::from_secs_f64(-self.available / self.per_second)
+        }
+    }
+
+    /// Lowers the budget to what the server reports as remaining
+    fn clamp(&mut self, remaining: f64, reset: Option<Duration>, now: Duration) {
+        self.refill(now);
+        let floor = match reset {
+            // Nothing left: hold everyone back until the window resets
+            Some(reset) if remaining < 1.0 => -reset.as_secs_f64() * self.per_second,
+            _ => remaining,
+        };
+        self.available = self.available.min(floor);
+    }
+}
+
+#[derive(Debug)]
+struct State {
+    requests: Option<Bucket>,
+    tokens: Option<Bucket>,
+}
+
+/// Token-bucket limiter shared between clones of a client
+#[derive(Debug, Clone)]
+pub(crate) struct RateLimiter {
+    config: RateLimitConfig,
+    clock: Stopwatch,
+    state: Arc<Mutex<State>>,
+}
+
+impl RateLimiter {
+    pub(crate) fn new(config: &RateLimitConfig) -> Self {
+        let burst = f64::from(config.burst.max(1));
+        let requests = config
+            .requests_per_minute
+            .map(|rpm| Bucket::new(rpm, burst));
+        // Allow the same burst of tokens as an evenly split request would use
+        let tokens = config.tokens_per_minute.map(|tpm| {
+            let per_request = match config.requests_per_minute {
+                Some(rpm) if rpm > 0 => f64::from(tpm) / f64::from(rpm),
+                _ => f64::from(tpm) / 60.0,
+            };
+            Bucket::new(tpm, per_request * burst)
+        });
+        Self {
+            config: *config,
+            clock: Stopwatch::start(),
+            state: Arc::new(Mutex::new(State { requests, tokens })),
+        }
+    }
+
+    /// A new limiter with the same settings and full buckets, shared with nothing
+    pub(crate) fn fresh(&self) -> Self {
+        Self::new(&self.config)
+    }
+
+    /// Waits until a request using roughly `estimated_tokens` may be sent
+    pub(crate) async fn acquire(&self, estimated_tokens: u32) {
+        let wait = {
+            let Ok(mut state) = self.state.lock() else {
+                return;
+            };
+            let now = self.clock.elapsed();
+            let requests = state
+                .requests
+                .as_mut()
+                .map_or(Duration::ZERO, |bucket| bucket.reserve(1.0, now));
+            let tokens = state.tokens.as_mut().map_or(Duration::ZERO, |bucket| {
+                bucket.reserve(bucket.share(estimated_tokens), now)
+            });
+            requests.max(tokens)
+        };
+        if !wait.is_zero() {
+            crate::diag::debug!("Rate limiter delaying request by {wait:?}");
+            crate::runtime::sleep(wait).await;
+        }
+    }
+
+    /// Corrects the token budget once the real usage of a request is known
+    pub(crate) fn record_usage(&self, estimated_tokens: u32, used_tokens: u32) {
+        if let Ok(mut state) = self.state.lock() {
+            if let Some(bucket) = state.tokens.as_mut() {
+                bucket.available += bucket.share(estimated_tokens) - bucket.share(used_tokens);
+            }
+        }
+    }
+
+    /// Holds every request back for `delay`, e.g. after a 429 with `retry-after`
+    pub(crate) fn pause(&self, delay: Duration) {
+        if let Ok(mut state) = self.state.lock() {
+            let now = self.clock.elapsed();
+            let State { requests, tokens } = &mut *state;
+            for bucket in [requests, tokens].into_iter().flatten() {
+                bucket.clamp(0.0, Some(delay), now);
+            }
+        }
+    }
+
+    /// Tightens the buckets to the server's `x-ratelimit-*` headers
+    pub(crate) fn observe_headers(&self, headers: &HeaderMap) {
+        if !self.config.follow_headers {
+            return;
+        }
+        let Ok(mut state) = self.state.lock() else {
+            return;
+        };
+        let now = self.clock.elapsed();
+        let State { requests, tokens } = &mut *state;
+        for (kind, bucket) in [("requests", requests), ("tokens", tokens)] {
+            let (Some(bucket), Some(remaining)) = (
+                bucket.as_mut(),
+                header(headers, &format!("x-ratelimit-remaining-{kind}"))
+                    .and_then(|value| value.parse::<f64>().ok()),
+            ) else {
+                continue;
+            };
+            let reset = header(headers, &format!("x-ratelimit-reset-{kind}"))
+                .and_then(parse_reset_duration);
+            bucket.clamp(remaining, reset, now);
+        }
+    }
+}
+
+fn header<'a>(headers: &'a HeaderMap, name: &str) -> Option<&'a str> {
+    headers.get(name)?.to_str().ok()
+}
+
+/// Rough token estimate for a request: about four bytes of text per token plus the output cap.
+///
+/// Only text counts. Images and files sent inline as base64 would otherwise dominate the
+/// estimate while costing far fewer tokens.
+pub(crate) fn estimate_tokens(request: &crate::Request) -> u32 {
+    let input = match &request.input {
+        crate::types::Input::Text(text) => text.len(),
+        crate::types::Input::Items(items) => items.iter().map(item_text_len).sum(),
+    };
+    let tools = request
+        .tools
+        .as_ref()
+        .and_then(|tools| serde_json::to_vec(tools).ok())
+        .map_or(0, |json| json.len());
+    let bytes = input + tools + request.instructions.as_ref().map_or(0, String::len);
+    u32::try_from(bytes / 4)
+        .unwrap_or(u32::MAX)
+        .saturating_add(request.max_output_tokens.unwrap_or(0))
+}
+
+fn item_text_len(item: &crate::types::InputItem) -> usize {
+    let fields = [&item.text, &item.output, &item.arguments, &item.input];
+    fields.into_iter().flatten().map(String::len).sum::<usize>()
+        + item.content.as_ref().map_or(0, content_text_len)
+}
+
+/// Length of the text in a message's content: a plain string or the `text` of its parts
+fn content_text_len(content: &serde_json::Value) -> usize {
+    match content {
+        serde_json::Value::String(text) => text.len(),
+        serde_json::Value::Array(parts) => parts.iter().map(content_text_len).sum(),
+        serde_json::Value::Object(part) => part.get("text").map_or(0, content_text_len),
+        _ => 0,
+    }
+}
+
+/// Parses reset durations such as `1s`, `6m0s`, `20ms` or `1h2m3.5s`
+fn parse_reset_duration(value: &str) -> Option<Duration> {
+    let mut total = 0.0;
+    let mut rest = value.trim();
+    if rest.is_empty() {
+        return None;
+    }
+    while !rest.is_empty() {
+        let number_len = rest
+            .find(|c: char| !(c.is_ascii_digit() || c == '.'))
+            .unwrap_or(rest.len());
+        let number: f64 = rest[..number_len].parse().ok()?;
+        rest = &rest[number_len..];
+        let unit_len = rest
+            .find(|c: char| c.is_ascii_digit() || c == '.')
+            .unwrap_or(rest.len());
+        let seconds = match &rest[..unit_len] {
+            "ms" => number / 1000.0,
+            "s" | "" => number,
+            "m" => number * 60.0,
+            "h" => number * 3600.0,
+            _ => return None,
+        };
+        total += seconds;
+        rest = &rest[unit_len..];
+    }
+    Duration::try_from_secs_f64(total).ok()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::responses::Responses;
+    use std::time::Duration;
+
+    const BODY: &str = r#"{"id":"resp_1","object":"response","created_at":1700000000,"model":"gpt-4o","status":"completed","output":[]}"#;
+
+    fn request() -> crate::Request {
+        crate::Request::builder()
+            .model("gpt-4o")
+            .input("hello")
+            .build()
+    }
+
+    #[tokio::test(start_paused = true)]
+    async fn concurrent_creates_are_spaced_out() {
+        let mut server = mockito::Server::new_async().await;
+        let mock = server
+            .mock("POST", "/responses")
+            .with_status(200)
+            .with_body(BODY)
+            .expect(3)
+            .create_async()
+            .await;
+
+        // 2 requests per second, shared by the clones
+        let responses = Responses::new(reqwest::Client::new(), server.url())
+            .with_rate_limiter(RateLimitConfig::requests_per_minute(120));
+        let start = tokio::time::Instant::now();
+        let send = |responses: Responses| async move {
+            responses.create(request()).await.unwrap();
+            start.elapsed()
+        };
+        let (a, b, c) = tokio::join!(
+            send(responses.clone()),
+            send(responses.clone()),
+            send(responses)
+        );
+        let mut elapsed = [a, b, c];
+        elapsed.sort();
+
+        assert!(elapsed[1] >= Duration::from_millis(500), "{elapsed:?}");
+        assert!(elapsed[2] >= Duration::from_secs(1), "{elapsed:?}");
+        mock.assert_async().await;
+    }
+
+    #[tokio::test(start_paused = true)]
+    async fn exhausted_headers_hold_requests_until_reset() {
+        let limiter = RateLimiter::new(&RateLimitConfig::new(6000, 1_000_000).with_burst(10));
+        let mut headers = HeaderMap::new();
+        headers.insert("x-ratelimit-remaining-requests", "0".parse().unwrap());
+        headers.insert("x-ratelimit-reset-requests", "2s".parse().unwrap());
+        headers.insert("x-ratelimit-remaining-tokens", "500000".parse().unwrap());
+        limiter.observe_headers(&headers);
+
+        let start = tokio::time::Instant::now();
+        limiter.acquire(100).await;
+        assert!(start.elapsed() >= Duration::from_secs(2));
+
+        // Without header following the burst is available immediately
+        let ignoring = RateLimiter::new(
+            &RateLimitConfig::requests_per_minute(60)
+                .with_burst(2)
+                .with_follow_headers(false),
+        );
+        ignoring.observe_headers(&headers);
+        let start = tokio::time::Instant::now();
+        ignoring.acquire(0).await;
+        ignoring.acquire(0).await;
+        assert!(start.elapsed().is_zero());
+    }
+
+    #[test]
+    fn estimates_count_text_but_not_inline_images() {
+        let image = format!("data:image/png;base64,{}", "A".repeat(400_000));
+        let request = crate::Request::builder()
+            .model("gpt-4o")
+            .instructions("1234")
+            .input_items(vec![
+                crate::types::InputItem::message(
+                    "user",
+                    vec![
+                        crate::types::InputItem::content_text("12345678"),
+                        crate::types::InputItem::content_image_with_detail(&image, "auto"),
+                    ],
+                ),
+                crate::types::InputItem::function_call_output("call_1", "1234"),
+            ])
+            .max_output_tokens(10)
+            .build();
+
+        assert_eq!(estimate_tokens(&request), 4 + 10);
+    }
+
+    #[tokio::test(start_paused = true)]
+    async fn oversized_requests_wait_for_a_full_bucket_at_most() {
+        // 1000 tokens per request, refilling at 1000 tokens per second
+        let limiter = RateLimiter::new(&RateLimitConfig::new(60, 60_000));
+
+        let start = tokio::time::Instant::now();
+        limiter.acquire(1_000_000).await;
+        limiter.acquire(1_000_000).await;
+        assert_eq!(start.elapsed(), Duration::from_secs(1));
+
+        // Real usage far above the estimate is clamped the same way
+        limiter.record_usage(1_000_000, 5_000_000);
+        let start = tokio::time::Instant::now();
+        limiter.acquire(1_000).await;
+        assert_eq!(start.elapsed(), Duration::from_secs(1));
+    }
+
+    #[test]
+    fn parses_reset_durations() {
+        assert_eq!(parse_reset_duration("1s"), Some(Duration::from_secs(1)));
+        assert_eq!(
+            parse_reset_duration("20ms"),
+            Some(Duration::from_millis(20))
+        );
+        assert_eq!(parse_reset_duration("6m0s"), Some(Duration::from_secs(360)));
+        assert_eq!(
+            parse_reset_duration("1h2m3.5s"),
+            Some(Duration::from_secs_f64(3723.5))
+        );
+        assert_eq!(parse_reset_duration(""), None);
+        assert_eq!(parse_reset_duration("soon"), None);
+    }
+}
@@ -0,0 +1,547 @@
+//! Retry logic shared by every endpoint that honours the client's [`RecoveryPolicy`].
+//!
+//! [`Responses`](crate::responses::Responses) runs its own loop on top of [`decide`],
+//! [`log_retry`], [`wait`] and [`give_up`], because it also rewrites the request between
+//! attempts. The other endpoints resend the same call through [`execute_with_policy`],
+//! usually via [`Retrying`].
+
+use crate::error::{ErrorClass, Result};
+use crate::http::Transport;
+use crate::types::{RecoveryPolicy, RetryScope};
+use reqwest::{RequestBuilder, StatusCode};
+use serde::de::DeserializeOwned;
+use std::future::Future;
+use std::time::Duration;
+
+/// Upper bound for the backoff applied to flex-tier "Resource unavailable" errors
+const MAX_RESOURCE_UNAVAILABLE_DELAY: Duration = Duration::from_secs(300);
+
+/// Whether a failed attempt should be retried
+pub(crate) enum Verdict {
+    /// Retry after waiting this long, jitter included
+    Retry { delay: Duration },
+    /// Return the error
+    Stop,
+}
+
+/// Decides whether `policy` allows retrying `error` after `retry_count` retries
+pub(crate) fn decide(policy: &RecoveryPolicy, error: &crate::Error, retry_count: u32) -> Verdict {
+    let classification = error.classify();
+    let container_expired = matches!(
+        classification,
+        ErrorClass::ContainerExpired | ErrorClass::ApiContainerExpired
+    );
+    let scope_allows_retry = match policy.retry_scope {
+        RetryScope::AllRecoverable => error.is_recoverable(),
+        RetryScope::ContainerOnly => container_expired,
+        RetryScope::TransientOnly => matches!(
+            classification,
+            ErrorClass::TransientHttp | ErrorClass::RetryableServer
+        ),
+    };
+    let stop_reason = if retry_count >= policy.max_retries {
+        Some("max_retries_reached")
+    } else if container_expired && !policy.auto_retry_on_expired_container {
+        Some("auto_retry_disabled")
+    } else if classification == ErrorClass::PreviousResponseNotFound
+        && !policy.reset_missing_previous_response
+    {
+        Some("chain_reset_disabled")
+    } else if !error.is_recoverable() {
+        Some("non_recoverable")
+    } else if !scope_allows_retry {
+        Some("scope_restricted")
+    } else {
+        None
+    };
+
+    let scope = policy.retry_scope.as_str();
+    let suggested_retry_after = error.retry_after();
+    if let Some(reason) = stop_reason {
+        crate::diag::record!("error_class", classification.as_str());
+        if policy.log_recovery_attempts {
+            crate::diag::debug!(
+                "Retry decision: classification={classification}, scope={scope}, retry_count={retry_count}, retry_after={suggested_retry_after:?}, decision=Stop, reason={reason}"
+            );
+        }
+        return Verdict::Stop;
+    }
+
+    let mut base = suggested_retry_after.unwrap_or(Duration::from_secs(1));
+    if error.is_resource_unavailable() {
+        // Flex capacity frees up slowly; back off exponentially
+        base = base
+            .saturating_mul(2_u32.saturating_pow(retry_count))
+            .min(MAX_RESOURCE_UNAVAILABLE_DELAY);
+    }
+    let delay = policy.jitter.apply(base, random_unit());
+    if policy.log_recovery_attempts {
+        let next_retry_count = retry_count.saturating_add(1);
+        let jitter = policy.jitter.as_str();
+        crate::diag::debug!(
+            "Retry decision: classification={classification}, scope={scope}, retry_count={retry_count}->{next_retry_count}, retry_after={base:?}, jitter={jitter}, delay={delay:?}, decision=Continue"
+        );
+    }
+    Verdict::Retry { delay }
+}
+
+/// Random number in `[0, 1)` for jitter, from the same source as idempotency keys
+fn random_unit() -> f64 {
+    // The top 53 bits fill an f64 mantissa exactly
+    let bits = uuid::Uuid::new_v4().as_u64_pair().0 >> 11;
+    #[allow(clippy::cast_precision_loss)]
+    let unit = bits as f64 / (1_u64 << 53) as f64;
+    unit
+}
+
+/// Turns the error that ended a retry loop into the error to return.
+///
+/// Once any retry was made this is [`Error::MaxRetriesExceeded`](crate::Error::MaxRetriesExceeded);
+/// otherwise the error itself.
+pub(crate) fn give_up(
+    policy: &RecoveryPolicy,
+    error: crate::Error,
+    retry_count: u32,
+) -> crate::Error {
+    if retry_count == 0 {
+        return error;
+    }
+    if policy.log_recovery_attempts {
+        crate::diag::error!("Recovery failed after {retry_count} attempts: {error}");
+    }
+    crate::Error::MaxRetriesExceeded {
+        attempts: retry_count,
+    }
+}
+
+/// Logs a retry based on the error type.
+///
+/// With the `tracing` feature every retry is also recorded on the current span and
+/// emitted as an event, whether or not `log_recovery_attempts` is set.
+pub(crate) fn log_retry(
+    policy: &RecoveryPolicy,
+    error: &crate::Error,
+    retry_count: u32,
+    delay: Duration,
+) {
+    let classification = error.classify();
+    crate::diag::record!("retry_count", retry_count);
+    crate::diag::record!("error_class", classification.as_str());
+    #[cfg(feature = "tracing")]
+    tracing::info!(
+        retry_count,
+        error_class = %classification,
+        delay_ms = u64::try_from(delay.as_millis()).unwrap_or(u64::MAX),
+        "Retrying after recoverable error"
+    );
+
+    if !policy.log_recovery_attempts {
+        return;
+    }
+
+    let max_retries = policy.max_retries;
+    match classification {
+        ErrorClass::ContainerExpired | ErrorClass::ApiContainerExpired => {
+            crate::diag::warn!(
+                "Container expired, attempting recovery (attempt {retry_count}/{max_retries})"
+            );
+        }
+        ErrorClass::RetryableServer => {
+            let what = match error {
+                crate::Error::BadGateway { .. } => "Bad Gateway error",
+                crate::Error::ServiceUnavailable { .. } => "Service unavailable",
+                crate::Error::GatewayTimeout { .. } => "Gateway timeout",
+                crate::Error::ServerError {
+                    retry_suggested: true,
+                    ..
+                } => "Server error (retryable)",
+                _ => {
+                    log_generic_retry(error, retry_count, max_retries);
+                    return;
+                }
+            };
+            crate::diag::warn!(
+                "{what}, retrying in {delay:?} (attempt {retry_count}/{max_retries})"
+            );
+        }
+        ErrorClass::PreviousResponseNotFound => {
+            crate::diag::warn!(
+                "Previous response not found, retrying as a new conversation (attempt {retry_count}/{max_retries})"
+            );
+        }
+        ErrorClass::RateLimited => {
+            crate::diag::warn!(
+                "Rate limited, retrying in {delay:?} (attempt {retry_count}/{max_retries})"
+            );
+        }
+        ErrorClass::TransientHttp => {
+            let crate::Error::Http(reqwest_error) = error else {
+                log_generic_retry(error, retry_count, max_retries);
+                return;
+            };
+            if reqwest_error.is_timeout() {
+                crate::diag::warn!(
+                    "HTTP timeout, retrying in {delay:?} (attempt {retry_count}/{max_retries})"
+                );
+            } else if crate::runtime::is_connect_error(reqwest_error) {
+                crate::diag::warn!(
+                    "HTTP connection error, retrying in {delay:?} (attempt {retry_count}/{max_retries})"
+                );
+            } else if reqwest_error.is_request() {
+                crate::diag::warn!(
+                    "HTTP request error, attempting recovery (attempt {retry_count}/{max_retries})"
+                );
+            } else {
+                crate::diag::warn!(
+                    "Recoverable HTTP error, attempting recovery (attempt {retry_count}/{max_retries}): {reqwest_error}"
+                );
+            }
+        }
+        ErrorClass::NonRecoverable => {
+            crate::diag::warn!(
+                "Retrying after unexpected classification ({classification}) (attempt {}/{}): {}",
+                retry_count,
+                max_retries,
+                error.user_message()
+            );
+        }
+    }
+}
+
+fn log_generic_retry(error: &crate::Error, retry_count: u32, max_retries: u32) {
+    crate::diag::warn!(
+        "Recoverable error, attempting recovery (attempt {}/{}): {}",
+        retry_count,
+        max_retries,
+        error.user_message()
+    );
+}
+
+/// Waits before retrying `error`; container expiry is retried at once
+pub(crate) async fn wait(error: &crate::Error, delay: Duration) {
+    let delay = effective_delay(error, delay);
+    if !delay.is_zero() {
+        // Runtime-agnostic sleep so the executor (tokio or wasm) is never blocked
+        crate::runtime::sleep(delay).await;
+    }
+}
+
+/// How long [`wait`] sleeps before retrying `error`; container expiry is retried at once
+pub(crate) fn effective_delay(error: &crate::Error, delay: Duration) -> Duration {
+    if error.is_transient() && !error.is_container_expired() {
+        delay
+    } else {
+        Duration::ZERO
+    }
+}
+
+/// Runs `operation` until it succeeds or `policy` stops retrying its error.
+///
+/// `operation` is called with the attempt number, starting at 1. Retries are logged
+/// rather than reported to the caller.
+pub(crate) async fn execute_with_policy<T, F, Fut>(
+    policy: &RecoveryPolicy,
+    mut operation: F,
+) -> Result<T>
+where
+    F: FnMut(u32) -> Fut,
+    Fut: Future<Output = Result<T>>,
+{
+    let mut retry_count: u32 = 0;
+    loop {
+        match operation(retry_count.saturating_add(1)).await {
+            Ok(value) => {
+                if retry_count > 0 && policy.log_recovery_attempts {
+                    crate::diag::info!("Successfully recovered after {retry_count} attempt(s)");
+                }
+                return Ok(value);
+            }
+            Err(error) => match decide(policy, &error, retry_count) {
+                Verdict::Retry { delay } => {
+                    retry_count = retry_count.saturating_add(1);
+                    log_retry(policy, &error, retry_count, delay);
+                    wait(&error, delay).await;
+                }
+                Verdict::Stop => return Err(give_up(policy, error, retry_count)),
+            },
+        }
+    }
+}
+
+/// Sends requests through a [`Transport`], retrying them as `policy` allows.
+///
+/// Each method takes a closure that builds the request, since a request with a
+/// multipart body cannot be cloned for the next attempt. When
+/// [`RecoveryPolicy::use_idempotency_key`] is set, every attempt of a POST carries the
+/// same idempotency key, so a retry after a timeout or gateway error cannot create a
+/// second file, vector store or billed image.
+pub(crate) struct Retrying<'a> {
+    transport: &'a Transport,
+    policy: &'a RecoveryPolicy,
+}
+
+impl<'a> Retrying<'a> {
+    pub(crate) fn new(transport: &'a Transport, policy: &'a RecoveryPolicy) -> Self {
+        Self { transport, policy }
+    }
+
+    /// Like [`Transport::send`], with retries
+    pub(crate) async fn send(
+        &self,
+        build: impl Fn() -> Result<RequestBuilder>,
+    ) -> Result<reqwest::Response> {
+        self.send_allowing(build, &[]).await
+    }
+
+    /// Like [`Transport::send_allowing`], with retries; allowed statuses are not retried
+    pub(crate) async fn send_allowing(
+        &self,
+        build: impl Fn() -> Result<RequestBuilder>,
+        allowed: &[StatusCode],
+    ) -> Result<reqwest::Response> {
+        let key = self.idempotency_key();
+        let (build, key) = (&build, key.as_deref());
+        execute_with_policy(self.policy, |attempt| async move {
+            let request = self.with_idempotency_key(build()?, key)?;
+            self.transport
+                .send_attempt_allowing(request, attempt, allowed)
+                .await
+        })
+        .await
+    }
+
+    /// Like [`Transport::send_json`], with retries; reading the body is part of each attempt
+    pub(crate) async fn send_json<T: DeserializeOwned>(
+        &self,
+        build: impl Fn() -> Result<RequestBuilder>,
+    ) -> Result<T> {
+        let key = self.idempotency_key();
+        let (build, key) = (&build, key.as_deref());
+        execute_with_policy(self.policy, |attempt| async move {
+            let request = self.with_idempotency_key(build()?, key)?;
+            let response = self.transport.send_attempt(request, attempt).await?;
+            self.transport.json(response).await
+        })
+        .await
+    }
+
+    /// A fresh key for one logical call, if the policy sends them
+    fn idempotency_key(&self) -> Option<String> {
+        self.policy
+            .use_idempotency_key
+            .then(|| uuid::Uuid::new_v4().to_string())
+    }
+
+    /// Adds `key` to `request` if it is a POST; other methods are idempotent already
+    fn with_idempotency_key(
+        &self,
+        request: RequestBuilder,
+        key: Option<&str>,
+    ) -> Result<RequestBuilder> {
+        let Some(key) = key else {
+            return Ok(request);
+        };
+        let (client, request) = request.build_split();
+        let request = request.map_err(crate::Error::Http)?;
+        let is_post = request.method() == reqwest::Method::POST;
+        let request = RequestBuilder::from_parts(client, request);
+        Ok(if is_post {
+            request.header(self.policy.idempotency_header.as_str(), key)
+        } else {
+            request
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn unavailable() -> crate::Error {
+        crate::Error::ServiceUnavailable {
+            retry_message: "overloaded".to_string(),
+            retry_after: Some(Duration::ZERO),
+        }
+    }
+
+    #[tokio::test]
+    async fn execute_with_policy_retries_within_the_limit() {
+        let policy = RecoveryPolicy::aggressive().with_logging(false);
+        let mut attempts = Vec::new();
+        let result = execute_with_policy(&policy, |attempt| {
+            attempts.push(attempt);
+            async move {
+                if attempt < 3 {
+                    Err(unavailable())
+                } else {
+                    Ok(attempt)
+                }
+            }
+        })
+        .await;
+        assert_eq!(result.unwrap(), 3);
+        assert_eq!(attempts, [1, 2, 3]);
+
+        let exhausted: Result<()> =
+            execute_with_policy(&policy, |_| async { Err(unavailable()) }).await;
+        assert!(matches!(
+            exhausted,
+            Err(crate::Error::MaxRetriesExceeded { attempts: 3 })
+        ));
+    }
+
+    #[tokio::test]
+    async fn execute_with_policy_returns_non_recoverable_errors_at_once() {
+        let mut calls = 0;
+        let result: Result<()> = execute_with_policy(&RecoveryPolicy::aggressive(), |_| {
+            calls += 1;
+            async { Err(crate::Error::InvalidApiKey) }
+        })
+        .await;
+        assert!(matches!(result, Err(crate::Error::InvalidApiKey)));
+        assert_eq!(calls, 1);
+
+        // Off entirely
+        let mut calls = 0;
+        let result: Result<()> = execute_with_policy(&RecoveryPolicy::conservative(), |_| {
+            calls += 1;
+            async { Err(unavailable()) }
+        })
+        .await;
+        assert!(matches!(
+            result,
+            Err(crate::Error::ServiceUnavailable { .. })
+        ));
+        assert_eq!(calls, 1);
+    }
+
+    #[tokio::test]
+    async fn posts_keep_one_idempotency_key_across_retries() {
+        use mockito::Matcher;
+
+        let keys = std::sync::Arc::new(std::sync::Mutex::new(Vec::new()));
+        let seen = keys.clone();
+        let mut server = mockito::Server::new_async().await;
+        let timeout = server
+            .mock("POST", "/files")
+            .match_request(move |request| {
+                let Some(key) = request.header("idempotency-key").first().copied() else {
+                    return false;
+                };
+                let key = key.to_str().unwrap().to_string();
+                let mut seen = seen.lock().unwrap();
+                if !seen.contains(&key) {
+                    seen.push(key);
+                }
+                true
+            })
+            .with_status(504)
+            .with_header("retry-after", "0")
+            .expect(2)
+            .create_async()
+            .await;
+        let listed = server
+            .mock("GET", "/files")
+            .match_header("idempotency-key", Matcher::Missing)
+            .with_status(200)
+            .with_body("{}")
+            .expect(1)
+            .create_async()
+            .await;
+
+        let transport = Transport::from(reqwest::Client::new());
+        let policy = RecoveryPolicy::aggressive()
+            .with_logging(false)
+            .with_max_retries(1);
+        let url = format!("{}/files", server.url());
+        let result = Retrying::new(&transport, &policy)
+            .send(|| Ok(transport.post(&url)))
+            .await;
+        assert!(result.is_err());
+        timeout.assert_async().await;
+        timeout.remove_async().await;
+        assert_eq!(
+            keys.lock().unwrap().len(),
+            1,
+            "a retry must reuse the first attempt's key"
+        );
+
+        // Reads need no key, and a disabled policy sends none
+        let _: serde_json::Value = Retrying::new(&transport, &policy)
+            .send_json(|| Ok(transport.get(&url)))
+            .await
+            .unwrap();
+        listed.assert_async().await;
+        let bare = server
+            .mock("POST", "/files")
+            .match_header("idempotency-key", Matcher::Missing)
+            .with_status(200)
+            .expect(1)
+            .create_async()
+            .await;
+        let policy = policy.with_idempotency_key(false);
+        Retrying::new(&transport, &policy)
+            .send(|| Ok(transport.post(&url)))
+            .await
+            .unwrap();
+        bare.assert_async().await;
+    }
+
+    #[test]
+    fn jitter_keeps_delays_within_the_suggested_delay() {
+        use crate::types::RetryJitter;
+
+        let base = Duration::from_millis(800);
+        assert_eq!(RetryJitter::None.apply(base, 0.3), base);
+        assert_eq!(RetryJitter::Full.apply(base, 0.0), Duration::ZERO);
+        assert_eq!(RetryJitter::Full.apply(base, 0.5), base / 2);
+        assert_eq!(RetryJitter::Equal.apply(base, 0.0), base / 2);
+        assert_eq!(RetryJitter::Equal.apply(base, 0.5), base * 3 / 4);
+
+        let error = crate::Error::rate_limited(Some(base), None);
+        for jitter in [RetryJitter::Full, RetryJitter::Equal] {
+            let policy = RecoveryPolicy::aggressive()
+                .with_logging(false)
+                .with_jitter(jitter);
+            let floor = if jitter == RetryJitter::Equal {
+                base / 2
+            } else {
+                Duration::ZERO
+            };
+            for _ in 0..200 {
+                let Verdict::Retry { delay } = decide(&policy, &error, 0) else {
+                    panic!("rate limits are retried");
+                };
+                assert!((floor..=base).contains(&delay), "{jitter:?}: {delay:?}");
+            }
+        }
+
+        let Verdict::Retry { delay } = decide(&RecoveryPolicy::aggressive(), &error, 0) else {
+            panic!("rate limits are retried");
+        };
+        assert_eq!(delay, base);
+    }
+
+    #[test]
+    fn auto_retry_flag_only_gates_container_expiry() {
+        let policy = RecoveryPolicy::new()
+            .with_logging(false)
+            .with_auto_retry(false)
+            .with_max_retries(2);
+        assert!(policy.enabled());
+        assert!(matches!(
+            decide(&policy, &unavailable(), 0),
+            Verdict::Retry { .. }
+        ));
+        let expired = crate::Error::container_expired("Container expired", false);
+        assert!(matches!(decide(&policy, &expired, 0), Verdict::Stop));
+
+        assert!(!policy.clone().with_max_retries(0).enabled());
+        assert!(!policy
+            .clone()
+            .with_retry_scope(RetryScope::ContainerOnly)
+            .enabled());
+        assert!(RecoveryPolicy::default().enabled());
+        assert!(!RecoveryPolicy::conservative().enabled());
+    }
+}
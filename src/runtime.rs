@@ -0,0 +1,119 @@
+//! Runtime abstraction shared by the endpoint modules.
+//!
+//! On native targets the crate runs on tokio (which reqwest already requires), so
+//! timers come from `tokio::time`. On `wasm32` targets (Cloudflare Workers, browsers)
+//! reqwest uses the `fetch` backend, futures are not `Send`, and timers come from
+//! `gloo-timers` (enabled by the `wasm` feature). Code paths that touch the local
+//! filesystem or raw sockets are compiled out on `wasm32` by target cfg rather than
+//! by feature, so enabling `wasm` alongside native features stays additive.
+
+use std::time::Duration;
+
+/// Sleeps for the given duration without blocking the executor
+#[cfg(not(target_arch = "wasm32"))]
+pub(crate) async fn sleep(duration: Duration) {
+    tokio::time::sleep(duration).await;
+}
+
+/// Sleeps for the given duration without blocking the executor
+#[cfg(all(target_arch = "wasm32", feature = "wasm"))]
+pub(crate) async fn sleep(duration: Duration) {
+    gloo_timers::future::sleep(duration).await;
+}
+
+/// Fallback when targeting wasm32 without the `wasm` feature: retries happen immediately
+#[cfg(all(target_arch = "wasm32", not(feature = "wasm")))]
+pub(crate) async fn sleep(duration: Duration) {
+    crate::diag::debug!(
+        "No timer available on wasm32 without the `wasm` feature; skipping {duration:?} delay"
+    );
+}
+
+/// Runs `future` to completion, or returns `None` if `duration` passes first
+#[cfg(all(feature = "stream", not(target_arch = "wasm32")))]
+pub(crate) async fn timeout<F: std::future::Future>(
+    duration: Duration,
+    future: F,
+) -> Option<F::Output> {
+    tokio::time::timeout(duration, future).await.ok()
+}
+
+/// Runs `future` to completion, or returns `None` if `duration` passes first
+#[cfg(all(feature = "stream", target_arch = "wasm32", feature = "wasm"))]
+pub(crate) async fn timeout<F: std::future::Future>(
+    duration: Duration,
+    future: F,
+) -> Option<F::Output> {
+    use futures_util::future::{select, Either};
+
+    match select(std::pin::pin!(future), gloo_timers::future::sleep(duration)).await {
+        Either::Left((output, _)) => Some(output),
+        Either::Right(((), _)) => None,
+    }
+}
+
+/// Fallback when targeting wasm32 without the `wasm` feature: there is no timer, so
+/// the future is never cut short
+#[cfg(all(feature = "stream", target_arch = "wasm32", not(feature = "wasm")))]
+pub(crate) async fn timeout<F: std::future::Future>(
+    _duration: Duration,
+    future: F,
+) -> Option<F::Output> {
+    Some(future.await)
+}
+
+/// Returns true if the reqwest error is a connection failure.
+///
+/// The fetch backend used on wasm32 does not expose connection errors separately.
+pub(crate) fn is_connect_error(error: &reqwest::Error) -> bool {
+    #[cfg(not(target_arch = "wasm32"))]
+    {
+        error.is_connect()
+    }
+    #[cfg(target_arch = "wasm32")]
+    {
+        let _ = error;
+        false
+    }
+}
+
+/// Measures elapsed time for telemetry.
+///
+/// Natively this is tokio's clock, so it follows `tokio::time::pause` in tests.
+/// `std::time::Instant` panics on wasm32, so there the clock comes from `Date.now()`
+/// (millisecond resolution) when the `wasm` feature is enabled, and reads zero otherwise.
+#[derive(Debug, Clone, Copy)]
+pub(crate) struct Stopwatch {
+    #[cfg(not(target_arch = "wasm32"))]
+    started: tokio::time::Instant,
+    #[cfg(all(target_arch = "wasm32", feature = "wasm"))]
+    started_ms: f64,
+}
+
+impl Stopwatch {
+    /// Starts measuring from now
+    pub(crate) fn start() -> Self {
+        Self {
+            #[cfg(not(target_arch = "wasm32"))]
+            started: tokio::time::Instant::now(),
+            #[cfg(all(target_arch = "wasm32", feature = "wasm"))]
+            started_ms: js_sys::Date::now(),
+        }
+    }
+
+    /// Time elapsed since [`Stopwatch::start`]
+    pub(crate) fn elapsed(&self) -> Duration {
+        #[cfg(not(target_arch = "wasm32"))]
+        {
+            self.started.elapsed()
+        }
+        #[cfg(all(target_arch = "wasm32", feature = "wasm"))]
+        {
+            Duration::from_secs_f64((js_sys::Date::now() - self.started_ms).max(0.0) / 1000.0)
+        }
+        #[cfg(all(target_arch = "wasm32", not(feature = "wasm")))]
+        {
+            Duration::ZERO
+        }
+    }
+}
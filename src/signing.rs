@@ -0,0 +1,227 @@
+//! Request signing for gateways that authenticate the request body.
+//!
+//! A [`RequestSigner`] registered with
+//! [`ClientBuilder::request_signer`](crate::ClientBuilder::request_signer) sees the final
+//! serialized body of every request, including each retry attempt, and returns headers to
+//! add before it is sent. Requests without a body (`GET`, `DELETE`) are signed over an
+//! empty body. Multipart uploads stream their body, so they cannot be signed and are sent
+//! without signature headers.
+//!
+//! With the `hmac-signing` feature, [`HmacSha256Signer`] implements a common scheme: an
+//! HMAC-SHA256 over `"{timestamp}.{body}"`, sent hex-encoded alongside the timestamp.
+
+use reqwest::header::{HeaderName, HeaderValue};
+use std::fmt::Debug;
+
+/// Computes authentication headers over a request body
+pub trait RequestSigner: Debug + Send + Sync {
+    /// Returns the headers to add to a request with the given body
+    fn sign(&self, body: &[u8]) -> Vec<(HeaderName, HeaderValue)>;
+}
+
+/// Adds the signer's headers to `request`, unless its body is streamed
+pub(crate) fn sign_request(signer: &dyn RequestSigner, request: &mut reqwest::Request) {
+    let body = match request.body() {
+        None => &[][..],
+        Some(body) => {
+            let Some(bytes) = body.as_bytes() else {
+                crate::diag::debug!(
+                    "Not signing {} {}: streamed bodies cannot be signed",
+                    request.method(),
+                    request.url().path()
+                );
+                return;
+            };
+            bytes
+        }
+    };
+    let headers = signer.sign(body);
+    request.headers_mut().extend(headers);
+}
+
+/// Signs requests with an HMAC-SHA256 of the timestamp and body.
+///
+/// The signature covers `"{timestamp}.{body}"`, where the timestamp is in unix seconds,
+/// and is sent hex-encoded in `x-signature`, with the timestamp in `x-timestamp`.
+#[cfg(feature = "hmac-signing")]
+#[derive(Clone)]
+pub struct HmacSha256Signer {
+    key: Vec<u8>,
+    signature_header: HeaderName,
+    timestamp_header: HeaderName,
+    clock: fn() -> i64,
+}
+
+#[cfg(feature = "hmac-signing")]
+impl Debug for HmacSha256Signer {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("HmacSha256Signer")
+            .field("key", &"<redacted>")
+            .field("signature_header", &self.signature_header)
+            .field("timestamp_header", &self.timestamp_header)
+            .finish_non_exhaustive()
+    }
+}
+
+#[cfg(feature = "hmac-signing")]
+impl HmacSha256Signer {
+    /// Creates a signer with the shared secret
+    #[must_use]
+    pub fn new(secret: impl AsRef<[u8]>) -> Self {
+        Self {
+            key: secret.as_ref().to_vec(),
+            signature_header: HeaderName::from_static("x-signature"),
+            timestamp_header: HeaderName::from_static("x-timestamp"),
+            clock: || chrono::Utc::now().timestamp(),
+        }
+    }
+
+    /// Sets the header names for the signature and the timestamp
+    #[must_use]
+    pub fn with_headers(mut self, signature: HeaderName, timestamp: HeaderName) -> Self {
+        self.signature_header = signature;
+        self.timestamp_header = timestamp;
+        self
+    }
+
+    /// Replaces the clock supplying unix timestamps, e.g. with a fixed one in tests
+    #[must_use]
+    pub fn with_clock(mut self, clock: fn() -> i64) -> Self {
+        self.clock = clock;
+        self
+    }
+
+    /// Returns the hex-encoded signature of `body` at the given unix timestamp
+    #[must_use]
+    pub fn signature(&self, body: &[u8], timestamp: i64) -> String {
+        let mut message = timestamp.to_string().into_bytes();
+        message.push(b'.');
+        message.extend_from_slice(body);
+        to_hex(&hmac_sha256(&self.key, &message))
+    }
+}
+
+#[cfg(feature = "hmac-signing")]
+impl RequestSigner for HmacSha256Signer {
+    fn sign(&self, body: &[u8]) -> Vec<(HeaderName, HeaderValue)> {
+        let timestamp = (self.clock)();
+        let signature = self.signature(body, timestamp);
+        // Decimal digits and lowercase hex are always valid header values
+        vec![
+            (
+                self.timestamp_header.clone(),
+                HeaderValue::from_str(&timestamp.to_string()).expect("valid header value"),
+            ),
+            (
+                self.signature_header.clone(),
+                HeaderValue::from_str(&signature).expect("valid header value"),
+            ),
+        ]
+    }
+}
+
+#[cfg(feature = "hmac-signing")]
+fn to_hex(bytes: &[u8]) -> String {
+    use std::fmt::Write;
+
+    bytes
+        .iter()
+        .fold(String::with_capacity(bytes.len() * 2), |mut hex, byte| {
+            let _ = write!(hex, "{byte:02x}");
+            hex
+        })
+}
+
+/// HMAC (RFC 2104) with SHA-256
+pub(crate) fn hmac_sha256(key: &[u8], message: &[u8]) -> [u8; 32] {
+    use sha2::{Digest, Sha256};
+
+    const BLOCK_SIZE: usize = 64;
+    let mut block = [0_u8; BLOCK_SIZE];
+    if key.len() > BLOCK_SIZE {
+        block[..32].copy_from_slice(&Sha256::digest(key));
+    } else {
+        block[..key.len()].copy_from_slice(key);
+    }
+
+    let pad = |byte: u8| block.map(|b| b ^ byte);
+    let inner = Sha256::new()
+        .chain_update(pad(0x36))
+        .chain_update(message)
+        .finalize();
+    Sha256::new()
+        .chain_update(pad(0x5c))
+        .chain_update(inner)
+        .finalize()
+        .into()
+}
+
+#[cfg(all(test, feature = "hmac-signing"))]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn hmac_matches_rfc_4231_vectors() {
+        // Test case 2
+        assert_eq!(
+            to_hex(&hmac_sha256(b"Jefe", b"what do ya want for nothing?")),
+            "5bdcc146bf60754e6a042426089575c75a003f089d2739839dec58b964ec3843"
+        );
+        // Test case 6: key longer than the block size
+        assert_eq!(
+            to_hex(&hmac_sha256(
+                &[0xaa; 131],
+                b"Test Using Larger Than Block-Size Key - Hash Key First"
+            )),
+            "60e431591ee0b67f0d8a26aacbf5b77f8e0bc6213728c5140546040f0ee37f54"
+        );
+    }
+
+    #[tokio::test]
+    async fn signs_the_serialized_body_of_each_request() {
+        use std::sync::Arc;
+
+        let request = crate::Request::builder()
+            .model("gpt-4o")
+            .input("hello")
+            .build();
+        let body = r#"{"model":"gpt-4o","input":"hello"}"#;
+        assert_eq!(serde_json::to_string(&request).unwrap(), body);
+        let signer = HmacSha256Signer::new("s3cret").with_clock(|| 1_700_000_000);
+
+        let mut server = mockito::Server::new_async().await;
+        let create = server
+            .mock("POST", "/responses")
+            .match_body(body)
+            .match_header("x-timestamp", "1700000000")
+            .match_header(
+                "x-signature",
+                "0f131925d0a9c592de542806956126540e6a62deec70f8c29b0d8a61e66a6f94",
+            )
+            .with_status(200)
+            .with_body(r#"{"id":"resp_1","object":"response","created_at":1700000000,"model":"gpt-4o","status":"completed","output":[]}"#)
+            .create_async()
+            .await;
+        let delete = server
+            .mock("DELETE", "/files/file_1")
+            .match_header(
+                "x-signature",
+                "21948100f1d7a89f3338f6b1106fc4f7a702fbe1493b833a3382f80193bde3fe",
+            )
+            .with_status(200)
+            .with_body(r#"{"id":"file_1","object":"file","deleted":true}"#)
+            .create_async()
+            .await;
+
+        let client = crate::Client::builder()
+            .http_client(reqwest::Client::new())
+            .base_url(server.url())
+            .request_signer(Arc::new(signer))
+            .build()
+            .unwrap();
+        client.responses.create(request).await.unwrap();
+        client.files.delete("file_1").await.unwrap();
+        create.assert_async().await;
+        delete.assert_async().await;
+    }
+}
@@ -24,7 +24,10 @@ fn test_tool_registry_dispatch() {
                     }]
                 })
             } else {
-                json!({ "tools": [] })
+                json!({ "tools": [
+                    {"name": "local_tool", "description": "Remote copy", "inputSchema": {}},
+                    {"name": "mcp_tool", "description": "Remote tool", "inputSchema": {}}
+                ] })
             };
 
             Ok(JsonRpcResponse {
@@ -69,6 +72,20 @@ fn test_tool_registry_dispatch() {
             name: "local_tool".to_string(),
         }));
 
+        // A local tool shadows the MCP tool of the same name
+        let tools = registry.list_tools().await.unwrap();
+        let listed: Vec<_> = tools
+            .iter()
+            .map(|t| (t.name.as_deref(), t.description.as_deref()))
+            .collect();
+        assert_eq!(
+            listed,
+            [
+                (Some("local_tool"), Some("Mock tool")),
+                (Some("mcp_tool"), Some("Remote tool"))
+            ]
+        );
+
         // Test calling local tool
         let result = registry.call_tool("local_tool", json!({})).await.unwrap();
         assert_eq!(result["result"], "local");
@@ -1,12 +1,79 @@
-use crate::error::{try_parse_api_error, Result};
-use reqwest::Client as HttpClient;
+use crate::endpoint::Endpoint;
+use crate::error::Result;
+use crate::http::Transport;
+use crate::retry::Retrying;
+use crate::types::RecoveryPolicy;
+use crate::vector_stores::{SearchVectorStoreRequest, SearchVectorStoreResponse, VectorStores};
+use reqwest::StatusCode;
 use serde::{Deserialize, Serialize};
+use std::sync::{Arc, OnceLock};
 
 /// Tools API endpoints
 #[derive(Debug, Clone)]
 pub struct Tools {
-    client: HttpClient,
-    base_url: String,
+    client: Transport,
+    endpoint: Endpoint,
+    recovery_policy: RecoveryPolicy,
+    /// Web search path that last worked for the base URL, shared between clones
+    web_search_path: Arc<OnceLock<String>>,
+    /// How web searches are sent
+    web_search_mode: WebSearchMode,
+    /// Whether the server accepted a POST web search, once [`WebSearchMode::Auto`] has
+    /// found out; shared between clones
+    web_search_post: Arc<OnceLock<bool>>,
+}
+
+/// How [`Tools::web_search`] sends its request
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub enum WebSearchMode {
+    /// `GET` with the request as query parameters, as older API revisions expect
+    #[default]
+    Get,
+    /// `POST` with the request as a JSON body, as newer API revisions expect
+    Post,
+    /// `POST` first, switching to `GET` for good if the server answers 404 or 405
+    Auto,
+}
+
+/// Web search request
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct WebSearchRequest {
+    /// Text to search for
+    pub query: String,
+
+    /// Maximum number of results to return
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub max_results: Option<u32>,
+
+    /// Only return results published within this many days
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub recency_days: Option<u32>,
+}
+
+impl WebSearchRequest {
+    /// Creates a search request for `query`
+    #[must_use]
+    pub fn new(query: impl Into<String>) -> Self {
+        Self {
+            query: query.into(),
+            max_results: None,
+            recency_days: None,
+        }
+    }
+
+    /// Sets the maximum number of results to return
+    #[must_use]
+    pub fn with_max_results(mut self, max_results: u32) -> Self {
+        self.max_results = Some(max_results);
+        self
+    }
+
+    /// Only returns results published within the last `days` days
+    #[must_use]
+    pub fn with_recency_days(mut self, days: u32) -> Self {
+        self.recency_days = Some(days);
+        self
+    }
 }
 
 /// Web search result
@@ -30,6 +97,9 @@ pub struct WebSearchResponse {
 }
 
 /// File search result
+#[deprecated(
+    note = "never matched the API's response; `Tools::file_search` now returns `SearchVectorStoreResponse`"
+)]
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct FileSearchResult {
     /// ID of the file that matched the search
@@ -43,68 +113,209 @@ pub struct FileSearchResult {
 }
 
 /// Response from a file search
+#[deprecated(
+    note = "never matched the API's response; `Tools::file_search` now returns `SearchVectorStoreResponse`"
+)]
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct FileSearchResponse {
     /// Results from the search
+    #[allow(deprecated)]
     pub results: Vec<FileSearchResult>,
 }
 
 impl Tools {
-    /// Creates a new Tools API client
-    pub(crate) fn new(client: HttpClient, base_url: String) -> Self {
-        Self { client, base_url }
+    /// Creates a new Tools API client that retries failed requests as `recovery_policy` allows
+    pub(crate) fn new(
+        client: impl Into<Transport>,
+        endpoint: impl Into<Endpoint>,
+        recovery_policy: RecoveryPolicy,
+    ) -> Self {
+        Self {
+            client: client.into(),
+            endpoint: endpoint.into(),
+            recovery_policy,
+            web_search_path: Arc::new(OnceLock::new()),
+            web_search_mode: WebSearchMode::default(),
+            web_search_post: Arc::new(OnceLock::new()),
+        }
+    }
+
+    /// Sends this handle's requests with `auth` as the `Authorization` header
+    pub(crate) fn with_auth(mut self, auth: &reqwest::header::HeaderValue) -> Self {
+        self.client = self.client.with_auth(auth.clone());
+        self
+    }
+
+    fn retrying(&self) -> Retrying<'_> {
+        Retrying::new(&self.client, &self.recovery_policy)
     }
 
     /// Path constants for web search endpoint
     const WEB_SEARCH_PATH: &'static str = "/web_search"; // canonical
     const LEGACY_WEB_SEARCH_PATH: &'static str = "/tools/web_search";
 
+    /// Uses a fixed web search path instead of probing the canonical and legacy paths.
+    ///
+    /// Useful for proxies that expose web search under a custom layout. The path is
+    /// appended to the base URL, e.g. `"/search/web"`.
+    #[must_use]
+    pub fn with_web_search_path(mut self, path: impl Into<String>) -> Self {
+        self.web_search_path = Arc::new(OnceLock::from(path.into()));
+        self
+    }
+
+    /// Returns the web search path in use, if one has been configured or resolved
+    #[must_use]
+    pub fn web_search_path(&self) -> Option<&str> {
+        self.web_search_path.get().map(String::as_str)
+    }
+
+    /// Sets whether web searches are sent as `GET` (the default), `POST`, or `POST` with
+    /// a fallback to `GET`
+    #[must_use]
+    pub fn with_web_search_mode(mut self, mode: WebSearchMode) -> Self {
+        self.web_search_mode = mode;
+        self.web_search_post = Arc::new(OnceLock::new());
+        self
+    }
+
+    /// Returns the configured web search mode
+    #[must_use]
+    pub fn web_search_mode(&self) -> WebSearchMode {
+        self.web_search_mode
+    }
+
     /// Performs a web search.
     ///
+    /// In [`WebSearchMode::Get`] the first call tries the canonical path and falls back to
+    /// the legacy path on 404. Whichever path succeeds is remembered, so later calls go
+    /// straight to it.
+    ///
     /// # Errors
     ///
     /// Returns an error if the request fails to send or has a non-200 status code.
     pub async fn web_search(&self, query: &str) -> Result<WebSearchResponse> {
-        // Try the canonical path first
+        self.web_search_with(&WebSearchRequest::new(query)).await
+    }
+
+    /// Performs a web search with a result limit or freshness filter.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the request fails to send or has a non-200 status code.
+    pub async fn web_search_with(&self, request: &WebSearchRequest) -> Result<WebSearchResponse> {
+        let post = match self.web_search_mode {
+            WebSearchMode::Get => false,
+            WebSearchMode::Post => true,
+            WebSearchMode::Auto => match self.web_search_post.get() {
+                Some(post) => *post,
+                None => return self.detect_web_search_method(request).await,
+            },
+        };
+        if post {
+            let path = self.web_search_path().unwrap_or(Self::WEB_SEARCH_PATH);
+            self.retrying()
+                .send_json(|| self.web_search_post_request(path, request))
+                .await
+        } else {
+            self.web_search_get(request).await
+        }
+    }
+
+    /// Tries a `POST` search and remembers whether the server supports it
+    async fn detect_web_search_method(
+        &self,
+        request: &WebSearchRequest,
+    ) -> Result<WebSearchResponse> {
+        let path = self.web_search_path().unwrap_or(Self::WEB_SEARCH_PATH);
         let response = self
-            .client
-            .get(format!("{}{}", self.base_url, Self::WEB_SEARCH_PATH))
-            .query(&[("query", query)])
-            .send()
-            .await;
+            .retrying()
+            .send_allowing(
+                || self.web_search_post_request(path, request),
+                &[StatusCode::NOT_FOUND, StatusCode::METHOD_NOT_ALLOWED],
+            )
+            .await?;
+        if response.status().is_success() {
+            let _ = self.web_search_post.set(true);
+            return self.client.json(response).await;
+        }
 
-        match response {
-            Ok(resp) => {
-                if resp.status().is_success() || resp.status().as_u16() != 404 {
-                    // If successful or any error other than 404, process normally
-                    let response = try_parse_api_error(resp).await?;
-                    response.json().await.map_err(crate::Error::Http)
-                } else {
-                    // If 404, try the legacy path
-                    log::warn!(
-                        "Web search endpoint {} returned 404, trying legacy path {}",
-                        Self::WEB_SEARCH_PATH,
-                        Self::LEGACY_WEB_SEARCH_PATH
-                    );
-
-                    let legacy_response = self
-                        .client
-                        .get(format!("{}{}", self.base_url, Self::LEGACY_WEB_SEARCH_PATH))
-                        .query(&[("query", query)])
-                        .send()
-                        .await
-                        .map_err(crate::Error::Http)?;
-
-                    let response = try_parse_api_error(legacy_response).await?;
-                    response.json().await.map_err(crate::Error::Http)
-                }
-            }
-            Err(e) => Err(crate::Error::Http(e)),
+        crate::diag::warn!(
+            "POST web search returned {}, using GET from now on",
+            response.status()
+        );
+        let _ = self.web_search_post.set(false);
+        self.web_search_get(request).await
+    }
+
+    async fn web_search_get(&self, request: &WebSearchRequest) -> Result<WebSearchResponse> {
+        if let Some(path) = self.web_search_path.get() {
+            return self
+                .retrying()
+                .send_json(|| self.web_search_request(path, request))
+                .await;
         }
+
+        // Try the canonical path first; only a 404 means the server lacks it
+        let response = self
+            .retrying()
+            .send_allowing(
+                || self.web_search_request(Self::WEB_SEARCH_PATH, request),
+                &[StatusCode::NOT_FOUND],
+            )
+            .await?;
+        let (path, result) = match response.status() {
+            StatusCode::NOT_FOUND => {
+                crate::diag::warn!(
+                    "Web search endpoint {} returned 404, trying legacy path {}",
+                    Self::WEB_SEARCH_PATH,
+                    Self::LEGACY_WEB_SEARCH_PATH
+                );
+                (
+                    Self::LEGACY_WEB_SEARCH_PATH,
+                    self.retrying()
+                        .send_json(|| {
+                            self.web_search_request(Self::LEGACY_WEB_SEARCH_PATH, request)
+                        })
+                        .await?,
+                )
+            }
+            _ => (Self::WEB_SEARCH_PATH, self.client.json(response).await?),
+        };
+
+        // Only cache a path once it has produced a usable response
+        let _ = self.web_search_path.set(path.to_string());
+        Ok(result)
+    }
+
+    /// `GET` search; `.query` encodes every parameter exactly once
+    fn web_search_request(
+        &self,
+        path: &str,
+        request: &WebSearchRequest,
+    ) -> Result<reqwest::RequestBuilder> {
+        Ok(self
+            .client
+            .get(self.endpoint.path_url(path)?)
+            .query(request))
+    }
+
+    fn web_search_post_request(
+        &self,
+        path: &str,
+        request: &WebSearchRequest,
+    ) -> Result<reqwest::RequestBuilder> {
+        Ok(self
+            .client
+            .post(self.endpoint.path_url(path)?)
+            .json(request))
     }
 
     /// Searches files in a vector store.
     ///
+    /// Shorthand for [`VectorStores::search`] with only a query; use
+    /// [`Tools::file_search_with`] to limit or filter the results.
+    ///
     /// # Errors
     ///
     /// Returns an error if the request fails to send or has a non-200 status code.
@@ -112,23 +323,320 @@ impl Tools {
         &self,
         vector_store_id: &str,
         query: &str,
-    ) -> Result<FileSearchResponse> {
-        let request = serde_json::json!({
-            "query": query
-        });
+    ) -> Result<SearchVectorStoreResponse> {
+        self.file_search_with(vector_store_id, SearchVectorStoreRequest::new(query))
+            .await
+    }
 
-        let response = self
-            .client
-            .post(format!(
-                "{}/vector_stores/{}/search",
-                self.base_url, vector_store_id
+    /// Searches files in a vector store with a full search request.
+    ///
+    /// Same as [`VectorStores::search`], sent with this handle's client and recovery
+    /// policy.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the request fails to send or has a non-200 status code.
+    pub async fn file_search_with(
+        &self,
+        vector_store_id: &str,
+        request: SearchVectorStoreRequest,
+    ) -> Result<SearchVectorStoreResponse> {
+        VectorStores::new(
+            self.client.clone(),
+            self.endpoint.clone(),
+            self.recovery_policy.clone(),
+        )
+        .search(vector_store_id, request)
+        .await
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const RESULTS_BODY: &str = r#"{"results":[{"title":"Rust","url":"https://www.rust-lang.org","snippet":"A language"}]}"#;
+
+    /// Without retries, so every status reaches the path probing once
+    fn tools_for(server: &mockito::Server) -> Tools {
+        Tools::new(
+            reqwest::Client::new(),
+            server.url(),
+            RecoveryPolicy::conservative(),
+        )
+    }
+
+    #[tokio::test]
+    async fn web_search_uses_canonical_path_and_caches_it() {
+        let mut server = mockito::Server::new_async().await;
+        let canonical = server
+            .mock("GET", "/web_search")
+            .match_query(mockito::Matcher::UrlEncoded("query".into(), "rust".into()))
+            .with_status(200)
+            .with_body(RESULTS_BODY)
+            .expect(2)
+            .create_async()
+            .await;
+        let legacy = server
+            .mock("GET", "/tools/web_search")
+            .expect(0)
+            .create_async()
+            .await;
+
+        let tools = tools_for(&server);
+        assert_eq!(tools.web_search_path(), None);
+        let response = tools.web_search("rust").await.unwrap();
+        assert_eq!(response.results[0].title, "Rust");
+        assert_eq!(tools.web_search_path(), Some("/web_search"));
+        tools.web_search("rust").await.unwrap();
+
+        canonical.assert_async().await;
+        legacy.assert_async().await;
+    }
+
+    #[tokio::test]
+    async fn web_search_falls_back_on_404_and_skips_probe_afterwards() {
+        let mut server = mockito::Server::new_async().await;
+        let canonical = server
+            .mock("GET", "/web_search")
+            .match_query(mockito::Matcher::Any)
+            .with_status(404)
+            .with_body(r#"{"results":[]}"#)
+            .expect(1)
+            .create_async()
+            .await;
+        let legacy = server
+            .mock("GET", "/tools/web_search")
+            .match_query(mockito::Matcher::Any)
+            .with_status(200)
+            .with_body(RESULTS_BODY)
+            .expect(2)
+            .create_async()
+            .await;
+
+        let tools = tools_for(&server);
+        let response = tools.web_search("rust").await.unwrap();
+        assert_eq!(response.results.len(), 1);
+        assert_eq!(tools.web_search_path(), Some("/tools/web_search"));
+
+        // Clones share the resolved path
+        let cloned = tools.clone();
+        cloned.web_search("rust").await.unwrap();
+
+        canonical.assert_async().await;
+        legacy.assert_async().await;
+    }
+
+    #[tokio::test]
+    async fn web_search_server_error_does_not_fall_back_or_cache() {
+        let mut server = mockito::Server::new_async().await;
+        let canonical = server
+            .mock("GET", "/web_search")
+            .match_query(mockito::Matcher::Any)
+            .with_status(500)
+            .with_body(r#"{"error":{"message":"boom","type":"server_error"}}"#)
+            .expect(1)
+            .create_async()
+            .await;
+        let legacy = server
+            .mock("GET", "/tools/web_search")
+            .match_query(mockito::Matcher::Any)
+            .expect(0)
+            .create_async()
+            .await;
+
+        let tools = tools_for(&server);
+        let error = tools.web_search("rust").await.unwrap_err();
+        assert!(
+            matches!(error, crate::Error::ServerError { .. }),
+            "{error:?}"
+        );
+        assert_eq!(tools.web_search_path(), None);
+
+        canonical.assert_async().await;
+        legacy.assert_async().await;
+    }
+
+    #[tokio::test]
+    async fn get_web_search_encodes_unicode_queries_once() {
+        let query = "café & crème? 東京/100% +plus";
+        let mut server = mockito::Server::new_async().await;
+        let search = server
+            .mock("GET", "/web_search")
+            .match_query(mockito::Matcher::AllOf(vec![
+                mockito::Matcher::UrlEncoded("query".into(), query.into()),
+                mockito::Matcher::UrlEncoded("max_results".into(), "3".into()),
+            ]))
+            .with_status(200)
+            .with_body(RESULTS_BODY)
+            .expect(1)
+            .create_async()
+            .await;
+
+        let request = WebSearchRequest::new(query).with_max_results(3);
+        tools_for(&server).web_search_with(&request).await.unwrap();
+        search.assert_async().await;
+    }
+
+    #[tokio::test]
+    async fn post_web_search_sends_a_json_body() {
+        let mut server = mockito::Server::new_async().await;
+        let search = server
+            .mock("POST", "/web_search")
+            .match_body(mockito::Matcher::Json(serde_json::json!({
+                "query": "rust 2024 edition",
+                "max_results": 5,
+                "recency_days": 7
+            })))
+            .with_status(200)
+            .with_body(RESULTS_BODY)
+            .expect(1)
+            .create_async()
+            .await;
+
+        let tools = tools_for(&server).with_web_search_mode(WebSearchMode::Post);
+        let request = WebSearchRequest::new("rust 2024 edition")
+            .with_max_results(5)
+            .with_recency_days(7);
+        let response = tools.web_search_with(&request).await.unwrap();
+        assert_eq!(response.results[0].url, "https://www.rust-lang.org");
+        search.assert_async().await;
+    }
+
+    #[tokio::test]
+    async fn auto_web_search_falls_back_to_get_once() {
+        let mut server = mockito::Server::new_async().await;
+        let post = server
+            .mock("POST", "/web_search")
+            .with_status(405)
+            .expect(1)
+            .create_async()
+            .await;
+        let get = server
+            .mock("GET", "/web_search")
+            .match_query(mockito::Matcher::UrlEncoded("query".into(), "rust".into()))
+            .with_status(200)
+            .with_body(RESULTS_BODY)
+            .expect(2)
+            .create_async()
+            .await;
+
+        let tools = tools_for(&server).with_web_search_mode(WebSearchMode::Auto);
+        tools.web_search("rust").await.unwrap();
+        // Clones share what was detected and skip the POST
+        tools.clone().web_search("rust").await.unwrap();
+        assert_eq!(tools.web_search_path(), Some("/web_search"));
+
+        post.assert_async().await;
+        get.assert_async().await;
+    }
+
+    /// Search response captured from the API, trimmed to two results
+    const FILE_SEARCH_BODY: &str = r#"{
+        "object": "vector_store.search_results.page",
+        "search_query": "return policy",
+        "data": [
+            {
+                "file_id": "file-2dmvVBDEhH4mbqT5LtsVMU",
+                "filename": "returns.md",
+                "score": 0.8472,
+                "attributes": {"lang": "en"},
+                "content": [{"type": "text", "text": "Items can be returned within 30 days."}]
+            },
+            {
+                "file_id": "file-9QkMPf5dGXDh5jVq2sVzxN",
+                "filename": "faq.pdf",
+                "score": 0.5123,
+                "attributes": {},
+                "content": [
+                    {"type": "text", "text": "Refunds go to the original payment method."},
+                    {"type": "text", "text": "Gift cards cannot be refunded."}
+                ]
+            }
+        ],
+        "has_more": false,
+        "next_page": null
+    }"#;
+
+    #[tokio::test]
+    async fn file_search_decodes_real_search_responses() {
+        let mut server = mockito::Server::new_async().await;
+        let search = server
+            .mock("POST", "/vector_stores/vs_1/search")
+            .match_body(mockito::Matcher::Json(serde_json::json!({
+                "query": "return policy",
+                "max_num_results": 2,
+                "filters": {"type": "eq", "key": "lang", "value": "en"}
+            })))
+            .with_status(200)
+            .with_body(FILE_SEARCH_BODY)
+            .create_async()
+            .await;
+        let plain = server
+            .mock("POST", "/vector_stores/vs_1/search")
+            .match_body(mockito::Matcher::Json(
+                serde_json::json!({"query": "return policy"}),
             ))
-            .json(&request)
-            .send()
-            .await
-            .map_err(crate::Error::Http)?;
+            .with_status(200)
+            .with_body(FILE_SEARCH_BODY)
+            .create_async()
+            .await;
+
+        let tools = tools_for(&server);
+        let request = SearchVectorStoreRequest::new("return policy")
+            .with_max_num_results(2)
+            .with_filters(serde_json::json!({"type": "eq", "key": "lang", "value": "en"}));
+        let response = tools.file_search_with("vs_1", request).await.unwrap();
+        assert_eq!(response.data.len(), 2);
+        let first = &response.data[0];
+        assert_eq!(
+            (first.file_id.as_str(), first.filename.as_str()),
+            ("file-2dmvVBDEhH4mbqT5LtsVMU", "returns.md")
+        );
+        assert!((first.score - 0.8472).abs() < f64::EPSILON);
+        assert_eq!(first.attributes, Some(serde_json::json!({"lang": "en"})));
+        assert_eq!(
+            response.data[1].content[1].text,
+            "Gift cards cannot be refunded."
+        );
+        assert!(!response.has_more);
+
+        let response = tools.file_search("vs_1", "return policy").await.unwrap();
+        assert_eq!(response.data[1].filename, "faq.pdf");
+
+        search.assert_async().await;
+        plain.assert_async().await;
+    }
+
+    #[test]
+    #[allow(deprecated)]
+    fn old_file_search_shape_does_not_match_the_api() {
+        // The type `file_search` used to return; kept only for source compatibility
+        assert!(serde_json::from_str::<FileSearchResponse>(FILE_SEARCH_BODY).is_err());
+    }
+
+    #[tokio::test]
+    async fn web_search_path_override_skips_probing() {
+        let mut server = mockito::Server::new_async().await;
+        let custom = server
+            .mock("GET", "/proxy/search")
+            .match_query(mockito::Matcher::Any)
+            .with_status(200)
+            .with_body(RESULTS_BODY)
+            .expect(1)
+            .create_async()
+            .await;
+        let canonical = server
+            .mock("GET", "/web_search")
+            .match_query(mockito::Matcher::Any)
+            .expect(0)
+            .create_async()
+            .await;
+
+        let tools = tools_for(&server).with_web_search_path("/proxy/search");
+        tools.web_search("rust").await.unwrap();
 
-        let response = try_parse_api_error(response).await?;
-        response.json().await.map_err(crate::Error::Http)
+        custom.assert_async().await;
+        canonical.assert_async().await;
     }
 }
@@ -0,0 +1,135 @@
+use serde::Serialize;
+use serde_json::{Map, Value};
+use sha2::{Digest, Sha256};
+use std::fmt::Write;
+
+/// Placeholder written over redacted secrets
+pub const REDACTED: &str = "***";
+
+/// Keys whose string values hold base64 payloads
+const BASE64_KEYS: &[&str] = &["file_data", "b64_json", "partial_image_b64"];
+
+/// Controls what [`Request::to_audit_json_with`](crate::Request::to_audit_json_with) and
+/// [`Response::to_audit_json_with`](crate::Response::to_audit_json_with) strip out.
+///
+/// The defaults mask header values, shorten inline data longer than 256 characters
+/// and keep instructions as-is.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct RedactionConfig {
+    /// Replace every value of a `headers` object (e.g. MCP tool auth headers) with `***`
+    pub redact_headers: bool,
+
+    /// Inline base64 data and `data:` URLs longer than this many characters are replaced
+    /// with a length marker; `None` keeps them
+    pub max_inline_data_len: Option<usize>,
+
+    /// Replace `instructions` with its SHA-256 hash, so identical prompts can still be matched
+    pub hash_instructions: bool,
+}
+
+impl Default for RedactionConfig {
+    fn default() -> Self {
+        Self {
+            redact_headers: true,
+            max_inline_data_len: Some(256),
+            hash_instructions: false,
+        }
+    }
+}
+
+impl RedactionConfig {
+    /// Creates a config with the default redactions
+    #[must_use]
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Sets whether header values are masked
+    #[must_use]
+    pub fn with_redact_headers(mut self, redact: bool) -> Self {
+        self.redact_headers = redact;
+        self
+    }
+
+    /// Sets the length above which inline data is replaced, or `None` to keep it
+    #[must_use]
+    pub fn with_max_inline_data_len(mut self, max_len: Option<usize>) -> Self {
+        self.max_inline_data_len = max_len;
+        self
+    }
+
+    /// Sets whether `instructions` are replaced by their hash
+    #[must_use]
+    pub fn with_hash_instructions(mut self, hash: bool) -> Self {
+        self.hash_instructions = hash;
+        self
+    }
+
+    /// Serializes a value and applies the redactions
+    pub(crate) fn audit<T: Serialize>(&self, value: &T) -> Value {
+        let mut json = serde_json::to_value(value).unwrap_or(Value::Null);
+        if self.hash_instructions {
+            if let Some(Value::String(instructions)) = json.get_mut("instructions") {
+                *instructions = format!("sha256:{:x}", Sha256::digest(instructions.as_bytes()));
+            }
+        }
+        self.redact(&mut json);
+        json
+    }
+
+    fn redact(&self, value: &mut Value) {
+        match value {
+            Value::Object(map) => self.redact_object(map),
+            Value::Array(items) => items.iter_mut().for_each(|item| self.redact(item)),
+            Value::String(s) => self.shorten_data_url(s),
+            _ => {}
+        }
+    }
+
+    fn redact_object(&self, map: &mut Map<String, Value>) {
+        let is_image_call =
+            map.get("type").and_then(Value::as_str) == Some("image_generation_call");
+
+        for (key, value) in map.iter_mut() {
+            match value {
+                Value::Object(headers) if self.redact_headers && key == "headers" => {
+                    for header in headers.values_mut() {
+                        *header = Value::String(REDACTED.to_string());
+                    }
+                }
+                Value::String(data)
+                    if BASE64_KEYS.contains(&key.as_str())
+                        || (is_image_call && key == "result") =>
+                {
+                    self.shorten(data);
+                }
+                _ => self.redact(value),
+            }
+        }
+    }
+
+    fn shorten_data_url(&self, s: &mut String) {
+        let Some(max) = self.max_inline_data_len else {
+            return;
+        };
+        let Some(comma) = s.strip_prefix("data:").and(s.find(',')) else {
+            return;
+        };
+        let data_len = s.len() - comma - 1;
+        if data_len > max {
+            s.truncate(comma + 1);
+            let _ = write!(s, "<{data_len} bytes redacted>");
+        }
+    }
+
+    /// Replaces `data` with a length marker if it is over the limit; returns true if it was
+    fn shorten(&self, data: &mut String) -> bool {
+        match self.max_inline_data_len {
+            Some(max) if data.len() > max => {
+                *data = format!("<{} bytes redacted>", data.len());
+                true
+            }
+            _ => false,
+        }
+    }
+}
@@ -1,26 +1,33 @@
 use serde::{Deserialize, Serialize};
 
-/// Status of a background processing operation
+/// Status of a background processing operation.
+///
+/// Also accepts the Responses API's spellings: `in_progress` for `running` and
+/// `canceled` for `cancelled`.
 #[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
 #[serde(rename_all = "lowercase")]
 pub enum BackgroundStatus {
     /// Background task is queued but not started
     Queued,
     /// Background task is currently running
+    #[serde(alias = "in_progress")]
     Running,
     /// Background task completed successfully
     Completed,
     /// Background task failed with an error
     Failed,
     /// Background task was cancelled
+    #[serde(alias = "canceled")]
     Cancelled,
+    /// Background task stopped early, e.g. at the output token limit
+    Incomplete,
 }
 
 /// Handle for background processing operations
 ///
 /// When a request is submitted with `background: true` and returns HTTP 202,
 /// this handle allows you to poll for status or stream results.
-#[derive(Debug, Clone, Serialize, Deserialize)]
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
 pub struct BackgroundHandle {
     /// Unique identifier for the background operation
     pub id: String,
@@ -57,12 +64,21 @@ pub struct BackgroundStatusResponse {
     #[serde(skip_serializing_if = "Option::is_none")]
     pub progress: Option<u8>,
 
+    /// Position in the queue while the status is `Queued` (if available)
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub queue_position: Option<u32>,
+
     /// Estimated completion time (if available)
     #[serde(skip_serializing_if = "Option::is_none")]
     pub estimated_completion: Option<String>,
 
-    /// Error message (if status is Failed)
-    #[serde(skip_serializing_if = "Option::is_none")]
+    /// Error message (if status is Failed); taken from `error.message` when the error is
+    /// an object
+    #[serde(
+        default,
+        deserialize_with = "error_message",
+        skip_serializing_if = "Option::is_none"
+    )]
     pub error: Option<String>,
 
     /// Result data (if status is Completed)
@@ -70,6 +86,25 @@ pub struct BackgroundStatusResponse {
     pub result: Option<serde_json::Value>,
 }
 
+/// Reads an error given either as a message or as an object with a `message`
+fn error_message<'de, D>(deserializer: D) -> Result<Option<String>, D::Error>
+where
+    D: serde::Deserializer<'de>,
+{
+    Ok(
+        match Option::<serde_json::Value>::deserialize(deserializer)? {
+            Some(serde_json::Value::String(message)) => Some(message),
+            Some(serde_json::Value::Null) | None => None,
+            Some(error) => Some(
+                error
+                    .get("message")
+                    .and_then(serde_json::Value::as_str)
+                    .map_or_else(|| error.to_string(), ToString::to_string),
+            ),
+        },
+    )
+}
+
 impl BackgroundHandle {
     /// Create a new background handle
     #[must_use]
@@ -125,11 +160,21 @@ impl BackgroundHandle {
         self.status == BackgroundStatus::Cancelled
     }
 
-    /// Check if the operation is done (completed, failed, or cancelled)
+    /// Check if the operation is done (completed, failed, cancelled, or incomplete)
     #[must_use]
     pub fn is_done(&self) -> bool {
         !self.is_running()
     }
+
+    /// Updates the handle from a polled status
+    pub fn apply(&mut self, status: &BackgroundStatusResponse) {
+        self.status = status.status.clone();
+        if status.estimated_completion.is_some() {
+            self.estimated_completion
+                .clone_from(&status.estimated_completion);
+        }
+        self.error.clone_from(&status.error);
+    }
 }
 
 #[cfg(test)]
@@ -0,0 +1,151 @@
+//! Canonical JSON for hashing requests.
+//!
+//! `Request` serializes `HashMap`-backed fields such as `metadata` and MCP tool
+//! `headers` in iteration order, which differs between runs, so two equal requests can
+//! produce different bytes. [`Request::canonical_json`] sorts object keys at every
+//! level, including inside free-form `serde_json::Value` fields such as tool
+//! `parameters`, and [`Request::fingerprint`] hashes that form.
+
+use crate::types::Request;
+use serde_json::Value;
+use sha2::{Digest, Sha256};
+
+/// Writes `value` as compact JSON with the keys of every object sorted
+pub(crate) fn to_canonical_string(value: &Value) -> String {
+    let mut out = String::new();
+    write_canonical(value, &mut out);
+    out
+}
+
+fn write_canonical(value: &Value, out: &mut String) {
+    match value {
+        Value::Object(fields) => {
+            let mut fields: Vec<_> = fields.iter().collect();
+            fields.sort_unstable_by_key(|(key, _)| *key);
+            out.push('{');
+            for (i, (key, value)) in fields.into_iter().enumerate() {
+                if i > 0 {
+                    out.push(',');
+                }
+                out.push_str(&Value::from(key.as_str()).to_string());
+                out.push(':');
+                write_canonical(value, out);
+            }
+            out.push('}');
+        }
+        Value::Array(items) => {
+            out.push('[');
+            for (i, item) in items.iter().enumerate() {
+                if i > 0 {
+                    out.push(',');
+                }
+                write_canonical(item, out);
+            }
+            out.push(']');
+        }
+        scalar => out.push_str(&scalar.to_string()),
+    }
+}
+
+/// Lowercase hex encoding of a fingerprint
+#[cfg(feature = "client")]
+pub(crate) fn to_hex(bytes: &[u8]) -> String {
+    use std::fmt::Write;
+
+    bytes
+        .iter()
+        .fold(String::with_capacity(bytes.len() * 2), |mut hex, byte| {
+            let _ = write!(hex, "{byte:02x}");
+            hex
+        })
+}
+
+impl Request {
+    /// Serializes the request as compact JSON with object keys sorted at every level.
+    ///
+    /// Equal requests always produce the same string, whatever order their maps were
+    /// filled in, so it can be stored, compared or hashed.
+    ///
+    /// # Errors
+    ///
+    /// Returns [`Error::Json`](crate::Error::Json) if the request cannot be serialized.
+    pub fn canonical_json(&self) -> crate::Result<String> {
+        Ok(to_canonical_string(&serde_json::to_value(self)?))
+    }
+
+    /// SHA-256 of [`Request::canonical_json`], for cache keys and de-duplication.
+    ///
+    /// # Panics
+    ///
+    /// Never for a `Request`: every map it holds has string keys, so it always
+    /// serializes.
+    #[must_use]
+    pub fn fingerprint(&self) -> [u8; 32] {
+        let json = self
+            .canonical_json()
+            .expect("a Request always serializes to JSON");
+        Sha256::digest(json.as_bytes()).into()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::types::{Metadata, Tool};
+    use std::collections::HashMap;
+
+    fn request(pairs: &[(&str, &str)]) -> Request {
+        let mut metadata = Metadata::new();
+        let mut headers = HashMap::new();
+        for (key, value) in pairs {
+            metadata.insert(*key, *value).unwrap();
+            headers.insert((*key).to_string(), (*value).to_string());
+        }
+        Request::builder()
+            .model("gpt-4o")
+            .input("Hi")
+            .metadata(metadata)
+            .tools(vec![
+                Tool::mcp("docs", "https://mcp.example.com", Some(headers)),
+                Tool::function(
+                    "lookup",
+                    "Look something up",
+                    serde_json::json!({"type": "object", "properties": {"b": {}, "a": {}}}),
+                ),
+            ])
+            .build()
+    }
+
+    #[test]
+    fn map_insertion_order_does_not_change_the_fingerprint() {
+        let pairs: Vec<(String, String)> = (0..16)
+            .map(|i| (format!("key{i}"), format!("value{i}")))
+            .collect();
+        let forward: Vec<_> = pairs
+            .iter()
+            .map(|(k, v)| (k.as_str(), v.as_str()))
+            .collect();
+        let mut backward = forward.clone();
+        backward.reverse();
+
+        let (a, b) = (request(&forward), request(&backward));
+        assert_eq!(a.canonical_json().unwrap(), b.canonical_json().unwrap());
+        assert_eq!(a.fingerprint(), b.fingerprint());
+        assert_ne!(a.fingerprint(), request(&forward[1..]).fingerprint());
+
+        let json = a.canonical_json().unwrap();
+        assert!(json.contains(r#""properties":{"a":{},"b":{}}"#), "{json}");
+        assert!(json.starts_with(r#"{"input":"Hi","metadata":{"key0":"value0","key1":"#));
+    }
+
+    #[test]
+    fn canonical_strings_escape_keys_and_keep_array_order() {
+        let value = serde_json::json!({"z": [3, 1, {"y": null, "x": "\"q\""}], "a\nb": 1.5});
+        assert_eq!(
+            to_canonical_string(&value),
+            r#"{"a\nb":1.5,"z":[3,1,{"x":"\"q\"","y":null}]}"#
+        );
+        #[cfg(feature = "client")]
+        assert_eq!(to_hex(&[0x00, 0xab, 0x0f]), "00ab0f");
+    }
+}
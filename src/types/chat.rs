@@ -0,0 +1,682 @@
+//! Conversion from Chat Completions messages to Responses input.
+//!
+//! Code migrating from a Chat Completions SDK usually holds conversations as
+//! `{role, content}` messages. [`ChatMessage`] mirrors that shape, and
+//! [`convert_chat_messages`] turns a conversation into [`Input`] items:
+//!
+//! - `system` messages become [`instructions`](ConvertedChat::instructions) or developer
+//!   messages, see [`SystemMessages`]
+//! - `developer`, `user` and `assistant` text and image parts become messages
+//! - assistant `tool_calls` become `function_call` items, and `tool` messages their
+//!   `function_call_output`
+//!
+//! Audio, file parts and the legacy `function_call` / `function` role are rejected with
+//! a [`ChatConversionError`].
+
+use crate::types::{Input, InputItem, RequestBuilder};
+use serde::{Deserialize, Serialize};
+
+/// A Chat Completions message
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct ChatMessage {
+    /// Role: `system`, `developer`, `user`, `assistant` or `tool`
+    pub role: String,
+
+    /// Content of the message; empty for assistant messages that only call tools
+    #[serde(default)]
+    pub content: ChatContent,
+
+    /// Participant name; not carried over, the Responses API has no equivalent
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub name: Option<String>,
+
+    /// Tool calls made by an assistant message
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub tool_calls: Option<Vec<ChatToolCall>>,
+
+    /// ID of the tool call a `tool` message answers
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub tool_call_id: Option<String>,
+
+    /// Legacy single function call; rejected when converting
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub function_call: Option<serde_json::Value>,
+
+    /// Audio generated for an assistant message; rejected when converting
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub audio: Option<serde_json::Value>,
+}
+
+impl ChatMessage {
+    /// Creates a message with text content
+    pub fn new(role: impl Into<String>, content: impl Into<ChatContent>) -> Self {
+        Self {
+            role: role.into(),
+            content: content.into(),
+            name: None,
+            tool_calls: None,
+            tool_call_id: None,
+            function_call: None,
+            audio: None,
+        }
+    }
+
+    /// Creates a system message
+    pub fn system(content: impl Into<String>) -> Self {
+        Self::new("system", content.into())
+    }
+
+    /// Creates a user message
+    pub fn user(content: impl Into<ChatContent>) -> Self {
+        Self::new("user", content)
+    }
+
+    /// Creates an assistant message
+    pub fn assistant(content: impl Into<String>) -> Self {
+        Self::new("assistant", content.into())
+    }
+
+    /// Creates an assistant message that only calls tools
+    #[must_use]
+    pub fn assistant_tool_calls(tool_calls: Vec<ChatToolCall>) -> Self {
+        Self {
+            tool_calls: Some(tool_calls),
+            ..Self::new("assistant", ChatContent::Empty)
+        }
+    }
+
+    /// Creates a tool message answering the call `tool_call_id`
+    pub fn tool(tool_call_id: impl Into<String>, content: impl Into<String>) -> Self {
+        Self {
+            tool_call_id: Some(tool_call_id.into()),
+            ..Self::new("tool", content.into())
+        }
+    }
+}
+
+/// Content of a [`ChatMessage`]: a string, an array of parts, or `null`
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+#[serde(untagged)]
+pub enum ChatContent {
+    /// No content
+    #[default]
+    Empty,
+    /// Plain text
+    Text(String),
+    /// Text, image and other parts
+    Parts(Vec<ChatContentPart>),
+}
+
+impl From<String> for ChatContent {
+    fn from(text: String) -> Self {
+        Self::Text(text)
+    }
+}
+
+impl From<&str> for ChatContent {
+    fn from(text: &str) -> Self {
+        Self::Text(text.to_string())
+    }
+}
+
+impl From<Vec<ChatContentPart>> for ChatContent {
+    fn from(parts: Vec<ChatContentPart>) -> Self {
+        Self::Parts(parts)
+    }
+}
+
+/// One part of an array [`ChatContent`]
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(tag = "type", rename_all = "snake_case")]
+pub enum ChatContentPart {
+    /// Text
+    Text {
+        /// The text
+        text: String,
+    },
+    /// Image by URL or data URL
+    ImageUrl {
+        /// The image
+        image_url: ChatImageUrl,
+    },
+    /// Refusal from an assistant message
+    Refusal {
+        /// Refusal explanation
+        refusal: String,
+    },
+    /// Audio input; rejected when converting
+    InputAudio {
+        /// Audio data and format
+        input_audio: serde_json::Value,
+    },
+    /// File input; rejected when converting
+    File {
+        /// File data or ID
+        file: serde_json::Value,
+    },
+}
+
+impl ChatContentPart {
+    /// Creates a text part
+    pub fn text(text: impl Into<String>) -> Self {
+        Self::Text { text: text.into() }
+    }
+
+    /// Creates an image part
+    pub fn image_url(url: impl Into<String>) -> Self {
+        Self::ImageUrl {
+            image_url: ChatImageUrl {
+                url: url.into(),
+                detail: None,
+            },
+        }
+    }
+
+    fn kind(&self) -> &'static str {
+        match self {
+            Self::Text { .. } => "text",
+            Self::ImageUrl { .. } => "image_url",
+            Self::Refusal { .. } => "refusal",
+            Self::InputAudio { .. } => "input_audio",
+            Self::File { .. } => "file",
+        }
+    }
+}
+
+/// Image referenced by a [`ChatContentPart::ImageUrl`]
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct ChatImageUrl {
+    /// URL or data URL of the image
+    pub url: String,
+
+    /// Detail level (`low`, `high` or `auto`)
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub detail: Option<String>,
+}
+
+/// Tool call made by an assistant [`ChatMessage`]
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct ChatToolCall {
+    /// ID of the call, answered by a `tool` message
+    pub id: String,
+
+    /// Type of the call; always `function`
+    #[serde(rename = "type", default = "function_type")]
+    pub call_type: String,
+
+    /// The function called
+    pub function: ChatFunctionCall,
+}
+
+impl ChatToolCall {
+    /// Creates a function tool call
+    pub fn function(
+        id: impl Into<String>,
+        name: impl Into<String>,
+        arguments: impl Into<String>,
+    ) -> Self {
+        Self {
+            id: id.into(),
+            call_type: function_type(),
+            function: ChatFunctionCall {
+                name: name.into(),
+                arguments: arguments.into(),
+            },
+        }
+    }
+}
+
+fn function_type() -> String {
+    "function".to_string()
+}
+
+/// Function called by a [`ChatToolCall`]
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct ChatFunctionCall {
+    /// Name of the function
+    pub name: String,
+
+    /// JSON-encoded arguments
+    pub arguments: String,
+}
+
+/// A Chat Completions message that has no Responses equivalent.
+///
+/// `index` is the position of the offending message in the conversation.
+#[derive(Debug, Clone, PartialEq, Eq, thiserror::Error)]
+pub enum ChatConversionError {
+    /// The role is not one of `system`, `developer`, `user`, `assistant` or `tool`
+    #[error("message {index} has unsupported role `{role}`")]
+    UnsupportedRole {
+        /// Position of the message
+        index: usize,
+        /// The role
+        role: String,
+    },
+
+    /// A content part cannot be sent in this role's message
+    #[error("message {index} has a `{part}` part, which a `{role}` message cannot carry")]
+    UnsupportedPart {
+        /// Position of the message
+        index: usize,
+        /// The message's role
+        role: String,
+        /// Type of the part, e.g. `input_audio`
+        part: &'static str,
+    },
+
+    /// An assistant message carries generated audio
+    #[error("message {index} carries assistant audio, which cannot be replayed")]
+    Audio {
+        /// Position of the message
+        index: usize,
+    },
+
+    /// The legacy `function_call` field or `function` role is used
+    #[error("message {index} uses the legacy function calling format; use `tool_calls` and `tool` messages")]
+    LegacyFunctionCall {
+        /// Position of the message
+        index: usize,
+    },
+
+    /// A `tool` message has no `tool_call_id`
+    #[error("tool message {index} has no `tool_call_id`")]
+    MissingToolCallId {
+        /// Position of the message
+        index: usize,
+    },
+}
+
+/// Where `system` messages go when converting a conversation
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub enum SystemMessages {
+    /// Joined, in order, into [`ConvertedChat::instructions`]
+    #[default]
+    Instructions,
+    /// Kept in place as developer messages
+    Developer,
+}
+
+/// A conversation converted from Chat Completions messages
+#[derive(Debug, Clone)]
+pub struct ConvertedChat {
+    /// Text of the `system` messages, separated by blank lines, with
+    /// [`SystemMessages::Instructions`]
+    pub instructions: Option<String>,
+
+    /// The remaining messages as input items
+    pub input: Input,
+}
+
+/// Converts a Chat Completions conversation to Responses input.
+///
+/// # Errors
+///
+/// Returns a [`ChatConversionError`] for the first message with no Responses equivalent.
+pub fn convert_chat_messages(
+    messages: Vec<ChatMessage>,
+    system: SystemMessages,
+) -> Result<ConvertedChat, ChatConversionError> {
+    let mut instructions: Vec<String> = Vec::new();
+    let mut items = Vec::new();
+    for (index, message) in messages.into_iter().enumerate() {
+        if message.function_call.is_some() || message.role == "function" {
+            return Err(ChatConversionError::LegacyFunctionCall { index });
+        }
+        if message.audio.is_some() {
+            return Err(ChatConversionError::Audio { index });
+        }
+        match message.role.as_str() {
+            "system" if system == SystemMessages::Instructions => {
+                instructions.push(text_of(index, &message)?);
+            }
+            "system" | "developer" => {
+                let text = text_of(index, &message)?;
+                items.push(InputItem::message(
+                    "developer",
+                    vec![InputItem::content_text(text)],
+                ));
+            }
+            "user" => {
+                let parts = user_parts(index, &message)?;
+                if !parts.is_empty() {
+                    items.push(InputItem::message("user", parts));
+                }
+            }
+            "assistant" => {
+                let parts = assistant_parts(index, &message)?;
+                if !parts.is_empty() {
+                    items.push(InputItem::message("assistant", parts));
+                }
+                items.extend(message.tool_calls.into_iter().flatten().map(|call| {
+                    InputItem::function_call(call.id, call.function.name, call.function.arguments)
+                }));
+            }
+            "tool" => {
+                let output = text_of(index, &message)?;
+                let call_id = message
+                    .tool_call_id
+                    .ok_or(ChatConversionError::MissingToolCallId { index })?;
+                items.push(InputItem::function_call_output(call_id, output));
+            }
+            _ => {
+                return Err(ChatConversionError::UnsupportedRole {
+                    index,
+                    role: message.role,
+                })
+            }
+        }
+    }
+    Ok(ConvertedChat {
+        instructions: (!instructions.is_empty()).then(|| instructions.join("\n\n")),
+        input: Input::Items(items),
+    })
+}
+
+fn unsupported(index: usize, message: &ChatMessage, part: &ChatContentPart) -> ChatConversionError {
+    ChatConversionError::UnsupportedPart {
+        index,
+        role: message.role.clone(),
+        part: part.kind(),
+    }
+}
+
+/// Text of a message that may only hold text parts, which are concatenated
+fn text_of(index: usize, message: &ChatMessage) -> Result<String, ChatConversionError> {
+    match &message.content {
+        ChatContent::Empty => Ok(String::new()),
+        ChatContent::Text(text) => Ok(text.clone()),
+        ChatContent::Parts(parts) => parts
+            .iter()
+            .map(|part| match part {
+                ChatContentPart::Text { text } => Ok(text.as_str()),
+                _ => Err(unsupported(index, message, part)),
+            })
+            .collect(),
+    }
+}
+
+fn user_parts(
+    index: usize,
+    message: &ChatMessage,
+) -> Result<Vec<serde_json::Value>, ChatConversionError> {
+    match &message.content {
+        ChatContent::Empty => Ok(Vec::new()),
+        ChatContent::Text(text) => Ok(vec![InputItem::content_text(text)]),
+        ChatContent::Parts(parts) => parts
+            .iter()
+            .map(|part| match part {
+                ChatContentPart::Text { text } => Ok(InputItem::content_text(text)),
+                ChatContentPart::ImageUrl {
+                    image_url: ChatImageUrl { url, detail: None },
+                } => Ok(InputItem::content_image(url)),
+                ChatContentPart::ImageUrl {
+                    image_url:
+                        ChatImageUrl {
+                            url,
+                            detail: Some(detail),
+                        },
+                } => Ok(InputItem::content_image_with_detail(url, detail)),
+                ChatContentPart::Refusal { .. }
+                | ChatContentPart::InputAudio { .. }
+                | ChatContentPart::File { .. } => Err(unsupported(index, message, part)),
+            })
+            .collect(),
+    }
+}
+
+fn assistant_parts(
+    index: usize,
+    message: &ChatMessage,
+) -> Result<Vec<serde_json::Value>, ChatConversionError> {
+    let output_text = |text: &str| serde_json::json!({"type": "output_text", "text": text});
+    match &message.content {
+        ChatContent::Empty => Ok(Vec::new()),
+        ChatContent::Text(text) if text.is_empty() => Ok(Vec::new()),
+        ChatContent::Text(text) => Ok(vec![output_text(text)]),
+        ChatContent::Parts(parts) => parts
+            .iter()
+            .map(|part| match part {
+                ChatContentPart::Text { text } => Ok(output_text(text)),
+                ChatContentPart::Refusal { refusal } => {
+                    Ok(serde_json::json!({"type": "refusal", "refusal": refusal}))
+                }
+                ChatContentPart::ImageUrl { .. }
+                | ChatContentPart::InputAudio { .. }
+                | ChatContentPart::File { .. } => Err(unsupported(index, message, part)),
+            })
+            .collect(),
+    }
+}
+
+impl TryFrom<Vec<ChatMessage>> for Input {
+    type Error = ChatConversionError;
+
+    /// Converts a conversation, keeping `system` messages in place as developer messages
+    fn try_from(messages: Vec<ChatMessage>) -> Result<Self, Self::Error> {
+        convert_chat_messages(messages, SystemMessages::Developer).map(|chat| chat.input)
+    }
+}
+
+impl RequestBuilder {
+    /// Starts a request from a Chat Completions conversation, with `system` messages as
+    /// its instructions.
+    ///
+    /// ```rust
+    /// use open_ai_rust_responses_by_sshift::types::ChatMessage;
+    /// use open_ai_rust_responses_by_sshift::{Model, RequestBuilder};
+    ///
+    /// let request = RequestBuilder::from_chat_messages(vec![
+    ///     ChatMessage::system("Be brief."),
+    ///     ChatMessage::user("What is Rust?"),
+    /// ])?
+    /// .model(Model::GPT4oMini)
+    /// .build();
+    /// assert_eq!(request.instructions.as_deref(), Some("Be brief."));
+    /// # Ok::<(), open_ai_rust_responses_by_sshift::types::ChatConversionError>(())
+    /// ```
+    ///
+    /// # Errors
+    ///
+    /// Returns a [`ChatConversionError`] for the first message with no Responses equivalent.
+    pub fn from_chat_messages(messages: Vec<ChatMessage>) -> Result<Self, ChatConversionError> {
+        Self::from_chat_messages_with(messages, SystemMessages::Instructions)
+    }
+
+    /// Starts a request from a Chat Completions conversation, placing `system` messages
+    /// as `system` says
+    ///
+    /// # Errors
+    ///
+    /// Returns a [`ChatConversionError`] for the first message with no Responses equivalent.
+    pub fn from_chat_messages_with(
+        messages: Vec<ChatMessage>,
+        system: SystemMessages,
+    ) -> Result<Self, ChatConversionError> {
+        let chat = convert_chat_messages(messages, system)?;
+        let Input::Items(items) = chat.input else {
+            unreachable!("conversations convert to input items");
+        };
+        let builder = crate::types::Request::builder().input_items(items);
+        Ok(match chat.instructions {
+            Some(instructions) => builder.instructions(instructions),
+            None => builder,
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn transcript() -> Vec<ChatMessage> {
+        serde_json::from_value(serde_json::json!([
+            {"role": "system", "content": "You are a travel assistant."},
+            {"role": "system", "content": [{"type": "text", "text": "Answer in English."}]},
+            {"role": "user", "content": "Is it warm in Lisbon?", "name": "ana"},
+            {"role": "assistant", "content": null, "tool_calls": [
+                {"id": "call_1", "type": "function", "function": {"name": "get_weather", "arguments": "{\"city\":\"Lisbon\"}"}},
+                {"id": "call_2", "type": "function", "function": {"name": "get_time", "arguments": "{\"city\":\"Lisbon\"}"}}
+            ]},
+            {"role": "tool", "tool_call_id": "call_1", "content": "{\"temp_c\":24}"},
+            {"role": "tool", "tool_call_id": "call_2", "content": [{"type": "text", "text": "14:05"}]},
+            {"role": "assistant", "content": "Yes, 24°C at 14:05."},
+            {"role": "user", "content": [
+                {"type": "text", "text": "What about this place?"},
+                {"type": "image_url", "image_url": {"url": "https://example.com/porto.jpg", "detail": "low"}}
+            ]},
+            {"role": "assistant", "content": [{"type": "text", "text": "That's Porto."}]},
+            {"role": "developer", "content": "Keep answers short."}
+        ]))
+        .unwrap()
+    }
+
+    fn expected_items() -> Vec<serde_json::Value> {
+        serde_json::from_value(serde_json::json!([
+            {"type": "message", "role": "user", "content": [{"type": "input_text", "text": "Is it warm in Lisbon?"}]},
+            {"type": "function_call", "call_id": "call_1", "name": "get_weather", "arguments": "{\"city\":\"Lisbon\"}"},
+            {"type": "function_call", "call_id": "call_2", "name": "get_time", "arguments": "{\"city\":\"Lisbon\"}"},
+            {"type": "function_call_output", "call_id": "call_1", "output": "{\"temp_c\":24}"},
+            {"type": "function_call_output", "call_id": "call_2", "output": "14:05"},
+            {"type": "message", "role": "assistant", "content": [{"type": "output_text", "text": "Yes, 24°C at 14:05."}]},
+            {"type": "message", "role": "user", "content": [
+                {"type": "input_text", "text": "What about this place?"},
+                {"type": "input_image", "image_url": "https://example.com/porto.jpg", "detail": "low"}
+            ]},
+            {"type": "message", "role": "assistant", "content": [{"type": "output_text", "text": "That's Porto."}]},
+            {"type": "message", "role": "developer", "content": [{"type": "input_text", "text": "Keep answers short."}]}
+        ]))
+        .unwrap()
+    }
+
+    fn items(input: &Input) -> Vec<serde_json::Value> {
+        let Input::Items(items) = input else {
+            panic!("expected items, got {input:?}");
+        };
+        items
+            .iter()
+            .map(|item| serde_json::to_value(item).unwrap())
+            .collect()
+    }
+
+    #[test]
+    fn transcript_with_tool_round_trip_converts_in_order() {
+        let chat = convert_chat_messages(transcript(), SystemMessages::Instructions).unwrap();
+        assert_eq!(
+            chat.instructions.as_deref(),
+            Some("You are a travel assistant.\n\nAnswer in English.")
+        );
+        assert_eq!(items(&chat.input), expected_items());
+
+        // Converting keeps system messages in place as developer messages
+        let input = Input::try_from(transcript()).unwrap();
+        let mut expected = expected_items();
+        for text in ["Answer in English.", "You are a travel assistant."] {
+            expected.insert(
+                0,
+                serde_json::to_value(InputItem::message(
+                    "developer",
+                    vec![InputItem::content_text(text)],
+                ))
+                .unwrap(),
+            );
+        }
+        assert_eq!(items(&input), expected);
+
+        let request = RequestBuilder::from_chat_messages(transcript())
+            .unwrap()
+            .model(crate::Model::GPT4o)
+            .build();
+        assert_eq!(
+            request.instructions.as_deref(),
+            Some("You are a travel assistant.\n\nAnswer in English.")
+        );
+        assert_eq!(items(&request.input), expected_items());
+    }
+
+    #[test]
+    fn constructors_match_the_wire_format() {
+        let built = vec![
+            ChatMessage::system("Be brief."),
+            ChatMessage::user(vec![
+                ChatContentPart::text("Describe"),
+                ChatContentPart::image_url("https://example.com/a.png"),
+            ]),
+            ChatMessage::assistant_tool_calls(vec![ChatToolCall::function("call_1", "f", "{}")]),
+            ChatMessage::tool("call_1", "done"),
+            ChatMessage::assistant("Done."),
+        ];
+        let parsed: Vec<ChatMessage> = serde_json::from_value(serde_json::json!([
+            {"role": "system", "content": "Be brief."},
+            {"role": "user", "content": [
+                {"type": "text", "text": "Describe"},
+                {"type": "image_url", "image_url": {"url": "https://example.com/a.png"}}
+            ]},
+            {"role": "assistant", "content": null, "tool_calls": [
+                {"id": "call_1", "type": "function", "function": {"name": "f", "arguments": "{}"}}
+            ]},
+            {"role": "tool", "tool_call_id": "call_1", "content": "done"},
+            {"role": "assistant", "content": "Done."}
+        ]))
+        .unwrap();
+        assert_eq!(built, parsed);
+    }
+
+    #[test]
+    fn unsupported_shapes_are_rejected() {
+        let convert = |message: serde_json::Value| {
+            let messages = vec![
+                ChatMessage::user("Hi"),
+                serde_json::from_value(message).unwrap(),
+            ];
+            Input::try_from(messages).unwrap_err()
+        };
+
+        assert_eq!(
+            convert(serde_json::json!({"role": "user", "content": [
+                {"type": "input_audio", "input_audio": {"data": "UklGRg==", "format": "wav"}}
+            ]})),
+            ChatConversionError::UnsupportedPart {
+                index: 1,
+                role: "user".to_string(),
+                part: "input_audio"
+            }
+        );
+        assert_eq!(
+            convert(
+                serde_json::json!({"role": "assistant", "content": null, "audio": {"id": "audio_1"}})
+            ),
+            ChatConversionError::Audio { index: 1 }
+        );
+        assert_eq!(
+            convert(serde_json::json!({"role": "assistant", "content": null,
+                "function_call": {"name": "f", "arguments": "{}"}})),
+            ChatConversionError::LegacyFunctionCall { index: 1 }
+        );
+        assert_eq!(
+            convert(serde_json::json!({"role": "function", "name": "f", "content": "42"})),
+            ChatConversionError::LegacyFunctionCall { index: 1 }
+        );
+        assert_eq!(
+            convert(serde_json::json!({"role": "tool", "content": "42"})),
+            ChatConversionError::MissingToolCallId { index: 1 }
+        );
+        assert_eq!(
+            convert(serde_json::json!({"role": "assistant", "content": [
+                {"type": "image_url", "image_url": {"url": "https://example.com/a.png"}}
+            ]})),
+            ChatConversionError::UnsupportedPart {
+                index: 1,
+                role: "assistant".to_string(),
+                part: "image_url"
+            }
+        );
+        assert_eq!(
+            convert(serde_json::json!({"role": "critic", "content": "Meh"})),
+            ChatConversionError::UnsupportedRole {
+                index: 1,
+                role: "critic".to_string()
+            }
+        );
+    }
+}
@@ -1,5 +1,7 @@
 use serde::{Deserialize, Serialize};
 use std::env;
+use std::fmt;
+use std::sync::Arc;
 
 /// Scope that controls which recoverable errors should be retried automatically.
 #[derive(Debug, Clone, Copy, Serialize, Deserialize, PartialEq, Eq, Default)]
@@ -25,6 +27,41 @@ impl RetryScope {
     }
 }
 
+/// Randomization applied to retry delays, so clients that failed together do not retry
+/// in lockstep.
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, PartialEq, Eq, Default)]
+pub enum RetryJitter {
+    /// Wait exactly the suggested delay.
+    #[default]
+    None,
+    /// Wait a random delay between zero and the suggested delay.
+    Full,
+    /// Wait half the suggested delay plus a random part of the other half.
+    Equal,
+}
+
+impl RetryJitter {
+    /// Returns a human-friendly label for telemetry and logging.
+    #[must_use]
+    pub const fn as_str(self) -> &'static str {
+        match self {
+            Self::None => "none",
+            Self::Full => "full",
+            Self::Equal => "equal",
+        }
+    }
+
+    /// Scales `base` by `unit`, a random number in `[0, 1)`
+    #[cfg(feature = "client")]
+    pub(crate) fn apply(self, base: std::time::Duration, unit: f64) -> std::time::Duration {
+        match self {
+            Self::None => base,
+            Self::Full => base.mul_f64(unit),
+            Self::Equal => base / 2 + (base / 2).mul_f64(unit),
+        }
+    }
+}
+
 /// Recovery policy for handling container expiration and other recoverable errors.
 ///
 /// When constructed via [`RecoveryPolicy::from_env`], any environment variables that are
@@ -44,6 +81,18 @@ pub struct RecoveryPolicy {
     /// Whether to automatically prune expired containers from context
     pub auto_prune_expired_containers: bool,
 
+    /// Whether to replace an expired container referenced by ID
+    /// ([`Container::id`](crate::types::Container::id)) with a new one before retrying
+    #[serde(default)]
+    pub recreate_expired_containers: bool,
+
+    /// Whether to retry without `previous_response_id` when the response it names no
+    /// longer exists ([`Error::PreviousResponseNotFound`](crate::Error::PreviousResponseNotFound)).
+    ///
+    /// The retried request starts a new conversation, so this is off by default.
+    #[serde(default)]
+    pub reset_missing_previous_response: bool,
+
     /// Custom user-friendly message to show when containers are reset
     pub reset_message: Option<String>,
 
@@ -53,6 +102,89 @@ pub struct RecoveryPolicy {
     /// Scope that limits which recoverable errors are retried
     #[serde(default)]
     pub retry_scope: RetryScope,
+
+    /// Randomization applied to the delay before each retry
+    #[serde(default)]
+    pub jitter: RetryJitter,
+
+    /// Whether to send an idempotency key header with every attempt of a recovered create,
+    /// so the server can de-duplicate a request that succeeded before a retry. The key
+    /// changes whenever recovery changes the request body. Retried POSTs on the other
+    /// endpoints (file uploads, vector stores, image generation) carry one key per call.
+    #[serde(default = "default_use_idempotency_key")]
+    pub use_idempotency_key: bool,
+
+    /// Name of the idempotency key header
+    #[serde(default = "default_idempotency_header")]
+    pub idempotency_header: String,
+
+    /// Whether the idempotency key is the request's
+    /// [`fingerprint`](crate::Request::fingerprint) instead of a random id.
+    ///
+    /// Retries of a create then send the same key even across process restarts, but two
+    /// deliberate creates of an identical request are also de-duplicated by the server,
+    /// so this is off by default.
+    #[serde(default)]
+    pub idempotency_key_from_fingerprint: bool,
+
+    /// Hook that adjusts the request before each retry; not serialized
+    #[serde(skip)]
+    pub on_retry_transform: Option<RetryTransform>,
+}
+
+/// Hook applied to the request before each retry attempt.
+///
+/// Receives the request about to be resent and the attempt number it will be sent as
+/// (2 for the first retry). Runs after the built-in recovery adjustments, such as
+/// clearing `previous_response_id` for an expired container.
+///
+/// ```rust
+/// use open_ai_rust_responses_by_sshift::RecoveryPolicy;
+///
+/// // Ask for a shorter answer on every retry
+/// let policy = RecoveryPolicy::new().with_retry_transform(|request, _attempt| {
+///     request.max_output_tokens = request.max_output_tokens.map(|tokens| tokens / 2);
+/// });
+/// ```
+#[derive(Clone)]
+pub struct RetryTransform(Arc<RetryTransformFn>);
+
+type RetryTransformFn = dyn Fn(&mut crate::Request, u32) + Send + Sync;
+
+impl RetryTransform {
+    /// Wraps a closure as a retry transform
+    pub fn new(transform: impl Fn(&mut crate::Request, u32) + Send + Sync + 'static) -> Self {
+        Self(Arc::new(transform))
+    }
+
+    /// Applies the transform to a request about to be sent as `attempt`
+    pub fn apply(&self, request: &mut crate::Request, attempt: u32) {
+        (self.0)(request, attempt);
+    }
+}
+
+impl fmt::Debug for RetryTransform {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str("RetryTransform(..)")
+    }
+}
+
+// Closures have no structural equality; two transforms are equal only if they are the same hook
+impl PartialEq for RetryTransform {
+    fn eq(&self, other: &Self) -> bool {
+        Arc::ptr_eq(&self.0, &other.0)
+    }
+}
+
+/// Default header used to carry the idempotency key
+pub const DEFAULT_IDEMPOTENCY_HEADER: &str = "Idempotency-Key";
+
+fn default_use_idempotency_key() -> bool {
+    true
+}
+
+fn default_idempotency_header() -> String {
+    DEFAULT_IDEMPOTENCY_HEADER.to_string()
 }
 
 impl Default for RecoveryPolicy {
@@ -62,9 +194,16 @@ impl Default for RecoveryPolicy {
             notify_on_reset: false,
             max_retries: 1,
             auto_prune_expired_containers: true,
+            recreate_expired_containers: false,
+            reset_missing_previous_response: false,
             reset_message: None,
             log_recovery_attempts: false,
             retry_scope: RetryScope::default(),
+            jitter: RetryJitter::default(),
+            use_idempotency_key: true,
+            idempotency_header: default_idempotency_header(),
+            idempotency_key_from_fingerprint: false,
+            on_retry_transform: None,
         }
     }
 }
@@ -85,6 +224,7 @@ impl RecoveryPolicy {
     /// - `OAI_RECOVERY_AUTO_PRUNE` (`bool`)
     /// - `OAI_RECOVERY_LOG` (`bool`)
     /// - `OAI_RECOVERY_SCOPE` (`all | container | transient`)
+    /// - `OAI_RECOVERY_JITTER` (`none | full | equal`)
     ///
     /// Any variable that is unset or fails to parse will leave the default value intact.
     #[must_use]
@@ -98,7 +238,7 @@ impl RecoveryPolicy {
                     policy.max_retries = parsed;
                 }
                 Err(error) => {
-                    log::warn!(
+                    crate::diag::warn!(
                         "Failed to parse OAI_RECOVERY_MAX_RETRIES='{}': {error}; using default {}",
                         trimmed,
                         policy.max_retries
@@ -114,7 +254,7 @@ impl RecoveryPolicy {
                     policy.auto_retry_on_expired_container = parsed;
                 }
                 Err(error) => {
-                    log::warn!(
+                    crate::diag::warn!(
                         "Failed to parse OAI_RECOVERY_AUTO_RETRY='{}': {error}; using default {}",
                         trimmed,
                         policy.auto_retry_on_expired_container
@@ -130,7 +270,7 @@ impl RecoveryPolicy {
                     policy.auto_prune_expired_containers = parsed;
                 }
                 Err(error) => {
-                    log::warn!(
+                    crate::diag::warn!(
                         "Failed to parse OAI_RECOVERY_AUTO_PRUNE='{}': {error}; using default {}",
                         trimmed,
                         policy.auto_prune_expired_containers
@@ -146,7 +286,7 @@ impl RecoveryPolicy {
                     policy.log_recovery_attempts = parsed;
                 }
                 Err(error) => {
-                    log::warn!(
+                    crate::diag::warn!(
                         "Failed to parse OAI_RECOVERY_LOG='{}': {error}; using default {}",
                         trimmed,
                         policy.log_recovery_attempts
@@ -168,7 +308,7 @@ impl RecoveryPolicy {
                     policy.retry_scope = RetryScope::TransientOnly;
                 }
                 _ => {
-                    log::warn!(
+                    crate::diag::warn!(
                         "Unrecognized OAI_RECOVERY_SCOPE='{}'; expected all|container|transient; using default {}",
                         trimmed,
                         policy.retry_scope.as_str()
@@ -177,9 +317,37 @@ impl RecoveryPolicy {
             }
         }
 
+        if let Ok(value) = env::var("OAI_RECOVERY_JITTER") {
+            let trimmed = value.trim().to_ascii_lowercase();
+            match trimmed.as_str() {
+                "none" => policy.jitter = RetryJitter::None,
+                "full" => policy.jitter = RetryJitter::Full,
+                "equal" => policy.jitter = RetryJitter::Equal,
+                _ => {
+                    crate::diag::warn!(
+                        "Unrecognized OAI_RECOVERY_JITTER='{}'; expected none|full|equal; using default {}",
+                        trimmed,
+                        policy.jitter.as_str()
+                    );
+                }
+            }
+        }
+
         policy
     }
 
+    /// Whether this policy retries anything at all.
+    ///
+    /// False when `max_retries` is zero, or when `retry_scope` only covers container
+    /// expirations and `auto_retry_on_expired_container` is off. Other error classes are
+    /// retried regardless of `auto_retry_on_expired_container`.
+    #[must_use]
+    pub fn enabled(&self) -> bool {
+        self.max_retries > 0
+            && (self.auto_retry_on_expired_container
+                || self.retry_scope != RetryScope::ContainerOnly)
+    }
+
     /// Creates a conservative recovery policy (no automatic retries)
     #[must_use]
     pub fn conservative() -> Self {
@@ -188,9 +356,16 @@ impl RecoveryPolicy {
             notify_on_reset: true,
             max_retries: 0,
             auto_prune_expired_containers: false,
+            recreate_expired_containers: false,
+            reset_missing_previous_response: false,
             reset_message: None,
             log_recovery_attempts: true,
             retry_scope: RetryScope::ContainerOnly,
+            jitter: RetryJitter::None,
+            use_idempotency_key: true,
+            idempotency_header: default_idempotency_header(),
+            idempotency_key_from_fingerprint: false,
+            on_retry_transform: None,
         }
     }
 
@@ -202,15 +377,54 @@ impl RecoveryPolicy {
             notify_on_reset: false,
             max_retries: 3,
             auto_prune_expired_containers: true,
+            recreate_expired_containers: false,
+            reset_missing_previous_response: true,
             reset_message: Some(
                 "Your previous code session expired, so I've started a fresh conversation for you."
                     .to_string(),
             ),
             log_recovery_attempts: true,
             retry_scope: RetryScope::AllRecoverable,
+            jitter: RetryJitter::None,
+            use_idempotency_key: true,
+            idempotency_header: default_idempotency_header(),
+            idempotency_key_from_fingerprint: false,
+            on_retry_transform: None,
         }
     }
 
+    /// Sets whether to send an idempotency key header on recovered creates
+    #[must_use]
+    pub fn with_idempotency_key(mut self, enabled: bool) -> Self {
+        self.use_idempotency_key = enabled;
+        self
+    }
+
+    /// Sets whether the idempotency key is derived from the request's fingerprint, so an
+    /// identical request sent again after a restart carries the same key
+    #[must_use]
+    pub fn with_fingerprint_idempotency_key(mut self, enabled: bool) -> Self {
+        self.idempotency_key_from_fingerprint = enabled;
+        self
+    }
+
+    /// Sets the name of the idempotency key header (default `Idempotency-Key`)
+    #[must_use]
+    pub fn with_idempotency_header(mut self, header: impl Into<String>) -> Self {
+        self.idempotency_header = header.into();
+        self
+    }
+
+    /// Sets a hook that adjusts the request before each retry attempt
+    #[must_use]
+    pub fn with_retry_transform(
+        mut self,
+        transform: impl Fn(&mut crate::Request, u32) + Send + Sync + 'static,
+    ) -> Self {
+        self.on_retry_transform = Some(RetryTransform::new(transform));
+        self
+    }
+
     /// Sets whether to automatically retry on expired container errors
     #[must_use]
     pub fn with_auto_retry(mut self, auto_retry: bool) -> Self {
@@ -239,6 +453,24 @@ impl RecoveryPolicy {
         self
     }
 
+    /// Sets whether an expired container referenced by ID is recreated before retrying.
+    ///
+    /// The replacement gets the old container's name and expiration policy, and the
+    /// retried request points at it instead.
+    #[must_use]
+    pub fn with_recreate_expired_containers(mut self, recreate: bool) -> Self {
+        self.recreate_expired_containers = recreate;
+        self
+    }
+
+    /// Sets whether a request whose `previous_response_id` no longer exists is retried
+    /// without it, starting a new conversation
+    #[must_use]
+    pub fn with_reset_missing_previous_response(mut self, reset: bool) -> Self {
+        self.reset_missing_previous_response = reset;
+        self
+    }
+
     /// Sets a custom reset message
     #[must_use]
     pub fn with_reset_message(mut self, message: impl Into<String>) -> Self {
@@ -260,6 +492,13 @@ impl RecoveryPolicy {
         self
     }
 
+    /// Sets the randomization applied to the delay before each retry
+    #[must_use]
+    pub fn with_jitter(mut self, jitter: RetryJitter) -> Self {
+        self.jitter = jitter;
+        self
+    }
+
     /// Returns the user-friendly reset message
     #[must_use]
     pub fn get_reset_message(&self) -> String {
@@ -273,8 +512,10 @@ impl RecoveryPolicy {
 /// Callback function type for recovery notifications
 pub type RecoveryCallback = Box<dyn Fn(&crate::Error, u32) + Send + Sync>;
 
-/// Configuration for the OpenAI Responses API client
-#[derive(Debug, Clone, Serialize, Deserialize)]
+/// Configuration for the OpenAI Responses API client.
+///
+/// The `Debug` output shows the API key only as [`redact_api_key`] would.
+#[derive(Clone, Serialize, Deserialize)]
 pub struct Config {
     /// API key for authentication
     pub api_key: String,
@@ -296,6 +537,31 @@ fn default_base_url() -> String {
     "https://api.openai.com/v1".to_string()
 }
 
+impl std::fmt::Debug for Config {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("Config")
+            .field("api_key", &redact_api_key(&self.api_key))
+            .field("base_url", &self.base_url)
+            .field("organization_id", &self.organization_id)
+            .field("recovery_policy", &self.recovery_policy)
+            .finish()
+    }
+}
+
+/// Shortens an API key to a form that is safe to log, such as `sk-...x7Qz`.
+///
+/// Only the last four characters are kept, and only for keys long enough that they
+/// give little away; shorter keys become `sk-...`.
+#[must_use]
+pub fn redact_api_key(api_key: &str) -> String {
+    const MIN_LEN_FOR_TAIL: usize = 20;
+    let tail = match api_key.char_indices().rev().nth(3) {
+        Some((start, _)) if api_key.len() >= MIN_LEN_FOR_TAIL => &api_key[start..],
+        _ => "",
+    };
+    format!("sk-...{tail}")
+}
+
 impl Config {
     /// Creates a new configuration with the given API key
     pub fn new(api_key: impl Into<String>) -> Self {
@@ -463,6 +729,118 @@ pub enum Model {
     Custom(String),
 }
 
+impl Model {
+    /// Returns the most output tokens the model can generate in one response, if known.
+    ///
+    /// Returns `None` for custom models and models not used with the Responses API.
+    #[must_use]
+    pub fn max_output_tokens(&self) -> Option<u32> {
+        match self {
+            Self::GPT5 | Self::GPT5Mini | Self::GPT5Nano => Some(128_000),
+            Self::O3 | Self::O4Mini | Self::O3Mini | Self::O1 => Some(100_000),
+            Self::O1Mini => Some(65_536),
+            Self::GPT41 | Self::GPT41Nano | Self::GPT41Mini | Self::O1Preview => Some(32_768),
+            Self::GPT4o | Self::GPT4o20241120 | Self::GPT4o20240806 | Self::GPT4oMini => {
+                Some(16_384)
+            }
+            Self::GPT4 => Some(8_192),
+            Self::GPT4o20240513
+            | Self::GPT4Turbo
+            | Self::GPT4Turbo20240409
+            | Self::GPT35Turbo
+            | Self::GPT35Turbo0125
+            | Self::GPT35Turbo1106
+            | Self::GPT35TurboInstruct => Some(4_096),
+            Self::GPT4_32k | Self::GPTImage1 | Self::Custom(_) => None,
+        }
+    }
+
+    /// Returns the model's context window in tokens (input plus output), if known.
+    ///
+    /// Returns `None` for custom models and image models.
+    #[must_use]
+    pub fn context_window(&self) -> Option<u32> {
+        match self {
+            Self::GPT5 | Self::GPT5Mini | Self::GPT5Nano => Some(400_000),
+            Self::GPT41 | Self::GPT41Nano | Self::GPT41Mini => Some(1_047_576),
+            Self::O3 | Self::O4Mini | Self::O3Mini | Self::O1 => Some(200_000),
+            Self::O1Mini
+            | Self::O1Preview
+            | Self::GPT4o
+            | Self::GPT4o20241120
+            | Self::GPT4o20240806
+            | Self::GPT4o20240513
+            | Self::GPT4oMini
+            | Self::GPT4Turbo
+            | Self::GPT4Turbo20240409 => Some(128_000),
+            Self::GPT4_32k => Some(32_768),
+            Self::GPT35Turbo | Self::GPT35Turbo0125 | Self::GPT35Turbo1106 => Some(16_385),
+            Self::GPT4 => Some(8_192),
+            Self::GPT35TurboInstruct => Some(4_096),
+            Self::GPTImage1 | Self::Custom(_) => None,
+        }
+    }
+
+    /// Returns whether the model accepts `temperature` and `top_p`.
+    ///
+    /// The GPT-5 and o-series reasoning models reject them. Custom models are judged by
+    /// name, so `gpt-5-*` and `o<digit>*` names are treated as reasoning models.
+    #[must_use]
+    pub fn supports_temperature(&self) -> bool {
+        match self {
+            Self::GPT5
+            | Self::GPT5Mini
+            | Self::GPT5Nano
+            | Self::O3
+            | Self::O4Mini
+            | Self::O3Mini
+            | Self::O1
+            | Self::O1Preview
+            | Self::O1Mini
+            | Self::GPTImage1 => false,
+            Self::Custom(name) => !(name.starts_with("gpt-5") || is_o_series_name(name)),
+            _ => true,
+        }
+    }
+
+    /// Returns whether the model accepts a `reasoning` object.
+    ///
+    /// Only the GPT-5 and o-series reasoning models do. Custom models are assumed to,
+    /// since their capabilities are unknown.
+    #[must_use]
+    pub fn supports_reasoning(&self) -> bool {
+        match self {
+            Self::Custom(_) => true,
+            Self::GPTImage1 => false,
+            model => !model.supports_temperature(),
+        }
+    }
+
+    /// Returns whether the model accepts `effort` as `reasoning.effort`.
+    ///
+    /// `minimal` is GPT-5 only, and o1-mini and o1-preview take no effort at all. Custom
+    /// models named `o<digit>*` are treated as o-series; other custom models accept any effort.
+    #[must_use]
+    pub fn supports_effort(&self, effort: crate::types::Effort) -> bool {
+        match self {
+            Self::GPT5 | Self::GPT5Mini | Self::GPT5Nano => true,
+            Self::O3 | Self::O4Mini | Self::O3Mini | Self::O1 => {
+                effort != crate::types::Effort::Minimal
+            }
+            Self::Custom(name) => {
+                effort != crate::types::Effort::Minimal || !is_o_series_name(name)
+            }
+            _ => false,
+        }
+    }
+}
+
+/// Whether a model name looks like an o-series model (`o` followed by a digit)
+fn is_o_series_name(name: &str) -> bool {
+    let mut chars = name.chars();
+    chars.next() == Some('o') && chars.next().is_some_and(|c| c.is_ascii_digit())
+}
+
 impl From<String> for Model {
     fn from(s: String) -> Self {
         match s.as_str() {
@@ -0,0 +1,353 @@
+//! Comparison of the parameters a response echoes back against the request that
+//! produced it.
+//!
+//! The server reports the `temperature`, `top_p`, `max_output_tokens`, `tool_choice`
+//! and `truncation` it actually used. When it drops or adjusts a parameter, for example
+//! `temperature` on a reasoning model, the request still succeeds and only the echo
+//! shows it. [`Response::diff_request`](crate::Response::diff_request) lists those
+//! differences.
+
+use crate::types::{ResponseTruncation, ToolChoice, TruncationSetting};
+use std::fmt;
+
+/// Request parameter the server echoes back on a response
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum ParamField {
+    /// `temperature`
+    Temperature,
+    /// `top_p`
+    TopP,
+    /// `max_output_tokens`
+    MaxOutputTokens,
+    /// `tool_choice`
+    ToolChoice,
+    /// `truncation`
+    Truncation,
+}
+
+impl ParamField {
+    /// Name of the field on the wire
+    #[must_use]
+    pub fn as_str(self) -> &'static str {
+        match self {
+            Self::Temperature => "temperature",
+            Self::TopP => "top_p",
+            Self::MaxOutputTokens => "max_output_tokens",
+            Self::ToolChoice => "tool_choice",
+            Self::Truncation => "truncation",
+        }
+    }
+}
+
+impl fmt::Display for ParamField {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(self.as_str())
+    }
+}
+
+/// A difference between a sent parameter and its echo, as reported by
+/// [`Response::diff_request`](crate::Response::diff_request).
+///
+/// Values are rendered as they appear on the wire: numbers as written, tool choice
+/// modes and truncation settings as bare strings, other tool choices as compact JSON.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum ParamDiscrepancy {
+    /// The parameter was sent, but the response does not echo it
+    Ignored {
+        /// The parameter
+        field: ParamField,
+    },
+    /// The parameter was sent, but the response echoes a different value
+    Clamped {
+        /// The parameter
+        field: ParamField,
+        /// Value in the request
+        sent: String,
+        /// Value the response reports
+        effective: String,
+    },
+    /// The parameter was not sent, and the response reports the value used in its place
+    Defaulted {
+        /// The parameter
+        field: ParamField,
+        /// Value the response reports
+        effective: String,
+    },
+}
+
+impl ParamDiscrepancy {
+    /// The parameter that differs
+    #[must_use]
+    pub fn field(&self) -> ParamField {
+        match self {
+            Self::Ignored { field }
+            | Self::Clamped { field, .. }
+            | Self::Defaulted { field, .. } => *field,
+        }
+    }
+
+    /// Whether a parameter that was sent did not take effect as sent, as opposed to a
+    /// server default filling in for one that was not
+    #[must_use]
+    pub fn is_dropped(&self) -> bool {
+        !matches!(self, Self::Defaulted { .. })
+    }
+}
+
+impl fmt::Display for ParamDiscrepancy {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::Ignored { field } => write!(f, "{field} was sent but ignored"),
+            Self::Clamped {
+                field,
+                sent,
+                effective,
+            } => write!(f, "{field} was sent as {sent} but {effective} was used"),
+            Self::Defaulted { field, effective } => {
+                write!(f, "{field} was not sent; {effective} was used")
+            }
+        }
+    }
+}
+
+/// Compares one sent value against its echo, rendering the values of a mismatch
+fn compare<S, E>(
+    field: ParamField,
+    sent: Option<&S>,
+    effective: Option<&E>,
+    same: impl Fn(&S, &E) -> bool,
+    render_sent: impl Fn(&S) -> String,
+    render_effective: impl Fn(&E) -> String,
+) -> Option<ParamDiscrepancy> {
+    match (sent, effective) {
+        (None, None) => None,
+        (Some(_), None) => Some(ParamDiscrepancy::Ignored { field }),
+        (None, Some(effective)) => Some(ParamDiscrepancy::Defaulted {
+            field,
+            effective: render_effective(effective),
+        }),
+        (Some(sent), Some(effective)) if same(sent, effective) => None,
+        (Some(sent), Some(effective)) => Some(ParamDiscrepancy::Clamped {
+            field,
+            sent: render_sent(sent),
+            effective: render_effective(effective),
+        }),
+    }
+}
+
+/// Sampling values go through `f32` on both sides, so only rounding noise is tolerated
+#[allow(clippy::trivially_copy_pass_by_ref)]
+fn same_f32(sent: &f32, effective: &f32) -> bool {
+    (sent - effective).abs() <= 1e-6
+}
+
+fn render_tool_choice(choice: &ToolChoice) -> String {
+    match choice {
+        ToolChoice::String(mode) => mode.clone(),
+        other => serde_json::to_string(other).unwrap_or_default(),
+    }
+}
+
+fn render_truncation_setting(setting: TruncationSetting) -> String {
+    match setting {
+        TruncationSetting::Auto => "auto",
+        TruncationSetting::Disabled => "disabled",
+    }
+    .to_string()
+}
+
+fn render_truncation(truncation: &ResponseTruncation) -> String {
+    match truncation {
+        ResponseTruncation::Setting(setting) => render_truncation_setting(*setting),
+        ResponseTruncation::Config(config) => config.truncation_type.clone(),
+        ResponseTruncation::Other(other) => other.clone(),
+    }
+}
+
+pub(crate) fn diff_request(
+    response: &crate::Response,
+    request: &crate::Request,
+) -> Vec<ParamDiscrepancy> {
+    [
+        compare(
+            ParamField::Temperature,
+            request.temperature.as_ref(),
+            response.temperature.as_ref(),
+            same_f32,
+            ToString::to_string,
+            ToString::to_string,
+        ),
+        compare(
+            ParamField::TopP,
+            request.top_p.as_ref(),
+            response.top_p.as_ref(),
+            same_f32,
+            ToString::to_string,
+            ToString::to_string,
+        ),
+        compare(
+            ParamField::MaxOutputTokens,
+            request.max_output_tokens.as_ref(),
+            response.max_output_tokens.as_ref(),
+            PartialEq::eq,
+            ToString::to_string,
+            ToString::to_string,
+        ),
+        compare(
+            ParamField::ToolChoice,
+            request.tool_choice.as_ref(),
+            response.tool_choice.as_ref(),
+            PartialEq::eq,
+            render_tool_choice,
+            render_tool_choice,
+        ),
+        compare(
+            ParamField::Truncation,
+            request.truncation.as_ref(),
+            response.truncation.as_ref(),
+            |sent, effective| render_truncation_setting(*sent) == render_truncation(effective),
+            |sent| render_truncation_setting(*sent),
+            render_truncation,
+        ),
+    ]
+    .into_iter()
+    .flatten()
+    .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    fn response(echo: serde_json::Value) -> crate::Response {
+        let mut base = json!({
+            "id": "resp_1",
+            "object": "response",
+            "created_at": 1_700_000_000,
+            "model": "o3",
+            "status": "completed",
+            "output": [],
+        });
+        let serde_json::Value::Object(fields) = echo else {
+            panic!("expected an object");
+        };
+        base.as_object_mut().unwrap().extend(fields);
+        serde_json::from_value(base).unwrap()
+    }
+
+    fn request() -> crate::types::RequestBuilder {
+        crate::Request::builder().model("o3").input("Hi")
+    }
+
+    #[test]
+    fn matching_echoes_report_nothing() {
+        let sent = request()
+            .temperature(0.7)
+            .top_p(0.9)
+            .max_output_tokens(500)
+            .tool_choice(ToolChoice::required())
+            .truncation(TruncationSetting::Auto)
+            .build();
+        let echoed = response(json!({
+            "temperature": 0.7,
+            "top_p": 0.9,
+            "max_output_tokens": 500,
+            "tool_choice": "required",
+            "truncation": "auto",
+        }));
+        assert_eq!(echoed.diff_request(&sent), []);
+
+        // Neither sent nor echoed
+        assert_eq!(response(json!({})).diff_request(&request().build()), []);
+
+        // The object form of truncation matches on its type
+        let echoed = response(json!({"truncation": {"type": "auto", "last_messages": 10}}));
+        let sent = request().truncation(TruncationSetting::Auto).build();
+        assert_eq!(echoed.diff_request(&sent), []);
+    }
+
+    #[test]
+    fn every_sent_and_echoed_combination_is_classified() {
+        // Sent, not echoed
+        let sent = request()
+            .temperature(0.2)
+            .top_p(0.5)
+            .max_output_tokens(100)
+            .tool_choice(ToolChoice::function("get_weather"))
+            .truncation(TruncationSetting::Auto)
+            .build();
+        let ignored: Vec<_> = response(json!({}))
+            .diff_request(&sent)
+            .iter()
+            .map(|discrepancy| {
+                assert!(discrepancy.is_dropped());
+                assert!(matches!(discrepancy, ParamDiscrepancy::Ignored { .. }));
+                discrepancy.field()
+            })
+            .collect();
+        assert_eq!(
+            ignored,
+            [
+                ParamField::Temperature,
+                ParamField::TopP,
+                ParamField::MaxOutputTokens,
+                ParamField::ToolChoice,
+                ParamField::Truncation,
+            ]
+        );
+
+        // Sent, echoed with another value
+        let echoed = response(json!({
+            "temperature": 1.0,
+            "top_p": 1.0,
+            "max_output_tokens": 64,
+            "tool_choice": "auto",
+            "truncation": "disabled",
+        }));
+        let clamped = echoed.diff_request(&sent);
+        let rendered: Vec<_> = clamped
+            .iter()
+            .map(|discrepancy| match discrepancy {
+                ParamDiscrepancy::Clamped {
+                    field,
+                    sent,
+                    effective,
+                } => format!("{field}: {sent} -> {effective}"),
+                other => panic!("expected a clamped value, got {other:?}"),
+            })
+            .collect();
+        assert_eq!(
+            rendered,
+            [
+                "temperature: 0.2 -> 1",
+                "top_p: 0.5 -> 1",
+                "max_output_tokens: 100 -> 64",
+                r#"tool_choice: {"type":"function","name":"get_weather"} -> auto"#,
+                "truncation: auto -> disabled",
+            ]
+        );
+        assert_eq!(
+            clamped[0].to_string(),
+            "temperature was sent as 0.2 but 1 was used"
+        );
+
+        // Not sent, echoed
+        let defaulted = echoed.diff_request(&request().build());
+        assert!(defaulted
+            .iter()
+            .all(|discrepancy| !discrepancy.is_dropped()));
+        assert_eq!(
+            defaulted[0],
+            ParamDiscrepancy::Defaulted {
+                field: ParamField::Temperature,
+                effective: "1".to_string(),
+            }
+        );
+        assert_eq!(defaulted.len(), 5);
+        assert_eq!(
+            defaulted[4].to_string(),
+            "truncation was not sent; disabled was used"
+        );
+    }
+}
@@ -1,10 +1,27 @@
 use serde::{Deserialize, Serialize};
 
+/// Sort order for list endpoints, by creation time
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum Order {
+    /// Oldest first
+    Asc,
+    /// Newest first
+    Desc,
+}
+
 /// Pagination parameters for list endpoints
-#[derive(Debug, Clone, Serialize, Deserialize)]
+///
+/// ```rust
+/// use open_ai_rust_responses_by_sshift::types::{Order, PaginationParams};
+///
+/// let params = PaginationParams::new().limit(50).order(Order::Desc).build()?;
+/// # Ok::<(), open_ai_rust_responses_by_sshift::Error>(())
+/// ```
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
 #[serde(rename_all = "snake_case")]
 pub struct PaginationParams {
-    /// Maximum number of items to return
+    /// Maximum number of items to return (1 to 100 on most endpoints)
     #[serde(skip_serializing_if = "Option::is_none")]
     pub limit: Option<u32>,
 
@@ -15,22 +32,131 @@ pub struct PaginationParams {
     /// Token for pagination
     #[serde(skip_serializing_if = "Option::is_none")]
     pub before: Option<String>,
+
+    /// Sort order by creation time
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub order: Option<Order>,
 }
 
-/// Paginated list of items
+impl PaginationParams {
+    /// Smallest `limit` the API accepts
+    pub const MIN_LIMIT: u32 = 1;
+
+    /// Largest `limit` most list endpoints accept; the files endpoint allows more
+    pub const MAX_LIMIT: u32 = 100;
+
+    /// Creates empty pagination parameters (server defaults)
+    #[must_use]
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Sets the maximum number of items to return
+    #[must_use]
+    pub fn limit(mut self, limit: u32) -> Self {
+        self.limit = Some(limit);
+        self
+    }
+
+    /// Returns items after the given object ID
+    #[must_use]
+    pub fn after(mut self, after: impl Into<String>) -> Self {
+        self.after = Some(after.into());
+        self
+    }
+
+    /// Returns items before the given object ID
+    #[must_use]
+    pub fn before(mut self, before: impl Into<String>) -> Self {
+        self.before = Some(before.into());
+        self
+    }
+
+    /// Sets the sort order
+    #[must_use]
+    pub fn order(mut self, order: Order) -> Self {
+        self.order = Some(order);
+        self
+    }
+
+    /// Checks that `limit`, if set, is within the range the API accepts
+    ///
+    /// # Errors
+    ///
+    /// Returns `ValidationError::PaginationLimitOutOfRange` if it is not.
+    pub fn validate(&self) -> std::result::Result<(), crate::types::ValidationError> {
+        self.validate_max(Self::MAX_LIMIT)
+    }
+
+    /// Checks that `limit`, if set, is between [`MIN_LIMIT`](Self::MIN_LIMIT) and `max`,
+    /// for endpoints whose cap differs from [`MAX_LIMIT`](Self::MAX_LIMIT)
+    ///
+    /// # Errors
+    ///
+    /// Returns `ValidationError::PaginationLimitOutOfRange` if it is not.
+    pub fn validate_max(&self, max: u32) -> std::result::Result<(), crate::types::ValidationError> {
+        match self.limit {
+            Some(limit) if !(Self::MIN_LIMIT..=max).contains(&limit) => {
+                Err(crate::types::ValidationError::PaginationLimitOutOfRange {
+                    limit,
+                    min: Self::MIN_LIMIT,
+                    max,
+                })
+            }
+            _ => Ok(()),
+        }
+    }
+
+    /// Validates and returns the parameters
+    ///
+    /// # Errors
+    ///
+    /// Returns `Error::Validation` if `limit` is outside 1..=100.
+    pub fn build(self) -> crate::Result<Self> {
+        self.validate()?;
+        Ok(self)
+    }
+}
+
+/// Paginated list of items.
+///
+/// Only `data` is required, so list payloads from OpenAI-compatible proxies that leave
+/// out `object`, `has_more` or the page IDs still parse.
 #[derive(Debug, Clone, Deserialize)]
 pub struct PaginatedList<T> {
     /// List of items
     pub data: Vec<T>,
 
-    /// Type of object
+    /// Type of object; `"list"` if the payload leaves it out
+    #[serde(default = "default_list_object")]
     pub object: String,
 
-    /// Whether there are more items to retrieve
+    /// Whether there are more items to retrieve; `false` if missing or `null`
+    #[serde(default, deserialize_with = "bool_or_null")]
     pub has_more: bool,
 
     /// Token for pagination
+    #[serde(default)]
     pub next_cursor: Option<String>,
+
+    /// ID of the first item in this page
+    #[serde(default)]
+    pub first_id: Option<String>,
+
+    /// ID of the last item in this page
+    #[serde(default)]
+    pub last_id: Option<String>,
+}
+
+fn default_list_object() -> String {
+    "list".to_string()
+}
+
+fn bool_or_null<'de, D>(deserializer: D) -> Result<bool, D::Error>
+where
+    D: serde::Deserializer<'de>,
+{
+    Ok(Option::<bool>::deserialize(deserializer)?.unwrap_or_default())
 }
 
 impl<T> PaginatedList<T> {
@@ -40,6 +166,24 @@ impl<T> PaginatedList<T> {
         &self.data
     }
 
+    /// Iterates over the items in this page
+    pub fn iter(&self) -> std::slice::Iter<'_, T> {
+        self.data.iter()
+    }
+
+    /// Converts every item with `f`, keeping the page's metadata
+    #[must_use]
+    pub fn map<U>(self, f: impl FnMut(T) -> U) -> PaginatedList<U> {
+        PaginatedList {
+            data: self.data.into_iter().map(f).collect(),
+            object: self.object,
+            has_more: self.has_more,
+            next_cursor: self.next_cursor,
+            first_id: self.first_id,
+            last_id: self.last_id,
+        }
+    }
+
     /// Returns the number of items in this page
     #[must_use]
     pub fn len(&self) -> usize {
@@ -51,4 +195,54 @@ impl<T> PaginatedList<T> {
     pub fn is_empty(&self) -> bool {
         self.data.is_empty()
     }
+
+    /// Returns the cursor for the next page: `last_id`, falling back to `next_cursor`
+    #[must_use]
+    pub fn last_id(&self) -> Option<&str> {
+        self.last_id.as_deref().or(self.next_cursor.as_deref())
+    }
+
+    /// Returns the parameters for the next page, or `None` if this is the last one.
+    ///
+    /// Keeps `limit` and `order` from `base` and continues after this page's last item.
+    ///
+    /// ```rust,ignore
+    /// let mut params = PaginationParams::new().limit(100);
+    /// loop {
+    ///     let page = client.files.list(Some(params.clone())).await?;
+    ///     // ... use page.data ...
+    ///     let Some(next) = page.next_params(&params) else { break };
+    ///     params = next;
+    /// }
+    /// ```
+    #[must_use]
+    pub fn next_params(&self, base: &PaginationParams) -> Option<PaginationParams> {
+        if !self.has_more {
+            return None;
+        }
+        let last_id = self.last_id()?;
+        Some(PaginationParams {
+            after: Some(last_id.to_string()),
+            before: None,
+            ..base.clone()
+        })
+    }
+}
+
+impl<T> IntoIterator for PaginatedList<T> {
+    type Item = T;
+    type IntoIter = std::vec::IntoIter<T>;
+
+    fn into_iter(self) -> Self::IntoIter {
+        self.data.into_iter()
+    }
+}
+
+impl<'a, T> IntoIterator for &'a PaginatedList<T> {
+    type Item = &'a T;
+    type IntoIter = std::slice::Iter<'a, T>;
+
+    fn into_iter(self) -> Self::IntoIter {
+        self.data.iter()
+    }
 }
@@ -0,0 +1,238 @@
+use crate::types::{Input, InputItem, ValidationError};
+
+/// Default limit on the decoded size of an inline (base64) image, in bytes
+pub const IMAGE_MAX_BYTES: usize = 20 * 1024 * 1024;
+
+/// Decoded size of base64 `data`, computed from its length without decoding it
+fn base64_decoded_len(data: &str) -> usize {
+    let data = data.trim_end();
+    let padding = data.bytes().rev().take_while(|&byte| byte == b'=').count();
+    (data.len() - padding) * 3 / 4
+}
+
+/// Decoded size of the payload of a `data:<mime>;base64,<data>` URL
+fn data_url_decoded_len(url: &str) -> Option<usize> {
+    let (header, data) = url.strip_prefix("data:")?.split_once(',')?;
+    header
+        .ends_with(";base64")
+        .then(|| base64_decoded_len(data))
+}
+
+fn check_image_size(bytes: usize, max: usize) -> Result<(), ValidationError> {
+    if bytes > max {
+        return Err(ValidationError::ImageTooLarge { bytes, max });
+    }
+    Ok(())
+}
+
+impl InputItem {
+    /// Creates an image input from base64 data, rejecting images that decode to more
+    /// than `max_bytes` (see [`IMAGE_MAX_BYTES`])
+    ///
+    /// # Errors
+    ///
+    /// Returns [`ValidationError::ImageTooLarge`] if the image is over the limit.
+    pub fn try_image_base64(
+        base64_data: impl Into<String>,
+        mime_type: impl Into<String>,
+        max_bytes: usize,
+    ) -> Result<Self, ValidationError> {
+        Self::try_image_base64_with_detail(base64_data, mime_type, "auto", max_bytes)
+    }
+
+    /// Creates an image input from base64 data with custom detail level, rejecting
+    /// images that decode to more than `max_bytes`
+    ///
+    /// # Errors
+    ///
+    /// Returns [`ValidationError::ImageTooLarge`] if the image is over the limit.
+    pub fn try_image_base64_with_detail(
+        base64_data: impl Into<String>,
+        mime_type: impl Into<String>,
+        detail: impl Into<String>,
+        max_bytes: usize,
+    ) -> Result<Self, ValidationError> {
+        let base64_data = base64_data.into();
+        check_image_size(base64_decoded_len(&base64_data), max_bytes)?;
+        Ok(Self::image_base64_with_detail(
+            base64_data,
+            mime_type,
+            detail,
+        ))
+    }
+
+    /// Decoded size of this item's inline image, or `None` if it is not a base64 image
+    #[must_use]
+    pub fn inline_image_bytes(&self) -> Option<usize> {
+        if self.item_type != "input_image" {
+            return None;
+        }
+        self.image_url.as_deref().and_then(data_url_decoded_len)
+    }
+
+    /// Decodes an image, downscales it so neither side exceeds `max_dim` (keeping the
+    /// aspect ratio) and builds an image input from the result.
+    ///
+    /// Images with transparency are re-encoded as PNG, everything else as JPEG. Images
+    /// already within `max_dim` are re-encoded at their original size.
+    ///
+    /// # Errors
+    ///
+    /// Returns [`Error::Decode`](crate::Error::Decode) if `bytes` is not a supported
+    /// image, or [`Error::Validation`](crate::Error::Validation) if the re-encoded image
+    /// is still larger than [`IMAGE_MAX_BYTES`].
+    #[cfg(feature = "image")]
+    pub fn image_from_bytes_resized(
+        bytes: &[u8],
+        max_dim: u32,
+        detail: impl Into<String>,
+    ) -> crate::Result<Self> {
+        use base64::Engine as _;
+
+        let image = image::load_from_memory(bytes)
+            .map_err(|error| crate::Error::Decode(error.to_string()))?;
+        let image = if image.width() > max_dim || image.height() > max_dim {
+            image.resize(max_dim, max_dim, image::imageops::FilterType::Lanczos3)
+        } else {
+            image
+        };
+
+        let mut encoded = std::io::Cursor::new(Vec::new());
+        let (result, mime_type) = if image.color().has_alpha() {
+            (
+                image.write_to(&mut encoded, image::ImageFormat::Png),
+                "image/png",
+            )
+        } else {
+            (
+                image::DynamicImage::ImageRgb8(image.to_rgb8())
+                    .write_to(&mut encoded, image::ImageFormat::Jpeg),
+                "image/jpeg",
+            )
+        };
+        result.map_err(|error| crate::Error::Decode(error.to_string()))?;
+
+        let data = base64::engine::general_purpose::STANDARD.encode(encoded.into_inner());
+        Ok(Self::try_image_base64_with_detail(
+            data,
+            mime_type,
+            detail,
+            IMAGE_MAX_BYTES,
+        )?)
+    }
+}
+
+impl Input {
+    /// Checks every inline image, top-level or inside message content, against `max_bytes`
+    pub(crate) fn check_image_sizes(&self, max_bytes: usize) -> Result<(), ValidationError> {
+        let Self::Items(items) = self else {
+            return Ok(());
+        };
+        for item in items {
+            if let Some(bytes) = item.inline_image_bytes() {
+                check_image_size(bytes, max_bytes)?;
+            }
+            let Some(serde_json::Value::Array(parts)) = &item.content else {
+                continue;
+            };
+            for part in parts {
+                if part.get("type").and_then(serde_json::Value::as_str) != Some("input_image") {
+                    continue;
+                }
+                if let Some(bytes) = part
+                    .get("image_url")
+                    .and_then(serde_json::Value::as_str)
+                    .and_then(data_url_decoded_len)
+                {
+                    check_image_size(bytes, max_bytes)?;
+                }
+            }
+        }
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn base64_images_over_the_limit_are_rejected() {
+        // 8 base64 characters decode to 6 bytes; with padding, to fewer
+        assert_eq!(base64_decoded_len("QUJDREVG"), 6);
+        assert_eq!(base64_decoded_len("QUJDRA=="), 4);
+
+        let item = InputItem::try_image_base64("QUJDREVG", "image/png", 6).unwrap();
+        assert_eq!(item.inline_image_bytes(), Some(6));
+        assert_eq!(
+            InputItem::try_image_base64("QUJDREVG", "image/png", 5).unwrap_err(),
+            ValidationError::ImageTooLarge { bytes: 6, max: 5 }
+        );
+        assert_eq!(
+            InputItem::image_url("https://example.com/a.png").inline_image_bytes(),
+            None
+        );
+
+        let error = crate::Request::builder()
+            .model("gpt-4o")
+            .input("Describe this")
+            .push_image_url("data:image/png;base64,QUJDREVG")
+            .max_image_bytes(5)
+            .build_validated()
+            .unwrap_err();
+        assert!(matches!(
+            error,
+            crate::Error::Validation(ValidationError::ImageTooLarge { bytes: 6, max: 5 })
+        ));
+
+        let request = crate::Request::builder()
+            .model("gpt-4o")
+            .input_items(vec![InputItem::image_base64("QUJDREVG", "image/png")])
+            .build_validated();
+        assert!(request.is_ok());
+    }
+
+    #[cfg(feature = "image")]
+    #[test]
+    fn resized_images_fit_within_max_dim() {
+        use base64::Engine as _;
+
+        let decode = |item: &InputItem| {
+            let url = item.image_url.as_deref().unwrap();
+            let (header, data) = url.split_once(',').unwrap();
+            let bytes = base64::engine::general_purpose::STANDARD
+                .decode(data)
+                .unwrap();
+            (header.to_string(), image::load_from_memory(&bytes).unwrap())
+        };
+        let encode = |image: image::DynamicImage, format| {
+            let mut bytes = std::io::Cursor::new(Vec::new());
+            image.write_to(&mut bytes, format).unwrap();
+            bytes.into_inner()
+        };
+
+        let photo = encode(
+            image::DynamicImage::new_rgb8(400, 200),
+            image::ImageFormat::Png,
+        );
+        let item = InputItem::image_from_bytes_resized(&photo, 100, "low").unwrap();
+        assert_eq!(item.detail.as_deref(), Some("low"));
+        let (header, resized) = decode(&item);
+        assert_eq!(header, "data:image/jpeg;base64");
+        assert_eq!((resized.width(), resized.height()), (100, 50));
+
+        let icon = encode(
+            image::DynamicImage::new_rgba8(30, 60),
+            image::ImageFormat::Png,
+        );
+        let item = InputItem::image_from_bytes_resized(&icon, 100, "auto").unwrap();
+        let (header, kept) = decode(&item);
+        assert_eq!(header, "data:image/png;base64");
+        assert_eq!((kept.width(), kept.height()), (30, 60));
+
+        assert!(matches!(
+            InputItem::image_from_bytes_resized(b"not an image", 100, "auto"),
+            Err(crate::Error::Decode(_))
+        ));
+    }
+}
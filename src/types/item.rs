@@ -11,8 +11,194 @@ pub enum Input {
     Items(Vec<InputItem>),
 }
 
+impl Input {
+    /// Drops the oldest items so that at most `max_items` remain, returning how many were dropped.
+    ///
+    /// Intended for callers that manage stateless histories themselves. Leading
+    /// `system`/`developer` messages are always kept and do not count towards the limit,
+    /// and function call outputs left at the start of the window without their call are
+    /// dropped too. Text input is left unchanged.
+    pub fn truncate_to_items(&mut self, max_items: usize) -> usize {
+        let Self::Items(items) = self else {
+            return 0;
+        };
+
+        let pinned = items
+            .iter()
+            .take_while(|item| {
+                item.item_type == "message"
+                    && matches!(item.role.as_deref(), Some("system" | "developer"))
+            })
+            .count();
+        let history = items.len() - pinned;
+        if history <= max_items {
+            return 0;
+        }
+
+        let mut start = pinned + (history - max_items);
+        while start < items.len() && items[start].item_type == "function_call_output" {
+            start += 1;
+        }
+
+        let dropped = start - pinned;
+        items.drain(pinned..start);
+        dropped
+    }
+}
+
+/// How [`Input::from_responses_with`] treats items that cannot be replayed as-is
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct ReplayOptions {
+    /// Keep reasoning items that carry encrypted content; others are always dropped
+    pub keep_encrypted_reasoning: bool,
+
+    /// Send generated images back as `input_image` parts of a user message instead of
+    /// dropping them
+    pub images_as_input: bool,
+}
+
+impl ReplayOptions {
+    /// Creates options that drop reasoning and generated images
+    #[must_use]
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Keeps reasoning items that carry encrypted content
+    #[must_use]
+    pub fn with_encrypted_reasoning(mut self, keep: bool) -> Self {
+        self.keep_encrypted_reasoning = keep;
+        self
+    }
+
+    /// Sends generated images back as image inputs
+    #[must_use]
+    pub fn with_images_as_input(mut self, enabled: bool) -> Self {
+        self.images_as_input = enabled;
+        self
+    }
+}
+
+impl Input {
+    /// Rebuilds the output of a chain of responses, oldest first, as input items for a
+    /// stateless request, with the default [`ReplayOptions`].
+    ///
+    /// See [`Input::from_responses_with`].
+    #[must_use]
+    pub fn from_responses(history: &[crate::types::Response]) -> Self {
+        Self::from_responses_with(history, ReplayOptions::default())
+    }
+
+    /// Rebuilds the output of a chain of responses, oldest first, as input items.
+    ///
+    /// Messages become assistant messages, and function calls, custom tool calls and
+    /// stored function call outputs keep their call IDs, all in output order.
+    /// Server-side tool calls (web search, file search, code interpreter) are dropped;
+    /// what they found is in the messages that follow them. Responses only hold what the
+    /// model produced, so the user turns that led to them are not included; list those
+    /// with [`Responses::list_input_items`](crate::responses::Responses::list_input_items).
+    #[must_use]
+    pub fn from_responses_with(history: &[crate::types::Response], options: ReplayOptions) -> Self {
+        let items = history
+            .iter()
+            .flat_map(|response| &response.output)
+            .filter_map(|item| Self::replay_item(item, options))
+            .collect();
+        Self::Items(items)
+    }
+
+    fn replay_item(item: &ResponseItem, options: ReplayOptions) -> Option<InputItem> {
+        match item {
+            ResponseItem::Message { content, role, .. } => {
+                let parts: Vec<serde_json::Value> = content
+                    .iter()
+                    .filter_map(|part| match part {
+                        MessageContent::OutputText { text, .. } => {
+                            Some(serde_json::json!({"type": "output_text", "text": text}))
+                        }
+                        MessageContent::Refusal { refusal } => {
+                            Some(serde_json::json!({"type": "refusal", "refusal": refusal}))
+                        }
+                        MessageContent::OutputAudio { transcript, .. } => {
+                            let text = transcript.as_ref()?;
+                            Some(serde_json::json!({"type": "output_text", "text": text}))
+                        }
+                        MessageContent::InputText { .. }
+                        | MessageContent::InputImage { .. }
+                        | MessageContent::InputFile { .. } => serde_json::to_value(part).ok(),
+                    })
+                    .collect();
+                (!parts.is_empty()).then(|| InputItem::message(role.clone(), parts))
+            }
+            ResponseItem::Text { content, .. } => Some(InputItem::message(
+                "assistant",
+                vec![serde_json::json!({"type": "output_text", "text": content})],
+            )),
+            ResponseItem::FunctionCall {
+                call_id,
+                name,
+                arguments,
+                ..
+            } => Some(InputItem::function_call(call_id, name, arguments)),
+            ResponseItem::CustomToolCall {
+                call_id,
+                name,
+                input,
+                ..
+            } => Some(InputItem::custom_tool_call(call_id, name, input)),
+            ResponseItem::FunctionCallOutput {
+                call_id, output, ..
+            } => Some(InputItem::function_call_output(call_id, output)),
+            ResponseItem::Reasoning {
+                id,
+                summary,
+                encrypted_content: Some(encrypted),
+                ..
+            } if options.keep_encrypted_reasoning => {
+                Some(InputItem::reasoning(id, summary.clone(), encrypted))
+            }
+            ResponseItem::ImageGenerationCall {
+                result,
+                output_format,
+                ..
+            } if options.images_as_input && !result.is_empty() => Some(InputItem::message(
+                "user",
+                vec![InputItem::content_image_base64(
+                    result,
+                    image_format(output_format.as_deref()).1,
+                )],
+            )),
+            ResponseItem::Reasoning { .. }
+            | ResponseItem::ImageGenerationCall { .. }
+            | ResponseItem::WebSearchCall { .. }
+            | ResponseItem::FileSearchCall { .. }
+            | ResponseItem::CodeInterpreterCall { .. }
+            | ResponseItem::ToolCall(_)
+            | ResponseItem::Unknown(_) => None,
+        }
+    }
+}
+
+impl From<String> for Input {
+    fn from(text: String) -> Self {
+        Self::Text(text)
+    }
+}
+
+impl From<&str> for Input {
+    fn from(text: &str) -> Self {
+        Self::Text(text.to_string())
+    }
+}
+
+impl From<Vec<InputItem>> for Input {
+    fn from(items: Vec<InputItem>) -> Self {
+        Self::Items(items)
+    }
+}
+
 /// Input item for the API request
-#[derive(Debug, Clone, Serialize, Deserialize)]
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
 pub struct InputItem {
     /// Type of the input item
     #[serde(rename = "type")]
@@ -45,6 +231,30 @@ pub struct InputItem {
     /// Text for input_text type  
     #[serde(skip_serializing_if = "Option::is_none")]
     pub text: Option<String>,
+
+    /// ID of a replayed output item (reasoning)
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub id: Option<String>,
+
+    /// Tool name for function and custom tool calls
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub name: Option<String>,
+
+    /// JSON arguments for function calls
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub arguments: Option<String>,
+
+    /// Free-form input for custom tool calls
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub input: Option<String>,
+
+    /// Summary parts for reasoning items
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub summary: Option<Vec<serde_json::Value>>,
+
+    /// Encrypted reasoning content for reasoning items
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub encrypted_content: Option<String>,
 }
 
 impl InputItem {
@@ -59,10 +269,18 @@ impl InputItem {
             detail: None,
             role: None,
             text: None,
+            ..Self::default()
         }
     }
 
-    /// Creates a function call output input item for submitting tool results
+    /// Creates a function call output input item for submitting tool results, sent as
+    /// `{"type": "function_call_output", "call_id": ..., "output": ...}`.
+    ///
+    /// `call_id` is the call's `call_id`, not its item `id`. In a stateless request the
+    /// output must follow the [`function_call`](Self::function_call) item with the same
+    /// `call_id`; when continuing with `previous_response_id`, the call is already in the
+    /// stored response and only the output is sent, as
+    /// [`RequestBuilder::with_function_outputs`](crate::types::RequestBuilder::with_function_outputs) does.
     pub fn function_call_output(call_id: impl Into<String>, output: impl Into<String>) -> Self {
         Self {
             item_type: "function_call_output".to_string(),
@@ -73,6 +291,72 @@ impl InputItem {
             detail: None,
             role: None,
             text: None,
+            ..Self::default()
+        }
+    }
+
+    /// Creates a custom tool call output input item for answering a
+    /// [`ResponseItem::CustomToolCall`]
+    pub fn custom_tool_output(call_id: impl Into<String>, output: impl Into<String>) -> Self {
+        Self {
+            item_type: "custom_tool_call_output".to_string(),
+            content: None,
+            call_id: Some(call_id.into()),
+            output: Some(output.into()),
+            image_url: None,
+            detail: None,
+            role: None,
+            text: None,
+            ..Self::default()
+        }
+    }
+
+    /// Creates a function call input item, replaying a call the model made, sent as
+    /// `{"type": "function_call", "call_id": ..., "name": ..., "arguments": ...}`.
+    ///
+    /// Place it before the [`function_call_output`](Self::function_call_output) that
+    /// answers it; the API rejects an output whose call it has not seen.
+    pub fn function_call(
+        call_id: impl Into<String>,
+        name: impl Into<String>,
+        arguments: impl Into<String>,
+    ) -> Self {
+        Self {
+            item_type: "function_call".to_string(),
+            call_id: Some(call_id.into()),
+            name: Some(name.into()),
+            arguments: Some(arguments.into()),
+            ..Self::default()
+        }
+    }
+
+    /// Creates a custom tool call input item, replaying a call the model made
+    pub fn custom_tool_call(
+        call_id: impl Into<String>,
+        name: impl Into<String>,
+        input: impl Into<String>,
+    ) -> Self {
+        Self {
+            item_type: "custom_tool_call".to_string(),
+            call_id: Some(call_id.into()),
+            name: Some(name.into()),
+            input: Some(input.into()),
+            ..Self::default()
+        }
+    }
+
+    /// Creates a reasoning input item from a reasoning output with encrypted content
+    pub fn reasoning(
+        id: impl Into<String>,
+        summary: Vec<serde_json::Value>,
+        encrypted_content: impl Into<String>,
+    ) -> Self {
+        Self {
+            item_type: "reasoning".to_string(),
+            id: Some(id.into()),
+            summary: Some(summary),
+            encrypted_content: Some(encrypted_content.into()),
+            ..Self::default()
         }
     }
 
@@ -87,6 +371,7 @@ impl InputItem {
             detail: Some("auto".to_string()),
             role: None,
             text: None,
+            ..Self::default()
         }
     }
 
@@ -101,6 +386,7 @@ impl InputItem {
             detail: Some(detail.into()),
             role: None,
             text: None,
+            ..Self::default()
         }
     }
 
@@ -116,6 +402,7 @@ impl InputItem {
             detail: Some("auto".to_string()),
             role: None,
             text: None,
+            ..Self::default()
         }
     }
 
@@ -135,6 +422,7 @@ impl InputItem {
             detail: Some(detail.into()),
             role: None,
             text: None,
+            ..Self::default()
         }
     }
 
@@ -149,6 +437,7 @@ impl InputItem {
             detail: Some("auto".to_string()),
             role: None,
             text: Some(file_id.into()), // File ID goes in the text field
+            ..Self::default()
         }
     }
 
@@ -166,6 +455,7 @@ impl InputItem {
             detail: Some(detail.into()),
             role: None,
             text: Some(file_id.into()), // File ID goes in the text field
+            ..Self::default()
         }
     }
 
@@ -180,6 +470,7 @@ impl InputItem {
             detail: None,
             role: Some(role.into()),
             text: None,
+            ..Self::default()
         }
     }
 
@@ -249,6 +540,20 @@ impl InputItem {
         })
     }
 
+    /// Creates a content item for input_audio from base64 data (used inside message content)
+    pub fn content_audio_base64(
+        base64_data: impl Into<String>,
+        format: AudioFormat,
+    ) -> serde_json::Value {
+        serde_json::json!({
+            "type": "input_audio",
+            "input_audio": {
+                "data": base64_data.into(),
+                "format": format
+            }
+        })
+    }
+
     /// Creates a content item for input_text (used inside message content)  
     pub fn content_text(text: impl Into<String>) -> serde_json::Value {
         serde_json::json!({
@@ -258,8 +563,35 @@ impl InputItem {
     }
 }
 
+/// Encoding of audio sent as `input_audio`
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum AudioFormat {
+    /// WAV audio
+    Wav,
+    /// MP3 audio
+    Mp3,
+}
+
+impl AudioFormat {
+    /// Returns the format as sent to the API
+    #[must_use]
+    pub fn as_str(self) -> &'static str {
+        match self {
+            Self::Wav => "wav",
+            Self::Mp3 => "mp3",
+        }
+    }
+}
+
+impl std::fmt::Display for AudioFormat {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.write_str(self.as_str())
+    }
+}
+
 /// Response item from the OpenAI Responses API
-#[derive(Debug, Clone, Deserialize, Serialize)]
+#[derive(Debug, Clone, PartialEq, Deserialize, Serialize)]
 #[serde(tag = "type", rename_all = "snake_case")]
 pub enum ResponseItem {
     /// Message response
@@ -274,6 +606,7 @@ pub enum ResponseItem {
         role: String,
 
         /// Status of the message
+        #[serde(default, deserialize_with = "crate::types::lenient::optional_status")]
         status: Option<String>,
     },
 
@@ -285,7 +618,13 @@ pub enum ResponseItem {
         /// Summary of the reasoning
         summary: Vec<serde_json::Value>,
 
+        /// Encrypted reasoning, when requested with
+        /// [`Include::ReasoningEncryptedContent`](crate::types::Include::ReasoningEncryptedContent)
+        #[serde(default, skip_serializing_if = "Option::is_none")]
+        encrypted_content: Option<String>,
+
         /// Status of the reasoning
+        #[serde(default, deserialize_with = "crate::types::lenient::optional_status")]
         status: Option<String>,
     },
 
@@ -295,7 +634,16 @@ pub enum ResponseItem {
         id: String,
 
         /// Status of the web search call
+        #[serde(deserialize_with = "crate::types::lenient::status")]
         status: String,
+
+        /// What the search did; absent in older payloads
+        #[serde(default, skip_serializing_if = "Option::is_none")]
+        action: Option<WebSearchAction>,
+
+        /// Sources found, when requested with [`Include::WebSearchResults`](crate::types::Include::WebSearchResults)
+        #[serde(default, skip_serializing_if = "Option::is_none")]
+        results: Option<Vec<WebSearchSource>>,
     },
 
     /// File search call
@@ -304,6 +652,7 @@ pub enum ResponseItem {
         id: String,
 
         /// Status of the file search call
+        #[serde(deserialize_with = "crate::types::lenient::status")]
         status: String,
     },
 
@@ -312,11 +661,33 @@ pub enum ResponseItem {
         /// ID of the image generation call
         id: String,
 
-        /// Base64-encoded image result
+        /// Base64-encoded image result; empty after [`Response::from_slice_lite`](crate::Response::from_slice_lite)
+        #[serde(deserialize_with = "crate::types::lite::image_result")]
         result: String,
 
         /// Status of the call
+        #[serde(deserialize_with = "crate::types::lenient::status")]
         status: String,
+
+        /// Prompt the model actually generated the image from
+        #[serde(default, skip_serializing_if = "Option::is_none")]
+        revised_prompt: Option<String>,
+
+        /// Image format (`png`, `webp` or `jpeg`)
+        #[serde(default, skip_serializing_if = "Option::is_none")]
+        output_format: Option<String>,
+
+        /// Image size, e.g. `1024x1024`
+        #[serde(default, skip_serializing_if = "Option::is_none")]
+        size: Option<String>,
+
+        /// Rendering quality (`low`, `medium` or `high`)
+        #[serde(default, skip_serializing_if = "Option::is_none")]
+        quality: Option<String>,
+
+        /// Background (`transparent` or `opaque`)
+        #[serde(default, skip_serializing_if = "Option::is_none")]
+        background: Option<String>,
     },
 
     /// Code interpreter call from the model
@@ -328,6 +699,7 @@ pub enum ResponseItem {
         container_id: String,
 
         /// Status of the call
+        #[serde(deserialize_with = "crate::types::lenient::status")]
         status: String,
     },
 
@@ -346,7 +718,51 @@ pub enum ResponseItem {
         name: String,
 
         /// Status of the function call
-        status: String,
+        status: ItemStatus,
+    },
+
+    /// Output submitted for a function call, as stored on the response
+    FunctionCallOutput {
+        /// ID of the output item
+        #[serde(default, skip_serializing_if = "Option::is_none")]
+        id: Option<String>,
+
+        /// Call ID of the function call this output answers
+        call_id: String,
+
+        /// Output returned by the function
+        output: String,
+
+        /// Status of the output item
+        #[serde(
+            default,
+            skip_serializing_if = "Option::is_none",
+            deserialize_with = "crate::types::lenient::optional_status"
+        )]
+        status: Option<String>,
+    },
+
+    /// Call of a custom tool, with free-form text input
+    CustomToolCall {
+        /// ID of the custom tool call item
+        id: String,
+
+        /// Call ID, used to link the call to its `custom_tool_call_output`
+        call_id: String,
+
+        /// Name of the custom tool
+        name: String,
+
+        /// Free-form input generated by the model
+        input: String,
+
+        /// Status of the call
+        #[serde(
+            default,
+            skip_serializing_if = "Option::is_none",
+            deserialize_with = "crate::types::lenient::optional_status"
+        )]
+        status: Option<String>,
     },
 
     /// Text response (legacy)
@@ -360,11 +776,125 @@ pub enum ResponseItem {
 
     /// Tool call response (legacy)
     #[serde(rename = "tool_call")]
-    ToolCall(ToolCall),
+    ToolCall(LegacyToolCall),
+
+    /// An item type this version of the crate does not model, such as `mcp_call` or
+    /// `computer_call`, kept as the server sent it so it round-trips unchanged
+    #[serde(untagged, deserialize_with = "unknown_item")]
+    Unknown(serde_json::Value),
+}
+
+/// `type` tags of the modelled [`ResponseItem`] variants
+const ITEM_TYPES: [&str; 11] = [
+    "message",
+    "reasoning",
+    "web_search_call",
+    "file_search_call",
+    "image_generation_call",
+    "code_interpreter_call",
+    "function_call",
+    "function_call_output",
+    "custom_tool_call",
+    "text",
+    "tool_call",
+];
+
+/// [`ResponseItem::Unknown`], which serde also tries when a modelled item fails to
+/// parse; such an item stays an error instead of being passed off as unknown
+fn unknown_item<'de, D>(deserializer: D) -> Result<serde_json::Value, D::Error>
+where
+    D: serde::Deserializer<'de>,
+{
+    use serde::de::Error as _;
+
+    let item = serde_json::Value::deserialize(deserializer)?;
+    match item.get("type").and_then(serde_json::Value::as_str) {
+        Some(item_type) if ITEM_TYPES.contains(&item_type) => {
+            Err(D::Error::custom(format!("invalid `{item_type}` item")))
+        }
+        _ => Ok(item),
+    }
+}
+
+impl ResponseItem {
+    /// Returns the item's `type` as sent on the wire, e.g. `"function_call"`
+    #[must_use]
+    pub fn item_type(&self) -> &str {
+        match self {
+            Self::Message { .. } => "message",
+            Self::Reasoning { .. } => "reasoning",
+            Self::WebSearchCall { .. } => "web_search_call",
+            Self::FileSearchCall { .. } => "file_search_call",
+            Self::ImageGenerationCall { .. } => "image_generation_call",
+            Self::CodeInterpreterCall { .. } => "code_interpreter_call",
+            Self::FunctionCall { .. } => "function_call",
+            Self::FunctionCallOutput { .. } => "function_call_output",
+            Self::CustomToolCall { .. } => "custom_tool_call",
+            Self::Text { .. } => "text",
+            Self::ToolCall(_) => "tool_call",
+            Self::Unknown(item) => item
+                .get("type")
+                .and_then(serde_json::Value::as_str)
+                .unwrap_or("unknown"),
+        }
+    }
+}
+
+/// Action taken by a web search call
+#[derive(Debug, Clone, PartialEq, Eq, Deserialize, Serialize)]
+#[serde(tag = "type", rename_all = "snake_case")]
+pub enum WebSearchAction {
+    /// Searched the web
+    Search {
+        /// Search query
+        #[serde(default)]
+        query: String,
+
+        /// Sources consulted, when requested with the `web_search_call.action.sources` include
+        #[serde(default, skip_serializing_if = "Option::is_none")]
+        sources: Option<Vec<WebSearchSource>>,
+    },
+
+    /// Opened a page
+    OpenPage {
+        /// URL of the page
+        #[serde(default)]
+        url: String,
+    },
+
+    /// Searched within a page
+    FindInPage {
+        /// URL of the page
+        #[serde(default)]
+        url: String,
+
+        /// Text searched for
+        #[serde(default)]
+        pattern: String,
+    },
+
+    /// An action type this version of the crate does not know
+    #[serde(other)]
+    Unknown,
+}
+
+/// Web page found by a web search call
+#[derive(Debug, Clone, PartialEq, Eq, Deserialize, Serialize)]
+pub struct WebSearchSource {
+    /// URL of the page
+    pub url: String,
+
+    /// Title of the page
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub title: Option<String>,
+
+    /// Excerpt of the page relevant to the query
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub snippet: Option<String>,
 }
 
 /// Message content item
-#[derive(Debug, Clone, Deserialize, Serialize)]
+#[derive(Debug, Clone, PartialEq, Deserialize, Serialize)]
 #[serde(tag = "type", rename_all = "snake_case")]
 pub enum MessageContent {
     /// Output text content
@@ -378,11 +908,185 @@ pub enum MessageContent {
         /// Log probabilities
         logprobs: Option<serde_json::Value>,
     },
+
+    /// Refusal content returned when the model declines to answer
+    Refusal {
+        /// Refusal explanation from the model
+        refusal: String,
+    },
+
+    /// Audio generated by an audio-capable model
+    OutputAudio {
+        /// Base64-encoded audio
+        data: String,
+
+        /// Text of what the audio says, if the model returned one
+        #[serde(default, skip_serializing_if = "Option::is_none")]
+        transcript: Option<String>,
+    },
+
+    /// Text sent as input, as listed by
+    /// [`Responses::list_input_items`](crate::responses::Responses::list_input_items)
+    InputText {
+        /// Text content
+        text: String,
+    },
+
+    /// Image sent as input
+    InputImage {
+        /// URL or data URL of the image; listed only with
+        /// [`Include::MessageInputImageUrl`](crate::types::Include::MessageInputImageUrl)
+        #[serde(default, skip_serializing_if = "Option::is_none")]
+        image_url: Option<String>,
+
+        /// ID of an uploaded image file
+        #[serde(default, skip_serializing_if = "Option::is_none")]
+        file_id: Option<String>,
+
+        /// Detail level (`low`, `high` or `auto`)
+        #[serde(default, skip_serializing_if = "Option::is_none")]
+        detail: Option<String>,
+    },
+
+    /// File sent as input
+    InputFile {
+        /// ID of an uploaded file
+        #[serde(default, skip_serializing_if = "Option::is_none")]
+        file_id: Option<String>,
+
+        /// Name of the file
+        #[serde(default, skip_serializing_if = "Option::is_none")]
+        filename: Option<String>,
+    },
+}
+
+/// Audio part of a response, as returned by [`Response::audio_outputs`](crate::Response::audio_outputs)
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct AudioOutput<'a> {
+    /// Base64-encoded audio
+    pub data: &'a str,
+
+    /// Text of what the audio says, if the model returned one
+    pub transcript: Option<&'a str>,
+}
+
+impl AudioOutput<'_> {
+    /// Decodes the audio into raw bytes.
+    ///
+    /// # Errors
+    ///
+    /// Returns [`Error::Decode`](crate::Error::Decode) if `data` is not valid base64.
+    pub fn to_bytes(&self) -> crate::Result<Vec<u8>> {
+        use base64::Engine as _;
+        base64::engine::general_purpose::STANDARD
+            .decode(self.data)
+            .map_err(|error| crate::Error::Decode(error.to_string()))
+    }
+}
+
+/// Image from the image generation tool, as returned by
+/// [`Response::image_generations`](crate::Response::image_generations)
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct ImageGeneration<'a> {
+    /// ID of the image generation call
+    pub id: &'a str,
+
+    /// Base64-encoded image; empty after [`Response::from_slice_lite`](crate::Response::from_slice_lite)
+    pub result: &'a str,
+
+    /// Status of the call
+    pub status: &'a str,
+
+    /// Prompt the model actually generated the image from
+    pub revised_prompt: Option<&'a str>,
+
+    /// Image format (`png`, `webp` or `jpeg`)
+    pub output_format: Option<&'a str>,
+
+    /// Image size, e.g. `1024x1024`
+    pub size: Option<&'a str>,
+
+    /// Rendering quality
+    pub quality: Option<&'a str>,
+
+    /// Background
+    pub background: Option<&'a str>,
+}
+
+/// File extension and MIME type for an `output_format`; the API defaults to PNG
+fn image_format(output_format: Option<&str>) -> (&'static str, &'static str) {
+    match output_format.map(str::to_ascii_lowercase).as_deref() {
+        Some("webp") => ("webp", "image/webp"),
+        Some("jpeg" | "jpg") => ("jpg", "image/jpeg"),
+        _ => ("png", "image/png"),
+    }
+}
+
+impl ImageGeneration<'_> {
+    /// File extension for the image's `output_format`: `png`, `webp` or `jpg`
+    #[must_use]
+    pub fn extension(&self) -> &'static str {
+        image_format(self.output_format).0
+    }
+
+    /// MIME type for the image's `output_format`
+    #[must_use]
+    pub fn mime_type(&self) -> &'static str {
+        image_format(self.output_format).1
+    }
+
+    /// Decodes the image into raw bytes.
+    ///
+    /// # Errors
+    ///
+    /// Returns [`Error::Decode`](crate::Error::Decode) if `result` is empty or not valid base64.
+    pub fn to_bytes(&self) -> crate::Result<Vec<u8>> {
+        use base64::Engine as _;
+        if self.result.is_empty() {
+            return Err(crate::Error::Decode(format!(
+                "image generation call {} has no image data",
+                self.id
+            )));
+        }
+        base64::engine::general_purpose::STANDARD
+            .decode(self.result)
+            .map_err(|error| crate::Error::Decode(error.to_string()))
+    }
+
+    /// Writes the image to `dir` as `<id>.<extension>` and returns its path.
+    ///
+    /// # Errors
+    ///
+    /// Returns [`Error::Decode`](crate::Error::Decode) as [`to_bytes`](Self::to_bytes) does,
+    /// or [`Error::Io`](crate::Error::Io) if the file cannot be written.
+    #[cfg(not(target_arch = "wasm32"))]
+    pub fn save_to(&self, dir: impl AsRef<std::path::Path>) -> crate::Result<std::path::PathBuf> {
+        let bytes = self.to_bytes()?;
+        // Keep the ID from escaping `dir`
+        let stem: String = self
+            .id
+            .chars()
+            .map(|c| {
+                if c.is_ascii_alphanumeric() || c == '-' || c == '_' {
+                    c
+                } else {
+                    '_'
+                }
+            })
+            .collect();
+        let path = dir.as_ref().join(format!("{stem}.{}", self.extension()));
+        std::fs::write(&path, bytes)?;
+        Ok(path)
+    }
 }
 
-/// Tool call from the OpenAI Responses API
-#[derive(Debug, Clone, Deserialize, Serialize)]
-pub struct ToolCall {
+/// Legacy `tool_call` output item, carried by [`ResponseItem::ToolCall`].
+///
+/// Older API versions emitted these instead of `function_call` items. Read calls through
+/// [`Response::tool_calls`](crate::Response::tool_calls), which returns both kinds as
+/// [`FunctionCallInfo`].
+#[derive(Debug, Clone, PartialEq, Deserialize, Serialize)]
+pub struct LegacyToolCall {
     /// ID of the tool call
     pub id: String,
 
@@ -397,7 +1101,7 @@ pub struct ToolCall {
 }
 
 /// Tool result for the OpenAI Responses API
-#[derive(Debug, Clone, Serialize, Deserialize)]
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
 pub struct ToolResult {
     /// ID of the tool call this result is for
     pub tool_call_id: String,
@@ -406,8 +1110,15 @@ pub struct ToolResult {
     pub result: serde_json::Value,
 }
 
-/// Function call information
-#[derive(Debug, Clone, Serialize, Deserialize)]
+/// Former name of [`LegacyToolCall`], kept so existing code still compiles
+#[deprecated(
+    note = "renamed to `LegacyToolCall`; read calls from a response as `FunctionCallInfo`"
+)]
+pub type ToolCall = LegacyToolCall;
+
+/// A function call the model asked for, as returned by
+/// [`Response::tool_calls`](crate::Response::tool_calls)
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
 pub struct FunctionCallInfo {
     /// Name of the function
     pub name: String,
@@ -415,6 +1126,140 @@ pub struct FunctionCallInfo {
     /// Arguments for the function call
     pub arguments: String,
 
-    /// Call ID
+    /// Call ID, used to link the call to its `function_call_output`
     pub call_id: String,
+
+    /// ID of the output item that carried the call, if known
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub id: Option<String>,
+
+    /// Status of the call; `None` for legacy `tool_call` items, which carry none
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub status: Option<ItemStatus>,
+}
+
+impl FunctionCallInfo {
+    /// Parses the arguments as JSON.
+    ///
+    /// # Errors
+    ///
+    /// Returns [`Error::InvalidToolArguments`](crate::Error::InvalidToolArguments) if the
+    /// arguments are not valid JSON, e.g. because the call was cut off mid-generation.
+    pub fn arguments_json(&self) -> crate::Result<serde_json::Value> {
+        serde_json::from_str(&self.arguments).map_err(|source| crate::Error::InvalidToolArguments {
+            name: self.name.clone(),
+            call_id: self.call_id.clone(),
+            source,
+        })
+    }
+
+    /// Returns true if the model finished generating the call.
+    ///
+    /// Legacy `tool_call` items have no status and count as completed.
+    #[must_use]
+    pub fn is_completed(&self) -> bool {
+        matches!(self.status, None | Some(ItemStatus::Completed))
+    }
+
+    /// Returns true if the call is still being generated, so its arguments may be partial
+    #[must_use]
+    pub fn is_in_progress(&self) -> bool {
+        self.status == Some(ItemStatus::InProgress)
+    }
+}
+
+/// Function call borrowed from a response, as returned by
+/// [`Response::tool_call_refs`](crate::Response::tool_call_refs)
+#[derive(Debug, Clone, PartialEq)]
+pub struct FunctionCallRef<'a> {
+    /// Name of the function
+    pub name: &'a str,
+
+    /// Arguments for the function call; only legacy `tool_call` items need an allocation
+    pub arguments: std::borrow::Cow<'a, str>,
+
+    /// Call ID, used to link the call to its `function_call_output`
+    pub call_id: &'a str,
+
+    /// ID of the output item that carried the call, if known
+    pub id: Option<&'a str>,
+
+    /// Status of the call, if known
+    pub status: Option<&'a ItemStatus>,
+}
+
+impl FunctionCallRef<'_> {
+    /// Copies the call into an owned [`FunctionCallInfo`]
+    #[must_use]
+    pub fn to_info(&self) -> FunctionCallInfo {
+        FunctionCallInfo {
+            name: self.name.to_string(),
+            arguments: self.arguments.clone().into_owned(),
+            call_id: self.call_id.to_string(),
+            id: self.id.map(ToString::to_string),
+            status: self.status.cloned(),
+        }
+    }
+}
+
+/// Lifecycle status of an output item
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum ItemStatus {
+    /// The item is still being generated
+    InProgress,
+    /// The item is complete
+    Completed,
+    /// The item was cut short (e.g. by the token limit)
+    Incomplete,
+    /// A status this crate does not recognize, kept as sent
+    Unknown(String),
+}
+
+impl Serialize for ItemStatus {
+    fn serialize<S>(&self, serializer: S) -> Result<S::Ok, S::Error>
+    where
+        S: serde::Serializer,
+    {
+        serializer.serialize_str(self.as_str())
+    }
+}
+
+impl<'de> Deserialize<'de> for ItemStatus {
+    fn deserialize<D>(deserializer: D) -> Result<Self, D::Error>
+    where
+        D: serde::Deserializer<'de>,
+    {
+        let status = crate::types::lenient::status(deserializer)?;
+        Ok(Self::parse(&status))
+    }
+}
+
+impl ItemStatus {
+    /// Parses a status string as sent by the API
+    #[must_use]
+    pub fn parse(status: &str) -> Self {
+        match status {
+            "in_progress" => Self::InProgress,
+            "completed" => Self::Completed,
+            "incomplete" => Self::Incomplete,
+            other => Self::Unknown(other.to_string()),
+        }
+    }
+
+    /// Returns the status as sent by the API
+    #[must_use]
+    pub fn as_str(&self) -> &str {
+        match self {
+            Self::InProgress => "in_progress",
+            Self::Completed => "completed",
+            Self::Incomplete => "incomplete",
+            Self::Unknown(status) => status,
+        }
+    }
+}
+
+impl std::fmt::Display for ItemStatus {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.write_str(self.as_str())
+    }
 }
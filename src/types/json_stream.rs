@@ -0,0 +1,285 @@
+//! Incremental parsing of structured (JSON mode) output while it streams.
+//!
+//! With a `json_schema` text format the text deltas are fragments of one JSON
+//! document. [`JsonStreamAccumulator`] collects them and can parse the document
+//! before it is complete by repairing the unfinished tail.
+//!
+//! ```rust
+//! use open_ai_rust_responses_by_sshift::types::JsonStreamAccumulator;
+//! use serde::Deserialize;
+//!
+//! #[derive(Deserialize)]
+//! struct Answer {
+//!     title: String,
+//!     #[serde(default)]
+//!     steps: Vec<String>,
+//! }
+//!
+//! let mut json = JsonStreamAccumulator::new();
+//! json.push_str(r#"{"title": "Boil water", "steps": ["Fill the ke"#);
+//!
+//! let partial: Answer = json.try_partial().unwrap();
+//! assert_eq!(partial.title, "Boil water");
+//! assert_eq!(partial.steps, ["Fill the ke"]);
+//!
+//! json.push_str(r#"ttle"]}"#);
+//! let answer: Answer = json.finish().unwrap();
+//! assert_eq!(answer.steps, ["Fill the kettle"]);
+//! ```
+
+use crate::types::StreamEvent;
+use serde::de::DeserializeOwned;
+
+/// Accumulates streamed JSON text and parses it, partially or in full.
+#[derive(Debug, Clone, Default)]
+pub struct JsonStreamAccumulator {
+    buffer: String,
+}
+
+impl JsonStreamAccumulator {
+    /// Creates an empty accumulator
+    #[must_use]
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Appends the text of a [`StreamEvent::TextDelta`]; other events are ignored.
+    ///
+    /// Returns true if the event was a text delta.
+    pub fn push_event(&mut self, event: &StreamEvent) -> bool {
+        match event.as_text_delta() {
+            Some(delta) => {
+                self.buffer.push_str(delta);
+                true
+            }
+            None => false,
+        }
+    }
+
+    /// Appends raw JSON text
+    pub fn push_str(&mut self, delta: &str) {
+        self.buffer.push_str(delta);
+    }
+
+    /// Returns the text received so far
+    #[must_use]
+    pub fn as_str(&self) -> &str {
+        &self.buffer
+    }
+
+    /// Parses the text received so far, repairing an unfinished document.
+    ///
+    /// Open strings, arrays and objects are closed, and a trailing key or literal
+    /// that cannot be completed is dropped. The last string or number may therefore
+    /// be cut short (`"Ali"` for what will become `"Alice"`). Returns `None` if no
+    /// repair deserializes into `T`, e.g. because a required field has not arrived.
+    #[must_use]
+    pub fn try_partial<T: DeserializeOwned>(&self) -> Option<T> {
+        let value = self.partial_value()?;
+        serde_json::from_value(value).ok()
+    }
+
+    /// Like [`JsonStreamAccumulator::try_partial`], but returns the repaired JSON value
+    #[must_use]
+    pub fn partial_value(&self) -> Option<serde_json::Value> {
+        repair(&self.buffer)
+    }
+
+    /// Parses the complete document strictly.
+    ///
+    /// # Errors
+    ///
+    /// Returns [`crate::Error::Json`] if the text is not valid JSON for `T`.
+    pub fn finish<T: DeserializeOwned>(&self) -> crate::Result<T> {
+        Ok(serde_json::from_str(&self.buffer)?)
+    }
+}
+
+/// Scan state at the end of a JSON prefix
+struct Scan {
+    /// Closing characters for the open containers, innermost last
+    closers: Vec<char>,
+    in_string: bool,
+    /// Byte offset where the unfinished escape sequence starts, if the text ends inside one
+    open_escape: Option<usize>,
+    /// Offsets of `,` and opening brackets outside strings, where the prefix can be cut
+    cut_points: Vec<usize>,
+}
+
+fn scan(text: &str) -> Scan {
+    let mut scan = Scan {
+        closers: Vec::new(),
+        in_string: false,
+        open_escape: None,
+        cut_points: Vec::new(),
+    };
+    let mut chars = text.char_indices();
+
+    while let Some((offset, c)) = chars.next() {
+        if scan.in_string {
+            match c {
+                '"' => scan.in_string = false,
+                '\\' => {
+                    // `\uXXXX` needs four more characters, other escapes one
+                    let needed = match chars.next() {
+                        Some((_, 'u')) => 4,
+                        Some(_) => 0,
+                        None => {
+                            scan.open_escape = Some(offset);
+                            break;
+                        }
+                    };
+                    if chars.by_ref().take(needed).count() < needed {
+                        scan.open_escape = Some(offset);
+                        break;
+                    }
+                }
+                _ => {}
+            }
+            continue;
+        }
+
+        match c {
+            '"' => scan.in_string = true,
+            '{' => {
+                scan.closers.push('}');
+                scan.cut_points.push(offset + 1);
+            }
+            '[' => {
+                scan.closers.push(']');
+                scan.cut_points.push(offset + 1);
+            }
+            '}' | ']' => {
+                scan.closers.pop();
+            }
+            ',' => scan.cut_points.push(offset),
+            _ => {}
+        }
+    }
+
+    scan
+}
+
+/// Closes the open string and containers of a prefix
+fn close(text: &str, scan: &Scan) -> String {
+    let mut repaired = match scan.open_escape {
+        Some(offset) => text[..offset].to_string(),
+        None => text.to_string(),
+    };
+    if scan.in_string {
+        repaired.push('"');
+    }
+
+    let trimmed_len = repaired.trim_end().len();
+    repaired.truncate(trimmed_len);
+    if repaired.ends_with(',') {
+        repaired.pop();
+    } else if repaired.ends_with(':') {
+        repaired.push_str("null");
+    }
+
+    repaired.extend(scan.closers.iter().rev());
+    repaired
+}
+
+/// Finds the longest repairable prefix of `text` and parses it
+fn repair(text: &str) -> Option<serde_json::Value> {
+    let text = text.trim_start();
+    if text.is_empty() {
+        return None;
+    }
+
+    let full = scan(text);
+    if let Ok(value) = serde_json::from_str(&close(text, &full)) {
+        return Some(value);
+    }
+
+    // Drop the unfinished trailing member (a bare key, `tr` of `true`, `1.` ...) and retry
+    full.cut_points.iter().rev().find_map(|&cut| {
+        let prefix = &text[..cut];
+        serde_json::from_str(&close(prefix, &scan(prefix))).ok()
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde::Deserialize;
+
+    #[derive(Debug, PartialEq, Deserialize)]
+    struct Profile {
+        name: String,
+        #[serde(default)]
+        tags: Vec<String>,
+        age: Option<u32>,
+    }
+
+    #[test]
+    fn partial_parses_follow_awkward_chunk_boundaries() {
+        let mut json = JsonStreamAccumulator::new();
+        let mut partial = |chunk: &str| {
+            json.push_str(chunk);
+            json.try_partial::<Profile>()
+        };
+
+        // Mid-key: nothing usable yet
+        assert_eq!(partial(r#"{"na"#), None);
+        assert_eq!(partial(r#"me""#), None);
+        // Mid-escape: the dangling backslash is dropped
+        assert_eq!(partial(r#": "Al\"#).unwrap().name, "Al");
+        assert_eq!(partial(r#""ice\" \u00"#).unwrap().name, "Al\"ice\" ");
+        assert_eq!(
+            partial(r#"e9", "tags": ["a", "b"#).unwrap().tags,
+            ["a", "b"]
+        );
+        // A half-written key after a comma is dropped with its comma
+        let profile = partial(r#""], "ag"#).unwrap();
+        assert_eq!(profile.name, "Al\"ice\" é");
+        assert_eq!(profile.age, None);
+        assert_eq!(partial(r#"e": "#).unwrap().age, None);
+        assert_eq!(partial("4").unwrap().age, Some(4));
+        assert_eq!(partial("2").unwrap().age, Some(42));
+
+        assert!(json.finish::<Profile>().is_err());
+        json.push_str("}");
+        assert_eq!(
+            json.finish::<Profile>().unwrap(),
+            Profile {
+                name: "Al\"ice\" é".to_string(),
+                tags: vec!["a".to_string(), "b".to_string()],
+                age: Some(42),
+            }
+        );
+    }
+
+    #[test]
+    fn partial_literals_and_nesting_are_trimmed() {
+        let mut json = JsonStreamAccumulator::new();
+        json.push_str(r#"{"ok": tr"#);
+        assert_eq!(json.partial_value(), Some(serde_json::json!({})));
+        json.push_str(r#"ue, "items": [{"id": 1}, {"id": 2, "note": "x"#);
+        assert_eq!(
+            json.partial_value(),
+            Some(serde_json::json!({"ok": true, "items": [{"id": 1}, {"id": 2, "note": "x"}]}))
+        );
+        json.push_str(r#""}], "score": 1."#);
+        assert_eq!(
+            json.partial_value(),
+            Some(serde_json::json!({"ok": true, "items": [{"id": 1}, {"id": 2, "note": "x"}]}))
+        );
+    }
+
+    #[test]
+    fn only_text_deltas_are_accumulated() {
+        let mut json = JsonStreamAccumulator::new();
+        assert!(!json.push_event(&StreamEvent::Done));
+        assert!(json.push_event(&StreamEvent::TextDelta {
+            content: "[1, 2".to_string(),
+            index: 0,
+            content_index: 0,
+        }));
+        assert_eq!(json.as_str(), "[1, 2");
+        assert_eq!(json.try_partial::<Vec<u8>>(), Some(vec![1, 2]));
+        assert_eq!(JsonStreamAccumulator::new().partial_value(), None);
+    }
+}
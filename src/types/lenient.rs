@@ -0,0 +1,173 @@
+//! Deserializers for off-spec payloads from OpenAI-compatible gateways.
+//!
+//! Gateways such as OpenRouter mostly follow the OpenAI schema but deviate in small
+//! ways: `created_at` sent as an RFC 3339 string, status values in a different case,
+//! or `object` fields left out. With the `lenient` feature enabled these helpers
+//! accept such payloads; without it they behave exactly like the strict defaults.
+//!
+//! Unknown fields are ignored in both modes.
+
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Deserializer};
+
+/// `created_at` timestamps.
+///
+/// Always serialized as unix seconds. Strict mode only accepts integer seconds;
+/// lenient mode also accepts floats, numeric strings and RFC 3339 strings.
+pub mod timestamp {
+    use super::{DateTime, Deserializer, Utc};
+    use serde::Serializer;
+
+    /// Serializes a timestamp as unix seconds
+    ///
+    /// # Errors
+    ///
+    /// Returns the serializer's error.
+    pub fn serialize<S>(value: &DateTime<Utc>, serializer: S) -> Result<S::Ok, S::Error>
+    where
+        S: Serializer,
+    {
+        chrono::serde::ts_seconds::serialize(value, serializer)
+    }
+
+    /// Deserializes a timestamp
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the value is not a timestamp accepted by the current mode.
+    #[cfg(not(feature = "lenient"))]
+    pub fn deserialize<'de, D>(deserializer: D) -> Result<DateTime<Utc>, D::Error>
+    where
+        D: Deserializer<'de>,
+    {
+        chrono::serde::ts_seconds::deserialize(deserializer)
+    }
+
+    /// Deserializes a timestamp
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the value is not a timestamp accepted by the current mode.
+    #[cfg(feature = "lenient")]
+    pub fn deserialize<'de, D>(deserializer: D) -> Result<DateTime<Utc>, D::Error>
+    where
+        D: Deserializer<'de>,
+    {
+        use serde::de::Error;
+        use serde::Deserialize;
+
+        let value = serde_json::Value::deserialize(deserializer)?;
+        parse_lenient(&value).ok_or_else(|| D::Error::custom(format!("invalid timestamp: {value}")))
+    }
+
+    #[cfg(feature = "lenient")]
+    fn parse_lenient(value: &serde_json::Value) -> Option<DateTime<Utc>> {
+        match value {
+            serde_json::Value::Number(n) => {
+                if let Some(secs) = n.as_i64() {
+                    DateTime::from_timestamp(secs, 0)
+                } else {
+                    from_float(n.as_f64()?)
+                }
+            }
+            serde_json::Value::String(s) => {
+                let s = s.trim();
+                if let Ok(secs) = s.parse::<i64>() {
+                    DateTime::from_timestamp(secs, 0)
+                } else if let Ok(secs) = s.parse::<f64>() {
+                    from_float(secs)
+                } else {
+                    DateTime::parse_from_rfc3339(s)
+                        .ok()
+                        .map(|dt| dt.with_timezone(&Utc))
+                }
+            }
+            _ => None,
+        }
+    }
+
+    #[cfg(feature = "lenient")]
+    #[allow(clippy::cast_possible_truncation)]
+    fn from_float(secs: f64) -> Option<DateTime<Utc>> {
+        let millis = (secs * 1000.0).round();
+        // Well inside i64, and far beyond chrono's own range
+        if !(-9.0e18..=9.0e18).contains(&millis) {
+            return None;
+        }
+        DateTime::from_timestamp_millis(millis as i64)
+    }
+}
+
+/// Optional timestamps such as `completed_at`, accepted like [`timestamp`].
+///
+/// A missing or `null` value is `None`; use with `#[serde(default)]`.
+pub mod timestamp_option {
+    use super::{DateTime, Deserializer, Utc};
+    use serde::{Deserialize, Serializer};
+
+    #[derive(Deserialize)]
+    struct Timestamp(#[serde(with = "super::timestamp")] DateTime<Utc>);
+
+    /// Serializes a timestamp as unix seconds, or `None` as `null`
+    ///
+    /// # Errors
+    ///
+    /// Returns the serializer's error.
+    pub fn serialize<S>(value: &Option<DateTime<Utc>>, serializer: S) -> Result<S::Ok, S::Error>
+    where
+        S: Serializer,
+    {
+        chrono::serde::ts_seconds_option::serialize(value, serializer)
+    }
+
+    /// Deserializes an optional timestamp
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the value is neither `null` nor a timestamp accepted by the
+    /// current mode.
+    pub fn deserialize<'de, D>(deserializer: D) -> Result<Option<DateTime<Utc>>, D::Error>
+    where
+        D: Deserializer<'de>,
+    {
+        Ok(Option::<Timestamp>::deserialize(deserializer)?.map(|Timestamp(time)| time))
+    }
+}
+
+/// Status strings.
+///
+/// Lenient mode lowercases the value so `"Completed"` and `"COMPLETED"` compare
+/// equal to `"completed"`; strict mode keeps it as sent.
+///
+/// # Errors
+///
+/// Returns an error if the value is not a string.
+pub fn status<'de, D>(deserializer: D) -> Result<String, D::Error>
+where
+    D: Deserializer<'de>,
+{
+    let status = String::deserialize(deserializer)?;
+    Ok(normalize_status(status))
+}
+
+/// Optional status strings, normalized like [`status`]
+///
+/// # Errors
+///
+/// Returns an error if the value is neither a string nor null.
+pub fn optional_status<'de, D>(deserializer: D) -> Result<Option<String>, D::Error>
+where
+    D: Deserializer<'de>,
+{
+    let status = Option::<String>::deserialize(deserializer)?;
+    Ok(status.map(normalize_status))
+}
+
+/// Applies the current mode's status normalization
+fn normalize_status(status: String) -> String {
+    if cfg!(feature = "lenient") {
+        status.to_ascii_lowercase()
+    } else {
+        status
+    }
+}
@@ -0,0 +1,47 @@
+//! Deserialization that leaves out large binary payloads.
+//!
+//! A response with several generated images can carry tens of megabytes of base64
+//! in `ImageGenerationCall.result`. [`Response::from_slice_lite`](crate::Response::from_slice_lite)
+//! skips over those strings while parsing, so they are never allocated, and leaves
+//! `result` empty. Everything else deserializes as usual.
+
+use serde::de::IgnoredAny;
+use serde::{Deserialize, Deserializer};
+use std::cell::Cell;
+
+thread_local! {
+    /// Set while a lite deserialization runs on this thread
+    static LITE: Cell<bool> = const { Cell::new(false) };
+}
+
+/// Restores the previous flag even if deserialization panics
+struct Restore(bool);
+
+impl Drop for Restore {
+    fn drop(&mut self) {
+        LITE.with(|lite| lite.set(self.0));
+    }
+}
+
+pub(crate) fn from_slice(bytes: &[u8]) -> serde_json::Result<crate::Response> {
+    lite(|| serde_json::from_slice(bytes))
+}
+
+/// Runs `deserialize` as a lite deserialization
+pub(crate) fn lite<T>(deserialize: impl FnOnce() -> T) -> T {
+    let _restore = Restore(LITE.with(|lite| lite.replace(true)));
+    deserialize()
+}
+
+/// `ImageGenerationCall.result`: skipped during a lite deserialization
+pub(crate) fn image_result<'de, D>(deserializer: D) -> Result<String, D::Error>
+where
+    D: Deserializer<'de>,
+{
+    if LITE.with(Cell::get) {
+        IgnoredAny::deserialize(deserializer)?;
+        Ok(String::new())
+    } else {
+        String::deserialize(deserializer)
+    }
+}
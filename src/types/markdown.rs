@@ -0,0 +1,563 @@
+//! Markdown post-processing for model output.
+//!
+//! [`Response::code_blocks`](crate::Response::code_blocks) pulls fenced code blocks out of
+//! the output text and [`Response::plain_text`](crate::Response::plain_text) strips
+//! formatting for UIs that cannot render markdown. Fences follow CommonMark: three or more
+//! backticks or tildes, closed by a fence of the same character at least as long, with an
+//! unterminated block running to the end of the text. Fences may be indented, as they are
+//! inside list items.
+
+/// A fenced code block found in markdown text
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct CodeBlock {
+    /// First word of the fence's info string, e.g. `rust` for ```` ```rust ````
+    pub language: Option<String>,
+
+    /// Contents of the block, without the fences or a trailing newline
+    pub code: String,
+}
+
+impl CodeBlock {
+    /// Whether the block's language is `language`, ignoring ASCII case
+    #[must_use]
+    pub fn is_language(&self, language: &str) -> bool {
+        self.language
+            .as_deref()
+            .is_some_and(|own| own.eq_ignore_ascii_case(language))
+    }
+}
+
+/// A line of markdown outside code blocks, or a whole code block
+enum Block<'a> {
+    Line(&'a str),
+    Code(CodeBlock),
+}
+
+/// An opening fence: its character, length and indentation
+struct Fence {
+    marker: char,
+    len: usize,
+    indent: usize,
+}
+
+impl Fence {
+    /// Parses an opening fence and the language from its info string
+    fn open(line: &str) -> Option<(Self, Option<String>)> {
+        let trimmed = line.trim_start_matches([' ', '\t']);
+        let marker = trimmed.chars().next().filter(|c| matches!(c, '`' | '~'))?;
+        let len = trimmed.chars().take_while(|&c| c == marker).count();
+        if len < 3 {
+            return None;
+        }
+        // Fence characters are ASCII, so `len` is also a byte offset
+        let info = trimmed[len..].trim();
+        if marker == '`' && info.contains('`') {
+            // ```` ```js``` ```` is inline code, not a fence
+            return None;
+        }
+        let language = info
+            .split(|c: char| c.is_whitespace() || c == ',')
+            .next()
+            .filter(|word| !word.is_empty())
+            .map(ToString::to_string);
+        let fence = Self {
+            marker,
+            len,
+            indent: line.len() - trimmed.len(),
+        };
+        Some((fence, language))
+    }
+
+    /// Whether `line` closes this fence: the same character, at least as many of it,
+    /// and nothing else
+    fn closes(&self, line: &str) -> bool {
+        let trimmed = line.trim_start_matches([' ', '\t']);
+        let len = trimmed.chars().take_while(|&c| c == self.marker).count();
+        len >= self.len && trimmed[len..].trim().is_empty()
+    }
+
+    /// Removes up to the fence's indentation from a line inside the block
+    fn dedent<'a>(&self, line: &'a str) -> &'a str {
+        let indent = line
+            .bytes()
+            .take(self.indent)
+            .take_while(|byte| matches!(byte, b' ' | b'\t'))
+            .count();
+        &line[indent..]
+    }
+}
+
+/// Splits `text` into code blocks and the lines between them
+fn parse(text: &str) -> Vec<Block<'_>> {
+    let mut blocks = Vec::new();
+    let mut lines = text.lines();
+    while let Some(line) = lines.next() {
+        let Some((fence, language)) = Fence::open(line) else {
+            blocks.push(Block::Line(line));
+            continue;
+        };
+        let code = lines
+            .by_ref()
+            .take_while(|line| !fence.closes(line))
+            .map(|line| fence.dedent(line))
+            .collect::<Vec<_>>()
+            .join("\n");
+        blocks.push(Block::Code(CodeBlock { language, code }));
+    }
+    blocks
+}
+
+/// Fenced code blocks in `text`, in order
+pub(crate) fn code_blocks(text: &str) -> Vec<CodeBlock> {
+    parse(text)
+        .into_iter()
+        .filter_map(|block| match block {
+            Block::Code(code) => Some(code),
+            Block::Line(_) => None,
+        })
+        .collect()
+}
+
+/// `text` with headings, emphasis, links, quotes and fences removed; code is kept as is
+pub(crate) fn plain_text(text: &str) -> String {
+    let lines: Vec<String> = parse(text)
+        .into_iter()
+        .map(|block| match block {
+            Block::Line(line) => plain_line(line),
+            Block::Code(code) => code.code,
+        })
+        .collect();
+    lines.join("\n").trim_end().to_string()
+}
+
+fn plain_line(line: &str) -> String {
+    let trimmed = line.trim_start();
+    if is_thematic_break(trimmed) {
+        return String::new();
+    }
+    if let Some(heading) = heading_text(trimmed) {
+        return strip_inline(heading);
+    }
+
+    let indent = &line[..line.len() - trimmed.len()];
+    let mut rest = trimmed;
+    while let Some(quoted) = rest.strip_prefix('>') {
+        rest = quoted.strip_prefix(' ').unwrap_or(quoted);
+    }
+    // `* item` would otherwise read as an unclosed emphasis
+    let bullet = ["- ", "* ", "+ "]
+        .iter()
+        .find_map(|marker| rest.strip_prefix(marker));
+    match bullet {
+        Some(item) => format!("{indent}- {}", strip_inline(item)),
+        None => format!("{indent}{}", strip_inline(rest)),
+    }
+}
+
+/// `---`, `***` or `___`, optionally spaced out
+fn is_thematic_break(line: &str) -> bool {
+    let mut chars = line.chars().filter(|c| !c.is_whitespace());
+    let Some(first) = chars.next().filter(|c| matches!(c, '-' | '*' | '_')) else {
+        return false;
+    };
+    let mut count = 1;
+    for c in chars {
+        if c != first {
+            return false;
+        }
+        count += 1;
+    }
+    count >= 3
+}
+
+/// Text of an ATX heading (`# Title`, `## Title ##`)
+fn heading_text(line: &str) -> Option<&str> {
+    let level = line.chars().take_while(|&c| c == '#').count();
+    if !(1..=6).contains(&level) {
+        return None;
+    }
+    let rest = &line[level..];
+    if !rest.is_empty() && !rest.starts_with([' ', '\t']) {
+        return None;
+    }
+    let rest = rest.trim();
+    let without_closing = rest.trim_end_matches('#');
+    if without_closing.is_empty() || without_closing.ends_with([' ', '\t']) {
+        Some(without_closing.trim_end())
+    } else {
+        Some(rest)
+    }
+}
+
+/// Removes inline markdown: code spans, emphasis, strikethrough, links, images,
+/// autolinks and backslash escapes
+fn strip_inline(text: &str) -> String {
+    let chars: Vec<char> = text.chars().collect();
+    let mut out = String::with_capacity(text.len());
+    let mut i = 0;
+    while i < chars.len() {
+        let c = chars[i];
+        match c {
+            '\\' if chars.get(i + 1).is_some_and(char::is_ascii_punctuation) => {
+                out.push(chars[i + 1]);
+                i += 2;
+            }
+            '`' => {
+                let run = run_len(&chars, i);
+                if let Some(end) = code_span_end(&chars, i + run, run) {
+                    out.push_str(&code_span_text(&chars[i + run..end]));
+                    i = end + run;
+                } else {
+                    out.extend(&chars[i..i + run]);
+                    i += run;
+                }
+            }
+            '!' | '[' => {
+                let label_start = if c == '!' { i + 1 } else { i };
+                if let Some((label, next)) = link(&chars, label_start) {
+                    out.push_str(&strip_inline(&label));
+                    i = next;
+                } else {
+                    out.push(c);
+                    i += 1;
+                }
+            }
+            '<' => {
+                if let Some((url, next)) = autolink(&chars, i) {
+                    out.push_str(&url);
+                    i = next;
+                } else {
+                    out.push(c);
+                    i += 1;
+                }
+            }
+            '*' | '_' | '~' => {
+                let run = run_len(&chars, i);
+                if let Some(close) = emphasis_close(&chars, i, run) {
+                    let inner: String = chars[i + run..close].iter().collect();
+                    out.push_str(&strip_inline(&inner));
+                    i = close + run;
+                } else {
+                    out.extend(&chars[i..i + run]);
+                    i += run;
+                }
+            }
+            _ => {
+                out.push(c);
+                i += 1;
+            }
+        }
+    }
+    out
+}
+
+/// Length of the run of `chars[start]` starting at `start`
+fn run_len(chars: &[char], start: usize) -> usize {
+    chars[start..]
+        .iter()
+        .take_while(|&&c| c == chars[start])
+        .count()
+}
+
+/// Start of the backtick run of exactly `run` that closes a code span opened before `from`
+fn code_span_end(chars: &[char], from: usize, run: usize) -> Option<usize> {
+    let mut j = from;
+    while j < chars.len() {
+        if chars[j] == '`' {
+            let len = run_len(chars, j);
+            if len == run {
+                return Some(j);
+            }
+            j += len;
+        } else {
+            j += 1;
+        }
+    }
+    None
+}
+
+/// Code span contents, with one space of padding removed from each side
+fn code_span_text(inner: &[char]) -> String {
+    let padded = inner.len() >= 2
+        && inner[0] == ' '
+        && inner[inner.len() - 1] == ' '
+        && inner.iter().any(|&c| c != ' ');
+    let inner = if padded {
+        &inner[1..inner.len() - 1]
+    } else {
+        inner
+    };
+    inner.iter().collect()
+}
+
+/// Parses `[label](destination)` at `start`, returning the label and the index after it
+fn link(chars: &[char], start: usize) -> Option<(String, usize)> {
+    if chars.get(start) != Some(&'[') {
+        return None;
+    }
+    let label_end = matching(chars, start, '[', ']')?;
+    if chars.get(label_end + 1) != Some(&'(') {
+        return None;
+    }
+    let destination_end = matching(chars, label_end + 1, '(', ')')?;
+    let label = chars[start + 1..label_end].iter().collect();
+    Some((label, destination_end + 1))
+}
+
+/// Index of the bracket closing the one at `open`, allowing nesting
+fn matching(chars: &[char], open: usize, left: char, right: char) -> Option<usize> {
+    let mut depth = 0_usize;
+    for (j, &c) in chars.iter().enumerate().skip(open) {
+        if c == left {
+            depth += 1;
+        } else if c == right {
+            depth -= 1;
+            if depth == 0 {
+                return Some(j);
+            }
+        }
+    }
+    None
+}
+
+/// Parses `<https://…>` or `<mailto:…>` at `start`, returning the URL and the index after it
+fn autolink(chars: &[char], start: usize) -> Option<(String, usize)> {
+    let len = chars[start + 1..].iter().position(|&c| c == '>')?;
+    let url: String = chars[start + 1..start + 1 + len].iter().collect();
+    let is_url = ["http://", "https://", "mailto:"]
+        .iter()
+        .any(|scheme| url.starts_with(scheme));
+    (is_url && !url.contains(char::is_whitespace)).then_some((url, start + len + 2))
+}
+
+/// Start of the delimiter run closing emphasis opened by the `run` delimiters at `open`.
+///
+/// The opener must be followed by a non-space and the closer preceded by one, so
+/// `2 * 3 * 4` is left alone; `_` must also sit at word boundaries, so `snake_case_name`
+/// is too. `~` only counts as `~~` strikethrough.
+fn emphasis_close(chars: &[char], open: usize, run: usize) -> Option<usize> {
+    let delimiter = chars[open];
+    let valid_run = match delimiter {
+        '~' => run == 2,
+        _ => run <= 3,
+    };
+    let intraword = |index: Option<&char>| index.is_some_and(|c| c.is_alphanumeric());
+    if !valid_run
+        || chars.get(open + run).is_none_or(|c| c.is_whitespace())
+        || (delimiter == '_' && open > 0 && intraword(chars.get(open - 1)))
+    {
+        return None;
+    }
+
+    let mut j = open + run + 1;
+    while j < chars.len() {
+        if chars[j] != delimiter {
+            j += 1;
+            continue;
+        }
+        let len = run_len(chars, j);
+        let closes = len == run
+            && !chars[j - 1].is_whitespace()
+            && !(delimiter == '_' && intraword(chars.get(j + len)));
+        if closes {
+            return Some(j);
+        }
+        j += len;
+    }
+    None
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn block(language: Option<&str>, code: &str) -> CodeBlock {
+        CodeBlock {
+            language: language.map(ToString::to_string),
+            code: code.to_string(),
+        }
+    }
+
+    #[test]
+    fn fenced_blocks_keep_language_and_order() {
+        let text = "Here you go:\n\n```rust\nfn main() {\n    println!(\"hi\");\n}\n```\n\nAnd the test:\n\n```\ncargo test\n```\n";
+        assert_eq!(
+            code_blocks(text),
+            [
+                block(Some("rust"), "fn main() {\n    println!(\"hi\");\n}"),
+                block(None, "cargo test"),
+            ]
+        );
+
+        // Only the first word of the info string names the language
+        assert_eq!(
+            code_blocks("```rust,ignore\nlet x = 1;\n```\n```python title=\"a.py\"\npass\n```"),
+            [
+                block(Some("rust"), "let x = 1;"),
+                block(Some("python"), "pass")
+            ]
+        );
+        assert_eq!(code_blocks("```\n```"), [block(None, "")]);
+        assert!(code_blocks("No code here.").is_empty());
+    }
+
+    #[test]
+    fn tilde_and_longer_fences_contain_shorter_ones() {
+        let text = "~~~markdown\n```rust\nlet x = 1;\n```\n~~~";
+        assert_eq!(
+            code_blocks(text),
+            [block(Some("markdown"), "```rust\nlet x = 1;\n```")]
+        );
+
+        let text = "````md\nExample:\n```js\nalert(1)\n```\n````\nafter";
+        assert_eq!(
+            code_blocks(text),
+            [block(Some("md"), "Example:\n```js\nalert(1)\n```")]
+        );
+
+        // A closing fence may be longer but not of the other character or carry an info string
+        let text = "```\n~~~\n```rust\nstill code\n`````\n";
+        assert_eq!(code_blocks(text), [block(None, "~~~\n```rust\nstill code")]);
+    }
+
+    #[test]
+    fn fences_in_inline_code_are_not_blocks() {
+        let text = "Wrap snippets in `` ```rust `` fences.\nOr write ```js``` inline.\n";
+        assert!(code_blocks(text).is_empty());
+        assert_eq!(
+            plain_text(text),
+            "Wrap snippets in ```rust fences.\nOr write js inline."
+        );
+    }
+
+    #[test]
+    fn unterminated_blocks_run_to_the_end() {
+        let text = "Start:\n```sh\necho one\necho two\n";
+        assert_eq!(code_blocks(text), [block(Some("sh"), "echo one\necho two")]);
+        assert_eq!(code_blocks("```py"), [block(Some("py"), "")]);
+    }
+
+    #[test]
+    fn crlf_line_endings_are_normalized() {
+        let text = "Intro\r\n```json\r\n{\r\n  \"a\": 1\r\n}\r\n```\r\nDone\r\n";
+        assert_eq!(code_blocks(text), [block(Some("json"), "{\n  \"a\": 1\n}")]);
+        assert_eq!(plain_text(text), "Intro\n{\n  \"a\": 1\n}\nDone");
+    }
+
+    #[test]
+    fn indented_fences_in_lists_are_dedented() {
+        let text = "1. Install:\n    ```bash\n    cargo add serde\n      --features derive\n    ```\n2. Done";
+        assert_eq!(
+            code_blocks(text),
+            [block(Some("bash"), "cargo add serde\n  --features derive")]
+        );
+    }
+
+    #[test]
+    fn response_helpers_read_the_output_text() {
+        let response: crate::Response = serde_json::from_value(serde_json::json!({
+            "id": "resp_1",
+            "object": "response",
+            "created_at": 1_700_000_000,
+            "model": "gpt-4o",
+            "status": "completed",
+            "output": [{
+                "type": "message",
+                "id": "msg_1",
+                "role": "assistant",
+                "status": "completed",
+                "content": [{
+                    "type": "output_text",
+                    "text": "**Setup:**\n```toml\nserde = \"1\"\n```\nThen:\n```Rust\nfn main() {}\n```",
+                    "annotations": []
+                }]
+            }]
+        }))
+        .unwrap();
+
+        assert_eq!(response.code_blocks().len(), 2);
+        assert_eq!(
+            response.first_code_block(None),
+            Some(block(Some("toml"), "serde = \"1\""))
+        );
+        assert_eq!(
+            response.first_code_block(Some("rust")),
+            Some(block(Some("Rust"), "fn main() {}"))
+        );
+        assert_eq!(response.first_code_block(Some("python")), None);
+        assert_eq!(
+            response.plain_text(),
+            "Setup:\nserde = \"1\"\nThen:\nfn main() {}"
+        );
+    }
+
+    #[test]
+    fn languages_match_case_insensitively() {
+        let code = block(Some("Rust"), "");
+        assert!(code.is_language("rust"));
+        assert!(!code.is_language("rs"));
+        assert!(!block(None, "").is_language("rust"));
+    }
+
+    #[test]
+    fn plain_text_strips_block_markdown() {
+        let text = "# Title\n\n## Section ##\n\n> quoted **text**\n> > nested\n\n---\n\n* one\n- two\n  + three\n1. first\n#hashtag";
+        assert_eq!(
+            plain_text(text),
+            "Title\n\nSection\n\nquoted text\nnested\n\n\n\n- one\n- two\n  - three\n1. first\n#hashtag"
+        );
+    }
+
+    #[test]
+    fn plain_text_strips_inline_markdown() {
+        let cases = [
+            ("**bold** and __bold__", "bold and bold"),
+            ("*italic* and _italic_", "italic and italic"),
+            ("***both*** and ~~gone~~", "both and gone"),
+            ("**bold with *nested* italic**", "bold with nested italic"),
+            ("Call `parse_args()` now", "Call parse_args() now"),
+            ("`` a ` b ``", "a ` b"),
+            (
+                "See [the docs](https://docs.rs/x) and ![logo](logo.png)",
+                "See the docs and logo",
+            ),
+            ("[**bold link**](https://a.b/(c))", "bold link"),
+            (
+                "Visit <https://example.com> or <not a link>",
+                "Visit https://example.com or <not a link>",
+            ),
+            (
+                r"Escaped \*stars\* and \_underscores\_",
+                "Escaped *stars* and _underscores_",
+            ),
+        ];
+        for (markdown, plain) in cases {
+            assert_eq!(plain_text(markdown), plain, "{markdown}");
+        }
+    }
+
+    #[test]
+    fn plain_text_leaves_lookalikes_alone() {
+        let cases = [
+            "2 * 3 * 4 = 24",
+            "snake_case_name and my__dunder__var",
+            "a ~ b ~~ c",
+            "**unclosed bold",
+            "[not a link] (with space)",
+            "`unclosed code",
+            "price: $5_000",
+        ];
+        for text in cases {
+            assert_eq!(plain_text(text), text);
+        }
+    }
+
+    #[test]
+    fn plain_text_keeps_code_verbatim() {
+        let text = "Run this:\n\n```python\n# not a heading\nx = a * b * c  # **not bold**\n```\n";
+        assert_eq!(
+            plain_text(text),
+            "Run this:\n\n# not a heading\nx = a * b * c  # **not bold**"
+        );
+    }
+}
@@ -0,0 +1,256 @@
+use crate::types::ValidationError;
+use serde::{Deserialize, Deserializer, Serialize};
+use std::collections::HashMap;
+
+/// Maximum number of key-value pairs accepted by the API
+pub const METADATA_MAX_KEYS: usize = 16;
+
+/// Maximum length of a metadata key, in characters
+pub const METADATA_MAX_KEY_LEN: usize = 64;
+
+/// Maximum length of a metadata value, in characters
+pub const METADATA_MAX_VALUE_LEN: usize = 512;
+
+/// Metadata attached to requests, responses and messages.
+///
+/// The API accepts a flat map of up to 16 string keys (at most 64 characters each)
+/// and string values (at most 512 characters each). [`Metadata::insert`] enforces
+/// these limits; maps built any other way are checked by [`Metadata::validate`].
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize)]
+#[serde(transparent)]
+pub struct Metadata(HashMap<String, String>);
+
+impl Metadata {
+    /// Creates an empty metadata map
+    #[must_use]
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Inserts a key-value pair, returning the previous value for the key.
+    ///
+    /// # Errors
+    ///
+    /// Returns a [`ValidationError`] if the key or value is too long, or if inserting
+    /// a new key would exceed the maximum number of keys. The map is left unchanged.
+    pub fn insert(
+        &mut self,
+        key: impl Into<String>,
+        value: impl Into<String>,
+    ) -> Result<Option<String>, ValidationError> {
+        let key = key.into();
+        let value = value.into();
+        check_entry(&key, &value)?;
+        if !self.0.contains_key(&key) && self.0.len() >= METADATA_MAX_KEYS {
+            return Err(ValidationError::MetadataTooManyKeys {
+                count: self.0.len() + 1,
+                max: METADATA_MAX_KEYS,
+            });
+        }
+        Ok(self.0.insert(key, value))
+    }
+
+    /// Inserts a key-value pair without checking limits; [`Metadata::validate`] reports them later
+    pub(crate) fn insert_unchecked(&mut self, key: String, value: String) {
+        self.0.insert(key, value);
+    }
+
+    /// Returns the value for a key
+    #[must_use]
+    pub fn get(&self, key: &str) -> Option<&str> {
+        self.0.get(key).map(String::as_str)
+    }
+
+    /// Removes a key, returning its value
+    pub fn remove(&mut self, key: &str) -> Option<String> {
+        self.0.remove(key)
+    }
+
+    /// Returns the number of entries
+    #[must_use]
+    pub fn len(&self) -> usize {
+        self.0.len()
+    }
+
+    /// Returns true if there are no entries
+    #[must_use]
+    pub fn is_empty(&self) -> bool {
+        self.0.is_empty()
+    }
+
+    /// Iterates over the entries in arbitrary order
+    pub fn iter(&self) -> impl Iterator<Item = (&str, &str)> {
+        self.0.iter().map(|(k, v)| (k.as_str(), v.as_str()))
+    }
+
+    /// Checks the map against the API limits.
+    ///
+    /// Keys are checked in sorted order so the reported violation is deterministic.
+    ///
+    /// # Errors
+    ///
+    /// Returns the first [`ValidationError`] found.
+    pub fn validate(&self) -> Result<(), ValidationError> {
+        if self.0.len() > METADATA_MAX_KEYS {
+            return Err(ValidationError::MetadataTooManyKeys {
+                count: self.0.len(),
+                max: METADATA_MAX_KEYS,
+            });
+        }
+        let mut entries: Vec<_> = self.0.iter().collect();
+        entries.sort_unstable_by_key(|(k, _)| k.as_str());
+        for (key, value) in entries {
+            check_entry(key, value)?;
+        }
+        Ok(())
+    }
+
+    /// Returns the underlying map
+    #[must_use]
+    pub fn into_inner(self) -> HashMap<String, String> {
+        self.0
+    }
+}
+
+fn check_entry(key: &str, value: &str) -> Result<(), ValidationError> {
+    let key_len = key.chars().count();
+    if key_len > METADATA_MAX_KEY_LEN {
+        return Err(ValidationError::MetadataKeyTooLong {
+            key: key.to_string(),
+            len: key_len,
+            max: METADATA_MAX_KEY_LEN,
+        });
+    }
+    let value_len = value.chars().count();
+    if value_len > METADATA_MAX_VALUE_LEN {
+        return Err(ValidationError::MetadataValueTooLong {
+            key: key.to_string(),
+            len: value_len,
+            max: METADATA_MAX_VALUE_LEN,
+        });
+    }
+    Ok(())
+}
+
+impl From<HashMap<String, String>> for Metadata {
+    fn from(map: HashMap<String, String>) -> Self {
+        Self(map)
+    }
+}
+
+impl<K: Into<String>, V: Into<String>> FromIterator<(K, V)> for Metadata {
+    fn from_iter<I: IntoIterator<Item = (K, V)>>(iter: I) -> Self {
+        Self(
+            iter.into_iter()
+                .map(|(k, v)| (k.into(), v.into()))
+                .collect(),
+        )
+    }
+}
+
+// Metadata used to be an arbitrary `serde_json::Value`, so stored payloads may hold
+// non-string values. Those are kept as their JSON text rather than rejected.
+impl<'de> Deserialize<'de> for Metadata {
+    fn deserialize<D>(deserializer: D) -> Result<Self, D::Error>
+    where
+        D: Deserializer<'de>,
+    {
+        match serde_json::Value::deserialize(deserializer)? {
+            serde_json::Value::Null => Ok(Self::default()),
+            serde_json::Value::Object(map) => Ok(map
+                .into_iter()
+                .map(|(key, value)| match value {
+                    serde_json::Value::String(s) => (key, s),
+                    other => (key, other.to_string()),
+                })
+                .collect()),
+            other => Err(serde::de::Error::custom(format!(
+                "expected metadata to be an object, found {other}"
+            ))),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn insert_enforces_limits() {
+        let mut metadata = Metadata::new();
+        assert_eq!(metadata.insert("a".repeat(64), "ok"), Ok(None));
+        assert_eq!(
+            metadata.insert("b".repeat(65), "ok"),
+            Err(ValidationError::MetadataKeyTooLong {
+                key: "b".repeat(65),
+                len: 65,
+                max: 64,
+            })
+        );
+        assert!(matches!(
+            metadata.insert("key", "v".repeat(513)),
+            Err(ValidationError::MetadataValueTooLong {
+                len: 513,
+                max: 512,
+                ..
+            })
+        ));
+        // Limits count characters, not bytes
+        assert!(metadata.insert("é".repeat(64), "ü".repeat(512)).is_ok());
+
+        for i in 0..14 {
+            metadata.insert(format!("k{i}"), "v").unwrap();
+        }
+        assert_eq!(metadata.len(), 16);
+        assert_eq!(
+            metadata.insert("one_too_many", "v"),
+            Err(ValidationError::MetadataTooManyKeys { count: 17, max: 16 })
+        );
+        // Overwriting an existing key is still allowed at capacity
+        assert_eq!(metadata.insert("k0", "new"), Ok(Some("v".to_string())));
+        assert_eq!(metadata.get("k0"), Some("new"));
+        assert!(metadata.validate().is_ok());
+    }
+
+    #[test]
+    fn validate_reports_unchecked_entries() {
+        let metadata: Metadata = (0..17).map(|i| (format!("k{i}"), "v")).collect();
+        assert_eq!(
+            metadata.validate(),
+            Err(ValidationError::MetadataTooManyKeys { count: 17, max: 16 })
+        );
+
+        let mut metadata = Metadata::new();
+        metadata.insert_unchecked("long".to_string(), "x".repeat(600));
+        let err = metadata.validate().unwrap_err();
+        assert_eq!(
+            err.to_string(),
+            "metadata value for key `long` is 600 characters, exceeding the limit of 512"
+        );
+    }
+
+    #[test]
+    fn deserializes_legacy_loose_values() {
+        let metadata: Metadata = serde_json::from_str(
+            r#"{"user": "alice", "count": 3, "flag": true, "nested": {"a": [1, 2]}, "none": null}"#,
+        )
+        .unwrap();
+        assert_eq!(metadata.get("user"), Some("alice"));
+        assert_eq!(metadata.get("count"), Some("3"));
+        assert_eq!(metadata.get("flag"), Some("true"));
+        assert_eq!(metadata.get("nested"), Some(r#"{"a":[1,2]}"#));
+        assert_eq!(metadata.get("none"), Some("null"));
+
+        assert!(serde_json::from_str::<Metadata>("null").unwrap().is_empty());
+        assert!(serde_json::from_str::<Metadata>(r#"["a"]"#).is_err());
+    }
+
+    #[test]
+    fn serializes_as_flat_object() {
+        let metadata: Metadata = [("session", "abc")].into_iter().collect();
+        assert_eq!(
+            serde_json::to_value(&metadata).unwrap(),
+            serde_json::json!({"session": "abc"})
+        );
+    }
+}
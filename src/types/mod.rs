@@ -1,20 +1,74 @@
 // Common types used across the API
+pub mod audit;
 pub mod background;
+pub mod canonical;
+pub mod chat;
 pub mod config;
+pub mod discrepancy;
 pub mod helpers;
+pub mod image_input;
 pub mod item;
+#[cfg(feature = "stream")]
+pub mod json_stream;
+pub mod lenient;
+pub(crate) mod lite;
+pub mod markdown;
+pub mod metadata;
+pub mod outcome;
+pub mod partial;
+#[cfg(all(feature = "stream", not(target_arch = "wasm32")))]
+pub mod partial_images;
 pub mod reasoning;
 pub mod request;
 pub mod response;
+pub mod response_builder;
+pub mod sampling;
+pub mod size_report;
+pub mod snapshot;
+pub mod sse;
 pub mod stream;
+#[cfg(feature = "stream")]
+pub mod stream_collector;
+pub mod template;
 pub mod tools;
+pub mod transcript;
+pub mod usage;
+pub mod validation;
 
+pub use audit::RedactionConfig;
 pub use background::{BackgroundHandle, BackgroundStatus, BackgroundStatusResponse};
+pub use chat::{
+    convert_chat_messages, ChatContent, ChatContentPart, ChatConversionError, ChatFunctionCall,
+    ChatImageUrl, ChatMessage, ChatToolCall, ConvertedChat, SystemMessages,
+};
 pub use config::*;
+pub use discrepancy::{ParamDiscrepancy, ParamField};
 pub use helpers::*;
+pub use image_input::IMAGE_MAX_BYTES;
 pub use item::*;
+#[cfg(feature = "stream")]
+pub use json_stream::JsonStreamAccumulator;
+pub use markdown::CodeBlock;
+pub use metadata::*;
+pub use outcome::{
+    CancelOutcome, CreateOutcome, DeleteConfirmation, DeleteOutcome, DeletionStatus,
+};
+pub use partial::PartialRequest;
+#[cfg(all(feature = "stream", not(target_arch = "wasm32")))]
+pub use partial_images::PartialImageWriter;
 pub use reasoning::{Effort, ReasoningEffort, ReasoningParams, SummarySetting};
 pub use request::*;
 pub use response::*;
+pub use response_builder::ResponseBuilder;
+pub use sampling::{SamplingPreset, SamplingValues};
+pub use size_report::{RequestSizeLimits, RequestSizeReport};
+pub use snapshot::{DiffEntry, DiffKind};
+pub use sse::SseEvent;
 pub use stream::*;
+#[cfg(feature = "stream")]
+pub use stream_collector::{CollectedOutput, CollectedToolCall, StreamCollector};
+pub use template::RequestTemplate;
 pub use tools::*;
+pub use transcript::TranscriptOptions;
+pub use usage::{ToolCallCounts, ToolKind, ToolUsageBreakdown, UsageAggregate};
+pub use validation::ValidationError;
@@ -0,0 +1,140 @@
+//! Typed results of create, cancel and delete calls, for statuses that are expected
+//! outcomes rather than failures.
+
+use crate::types::{BackgroundHandle, Response};
+use serde::{Deserialize, Serialize};
+
+/// Result of [`Responses::create_background`](crate::responses::Responses::create_background)
+#[derive(Debug, Clone, PartialEq)]
+pub enum CreateOutcome {
+    /// The response already finished, e.g. because a gateway ignored `background`
+    Completed(Box<Response>),
+
+    /// The response was queued or started (HTTP 202, or a `queued` / `in_progress`
+    /// status); poll the handle with
+    /// [`Responses::background_status`](crate::responses::Responses::background_status)
+    Accepted(BackgroundHandle),
+}
+
+impl CreateOutcome {
+    /// Returns the response, if it already finished
+    #[must_use]
+    pub fn response(&self) -> Option<&Response> {
+        match self {
+            Self::Completed(response) => Some(response),
+            Self::Accepted(_) => None,
+        }
+    }
+
+    /// Returns the handle, if the response is still running
+    #[must_use]
+    pub fn handle(&self) -> Option<&BackgroundHandle> {
+        match self {
+            Self::Accepted(handle) => Some(handle),
+            Self::Completed(_) => None,
+        }
+    }
+}
+
+/// Result of [`Responses::cancel`](crate::responses::Responses::cancel)
+#[derive(Debug, Clone, PartialEq)]
+pub enum CancelOutcome {
+    /// The response was cancelled; carries the cancelled response
+    Cancelled(Response),
+
+    /// The API refused to cancel with 409 or 423 because the response had already
+    /// finished; carries the response as retrieved afterwards, check its `status`
+    AlreadyCompleted(Response),
+
+    /// No response with this ID exists (404)
+    NotFound,
+}
+
+impl CancelOutcome {
+    /// Returns the response, unless none was found
+    #[must_use]
+    pub fn response(&self) -> Option<&Response> {
+        match self {
+            Self::Cancelled(response) | Self::AlreadyCompleted(response) => Some(response),
+            Self::NotFound => None,
+        }
+    }
+
+    /// Returns true if this call cancelled the response
+    #[must_use]
+    pub fn is_cancelled(&self) -> bool {
+        matches!(self, Self::Cancelled(_))
+    }
+}
+
+/// Result of a delete call such as [`Responses::delete`](crate::responses::Responses::delete)
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum DeleteOutcome {
+    /// The object was deleted
+    Deleted,
+
+    /// The object did not exist (404). Clients built with `with_strict_deletes(true)`
+    /// return the 404 as an error instead
+    NotFound,
+}
+
+impl DeleteOutcome {
+    /// Returns true if the object was deleted by this call
+    #[must_use]
+    pub fn is_deleted(self) -> bool {
+        self == Self::Deleted
+    }
+}
+
+/// Confirmation object the API returns for a delete
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct DeletionStatus {
+    /// ID of the object
+    pub id: String,
+
+    /// Object type, such as `response` or `vector_store.file.deleted`
+    pub object: String,
+
+    /// Whether the object was deleted; the API can answer `false` with a 200
+    pub deleted: bool,
+}
+
+/// Result of a delete call that keeps the API's confirmation, such as
+/// [`Responses::delete_confirmed`](crate::responses::Responses::delete_confirmed)
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum DeleteConfirmation {
+    /// The API answered with a confirmation; check its `deleted` flag
+    Confirmed(DeletionStatus),
+
+    /// The object did not exist (404). Clients built with `with_strict_deletes(true)`
+    /// return the 404 as an error instead
+    NotFound,
+}
+
+impl DeleteConfirmation {
+    /// Returns the confirmation, unless the object was not found
+    #[must_use]
+    pub fn status(&self) -> Option<&DeletionStatus> {
+        match self {
+            Self::Confirmed(status) => Some(status),
+            Self::NotFound => None,
+        }
+    }
+
+    /// Returns true if the API confirmed the object was deleted
+    #[must_use]
+    pub fn is_deleted(&self) -> bool {
+        self.status().is_some_and(|status| status.deleted)
+    }
+
+    /// Reduces the confirmation to a [`DeleteOutcome`]; `deleted: false` counts as
+    /// [`DeleteOutcome::NotFound`]
+    #[must_use]
+    pub fn outcome(&self) -> DeleteOutcome {
+        if self.is_deleted() {
+            DeleteOutcome::Deleted
+        } else {
+            DeleteOutcome::NotFound
+        }
+    }
+}
@@ -0,0 +1,246 @@
+//! Loading partially specified requests.
+//!
+//! A request saved in a config file or a database often leaves out the model or the
+//! input, to be filled in at call time. [`Request::from_json_partial`] reads such JSON
+//! into a [`RequestBuilder`], where `Request`'s own `Deserialize` would fail:
+//!
+//! ```rust
+//! use open_ai_rust_responses_by_sshift::{Model, Request};
+//!
+//! let request = Request::from_json_partial(r#"{"instructions": "Answer briefly", "temperature": 0.2}"#)
+//!     .unwrap()
+//!     .model(Model::GPT4oMini)
+//!     .input("What is the capital of France?")
+//!     .build();
+//! assert_eq!(request.temperature, Some(0.2));
+//! ```
+
+use crate::types::{
+    Include, Input, Metadata, Model, ReasoningParams, Request, RequestBuilder, ServiceTier,
+    TextConfig, Tool, ToolChoice, TruncationSetting,
+};
+use serde::{Deserialize, Serialize};
+use std::collections::BTreeMap;
+
+/// Every field of a [`Request`], each optional, plus the top-level keys this crate does
+/// not know.
+///
+/// Applied to a builder with [`RequestBuilder::apply_partial`], fields that are set
+/// replace the builder's and fields that are unset leave it untouched.
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+#[serde(default)]
+pub struct PartialRequest {
+    /// The model to use
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub model: Option<Model>,
+
+    /// The input to generate a response for
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub input: Option<Input>,
+
+    /// System instructions
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub instructions: Option<String>,
+
+    /// Legacy alias for `max_output_tokens`
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub max_tokens: Option<u32>,
+
+    /// Maximum number of output tokens
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub max_output_tokens: Option<u32>,
+
+    /// Sampling temperature
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub temperature: Option<f32>,
+
+    /// Nucleus sampling parameter
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub top_p: Option<f32>,
+
+    /// Number of top log probabilities to return
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub top_logprobs: Option<u32>,
+
+    /// Whether to stream the response
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub stream: Option<bool>,
+
+    /// Tools the model may call
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub tools: Option<Vec<Tool>>,
+
+    /// Which tool the model calls
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub tool_choice: Option<ToolChoice>,
+
+    /// Whether tools can be called in parallel
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub parallel_tool_calls: Option<bool>,
+
+    /// ID of a previous response to continue from
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub previous_response_id: Option<String>,
+
+    /// Metadata attached to the response
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub metadata: Option<Metadata>,
+
+    /// Additional fields to include in the response
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub include: Option<Vec<Include>>,
+
+    /// Reasoning parameters
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub reasoning: Option<ReasoningParams>,
+
+    /// Whether to run in the background
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub background: Option<bool>,
+
+    /// Whether to store the response
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub store: Option<bool>,
+
+    /// Server-side truncation strategy
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub truncation: Option<TruncationSetting>,
+
+    /// Text generation configuration
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub text: Option<TextConfig>,
+
+    /// Legacy end-user identifier
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub user: Option<String>,
+
+    /// Stable identifier for the end user
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub safety_identifier: Option<String>,
+
+    /// Key that groups requests sharing a prompt prefix
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub prompt_cache_key: Option<String>,
+
+    /// Processing tier
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub service_tier: Option<ServiceTier>,
+
+    /// Top-level keys that are not request fields, with their values
+    #[serde(flatten)]
+    pub unknown_fields: BTreeMap<String, serde_json::Value>,
+}
+
+impl PartialRequest {
+    /// Copies the fields that are set onto `request`
+    pub(crate) fn apply_to(self, request: &mut Request) {
+        fn set<T>(target: &mut Option<T>, value: Option<T>) {
+            if value.is_some() {
+                *target = value;
+            }
+        }
+
+        if let Some(model) = self.model {
+            request.model = model;
+        }
+        if let Some(input) = self.input {
+            request.input = input;
+        }
+        set(&mut request.instructions, self.instructions);
+        set(&mut request.max_tokens, self.max_tokens);
+        set(&mut request.max_output_tokens, self.max_output_tokens);
+        set(&mut request.temperature, self.temperature);
+        set(&mut request.top_p, self.top_p);
+        set(&mut request.top_logprobs, self.top_logprobs);
+        set(&mut request.stream, self.stream);
+        set(&mut request.tools, self.tools);
+        set(&mut request.tool_choice, self.tool_choice);
+        set(&mut request.parallel_tool_calls, self.parallel_tool_calls);
+        set(&mut request.previous_response_id, self.previous_response_id);
+        set(&mut request.metadata, self.metadata);
+        set(&mut request.include, self.include);
+        set(&mut request.reasoning, self.reasoning);
+        set(&mut request.background, self.background);
+        set(&mut request.store, self.store);
+        set(&mut request.truncation, self.truncation);
+        set(&mut request.text, self.text);
+        set(&mut request.user, self.user);
+        set(&mut request.safety_identifier, self.safety_identifier);
+        set(&mut request.prompt_cache_key, self.prompt_cache_key);
+        set(&mut request.service_tier, self.service_tier);
+    }
+}
+
+impl Request {
+    /// Reads request JSON in which every field, including `model` and `input`, may be
+    /// missing, and returns a builder holding the fields that are present.
+    ///
+    /// Unset fields keep the builder's defaults, so the model and input can be filled in
+    /// afterwards. Unknown top-level keys are logged as warnings and otherwise ignored.
+    ///
+    /// # Errors
+    ///
+    /// Returns [`Error::Json`](crate::Error::Json) if `json` is not a JSON object, or a
+    /// known field has the wrong shape.
+    pub fn from_json_partial(json: &str) -> crate::Result<RequestBuilder> {
+        let partial: PartialRequest = serde_json::from_str(json)?;
+        for key in partial.unknown_fields.keys() {
+            crate::diag::warn!("Ignoring unknown request field `{key}`");
+        }
+        Ok(Request::builder().apply_partial(partial))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn partial_json_fills_only_the_fields_it_sets() {
+        let json = r#"{
+            "instructions": "Answer briefly",
+            "max_output_tokens": 300,
+            "tools": [{"type": "web_search_preview"}],
+            "metadata": {"team": "docs"},
+            "prompt_cache_key": "faq-v2",
+            "seed": 7,
+            "modalities": ["text"]
+        }"#;
+        let partial: PartialRequest = serde_json::from_str(json).unwrap();
+        assert_eq!(
+            partial.unknown_fields.keys().collect::<Vec<_>>(),
+            ["modalities", "seed"]
+        );
+
+        let request = Request::from_json_partial(json)
+            .unwrap()
+            .model(Model::GPT4oMini)
+            .input("What changed?")
+            .build();
+        assert_eq!(request.model, Model::GPT4oMini);
+        assert_eq!(request.instructions.as_deref(), Some("Answer briefly"));
+        assert_eq!(request.max_output_tokens, Some(300));
+        assert_eq!(request.tools.as_ref().map(Vec::len), Some(1));
+        assert_eq!(request.prompt_cache_key.as_deref(), Some("faq-v2"));
+        assert_eq!(request.temperature, None);
+        let serialized = serde_json::to_value(&request).unwrap();
+        assert!(serialized.get("seed").is_none());
+        assert_eq!(serialized["input"], "What changed?");
+
+        // Fields set on the builder before the partial survive unless it overrides them
+        let request = Request::builder()
+            .model(Model::GPT5)
+            .temperature(0.4)
+            .apply_partial(serde_json::from_str(r#"{"temperature": 0.9}"#).unwrap())
+            .build();
+        assert_eq!(request.model, Model::GPT5);
+        assert_eq!(request.temperature, Some(0.9));
+
+        // Known fields with the wrong shape are still errors
+        assert!(matches!(
+            Request::from_json_partial(r#"{"max_output_tokens": "lots"}"#),
+            Err(crate::Error::Json(_))
+        ));
+        assert!(Request::from_json_partial("[]").is_err());
+    }
+}
@@ -0,0 +1,330 @@
+//! Writes streamed image generation partials to disk as they arrive.
+
+use crate::types::StreamEvent;
+use std::path::PathBuf;
+
+/// Default file name template for partial images
+pub const DEFAULT_PARTIAL_IMAGE_TEMPLATE: &str = "image_partial_{index}.png";
+
+/// Writes the partial images of a streamed image generation call to a directory.
+///
+/// Each [`StreamEvent::ImageProgress`] carrying base64 data is decoded and written as
+/// the template with `{index}` replaced by its partial index, so a UI can show
+/// progressive previews. Partials without an index are numbered after the highest one
+/// seen, and a repeated index overwrites its file.
+///
+/// The final image is written from the finished response with
+/// [`handle_response`](Self::handle_response). If the stream fails before that,
+/// [`finalize`](Self::finalize) copies the latest partial to the final name instead, so
+/// the best image received is kept.
+///
+/// ```rust,no_run
+/// # async fn run(client: open_ai_rust_responses_by_sshift::Client, request: open_ai_rust_responses_by_sshift::Request) -> open_ai_rust_responses_by_sshift::Result<()> {
+/// use futures::StreamExt;
+/// use open_ai_rust_responses_by_sshift::types::PartialImageWriter;
+///
+/// let mut writer = PartialImageWriter::new("previews", "cat_{index}.png")?;
+/// let mut stream = client.responses.stream(request);
+/// while let Some(Ok(event)) = stream.next().await {
+///     if let Some(path) = writer.handle(&event)? {
+///         println!("preview at {}", path.display());
+///     }
+/// }
+/// // Written as previews/cat_final.png unless a final image was handled
+/// println!("{:?}", writer.finalize()?);
+/// # Ok(())
+/// # }
+/// ```
+#[derive(Debug, Clone)]
+pub struct PartialImageWriter {
+    dir: PathBuf,
+    template: String,
+    final_name: String,
+    /// Index and path of the highest partial written so far
+    latest: Option<(u32, PathBuf)>,
+    /// Path of the completed image, once one has been written
+    completed: Option<PathBuf>,
+}
+
+impl PartialImageWriter {
+    /// Creates a writer for `dir` that names partials after `template`, in which
+    /// `{index}` is replaced by the partial index.
+    ///
+    /// The final image is named after the template with `{index}` replaced by `final`.
+    ///
+    /// # Errors
+    ///
+    /// Returns an [`Error::Io`](crate::Error::Io) of kind `InvalidInput` if `template` has
+    /// no `{index}`, since every partial would then overwrite the same file.
+    pub fn new(dir: impl Into<PathBuf>, template: impl Into<String>) -> crate::Result<Self> {
+        let template = template.into();
+        if !template.contains("{index}") {
+            return Err(std::io::Error::new(
+                std::io::ErrorKind::InvalidInput,
+                format!("partial image template `{template}` has no `{{index}}`"),
+            )
+            .into());
+        }
+        Ok(Self::with_template(dir.into(), template))
+    }
+
+    fn with_template(dir: PathBuf, template: String) -> Self {
+        Self {
+            dir,
+            final_name: template.replace("{index}", "final"),
+            template,
+            latest: None,
+            completed: None,
+        }
+    }
+
+    /// Sets the file name of the final image
+    #[must_use]
+    pub fn with_final_name(mut self, final_name: impl Into<String>) -> Self {
+        self.final_name = final_name.into();
+        self
+    }
+
+    /// Path of the final image, whether or not it has been written yet
+    #[must_use]
+    pub fn final_path(&self) -> PathBuf {
+        self.dir.join(&self.final_name)
+    }
+
+    /// Path of the latest partial written, if any
+    #[must_use]
+    pub fn latest_partial(&self) -> Option<&std::path::Path> {
+        self.latest.as_ref().map(|(_, path)| path.as_path())
+    }
+
+    /// Writes the partial image carried by `event` and returns its path.
+    ///
+    /// Returns `Ok(None)` for events without partial image data.
+    ///
+    /// # Errors
+    ///
+    /// Returns [`Error::Decode`](crate::Error::Decode) if the data is not valid base64,
+    /// or [`Error::Io`](crate::Error::Io) if the file cannot be written.
+    pub fn handle(&mut self, event: &StreamEvent) -> crate::Result<Option<PathBuf>> {
+        let StreamEvent::ImageProgress {
+            partial_image_b64: Some(b64),
+            partial_image_index,
+            ..
+        } = event
+        else {
+            return Ok(None);
+        };
+
+        let bytes = decode(b64)?;
+        let index = partial_image_index.unwrap_or_else(|| {
+            self.latest
+                .as_ref()
+                .map_or(0, |(highest, _)| highest.saturating_add(1))
+        });
+        let path = self
+            .dir
+            .join(self.template.replace("{index}", &index.to_string()));
+        std::fs::create_dir_all(&self.dir)?;
+        std::fs::write(&path, bytes)?;
+
+        if self
+            .latest
+            .as_ref()
+            .is_none_or(|(highest, _)| index >= *highest)
+        {
+            self.latest = Some((index, path.clone()));
+        }
+        Ok(Some(path))
+    }
+
+    /// Writes the first completed image generation in `response` as the final image and
+    /// returns its path.
+    ///
+    /// Returns `Ok(None)` if the response has no completed image with data.
+    ///
+    /// # Errors
+    ///
+    /// Returns [`Error::Decode`](crate::Error::Decode) if the image is not valid base64,
+    /// or [`Error::Io`](crate::Error::Io) if the file cannot be written.
+    pub fn handle_response(
+        &mut self,
+        response: &crate::Response,
+    ) -> crate::Result<Option<PathBuf>> {
+        let Some(image) = response
+            .image_generations()
+            .into_iter()
+            .find(|image| image.status == "completed" && !image.result.is_empty())
+        else {
+            return Ok(None);
+        };
+
+        let path = self.final_path();
+        std::fs::create_dir_all(&self.dir)?;
+        std::fs::write(&path, image.to_bytes()?)?;
+        self.completed = Some(path.clone());
+        Ok(Some(path))
+    }
+
+    /// Makes sure the final image exists and returns its path.
+    ///
+    /// Keeps the completed image if one was handled; otherwise copies the latest partial
+    /// to the final name, unless the final name is that partial's own file. Returns
+    /// `Ok(None)` if no image arrived at all.
+    ///
+    /// # Errors
+    ///
+    /// Returns [`Error::Io`](crate::Error::Io) if the partial cannot be copied.
+    pub fn finalize(self) -> crate::Result<Option<PathBuf>> {
+        if let Some(path) = self.completed {
+            return Ok(Some(path));
+        }
+        let Some((_, partial)) = &self.latest else {
+            return Ok(None);
+        };
+        let path = self.final_path();
+        // Copying a file onto itself truncates it
+        if *partial != path {
+            std::fs::copy(partial, &path)?;
+        }
+        Ok(Some(path))
+    }
+}
+
+impl Default for PartialImageWriter {
+    /// Writes to the current directory with [`DEFAULT_PARTIAL_IMAGE_TEMPLATE`]
+    fn default() -> Self {
+        Self::with_template(".".into(), DEFAULT_PARTIAL_IMAGE_TEMPLATE.to_string())
+    }
+}
+
+fn decode(b64: &str) -> crate::Result<Vec<u8>> {
+    use base64::Engine as _;
+    base64::engine::general_purpose::STANDARD
+        .decode(b64)
+        .map_err(|error| crate::Error::Decode(error.to_string()))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use base64::Engine as _;
+
+    fn partial(index: Option<u32>, bytes: &[u8]) -> StreamEvent {
+        StreamEvent::ImageProgress {
+            url: None,
+            index: 0,
+            partial_image_b64: Some(base64::engine::general_purpose::STANDARD.encode(bytes)),
+            partial_image_index: index,
+        }
+    }
+
+    fn temp_dir(name: &str) -> PathBuf {
+        let dir = std::env::temp_dir().join(format!("{name}-{}", std::process::id()));
+        let _ = std::fs::remove_dir_all(&dir);
+        dir
+    }
+
+    #[test]
+    fn partials_are_written_and_the_latest_is_promoted() {
+        let dir = temp_dir("partial-images-promote");
+        let mut writer = PartialImageWriter::new(&dir, DEFAULT_PARTIAL_IMAGE_TEMPLATE).unwrap();
+
+        let first = writer.handle(&partial(Some(0), b"first")).unwrap().unwrap();
+        assert_eq!(first, dir.join("image_partial_0.png"));
+        writer.handle(&partial(Some(2), b"third")).unwrap();
+        // Late and repeated indices overwrite their file but never replace a newer partial
+        writer.handle(&partial(Some(1), b"second")).unwrap();
+        writer.handle(&partial(Some(1), b"second again")).unwrap();
+        // Without an index the partial comes after the highest one
+        let unnumbered = writer.handle(&partial(None, b"fourth")).unwrap().unwrap();
+        assert_eq!(unnumbered, dir.join("image_partial_3.png"));
+
+        assert_eq!(writer.handle(&StreamEvent::Done).unwrap(), None);
+        assert!(writer
+            .handle(&StreamEvent::ImageProgress {
+                url: Some("https://example.com/a.png".to_string()),
+                index: 0,
+                partial_image_b64: None,
+                partial_image_index: None,
+            })
+            .unwrap()
+            .is_none());
+        assert!(matches!(
+            writer.handle(&StreamEvent::ImageProgress {
+                url: None,
+                index: 0,
+                partial_image_b64: Some("not base64!".to_string()),
+                partial_image_index: Some(9),
+            }),
+            Err(crate::Error::Decode(_))
+        ));
+
+        assert_eq!(
+            std::fs::read(dir.join("image_partial_1.png")).unwrap(),
+            b"second again"
+        );
+        assert_eq!(writer.latest_partial(), Some(unnumbered.as_path()));
+        let final_path = writer.finalize().unwrap().unwrap();
+        assert_eq!(final_path, dir.join("image_partial_final.png"));
+        assert_eq!(std::fs::read(final_path).unwrap(), b"fourth");
+        std::fs::remove_dir_all(&dir).unwrap();
+    }
+
+    #[test]
+    fn completed_image_wins_over_partials() {
+        let dir = temp_dir("partial-images-complete");
+        let mut writer = PartialImageWriter::new(&dir, "cat_{index}.png")
+            .unwrap()
+            .with_final_name("cat.png");
+        writer.handle(&partial(Some(0), b"blurry")).unwrap();
+
+        let response = crate::Response::builder()
+            .with_output_item(crate::types::ResponseItem::ImageGenerationCall {
+                id: "ig_1".to_string(),
+                result: base64::engine::general_purpose::STANDARD.encode(b"sharp"),
+                status: "completed".to_string(),
+                revised_prompt: None,
+                output_format: None,
+                size: None,
+                quality: None,
+                background: None,
+            })
+            .build();
+        let path = writer.handle_response(&response).unwrap().unwrap();
+        assert_eq!(path, dir.join("cat.png"));
+        assert_eq!(writer.finalize().unwrap(), Some(path.clone()));
+        assert_eq!(std::fs::read(path).unwrap(), b"sharp");
+        std::fs::remove_dir_all(&dir).unwrap();
+
+        // Nothing arrived, so there is nothing to keep
+        assert_eq!(
+            PartialImageWriter::new(&dir, "x_{index}.png")
+                .unwrap()
+                .finalize()
+                .unwrap(),
+            None
+        );
+        assert!(!dir.exists());
+    }
+
+    #[test]
+    fn finalizing_onto_the_latest_partial_keeps_its_bytes() {
+        let dir = temp_dir("partial-images-same-path");
+        let mut writer = PartialImageWriter::new(&dir, "cat_{index}.png")
+            .unwrap()
+            .with_final_name("cat_1.png");
+        writer.handle(&partial(Some(0), b"blurry")).unwrap();
+        writer.handle(&partial(Some(1), b"sharper")).unwrap();
+
+        let path = writer.finalize().unwrap().unwrap();
+        assert_eq!(path, dir.join("cat_1.png"));
+        assert_eq!(std::fs::read(path).unwrap(), b"sharper");
+        std::fs::remove_dir_all(&dir).unwrap();
+
+        let error = PartialImageWriter::new(&dir, "cat.png").unwrap_err();
+        assert!(
+            matches!(&error, crate::Error::Io(io) if io.kind() == std::io::ErrorKind::InvalidInput),
+            "{error:?}"
+        );
+    }
+}
@@ -1,9 +1,12 @@
+use crate::types::ValidationError;
 use serde::{Deserialize, Serialize};
 
-/// Effort level for reasoning models
-#[derive(Debug, Clone, Serialize, Deserialize, PartialEq, Default)]
+/// Effort level for reasoning models, sent as `reasoning.effort`
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, PartialEq, Eq, Default)]
 #[serde(rename_all = "lowercase")]
 pub enum Effort {
+    /// Minimal reasoning tokens for fast, deterministic tasks (GPT-5)
+    Minimal,
     /// Low effort reasoning - faster responses
     #[default]
     Low,
@@ -13,6 +16,31 @@ pub enum Effort {
     High,
 }
 
+impl Effort {
+    /// Returns the wire name, e.g. `"minimal"`
+    #[must_use]
+    pub fn as_str(self) -> &'static str {
+        match self {
+            Self::Minimal => "minimal",
+            Self::Low => "low",
+            Self::Medium => "medium",
+            Self::High => "high",
+        }
+    }
+
+    /// Parses a wire name, ignoring case; `None` for levels this crate does not know
+    #[must_use]
+    pub fn parse(value: &str) -> Option<Self> {
+        [Self::Minimal, Self::Low, Self::Medium, Self::High]
+            .into_iter()
+            .find(|effort| value.eq_ignore_ascii_case(effort.as_str()))
+    }
+}
+
+/// Earlier name of [`Effort`], kept for the GPT-5 builder
+/// [`RequestBuilder::reasoning_effort`](crate::RequestBuilder::reasoning_effort)
+pub type ReasoningEffort = Effort;
+
 /// Summary setting for reasoning output
 #[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
 #[serde(rename_all = "lowercase")]
@@ -25,6 +53,18 @@ pub enum SummarySetting {
     Detailed,
 }
 
+impl SummarySetting {
+    /// Returns the wire name, e.g. `"concise"`
+    #[must_use]
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            Self::Auto => "auto",
+            Self::Concise => "concise",
+            Self::Detailed => "detailed",
+        }
+    }
+}
+
 impl From<&str> for SummarySetting {
     fn from(s: &str) -> Self {
         match s {
@@ -35,21 +75,66 @@ impl From<&str> for SummarySetting {
     }
 }
 
-/// Reasoning parameters for controlling reasoning model behavior
-#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+/// Reasoning parameters for controlling reasoning model behavior.
+///
+/// Serializes to the fields both the o-series and GPT-5 accept: `effort` and `summary`.
+/// [`generate_summary`](Self::generate_summary) is the deprecated name of `summary` and is
+/// sent as `summary` when that is unset. Which efforts a model takes is checked by
+/// [`RequestBuilder::build_validated`](crate::RequestBuilder::build_validated).
+#[derive(Debug, Clone, Deserialize, PartialEq)]
+#[serde(from = "ReasoningParamsWire")]
 pub struct ReasoningParams {
-    /// Effort level for reasoning (low/medium/high)
-    #[serde(skip_serializing_if = "Option::is_none")]
+    /// Effort level for reasoning (minimal/low/medium/high); `minimal` is GPT-5 only
     pub effort: Option<Effort>,
 
     /// Summary setting for reasoning output
-    #[serde(skip_serializing_if = "Option::is_none")]
     pub summary: Option<SummarySetting>,
 
-    /// GPT-5 reasoning effort control (minimal/medium/high)
-    /// Internal only: we derive `effort` for wire format as API expects
-    #[serde(skip_serializing)]
-    pub reasoning_effort: Option<ReasoningEffort>,
+    /// Deprecated API name for [`summary`](Self::summary), still read from older payloads
+    pub generate_summary: Option<SummarySetting>,
+}
+
+/// Accepts the `reasoning_effort` key that older versions of this crate wrote next to
+/// `effort`; `effort` wins if both are present
+#[derive(Deserialize)]
+struct ReasoningParamsWire {
+    #[serde(default)]
+    effort: Option<Effort>,
+    #[serde(default)]
+    summary: Option<SummarySetting>,
+    #[serde(default)]
+    generate_summary: Option<SummarySetting>,
+    #[serde(default)]
+    reasoning_effort: Option<Effort>,
+}
+
+impl From<ReasoningParamsWire> for ReasoningParams {
+    fn from(wire: ReasoningParamsWire) -> Self {
+        Self {
+            effort: wire.effort.or(wire.reasoning_effort),
+            summary: wire.summary,
+            generate_summary: wire.generate_summary,
+        }
+    }
+}
+
+/// The `reasoning` object as sent
+#[derive(Serialize)]
+struct ReasoningParamsOut<'a> {
+    #[serde(skip_serializing_if = "Option::is_none")]
+    effort: Option<Effort>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    summary: Option<&'a SummarySetting>,
+}
+
+impl Serialize for ReasoningParams {
+    fn serialize<S: serde::Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        ReasoningParamsOut {
+            effort: self.effort,
+            summary: self.summary.as_ref().or(self.generate_summary.as_ref()),
+        }
+        .serialize(serializer)
+    }
 }
 
 impl ReasoningParams {
@@ -59,7 +144,7 @@ impl ReasoningParams {
         Self {
             effort: None,
             summary: None,
-            reasoning_effort: None,
+            generate_summary: None,
         }
     }
 
@@ -77,19 +162,50 @@ impl ReasoningParams {
         self
     }
 
-    /// Set GPT-5 reasoning effort level; also map to `effort` for backward compatibility
+    /// Set the summary under its deprecated `generate_summary` name; sent as `summary`
     #[must_use]
-    pub fn with_reasoning_effort(mut self, effort: ReasoningEffort) -> Self {
-        self.effort = Some(match effort {
-            ReasoningEffort::Minimal => Effort::Low,
-            ReasoningEffort::Medium => Effort::Medium,
-            ReasoningEffort::High => Effort::High,
-        });
-        self.reasoning_effort = Some(effort);
+    pub fn with_generate_summary(mut self, summary: SummarySetting) -> Self {
+        self.generate_summary = Some(summary);
         self
     }
 
-    // (second with_reasoning_effort removed; deduplicated)
+    /// Checks the parameters against what `model` accepts.
+    ///
+    /// # Errors
+    ///
+    /// Returns [`ValidationError::ReasoningNotSupported`] for models without reasoning,
+    /// [`ValidationError::UnsupportedReasoningEffort`] for an effort the model rejects
+    /// (e.g. `minimal` on the o-series), or [`ValidationError::ConflictingReasoningSummary`]
+    /// if `summary` and `generate_summary` disagree.
+    pub fn validate_for(&self, model: &crate::types::Model) -> Result<(), ValidationError> {
+        if !model.supports_reasoning() {
+            return Err(ValidationError::ReasoningNotSupported {
+                model: model.to_string(),
+            });
+        }
+        if let Some(effort) = self.effort.filter(|effort| !model.supports_effort(*effort)) {
+            return Err(ValidationError::UnsupportedReasoningEffort {
+                model: model.to_string(),
+                effort: effort.as_str().to_string(),
+            });
+        }
+        if let (Some(summary), Some(generate_summary)) = (&self.summary, &self.generate_summary) {
+            if summary != generate_summary {
+                return Err(ValidationError::ConflictingReasoningSummary {
+                    summary: summary.as_str().to_string(),
+                    generate_summary: generate_summary.as_str().to_string(),
+                });
+            }
+        }
+        Ok(())
+    }
+
+    /// Set GPT-5 reasoning effort level
+    #[deprecated(note = "`ReasoningEffort` is now `Effort`; use `with_effort`")]
+    #[must_use]
+    pub fn with_reasoning_effort(self, effort: ReasoningEffort) -> Self {
+        self.with_effort(effort)
+    }
 
     /// Enable medium effort reasoning (balanced speed and thoroughness)
     #[must_use]
@@ -170,18 +286,6 @@ impl ReasoningParams {
     }
 }
 
-/// Reasoning effort levels for GPT-5 models
-#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
-#[serde(rename_all = "lowercase")]
-pub enum ReasoningEffort {
-    /// Minimal reasoning tokens for fast, deterministic tasks
-    Minimal,
-    /// Balanced reasoning depth and performance (default)
-    Medium,
-    /// Maximum reasoning depth for complex problem-solving
-    High,
-}
-
 impl Default for ReasoningParams {
     fn default() -> Self {
         Self::new()
@@ -208,6 +312,10 @@ mod tests {
 
     #[test]
     fn test_effort_serialization() {
+        assert_eq!(
+            serde_json::to_string(&Effort::Minimal).expect("minimal should serialize"),
+            r#""minimal""#
+        );
         let effort_low = Effort::Low;
         let effort_medium = Effort::Medium;
         let effort_high = Effort::High;
@@ -269,4 +377,225 @@ mod tests {
             r#""detailed""#
         );
     }
+
+    /// The `reasoning` object a built request serializes to, checking nothing else
+    /// mentions an effort
+    fn sent_reasoning(builder: crate::RequestBuilder) -> serde_json::Value {
+        let body = serde_json::to_value(builder.model("gpt-5").input("hi").build()).unwrap();
+        let mut keys: Vec<_> = body.as_object().unwrap().keys().cloned().collect();
+        keys.retain(|key| key.contains("effort"));
+        assert!(keys.is_empty(), "stray effort keys: {keys:?}");
+        body["reasoning"].clone()
+    }
+
+    #[test]
+    #[allow(deprecated)]
+    fn every_builder_combination_sends_one_reasoning_object() {
+        use serde_json::json;
+        let builder = crate::Request::builder;
+        let cases = [
+            (
+                builder().reasoning_effort(ReasoningEffort::Minimal),
+                json!({"effort": "minimal"}),
+            ),
+            (
+                builder().reasoning(ReasoningParams::high_effort_with_summary()),
+                json!({"effort": "high", "summary": "auto"}),
+            ),
+            (
+                builder()
+                    .reasoning(ReasoningParams::concise_summary())
+                    .reasoning_effort(Effort::Medium),
+                json!({"effort": "medium", "summary": "concise"}),
+            ),
+            (
+                builder()
+                    .reasoning_effort(Effort::Minimal)
+                    .reasoning(ReasoningParams::detailed_summary()),
+                json!({"effort": "minimal", "summary": "detailed"}),
+            ),
+            (
+                builder()
+                    .reasoning_effort(Effort::Minimal)
+                    .reasoning(ReasoningParams::high_effort()),
+                json!({"effort": "high"}),
+            ),
+            (
+                builder()
+                    .reasoning(ReasoningParams::high_effort_concise())
+                    .reasoning_effort(ReasoningEffort::Minimal),
+                json!({"effort": "minimal", "summary": "concise"}),
+            ),
+            (
+                builder().reasoning(ReasoningParams::new().with_reasoning_effort(Effort::Low)),
+                json!({"effort": "low"}),
+            ),
+            (builder(), serde_json::Value::Null),
+        ];
+        for (builder, expected) in cases {
+            assert_eq!(sent_reasoning(builder), expected);
+        }
+    }
+
+    #[test]
+    fn old_reasoning_shapes_still_deserialize() {
+        let legacy: ReasoningParams =
+            serde_json::from_str(r#"{"reasoning_effort":"minimal","summary":"auto"}"#).unwrap();
+        assert_eq!(legacy.effort, Some(Effort::Minimal));
+        assert_eq!(legacy.summary, Some(SummarySetting::Auto));
+        // Older versions wrote both keys; `effort` was the one on the wire
+        let both: ReasoningParams =
+            serde_json::from_str(r#"{"effort":"low","reasoning_effort":"minimal"}"#).unwrap();
+        assert_eq!(both.effort, Some(Effort::Low));
+
+        let response = |extra: &str| -> crate::Response {
+            serde_json::from_str(&format!(
+                r#"{{"id":"resp_1","object":"response","created_at":1700000000,"model":"gpt-5","status":"completed","output":[]{extra}}}"#
+            ))
+            .unwrap()
+        };
+        assert_eq!(
+            response(r#","reasoning":{"effort":"medium","summary":null}"#).effort(),
+            Some(Effort::Medium)
+        );
+        assert_eq!(
+            response(r#","reasoning_effort":"minimal""#).effort(),
+            Some(Effort::Minimal)
+        );
+        assert_eq!(
+            response(r#","reasoning":{"effort":"high"},"reasoning_effort":"low""#).effort(),
+            Some(Effort::High)
+        );
+        assert_eq!(
+            response(r#","reasoning":{"effort":"xhigh"}"#).effort(),
+            None
+        );
+        assert_eq!(response("").effort(), None);
+    }
+
+    /// The `reasoning` object sent for `model`, after validation
+    fn wire_reasoning(model: &str, reasoning: ReasoningParams) -> String {
+        let request = crate::Request::builder()
+            .model(model)
+            .input("hi")
+            .reasoning(reasoning)
+            .build_validated()
+            .unwrap();
+        serde_json::to_string(&request.reasoning).unwrap()
+    }
+
+    #[test]
+    fn o3_reasoning_wire_format() {
+        assert_eq!(
+            wire_reasoning("o3", ReasoningParams::high_effort_detailed()),
+            r#"{"effort":"high","summary":"detailed"}"#
+        );
+        assert_eq!(
+            wire_reasoning(
+                "o3",
+                ReasoningParams::new().with_generate_summary(SummarySetting::Auto)
+            ),
+            r#"{"summary":"auto"}"#
+        );
+    }
+
+    #[test]
+    fn o4_mini_reasoning_wire_format() {
+        assert_eq!(
+            wire_reasoning(
+                "o4-mini",
+                ReasoningParams::new()
+                    .with_effort(Effort::Low)
+                    .with_generate_summary(SummarySetting::Concise)
+            ),
+            r#"{"effort":"low","summary":"concise"}"#
+        );
+        assert_eq!(
+            wire_reasoning("o4-mini", ReasoningParams::medium_effort()),
+            r#"{"effort":"medium"}"#
+        );
+    }
+
+    #[test]
+    fn gpt5_reasoning_wire_format() {
+        assert_eq!(
+            wire_reasoning(
+                "gpt-5",
+                ReasoningParams::new()
+                    .with_effort(Effort::Minimal)
+                    .with_summary(SummarySetting::Auto)
+                    .with_generate_summary(SummarySetting::Auto)
+            ),
+            r#"{"effort":"minimal","summary":"auto"}"#
+        );
+        let request = crate::Request::builder()
+            .model("gpt-5-mini")
+            .input("hi")
+            .generate_summary(SummarySetting::Detailed)
+            .reasoning(ReasoningParams::high_effort())
+            .build();
+        assert_eq!(
+            serde_json::to_string(&request.reasoning).unwrap(),
+            r#"{"effort":"high","summary":"detailed"}"#
+        );
+        // The deprecated key is read back into its own field
+        let parsed: ReasoningParams =
+            serde_json::from_str(r#"{"effort":"minimal","generate_summary":"concise"}"#).unwrap();
+        assert_eq!(parsed.generate_summary, Some(SummarySetting::Concise));
+        assert_eq!(parsed.summary, None);
+    }
+
+    #[test]
+    fn build_validated_rejects_what_the_model_family_does_not_take() {
+        let error = |model: &str, reasoning: ReasoningParams| match crate::Request::builder()
+            .model(model)
+            .input("hi")
+            .reasoning(reasoning)
+            .build_validated()
+        {
+            Err(crate::Error::Validation(error)) => error,
+            other => panic!("expected a validation error, got {other:?}"),
+        };
+
+        assert_eq!(
+            error("o3", ReasoningParams::new().with_effort(Effort::Minimal)),
+            ValidationError::UnsupportedReasoningEffort {
+                model: "o3".to_string(),
+                effort: "minimal".to_string(),
+            }
+        );
+        assert!(matches!(
+            error("o1-mini", ReasoningParams::medium_effort()),
+            ValidationError::UnsupportedReasoningEffort { .. }
+        ));
+        assert_eq!(
+            error("gpt-4o", ReasoningParams::auto_summary()),
+            ValidationError::ReasoningNotSupported {
+                model: "gpt-4o".to_string(),
+            }
+        );
+        assert_eq!(
+            error(
+                "gpt-5",
+                ReasoningParams::concise_summary().with_generate_summary(SummarySetting::Detailed)
+            ),
+            ValidationError::ConflictingReasoningSummary {
+                summary: "concise".to_string(),
+                generate_summary: "detailed".to_string(),
+            }
+        );
+        // Custom o-series names follow the o-series rules; other custom models are trusted
+        assert!(matches!(
+            error(
+                "o5-preview",
+                ReasoningParams::new().with_effort(Effort::Minimal)
+            ),
+            ValidationError::UnsupportedReasoningEffort { .. }
+        ));
+        assert!(crate::Request::builder()
+            .model("my-finetune")
+            .reasoning(ReasoningParams::new().with_effort(Effort::Minimal))
+            .build_validated()
+            .is_ok());
+    }
 }
@@ -38,6 +38,16 @@ impl Include {
             Self::ReasoningEncryptedContent => "reasoning.encrypted_content",
         }
     }
+
+    /// Type prefix of the tool whose output this include adds to, if any
+    fn tool_type_prefix(&self) -> Option<&'static str> {
+        match self {
+            Self::FileSearchResults => Some("file_search"),
+            Self::WebSearchResults => Some("web_search"),
+            Self::ComputerCallOutputImageUrl => Some("computer_use"),
+            Self::MessageInputImageUrl | Self::ReasoningEncryptedContent => None,
+        }
+    }
 }
 
 impl std::fmt::Display for Include {
@@ -59,7 +69,10 @@ pub struct Request {
     #[serde(skip_serializing_if = "Option::is_none")]
     pub instructions: Option<String>,
 
-    /// The maximum number of tokens to generate (alias for max_output_tokens)
+    /// Legacy alias for `max_output_tokens`.
+    ///
+    /// Never sent alongside `max_output_tokens`: building the request or sending it moves
+    /// this value into `max_output_tokens`, or drops it if that is already set.
     #[serde(skip_serializing_if = "Option::is_none")]
     pub max_tokens: Option<u32>,
 
@@ -101,7 +114,7 @@ pub struct Request {
 
     /// Additional metadata to include in the response
     #[serde(skip_serializing_if = "Option::is_none")]
-    pub metadata: Option<serde_json::Value>,
+    pub metadata: Option<crate::types::Metadata>,
 
     /// Additional fields to include in the response
     #[serde(skip_serializing_if = "Option::is_none")]
@@ -129,9 +142,23 @@ pub struct Request {
     #[serde(skip_serializing_if = "Option::is_none")]
     pub text: Option<crate::types::TextConfig>,
 
-    /// User identifier for tracking and abuse prevention
+    /// User identifier for tracking and abuse prevention.
+    ///
+    /// Superseded by `safety_identifier` and `prompt_cache_key`, but still sent if set.
     #[serde(skip_serializing_if = "Option::is_none")]
     pub user: Option<String>,
+
+    /// Stable identifier for the end user, used for abuse monitoring
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub safety_identifier: Option<String>,
+
+    /// Key that groups requests sharing a prompt prefix to improve cache hit rates
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub prompt_cache_key: Option<String>,
+
+    /// Processing tier (`auto`, `default`, `flex` or `priority`)
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub service_tier: Option<crate::types::ServiceTier>,
 }
 
 impl Default for Request {
@@ -158,14 +185,33 @@ impl Default for Request {
             truncation: None,
             text: None,
             user: None,
+            safety_identifier: None,
+            prompt_cache_key: None,
+            service_tier: None,
         }
     }
 }
 
-/// Builder for creating requests
+/// Builder for creating requests.
+///
+/// Text set with [`input`](Self::input) and the parts added by the image and audio
+/// helpers go into one user message, in the order of the calls, so
+/// `.input("Describe this").input_image_url(url)` sends the text and the image together.
+/// Consecutive text is joined with a blank line. The user message is sent as plain text
+/// input when it holds nothing but text.
+///
+/// [`input_items`](Self::input_items), [`input_from_responses`](Self::input_from_responses)
+/// and [`with_function_outputs`](Self::with_function_outputs) replace all input added so far;
+/// text and images added after them form a user message that follows their items.
 #[derive(Debug, Clone)]
 pub struct RequestBuilder {
     request: Request,
+    /// Parts of the user message built from `input` and the image and audio helpers
+    content: Vec<serde_json::Value>,
+    max_input_items: Option<usize>,
+    developer_messages: Vec<String>,
+    sampling: Option<crate::types::SamplingPreset>,
+    max_image_bytes: usize,
 }
 
 impl Default for RequestBuilder {
@@ -180,6 +226,11 @@ impl RequestBuilder {
     pub fn new() -> Self {
         Self {
             request: Request::default(),
+            content: Vec::new(),
+            max_input_items: None,
+            developer_messages: Vec::new(),
+            sampling: None,
+            max_image_bytes: crate::types::IMAGE_MAX_BYTES,
         }
     }
 
@@ -190,20 +241,64 @@ impl RequestBuilder {
         self
     }
 
-    /// Sets the input text
+    /// Adds text to the user message; text added right after other text is joined to it
+    /// with a blank line
     #[must_use]
     pub fn input(mut self, input: impl Into<String>) -> Self {
-        self.request.input = crate::types::Input::Text(input.into());
-        self
+        let input = input.into();
+        if let Some(text) = self
+            .content
+            .last_mut()
+            .filter(|part| part["type"] == "input_text")
+            .and_then(|part| part.get_mut("text"))
+        {
+            *text = format!("{}\n\n{input}", text.as_str().unwrap_or_default()).into();
+            return self;
+        }
+        self.push_user_content(crate::types::InputItem::content_text(input))
     }
 
-    /// Sets the input items
+    /// Sets the input items, replacing all input added so far
     #[must_use]
     pub fn input_items(mut self, items: Vec<crate::types::InputItem>) -> Self {
+        self.content.clear();
         self.request.input = crate::types::Input::Items(items);
         self
     }
 
+    /// Sets the input to the replayed output of a chain of responses, oldest first,
+    /// replacing all input added so far.
+    ///
+    /// See [`Input::from_responses`](crate::types::Input::from_responses).
+    #[must_use]
+    pub fn input_from_responses(mut self, history: &[crate::types::Response]) -> Self {
+        self.content.clear();
+        self.request.input = crate::types::Input::from_responses(history);
+        self
+    }
+
+    /// Appends an input item after the input added so far, closing the user message
+    /// built from text and images first
+    #[must_use]
+    pub fn push_input_item(mut self, item: crate::types::InputItem) -> Self {
+        self.flush_content();
+        match &mut self.request.input {
+            crate::types::Input::Items(items) => items.push(item),
+            crate::types::Input::Text(text) => {
+                let mut items = Vec::with_capacity(2);
+                if !text.is_empty() {
+                    items.push(crate::types::InputItem::message(
+                        "user",
+                        vec![crate::types::InputItem::content_text(std::mem::take(text))],
+                    ));
+                }
+                items.push(item);
+                self.request.input = crate::types::Input::Items(items);
+            }
+        }
+        self
+    }
+
     /// Sets the system instructions
     #[must_use]
     pub fn instructions(mut self, instructions: impl Into<String>) -> Self {
@@ -211,13 +306,62 @@ impl RequestBuilder {
         self
     }
 
-    /// Sets the maximum number of tokens to generate (legacy parameter)
+    /// Adds a `developer` role message ahead of the input.
+    ///
+    /// This is the recommended way to steer GPT-5 models. Developer messages are
+    /// placed before the items set with `input`, `input_items` or the image helpers
+    /// when the request is built, so the order of builder calls does not matter.
+    /// Text input is turned into a user message to make room for them.
+    #[must_use]
+    pub fn developer_message(mut self, message: impl Into<String>) -> Self {
+        self.developer_messages.push(message.into());
+        self
+    }
+
+    /// Copies the instructions a previous response was created with.
+    ///
+    /// Instructions are not carried over by `previous_response_id`, so a follow-up
+    /// request has none unless they are set again. Does nothing if the response
+    /// reports no instructions.
+    #[must_use]
+    pub fn inherit_instructions_from(mut self, response: &crate::types::Response) -> Self {
+        if let Some(instructions) = response.instructions_used() {
+            self.request.instructions = Some(instructions.to_string());
+        }
+        self
+    }
+
+    /// Merges a [`RequestTemplate`](crate::types::RequestTemplate) into the request.
+    ///
+    /// Fields the template sets override the ones set so far; see the template's
+    /// documentation for how tools, includes and metadata are combined. Setters
+    /// called afterwards override the template in turn.
     #[must_use]
-    pub fn max_tokens(mut self, max_tokens: u32) -> Self {
-        self.request.max_tokens = Some(max_tokens);
+    pub fn apply_template(mut self, template: &crate::types::RequestTemplate) -> Self {
+        template.apply_to(&mut self.request);
         self
     }
 
+    /// Sets every field the [`PartialRequest`](crate::types::PartialRequest) holds,
+    /// leaving the rest untouched.
+    ///
+    /// An `input` in the partial request replaces all input added so far.
+    #[must_use]
+    pub fn apply_partial(mut self, partial: crate::types::PartialRequest) -> Self {
+        if partial.input.is_some() {
+            self.content.clear();
+        }
+        partial.apply_to(&mut self.request);
+        self
+    }
+
+    /// Sets the maximum number of output tokens (legacy name for [`RequestBuilder::max_output_tokens`])
+    #[deprecated(note = "use `max_output_tokens`, which this now sets")]
+    #[must_use]
+    pub fn max_tokens(self, max_tokens: u32) -> Self {
+        self.max_output_tokens(max_tokens)
+    }
+
     /// Sets the maximum number of output tokens to generate
     #[must_use]
     pub fn max_output_tokens(mut self, max_output_tokens: u32) -> Self {
@@ -239,6 +383,18 @@ impl RequestBuilder {
         self
     }
 
+    /// Sets `temperature` and `top_p` from a named preset.
+    ///
+    /// The preset is resolved for the request's model when it is built, so the call order
+    /// does not matter; see [`SamplingPreset::resolve`](crate::types::SamplingPreset::resolve).
+    /// Values set with [`temperature`](Self::temperature) or [`top_p`](Self::top_p) win
+    /// over the preset's, and models that reject sampling parameters get neither.
+    #[must_use]
+    pub fn sampling(mut self, preset: crate::types::SamplingPreset) -> Self {
+        self.sampling = Some(preset);
+        self
+    }
+
     /// Sets the number of top log probabilities to return
     #[must_use]
     pub fn top_logprobs(mut self, top_logprobs: u32) -> Self {
@@ -260,6 +416,16 @@ impl RequestBuilder {
         self
     }
 
+    /// Adds tools, de-duplicating by [`Tool::identity_key`](crate::types::Tool::identity_key).
+    ///
+    /// A tool with the same type and name (or MCP server label) as one already set, or
+    /// as an earlier one in `tools`, replaces it in place, so the last definition wins.
+    #[must_use]
+    pub fn merge_tools(mut self, tools: Vec<crate::types::Tool>) -> Self {
+        crate::types::tools::merge_tools(self.request.tools.get_or_insert_with(Vec::new), tools);
+        self
+    }
+
     /// Sets which tool is called by the model
     #[must_use]
     pub fn tool_choice(mut self, tool_choice: crate::types::ToolChoice) -> Self {
@@ -283,8 +449,21 @@ impl RequestBuilder {
 
     /// Sets additional metadata to include in the response
     #[must_use]
-    pub fn metadata(mut self, metadata: serde_json::Value) -> Self {
-        self.request.metadata = Some(metadata);
+    pub fn metadata(mut self, metadata: impl Into<crate::types::Metadata>) -> Self {
+        self.request.metadata = Some(metadata.into());
+        self
+    }
+
+    /// Adds a single metadata entry.
+    ///
+    /// Limits are not checked here; use [`RequestBuilder::build_validated`] to
+    /// report keys or values the API would reject.
+    #[must_use]
+    pub fn metadata_entry(mut self, key: impl Into<String>, value: impl Into<String>) -> Self {
+        self.request
+            .metadata
+            .get_or_insert_with(crate::types::Metadata::new)
+            .insert_unchecked(key.into(), value.into());
         self
     }
 
@@ -320,8 +499,16 @@ impl RequestBuilder {
     }
 
     /// Sets reasoning parameters for controlling reasoning model behavior (NEW: May 2025)
+    ///
+    /// Without an effort in `reasoning`, an effort set earlier with
+    /// [`RequestBuilder::reasoning_effort`] is kept, and likewise a summary set with
+    /// [`RequestBuilder::generate_summary`].
     #[must_use]
-    pub fn reasoning(mut self, reasoning: crate::types::ReasoningParams) -> Self {
+    pub fn reasoning(mut self, mut reasoning: crate::types::ReasoningParams) -> Self {
+        if let Some(previous) = self.request.reasoning.take() {
+            reasoning.effort = reasoning.effort.or(previous.effort);
+            reasoning.generate_summary = reasoning.generate_summary.or(previous.generate_summary);
+        }
         self.request.reasoning = Some(reasoning);
         self
     }
@@ -342,14 +529,26 @@ impl RequestBuilder {
         self
     }
 
-    /// Sets truncation configuration for automatic context management
+    /// Sets the server-side truncation strategy (`auto` or `disabled`)
     #[must_use]
     pub fn truncation(mut self, truncation: crate::types::TruncationSetting) -> Self {
         self.request.truncation = Some(truncation);
         self
     }
 
-    /// Sets text generation configuration
+    /// Drops the oldest input items at build time so at most `max_items` are sent.
+    ///
+    /// This is client-side truncation for stateless histories; see
+    /// [`Input::truncate_to_items`](crate::types::Input::truncate_to_items) for which
+    /// items are kept.
+    #[must_use]
+    pub fn truncate_input_to_items(mut self, max_items: usize) -> Self {
+        self.max_input_items = Some(max_items);
+        self
+    }
+
+    /// Sets text generation configuration, replacing any verbosity or stop sequences
+    /// set earlier
     #[must_use]
     pub fn text(mut self, text: crate::types::TextConfig) -> Self {
         self.request.text = Some(text);
@@ -359,43 +558,105 @@ impl RequestBuilder {
     /// Sets the verbosity level (GPT-5) in text config
     #[must_use]
     pub fn verbosity(mut self, verbosity: crate::types::Verbosity) -> Self {
-        let mut cfg = self
-            .request
-            .text
-            .take()
-            .unwrap_or(crate::types::TextConfig {
-                format: None,
-                stop: None,
-                verbosity: None,
-            });
-        cfg.verbosity = Some(verbosity);
-        self.request.text = Some(cfg);
+        self.text_config().verbosity = Some(verbosity);
+        self
+    }
+
+    /// Sets the stop sequences, sent as `text.stop`, replacing any set earlier.
+    ///
+    /// The rest of the text config is kept. [`RequestBuilder::build_validated`] checks
+    /// the API limits: at most [`STOP_SEQUENCES_MAX`](crate::types::STOP_SEQUENCES_MAX)
+    /// sequences, none of them empty.
+    #[must_use]
+    pub fn stop_sequences(mut self, sequences: Vec<String>) -> Self {
+        self.text_config().stop = Some(sequences);
+        self
+    }
+
+    /// Adds a stop sequence to `text.stop`
+    #[must_use]
+    pub fn stop(mut self, sequence: impl Into<String>) -> Self {
+        self.text_config()
+            .stop
+            .get_or_insert_with(Vec::new)
+            .push(sequence.into());
+        self
+    }
+
+    fn text_config(&mut self) -> &mut crate::types::TextConfig {
+        self.request.text.get_or_insert_with(Default::default)
+    }
+
+    /// Sets the reasoning effort, sent as `reasoning.effort`.
+    ///
+    /// Shorthand for [`RequestBuilder::reasoning`] with only an effort; the summary
+    /// setting of an earlier `reasoning` call is kept.
+    #[must_use]
+    pub fn reasoning_effort(mut self, effort: crate::types::Effort) -> Self {
+        self.request
+            .reasoning
+            .get_or_insert_with(crate::types::ReasoningParams::new)
+            .effort = Some(effort);
         self
     }
 
-    /// Sets GPT-5 reasoning effort level (top-level)
+    /// Sets `reasoning.generate_summary`, sent as `reasoning.summary`.
+    ///
+    /// Shorthand like [`RequestBuilder::reasoning_effort`]; an earlier effort is kept.
     #[must_use]
-    pub fn reasoning_effort(mut self, effort: crate::types::ReasoningEffort) -> Self {
-        // Ensure reasoning object exists and map effort accordingly
-        let reasoning = self
-            .request
+    pub fn generate_summary(mut self, summary: crate::types::SummarySetting) -> Self {
+        self.request
             .reasoning
-            .take()
-            .unwrap_or_default()
-            .with_reasoning_effort(effort);
-        self.request.reasoning = Some(reasoning);
+            .get_or_insert_with(crate::types::ReasoningParams::new)
+            .generate_summary = Some(summary);
         self
     }
 
-    /// Sets user identifier
+    /// Sets the service tier.
+    ///
+    /// Non-streaming creates on [`ServiceTier::Flex`](crate::types::ServiceTier::Flex) get a
+    /// longer per-request timeout; see [`ServiceTier::default_timeout`](crate::types::ServiceTier::default_timeout).
+    #[must_use]
+    pub fn service_tier(mut self, service_tier: crate::types::ServiceTier) -> Self {
+        self.request.service_tier = Some(service_tier);
+        self
+    }
+
+    /// Sets user identifier.
+    ///
+    /// For abuse monitoring prefer [`RequestBuilder::safety_identifier`], and for cache
+    /// routing [`RequestBuilder::prompt_cache_key`].
     #[must_use]
     pub fn user(mut self, user: impl Into<String>) -> Self {
         self.request.user = Some(user.into());
         self
     }
 
-    /// Creates a request to continue a conversation with function call outputs
+    /// Sets a stable identifier for the end user, used for abuse monitoring.
+    ///
+    /// Hash usernames or emails rather than sending them directly.
+    #[must_use]
+    pub fn safety_identifier(mut self, safety_identifier: impl Into<String>) -> Self {
+        self.request.safety_identifier = Some(safety_identifier.into());
+        self
+    }
+
+    /// Sets the prompt cache key, grouping requests that share a long prompt prefix
+    #[must_use]
+    pub fn prompt_cache_key(mut self, prompt_cache_key: impl Into<String>) -> Self {
+        self.request.prompt_cache_key = Some(prompt_cache_key.into());
+        self
+    }
+
+    /// Creates a request to continue a conversation with function call outputs,
+    /// replacing all input added so far.
     /// This is the correct way to submit tool results in the Responses API
+    ///
+    /// Each `(call_id, output)` pair becomes an
+    /// [`InputItem::function_call_output`](crate::types::InputItem::function_call_output);
+    /// the calls themselves are read from the response named by `previous_response_id`.
+    /// Without a stored response, send [`InputItem::function_call`](crate::types::InputItem::function_call)
+    /// items followed by their outputs through [`input_items`](Self::input_items) instead.
     #[must_use]
     pub fn with_function_outputs(
         mut self,
@@ -409,162 +670,405 @@ impl RequestBuilder {
             .map(|(call_id, output)| crate::types::InputItem::function_call_output(call_id, output))
             .collect();
 
+        self.content.clear();
         self.request.input = crate::types::Input::Items(input_items);
         self
     }
 
-    /// Sets the input as a single image URL in a user message
+    /// Adds an image URL to the user message
     #[must_use]
-    pub fn input_image_url(mut self, url: impl Into<String>) -> Self {
-        let message = crate::types::InputItem::message(
-            "user",
-            vec![crate::types::InputItem::content_image(url)],
-        );
-        self.request.input = crate::types::Input::Items(vec![message]);
-        self
+    pub fn input_image_url(self, url: impl Into<String>) -> Self {
+        self.push_user_content(crate::types::InputItem::content_image(url))
     }
 
-    /// Sets the input as a single image URL with detail level in a user message
+    /// Adds an image URL with detail level to the user message
     #[must_use]
     pub fn input_image_url_with_detail(
-        mut self,
+        self,
         url: impl Into<String>,
         detail: impl Into<String>,
     ) -> Self {
-        let message = crate::types::InputItem::message(
-            "user",
-            vec![crate::types::InputItem::content_image_with_detail(
-                url, detail,
-            )],
-        );
-        self.request.input = crate::types::Input::Items(vec![message]);
-        self
+        self.push_user_content(crate::types::InputItem::content_image_with_detail(
+            url, detail,
+        ))
     }
 
-    /// Sets the input as a single base64 image in a user message
+    /// Adds a base64 image to the user message
     #[must_use]
     pub fn input_image_base64(
-        mut self,
+        self,
         base64_data: impl Into<String>,
         mime_type: impl Into<String>,
     ) -> Self {
-        let message = crate::types::InputItem::message(
-            "user",
-            vec![crate::types::InputItem::content_image_base64(
-                base64_data,
-                mime_type,
-            )],
-        );
-        self.request.input = crate::types::Input::Items(vec![message]);
-        self
+        self.push_user_content(crate::types::InputItem::content_image_base64(
+            base64_data,
+            mime_type,
+        ))
     }
 
-    /// Sets the input as a single base64 image with detail level in a user message
+    /// Adds a base64 image with detail level to the user message
     #[must_use]
     pub fn input_image_base64_with_detail(
-        mut self,
+        self,
         base64_data: impl Into<String>,
         mime_type: impl Into<String>,
         detail: impl Into<String>,
     ) -> Self {
-        let message = crate::types::InputItem::message(
-            "user",
-            vec![crate::types::InputItem::content_image_base64_with_detail(
-                base64_data,
-                mime_type,
-                detail,
-            )],
-        );
-        self.request.input = crate::types::Input::Items(vec![message]);
-        self
+        self.push_user_content(crate::types::InputItem::content_image_base64_with_detail(
+            base64_data,
+            mime_type,
+            detail,
+        ))
     }
 
-    /// Sets the input as a single file ID image in a user message
+    /// Adds an uploaded image, by file ID, to the user message
     #[must_use]
-    pub fn input_image_file_id(mut self, file_id: impl Into<String>) -> Self {
-        let message = crate::types::InputItem::message(
-            "user",
-            vec![crate::types::InputItem::content_image_file_id(file_id)],
-        );
-        self.request.input = crate::types::Input::Items(vec![message]);
-        self
+    pub fn input_image_file_id(self, file_id: impl Into<String>) -> Self {
+        self.push_user_content(crate::types::InputItem::content_image_file_id(file_id))
     }
 
-    /// Sets the input as a single file ID image with detail level in a user message
+    /// Adds an uploaded image, by file ID, with detail level to the user message
     #[must_use]
     pub fn input_image_file_id_with_detail(
-        mut self,
+        self,
         file_id: impl Into<String>,
         detail: impl Into<String>,
     ) -> Self {
-        let message = crate::types::InputItem::message(
-            "user",
-            vec![crate::types::InputItem::content_image_file_id_with_detail(
-                file_id, detail,
-            )],
-        );
-        self.request.input = crate::types::Input::Items(vec![message]);
-        self
+        self.push_user_content(crate::types::InputItem::content_image_file_id_with_detail(
+            file_id, detail,
+        ))
     }
 
-    /// Sets the input as multiple image URLs in a single user message
+    /// Adds several image URLs to the user message
     #[must_use]
     pub fn input_image_urls<I, S>(mut self, urls: I) -> Self
     where
         I: IntoIterator<Item = S>,
         S: Into<String>,
     {
-        let content: Vec<serde_json::Value> = urls
-            .into_iter()
-            .map(|u| crate::types::InputItem::content_image(u))
-            .collect();
-        let message = crate::types::InputItem::message("user", content);
-        self.request.input = crate::types::Input::Items(vec![message]);
+        self.content
+            .extend(urls.into_iter().map(crate::types::InputItem::content_image));
         self
     }
 
-    /// Appends a single image URL to the current user message. If no message
-    /// exists yet it behaves like `input_image_url`.
+    /// Adds an image URL to the user message; the same as
+    /// [`input_image_url`](Self::input_image_url)
     #[must_use]
-    pub fn push_image_url(mut self, url: impl Into<String>) -> Self {
-        match &mut self.request.input {
-            crate::types::Input::Items(items)
-                if !items.is_empty() && items[0].item_type == "message" =>
-            {
-                if let Some(serde_json::Value::Array(content)) = items[0].content.as_mut() {
-                    content.push(crate::types::InputItem::content_image(url));
-                } else {
-                    // Fallback: rebuild the message content correctly
-                    let message = crate::types::InputItem::message(
+    pub fn push_image_url(self, url: impl Into<String>) -> Self {
+        self.input_image_url(url)
+    }
+
+    /// Adds audio to the user message, base64-encoding it
+    #[must_use]
+    pub fn input_audio_bytes(
+        self,
+        bytes: impl AsRef<[u8]>,
+        format: crate::types::AudioFormat,
+    ) -> Self {
+        use base64::Engine as _;
+        let data = base64::engine::general_purpose::STANDARD.encode(bytes);
+        self.push_user_content(crate::types::InputItem::content_audio_base64(data, format))
+    }
+
+    fn push_user_content(mut self, part: serde_json::Value) -> Self {
+        self.content.push(part);
+        self
+    }
+
+    /// Adds the user message built from text and images to the input: as plain text
+    /// input when it only holds text and there is no other input, otherwise as a user
+    /// message after the other input
+    fn flush_content(&mut self) {
+        if self.content.is_empty() {
+            return;
+        }
+        let mut content = std::mem::take(&mut self.content);
+        let input = std::mem::replace(
+            &mut self.request.input,
+            crate::types::Input::Text(String::new()),
+        );
+        self.request.input = match input {
+            crate::types::Input::Text(text) if text.is_empty() && content.len() == 1 => {
+                match content.pop() {
+                    Some(serde_json::Value::Object(mut part)) if part["type"] == "input_text" => {
+                        match part.remove("text") {
+                            Some(serde_json::Value::String(text)) => {
+                                crate::types::Input::Text(text)
+                            }
+                            _ => unreachable!("text parts hold their text as a string"),
+                        }
+                    }
+                    part => crate::types::Input::Items(vec![crate::types::InputItem::message(
                         "user",
-                        vec![crate::types::InputItem::content_image(url)],
-                    );
-                    *items = vec![message];
+                        part.into_iter().collect(),
+                    )]),
                 }
             }
-            _ => {
-                // No existing message – create one
-                let message = crate::types::InputItem::message(
-                    "user",
-                    vec![crate::types::InputItem::content_image(url)],
-                );
-                self.request.input = crate::types::Input::Items(vec![message]);
+            crate::types::Input::Text(text) => {
+                let mut items = Vec::with_capacity(2);
+                if !text.is_empty() {
+                    items.push(crate::types::InputItem::message(
+                        "user",
+                        vec![crate::types::InputItem::content_text(text)],
+                    ));
+                }
+                items.push(crate::types::InputItem::message("user", content));
+                crate::types::Input::Items(items)
             }
-        }
+            crate::types::Input::Items(mut items) => {
+                items.push(crate::types::InputItem::message("user", content));
+                crate::types::Input::Items(items)
+            }
+        };
+    }
+
+    /// Sets the largest inline (base64) image, in decoded bytes, that
+    /// [`build_validated`](Self::build_validated) accepts. Defaults to
+    /// [`IMAGE_MAX_BYTES`](crate::types::IMAGE_MAX_BYTES).
+    #[must_use]
+    pub fn max_image_bytes(mut self, max_bytes: usize) -> Self {
+        self.max_image_bytes = max_bytes;
         self
     }
 
     /// Builds the request
     #[must_use]
-    pub fn build(self) -> Request {
+    pub fn build(mut self) -> Request {
+        self.flush_content();
+        self.request.reconcile_max_tokens();
+        self.apply_sampling();
+        self.apply_developer_messages();
+        self.apply_input_truncation();
         self.request
     }
+
+    /// Builds the request, checking it against API limits first
+    ///
+    /// # Errors
+    ///
+    /// Returns [`Error::Validation`](crate::Error::Validation) describing the first
+    /// violation found, such as a metadata key or value that is too long, two tools with
+    /// the same name, an allowed-tools `tool_choice` naming a tool the request does not
+    /// define, `max_output_tokens` above what the model can produce, an inline image
+    /// over [`max_image_bytes`](Self::max_image_bytes), or more than four or empty stop
+    /// sequences.
+    pub fn build_validated(mut self) -> crate::Result<Request> {
+        self.flush_content();
+        if let Some(metadata) = &self.request.metadata {
+            metadata.validate()?;
+        }
+        self.request.input.check_image_sizes(self.max_image_bytes)?;
+        if let Some(text) = &self.request.text {
+            text.validate()?;
+        }
+        if let Some(tools) = &self.request.tools {
+            let names = crate::types::tools::duplicate_tool_names(tools);
+            if !names.is_empty() {
+                return Err(crate::types::ValidationError::DuplicateTools { names }.into());
+            }
+        }
+        let names = crate::types::tools::unknown_allowed_tools(
+            self.request.tool_choice.as_ref(),
+            self.request.tools.as_deref().unwrap_or_default(),
+        );
+        if !names.is_empty() {
+            return Err(crate::types::ValidationError::UnknownAllowedTools { names }.into());
+        }
+        if let Some(name) = crate::types::tools::unresolved_tool_choice(
+            self.request.tool_choice.as_ref(),
+            self.request.tools.as_deref().unwrap_or_default(),
+        ) {
+            return Err(crate::types::ValidationError::UnknownToolChoice { name }.into());
+        }
+        if self.request.previous_response_id.is_some() && self.request.store == Some(false) {
+            return Err(crate::types::ValidationError::PreviousResponseWithoutStore.into());
+        }
+        for warning in self.request.consistency_warnings() {
+            crate::diag::warn!("{warning}");
+        }
+        if let Some(reasoning) = &self.request.reasoning {
+            reasoning.validate_for(&self.request.model)?;
+        }
+        self.request.reconcile_max_tokens();
+        if let (Some(requested), Some(max)) = (
+            self.request.max_output_tokens,
+            self.request.model.max_output_tokens(),
+        ) {
+            if requested > max {
+                return Err(crate::types::ValidationError::MaxOutputTokensExceeded {
+                    model: self.request.model.to_string(),
+                    requested,
+                    max,
+                }
+                .into());
+            }
+        }
+        self.apply_sampling();
+        self.apply_developer_messages();
+        self.apply_input_truncation();
+        Ok(self.request)
+    }
+
+    fn apply_sampling(&mut self) {
+        if let Some(preset) = self.sampling.take() {
+            preset.apply_to(&mut self.request);
+        }
+    }
+
+    fn apply_developer_messages(&mut self) {
+        if self.developer_messages.is_empty() {
+            return;
+        }
+
+        let developer = self.developer_messages.drain(..).map(|message| {
+            crate::types::InputItem::message(
+                "developer",
+                vec![crate::types::InputItem::content_text(message)],
+            )
+        });
+        match &mut self.request.input {
+            crate::types::Input::Items(items) => {
+                items.splice(0..0, developer);
+            }
+            crate::types::Input::Text(text) => {
+                let mut items: Vec<_> = developer.collect();
+                if !text.is_empty() {
+                    items.push(crate::types::InputItem::message(
+                        "user",
+                        vec![crate::types::InputItem::content_text(std::mem::take(text))],
+                    ));
+                }
+                self.request.input = crate::types::Input::Items(items);
+            }
+        }
+    }
+
+    fn apply_input_truncation(&mut self) {
+        if let Some(max_items) = self.max_input_items {
+            let dropped = self.request.input.truncate_to_items(max_items);
+            if dropped > 0 {
+                crate::diag::debug!(
+                    "Dropped {dropped} oldest input item(s) to keep at most {max_items}"
+                );
+            }
+        }
+    }
 }
 
 impl Request {
+    /// Serializes to JSON for audit logs, with the default [`RedactionConfig`](crate::types::RedactionConfig)
+    #[must_use]
+    pub fn to_audit_json(&self) -> serde_json::Value {
+        self.to_audit_json_with(&crate::types::RedactionConfig::default())
+    }
+
+    /// Serializes to JSON for audit logs, masking secrets and shortening inline data per `config`
+    #[must_use]
+    pub fn to_audit_json_with(&self, config: &crate::types::RedactionConfig) -> serde_json::Value {
+        config.audit(self)
+    }
+
+    /// Settings that are accepted but have no effect given the rest of the request:
+    /// includes for tools the request does not define, and `parallel_tool_calls`
+    /// without any tools.
+    ///
+    /// [`RequestBuilder::build_validated`] logs each of these as a warning.
+    #[must_use]
+    pub fn consistency_warnings(&self) -> Vec<crate::types::ValidationError> {
+        let tools = self.tools.as_deref().unwrap_or_default();
+        let mut warnings = Vec::new();
+        for include in self.include.iter().flatten() {
+            let Some(prefix) = include.tool_type_prefix() else {
+                continue;
+            };
+            if !tools.iter().any(|tool| tool.tool_type.starts_with(prefix)) {
+                warnings.push(crate::types::ValidationError::IncludeWithoutTool {
+                    include: include.as_str(),
+                    tool_type: prefix,
+                });
+            }
+        }
+        if self.parallel_tool_calls.is_some() && tools.is_empty() {
+            warnings.push(crate::types::ValidationError::ParallelToolCallsWithoutTools);
+        }
+        warnings
+    }
+
+    /// Limits the model to the request's own tools named in `names`, without changing
+    /// `tools`, by setting an allowed-tools [`tool_choice`](Self::tool_choice).
+    ///
+    /// Each name is a function or custom tool name, an MCP server label, or a built-in
+    /// tool type such as `"file_search"`. The model must call one of them if `tool_choice`
+    /// was already `required`; otherwise it may answer without a tool.
+    ///
+    /// # Errors
+    ///
+    /// Returns [`ValidationError::UnknownAllowedTools`](crate::types::ValidationError::UnknownAllowedTools)
+    /// naming every entry that matches none of the request's tools; `tool_choice` is
+    /// left unchanged.
+    pub fn restrict_tools_to(
+        &mut self,
+        names: &[&str],
+    ) -> Result<(), crate::types::ValidationError> {
+        use crate::types::{AllowedToolsMode, ToolChoice, ToolRef};
+
+        let tools = self.tools.as_deref().unwrap_or_default();
+        let mut allowed = Vec::with_capacity(names.len());
+        let mut unknown = Vec::new();
+        for &name in names {
+            let tool = tools.iter().find(|tool| {
+                let (tool_type, tool_name) = tool.identity_key();
+                tool_name == Some(name) || (tool_name.is_none() && tool_type == name)
+            });
+            match tool {
+                Some(tool) => allowed.push(ToolRef::for_tool(tool)),
+                None => unknown.push(name.to_string()),
+            }
+        }
+        if !unknown.is_empty() {
+            return Err(crate::types::ValidationError::UnknownAllowedTools { names: unknown });
+        }
+
+        let required = match &self.tool_choice {
+            Some(ToolChoice::Allowed(current)) => current.mode == AllowedToolsMode::Required,
+            Some(choice) => *choice == ToolChoice::required(),
+            None => false,
+        };
+        let mode = if required {
+            AllowedToolsMode::Required
+        } else {
+            AllowedToolsMode::Auto
+        };
+        self.tool_choice = Some(ToolChoice::allowed(mode, allowed));
+        Ok(())
+    }
+
+    /// Measures the serialized size of this request, in total and per field
+    #[must_use]
+    pub fn size_report(&self) -> crate::types::RequestSizeReport {
+        crate::types::RequestSizeReport::measure(self)
+    }
+
     /// Creates a new request builder
     #[must_use]
     pub fn builder() -> RequestBuilder {
         RequestBuilder::new()
     }
+    /// Folds the legacy `max_tokens` into `max_output_tokens` so only one is sent.
+    ///
+    /// `max_output_tokens` wins if both are set; a conflicting `max_tokens` is logged
+    /// and dropped.
+    pub(crate) fn reconcile_max_tokens(&mut self) {
+        let Some(max_tokens) = self.max_tokens.take() else {
+            return;
+        };
+        match self.max_output_tokens {
+            None => self.max_output_tokens = Some(max_tokens),
+            Some(max_output_tokens) if max_output_tokens != max_tokens => crate::diag::warn!(
+                "Request sets both max_tokens ({max_tokens}) and max_output_tokens \
+                 ({max_output_tokens}); sending max_output_tokens only"
+            ),
+            Some(_) => {}
+        }
+    }
 }
@@ -2,7 +2,7 @@ use chrono::{DateTime, Utc};
 use serde::{Deserialize, Serialize};
 
 /// Token usage information for the response
-#[derive(Debug, Clone, Deserialize, Serialize)]
+#[derive(Debug, Clone, PartialEq, Deserialize, Serialize)]
 pub struct Usage {
     /// Number of tokens in the input (including images and tools if any)
     pub input_tokens: u32,
@@ -17,29 +17,36 @@ pub struct Usage {
     #[serde(skip_serializing_if = "Option::is_none")]
     pub output_tokens_details: Option<OutputTokensDetails>,
 
-    /// Additional details about input tokens  
-    #[serde(skip_serializing_if = "Option::is_none")]
+    /// Additional details about input tokens (sent as `input_tokens_details` by the Responses API)
+    #[serde(
+        alias = "input_tokens_details",
+        skip_serializing_if = "Option::is_none"
+    )]
     pub prompt_tokens_details: Option<PromptTokensDetails>,
 
-    /// Number of web search tool calls
+    /// Number of completed web search tool calls
     #[serde(skip_serializing_if = "Option::is_none")]
     pub web_search: Option<u32>,
 
-    /// Number of file search tool calls
+    /// Number of completed file search tool calls
     #[serde(skip_serializing_if = "Option::is_none")]
     pub file_search: Option<u32>,
 
-    /// Number of image generation tool calls
+    /// Number of completed image generation tool calls
     #[serde(skip_serializing_if = "Option::is_none")]
     pub image_generation: Option<u32>,
 
-    /// Number of code interpreter tool calls
+    /// Number of completed code interpreter tool calls
     #[serde(skip_serializing_if = "Option::is_none")]
     pub code_interpreter: Option<u32>,
+
+    /// Every tool call by kind and outcome, filled in by [`Response::usage_with_tools`]
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub tool_usage: Option<crate::types::ToolUsageBreakdown>,
 }
 
 /// Details about output tokens
-#[derive(Debug, Clone, Deserialize, Serialize)]
+#[derive(Debug, Clone, PartialEq, Deserialize, Serialize)]
 pub struct OutputTokensDetails {
     /// Number of tokens used for reasoning (for reasoning models)
     #[serde(skip_serializing_if = "Option::is_none")]
@@ -47,7 +54,7 @@ pub struct OutputTokensDetails {
 }
 
 /// Details about input tokens
-#[derive(Debug, Clone, Deserialize, Serialize)]
+#[derive(Debug, Clone, PartialEq, Deserialize, Serialize)]
 pub struct PromptTokensDetails {
     /// Number of cached tokens
     #[serde(skip_serializing_if = "Option::is_none")]
@@ -55,14 +62,14 @@ pub struct PromptTokensDetails {
 }
 
 /// Details about incomplete responses
-#[derive(Debug, Clone, Deserialize, Serialize)]
+#[derive(Debug, Clone, PartialEq, Deserialize, Serialize)]
 pub struct IncompleteDetails {
     /// Reason the response was incomplete
     pub reason: String,
 }
 
 /// Error information in the response
-#[derive(Debug, Clone, Deserialize, Serialize)]
+#[derive(Debug, Clone, PartialEq, Deserialize, Serialize)]
 pub struct ResponseError {
     /// Error code
     pub code: String,
@@ -75,15 +82,22 @@ pub struct ResponseError {
     pub metadata: Option<serde_json::Value>,
 }
 
+/// Maximum number of stop sequences accepted by the API
+pub const STOP_SEQUENCES_MAX: usize = 4;
+
 /// Text generation configuration
-#[derive(Debug, Clone, Deserialize, Serialize)]
+#[derive(Debug, Clone, Default, PartialEq, Deserialize, Serialize)]
 pub struct TextConfig {
     /// Text format configuration
     #[serde(skip_serializing_if = "Option::is_none")]
     pub format: Option<TextFormat>,
 
-    /// Stop sequences
-    #[serde(skip_serializing_if = "Option::is_none")]
+    /// Stop sequences; a single string is accepted when deserializing
+    #[serde(
+        default,
+        skip_serializing_if = "Option::is_none",
+        deserialize_with = "stop_sequences"
+    )]
     pub stop: Option<Vec<String>>,
 
     /// Verbosity level for GPT-5 text responses
@@ -91,8 +105,50 @@ pub struct TextConfig {
     pub verbosity: Option<Verbosity>,
 }
 
+fn stop_sequences<'de, D>(deserializer: D) -> Result<Option<Vec<String>>, D::Error>
+where
+    D: serde::Deserializer<'de>,
+{
+    #[derive(Deserialize)]
+    #[serde(untagged)]
+    enum Stop {
+        One(String),
+        Many(Vec<String>),
+    }
+
+    Ok(
+        Option::<Stop>::deserialize(deserializer)?.map(|stop| match stop {
+            Stop::One(sequence) => vec![sequence],
+            Stop::Many(sequences) => sequences,
+        }),
+    )
+}
+
+impl TextConfig {
+    /// Checks the stop sequences against the API limits: at most
+    /// [`STOP_SEQUENCES_MAX`], none of them empty.
+    ///
+    /// # Errors
+    ///
+    /// Returns [`ValidationError::TooManyStopSequences`](crate::types::ValidationError::TooManyStopSequences)
+    /// or [`ValidationError::EmptyStopSequence`](crate::types::ValidationError::EmptyStopSequence).
+    pub fn validate(&self) -> Result<(), crate::types::ValidationError> {
+        let stop = self.stop.as_deref().unwrap_or_default();
+        if stop.len() > STOP_SEQUENCES_MAX {
+            return Err(crate::types::ValidationError::TooManyStopSequences {
+                count: stop.len(),
+                max: STOP_SEQUENCES_MAX,
+            });
+        }
+        if let Some(index) = stop.iter().position(String::is_empty) {
+            return Err(crate::types::ValidationError::EmptyStopSequence { index });
+        }
+        Ok(())
+    }
+}
+
 /// Text format configuration
-#[derive(Debug, Clone, Deserialize, Serialize)]
+#[derive(Debug, Clone, PartialEq, Deserialize, Serialize)]
 pub struct TextFormat {
     /// Format type (e.g., "text")
     #[serde(rename = "type")]
@@ -108,18 +164,90 @@ pub enum Verbosity {
     High,
 }
 
-/// Truncation configuration - can be either a string ("disabled", "auto") or a config object
-#[derive(Debug, Clone, Deserialize, Serialize)]
-#[serde(untagged)]
+/// Truncation strategy sent with a request, serialized as `"auto"` or `"disabled"`
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Deserialize, Serialize)]
+#[serde(rename_all = "lowercase")]
 pub enum TruncationSetting {
-    /// Simple string setting like "disabled" or "auto"
-    Simple(String),
-    /// Full configuration object
+    /// Drop items from the middle of the conversation when it exceeds the context window
+    Auto,
+    /// Fail with an error instead of truncating (API default)
+    #[default]
+    Disabled,
+}
+
+/// Processing tier for a request, trading latency against cost.
+///
+/// Sent as `service_tier` on the request; the response echoes the tier that was
+/// actually used, which may differ when `auto` or `default` is requested.
+#[derive(Debug, Clone, Default, PartialEq, Eq, Hash, Deserialize, Serialize)]
+#[serde(rename_all = "lowercase")]
+pub enum ServiceTier {
+    /// Let the project settings decide (API default)
+    #[default]
+    Auto,
+    /// Standard processing
+    Default,
+    /// Lower cost, slower and subject to capacity; responses can take minutes
+    Flex,
+    /// Faster processing at a higher price
+    Priority,
+    /// A tier this crate does not recognize, kept as sent
+    #[serde(untagged)]
+    Unknown(String),
+}
+
+/// Per-request timeout applied to flex-tier creates, which can queue for minutes
+pub const FLEX_REQUEST_TIMEOUT: std::time::Duration = std::time::Duration::from_secs(900);
+
+impl ServiceTier {
+    /// Returns the wire value of the tier
+    #[must_use]
+    pub fn as_str(&self) -> &str {
+        match self {
+            Self::Auto => "auto",
+            Self::Default => "default",
+            Self::Flex => "flex",
+            Self::Priority => "priority",
+            Self::Unknown(tier) => tier,
+        }
+    }
+
+    /// Timeout applied to non-streaming creates on this tier, overriding the HTTP client's.
+    ///
+    /// Only [`ServiceTier::Flex`] has one ([`FLEX_REQUEST_TIMEOUT`]); other tiers use the
+    /// client's timeout.
+    #[must_use]
+    pub const fn default_timeout(&self) -> Option<std::time::Duration> {
+        match self {
+            Self::Flex => Some(FLEX_REQUEST_TIMEOUT),
+            _ => None,
+        }
+    }
+}
+
+impl std::fmt::Display for ServiceTier {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.write_str(self.as_str())
+    }
+}
+
+/// Truncation reported on a response.
+///
+/// The API echoes the request setting as a string, but older payloads carry a
+/// [`TruncationConfig`] object, so both shapes are accepted.
+#[derive(Debug, Clone, PartialEq, Deserialize, Serialize)]
+#[serde(untagged)]
+pub enum ResponseTruncation {
+    /// String form, e.g. `"auto"`
+    Setting(TruncationSetting),
+    /// Object form, e.g. `{"type": "auto", "last_messages": 10}`
     Config(TruncationConfig),
+    /// A string value this crate does not know yet
+    Other(String),
 }
 
 /// Truncation configuration object
-#[derive(Debug, Clone, Deserialize, Serialize)]
+#[derive(Debug, Clone, PartialEq, Eq, Deserialize, Serialize)]
 pub struct TruncationConfig {
     /// Truncation type (e.g., "auto", "disabled")
     #[serde(rename = "type")]
@@ -131,7 +259,7 @@ pub struct TruncationConfig {
 }
 
 /// Reasoning output from the model
-#[derive(Debug, Clone, Deserialize, Serialize)]
+#[derive(Debug, Clone, PartialEq, Deserialize, Serialize)]
 pub struct ReasoningOutput {
     /// Reasoning trace content (encrypted when using store=false)
     #[serde(skip_serializing_if = "Option::is_none")]
@@ -140,10 +268,14 @@ pub struct ReasoningOutput {
     /// Encrypted reasoning content for stateless mode
     #[serde(skip_serializing_if = "Option::is_none")]
     pub encrypted_content: Option<String>,
+
+    /// Effort level echoed by the API, as sent; see [`Response::effort`]
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub effort: Option<String>,
 }
 
 /// Individual reasoning content item
-#[derive(Debug, Clone, Deserialize, Serialize)]
+#[derive(Debug, Clone, PartialEq, Deserialize, Serialize)]
 pub struct ReasoningContent {
     /// Type of reasoning content
     #[serde(rename = "type")]
@@ -154,8 +286,104 @@ pub struct ReasoningContent {
     pub text: Option<String>,
 }
 
+/// Lifecycle status of a response.
+///
+/// Parsed case-insensitively; statuses this crate does not recognize are kept in
+/// [`Other`](Self::Other) as sent. Compares equal to its wire string, so
+/// `response.status == "completed"` keeps working.
+#[derive(Debug, Clone, Default, PartialEq, Eq, Hash)]
+pub enum ResponseStatus {
+    /// The response finished successfully
+    #[default]
+    Completed,
+    /// The response failed; see [`Response::error`]
+    Failed,
+    /// The response is being generated
+    InProgress,
+    /// The response stopped early; see [`Response::incomplete_details`]
+    Incomplete,
+    /// The response was cancelled
+    Cancelled,
+    /// A background response is waiting to start
+    Queued,
+    /// A status this crate does not recognize
+    Other(String),
+}
+
+impl ResponseStatus {
+    /// Parses a status string, ignoring case
+    #[must_use]
+    pub fn parse(status: &str) -> Self {
+        match status.to_ascii_lowercase().as_str() {
+            "completed" => Self::Completed,
+            "failed" => Self::Failed,
+            "in_progress" => Self::InProgress,
+            "incomplete" => Self::Incomplete,
+            "cancelled" => Self::Cancelled,
+            "queued" => Self::Queued,
+            _ => Self::Other(status.to_string()),
+        }
+    }
+
+    /// Returns the status as sent on the wire
+    #[must_use]
+    pub fn as_str(&self) -> &str {
+        match self {
+            Self::Completed => "completed",
+            Self::Failed => "failed",
+            Self::InProgress => "in_progress",
+            Self::Incomplete => "incomplete",
+            Self::Cancelled => "cancelled",
+            Self::Queued => "queued",
+            Self::Other(status) => status,
+        }
+    }
+}
+
+impl std::fmt::Display for ResponseStatus {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.write_str(self.as_str())
+    }
+}
+
+impl PartialEq<str> for ResponseStatus {
+    fn eq(&self, other: &str) -> bool {
+        self.as_str() == other
+    }
+}
+
+impl PartialEq<&str> for ResponseStatus {
+    fn eq(&self, other: &&str) -> bool {
+        self.as_str() == *other
+    }
+}
+
+impl Serialize for ResponseStatus {
+    fn serialize<S>(&self, serializer: S) -> Result<S::Ok, S::Error>
+    where
+        S: serde::Serializer,
+    {
+        serializer.serialize_str(self.as_str())
+    }
+}
+
+impl<'de> Deserialize<'de> for ResponseStatus {
+    fn deserialize<D>(deserializer: D) -> Result<Self, D::Error>
+    where
+        D: serde::Deserializer<'de>,
+    {
+        let status = crate::types::lenient::status(deserializer)?;
+        Ok(Self::parse(&status))
+    }
+}
+
 /// Response from the OpenAI Responses API
-#[derive(Debug, Clone, Deserialize, Serialize)]
+///
+/// New fields are added as the API grows, and each one breaks code that builds a
+/// `Response` with a struct literal. Build them with [`Response::builder`] instead. The
+/// struct is not `#[non_exhaustive]` yet, because that would itself break existing
+/// literals; it may become so in a future major release.
+#[derive(Debug, Clone, PartialEq, Deserialize, Serialize)]
 pub struct Response {
     /// Unique identifier for the response
     pub id: String,
@@ -165,17 +393,27 @@ pub struct Response {
     pub object: String,
 
     /// Creation timestamp
-    #[serde(with = "chrono::serde::ts_seconds")]
+    #[serde(with = "crate::types::lenient::timestamp")]
     pub created_at: DateTime<Utc>,
 
+    /// When the response finished, if the API reported it
+    #[serde(
+        default,
+        skip_serializing_if = "Option::is_none",
+        with = "crate::types::lenient::timestamp_option"
+    )]
+    pub completed_at: Option<DateTime<Utc>>,
+
     /// The model used to generate the response
     pub model: String,
 
-    /// Current status of the response (queued | in_progress | completed | cancelled | failed)
-    #[serde(default = "default_status")]
-    pub status: String,
+    /// Current status of the response; `completed` when the payload leaves it out
+    #[serde(default)]
+    pub status: ResponseStatus,
 
-    /// The output items generated by the model
+    /// The output items generated by the model, in the order the API returns them.
+    ///
+    /// An item's position here is the `output_index` its stream events carry.
     pub output: Vec<crate::types::ResponseItem>,
 
     /// Convenience field containing merged output text
@@ -192,7 +430,7 @@ pub struct Response {
 
     /// Optional metadata associated with the response
     #[serde(skip_serializing_if = "Option::is_none")]
-    pub metadata: Option<serde_json::Value>,
+    pub metadata: Option<crate::types::Metadata>,
 
     /// Token usage statistics
     #[serde(skip_serializing_if = "Option::is_none")]
@@ -232,16 +470,20 @@ pub struct Response {
 
     /// Truncation configuration used
     #[serde(skip_serializing_if = "Option::is_none")]
-    pub truncation: Option<TruncationSetting>,
+    pub truncation: Option<ResponseTruncation>,
 
     /// Reasoning output (for reasoning models)
     #[serde(skip_serializing_if = "Option::is_none")]
     pub reasoning: Option<ReasoningOutput>,
 
-    /// Reasoning effort level used
+    /// Reasoning effort level used, as echoed by older API versions; see [`Response::effort`]
     #[serde(skip_serializing_if = "Option::is_none")]
     pub reasoning_effort: Option<String>,
 
+    /// Service tier that processed the request
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub service_tier: Option<ServiceTier>,
+
     /// User identifier
     #[serde(skip_serializing_if = "Option::is_none")]
     pub user: Option<String>,
@@ -259,33 +501,135 @@ fn default_object_type() -> String {
     "response".to_string()
 }
 
-fn default_status() -> String {
-    "completed".to_string()
-}
-
 impl Response {
+    /// Deserializes a response body without the base64 images in
+    /// [`ImageGenerationCall`](crate::ResponseItem::ImageGenerationCall) results.
+    ///
+    /// The image strings are skipped while parsing and `result` is left empty, which keeps
+    /// memory use close to the size of the body. Pair with
+    /// [`Responses::create_raw`](crate::responses::Responses::create_raw).
+    ///
+    /// # Errors
+    ///
+    /// Returns [`Error::Json`](crate::Error::Json) if the body is not a valid response.
+    pub fn from_slice_lite(bytes: &[u8]) -> crate::Result<Self> {
+        crate::types::lite::from_slice(bytes).map_err(crate::Error::Json)
+    }
+
+    /// Serializes to JSON for audit logs, with the default [`RedactionConfig`](crate::types::RedactionConfig)
+    #[must_use]
+    pub fn to_audit_json(&self) -> serde_json::Value {
+        self.to_audit_json_with(&crate::types::RedactionConfig::default())
+    }
+
+    /// Serializes to JSON for audit logs, masking secrets and shortening inline data per `config`
+    #[must_use]
+    pub fn to_audit_json_with(&self, config: &crate::types::RedactionConfig) -> serde_json::Value {
+        config.audit(self)
+    }
+
+    /// Returns a copy for snapshot comparisons, with volatile fields replaced.
+    ///
+    /// Ids (including `previous_response_id`, call ids and container ids) become
+    /// placeholders such as `resp_1`, `msg_1` or `call_2`, numbered per prefix in encounter
+    /// order; an id that appears twice gets the same placeholder both times, so a
+    /// `function_call_output` still points at its call. Timestamps are set to the unix
+    /// epoch and `usage` is removed.
+    #[must_use]
+    pub fn normalized(&self) -> Self {
+        crate::types::snapshot::normalize(self)
+    }
+
+    /// Lists the output items that differ from `other` once both are [normalized](Self::normalized)
+    ///
+    /// Only `output` is compared; an empty list means the outputs are equivalent.
+    #[must_use]
+    pub fn semantic_diff(&self, other: &Self) -> Vec<crate::types::DiffEntry> {
+        crate::types::snapshot::diff(self, other)
+    }
+
+    /// Compares the parameters this response echoes back (`temperature`, `top_p`,
+    /// `max_output_tokens`, `tool_choice` and `truncation`) against `request`.
+    ///
+    /// A parameter that was sent but not echoed is
+    /// [`Ignored`](crate::types::ParamDiscrepancy::Ignored), one echoed with another value
+    /// is [`Clamped`](crate::types::ParamDiscrepancy::Clamped), and one echoed without
+    /// being sent is [`Defaulted`](crate::types::ParamDiscrepancy::Defaulted). An empty
+    /// list means every parameter took effect as sent.
+    #[must_use]
+    pub fn diff_request(&self, request: &crate::Request) -> Vec<crate::types::ParamDiscrepancy> {
+        crate::types::discrepancy::diff_request(self, request)
+    }
+
+    /// Renders the response as a human-readable transcript for CLIs and debugging.
+    ///
+    /// Every output item is printed as a label with its contents indented underneath,
+    /// followed by incomplete or error details and, if enabled, usage.
+    #[must_use]
+    pub fn render_transcript(&self, options: crate::types::TranscriptOptions) -> String {
+        crate::types::transcript::render(self, options)
+    }
+
+    /// Returns the fenced code blocks in [`output_text`](Self::output_text), in order.
+    ///
+    /// Both ```` ``` ```` and `~~~` fences are recognized; a block left open runs to the
+    /// end of the text.
+    #[must_use]
+    pub fn code_blocks(&self) -> Vec<crate::types::CodeBlock> {
+        crate::types::markdown::code_blocks(&self.output_text())
+    }
+
+    /// Returns the first fenced code block, or with `Some(language)` the first one in
+    /// that language (ignoring ASCII case)
+    #[must_use]
+    pub fn first_code_block(&self, language: Option<&str>) -> Option<crate::types::CodeBlock> {
+        self.code_blocks()
+            .into_iter()
+            .find(|block| language.is_none_or(|language| block.is_language(language)))
+    }
+
+    /// Returns [`output_text`](Self::output_text) with markdown headings, emphasis, links,
+    /// quotes and code fences removed, for UIs that show plain text. Code inside blocks
+    /// and spans is kept verbatim.
+    #[must_use]
+    pub fn plain_text(&self) -> String {
+        crate::types::markdown::plain_text(&self.output_text())
+    }
+
     /// Returns the response ID
     #[must_use]
     pub fn id(&self) -> &str {
         &self.id
     }
 
+    /// Returns the status string, as [`ResponseStatus::as_str`] gives it
+    #[must_use]
+    pub fn status_raw(&self) -> &str {
+        self.status.as_str()
+    }
+
     /// Returns true if the response is in a completed state
     #[must_use]
     pub fn is_complete(&self) -> bool {
-        matches!(self.status.as_str(), "completed" | "cancelled" | "failed")
+        matches!(
+            self.status,
+            ResponseStatus::Completed | ResponseStatus::Cancelled | ResponseStatus::Failed
+        )
     }
 
     /// Returns true if the response is currently being processed
     #[must_use]
     pub fn is_in_progress(&self) -> bool {
-        matches!(self.status.as_str(), "queued" | "in_progress")
+        matches!(
+            self.status,
+            ResponseStatus::Queued | ResponseStatus::InProgress
+        )
     }
 
     /// Returns true if the response has errors
     #[must_use]
     pub fn has_errors(&self) -> bool {
-        self.error.is_some() || self.status == "failed"
+        self.error.is_some() || self.status == ResponseStatus::Failed
     }
 
     /// Returns the total token count if available
@@ -294,6 +638,49 @@ impl Response {
         self.usage.as_ref().map(|u| u.total_tokens)
     }
 
+    /// Returns the system instructions this response was created with.
+    ///
+    /// Instructions are not inherited through `previous_response_id`; use
+    /// [`RequestBuilder::inherit_instructions_from`](crate::types::RequestBuilder::inherit_instructions_from)
+    /// to carry them over.
+    #[must_use]
+    pub fn instructions_used(&self) -> Option<&str> {
+        self.instructions.as_deref()
+    }
+
+    /// Returns how long the API took to generate the response (`completed_at - created_at`).
+    ///
+    /// Returns `None` if `completed_at` is missing or earlier than `created_at`.
+    /// Both timestamps have one-second resolution.
+    #[must_use]
+    pub fn generation_duration(&self) -> Option<std::time::Duration> {
+        (self.completed_at? - self.created_at).to_std().ok()
+    }
+
+    /// Returns the number of input tokens served from the prompt cache, if reported
+    #[must_use]
+    pub fn cached_tokens(&self) -> Option<u32> {
+        self.usage
+            .as_ref()?
+            .prompt_tokens_details
+            .as_ref()?
+            .cached_tokens
+    }
+
+    /// Returns the fraction of input tokens served from the prompt cache, from 0.0 to 1.0.
+    ///
+    /// Returns `None` if usage or cached token counts are missing, or if there were no
+    /// input tokens. Requests sharing a `prompt_cache_key` are more likely to hit the cache.
+    #[must_use]
+    pub fn cache_hit_ratio(&self) -> Option<f64> {
+        let input_tokens = self.usage.as_ref()?.input_tokens;
+        if input_tokens == 0 {
+            return None;
+        }
+        let cached = self.cached_tokens()?;
+        Some(f64::from(cached) / f64::from(input_tokens))
+    }
+
     /// Returns the response output as text if available
     #[must_use]
     pub fn output_text(&self) -> String {
@@ -309,8 +696,15 @@ impl Response {
                 crate::types::ResponseItem::Message { content, .. } => Some(
                     content
                         .iter()
-                        .map(|c| match c {
-                            crate::types::MessageContent::OutputText { text, .. } => text.as_str(),
+                        .filter_map(|c| match c {
+                            crate::types::MessageContent::OutputText { text, .. } => {
+                                Some(text.as_str())
+                            }
+                            crate::types::MessageContent::Refusal { .. }
+                            | crate::types::MessageContent::OutputAudio { .. }
+                            | crate::types::MessageContent::InputText { .. }
+                            | crate::types::MessageContent::InputImage { .. }
+                            | crate::types::MessageContent::InputFile { .. } => None,
                         })
                         .collect::<String>(),
                 ),
@@ -320,27 +714,172 @@ impl Response {
             .collect::<String>()
     }
 
-    /// Returns all tool calls in the response
+    /// Returns the refusal message if the model declined to answer
+    #[must_use]
+    pub fn refusal(&self) -> Option<&str> {
+        self.output.iter().find_map(|item| match item {
+            crate::types::ResponseItem::Message { content, .. } => {
+                content.iter().find_map(|c| match c {
+                    crate::types::MessageContent::Refusal { refusal } => Some(refusal.as_str()),
+                    crate::types::MessageContent::OutputText { .. }
+                    | crate::types::MessageContent::OutputAudio { .. }
+                    | crate::types::MessageContent::InputText { .. }
+                    | crate::types::MessageContent::InputImage { .. }
+                    | crate::types::MessageContent::InputFile { .. } => None,
+                })
+            }
+            _ => None,
+        })
+    }
+
+    /// Returns the audio parts of the output messages, in order
+    #[must_use]
+    pub fn audio_outputs(&self) -> Vec<crate::types::AudioOutput<'_>> {
+        self.output
+            .iter()
+            .filter_map(|item| match item {
+                crate::types::ResponseItem::Message { content, .. } => Some(content),
+                _ => None,
+            })
+            .flatten()
+            .filter_map(|c| match c {
+                crate::types::MessageContent::OutputAudio { data, transcript } => {
+                    Some(crate::types::AudioOutput {
+                        data,
+                        transcript: transcript.as_deref(),
+                    })
+                }
+                _ => None,
+            })
+            .collect()
+    }
+
+    /// Returns the images from image generation calls, in output order, with the revised
+    /// prompt and format the API reported
+    #[must_use]
+    pub fn image_generations(&self) -> Vec<crate::types::ImageGeneration<'_>> {
+        self.output
+            .iter()
+            .filter_map(|item| match item {
+                crate::types::ResponseItem::ImageGenerationCall {
+                    id,
+                    result,
+                    status,
+                    revised_prompt,
+                    output_format,
+                    size,
+                    quality,
+                    background,
+                } => Some(crate::types::ImageGeneration {
+                    id,
+                    result,
+                    status,
+                    revised_prompt: revised_prompt.as_deref(),
+                    output_format: output_format.as_deref(),
+                    size: size.as_deref(),
+                    quality: quality.as_deref(),
+                    background: background.as_deref(),
+                }),
+                _ => None,
+            })
+            .collect()
+    }
+
+    /// Returns the sources found by all web search calls, in order and without duplicate URLs.
+    ///
+    /// Collects both `results` and the sources attached to search actions; request them with
+    /// [`Include::WebSearchResults`](crate::types::Include::WebSearchResults).
+    #[must_use]
+    pub fn web_search_sources(&self) -> Vec<&crate::types::WebSearchSource> {
+        let mut seen = std::collections::HashSet::new();
+        self.output
+            .iter()
+            .filter_map(|item| match item {
+                crate::types::ResponseItem::WebSearchCall {
+                    action, results, ..
+                } => {
+                    let action_sources = match action {
+                        Some(crate::types::WebSearchAction::Search { sources, .. }) => {
+                            sources.as_deref()
+                        }
+                        _ => None,
+                    };
+                    Some(
+                        results
+                            .as_deref()
+                            .into_iter()
+                            .chain(action_sources)
+                            .flatten(),
+                    )
+                }
+                _ => None,
+            })
+            .flatten()
+            .filter(|source| seen.insert(source.url.as_str()))
+            .collect()
+    }
+
+    /// Returns the reasoning effort the response was generated with.
+    ///
+    /// Reads `reasoning.effort`, falling back to the top-level `reasoning_effort` older
+    /// payloads carry. `None` if neither is set or the level is not a known [`Effort`](crate::types::Effort).
+    #[must_use]
+    pub fn effort(&self) -> Option<crate::types::Effort> {
+        self.reasoning
+            .as_ref()
+            .and_then(|reasoning| reasoning.effort.as_deref())
+            .or(self.reasoning_effort.as_deref())
+            .and_then(crate::types::Effort::parse)
+    }
+
+    /// Returns the completed tool calls in the response.
+    ///
+    /// Calls that are still in progress or were cut short are skipped, since their
+    /// arguments may be partial; [`Response::tool_calls_all`] returns them too.
     #[must_use]
     pub fn tool_calls(&self) -> Vec<crate::types::FunctionCallInfo> {
+        self.tool_calls_all()
+            .into_iter()
+            .filter(crate::types::FunctionCallInfo::is_completed)
+            .collect()
+    }
+
+    /// Returns every tool call in the response, whatever its status
+    #[must_use]
+    pub fn tool_calls_all(&self) -> Vec<crate::types::FunctionCallInfo> {
+        self.tool_call_refs()
+            .iter()
+            .map(crate::types::FunctionCallRef::to_info)
+            .collect()
+    }
+
+    /// Returns every tool call in the response, whatever its status, borrowing their
+    /// names, IDs and arguments
+    #[must_use]
+    pub fn tool_call_refs(&self) -> Vec<crate::types::FunctionCallRef<'_>> {
         self.output
             .iter()
             .filter_map(|item| match item {
                 crate::types::ResponseItem::FunctionCall {
+                    id,
                     name,
                     arguments,
                     call_id,
-                    ..
-                } => Some(crate::types::FunctionCallInfo {
-                    name: name.clone(),
-                    arguments: arguments.clone(),
-                    call_id: call_id.clone(),
+                    status,
+                } => Some(crate::types::FunctionCallRef {
+                    name,
+                    arguments: arguments.into(),
+                    call_id,
+                    id: Some(id),
+                    status: Some(status),
                 }),
                 crate::types::ResponseItem::ToolCall(tool_call) => {
-                    Some(crate::types::FunctionCallInfo {
-                        name: tool_call.name.clone(),
-                        arguments: tool_call.arguments.to_string(),
-                        call_id: tool_call.id.clone(),
+                    Some(crate::types::FunctionCallRef {
+                        name: &tool_call.name,
+                        arguments: tool_call.arguments.to_string().into(),
+                        call_id: &tool_call.id,
+                        id: None,
+                        status: None,
                     })
                 }
                 _ => None,
@@ -348,161 +887,161 @@ impl Response {
             .collect()
     }
 
-    /// Calculates tool usage counts from the response output
+    /// Returns `(call_id, output)` for each function call output in the response
     #[must_use]
-    pub fn calculate_tool_usage(&self) -> (u32, u32, u32, u32) {
-        let mut web_search_count = 0;
-        let mut file_search_count = 0;
-        let mut image_generation_count = 0;
-        let mut code_interpreter_count = 0;
-
-        for item in &self.output {
-            match item {
-                crate::types::ResponseItem::WebSearchCall { .. } => web_search_count += 1,
-                crate::types::ResponseItem::FileSearchCall { .. } => file_search_count += 1,
-                crate::types::ResponseItem::ImageGenerationCall { .. } => {
-                    image_generation_count += 1;
-                }
-                crate::types::ResponseItem::CodeInterpreterCall { .. } => {
-                    code_interpreter_count += 1;
-                }
-                _ => {}
-            }
-        }
+    pub fn function_call_outputs(&self) -> Vec<(&str, &str)> {
+        self.output
+            .iter()
+            .filter_map(|item| match item {
+                crate::types::ResponseItem::FunctionCallOutput {
+                    call_id, output, ..
+                } => Some((call_id.as_str(), output.as_str())),
+                _ => None,
+            })
+            .collect()
+    }
 
+    /// Pairs each tool call, whatever its status, with the output submitted for it,
+    /// matched by `call_id`.
+    ///
+    /// Calls without a matching output in this response are paired with `None`.
+    #[must_use]
+    pub fn paired_tool_calls(&self) -> Vec<(crate::types::FunctionCallInfo, Option<&str>)> {
+        let outputs = self.function_call_outputs();
+        self.tool_calls_all()
+            .into_iter()
+            .map(|call| {
+                let output = outputs
+                    .iter()
+                    .find(|(call_id, _)| *call_id == call.call_id)
+                    .map(|(_, output)| *output);
+                (call, output)
+            })
+            .collect()
+    }
+
+    /// Counts the tool calls in the output by kind and outcome
+    #[must_use]
+    pub fn tool_usage(&self) -> crate::types::ToolUsageBreakdown {
+        crate::types::ToolUsageBreakdown::from_items(&self.output)
+    }
+
+    /// Counts the completed web search, file search, image generation and code
+    /// interpreter calls in the output.
+    ///
+    /// Failed and unfinished calls are left out; see [`Response::tool_usage`] for them
+    /// and for the other kinds of tool.
+    #[must_use]
+    pub fn calculate_tool_usage(&self) -> (u32, u32, u32, u32) {
+        let usage = self.tool_usage();
         (
-            web_search_count,
-            file_search_count,
-            image_generation_count,
-            code_interpreter_count,
+            usage.web_search.completed,
+            usage.file_search.completed,
+            usage.image_generation.completed,
+            usage.code_interpreter.completed,
         )
     }
 
-    /// Returns a usage object with token counts and tool usage populated
+    /// Returns a usage object with token counts and tool usage populated.
+    ///
+    /// The per-tool counters hold completed calls only, and
+    /// [`tool_usage`](Usage::tool_usage) holds every call by kind and outcome. Returns
+    /// `None` if the response has neither token usage nor tool calls.
     #[must_use]
     pub fn usage_with_tools(&self) -> Option<Usage> {
-        let (web_search, file_search, image_generation, code_interpreter) =
-            self.calculate_tool_usage();
-
-        if let Some(existing_usage) = &self.usage {
-            Some(Usage {
-                input_tokens: existing_usage.input_tokens,
-                output_tokens: existing_usage.output_tokens,
-                total_tokens: existing_usage.total_tokens,
-                output_tokens_details: existing_usage.output_tokens_details.clone(),
-                prompt_tokens_details: existing_usage.prompt_tokens_details.clone(),
-                web_search: if web_search > 0 {
-                    Some(web_search)
-                } else {
-                    None
-                },
-                file_search: if file_search > 0 {
-                    Some(file_search)
-                } else {
-                    None
-                },
-                image_generation: if image_generation > 0 {
-                    Some(image_generation)
-                } else {
-                    None
-                },
-                code_interpreter: if code_interpreter > 0 {
-                    Some(code_interpreter)
-                } else {
-                    None
-                },
-            })
-        } else {
-            // If no token usage, but we have tool usage, create a minimal usage object
-            if web_search > 0 || file_search > 0 || image_generation > 0 || code_interpreter > 0 {
-                Some(Usage {
-                    input_tokens: 0,
-                    output_tokens: 0,
-                    total_tokens: 0,
-                    output_tokens_details: None,
-                    prompt_tokens_details: None,
-                    web_search: if web_search > 0 {
-                        Some(web_search)
-                    } else {
-                        None
-                    },
-                    file_search: if file_search > 0 {
-                        Some(file_search)
-                    } else {
-                        None
-                    },
-                    image_generation: if image_generation > 0 {
-                        Some(image_generation)
-                    } else {
-                        None
-                    },
-                    code_interpreter: if code_interpreter > 0 {
-                        Some(code_interpreter)
-                    } else {
-                        None
-                    },
-                })
-            } else {
-                None
-            }
+        let tools = self.tool_usage();
+        if self.usage.is_none() && tools.is_empty() {
+            return None;
         }
+
+        let completed = |counts: crate::types::ToolCallCounts| {
+            (counts.completed > 0).then_some(counts.completed)
+        };
+        let mut usage = self.usage.clone().unwrap_or(Usage {
+            input_tokens: 0,
+            output_tokens: 0,
+            total_tokens: 0,
+            output_tokens_details: None,
+            prompt_tokens_details: None,
+            web_search: None,
+            file_search: None,
+            image_generation: None,
+            code_interpreter: None,
+            tool_usage: None,
+        });
+        usage.web_search = completed(tools.web_search);
+        usage.file_search = completed(tools.file_search);
+        usage.image_generation = completed(tools.image_generation);
+        usage.code_interpreter = completed(tools.code_interpreter);
+        usage.tool_usage = Some(tools);
+        Some(usage)
     }
 
-    /// Returns formatted usage statistics in the requested format
+    /// Returns formatted usage statistics in the requested format.
+    ///
+    /// Lists completed calls for every [`ToolKind`](crate::types::ToolKind), noting
+    /// failed and unfinished ones after the count.
     #[must_use]
     pub fn format_usage(&self) -> String {
-        if let Some(usage) = self.usage_with_tools() {
-            use std::fmt::Write;
-            let mut result = String::new();
-            writeln!(result, "input tokens: {}", usage.input_tokens).unwrap();
-            writeln!(result, "output tokens: {}", usage.output_tokens).unwrap();
-            writeln!(result, "total tokens: {}", usage.total_tokens).unwrap();
-            writeln!(result, "web search: {}", usage.web_search.unwrap_or(0)).unwrap();
-            writeln!(result, "file search: {}", usage.file_search.unwrap_or(0)).unwrap();
-            writeln!(
-                result,
-                "image generation: {}",
-                usage.image_generation.unwrap_or(0)
-            )
-            .unwrap();
-            write!(
-                result,
-                "code interpreter: {}",
-                usage.code_interpreter.unwrap_or(0)
-            )
-            .unwrap();
-            result
-        } else {
-            "No usage information available".to_string()
+        use std::fmt::Write;
+
+        let Some(usage) = self.usage_with_tools() else {
+            return "No usage information available".to_string();
+        };
+        let tools = usage.tool_usage.unwrap_or_default();
+        let mut result = String::new();
+        write!(result, "input tokens: {}", usage.input_tokens).unwrap();
+        write!(result, "\noutput tokens: {}", usage.output_tokens).unwrap();
+        write!(result, "\ntotal tokens: {}", usage.total_tokens).unwrap();
+        for kind in crate::types::ToolKind::ALL {
+            let counts = tools.get(kind);
+            write!(result, "\n{}: {}", kind.label(), counts.completed).unwrap();
+            if counts.failed > 0 {
+                write!(result, " ({} failed)", counts.failed).unwrap();
+            }
+            if counts.unfinished > 0 {
+                write!(result, " ({} unfinished)", counts.unfinished).unwrap();
+            }
         }
+        result
     }
 }
 
 impl TruncationSetting {
-    /// Creates a simple truncation setting with just a string value
+    /// Creates a disabled truncation setting
     #[must_use]
-    pub fn simple(value: impl Into<String>) -> Self {
-        Self::Simple(value.into())
+    pub const fn disabled() -> Self {
+        Self::Disabled
     }
 
-    /// Creates a disabled truncation setting
+    /// Creates an auto truncation setting
     #[must_use]
-    pub fn disabled() -> Self {
-        Self::Simple("disabled".to_string())
+    pub const fn auto() -> Self {
+        Self::Auto
     }
 
-    /// Creates an auto truncation setting
+    /// Returns the wire value (`"auto"` or `"disabled"`)
     #[must_use]
-    pub fn auto() -> Self {
-        Self::Simple("auto".to_string())
+    pub const fn as_str(self) -> &'static str {
+        match self {
+            Self::Auto => "auto",
+            Self::Disabled => "disabled",
+        }
     }
+}
 
-    /// Creates a truncation setting with configuration
+impl ResponseTruncation {
+    /// Returns the truncation strategy, if it is one this crate recognizes
     #[must_use]
-    pub fn config(truncation_type: impl Into<String>, last_messages: Option<u32>) -> Self {
-        Self::Config(TruncationConfig {
-            truncation_type: truncation_type.into(),
-            last_messages,
-        })
+    pub fn setting(&self) -> Option<TruncationSetting> {
+        match self {
+            Self::Setting(setting) => Some(*setting),
+            Self::Config(config) => match config.truncation_type.as_str() {
+                "auto" => Some(TruncationSetting::Auto),
+                "disabled" => Some(TruncationSetting::Disabled),
+                _ => None,
+            },
+            Self::Other(_) => None,
+        }
     }
 }
@@ -0,0 +1,257 @@
+//! Builder for [`Response`] values, mainly for testing code that handles responses.
+
+use crate::types::{ItemStatus, MessageContent, Response, ResponseItem, ResponseStatus, Usage};
+use chrono::{DateTime, Utc};
+
+/// Builds a [`Response`] with valid defaults for every field.
+///
+/// Without any calls it produces a completed, empty `gpt-4o` response with the ID
+/// `resp_test`, created at the Unix epoch. Output items are given IDs from their
+/// position, so equal builders produce equal responses.
+///
+/// ```rust
+/// use open_ai_rust_responses_by_sshift::types::ResponseStatus;
+/// use open_ai_rust_responses_by_sshift::Response;
+///
+/// let response = Response::builder()
+///     .with_output_message("Checking the weather")
+///     .with_function_call("get_weather", r#"{"city":"Paris"}"#, "call_1")
+///     .with_usage(120, 30)
+///     .with_status(ResponseStatus::Completed)
+///     .build();
+///
+/// assert_eq!(response.output_text(), "Checking the weather");
+/// assert_eq!(response.tool_calls()[0].name, "get_weather");
+/// assert_eq!(response.total_tokens(), Some(150));
+/// ```
+#[derive(Debug, Clone)]
+pub struct ResponseBuilder {
+    response: Response,
+}
+
+impl Default for ResponseBuilder {
+    fn default() -> Self {
+        Self {
+            response: Response {
+                id: "resp_test".to_string(),
+                object: "response".to_string(),
+                created_at: DateTime::<Utc>::UNIX_EPOCH,
+                completed_at: None,
+                model: "gpt-4o".to_string(),
+                status: ResponseStatus::Completed,
+                output: Vec::new(),
+                output_text: None,
+                previous_response_id: None,
+                instructions: None,
+                metadata: None,
+                usage: None,
+                temperature: None,
+                top_p: None,
+                max_output_tokens: None,
+                parallel_tool_calls: None,
+                tool_choice: None,
+                tools: None,
+                text: None,
+                top_logprobs: None,
+                truncation: None,
+                reasoning: None,
+                reasoning_effort: None,
+                service_tier: None,
+                user: None,
+                incomplete_details: None,
+                error: None,
+            },
+        }
+    }
+}
+
+impl ResponseBuilder {
+    /// Creates a builder with the defaults described on [`ResponseBuilder`]
+    #[must_use]
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Sets the response ID
+    #[must_use]
+    pub fn with_id(mut self, id: impl Into<String>) -> Self {
+        self.response.id = id.into();
+        self
+    }
+
+    /// Sets the model that produced the response
+    #[must_use]
+    pub fn with_model(mut self, model: impl Into<String>) -> Self {
+        self.response.model = model.into();
+        self
+    }
+
+    /// Sets the creation time
+    #[must_use]
+    pub fn with_created_at(mut self, created_at: DateTime<Utc>) -> Self {
+        self.response.created_at = created_at;
+        self
+    }
+
+    /// Sets the lifecycle status
+    #[must_use]
+    pub fn with_status(mut self, status: ResponseStatus) -> Self {
+        self.response.status = status;
+        self
+    }
+
+    /// Sets the ID of the response this one continues
+    #[must_use]
+    pub fn with_previous_response_id(mut self, id: impl Into<String>) -> Self {
+        self.response.previous_response_id = Some(id.into());
+        self
+    }
+
+    /// Appends an assistant message with a single text part
+    #[must_use]
+    pub fn with_output_message(mut self, text: impl Into<String>) -> Self {
+        let id = format!("msg_{}", self.response.output.len());
+        self.response.output.push(ResponseItem::Message {
+            id,
+            content: vec![MessageContent::OutputText {
+                text: text.into(),
+                annotations: Vec::new(),
+                logprobs: None,
+            }],
+            role: "assistant".to_string(),
+            status: Some("completed".to_string()),
+        });
+        self
+    }
+
+    /// Appends a completed function call with `arguments` as its JSON string
+    #[must_use]
+    pub fn with_function_call(
+        mut self,
+        name: impl Into<String>,
+        arguments: impl Into<String>,
+        call_id: impl Into<String>,
+    ) -> Self {
+        let id = format!("fc_{}", self.response.output.len());
+        self.response.output.push(ResponseItem::FunctionCall {
+            id,
+            arguments: arguments.into(),
+            call_id: call_id.into(),
+            name: name.into(),
+            status: ItemStatus::Completed,
+        });
+        self
+    }
+
+    /// Appends any output item, such as a web search or image generation call
+    #[must_use]
+    pub fn with_output_item(mut self, item: ResponseItem) -> Self {
+        self.response.output.push(item);
+        self
+    }
+
+    /// Sets the token usage; the total is the sum of both counts
+    #[must_use]
+    pub fn with_usage(mut self, input_tokens: u32, output_tokens: u32) -> Self {
+        self.response.usage = Some(Usage {
+            input_tokens,
+            output_tokens,
+            total_tokens: input_tokens.saturating_add(output_tokens),
+            output_tokens_details: None,
+            prompt_tokens_details: None,
+            web_search: None,
+            file_search: None,
+            image_generation: None,
+            code_interpreter: None,
+            tool_usage: None,
+        });
+        self
+    }
+
+    /// Returns the response
+    #[must_use]
+    pub fn build(self) -> Response {
+        self.response
+    }
+}
+
+impl Response {
+    /// Returns a builder for constructing responses by hand, e.g. in tests
+    #[must_use]
+    pub fn builder() -> ResponseBuilder {
+        ResponseBuilder::new()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn builder_responses_drive_the_accessors() {
+        let response = Response::builder()
+            .with_id("resp_1")
+            .with_output_message("Looking that up. ")
+            .with_function_call("lookup", r#"{"q":"rust"}"#, "call_1")
+            .with_output_item(ResponseItem::WebSearchCall {
+                id: "ws_1".to_string(),
+                status: "completed".to_string(),
+                action: None,
+                results: None,
+            })
+            .with_output_item(ResponseItem::FileSearchCall {
+                id: "fs_1".to_string(),
+                status: "failed".to_string(),
+            })
+            .with_output_message("Done.")
+            .with_usage(100, 25)
+            .build();
+
+        assert_eq!(response.id, "resp_1");
+        assert_eq!(response.output_text(), "Looking that up. Done.");
+        let calls = response.tool_calls();
+        assert_eq!(calls.len(), 1);
+        assert_eq!(
+            (calls[0].name.as_str(), calls[0].call_id.as_str()),
+            ("lookup", "call_1")
+        );
+        assert_eq!(calls[0].arguments, r#"{"q":"rust"}"#);
+        assert_eq!(response.calculate_tool_usage(), (1, 0, 0, 0));
+        assert_eq!(response.total_tokens(), Some(125));
+        assert!(response.is_complete());
+
+        let kinds: Vec<_> = response
+            .output
+            .iter()
+            .map(ResponseItem::item_type)
+            .collect();
+        assert_eq!(
+            kinds,
+            [
+                "message",
+                "function_call",
+                "web_search_call",
+                "file_search_call",
+                "message"
+            ]
+        );
+        assert!(matches!(
+            &response.output[4],
+            ResponseItem::Message { id, .. } if id == "msg_4"
+        ));
+    }
+
+    #[test]
+    fn built_responses_survive_a_wire_round_trip() {
+        let response = Response::builder()
+            .with_status(ResponseStatus::InProgress)
+            .with_previous_response_id("resp_0")
+            .with_function_call("lookup", "{}", "call_1")
+            .build();
+        assert!(!response.is_complete());
+
+        let json = serde_json::to_string(&response).unwrap();
+        assert_eq!(serde_json::from_str::<Response>(&json).unwrap(), response);
+        assert_eq!(Response::builder().build().created_at.timestamp(), 0);
+    }
+}
@@ -0,0 +1,222 @@
+//! Named sampling settings that resolve to per-model `temperature` and `top_p` values.
+//!
+//! A [`SamplingPreset`] names the intent ("deterministic", "balanced", "creative") and
+//! [`SamplingPreset::resolve`] looks up the concrete values for a model family, so the
+//! numbers live in one table instead of being copied into every call site. Presets
+//! serialize, so they can sit in config files next to a
+//! [`RequestTemplate`](crate::types::RequestTemplate):
+//!
+//! ```rust
+//! use open_ai_rust_responses_by_sshift::types::{Model, SamplingPreset};
+//!
+//! let preset: SamplingPreset = serde_json::from_str(r#""balanced""#).unwrap();
+//! assert_eq!(preset.resolve(&Model::GPT4o).unwrap().temperature, 0.7);
+//! // Reasoning models take no sampling parameters
+//! assert_eq!(preset.resolve(&Model::GPT5), None);
+//! ```
+
+use crate::types::Model;
+use serde::{Deserialize, Serialize};
+
+/// Named sampling settings for [`RequestBuilder::sampling`](crate::types::RequestBuilder::sampling)
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum SamplingPreset {
+    /// As repeatable as the model allows, for extraction and classification
+    Deterministic,
+    /// The model family's general-purpose settings
+    Balanced,
+    /// More varied output, for brainstorming and prose
+    Creative,
+    /// Explicit values, used as they are for every model that supports sampling
+    Custom {
+        /// Sampling temperature between 0 and 2
+        temperature: f32,
+        /// Nucleus sampling parameter
+        top_p: f32,
+    },
+}
+
+/// Concrete `temperature` and `top_p` a preset resolved to
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+pub struct SamplingValues {
+    /// Sampling temperature
+    pub temperature: f32,
+    /// Nucleus sampling parameter
+    pub top_p: f32,
+}
+
+impl SamplingValues {
+    const fn new(temperature: f32, top_p: f32) -> Self {
+        Self { temperature, top_p }
+    }
+}
+
+/// Deterministic, balanced and creative values for one model family
+type FamilyDefaults = [SamplingValues; 3];
+
+/// GPT-4o, GPT-4 and GPT-3.5, and custom models that are not reasoning models
+const CHAT_DEFAULTS: FamilyDefaults = [
+    SamplingValues::new(0.0, 1.0),
+    SamplingValues::new(0.7, 1.0),
+    SamplingValues::new(1.1, 0.95),
+];
+
+/// GPT-4.1, which follows instructions more literally and needs less heat
+const GPT41_DEFAULTS: FamilyDefaults = [
+    SamplingValues::new(0.0, 1.0),
+    SamplingValues::new(0.5, 1.0),
+    SamplingValues::new(0.9, 0.95),
+];
+
+/// Looks up a model's family defaults, or `None` if it takes no sampling parameters
+fn family_defaults(model: &Model) -> Option<&'static FamilyDefaults> {
+    if !model.supports_temperature() {
+        return None;
+    }
+    Some(match model {
+        Model::GPT41 | Model::GPT41Mini | Model::GPT41Nano => &GPT41_DEFAULTS,
+        _ => &CHAT_DEFAULTS,
+    })
+}
+
+impl SamplingPreset {
+    /// Resolves the preset to concrete values for `model`.
+    ///
+    /// Returns `None` for models that reject `temperature` and `top_p`, such as the
+    /// GPT-5 and o-series reasoning models; see [`Model::supports_temperature`].
+    #[must_use]
+    pub fn resolve(&self, model: &Model) -> Option<SamplingValues> {
+        let [deterministic, balanced, creative] = family_defaults(model)?;
+        Some(match *self {
+            Self::Deterministic => *deterministic,
+            Self::Balanced => *balanced,
+            Self::Creative => *creative,
+            Self::Custom { temperature, top_p } => SamplingValues::new(temperature, top_p),
+        })
+    }
+
+    /// Sets `temperature` and `top_p` on `request` from the preset, for its model.
+    ///
+    /// Values already on the request were set explicitly and win; a conflicting preset
+    /// value is logged and dropped.
+    pub(crate) fn apply_to(&self, request: &mut crate::Request) {
+        let Some(values) = self.resolve(&request.model) else {
+            crate::diag::debug!(
+                "{} takes no sampling parameters; ignoring sampling preset {self:?}",
+                request.model
+            );
+            return;
+        };
+        fill("temperature", &mut request.temperature, values.temperature);
+        fill("top_p", &mut request.top_p, values.top_p);
+    }
+}
+
+fn fill(name: &str, field: &mut Option<f32>, preset: f32) {
+    match *field {
+        None => *field = Some(preset),
+        #[allow(clippy::float_cmp)]
+        Some(explicit) if explicit != preset => crate::diag::debug!(
+            "Explicit {name} ({explicit}) overrides the sampling preset's {name} ({preset})"
+        ),
+        Some(_) => {}
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn presets_resolve_per_model_family() {
+        let balanced = |model: Model| SamplingPreset::Balanced.resolve(&model);
+        assert_eq!(balanced(Model::GPT4o), Some(SamplingValues::new(0.7, 1.0)));
+        assert_eq!(balanced(Model::GPT4oMini), balanced(Model::GPT35Turbo));
+        assert_eq!(balanced(Model::GPT41), Some(SamplingValues::new(0.5, 1.0)));
+        assert_eq!(
+            SamplingPreset::Deterministic
+                .resolve(&Model::GPT41Mini)
+                .map(|values| values.temperature),
+            Some(0.0)
+        );
+        assert_eq!(
+            SamplingPreset::Creative.resolve(&Model::Custom("ft:gpt-4o:acme".into())),
+            Some(SamplingValues::new(1.1, 0.95))
+        );
+
+        // Reasoning models get nothing, not even custom values
+        let custom = SamplingPreset::Custom {
+            temperature: 0.3,
+            top_p: 0.9,
+        };
+        for model in [
+            Model::GPT5,
+            Model::GPT5Nano,
+            Model::O3,
+            Model::O4Mini,
+            Model::O1Mini,
+            Model::Custom("o5-preview".into()),
+        ] {
+            assert_eq!(custom.resolve(&model), None, "{model}");
+            assert_eq!(balanced(model), None);
+        }
+        assert_eq!(
+            custom.resolve(&Model::GPT4o),
+            Some(SamplingValues::new(0.3, 0.9))
+        );
+    }
+
+    #[test]
+    fn explicit_values_win_over_the_preset() {
+        let request = crate::Request::builder()
+            .model(Model::GPT4o)
+            .temperature(0.2)
+            .sampling(SamplingPreset::Creative)
+            .build();
+        assert_eq!(request.temperature, Some(0.2));
+        assert_eq!(request.top_p, Some(0.95));
+
+        // The preset resolves against the final model, whatever the call order
+        let request = crate::Request::builder()
+            .sampling(SamplingPreset::Balanced)
+            .model(Model::GPT5)
+            .build();
+        assert_eq!((request.temperature, request.top_p), (None, None));
+
+        let request = crate::Request::builder()
+            .sampling(SamplingPreset::Balanced)
+            .model(Model::GPT41)
+            .build_validated()
+            .unwrap();
+        assert_eq!((request.temperature, request.top_p), (Some(0.5), Some(1.0)));
+    }
+
+    #[test]
+    fn presets_round_trip_through_config_files() {
+        for preset in [
+            SamplingPreset::Deterministic,
+            SamplingPreset::Creative,
+            SamplingPreset::Custom {
+                temperature: 0.4,
+                top_p: 0.8,
+            },
+        ] {
+            let json = serde_json::to_string(&preset).unwrap();
+            assert_eq!(
+                serde_json::from_str::<SamplingPreset>(&json).unwrap(),
+                preset
+            );
+        }
+        assert_eq!(
+            serde_json::from_str::<SamplingPreset>(
+                r#"{"custom": {"temperature": 0.4, "top_p": 0.8}}"#
+            )
+            .unwrap(),
+            SamplingPreset::Custom {
+                temperature: 0.4,
+                top_p: 0.8
+            }
+        );
+    }
+}
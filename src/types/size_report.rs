@@ -0,0 +1,279 @@
+//! Serialized size of a request, broken down by field.
+//!
+//! Sizes are counted by serializing into a writer that only counts bytes, so measuring
+//! a request does not allocate a copy of its body.
+
+use crate::types::{Input, Request};
+use serde::Serialize;
+use serde_json::Value;
+
+/// Byte sizes of a request as it would be sent, from [`Request::size_report`].
+///
+/// Field sizes count the serialized value only, not its key. Whatever no field below
+/// accounts for (keys, `model`, sampling parameters, ...) is in [`RequestSizeReport::other`].
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct RequestSizeReport {
+    /// Size of the whole JSON body
+    pub total: u64,
+
+    /// Size of `instructions`
+    pub instructions: u64,
+
+    /// Size of `input`, excluding inline images
+    pub input_text: u64,
+
+    /// Size of the `data:image/...` URLs inside `input`
+    pub input_images: u64,
+
+    /// Size of `tools`, mostly their parameter schemas
+    pub tools: u64,
+
+    /// Size of `metadata`
+    pub metadata: u64,
+}
+
+impl RequestSizeReport {
+    pub(crate) fn measure(request: &Request) -> Self {
+        let input = serialized_len(&request.input);
+        let input_images = match &request.input {
+            Input::Text(_) => 0,
+            Input::Items(items) => items
+                .iter()
+                .map(|item| {
+                    item.image_url.as_deref().map_or(0, inline_image_len)
+                        + item.content.as_ref().map_or(0, inline_images_len)
+                })
+                .sum(),
+        };
+        Self {
+            total: serialized_len(request),
+            instructions: request.instructions.as_ref().map_or(0, serialized_len),
+            input_text: input - input_images,
+            input_images,
+            tools: request.tools.as_ref().map_or(0, serialized_len),
+            metadata: request.metadata.as_ref().map_or(0, serialized_len),
+        }
+    }
+
+    /// Bytes not attributed to any of the measured fields
+    #[must_use]
+    pub fn other(&self) -> u64 {
+        self.total.saturating_sub(
+            self.instructions + self.input_text + self.input_images + self.tools + self.metadata,
+        )
+    }
+
+    /// Every measured field with its size, including `"other"`
+    #[must_use]
+    pub fn fields(&self) -> [(&'static str, u64); 6] {
+        [
+            ("instructions", self.instructions),
+            ("input_text", self.input_text),
+            ("input_images", self.input_images),
+            ("tools", self.tools),
+            ("metadata", self.metadata),
+            ("other", self.other()),
+        ]
+    }
+
+    /// The field taking up the most bytes, with its size
+    #[must_use]
+    pub fn largest_field(&self) -> (&'static str, u64) {
+        self.fields()
+            .into_iter()
+            .fold(("other", 0), |largest, field| {
+                if field.1 > largest.1 {
+                    field
+                } else {
+                    largest
+                }
+            })
+    }
+}
+
+/// Soft limits on the size of request bodies sent by `Responses::create` and
+/// `Responses::create_with_recovery`. Both are off by default.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct RequestSizeLimits {
+    /// Log a warning naming the largest field when a body is larger than this
+    pub warn_above_bytes: Option<u64>,
+
+    /// Fail with [`Error::RequestTooLarge`](crate::Error::RequestTooLarge) instead of
+    /// sending a body larger than this
+    pub reject_above_bytes: Option<u64>,
+}
+
+impl RequestSizeLimits {
+    /// Creates limits with both thresholds off
+    #[must_use]
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Sets the size above which a warning is logged
+    #[must_use]
+    pub fn with_warn_above_bytes(mut self, bytes: u64) -> Self {
+        self.warn_above_bytes = Some(bytes);
+        self
+    }
+
+    /// Sets the size above which requests are rejected before sending
+    #[must_use]
+    pub fn with_reject_above_bytes(mut self, bytes: u64) -> Self {
+        self.reject_above_bytes = Some(bytes);
+        self
+    }
+
+    /// Measures `request` if any limit is set, logging a warning when it is above
+    /// `warn_above_bytes`.
+    ///
+    /// The `create` methods call this before sending; call it directly to apply the same
+    /// limits to other requests, e.g. streams.
+    ///
+    /// # Errors
+    ///
+    /// Returns [`Error::RequestTooLarge`](crate::Error::RequestTooLarge) if the request
+    /// is above `reject_above_bytes`.
+    pub fn check(&self, request: &Request) -> crate::Result<()> {
+        if self.warn_above_bytes.is_none() && self.reject_above_bytes.is_none() {
+            return Ok(());
+        }
+        let report = request.size_report();
+        let (largest_field, largest_bytes) = report.largest_field();
+        if let Some(limit) = self
+            .reject_above_bytes
+            .filter(|&limit| report.total > limit)
+        {
+            return Err(crate::Error::RequestTooLarge {
+                limit,
+                actual: report.total,
+                largest_field: largest_field.to_string(),
+            });
+        }
+        if let Some(limit) = self.warn_above_bytes.filter(|&limit| report.total > limit) {
+            crate::diag::warn!(
+                "Request body is {} bytes, above the warning threshold of {limit}; largest field: {largest_field} ({largest_bytes} bytes)",
+                report.total
+            );
+        }
+        Ok(())
+    }
+}
+
+/// `io::Write` sink that only counts what is written to it
+struct ByteCounter(u64);
+
+impl std::io::Write for ByteCounter {
+    fn write(&mut self, buf: &[u8]) -> std::io::Result<usize> {
+        self.0 += buf.len() as u64;
+        Ok(buf.len())
+    }
+
+    fn flush(&mut self) -> std::io::Result<()> {
+        Ok(())
+    }
+}
+
+fn serialized_len<T: Serialize + ?Sized>(value: &T) -> u64 {
+    let mut counter = ByteCounter(0);
+    // Serializing these types into a writer that never fails cannot fail
+    let _ = serde_json::to_writer(&mut counter, value);
+    counter.0
+}
+
+fn inline_image_len(url: &str) -> u64 {
+    if url.starts_with("data:image/") {
+        serialized_len(url)
+    } else {
+        0
+    }
+}
+
+/// Size of the `data:image/...` strings anywhere inside `value`
+fn inline_images_len(value: &Value) -> u64 {
+    match value {
+        Value::String(s) => inline_image_len(s),
+        Value::Array(values) => values.iter().map(inline_images_len).sum(),
+        Value::Object(map) => map.values().map(inline_images_len).sum(),
+        _ => 0,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::types::{InputItem, Tool};
+
+    fn request() -> Request {
+        let image = format!("data:image/png;base64,{}", "A".repeat(3000));
+        Request::builder()
+            .model("gpt-4o")
+            .instructions("Be brief.".repeat(100))
+            .input_items(vec![
+                InputItem::message("user", vec![InputItem::content_text("What is this?")]),
+                InputItem::message("user", vec![InputItem::content_image(image)]),
+            ])
+            .tools(vec![Tool::function(
+                "lookup",
+                "Looks things up",
+                serde_json::json!({"type": "object", "properties": {"q": {"type": "string"}}}),
+            )])
+            .metadata_entry("trace", "abc")
+            .build()
+    }
+
+    #[test]
+    fn report_breaks_down_the_serialized_body() {
+        let request = request();
+        let report = request.size_report();
+        let body = serde_json::to_vec(&request).unwrap();
+
+        assert_eq!(report.total, body.len() as u64);
+        // 900 characters plus quotes
+        assert_eq!(report.instructions, 902);
+        let image_len = "data:image/png;base64,".len() as u64 + 3000 + 2;
+        assert_eq!(report.input_images, image_len);
+        assert_eq!(
+            report.input_text + report.input_images,
+            serde_json::to_vec(&request.input).unwrap().len() as u64
+        );
+        assert_eq!(
+            report.tools,
+            serde_json::to_vec(&request.tools).unwrap().len() as u64
+        );
+        assert_eq!(report.metadata, r#"{"trace":"abc"}"#.len() as u64);
+        let fields: u64 = report.fields().iter().map(|(_, bytes)| bytes).sum();
+        assert_eq!(fields, report.total);
+        assert_eq!(report.largest_field(), ("input_images", image_len));
+    }
+
+    #[test]
+    fn limits_warn_then_reject() {
+        let request = request();
+        let total = request.size_report().total;
+
+        assert!(RequestSizeLimits::new().check(&request).is_ok());
+        let warn_only = RequestSizeLimits::new().with_warn_above_bytes(total - 1);
+        assert!(warn_only.check(&request).is_ok());
+        let at_limit = RequestSizeLimits::new().with_reject_above_bytes(total);
+        assert!(at_limit.check(&request).is_ok());
+
+        let error = RequestSizeLimits::new()
+            .with_warn_above_bytes(100)
+            .with_reject_above_bytes(total - 1)
+            .check(&request)
+            .unwrap_err();
+        match error {
+            crate::Error::RequestTooLarge {
+                limit,
+                actual,
+                largest_field,
+            } => {
+                assert_eq!(limit, total - 1);
+                assert_eq!(actual, total);
+                assert_eq!(largest_field, "input_images");
+            }
+            other => panic!("unexpected error: {other:?}"),
+        }
+    }
+}
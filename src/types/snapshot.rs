@@ -0,0 +1,298 @@
+//! Normalization and diffing of responses for snapshot tests.
+//!
+//! Two runs of the same agent logic produce responses that differ in ids,
+//! timestamps and token counts. [`Response::normalized`](crate::Response::normalized)
+//! replaces those with stable values, and
+//! [`Response::semantic_diff`](crate::Response::semantic_diff) reports which output
+//! items still differ afterwards.
+
+use crate::types::ResponseItem;
+use chrono::DateTime;
+use std::collections::HashMap;
+
+/// How an output item differs between two responses
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum DiffKind {
+    /// Only the other response has an item at this index
+    Added,
+    /// Only this response has an item at this index
+    Removed,
+    /// Both responses have an item of the same type at this index, with different contents
+    Changed,
+    /// The items at this index have different types
+    TypeChanged,
+}
+
+/// One differing output item, as reported by [`Response::semantic_diff`](crate::Response::semantic_diff)
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct DiffEntry {
+    /// Index in `output`
+    pub index: usize,
+
+    /// How the items differ
+    pub kind: DiffKind,
+
+    /// Item type in this response, if it has an item at `index`
+    pub left_type: Option<String>,
+
+    /// Item type in the other response, if it has an item at `index`
+    pub right_type: Option<String>,
+}
+
+/// Hands out placeholders such as `resp_1` or `call_2`, one counter per id prefix
+#[derive(Default)]
+struct Placeholders {
+    assigned: HashMap<String, String>,
+    counters: HashMap<String, usize>,
+}
+
+impl Placeholders {
+    /// Replaces `id` with its placeholder; the same id always maps to the same placeholder
+    fn replace(&mut self, id: &mut String) {
+        if let Some(placeholder) = self.assigned.get(id.as_str()) {
+            id.clone_from(placeholder);
+            return;
+        }
+        let prefix = match id.split_once('_') {
+            Some((prefix, _)) if !prefix.is_empty() => prefix,
+            _ => "id",
+        };
+        let counter = self.counters.entry(prefix.to_string()).or_default();
+        *counter += 1;
+        let placeholder = format!("{prefix}_{counter}");
+        self.assigned.insert(id.clone(), placeholder.clone());
+        *id = placeholder;
+    }
+
+    fn replace_opt(&mut self, id: &mut Option<String>) {
+        if let Some(id) = id {
+            self.replace(id);
+        }
+    }
+}
+
+pub(crate) fn normalize(response: &crate::Response) -> crate::Response {
+    let mut normalized = response.clone();
+    let mut ids = Placeholders::default();
+
+    ids.replace(&mut normalized.id);
+    ids.replace_opt(&mut normalized.previous_response_id);
+    normalized.created_at = DateTime::UNIX_EPOCH;
+    if normalized.completed_at.is_some() {
+        normalized.completed_at = Some(DateTime::UNIX_EPOCH);
+    }
+    normalized.usage = None;
+
+    for item in &mut normalized.output {
+        match item {
+            ResponseItem::Message { id, .. }
+            | ResponseItem::Reasoning { id, .. }
+            | ResponseItem::WebSearchCall { id, .. }
+            | ResponseItem::FileSearchCall { id, .. }
+            | ResponseItem::ImageGenerationCall { id, .. } => ids.replace(id),
+            ResponseItem::CodeInterpreterCall {
+                id, container_id, ..
+            } => {
+                ids.replace(id);
+                ids.replace(container_id);
+            }
+            ResponseItem::FunctionCall { id, call_id, .. }
+            | ResponseItem::CustomToolCall { id, call_id, .. } => {
+                ids.replace(id);
+                ids.replace(call_id);
+            }
+            ResponseItem::FunctionCallOutput { id, call_id, .. } => {
+                ids.replace_opt(id);
+                ids.replace(call_id);
+            }
+            ResponseItem::ToolCall(call) => ids.replace(&mut call.id),
+            ResponseItem::Unknown(item) => {
+                for key in ["id", "call_id"] {
+                    if let Some(serde_json::Value::String(id)) = item.get_mut(key) {
+                        ids.replace(id);
+                    }
+                }
+            }
+            ResponseItem::Text { .. } => {}
+        }
+    }
+    normalized
+}
+
+pub(crate) fn diff(left: &crate::Response, right: &crate::Response) -> Vec<DiffEntry> {
+    let (left, right) = (normalize(left), normalize(right));
+    let len = left.output.len().max(right.output.len());
+
+    (0..len)
+        .filter_map(|index| {
+            let (l, r) = (left.output.get(index), right.output.get(index));
+            let kind = match (l, r) {
+                (Some(l), Some(r)) if l == r => return None,
+                (Some(l), Some(r)) if l.item_type() == r.item_type() => DiffKind::Changed,
+                (Some(_), Some(_)) => DiffKind::TypeChanged,
+                (Some(_), None) => DiffKind::Removed,
+                (None, _) => DiffKind::Added,
+            };
+            Some(DiffEntry {
+                index,
+                kind,
+                left_type: l.map(|item| item.item_type().to_string()),
+                right_type: r.map(|item| item.item_type().to_string()),
+            })
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    fn response(value: serde_json::Value) -> crate::Response {
+        let mut base = json!({
+            "object": "response",
+            "model": "gpt-4o",
+            "status": "completed",
+        });
+        let serde_json::Value::Object(fields) = value else {
+            panic!("expected an object");
+        };
+        base.as_object_mut().unwrap().extend(fields);
+        serde_json::from_value(base).unwrap()
+    }
+
+    fn agent_run(suffix: &str, created_at: i64, city: &str) -> crate::Response {
+        response(json!({
+            "id": format!("resp_{suffix}"),
+            "previous_response_id": format!("resp_prev{suffix}"),
+            "created_at": created_at,
+            "completed_at": created_at + 3,
+            "usage": {"input_tokens": 10, "output_tokens": 5, "total_tokens": 15},
+            "output": [
+                {"type": "reasoning", "id": format!("rs_{suffix}"), "summary": []},
+                {"type": "function_call", "id": format!("fc_{suffix}"), "call_id": format!("call_{suffix}"),
+                 "name": "get_weather", "arguments": format!(r#"{{"city":"{city}"}}"#), "status": "completed"},
+                {"type": "function_call_output", "call_id": format!("call_{suffix}"), "output": "sunny"},
+                {"type": "message", "id": format!("msg_{suffix}"), "role": "assistant", "status": "completed",
+                 "content": [{"type": "output_text", "text": "It is sunny", "annotations": []}]},
+            ],
+        }))
+    }
+
+    #[test]
+    fn normalized_replaces_volatile_fields_in_encounter_order() {
+        let normalized = agent_run("a8f", 1_700_000_000, "Paris").normalized();
+
+        assert_eq!(normalized.id, "resp_1");
+        assert_eq!(normalized.previous_response_id.as_deref(), Some("resp_2"));
+        assert_eq!(normalized.created_at.timestamp(), 0);
+        assert_eq!(normalized.completed_at.unwrap().timestamp(), 0);
+        assert!(normalized.usage.is_none());
+        let ids: Vec<_> = normalized
+            .output
+            .iter()
+            .map(|item| match item {
+                ResponseItem::Reasoning { id, .. } | ResponseItem::Message { id, .. } => id.clone(),
+                ResponseItem::FunctionCall { id, call_id, .. } => format!("{id} {call_id}"),
+                ResponseItem::FunctionCallOutput { id, call_id, .. } => {
+                    format!("{id:?} {call_id}")
+                }
+                other => panic!("unexpected item {other:?}"),
+            })
+            .collect();
+        // The output's call_id maps to the same placeholder as the call it answers
+        assert_eq!(ids, ["rs_1", "fc_1 call_1", "None call_1", "msg_1"]);
+    }
+
+    #[test]
+    fn identical_runs_compare_equal_after_normalizing() {
+        let first = agent_run("a8f", 1_700_000_000, "Paris");
+        let second = agent_run("zz9", 1_700_009_999, "Paris");
+
+        assert_ne!(first, second);
+        assert_eq!(first.normalized(), second.normalized());
+        assert!(first.semantic_diff(&second).is_empty());
+        // Normalizing twice changes nothing
+        assert_eq!(first.normalized(), first.normalized().normalized());
+    }
+
+    #[test]
+    fn ids_without_a_prefix_and_containers_get_placeholders() {
+        let normalized = response(json!({
+            "id": "abc",
+            "created_at": 1_700_000_000,
+            "output": [
+                {"type": "code_interpreter_call", "id": "ci_9", "container_id": "cntr_x", "status": "completed"},
+                {"type": "code_interpreter_call", "id": "ci_8", "container_id": "cntr_x", "status": "completed"},
+                {"type": "text", "content": "hi", "index": 0},
+            ],
+        }))
+        .normalized();
+
+        assert_eq!(normalized.id, "id_1");
+        assert!(normalized.completed_at.is_none());
+        assert!(matches!(
+            &normalized.output[1],
+            ResponseItem::CodeInterpreterCall { id, container_id, .. }
+                if id == "ci_2" && container_id == "cntr_1"
+        ));
+    }
+
+    #[test]
+    fn semantic_diff_reports_items_by_index_and_kind() {
+        let base = agent_run("a", 1_700_000_000, "Paris");
+        let changed = agent_run("b", 1_700_000_000, "Rome");
+        assert_eq!(
+            base.semantic_diff(&changed),
+            [DiffEntry {
+                index: 1,
+                kind: DiffKind::Changed,
+                left_type: Some("function_call".to_string()),
+                right_type: Some("function_call".to_string()),
+            }]
+        );
+
+        let mut shorter = base.clone();
+        shorter.output.truncate(2);
+        shorter.output[1] = ResponseItem::Text {
+            content: "Paris".to_string(),
+            index: 0,
+        };
+        assert_eq!(
+            base.semantic_diff(&shorter),
+            [
+                DiffEntry {
+                    index: 1,
+                    kind: DiffKind::TypeChanged,
+                    left_type: Some("function_call".to_string()),
+                    right_type: Some("text".to_string()),
+                },
+                DiffEntry {
+                    index: 2,
+                    kind: DiffKind::Removed,
+                    left_type: Some("function_call_output".to_string()),
+                    right_type: None,
+                },
+                DiffEntry {
+                    index: 3,
+                    kind: DiffKind::Removed,
+                    left_type: Some("message".to_string()),
+                    right_type: None,
+                },
+            ]
+        );
+        let added: Vec<_> = shorter
+            .semantic_diff(&base)
+            .into_iter()
+            .map(|entry| (entry.index, entry.kind))
+            .collect();
+        assert_eq!(
+            added,
+            [
+                (1, DiffKind::TypeChanged),
+                (2, DiffKind::Added),
+                (3, DiffKind::Added)
+            ]
+        );
+    }
+}
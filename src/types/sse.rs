@@ -0,0 +1,195 @@
+//! Server-sent event framing.
+//!
+//! A stream body is a sequence of events separated by blank lines. Each event is a set
+//! of `field: value` lines: `data` lines (joined with newlines when an event has more
+//! than one), an optional `event` name and an optional `id`. Lines starting with `:` are
+//! comments, such as keep-alive pings. [`SseEvent::parse`] reads one event this way.
+//!
+//! Streaming endpoints (responses and image generation) share the framing here: the
+//! body is regrouped into whole events before any of them is parsed.
+
+/// One server-sent event
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct SseEvent {
+    /// The `event` field, if the server named the event
+    pub event: Option<String>,
+
+    /// The `id` field; clients send the last one seen as `Last-Event-ID` to resume
+    pub id: Option<String>,
+
+    /// The event's `data` lines, joined with `\n`
+    pub data: String,
+}
+
+impl SseEvent {
+    /// Parses the first event in `block`, up to the blank line that ends it.
+    ///
+    /// Comment lines and unknown fields (including `retry`) are skipped, a single space
+    /// after the colon is dropped, and `\r\n` line endings are accepted. `None` if the
+    /// event has no `data` line, which the SSE spec says is never dispatched.
+    #[must_use]
+    pub fn parse(block: &str) -> Option<Self> {
+        let mut event = None;
+        let mut id = None;
+        let mut data: Option<String> = None;
+
+        for line in block.split('\n') {
+            let line = line.strip_suffix('\r').unwrap_or(line);
+            if line.is_empty() {
+                if data.is_some() || event.is_some() || id.is_some() {
+                    break;
+                }
+                continue;
+            }
+            if line.starts_with(':') {
+                continue;
+            }
+            let (field, value) = line.split_once(':').unwrap_or((line, ""));
+            let value = value.strip_prefix(' ').unwrap_or(value);
+            match field {
+                "data" => match &mut data {
+                    Some(data) => {
+                        data.push('\n');
+                        data.push_str(value);
+                    }
+                    None => data = Some(value.to_string()),
+                },
+                "event" => event = Some(value.to_string()),
+                // An id containing NUL is ignored, as the spec requires
+                "id" if !value.contains('\0') => id = Some(value.to_string()),
+                _ => {}
+            }
+        }
+
+        Some(Self {
+            event,
+            id,
+            data: data?,
+        })
+    }
+}
+
+/// Regroups a response body into one item per SSE event, so events that share a
+/// network chunk, or are split across chunks, reach the parser whole
+#[cfg(feature = "stream")]
+pub(crate) fn sse_events(body: crate::types::ByteStream) -> crate::types::ByteStream {
+    use futures::StreamExt;
+
+    // The buffer persists across chunks; events are split off it without copying, and
+    // `scanned` bytes of it are known not to start an event's terminating blank line
+    let state = (
+        Some(body),
+        bytes::BytesMut::new(),
+        0,
+        std::collections::VecDeque::new(),
+    );
+    Box::pin(futures::stream::unfold(
+        state,
+        |(mut body, mut buffer, mut scanned, mut ready)| async move {
+            loop {
+                if let Some(event) = ready.pop_front() {
+                    return Some((Ok(event), (body, buffer, scanned, ready)));
+                }
+                let Some(chunks) = body.as_mut() else {
+                    // A last event without its terminating blank line
+                    if buffer.iter().all(u8::is_ascii_whitespace) {
+                        return None;
+                    }
+                    let rest = buffer.split().freeze();
+                    return Some((Ok(rest), (None, buffer, 0, ready)));
+                };
+                match chunks.next().await {
+                    Some(Ok(chunk)) => {
+                        buffer.extend_from_slice(&chunk);
+                        while let Some(end) = sse_event_end(&buffer, scanned) {
+                            ready.push_back(buffer.split_to(end).freeze());
+                            scanned = 0;
+                        }
+                        scanned = buffer.len();
+                    }
+                    Some(Err(error)) => {
+                        return Some((Err(error), (None, bytes::BytesMut::new(), 0, ready)));
+                    }
+                    None => body = None,
+                }
+            }
+        },
+    ))
+}
+
+/// Returns the length of the first complete SSE event in `buffer`, including the blank
+/// line that ends it, skipping the first `scanned` bytes already searched
+#[cfg(feature = "stream")]
+fn sse_event_end(buffer: &[u8], scanned: usize) -> Option<usize> {
+    // A terminator ends with the third byte of `\n\r\n`, so it may start two bytes back
+    let mut from = scanned.saturating_sub(2);
+    while let Some(offset) = buffer[from..].iter().position(|&byte| byte == b'\n') {
+        let i = from + offset;
+        match &buffer[i + 1..] {
+            [b'\n', ..] => return Some(i + 2),
+            [b'\r', b'\n', ..] => return Some(i + 3),
+            _ => from = i + 1,
+        }
+    }
+    None
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[cfg(feature = "stream")]
+    #[tokio::test]
+    async fn sse_events_regroups_chunks_into_whole_events() {
+        use futures::StreamExt;
+
+        let chunks = [
+            "data: {\"a\":1}\n\ndata: {\"b\"",
+            ":2}\n",
+            "\nevent: x\r\ndata: {\"c\":3}\r\n\r",
+            "\n: keep-alive\n\ndata: {\"d\":4}",
+        ]
+        .map(|chunk| Ok(bytes::Bytes::from(chunk)));
+        let events: Vec<_> = super::sse_events(Box::pin(futures::stream::iter(chunks)))
+            .map(|event| String::from_utf8(event.unwrap().to_vec()).unwrap())
+            .collect()
+            .await;
+
+        assert_eq!(
+            events,
+            [
+                "data: {\"a\":1}\n\n",
+                "data: {\"b\":2}\n\n",
+                "event: x\r\ndata: {\"c\":3}\r\n\r\n",
+                ": keep-alive\n\n",
+                "data: {\"d\":4}",
+            ]
+        );
+    }
+
+    #[test]
+    fn multi_line_data_is_joined_with_newlines() {
+        let block = "id: 7\r\nevent: response.output_text.delta\r\n: keep-alive\r\ndata: {\"delta\":\r\ndata:\"Hi\"}\r\nretry: 1000\r\n\r\n";
+        assert_eq!(
+            SseEvent::parse(block),
+            Some(SseEvent {
+                event: Some("response.output_text.delta".to_string()),
+                id: Some("7".to_string()),
+                data: "{\"delta\":\n\"Hi\"}".to_string(),
+            })
+        );
+
+        // Only the first event is read; an empty `data` line still counts
+        let event = SseEvent::parse("\ndata\n\ndata: second\n").unwrap();
+        assert_eq!(event.data, "");
+
+        // Comments alone, or fields without data, dispatch nothing
+        assert_eq!(SseEvent::parse(": ping\n\n"), None);
+        assert_eq!(SseEvent::parse("event: ping\nid: 3\n\n"), None);
+        assert_eq!(
+            SseEvent::parse("id: a\0b\ndata: x\n").unwrap().id,
+            None,
+            "ids containing NUL are ignored"
+        );
+    }
+}
@@ -3,57 +3,134 @@ use futures::Stream;
 use serde::{Deserialize, Serialize};
 
 /// Stream event types for the OpenAI Responses API
-#[derive(Debug, Clone, Deserialize, Serialize)]
+#[derive(Debug, Clone, PartialEq, Deserialize, Serialize)]
 #[serde(tag = "type", rename_all = "snake_case")]
 pub enum StreamEvent {
     /// Text delta event
     TextDelta {
         /// Content of the text delta
         content: String,
-        /// Index of the text delta
+        /// Position of the message in `Response.output` (`output_index`)
         index: u32,
+        /// Position of the text part within the message's content
+        #[serde(default)]
+        content_index: u32,
     },
 
     /// Text stop event
     TextStop {
-        /// Index of the text stop
+        /// Position of the message in `Response.output` (`output_index`)
         index: u32,
+        /// Position of the text part within the message's content
+        #[serde(default)]
+        content_index: u32,
     },
 
     /// Tool call created event
     ToolCallCreated {
-        /// Tool call ID
+        /// Tool call ID (the output item ID)
         id: String,
+        /// Call ID to submit the call's output under, if the event carried one
+        #[serde(default, skip_serializing_if = "Option::is_none")]
+        call_id: Option<String>,
         /// Tool call name
         name: String,
-        /// Index of the tool call
+        /// Position of the tool call in `Response.output` (`output_index`)
         index: u32,
     },
 
     /// Tool call delta event
     ToolCallDelta {
-        /// Tool call ID
+        /// Tool call ID (the output item ID)
         id: String,
+        /// Call ID, from the event or the call's earlier `ToolCallCreated` event
+        #[serde(default, skip_serializing_if = "Option::is_none")]
+        call_id: Option<String>,
+        /// Function name, from the event or the call's earlier `ToolCallCreated` event
+        #[serde(default, skip_serializing_if = "Option::is_none")]
+        name: Option<String>,
         /// Delta content
         content: String,
-        /// Index of the tool call
+        /// Position of the tool call in `Response.output` (`output_index`)
         index: u32,
     },
 
     /// Tool call completed event
     ToolCallCompleted {
-        /// Tool call ID
+        /// Tool call ID (the output item ID)
         id: String,
-        /// Index of the tool call
+        /// Call ID, from the event or the call's earlier `ToolCallCreated` event
+        #[serde(default, skip_serializing_if = "Option::is_none")]
+        call_id: Option<String>,
+        /// Function name, from the event or the call's earlier `ToolCallCreated` event
+        #[serde(default, skip_serializing_if = "Option::is_none")]
+        name: Option<String>,
+        /// Position of the tool call in `Response.output` (`output_index`)
         index: u32,
     },
 
-    /// Image generation progress event (NEW for May 2025)
+    /// Image generation progress event (NEW for May 2025).
+    ///
+    /// Also parsed from `response.image_generation_call.partial_image`, which carries a
+    /// partial image as base64 instead of a URL, with `index` set to the call's
+    /// `output_index`.
     ImageProgress {
         /// URL of the progressive image (if available)
         url: Option<String>,
         /// Index of the image being generated
         index: u32,
+        /// Base64-encoded partial image
+        #[serde(default, skip_serializing_if = "Option::is_none")]
+        partial_image_b64: Option<String>,
+        /// Which partial image of the call this is, counting from 0
+        #[serde(default, skip_serializing_if = "Option::is_none")]
+        partial_image_index: Option<u32>,
+    },
+
+    /// A server-side MCP tool call started.
+    ///
+    /// Parsed from the `response.output_item.added` event of an `mcp_call` item, or from
+    /// `response.mcp_call.in_progress` when it carries the server label and tool name.
+    McpCallStarted {
+        /// MCP call item ID
+        id: String,
+        /// Label of the MCP server running the tool
+        server_label: String,
+        /// Tool name
+        name: String,
+    },
+
+    /// Arguments of a server-side MCP tool call (`response.mcp_call_arguments.delta`)
+    McpCallArgumentsDelta {
+        /// MCP call item ID
+        id: String,
+        /// Delta of the JSON arguments
+        delta: String,
+    },
+
+    /// A server-side MCP tool call finished (`response.mcp_call.completed`)
+    McpCallCompleted {
+        /// MCP call item ID
+        id: String,
+        /// Tool output, if the event carries it; it is always in the final `Response.output`
+        output: Option<String>,
+    },
+
+    /// A server-side MCP tool call failed (`response.mcp_call.failed`)
+    McpCallFailed {
+        /// MCP call item ID
+        id: String,
+        /// Error reported for the call, if any
+        error: Option<String>,
+    },
+
+    /// The tools of an MCP server were listed.
+    ///
+    /// Parsed from the `response.output_item.done` event of an `mcp_list_tools` item, or
+    /// from `response.mcp_list_tools.completed` when it carries the tools.
+    McpListToolsCompleted {
+        /// Tools offered by the server
+        tools: Vec<McpToolInfo>,
     },
 
     /// Response created event - emitted when the response is first created
@@ -63,6 +140,34 @@ pub enum StreamEvent {
         id: String,
     },
 
+    /// The response failed mid-generation (`response.failed` or an `error` event).
+    ///
+    /// This is a terminal event: the stream ends after it.
+    Failed {
+        /// Error reported by the server
+        error: crate::types::ResponseError,
+    },
+
+    /// The response stopped before completing (`response.incomplete`), e.g. because
+    /// it hit `max_output_tokens`.
+    ///
+    /// This is a terminal event: the stream ends after it.
+    Incomplete {
+        /// Reason reported by the server, such as `max_output_tokens`
+        reason: Option<String>,
+    },
+
+    /// Recovery reset the conversation before the stream opened, e.g. by dropping a
+    /// `previous_response_id` that no longer exists.
+    ///
+    /// Sent first by [`Responses::stream_with_recovery`](crate::Responses::stream_with_recovery)
+    /// when [`RecoveryPolicy::notify_on_reset`](crate::types::RecoveryPolicy::notify_on_reset)
+    /// is set; show `message` to the user.
+    SessionReset {
+        /// The policy's reset message
+        message: String,
+    },
+
     /// Chunk heartbeat event
     Chunk,
 
@@ -75,6 +180,32 @@ pub enum StreamEvent {
 }
 
 impl StreamEvent {
+    /// Returns the variant name in snake case, matching the serialized `type` tag
+    /// (e.g. `"text_delta"`)
+    #[must_use]
+    pub fn kind(&self) -> &'static str {
+        match self {
+            Self::TextDelta { .. } => "text_delta",
+            Self::TextStop { .. } => "text_stop",
+            Self::ToolCallCreated { .. } => "tool_call_created",
+            Self::ToolCallDelta { .. } => "tool_call_delta",
+            Self::ToolCallCompleted { .. } => "tool_call_completed",
+            Self::ImageProgress { .. } => "image_progress",
+            Self::McpCallStarted { .. } => "mcp_call_started",
+            Self::McpCallArgumentsDelta { .. } => "mcp_call_arguments_delta",
+            Self::McpCallCompleted { .. } => "mcp_call_completed",
+            Self::McpCallFailed { .. } => "mcp_call_failed",
+            Self::McpListToolsCompleted { .. } => "mcp_list_tools_completed",
+            Self::ResponseCreated { .. } => "response_created",
+            Self::Failed { .. } => "failed",
+            Self::Incomplete { .. } => "incomplete",
+            Self::SessionReset { .. } => "session_reset",
+            Self::Chunk => "chunk",
+            Self::Done => "done",
+            Self::Unknown => "unknown",
+        }
+    }
+
     /// Returns text delta if this is a text event
     #[must_use]
     pub fn as_text_delta(&self) -> Option<&str> {
@@ -93,6 +224,27 @@ impl StreamEvent {
         }
     }
 
+    /// Returns the arguments delta if this is an MCP call arguments event
+    #[must_use]
+    pub fn as_mcp_arguments_delta(&self) -> Option<&str> {
+        match self {
+            Self::McpCallArgumentsDelta { delta, .. } => Some(delta),
+            _ => None,
+        }
+    }
+
+    /// Returns the MCP call item ID if this is an MCP call event
+    #[must_use]
+    pub fn as_mcp_call_id(&self) -> Option<&str> {
+        match self {
+            Self::McpCallStarted { id, .. }
+            | Self::McpCallArgumentsDelta { id, .. }
+            | Self::McpCallCompleted { id, .. }
+            | Self::McpCallFailed { id, .. } => Some(id),
+            _ => None,
+        }
+    }
+
     /// Returns image progress URL if this is an image progress event
     #[must_use]
     pub fn as_image_progress(&self) -> Option<&str> {
@@ -102,12 +254,42 @@ impl StreamEvent {
         }
     }
 
+    /// Returns the base64 partial image if this is an image progress event carrying one
+    #[must_use]
+    pub fn as_partial_image(&self) -> Option<&str> {
+        match self {
+            Self::ImageProgress {
+                partial_image_b64: Some(b64),
+                ..
+            } => Some(b64),
+            _ => None,
+        }
+    }
+
     /// Returns true if this is a done event
     #[must_use]
     pub fn is_done(&self) -> bool {
         matches!(self, Self::Done)
     }
 
+    /// Returns true if the stream ends after this event
+    #[must_use]
+    pub fn is_terminal(&self) -> bool {
+        matches!(
+            self,
+            Self::Done | Self::Failed { .. } | Self::Incomplete { .. }
+        )
+    }
+
+    /// Returns the server error if this is a failed event
+    #[must_use]
+    pub fn as_failure(&self) -> Option<&crate::types::ResponseError> {
+        match self {
+            Self::Failed { error } => Some(error),
+            _ => None,
+        }
+    }
+
     /// Returns response ID if this is a ResponseCreated event
     #[must_use]
     pub fn as_response_id(&self) -> Option<&str> {
@@ -116,8 +298,347 @@ impl StreamEvent {
             _ => None,
         }
     }
+
+    /// Returns the `data:` payload the API sends for this event, or `None` for events
+    /// that have no wire form (`Chunk`, `SessionReset` and `Unknown`)
+    #[cfg(feature = "stream")]
+    pub(crate) fn to_sse_data(&self) -> Option<String> {
+        use serde_json::json;
+
+        let event = match self {
+            Self::Done => return Some("[DONE]".to_string()),
+            Self::Chunk | Self::SessionReset { .. } | Self::Unknown => return None,
+            Self::TextDelta {
+                content,
+                index,
+                content_index,
+            } => json!({
+                "type": "response.output_text.delta",
+                "delta": content,
+                "output_index": index,
+                "content_index": content_index,
+            }),
+            Self::TextStop {
+                index,
+                content_index,
+            } => json!({
+                "type": "response.output_text.done",
+                "output_index": index,
+                "content_index": content_index,
+            }),
+            Self::ToolCallCreated {
+                id,
+                call_id,
+                name,
+                index,
+            } => json!({
+                "type": "response.tool_call.created",
+                "tool_call": tool_call_json(id, call_id.as_deref(), Some(name)),
+                "output_index": index,
+            }),
+            Self::ToolCallDelta {
+                id,
+                call_id,
+                name,
+                content,
+                index,
+            } => json!({
+                "type": "response.tool_call.delta",
+                "tool_call": tool_call_json(id, call_id.as_deref(), name.as_deref()),
+                "delta": content,
+                "output_index": index,
+            }),
+            Self::ToolCallCompleted {
+                id,
+                call_id,
+                name,
+                index,
+            } => json!({
+                "type": "response.tool_call.completed",
+                "tool_call": tool_call_json(id, call_id.as_deref(), name.as_deref()),
+                "output_index": index,
+            }),
+            Self::ImageProgress { .. } => self.image_progress_json(),
+            Self::McpCallStarted {
+                id,
+                server_label,
+                name,
+            } => json!({
+                "type": "response.output_item.added",
+                "item": {"type": "mcp_call", "id": id, "server_label": server_label, "name": name},
+            }),
+            Self::McpCallArgumentsDelta { id, delta } => json!({
+                "type": "response.mcp_call_arguments.delta",
+                "item_id": id,
+                "delta": delta,
+            }),
+            Self::McpCallCompleted { id, output } => json!({
+                "type": "response.mcp_call.completed",
+                "item_id": id,
+                "output": output,
+            }),
+            Self::McpCallFailed { id, error } => json!({
+                "type": "response.mcp_call.failed",
+                "item_id": id,
+                "error": error,
+            }),
+            Self::McpListToolsCompleted { tools } => json!({
+                "type": "response.mcp_list_tools.completed",
+                "tools": tools,
+            }),
+            Self::ResponseCreated { id } => json!({
+                "type": "response.created",
+                "response": {"id": id},
+            }),
+            Self::Failed { error } => json!({
+                "type": "response.failed",
+                "response": {"status": "failed", "error": error},
+            }),
+            Self::Incomplete { reason } => json!({
+                "type": "response.incomplete",
+                "response": {"status": "incomplete", "incomplete_details": {"reason": reason}},
+            }),
+        };
+        Some(event.to_string())
+    }
+
+    /// A `response.image_generation_call.partial_image` event when there is a partial
+    /// image, otherwise a `response.image.progress` event
+    #[cfg(feature = "stream")]
+    fn image_progress_json(&self) -> serde_json::Value {
+        let Self::ImageProgress {
+            url,
+            index,
+            partial_image_b64,
+            partial_image_index,
+        } = self
+        else {
+            return serde_json::Value::Null;
+        };
+        let Some(b64) = partial_image_b64 else {
+            return serde_json::json!({
+                "type": "response.image.progress",
+                "image": {"url": url, "index": index, "partial_image_index": partial_image_index},
+            });
+        };
+        let mut data = serde_json::json!({
+            "type": "response.image_generation_call.partial_image",
+            "output_index": index,
+            "partial_image_b64": b64,
+            "partial_image_index": partial_image_index,
+        });
+        if let Some(url) = url {
+            data["url"] = url.as_str().into();
+        }
+        data
+    }
+}
+
+/// The `tool_call` object of a `response.tool_call.*` event
+#[cfg(feature = "stream")]
+fn tool_call_json(id: &str, call_id: Option<&str>, name: Option<&str>) -> serde_json::Value {
+    let mut tool_call = serde_json::json!({ "id": id });
+    if let Some(call_id) = call_id {
+        tool_call["call_id"] = call_id.into();
+    }
+    if let Some(name) = name {
+        tool_call["function"] = serde_json::json!({ "name": name });
+    }
+    tool_call
+}
+
+/// Remembers the call ID and name of each tool call from its `ToolCallCreated` event, and
+/// fills them into the call's later delta and completed events, which usually lack them
+#[cfg(feature = "stream")]
+#[derive(Debug, Default)]
+pub(crate) struct ToolCallIds {
+    by_item: std::collections::HashMap<String, (Option<String>, String)>,
+}
+
+#[cfg(feature = "stream")]
+impl ToolCallIds {
+    pub(crate) fn fill(&mut self, event: &mut StreamEvent) {
+        match event {
+            StreamEvent::ToolCallCreated {
+                id, call_id, name, ..
+            } => {
+                self.by_item
+                    .insert(id.clone(), (call_id.clone(), name.clone()));
+            }
+            StreamEvent::ToolCallDelta {
+                id, call_id, name, ..
+            }
+            | StreamEvent::ToolCallCompleted {
+                id, call_id, name, ..
+            } => {
+                if let Some((known_call_id, known_name)) = self.by_item.get(id.as_str()) {
+                    if call_id.is_none() {
+                        call_id.clone_from(known_call_id);
+                    }
+                    if name.is_none() {
+                        *name = Some(known_name.clone());
+                    }
+                }
+            }
+            _ => {}
+        }
+    }
+}
+
+/// A tool listed by an MCP server during a response
+#[derive(Debug, Clone, PartialEq, Deserialize, Serialize)]
+pub struct McpToolInfo {
+    /// Tool name
+    pub name: String,
+    /// Tool description
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub description: Option<String>,
+    /// JSON schema of the tool's arguments
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub input_schema: Option<serde_json::Value>,
+    /// Tool annotations, such as `read_only`
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub annotations: Option<serde_json::Value>,
+}
+
+/// Timeouts applied by [`Responses::stream_with_options`](crate::responses::Responses::stream_with_options).
+///
+/// Both are off by default, so a stream waits as long as the server keeps the
+/// connection open.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct StreamOptions {
+    /// Longest wait for the next piece of data, including the initial response.
+    /// SSE keep-alive comments (`: ping`) count as data.
+    pub idle_timeout: Option<std::time::Duration>,
+
+    /// Longest time the whole stream may take
+    pub overall_timeout: Option<std::time::Duration>,
+}
+
+impl StreamOptions {
+    /// Creates options with no timeouts
+    #[must_use]
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Sets the longest wait for the next piece of data
+    #[must_use]
+    pub fn with_idle_timeout(mut self, timeout: std::time::Duration) -> Self {
+        self.idle_timeout = Some(timeout);
+        self
+    }
+
+    /// Sets the longest time the whole stream may take
+    #[must_use]
+    pub fn with_overall_timeout(mut self, timeout: std::time::Duration) -> Self {
+        self.overall_timeout = Some(timeout);
+        self
+    }
 }
 
 /// Stream of events from the OpenAI Responses API
 #[cfg(feature = "stream")]
 pub type EventStream = dyn Stream<Item = crate::Result<StreamEvent>> + Send + Unpin;
+
+/// Boxed event stream returned by `Responses::stream`.
+///
+/// The stream is `Send` on native targets; on wasm32 the fetch backend's futures
+/// are not `Send`, so the bound is dropped there.
+#[cfg(all(feature = "stream", not(target_arch = "wasm32")))]
+pub type ResponseStream = std::pin::Pin<Box<dyn Stream<Item = crate::Result<StreamEvent>> + Send>>;
+
+/// Boxed event stream returned by `Responses::stream`.
+///
+/// The stream is `Send` on native targets; on wasm32 the fetch backend's futures
+/// are not `Send`, so the bound is dropped there.
+#[cfg(all(feature = "stream", target_arch = "wasm32"))]
+pub type ResponseStream = std::pin::Pin<Box<dyn Stream<Item = crate::Result<StreamEvent>>>>;
+
+/// Raw response body chunks consumed by the SSE parser
+#[cfg(all(feature = "stream", not(target_arch = "wasm32")))]
+pub(crate) type ByteStream =
+    std::pin::Pin<Box<dyn Stream<Item = reqwest::Result<bytes::Bytes>> + Send>>;
+
+/// Raw response body chunks consumed by the SSE parser
+#[cfg(all(feature = "stream", target_arch = "wasm32"))]
+pub(crate) type ByteStream = std::pin::Pin<Box<dyn Stream<Item = reqwest::Result<bytes::Bytes>>>>;
+
+/// Adapters that hand a stream of events to server frameworks.
+///
+/// Implemented for every stream of `Result<StreamEvent>`, including
+/// [`ResponseStream`].
+#[cfg(feature = "stream")]
+pub trait StreamExt2: Stream<Item = crate::Result<StreamEvent>> + Sized {
+    /// Re-serializes events into SSE frames (`data: {json}\n\n`) for proxying to browsers.
+    ///
+    /// Events keep the API's wire format, so clients that parse the OpenAI stream can read
+    /// the proxied one. The output always ends with `data: [DONE]`, also after a
+    /// `Failed` or `Incomplete` event or when the source ends early. An error is yielded
+    /// as-is and ends the stream without `[DONE]`. `Chunk` and `Unknown` events are dropped.
+    fn into_sse_bytes(self) -> impl Stream<Item = crate::Result<bytes::Bytes>> {
+        use futures::StreamExt;
+
+        futures::stream::unfold(Some(Box::pin(self)), |events| async move {
+            let mut events = events?;
+            loop {
+                let event = match events.next().await {
+                    Some(Ok(event)) => event,
+                    Some(Err(error)) => return Some((Err(error), None)),
+                    None => return Some((Ok(sse_frame("[DONE]").into()), None)),
+                };
+                let Some(data) = event.to_sse_data() else {
+                    continue;
+                };
+                let mut frame = sse_frame(&data);
+                if event.is_terminal() {
+                    if !event.is_done() {
+                        frame.push_str(&sse_frame("[DONE]"));
+                    }
+                    return Some((Ok(frame.into()), None));
+                }
+                return Some((Ok(frame.into()), Some(events)));
+            }
+        })
+    }
+
+    /// Spawns a task that sends every event to `tx`, for fanning a stream out.
+    ///
+    /// The task ends after forwarding a terminal event (`Done`, `Failed` or `Incomplete`),
+    /// when the stream ends, or when every receiver has been dropped. A stream error ends
+    /// the task and is returned through the handle.
+    #[cfg(not(target_arch = "wasm32"))]
+    fn forward_to_channel(
+        self,
+        tx: tokio::sync::mpsc::Sender<StreamEvent>,
+    ) -> tokio::task::JoinHandle<crate::Result<()>>
+    where
+        Self: Send + 'static,
+    {
+        use futures::StreamExt;
+
+        tokio::spawn(async move {
+            let mut events = Box::pin(self);
+            while let Some(event) = events.next().await {
+                let event = event?;
+                let terminal = event.is_terminal();
+                if tx.send(event).await.is_err() {
+                    crate::diag::debug!("Stream receiver dropped; stopping forwarding");
+                    return Ok(());
+                }
+                if terminal {
+                    break;
+                }
+            }
+            Ok(())
+        })
+    }
+}
+
+#[cfg(feature = "stream")]
+impl<S> StreamExt2 for S where S: Stream<Item = crate::Result<StreamEvent>> {}
+
+#[cfg(feature = "stream")]
+fn sse_frame(data: &str) -> String {
+    format!("data: {data}\n\n")
+}
@@ -0,0 +1,418 @@
+//! Reassembling a streamed response from its events.
+//!
+//! A response's output items can stream concurrently: deltas for a message and
+//! for several tool calls may interleave. Every event carries the position of its
+//! item in `Response.output` (`output_index`), and text deltas also carry the
+//! position of their part within the message (`content_index`).
+//! [`StreamCollector`] keys on both, so the collected outputs come back in the
+//! same order as in the non-streaming `Response.output`.
+//!
+//! ```rust
+//! use open_ai_rust_responses_by_sshift::types::{StreamCollector, StreamEvent};
+//!
+//! let mut collector = StreamCollector::new();
+//! for event in [
+//!     StreamEvent::ToolCallCreated {
+//!         id: "fc_1".into(),
+//!         call_id: Some("call_1".into()),
+//!         name: "lookup".into(),
+//!         index: 1,
+//!     },
+//!     StreamEvent::TextDelta { content: "Checking".into(), index: 0, content_index: 0 },
+//!     StreamEvent::ToolCallDelta {
+//!         id: "fc_1".into(),
+//!         call_id: None,
+//!         name: None,
+//!         content: "{}".into(),
+//!         index: 1,
+//!     },
+//!     StreamEvent::ToolCallCompleted { id: "fc_1".into(), call_id: None, name: None, index: 1 },
+//! ] {
+//!     collector.push_event(&event);
+//! }
+//!
+//! assert_eq!(collector.text(), "Checking");
+//! assert_eq!(collector.tool_calls()[0].arguments, "{}");
+//! // Ready to answer with `InputItem::function_call_output`
+//! assert_eq!(collector.function_calls()[0].call_id, "call_1");
+//! ```
+
+use crate::types::{FunctionCallInfo, ItemStatus, StreamEvent};
+use chrono::{DateTime, Utc};
+use std::collections::BTreeMap;
+
+/// A function call reassembled from its stream events
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct CollectedToolCall {
+    /// Position of the call in `Response.output`
+    pub output_index: u32,
+    /// Tool call ID (the output item ID)
+    pub id: String,
+    /// Call ID to submit the output under; empty until an event carried it
+    pub call_id: String,
+    /// Function name
+    pub name: String,
+    /// Concatenated argument deltas
+    pub arguments: String,
+    /// Whether the completion event was seen
+    pub completed: bool,
+}
+
+impl CollectedToolCall {
+    /// Converts the call into a [`FunctionCallInfo`], or `None` while its call ID is
+    /// unknown.
+    ///
+    /// The status is `completed` once the completion event was seen, `in_progress` before.
+    #[must_use]
+    pub fn to_info(&self) -> Option<FunctionCallInfo> {
+        if self.call_id.is_empty() {
+            return None;
+        }
+        Some(FunctionCallInfo {
+            name: self.name.clone(),
+            arguments: self.arguments.clone(),
+            call_id: self.call_id.clone(),
+            id: Some(self.id.clone()),
+            status: Some(if self.completed {
+                ItemStatus::Completed
+            } else {
+                ItemStatus::InProgress
+            }),
+        })
+    }
+}
+
+/// An output item reassembled from its stream events
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum CollectedOutput {
+    /// Message text, one entry per content part in `content_index` order
+    Text(Vec<String>),
+    /// A function call
+    ToolCall(CollectedToolCall),
+}
+
+#[derive(Debug, Clone)]
+enum Slot {
+    Text(BTreeMap<u32, String>),
+    ToolCall(CollectedToolCall),
+}
+
+/// Collects text and tool call events by `output_index` and `content_index`.
+#[derive(Debug, Clone, Default)]
+pub struct StreamCollector {
+    slots: BTreeMap<u32, Slot>,
+    first_token_at: Option<DateTime<Utc>>,
+}
+
+impl StreamCollector {
+    /// Creates an empty collector
+    #[must_use]
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Records a text or tool call event; other events are ignored.
+    ///
+    /// Returns true if the event was recorded. Events whose `output_index` is
+    /// already taken by an item of the other kind are ignored.
+    pub fn push_event(&mut self, event: &StreamEvent) -> bool {
+        match event {
+            StreamEvent::TextDelta {
+                content,
+                index,
+                content_index,
+            } => match self
+                .slots
+                .entry(*index)
+                .or_insert_with(|| Slot::Text(BTreeMap::new()))
+            {
+                Slot::Text(parts) => {
+                    parts.entry(*content_index).or_default().push_str(content);
+                    self.first_token_at.get_or_insert_with(Utc::now);
+                    true
+                }
+                Slot::ToolCall(_) => false,
+            },
+            StreamEvent::ToolCallCreated {
+                id,
+                call_id,
+                name,
+                index,
+            } => self
+                .tool_call(*index, id, call_id.as_deref(), Some(name))
+                .is_some(),
+            StreamEvent::ToolCallDelta {
+                id,
+                call_id,
+                name,
+                content,
+                index,
+            } => self
+                .tool_call(*index, id, call_id.as_deref(), name.as_deref())
+                .is_some_and(|call| {
+                    call.arguments.push_str(content);
+                    true
+                }),
+            StreamEvent::ToolCallCompleted {
+                id,
+                call_id,
+                name,
+                index,
+            } => self
+                .tool_call(*index, id, call_id.as_deref(), name.as_deref())
+                .is_some_and(|call| {
+                    call.completed = true;
+                    true
+                }),
+            _ => false,
+        }
+    }
+
+    /// Returns the call at `index`, recording the IDs and name the event carried
+    fn tool_call(
+        &mut self,
+        index: u32,
+        id: &str,
+        call_id: Option<&str>,
+        name: Option<&str>,
+    ) -> Option<&mut CollectedToolCall> {
+        let slot = self.slots.entry(index).or_insert_with(|| {
+            Slot::ToolCall(CollectedToolCall {
+                output_index: index,
+                ..CollectedToolCall::default()
+            })
+        });
+        match slot {
+            Slot::ToolCall(call) => {
+                if call.id.is_empty() {
+                    call.id = id.to_string();
+                }
+                if let Some(call_id) = call_id.filter(|_| call.call_id.is_empty()) {
+                    call.call_id = call_id.to_string();
+                }
+                if let Some(name) = name.filter(|_| call.name.is_empty()) {
+                    call.name = name.to_string();
+                }
+                Some(call)
+            }
+            Slot::Text(_) => None,
+        }
+    }
+
+    /// When the first text delta was recorded, by the local clock.
+    ///
+    /// Compare with [`Response::created_at`](crate::Response::created_at) for the time
+    /// to first token; `None` until text arrives.
+    #[must_use]
+    pub fn first_token_at(&self) -> Option<DateTime<Utc>> {
+        self.first_token_at
+    }
+
+    /// Returns the collected outputs in `output_index` order
+    #[must_use]
+    pub fn outputs(&self) -> Vec<CollectedOutput> {
+        self.slots
+            .values()
+            .map(|slot| match slot {
+                Slot::Text(parts) => CollectedOutput::Text(parts.values().cloned().collect()),
+                Slot::ToolCall(call) => CollectedOutput::ToolCall(call.clone()),
+            })
+            .collect()
+    }
+
+    /// Returns all message text, in output and content order
+    #[must_use]
+    pub fn text(&self) -> String {
+        self.slots
+            .values()
+            .filter_map(|slot| match slot {
+                Slot::Text(parts) => Some(parts.values().map(String::as_str)),
+                Slot::ToolCall(_) => None,
+            })
+            .flatten()
+            .collect()
+    }
+
+    /// Returns the tool calls in `output_index` order
+    #[must_use]
+    pub fn tool_calls(&self) -> Vec<CollectedToolCall> {
+        self.slots
+            .values()
+            .filter_map(|slot| match slot {
+                Slot::ToolCall(call) => Some(call.clone()),
+                Slot::Text(_) => None,
+            })
+            .collect()
+    }
+
+    /// Returns the completed tool calls with a known call ID, in `output_index` order.
+    ///
+    /// These can be answered with
+    /// [`InputItem::function_call_output`](crate::types::InputItem::function_call_output)
+    /// without retrieving the response first.
+    #[must_use]
+    pub fn function_calls(&self) -> Vec<FunctionCallInfo> {
+        self.slots
+            .values()
+            .filter_map(|slot| match slot {
+                Slot::ToolCall(call) if call.completed => call.to_info(),
+                _ => None,
+            })
+            .collect()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn text(content: &str, index: u32, content_index: u32) -> StreamEvent {
+        StreamEvent::TextDelta {
+            content: content.to_string(),
+            index,
+            content_index,
+        }
+    }
+
+    fn args(id: &str, content: &str, index: u32) -> StreamEvent {
+        StreamEvent::ToolCallDelta {
+            id: id.to_string(),
+            call_id: None,
+            name: None,
+            content: content.to_string(),
+            index,
+        }
+    }
+
+    #[test]
+    fn interleaved_events_are_reassembled_in_output_order() {
+        let events = [
+            StreamEvent::ToolCallCreated {
+                id: "call_b".to_string(),
+                call_id: None,
+                name: "weather".to_string(),
+                index: 2,
+            },
+            text("Let me ", 0, 0),
+            StreamEvent::ToolCallCreated {
+                id: "call_a".to_string(),
+                call_id: None,
+                name: "time".to_string(),
+                index: 1,
+            },
+            args("call_b", r#"{"city":"#, 2),
+            args("call_a", r#"{"tz":"UTC"}"#, 1),
+            text("Sources: ", 0, 1),
+            text("check.", 0, 0),
+            args("call_b", r#""Paris"}"#, 2),
+            text("docs", 0, 1),
+            StreamEvent::ToolCallCompleted {
+                id: "call_b".to_string(),
+                call_id: None,
+                name: None,
+                index: 2,
+            },
+            StreamEvent::Done,
+        ];
+        let mut collector = StreamCollector::new();
+        let recorded = events.iter().filter(|e| collector.push_event(e)).count();
+        assert_eq!(recorded, events.len() - 1);
+
+        assert_eq!(collector.text(), "Let me check.Sources: docs");
+        let calls = collector.tool_calls();
+        assert_eq!(
+            calls
+                .iter()
+                .map(|c| (
+                    c.output_index,
+                    c.name.as_str(),
+                    c.arguments.as_str(),
+                    c.completed
+                ))
+                .collect::<Vec<_>>(),
+            [
+                (1, "time", r#"{"tz":"UTC"}"#, false),
+                (2, "weather", r#"{"city":"Paris"}"#, true),
+            ]
+        );
+        assert_eq!(
+            collector.outputs()[0],
+            CollectedOutput::Text(vec![
+                "Let me check.".to_string(),
+                "Sources: docs".to_string()
+            ])
+        );
+    }
+
+    #[test]
+    fn mismatched_events_do_not_overwrite_an_output() {
+        let mut collector = StreamCollector::new();
+        assert!(collector.push_event(&text("hi", 0, 0)));
+        assert!(!collector.push_event(&args("call_1", "{}", 0)));
+        assert!(collector.push_event(&args("call_1", "{}", 1)));
+        assert!(!collector.push_event(&text("stray", 1, 0)));
+
+        assert_eq!(collector.text(), "hi");
+        assert_eq!(collector.tool_calls()[0].id, "call_1");
+        assert!(collector.tool_calls()[0].name.is_empty());
+    }
+
+    #[test]
+    fn first_token_at_is_set_by_the_first_text_delta_only() {
+        let mut collector = StreamCollector::new();
+        collector.push_event(&args("call_1", "{}", 1));
+        assert_eq!(collector.first_token_at(), None);
+
+        collector.push_event(&text("hi", 0, 0));
+        let first = collector.first_token_at().unwrap();
+        std::thread::sleep(std::time::Duration::from_millis(5));
+        collector.push_event(&text(" there", 0, 0));
+        assert_eq!(collector.first_token_at(), Some(first));
+    }
+
+    #[test]
+    fn function_calls_take_ids_from_whichever_event_carried_them() {
+        let mut collector = StreamCollector::new();
+        collector.push_event(&StreamEvent::ToolCallCreated {
+            id: "fc_1".to_string(),
+            call_id: Some("call_1".to_string()),
+            name: "lookup".to_string(),
+            index: 0,
+        });
+        collector.push_event(&args("fc_1", r#"{"q":1}"#, 0));
+        // Only known from the delta
+        collector.push_event(&StreamEvent::ToolCallDelta {
+            id: "fc_2".to_string(),
+            call_id: Some("call_2".to_string()),
+            name: Some("search".to_string()),
+            content: "{}".to_string(),
+            index: 1,
+        });
+        // Never learns its call ID
+        collector.push_event(&args("fc_3", "{}", 2));
+        for (id, index) in [("fc_1", 0), ("fc_3", 2)] {
+            collector.push_event(&StreamEvent::ToolCallCompleted {
+                id: id.to_string(),
+                call_id: None,
+                name: None,
+                index,
+            });
+        }
+
+        let calls = collector.function_calls();
+        assert_eq!(calls.len(), 1);
+        assert_eq!(calls[0].call_id, "call_1");
+        assert_eq!(calls[0].name, "lookup");
+        assert_eq!(calls[0].id.as_deref(), Some("fc_1"));
+        assert!(calls[0].is_completed());
+        assert_eq!(calls[0].arguments_json().unwrap()["q"], 1);
+
+        let pending = collector.tool_calls()[1].to_info().unwrap();
+        assert_eq!(
+            (pending.call_id.as_str(), pending.name.as_str()),
+            ("call_2", "search")
+        );
+        assert!(pending.is_in_progress());
+        assert!(collector.tool_calls()[2].to_info().is_none());
+    }
+}
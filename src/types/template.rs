@@ -0,0 +1,400 @@
+//! Reusable request configuration.
+//!
+//! A [`RequestTemplate`] holds everything a [`Request`] does except the input and
+//! per-call state (`stream`, `previous_response_id`). It serializes with every field
+//! optional, so templates can live in config files:
+//!
+//! ```rust
+//! use open_ai_rust_responses_by_sshift::types::RequestTemplate;
+//!
+//! let template: RequestTemplate = serde_json::from_str(
+//!     r#"{"model": "gpt-4o-mini", "instructions": "Answer briefly", "include": ["web_search_call.results"]}"#,
+//! )
+//! .unwrap();
+//!
+//! let request = template.request("What is the capital of France?").build();
+//! assert_eq!(request.instructions.as_deref(), Some("Answer briefly"));
+//! ```
+
+use crate::types::{Include, Input, Metadata, Request, RequestBuilder, Tool};
+use serde::{Deserialize, Serialize};
+
+/// Shared configuration for many requests.
+///
+/// Build one from a request (`RequestTemplate::from(Request::builder()...build())`), a
+/// struct literal or a config file, then start each request with
+/// [`RequestTemplate::request`] or mix it into a builder with
+/// [`RequestBuilder::apply_template`].
+///
+/// When a template is applied to a builder:
+/// - fields the template sets override the builder's, fields it leaves unset are kept
+/// - tools are merged as by [`RequestBuilder::merge_tools`]: a template tool with the same
+///   [`identity_key`](Tool::identity_key) as an existing one replaces it in place
+/// - include lists are unioned, keeping the builder's order first
+/// - metadata entries are merged, the template's value winning for a shared key
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct RequestTemplate {
+    /// The model to use
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub model: Option<crate::types::Model>,
+
+    /// System instructions
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub instructions: Option<String>,
+
+    /// Maximum number of output tokens
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub max_output_tokens: Option<u32>,
+
+    /// Sampling temperature
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub temperature: Option<f32>,
+
+    /// Nucleus sampling parameter
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub top_p: Option<f32>,
+
+    /// Number of top log probabilities to return
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub top_logprobs: Option<u32>,
+
+    /// Tools that the model may call
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub tools: Option<Vec<Tool>>,
+
+    /// Which tool the model calls
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub tool_choice: Option<crate::types::ToolChoice>,
+
+    /// Whether tools can be called in parallel
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub parallel_tool_calls: Option<bool>,
+
+    /// Metadata attached to every request
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub metadata: Option<Metadata>,
+
+    /// Additional fields to include in the response
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub include: Option<Vec<Include>>,
+
+    /// Reasoning parameters
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub reasoning: Option<crate::types::ReasoningParams>,
+
+    /// Background processing mode
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub background: Option<bool>,
+
+    /// Whether to store the conversation state
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub store: Option<bool>,
+
+    /// Server-side truncation strategy
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub truncation: Option<crate::types::TruncationSetting>,
+
+    /// Text generation configuration
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub text: Option<crate::types::TextConfig>,
+
+    /// User identifier
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub user: Option<String>,
+
+    /// Stable identifier for the end user
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub safety_identifier: Option<String>,
+
+    /// Prompt cache key
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub prompt_cache_key: Option<String>,
+
+    /// Processing tier
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub service_tier: Option<crate::types::ServiceTier>,
+}
+
+impl RequestTemplate {
+    /// Starts a builder with this template applied and the given input
+    #[must_use]
+    pub fn request(&self, input: impl Into<Input>) -> RequestBuilder {
+        let builder = RequestBuilder::new().apply_template(self);
+        match input.into() {
+            Input::Text(text) => builder.input(text),
+            Input::Items(items) => builder.input_items(items),
+        }
+    }
+
+    /// Merges this template into `request` using the rules on [`RequestTemplate`]
+    pub(crate) fn apply_to(&self, request: &mut Request) {
+        fn set<T: Clone>(target: &mut Option<T>, value: Option<&T>) {
+            if let Some(value) = value {
+                *target = Some(value.clone());
+            }
+        }
+
+        if let Some(model) = &self.model {
+            request.model = model.clone();
+        }
+        set(&mut request.instructions, self.instructions.as_ref());
+        set(
+            &mut request.max_output_tokens,
+            self.max_output_tokens.as_ref(),
+        );
+        set(&mut request.temperature, self.temperature.as_ref());
+        set(&mut request.top_p, self.top_p.as_ref());
+        set(&mut request.top_logprobs, self.top_logprobs.as_ref());
+        set(&mut request.tool_choice, self.tool_choice.as_ref());
+        set(
+            &mut request.parallel_tool_calls,
+            self.parallel_tool_calls.as_ref(),
+        );
+        set(&mut request.reasoning, self.reasoning.as_ref());
+        set(&mut request.background, self.background.as_ref());
+        set(&mut request.store, self.store.as_ref());
+        set(&mut request.truncation, self.truncation.as_ref());
+        set(&mut request.text, self.text.as_ref());
+        set(&mut request.user, self.user.as_ref());
+        set(
+            &mut request.safety_identifier,
+            self.safety_identifier.as_ref(),
+        );
+        set(
+            &mut request.prompt_cache_key,
+            self.prompt_cache_key.as_ref(),
+        );
+        set(&mut request.service_tier, self.service_tier.as_ref());
+
+        if let Some(tools) = &self.tools {
+            crate::types::tools::merge_tools(
+                request.tools.get_or_insert_with(Vec::new),
+                tools.iter().cloned(),
+            );
+        }
+        if let Some(include) = &self.include {
+            let merged = request.include.get_or_insert_with(Vec::new);
+            for item in include {
+                if !merged.contains(item) {
+                    merged.push(item.clone());
+                }
+            }
+        }
+        if let Some(metadata) = &self.metadata {
+            let merged: Metadata = request
+                .metadata
+                .iter()
+                .flat_map(Metadata::iter)
+                .chain(metadata.iter())
+                .collect();
+            request.metadata = Some(merged);
+        }
+    }
+}
+
+/// Keeps the request's configuration; `input`, `stream` and `previous_response_id` are
+/// dropped, and a legacy `max_tokens` becomes `max_output_tokens` if that is unset
+impl From<Request> for RequestTemplate {
+    fn from(request: Request) -> Self {
+        Self {
+            model: Some(request.model),
+            instructions: request.instructions,
+            max_output_tokens: request.max_output_tokens.or(request.max_tokens),
+            temperature: request.temperature,
+            top_p: request.top_p,
+            top_logprobs: request.top_logprobs,
+            tools: request.tools,
+            tool_choice: request.tool_choice,
+            parallel_tool_calls: request.parallel_tool_calls,
+            metadata: request.metadata,
+            include: request.include,
+            reasoning: request.reasoning,
+            background: request.background,
+            store: request.store,
+            truncation: request.truncation,
+            text: request.text,
+            user: request.user,
+            safety_identifier: request.safety_identifier,
+            prompt_cache_key: request.prompt_cache_key,
+            service_tier: request.service_tier,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::types::{InputItem, Model, ToolChoice};
+    use serde_json::json;
+
+    fn search_tool(vector_store: &str) -> Tool {
+        Tool::file_search(vec![vector_store.to_string()])
+    }
+
+    fn template() -> RequestTemplate {
+        RequestTemplate::from(
+            Request::builder()
+                .model(Model::GPT4oMini)
+                .instructions("Answer briefly")
+                .temperature(0.2)
+                .tools(vec![
+                    Tool::function("lookup", "Look up an order", json!({"type": "object"})),
+                    search_tool("vs_docs"),
+                ])
+                .include(vec![Include::FileSearchResults])
+                .metadata_entry("app", "support")
+                .user("team-a")
+                .input("ignored")
+                .previous_response_id("resp_ignored")
+                .build(),
+        )
+    }
+
+    #[test]
+    fn request_starts_from_the_template() {
+        let request = template().request("Where is my order?").build();
+        assert_eq!(request.model, Model::GPT4oMini);
+        assert_eq!(request.instructions.as_deref(), Some("Answer briefly"));
+        assert_eq!(request.temperature, Some(0.2));
+        assert_eq!(request.tools.as_ref().map(Vec::len), Some(2));
+        assert_eq!(request.user.as_deref(), Some("team-a"));
+        assert!(request.previous_response_id.is_none());
+        assert!(matches!(&request.input, Input::Text(text) if text == "Where is my order?"));
+
+        let items = vec![InputItem::message(
+            "user",
+            vec![InputItem::content_text("hi")],
+        )];
+        let request = template().request(items).build();
+        assert!(matches!(&request.input, Input::Items(items) if items.len() == 1));
+    }
+
+    #[test]
+    fn set_fields_override_and_unset_fields_are_kept() {
+        let request = Request::builder()
+            .model(Model::GPT4o)
+            .instructions("Be verbose")
+            .max_output_tokens(500)
+            .tool_choice(ToolChoice::auto())
+            .apply_template(&template())
+            .input("hi")
+            .build();
+
+        assert_eq!(request.model, Model::GPT4oMini);
+        assert_eq!(request.instructions.as_deref(), Some("Answer briefly"));
+        // Not set on the template
+        assert_eq!(request.max_output_tokens, Some(500));
+        assert_eq!(request.tool_choice, Some(ToolChoice::auto()));
+
+        // Setters called after the template win
+        let request = Request::builder()
+            .apply_template(&template())
+            .instructions("Be verbose")
+            .build();
+        assert_eq!(request.instructions.as_deref(), Some("Be verbose"));
+
+        // An empty template changes nothing
+        let plain = Request::builder().model(Model::O3).user("x").build();
+        let applied = Request::builder()
+            .model(Model::O3)
+            .user("x")
+            .apply_template(&RequestTemplate::default())
+            .build();
+        assert_eq!(
+            serde_json::to_value(&plain).unwrap(),
+            serde_json::to_value(&applied).unwrap()
+        );
+    }
+
+    #[test]
+    fn tools_concatenate_and_replace_matching_tools_in_place() {
+        let request = Request::builder()
+            .tools(vec![
+                Tool::web_search_preview(),
+                Tool::function("lookup", "Old description", json!({})),
+                Tool::function("refund", "Issue a refund", json!({})),
+            ])
+            .apply_template(&template())
+            .build();
+
+        let tools = request.tools.unwrap();
+        let summary: Vec<_> = tools
+            .iter()
+            .map(|tool| (tool.tool_type.as_str(), tool.name.as_deref()))
+            .collect();
+        assert_eq!(
+            summary,
+            [
+                ("web_search_preview", None),
+                ("function", Some("lookup")),
+                ("function", Some("refund")),
+                ("file_search", None),
+            ]
+        );
+        assert_eq!(tools[1].description.as_deref(), Some("Look up an order"));
+
+        // Built-in tools are keyed by type, MCP tools by server label
+        let template = RequestTemplate {
+            tools: Some(vec![
+                search_tool("vs_new"),
+                Tool::mcp("github", "https://example.com/mcp", None),
+            ]),
+            ..RequestTemplate::default()
+        };
+        let request = Request::builder()
+            .tools(vec![
+                search_tool("vs_old"),
+                Tool::mcp("deepwiki", "https://example.com/wiki", None),
+            ])
+            .apply_template(&template)
+            .build();
+        let tools = request.tools.unwrap();
+        assert_eq!(tools.len(), 3);
+        assert_eq!(tools[0].vector_store_ids, Some(vec!["vs_new".to_string()]));
+        assert_eq!(tools[2].server_label.as_deref(), Some("github"));
+    }
+
+    #[test]
+    fn includes_union_and_metadata_merges() {
+        let request = Request::builder()
+            .include(vec![
+                Include::ReasoningEncryptedContent,
+                Include::FileSearchResults,
+            ])
+            .metadata_entry("app", "billing")
+            .metadata_entry("tenant", "acme")
+            .apply_template(&template())
+            .build();
+
+        assert_eq!(
+            request.include,
+            Some(vec![
+                Include::ReasoningEncryptedContent,
+                Include::FileSearchResults
+            ])
+        );
+        let metadata = request.metadata.unwrap();
+        assert_eq!(metadata.get("app"), Some("support"));
+        assert_eq!(metadata.get("tenant"), Some("acme"));
+
+        let request = Request::builder().apply_template(&template()).build();
+        assert_eq!(request.include, Some(vec![Include::FileSearchResults]));
+    }
+
+    #[test]
+    fn templates_round_trip_through_config_files() {
+        let template = template();
+        let json = serde_json::to_value(&template).unwrap();
+        assert_eq!(json["model"], "gpt-4o-mini");
+        assert!(json.get("input").is_none());
+        assert!(json.get("top_p").is_none());
+        let parsed: RequestTemplate = serde_json::from_value(json).unwrap();
+        assert_eq!(parsed, template);
+
+        let partial: RequestTemplate =
+            serde_json::from_value(json!({"instructions": "Be kind"})).unwrap();
+        assert_eq!(partial.model, None);
+        assert_eq!(partial.instructions.as_deref(), Some("Be kind"));
+    }
+}
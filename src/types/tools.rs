@@ -1,12 +1,63 @@
 use serde::{Deserialize, Serialize};
 use std::collections::HashMap;
 
-/// Container configuration for tools that support it
+/// Container configuration for tools that support it.
+///
+/// Serialized as `{"type": "auto", "file_ids": [...]}`, or as the plain container ID
+/// string when it refers to an existing container created through
+/// [`Containers`](crate::containers::Containers).
 #[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+#[serde(from = "ContainerRepr", into = "ContainerRepr")]
 pub struct Container {
-    /// Container type (e.g., "default", "secure", etc.)
-    #[serde(rename = "type")]
+    /// Container type (e.g., "default", "auto"); `"id"` for a container referenced by ID
     pub container_type: String,
+
+    /// Files copied into an auto container before the code runs
+    pub file_ids: Option<Vec<String>>,
+
+    /// ID of an existing container (`cntr_...`) to run in
+    pub id: Option<String>,
+}
+
+/// Wire form of [`Container`]: an existing container's ID, or an inline configuration
+#[derive(Serialize, Deserialize)]
+#[serde(untagged)]
+enum ContainerRepr {
+    Id(String),
+    Config {
+        #[serde(rename = "type")]
+        container_type: String,
+        #[serde(default, skip_serializing_if = "Option::is_none")]
+        file_ids: Option<Vec<String>>,
+    },
+}
+
+impl From<ContainerRepr> for Container {
+    fn from(repr: ContainerRepr) -> Self {
+        match repr {
+            ContainerRepr::Id(id) => Self::id(id),
+            ContainerRepr::Config {
+                container_type,
+                file_ids,
+            } => Self {
+                container_type,
+                file_ids,
+                id: None,
+            },
+        }
+    }
+}
+
+impl From<Container> for ContainerRepr {
+    fn from(container: Container) -> Self {
+        match container.id {
+            Some(id) => Self::Id(id),
+            None => Self::Config {
+                container_type: container.container_type,
+                file_ids: container.file_ids,
+            },
+        }
+    }
 }
 
 impl Container {
@@ -15,6 +66,8 @@ impl Container {
     pub fn default_type() -> Self {
         Self {
             container_type: "default".to_string(),
+            file_ids: None,
+            id: None,
         }
     }
 
@@ -23,6 +76,32 @@ impl Container {
     pub fn auto_type() -> Self {
         Self {
             container_type: "auto".to_string(),
+            file_ids: None,
+            id: None,
+        }
+    }
+
+    /// Creates an auto container that starts with the given files preloaded
+    #[must_use]
+    pub fn auto_with_files<I, S>(file_ids: I) -> Self
+    where
+        I: IntoIterator<Item = S>,
+        S: Into<String>,
+    {
+        Self {
+            file_ids: Some(file_ids.into_iter().map(Into::into).collect()),
+            ..Self::auto_type()
+        }
+    }
+
+    /// Refers to an existing container by ID, e.g. one made with
+    /// [`Containers::create`](crate::containers::Containers::create)
+    #[must_use]
+    pub fn id(container_id: impl Into<String>) -> Self {
+        Self {
+            container_type: "id".to_string(),
+            file_ids: None,
+            id: Some(container_id.into()),
         }
     }
 }
@@ -125,21 +204,213 @@ pub struct GrammarRule {
 #[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
 #[serde(untagged)]
 pub enum ToolChoice {
-    /// Automatic tool choice
+    /// Tool choice mode: `"auto"`, `"required"` or `"none"`
     String(String),
 
-    /// Specific tool choice
+    /// Forces a specific tool, e.g. `{"type": "function", "name": "get_weather"}`
     Object {
-        /// Type of tool choice (always "function")
+        /// Type of the forced tool (`"function"` or `"custom"`)
         #[serde(rename = "type")]
         choice_type: String,
 
-        /// Function to use
-        function: ToolChoiceFunction,
+        /// Name of the tool to call
+        name: String,
     },
+
+    /// Limits the model to a subset of the request's tools; see [`ToolChoice::allowed`]
+    Allowed(AllowedTools),
+
+    /// Any other shape, e.g. a hosted tool (`{"type": "file_search"}`) echoed on a
+    /// response; sent back as-is
+    Other(serde_json::Value),
+}
+
+/// Subset of the request's tools the model may call, sent as
+/// `{"type": "allowed_tools", "mode": ..., "tools": [...]}`.
+///
+/// Restricting tools this way keeps the `tools` array, and with it the prompt cache,
+/// unchanged from request to request.
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq, Eq)]
+#[serde(tag = "type", rename = "allowed_tools", try_from = "AllowedToolsWire")]
+pub struct AllowedTools {
+    /// Whether the model may answer without calling a tool
+    pub mode: AllowedToolsMode,
+
+    /// Tools the model may call
+    pub tools: Vec<ToolRef>,
 }
 
-/// Function choice for tool choice
+/// `AllowedTools` as received; serde does not check a struct's tag when deserializing,
+/// so the untagged [`ToolChoice`] would otherwise take any `{mode, tools}` object for one
+#[derive(Deserialize)]
+struct AllowedToolsWire {
+    #[serde(rename = "type")]
+    choice_type: String,
+    mode: AllowedToolsMode,
+    tools: Vec<ToolRef>,
+}
+
+impl TryFrom<AllowedToolsWire> for AllowedTools {
+    type Error = String;
+
+    fn try_from(wire: AllowedToolsWire) -> Result<Self, Self::Error> {
+        if wire.choice_type != "allowed_tools" {
+            return Err(format!(
+                "expected type `allowed_tools`, got `{}`",
+                wire.choice_type
+            ));
+        }
+        Ok(Self {
+            mode: wire.mode,
+            tools: wire.tools,
+        })
+    }
+}
+
+/// Whether a model limited to [`AllowedTools`] must call one of them
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, PartialEq, Eq, Default)]
+#[serde(rename_all = "snake_case")]
+pub enum AllowedToolsMode {
+    /// The model decides whether to call an allowed tool
+    #[default]
+    Auto,
+    /// The model must call one of the allowed tools
+    Required,
+}
+
+/// Reference to one of a request's tools: a function or custom tool by name, a built-in
+/// tool by type, or an MCP server by label
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq, Eq)]
+pub struct ToolRef {
+    /// Type of the tool (`"function"`, `"custom"`, `"mcp"`, `"file_search"`, ...)
+    #[serde(rename = "type")]
+    pub tool_type: String,
+
+    /// Name of a function or custom tool, or of one tool on an MCP server
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub name: Option<String>,
+
+    /// Label of an MCP server
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub server_label: Option<String>,
+}
+
+impl ToolRef {
+    /// References a function tool
+    pub fn function(name: impl Into<String>) -> Self {
+        Self::named("function", name)
+    }
+
+    /// References a custom tool
+    pub fn custom(name: impl Into<String>) -> Self {
+        Self::named("custom", name)
+    }
+
+    /// References a built-in tool by type, e.g. `"file_search"` or `"image_generation"`
+    pub fn built_in(tool_type: impl Into<String>) -> Self {
+        Self {
+            tool_type: tool_type.into(),
+            name: None,
+            server_label: None,
+        }
+    }
+
+    /// References every tool on an MCP server
+    pub fn mcp(server_label: impl Into<String>) -> Self {
+        Self {
+            tool_type: "mcp".to_string(),
+            name: None,
+            server_label: Some(server_label.into()),
+        }
+    }
+
+    /// References one tool on an MCP server
+    pub fn mcp_tool(server_label: impl Into<String>, name: impl Into<String>) -> Self {
+        Self {
+            name: Some(name.into()),
+            ..Self::mcp(server_label)
+        }
+    }
+
+    /// References `tool`: by name for function and custom tools, by server label for
+    /// MCP servers, and by type for built-in tools
+    #[must_use]
+    pub fn for_tool(tool: &Tool) -> Self {
+        let (tool_type, name) = tool.identity_key();
+        match (tool_type, name) {
+            ("mcp", Some(label)) => Self::mcp(label),
+            ("function" | "custom", Some(name)) => Self::named(tool_type, name),
+            _ => Self::built_in(tool_type),
+        }
+    }
+
+    /// Whether this reference points at `tool`. A reference to one tool on an MCP server
+    /// matches the server, since its tools are only known once the server is listed.
+    #[must_use]
+    pub fn matches(&self, tool: &Tool) -> bool {
+        let (tool_type, name) = tool.identity_key();
+        if self.tool_type != tool_type {
+            return false;
+        }
+        if tool_type == "mcp" {
+            return self.server_label.as_deref() == name;
+        }
+        self.name.as_deref().is_none_or(|own| Some(own) == name)
+    }
+
+    /// Short label for messages: the name, `server/name` for an MCP tool, the server
+    /// label, or the type
+    #[must_use]
+    pub fn label(&self) -> String {
+        match (&self.server_label, &self.name) {
+            (Some(server), Some(name)) => format!("{server}/{name}"),
+            (Some(server), None) => server.clone(),
+            (None, Some(name)) => name.clone(),
+            (None, None) => self.tool_type.clone(),
+        }
+    }
+
+    fn named(tool_type: impl Into<String>, name: impl Into<String>) -> Self {
+        Self {
+            name: Some(name.into()),
+            ..Self::built_in(tool_type)
+        }
+    }
+}
+
+/// Labels of the tools an allowed-tools `choice` lists that are not in `tools`
+pub(crate) fn unknown_allowed_tools(choice: Option<&ToolChoice>, tools: &[Tool]) -> Vec<String> {
+    let Some(ToolChoice::Allowed(allowed)) = choice else {
+        return Vec::new();
+    };
+    allowed
+        .tools
+        .iter()
+        .filter(|tool_ref| !tools.iter().any(|tool| tool_ref.matches(tool)))
+        .map(ToolRef::label)
+        .collect()
+}
+
+/// Label of the tool a forcing `choice` names when the request's `tools` do not define it.
+///
+/// Mode strings and allowed-tools lists force no single tool and are not checked here.
+pub(crate) fn unresolved_tool_choice(
+    choice: Option<&ToolChoice>,
+    tools: &[Tool],
+) -> Option<String> {
+    let tool_ref = match choice? {
+        ToolChoice::Object { choice_type, name } => {
+            ToolRef::named(choice_type.as_str(), name.as_str())
+        }
+        ToolChoice::Other(value) => serde_json::from_value::<ToolRef>(value.clone()).ok()?,
+        ToolChoice::String(_) | ToolChoice::Allowed(_) => return None,
+    };
+    (!tools.iter().any(|tool| tool_ref.matches(tool))).then(|| tool_ref.label())
+}
+
+/// Function choice in the Chat Completions `tool_choice` shape.
+///
+/// The Responses API takes the name at the top level instead; see [`ToolChoice::function`].
 #[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
 pub struct ToolChoiceFunction {
     /// Name of the function to use
@@ -192,6 +463,30 @@ impl Tool {
         }
     }
 
+    /// Creates a custom tool whose input is free-form text rather than JSON.
+    ///
+    /// The model calls it with a [`ResponseItem::CustomToolCall`](crate::types::ResponseItem::CustomToolCall);
+    /// answer with [`InputItem::custom_tool_output`](crate::types::InputItem::custom_tool_output).
+    #[must_use]
+    pub fn custom(name: impl Into<String>, description: impl Into<String>) -> Self {
+        Self {
+            tool_type: "custom".to_string(),
+            name: Some(name.into()),
+            description: Some(description.into()),
+            parameters: None,
+            vector_store_ids: None,
+            container: None,
+            partial_images: None,
+            require_approval: None,
+            server_label: None,
+            server_url: None,
+            headers: None,
+            function: None,
+            free_form: None,
+            grammar: None,
+        }
+    }
+
     /// Creates a grammar-constrained free-form function (GPT-5)
     #[must_use]
     pub fn grammar_function(
@@ -374,6 +669,56 @@ impl Tool {
             grammar: None,
         }
     }
+
+    /// Returns what identifies this tool within a request: its type plus the function
+    /// name (including a legacy `function.name`) or MCP server label.
+    ///
+    /// Built-in tools such as `web_search_preview` have no name, so every tool of one
+    /// built-in type shares a key. Two tools with the same key in one request are
+    /// duplicates.
+    #[must_use]
+    pub fn identity_key(&self) -> (&str, Option<&str>) {
+        let name = self
+            .name
+            .as_deref()
+            .or_else(|| self.function.as_ref().map(|f| f.name.as_str()))
+            .or(self.server_label.as_deref());
+        (self.tool_type.as_str(), name)
+    }
+}
+
+/// Adds `incoming` to `tools`, replacing any tool with the same
+/// [`identity_key`](Tool::identity_key) in place, so the last definition wins
+pub(crate) fn merge_tools(tools: &mut Vec<Tool>, incoming: impl IntoIterator<Item = Tool>) {
+    for tool in incoming {
+        match tools
+            .iter_mut()
+            .find(|existing| existing.identity_key() == tool.identity_key())
+        {
+            Some(existing) => *existing = tool,
+            None => tools.push(tool),
+        }
+    }
+}
+
+/// Names the tools that share an identity key with an earlier one, each listed once:
+/// the function name or MCP server label, or the type for built-in tools
+pub(crate) fn duplicate_tool_names(tools: &[Tool]) -> Vec<String> {
+    let mut duplicates: Vec<String> = Vec::new();
+    for (i, tool) in tools.iter().enumerate() {
+        let key = tool.identity_key();
+        if tools[..i]
+            .iter()
+            .any(|earlier| earlier.identity_key() == key)
+        {
+            let (tool_type, name) = key;
+            let label = name.unwrap_or(tool_type).to_string();
+            if !duplicates.contains(&label) {
+                duplicates.push(label);
+            }
+        }
+    }
+    duplicates
 }
 
 impl ToolChoice {
@@ -389,11 +734,33 @@ impl ToolChoice {
         Self::String("required".to_string())
     }
 
-    /// Creates a tool choice that specifies a specific function
+    /// None tool choice - model must not call any tool
+    #[must_use]
+    pub fn none() -> Self {
+        Self::String("none".to_string())
+    }
+
+    /// Creates a tool choice that forces the function with the given name
     pub fn function(name: impl Into<String>) -> Self {
         Self::Object {
             choice_type: "function".to_string(),
-            function: ToolChoiceFunction { name: name.into() },
+            name: name.into(),
+        }
+    }
+
+    /// Creates a tool choice that forces the custom tool with the given name
+    pub fn custom(name: impl Into<String>) -> Self {
+        Self::Object {
+            choice_type: "custom".to_string(),
+            name: name.into(),
         }
     }
+
+    /// Creates a tool choice that limits the model to `tools`, a subset of the request's tools
+    pub fn allowed(mode: AllowedToolsMode, tools: impl IntoIterator<Item = ToolRef>) -> Self {
+        Self::Allowed(AllowedTools {
+            mode,
+            tools: tools.into_iter().collect(),
+        })
+    }
 }
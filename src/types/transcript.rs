@@ -0,0 +1,541 @@
+//! Human-readable rendering of responses for CLIs and debugging.
+//!
+//! [`Response::render_transcript`](crate::Response::render_transcript) prints each output
+//! item as a labelled block: messages, reasoning summaries, tool calls with their
+//! arguments and outputs, images noted by size, and usage at the end.
+
+use crate::types::{FunctionCallInfo, MessageContent, ResponseItem, Usage, WebSearchAction};
+use std::fmt::{self, Write};
+
+const RESET: &str = "\x1b[0m";
+const BOLD: &str = "\x1b[1m";
+const DIM: &str = "\x1b[2m";
+const RED: &str = "\x1b[31m";
+const GREEN: &str = "\x1b[32m";
+const YELLOW: &str = "\x1b[33m";
+const MAGENTA: &str = "\x1b[35m";
+const CYAN: &str = "\x1b[36m";
+
+/// Indent of item bodies under their label
+const INDENT: &str = "  ";
+
+/// Controls what [`Response::render_transcript`](crate::Response::render_transcript) prints.
+///
+/// The defaults show tool arguments cut to 200 characters and usage, without color
+/// or wrapping.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct TranscriptOptions {
+    /// Show function call arguments, custom tool inputs and function outputs
+    pub tool_arguments: bool,
+
+    /// Tool arguments and outputs longer than this many characters are cut off; `None` keeps them whole
+    pub max_tool_argument_len: Option<usize>,
+
+    /// End the transcript with token and tool usage
+    pub usage: bool,
+
+    /// Color labels with ANSI escape codes
+    pub color: bool,
+
+    /// Wrap text to this many columns, including the indent; `None` leaves lines as they are
+    pub max_width: Option<usize>,
+}
+
+impl Default for TranscriptOptions {
+    fn default() -> Self {
+        Self {
+            tool_arguments: true,
+            max_tool_argument_len: Some(200),
+            usage: true,
+            color: false,
+            max_width: None,
+        }
+    }
+}
+
+impl TranscriptOptions {
+    /// Creates the default options
+    #[must_use]
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Sets whether tool arguments and outputs are shown
+    #[must_use]
+    pub fn with_tool_arguments(mut self, show: bool) -> Self {
+        self.tool_arguments = show;
+        self
+    }
+
+    /// Sets the length above which tool arguments and outputs are cut off, or `None` to keep them whole
+    #[must_use]
+    pub fn with_max_tool_argument_len(mut self, max_len: Option<usize>) -> Self {
+        self.max_tool_argument_len = max_len;
+        self
+    }
+
+    /// Sets whether usage is shown
+    #[must_use]
+    pub fn with_usage(mut self, show: bool) -> Self {
+        self.usage = show;
+        self
+    }
+
+    /// Sets whether labels are colored with ANSI escape codes
+    #[must_use]
+    pub fn with_color(mut self, color: bool) -> Self {
+        self.color = color;
+        self
+    }
+
+    /// Sets the column text is wrapped to, or `None` to disable wrapping
+    #[must_use]
+    pub fn with_max_width(mut self, max_width: Option<usize>) -> Self {
+        self.max_width = max_width;
+        self
+    }
+}
+
+impl fmt::Display for FunctionCallInfo {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}({}) [{}]", self.name, self.arguments, self.call_id)
+    }
+}
+
+impl fmt::Display for Usage {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{} input", self.input_tokens)?;
+        if let Some(cached) = self
+            .prompt_tokens_details
+            .as_ref()
+            .and_then(|details| details.cached_tokens)
+        {
+            write!(f, " ({cached} cached)")?;
+        }
+        write!(f, " + {} output", self.output_tokens)?;
+        if let Some(reasoning) = self
+            .output_tokens_details
+            .as_ref()
+            .and_then(|details| details.reasoning_tokens)
+        {
+            write!(f, " ({reasoning} reasoning)")?;
+        }
+        write!(f, " = {} tokens", self.total_tokens)?;
+
+        let tools = [
+            ("web search", self.web_search),
+            ("file search", self.file_search),
+            ("image generation", self.image_generation),
+            ("code interpreter", self.code_interpreter),
+        ];
+        let mut separator = "; ";
+        for (name, count) in tools {
+            if let Some(count) = count.filter(|count| *count > 0) {
+                write!(f, "{separator}{name}: {count}")?;
+                separator = ", ";
+            }
+        }
+        Ok(())
+    }
+}
+
+struct Renderer {
+    options: TranscriptOptions,
+    out: String,
+}
+
+impl Renderer {
+    fn paint(&self, color: &str, text: &str) -> String {
+        if self.options.color {
+            format!("{color}{text}{RESET}")
+        } else {
+            text.to_string()
+        }
+    }
+
+    /// Writes a label line, with `detail` appended uncolored
+    fn label(&mut self, color: &str, label: &str, detail: &str) {
+        let label = self.paint(color, label);
+        if detail.is_empty() {
+            writeln!(self.out, "{label}").unwrap();
+        } else {
+            writeln!(self.out, "{label} {detail}").unwrap();
+        }
+    }
+
+    /// Writes indented, wrapped text under the last label
+    fn body(&mut self, text: &str) {
+        let width = self
+            .options
+            .max_width
+            .map(|width| width.saturating_sub(INDENT.len()).max(1));
+        for line in text.lines() {
+            for wrapped in wrap(line, width) {
+                writeln!(self.out, "{INDENT}{wrapped}").unwrap();
+            }
+        }
+    }
+
+    /// Writes tool arguments or outputs, if enabled
+    fn tool_payload(&mut self, payload: &str) {
+        if !self.options.tool_arguments || payload.is_empty() {
+            return;
+        }
+        let payload = match self.options.max_tool_argument_len {
+            Some(max_len) => truncate(payload, max_len),
+            None => payload.to_string(),
+        };
+        self.body(&payload);
+    }
+
+    fn content(&mut self, role: &str, part: &MessageContent) {
+        match part {
+            MessageContent::OutputText { text, .. } | MessageContent::InputText { text } => {
+                self.label(GREEN, role, "");
+                self.body(text);
+            }
+            MessageContent::Refusal { refusal } => {
+                self.label(RED, "refusal", "");
+                self.body(refusal);
+            }
+            MessageContent::OutputAudio { data, transcript } => {
+                let size = format_bytes(base64_decoded_len(data));
+                self.label(GREEN, role, &format!("(audio, {size})"));
+                if let Some(transcript) = transcript {
+                    self.body(transcript);
+                }
+            }
+            MessageContent::InputImage { .. } => self.label(GREEN, role, "(image)"),
+            MessageContent::InputFile { filename, .. } => {
+                let name = filename.as_deref().unwrap_or("unnamed");
+                self.label(GREEN, role, &format!("(file {name})"));
+            }
+        }
+    }
+
+    fn item(&mut self, item: &ResponseItem) {
+        match item {
+            ResponseItem::Message { role, content, .. } => {
+                for part in content {
+                    self.content(role, part);
+                }
+            }
+            ResponseItem::Reasoning { summary, .. } => {
+                self.label(MAGENTA, "reasoning", "");
+                let texts: Vec<&str> = summary
+                    .iter()
+                    .filter_map(|part| part.get("text").and_then(serde_json::Value::as_str))
+                    .collect();
+                if texts.is_empty() {
+                    self.body("(no summary)");
+                } else {
+                    self.body(&texts.join("\n"));
+                }
+            }
+            ResponseItem::WebSearchCall { status, action, .. } => {
+                let detail = match action {
+                    Some(WebSearchAction::Search { query, .. }) => format!("{query:?} ({status})"),
+                    Some(WebSearchAction::OpenPage { url }) => format!("open {url} ({status})"),
+                    Some(WebSearchAction::FindInPage { url, pattern }) => {
+                        format!("find {pattern:?} in {url} ({status})")
+                    }
+                    Some(WebSearchAction::Unknown) | None => format!("({status})"),
+                };
+                self.label(YELLOW, "web_search", &detail);
+            }
+            ResponseItem::FileSearchCall { status, .. } => {
+                self.label(YELLOW, "file_search", &format!("({status})"));
+            }
+            ResponseItem::CodeInterpreterCall {
+                container_id,
+                status,
+                ..
+            } => {
+                self.label(
+                    YELLOW,
+                    "code_interpreter",
+                    &format!("in {container_id} ({status})"),
+                );
+            }
+            ResponseItem::ImageGenerationCall { result, status, .. } => {
+                let size = if result.is_empty() {
+                    "no image".to_string()
+                } else {
+                    format!("image, {}", format_bytes(base64_decoded_len(result)))
+                };
+                self.label(YELLOW, "image_generation", &format!("{size} ({status})"));
+            }
+            ResponseItem::FunctionCall {
+                name,
+                arguments,
+                call_id,
+                ..
+            } => {
+                self.label(YELLOW, "function_call", &format!("{name} [{call_id}]"));
+                self.tool_payload(arguments);
+            }
+            ResponseItem::CustomToolCall {
+                name,
+                input,
+                call_id,
+                ..
+            } => {
+                self.label(YELLOW, "custom_tool_call", &format!("{name} [{call_id}]"));
+                self.tool_payload(input);
+            }
+            ResponseItem::FunctionCallOutput {
+                call_id, output, ..
+            } => {
+                self.label(CYAN, "function_call_output", &format!("[{call_id}]"));
+                self.tool_payload(output);
+            }
+            ResponseItem::ToolCall(call) => {
+                self.label(YELLOW, "tool_call", &format!("{} [{}]", call.name, call.id));
+                self.tool_payload(&call.arguments.to_string());
+            }
+            ResponseItem::Text { content, .. } => {
+                self.label(GREEN, "text", "");
+                self.body(content);
+            }
+            ResponseItem::Unknown(value) => {
+                let status = value.get("status").and_then(serde_json::Value::as_str);
+                let detail = status
+                    .map(|status| format!("({status})"))
+                    .unwrap_or_default();
+                self.label(YELLOW, item.item_type(), &detail);
+            }
+        }
+    }
+}
+
+pub(crate) fn render(response: &crate::Response, options: TranscriptOptions) -> String {
+    let mut renderer = Renderer {
+        options,
+        out: String::new(),
+    };
+    let header = format!("response {}", response.id);
+    let header = renderer.paint(BOLD, &header);
+    writeln!(
+        renderer.out,
+        "{header} ({}, {})",
+        response.model, response.status
+    )
+    .unwrap();
+
+    for item in &response.output {
+        renderer.item(item);
+    }
+
+    if let Some(details) = &response.incomplete_details {
+        renderer.label(RED, "incomplete", &details.reason);
+    }
+    if let Some(error) = &response.error {
+        renderer.label(RED, "error", &format!("{}: {}", error.code, error.message));
+    }
+    if options.usage {
+        if let Some(usage) = response.usage_with_tools() {
+            renderer.label(DIM, "usage", &usage.to_string());
+        }
+    }
+    renderer.out
+}
+
+/// Cuts `text` to `max_len` characters, noting how many were dropped
+fn truncate(text: &str, max_len: usize) -> String {
+    let len = text.chars().count();
+    if len <= max_len {
+        return text.to_string();
+    }
+    let kept: String = text.chars().take(max_len).collect();
+    format!("{kept}… (+{} chars)", len - max_len)
+}
+
+/// Greedy word wrap; words longer than `width` are split
+fn wrap(line: &str, width: Option<usize>) -> Vec<String> {
+    let Some(width) = width else {
+        return vec![line.to_string()];
+    };
+    let mut lines = Vec::new();
+    let mut current = String::new();
+    let mut current_len = 0;
+    for word in line.split_whitespace() {
+        let mut word: Vec<char> = word.chars().collect();
+        while word.len() > width {
+            if current_len > 0 {
+                lines.push(std::mem::take(&mut current));
+                current_len = 0;
+            }
+            lines.push(word.drain(..width).collect());
+        }
+        if word.is_empty() {
+            continue;
+        }
+        if current_len > 0 && current_len + 1 + word.len() > width {
+            lines.push(std::mem::take(&mut current));
+            current_len = 0;
+        }
+        if current_len > 0 {
+            current.push(' ');
+            current_len += 1;
+        }
+        current.extend(&word);
+        current_len += word.len();
+    }
+    if current_len > 0 || lines.is_empty() {
+        lines.push(current);
+    }
+    lines
+}
+
+/// Size of the data encoded in a base64 string
+fn base64_decoded_len(encoded: &str) -> usize {
+    let padding = encoded.bytes().rev().take_while(|b| *b == b'=').count();
+    (encoded.len() / 4 * 3).saturating_sub(padding)
+}
+
+fn format_bytes(bytes: usize) -> String {
+    const KIB: usize = 1024;
+    const MIB: usize = 1024 * KIB;
+    if bytes < KIB {
+        format!("{bytes} B")
+    } else if bytes < MIB {
+        format!("{}.{} KB", bytes / KIB, bytes % KIB * 10 / KIB)
+    } else {
+        format!("{}.{} MB", bytes / MIB, bytes % MIB * 10 / MIB)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    fn mixed_response() -> crate::Response {
+        serde_json::from_value(json!({
+            "id": "resp_1",
+            "object": "response",
+            "created_at": 1_700_000_000,
+            "model": "gpt-4o",
+            "status": "incomplete",
+            "incomplete_details": {"reason": "max_output_tokens"},
+            "usage": {
+                "input_tokens": 120,
+                "output_tokens": 40,
+                "total_tokens": 160,
+                "input_tokens_details": {"cached_tokens": 100},
+                "output_tokens_details": {"reasoning_tokens": 12}
+            },
+            "output": [
+                {"type": "reasoning", "id": "rs_1", "summary": [{"type": "summary_text", "text": "Need the forecast."}]},
+                {"type": "reasoning", "id": "rs_2", "summary": []},
+                {"type": "web_search_call", "id": "ws_1", "status": "completed"},
+                {"type": "file_search_call", "id": "fs_1", "status": "completed"},
+                {"type": "code_interpreter_call", "id": "ci_1", "container_id": "cntr_1", "status": "completed"},
+                {"type": "image_generation_call", "id": "ig_1", "result": "A".repeat(4096), "status": "completed"},
+                {"type": "function_call", "id": "fc_1", "call_id": "call_1", "name": "get_weather",
+                 "arguments": r#"{"city":"Paris","units":"metric"}"#, "status": "completed"},
+                {"type": "function_call_output", "call_id": "call_1", "output": "sunny"},
+                {"type": "custom_tool_call", "id": "ct_1", "call_id": "call_2", "name": "sql", "input": "SELECT 1"},
+                {"type": "tool_call", "id": "tc_1", "name": "lookup", "arguments": {"q": "x"}, "index": 0},
+                {"type": "text", "content": "legacy text", "index": 0},
+                {"type": "message", "id": "msg_1", "role": "assistant", "status": "completed", "content": [
+                    {"type": "output_text", "text": "It is sunny in Paris today.", "annotations": []},
+                    {"type": "refusal", "refusal": "I can't share that."}
+                ]}
+            ]
+        }))
+        .unwrap()
+    }
+
+    #[test]
+    fn renders_every_item_type() {
+        let transcript = mixed_response().render_transcript(TranscriptOptions::default());
+        assert_eq!(
+            transcript,
+            "response resp_1 (gpt-4o, incomplete)
+reasoning
+  Need the forecast.
+reasoning
+  (no summary)
+web_search (completed)
+file_search (completed)
+code_interpreter in cntr_1 (completed)
+image_generation image, 3.0 KB (completed)
+function_call get_weather [call_1]
+  {\"city\":\"Paris\",\"units\":\"metric\"}
+function_call_output [call_1]
+  sunny
+custom_tool_call sql [call_2]
+  SELECT 1
+tool_call lookup [tc_1]
+  {\"q\":\"x\"}
+text
+  legacy text
+assistant
+  It is sunny in Paris today.
+refusal
+  I can't share that.
+incomplete max_output_tokens
+usage 120 input (100 cached) + 40 output (12 reasoning) = 160 tokens; web search: 1, file search: 1, image generation: 1, code interpreter: 1
+"
+        );
+    }
+
+    #[test]
+    fn options_control_arguments_usage_color_and_width() {
+        let response = mixed_response();
+
+        let truncated = response.render_transcript(
+            TranscriptOptions::new()
+                .with_max_tool_argument_len(Some(10))
+                .with_usage(false),
+        );
+        assert!(truncated.contains("  {\"city\":\"P… (+23 chars)\n"));
+        assert!(!truncated.contains("usage"));
+
+        let hidden =
+            response.render_transcript(TranscriptOptions::new().with_tool_arguments(false));
+        assert!(hidden.contains("function_call get_weather [call_1]\nfunction_call_output"));
+        assert!(!hidden.contains("sunny\n"));
+
+        let colored = response.render_transcript(TranscriptOptions::new().with_color(true));
+        assert!(colored.starts_with("\x1b[1mresponse resp_1\x1b[0m (gpt-4o"));
+        assert!(colored.contains("\x1b[32massistant\x1b[0m\n"));
+        assert!(!response
+            .render_transcript(TranscriptOptions::default())
+            .contains('\x1b'));
+
+        let wrapped = response.render_transcript(TranscriptOptions::new().with_max_width(Some(14)));
+        assert!(wrapped.contains("assistant\n  It is sunny\n  in Paris\n  today.\n"));
+        for line in wrapped.lines().filter(|line| line.starts_with(INDENT)) {
+            assert!(line.chars().count() <= 14, "{line:?}");
+        }
+    }
+
+    #[test]
+    fn display_building_blocks() {
+        let call = FunctionCallInfo {
+            name: "get_weather".to_string(),
+            arguments: r#"{"city":"Paris"}"#.to_string(),
+            call_id: "call_1".to_string(),
+            id: None,
+            status: None,
+        };
+        assert_eq!(
+            call.to_string(),
+            r#"get_weather({"city":"Paris"}) [call_1]"#
+        );
+
+        let usage: Usage = serde_json::from_value(json!({
+            "input_tokens": 10, "output_tokens": 5, "total_tokens": 15
+        }))
+        .unwrap();
+        assert_eq!(usage.to_string(), "10 input + 5 output = 15 tokens");
+
+        assert_eq!(wrap("abcdefgh ij", Some(3)), ["abc", "def", "gh", "ij"]);
+        assert_eq!(wrap("", Some(3)), [""]);
+        assert_eq!(format_bytes(512), "512 B");
+        assert_eq!(format_bytes(3 * 1024 * 1024 / 2), "1.5 MB");
+        assert_eq!(base64_decoded_len("aGk="), 2);
+    }
+}
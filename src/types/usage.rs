@@ -0,0 +1,611 @@
+//! Token and tool usage, per response and summed over several responses.
+//!
+//! [`ToolUsageBreakdown`] counts a response's tool calls by kind and outcome.
+//!
+//! Billing is usually attributed per conversation, which spans every response in a
+//! `previous_response_id` chain. [`UsageAggregate`] folds their [`Usage`] together,
+//! either from responses already at hand or through
+//! [`Responses::aggregate_chain_usage`](crate::responses::Responses::aggregate_chain_usage).
+
+use crate::types::{Response, ResponseItem, Usage};
+use serde::{Deserialize, Serialize};
+use std::iter::Sum;
+use std::ops::{Add, AddAssign};
+
+/// Kind of tool call, as counted by [`ToolUsageBreakdown`]
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum ToolKind {
+    /// Web search
+    WebSearch,
+    /// File search
+    FileSearch,
+    /// Image generation
+    ImageGeneration,
+    /// Code interpreter
+    CodeInterpreter,
+    /// Function call
+    Function,
+    /// Custom tool call
+    Custom,
+    /// Any other tool call, such as MCP, computer use or legacy `tool_call` items
+    Other,
+}
+
+impl ToolKind {
+    /// Every kind, in the order [`Response::format_usage`] lists them
+    pub const ALL: [Self; 7] = [
+        Self::WebSearch,
+        Self::FileSearch,
+        Self::ImageGeneration,
+        Self::CodeInterpreter,
+        Self::Function,
+        Self::Custom,
+        Self::Other,
+    ];
+
+    /// Returns a human-readable label, e.g. `"web search"`
+    #[must_use]
+    pub const fn label(self) -> &'static str {
+        match self {
+            Self::WebSearch => "web search",
+            Self::FileSearch => "file search",
+            Self::ImageGeneration => "image generation",
+            Self::CodeInterpreter => "code interpreter",
+            Self::Function => "function calls",
+            Self::Custom => "custom tools",
+            Self::Other => "other tools",
+        }
+    }
+
+    /// Returns the kind of tool call `item` is and the status it reported, or `None` if
+    /// it is not a tool call.
+    ///
+    /// No `_` arm on purpose: a new [`ResponseItem`] variant must be classified here.
+    fn of(item: &ResponseItem) -> Option<(Self, Option<&str>)> {
+        match item {
+            ResponseItem::Message { .. }
+            | ResponseItem::Reasoning { .. }
+            | ResponseItem::FunctionCallOutput { .. }
+            | ResponseItem::Text { .. } => None,
+            ResponseItem::WebSearchCall { status, .. } => Some((Self::WebSearch, Some(status))),
+            ResponseItem::FileSearchCall { status, .. } => Some((Self::FileSearch, Some(status))),
+            ResponseItem::ImageGenerationCall { status, .. } => {
+                Some((Self::ImageGeneration, Some(status)))
+            }
+            ResponseItem::CodeInterpreterCall { status, .. } => {
+                Some((Self::CodeInterpreter, Some(status)))
+            }
+            ResponseItem::FunctionCall { status, .. } => {
+                Some((Self::Function, Some(status.as_str())))
+            }
+            ResponseItem::CustomToolCall { status, .. } => Some((Self::Custom, status.as_deref())),
+            ResponseItem::ToolCall(_) => Some((Self::Other, None)),
+            // Tool call items the crate does not model are all named `<tool>_call`
+            ResponseItem::Unknown(item) => item
+                .get("type")
+                .and_then(serde_json::Value::as_str)
+                .filter(|item_type| item_type.ends_with("_call"))
+                .map(|_| {
+                    let status = item.get("status").and_then(serde_json::Value::as_str);
+                    (Self::Other, status)
+                }),
+        }
+    }
+}
+
+/// Tool calls of one kind, split by outcome
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct ToolCallCounts {
+    /// Calls that completed, or reported no status
+    pub completed: u32,
+
+    /// Calls with status `failed`
+    pub failed: u32,
+
+    /// Calls still in progress, cut short, or with another status
+    pub unfinished: u32,
+}
+
+impl ToolCallCounts {
+    /// Total calls, whatever their outcome
+    #[must_use]
+    pub fn total(&self) -> u32 {
+        self.completed
+            .saturating_add(self.failed)
+            .saturating_add(self.unfinished)
+    }
+
+    fn record(&mut self, status: Option<&str>) {
+        let counter = match status {
+            None | Some("completed") => &mut self.completed,
+            Some("failed") => &mut self.failed,
+            Some(_) => &mut self.unfinished,
+        };
+        *counter = counter.saturating_add(1);
+    }
+}
+
+impl AddAssign for ToolCallCounts {
+    fn add_assign(&mut self, other: Self) {
+        self.completed = self.completed.saturating_add(other.completed);
+        self.failed = self.failed.saturating_add(other.failed);
+        self.unfinished = self.unfinished.saturating_add(other.unfinished);
+    }
+}
+
+/// A response's tool calls, counted by kind and outcome.
+///
+/// ```rust
+/// use open_ai_rust_responses_by_sshift::types::{ToolKind, ToolUsageBreakdown};
+/// use open_ai_rust_responses_by_sshift::ResponseItem;
+///
+/// let output: Vec<ResponseItem> = serde_json::from_str(
+///     r#"[{"type":"web_search_call","id":"ws_1","status":"completed"},
+///         {"type":"web_search_call","id":"ws_2","status":"failed"}]"#,
+/// )?;
+/// let usage = ToolUsageBreakdown::from_items(&output);
+/// assert_eq!(usage.get(ToolKind::WebSearch).completed, 1);
+/// assert_eq!(usage.get(ToolKind::WebSearch).failed, 1);
+/// # Ok::<(), serde_json::Error>(())
+/// ```
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct ToolUsageBreakdown {
+    /// Web search calls
+    pub web_search: ToolCallCounts,
+
+    /// File search calls
+    pub file_search: ToolCallCounts,
+
+    /// Image generation calls
+    pub image_generation: ToolCallCounts,
+
+    /// Code interpreter calls
+    pub code_interpreter: ToolCallCounts,
+
+    /// Function calls
+    pub function: ToolCallCounts,
+
+    /// Custom tool calls
+    pub custom: ToolCallCounts,
+
+    /// Tool calls of any other kind
+    pub other_tools: ToolCallCounts,
+}
+
+impl ToolUsageBreakdown {
+    /// Counts the tool calls among `items`
+    #[must_use]
+    pub fn from_items(items: &[ResponseItem]) -> Self {
+        let mut usage = Self::default();
+        for (kind, status) in items.iter().filter_map(ToolKind::of) {
+            usage.get_mut(kind).record(status);
+        }
+        usage
+    }
+
+    /// Returns the counts for one kind of tool
+    #[must_use]
+    pub fn get(&self, kind: ToolKind) -> ToolCallCounts {
+        match kind {
+            ToolKind::WebSearch => self.web_search,
+            ToolKind::FileSearch => self.file_search,
+            ToolKind::ImageGeneration => self.image_generation,
+            ToolKind::CodeInterpreter => self.code_interpreter,
+            ToolKind::Function => self.function,
+            ToolKind::Custom => self.custom,
+            ToolKind::Other => self.other_tools,
+        }
+    }
+
+    fn get_mut(&mut self, kind: ToolKind) -> &mut ToolCallCounts {
+        match kind {
+            ToolKind::WebSearch => &mut self.web_search,
+            ToolKind::FileSearch => &mut self.file_search,
+            ToolKind::ImageGeneration => &mut self.image_generation,
+            ToolKind::CodeInterpreter => &mut self.code_interpreter,
+            ToolKind::Function => &mut self.function,
+            ToolKind::Custom => &mut self.custom,
+            ToolKind::Other => &mut self.other_tools,
+        }
+    }
+
+    /// Counts summed over every kind of tool
+    #[must_use]
+    pub fn total(&self) -> ToolCallCounts {
+        let mut total = ToolCallCounts::default();
+        for kind in ToolKind::ALL {
+            total += self.get(kind);
+        }
+        total
+    }
+
+    /// Whether there were no tool calls at all
+    #[must_use]
+    pub fn is_empty(&self) -> bool {
+        self.total().total() == 0
+    }
+}
+
+/// Usage summed over several responses.
+///
+/// Counters are `u64` and saturate, so long conversations cannot overflow them.
+///
+/// ```rust
+/// use open_ai_rust_responses_by_sshift::types::{Usage, UsageAggregate};
+///
+/// let usage: Usage = serde_json::from_str(
+///     r#"{"input_tokens":10,"output_tokens":5,"total_tokens":15}"#,
+/// )?;
+/// let total: UsageAggregate = [&usage, &usage].into_iter().sum();
+/// assert_eq!((total.responses, total.total_tokens), (2, 30));
+/// # Ok::<(), serde_json::Error>(())
+/// ```
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct UsageAggregate {
+    /// Number of responses folded in, including those without usage
+    pub responses: u64,
+
+    /// Number of responses that reported no token usage
+    pub responses_without_usage: u64,
+
+    /// Input tokens
+    pub input_tokens: u64,
+
+    /// Output tokens
+    pub output_tokens: u64,
+
+    /// Total tokens
+    pub total_tokens: u64,
+
+    /// Output tokens spent on reasoning
+    pub reasoning_tokens: u64,
+
+    /// Input tokens served from the prompt cache
+    pub cached_tokens: u64,
+
+    /// Web search tool calls
+    pub web_search: u64,
+
+    /// File search tool calls
+    pub file_search: u64,
+
+    /// Image generation tool calls
+    pub image_generation: u64,
+
+    /// Code interpreter tool calls
+    pub code_interpreter: u64,
+}
+
+impl UsageAggregate {
+    /// Creates an empty aggregate
+    #[must_use]
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Sums the usage of `responses`, with tool calls counted from their output
+    #[must_use]
+    pub fn from_responses<'a>(responses: impl IntoIterator<Item = &'a Response>) -> Self {
+        let mut aggregate = Self::new();
+        for response in responses {
+            aggregate.add_response(response);
+        }
+        aggregate
+    }
+
+    /// Adds one response's usage, counting its tool calls from the output as
+    /// [`Response::usage_with_tools`] does.
+    ///
+    /// A response without `usage` still counts towards [`responses`](Self::responses)
+    /// and [`responses_without_usage`](Self::responses_without_usage), and its tool
+    /// calls are still added.
+    pub fn add_response(&mut self, response: &Response) {
+        match response.usage_with_tools() {
+            Some(usage) => *self += &usage,
+            None => self.responses = self.responses.saturating_add(1),
+        }
+        if response.usage.is_none() {
+            self.responses_without_usage = self.responses_without_usage.saturating_add(1);
+        }
+    }
+
+    /// Total tool calls of every kind
+    #[must_use]
+    pub fn tool_calls(&self) -> u64 {
+        [
+            self.web_search,
+            self.file_search,
+            self.image_generation,
+            self.code_interpreter,
+        ]
+        .into_iter()
+        .fold(0, u64::saturating_add)
+    }
+}
+
+fn add(total: &mut u64, value: impl Into<u64>) {
+    *total = total.saturating_add(value.into());
+}
+
+impl AddAssign<&Usage> for UsageAggregate {
+    fn add_assign(&mut self, usage: &Usage) {
+        add(&mut self.responses, 1_u32);
+        add(&mut self.input_tokens, usage.input_tokens);
+        add(&mut self.output_tokens, usage.output_tokens);
+        add(&mut self.total_tokens, usage.total_tokens);
+        add(
+            &mut self.reasoning_tokens,
+            usage
+                .output_tokens_details
+                .as_ref()
+                .and_then(|details| details.reasoning_tokens)
+                .unwrap_or(0),
+        );
+        add(
+            &mut self.cached_tokens,
+            usage
+                .prompt_tokens_details
+                .as_ref()
+                .and_then(|details| details.cached_tokens)
+                .unwrap_or(0),
+        );
+        add(&mut self.web_search, usage.web_search.unwrap_or(0));
+        add(&mut self.file_search, usage.file_search.unwrap_or(0));
+        add(
+            &mut self.image_generation,
+            usage.image_generation.unwrap_or(0),
+        );
+        add(
+            &mut self.code_interpreter,
+            usage.code_interpreter.unwrap_or(0),
+        );
+    }
+}
+
+impl AddAssign for UsageAggregate {
+    fn add_assign(&mut self, other: Self) {
+        add(&mut self.responses, other.responses);
+        add(
+            &mut self.responses_without_usage,
+            other.responses_without_usage,
+        );
+        add(&mut self.input_tokens, other.input_tokens);
+        add(&mut self.output_tokens, other.output_tokens);
+        add(&mut self.total_tokens, other.total_tokens);
+        add(&mut self.reasoning_tokens, other.reasoning_tokens);
+        add(&mut self.cached_tokens, other.cached_tokens);
+        add(&mut self.web_search, other.web_search);
+        add(&mut self.file_search, other.file_search);
+        add(&mut self.image_generation, other.image_generation);
+        add(&mut self.code_interpreter, other.code_interpreter);
+    }
+}
+
+impl Add for UsageAggregate {
+    type Output = Self;
+
+    fn add(mut self, other: Self) -> Self {
+        self += other;
+        self
+    }
+}
+
+impl<'a> Sum<&'a Usage> for UsageAggregate {
+    fn sum<I: Iterator<Item = &'a Usage>>(iter: I) -> Self {
+        let mut aggregate = Self::new();
+        for usage in iter {
+            aggregate += usage;
+        }
+        aggregate
+    }
+}
+
+impl Sum for UsageAggregate {
+    fn sum<I: Iterator<Item = Self>>(iter: I) -> Self {
+        iter.fold(Self::new(), Add::add)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn response(usage: &serde_json::Value, output: &serde_json::Value) -> Response {
+        serde_json::from_value(serde_json::json!({
+            "id": "resp_1",
+            "object": "response",
+            "created_at": 1_700_000_000,
+            "model": "gpt-5",
+            "status": "completed",
+            "output": output,
+            "usage": usage,
+        }))
+        .unwrap()
+    }
+
+    #[test]
+    fn sums_tokens_details_and_tool_counters() {
+        let first: Usage = serde_json::from_value(serde_json::json!({
+            "input_tokens": 100,
+            "output_tokens": 40,
+            "total_tokens": 140,
+            "input_tokens_details": {"cached_tokens": 64},
+            "output_tokens_details": {"reasoning_tokens": 16},
+            "web_search": 2
+        }))
+        .unwrap();
+        let second: Usage = serde_json::from_value(serde_json::json!({
+            "input_tokens": 200,
+            "output_tokens": 10,
+            "total_tokens": 210,
+            "code_interpreter": 1
+        }))
+        .unwrap();
+
+        let total: UsageAggregate = [&first, &second, &first].into_iter().sum();
+        assert_eq!(
+            total,
+            UsageAggregate {
+                responses: 3,
+                responses_without_usage: 0,
+                input_tokens: 400,
+                output_tokens: 90,
+                total_tokens: 490,
+                reasoning_tokens: 32,
+                cached_tokens: 128,
+                web_search: 4,
+                file_search: 0,
+                image_generation: 0,
+                code_interpreter: 1,
+            }
+        );
+        assert_eq!(total.tool_calls(), 5);
+
+        // Aggregates combine like their parts
+        let split = [&first].into_iter().sum::<UsageAggregate>()
+            + [&second, &first].into_iter().sum::<UsageAggregate>();
+        assert_eq!(split, total);
+    }
+
+    #[test]
+    fn responses_without_usage_are_counted_but_keep_their_tool_calls() {
+        let chain = [
+            response(
+                &serde_json::json!({
+                    "input_tokens": 10, "output_tokens": 5, "total_tokens": 15
+                }),
+                &serde_json::json!([]),
+            ),
+            response(
+                &serde_json::Value::Null,
+                &serde_json::json!([{"type": "web_search_call", "id": "ws_1", "status": "completed"}]),
+            ),
+            response(&serde_json::Value::Null, &serde_json::json!([])),
+        ];
+
+        let total = UsageAggregate::from_responses(&chain);
+        assert_eq!(total.responses, 3);
+        assert_eq!(total.responses_without_usage, 2);
+        assert_eq!(total.total_tokens, 15);
+        assert_eq!(total.web_search, 1);
+
+        let mut saturated = UsageAggregate {
+            total_tokens: u64::MAX,
+            ..UsageAggregate::new()
+        };
+        saturated.add_response(&chain[0]);
+        assert_eq!(saturated.total_tokens, u64::MAX);
+    }
+
+    #[test]
+    fn unmodelled_tool_calls_count_as_other_tools() {
+        const FIXTURE: &str = include_str!("../tests/fixtures/mcp_computer_response.json");
+        let response: Response = serde_json::from_str(FIXTURE).unwrap();
+
+        let types: Vec<_> = response
+            .output
+            .iter()
+            .map(ResponseItem::item_type)
+            .collect();
+        assert_eq!(
+            types,
+            [
+                "mcp_list_tools",
+                "mcp_call",
+                "mcp_call",
+                "computer_call",
+                "local_shell_call",
+                "mcp_approval_request"
+            ]
+        );
+        assert!(matches!(response.output[1], ResponseItem::Unknown(_)));
+
+        // Only the `*_call` items are tool calls; the listing and approval request are not
+        let tools = response.tool_usage();
+        assert_eq!(
+            tools.other_tools,
+            ToolCallCounts {
+                completed: 2,
+                failed: 1,
+                unfinished: 1,
+            }
+        );
+        assert_eq!(tools.total(), tools.other_tools);
+
+        // Unknown items are kept as sent
+        let expected: serde_json::Value = serde_json::from_str(FIXTURE).unwrap();
+        assert_eq!(
+            serde_json::to_value(&response.output).unwrap(),
+            expected["output"]
+        );
+        // A modelled item with a missing field is still an error
+        assert!(
+            serde_json::from_str::<ResponseItem>(r#"{"type":"function_call","id":"fc_1"}"#)
+                .is_err()
+        );
+    }
+
+    #[test]
+    fn tool_calls_are_counted_by_kind_and_outcome() {
+        let response = response(
+            &serde_json::Value::Null,
+            &serde_json::json!([
+                {"type": "message", "id": "msg_1", "role": "assistant", "content": [], "status": "completed"},
+                {"type": "reasoning", "id": "rs_1", "summary": []},
+                {"type": "text", "content": "legacy", "index": 0},
+                {"type": "function_call_output", "call_id": "call_1", "output": "{}"},
+                {"type": "web_search_call", "id": "ws_1", "status": "completed"},
+                {"type": "web_search_call", "id": "ws_2", "status": "failed"},
+                {"type": "file_search_call", "id": "fs_1", "status": "searching"},
+                {"type": "image_generation_call", "id": "ig_1", "result": "", "status": "completed"},
+                {"type": "image_generation_call", "id": "ig_2", "result": "", "status": "failed"},
+                {"type": "code_interpreter_call", "id": "ci_1", "container_id": "cntr_1", "status": "completed"},
+                {"type": "code_interpreter_call", "id": "ci_2", "container_id": "cntr_1", "status": "interpreting"},
+                {"type": "function_call", "id": "fc_1", "call_id": "call_1", "name": "f", "arguments": "{}", "status": "completed"},
+                {"type": "function_call", "id": "fc_2", "call_id": "call_2", "name": "f", "arguments": "{}", "status": "incomplete"},
+                {"type": "custom_tool_call", "id": "ct_1", "call_id": "call_3", "name": "sql", "input": "select 1"},
+                {"type": "custom_tool_call", "id": "ct_2", "call_id": "call_4", "name": "sql", "input": "drop", "status": "failed"},
+                {"type": "tool_call", "id": "tc_1", "name": "legacy", "arguments": {}, "index": 0}
+            ]),
+        );
+
+        let counts = |completed, failed, unfinished| ToolCallCounts {
+            completed,
+            failed,
+            unfinished,
+        };
+        let expected = [
+            (ToolKind::WebSearch, counts(1, 1, 0)),
+            (ToolKind::FileSearch, counts(0, 0, 1)),
+            (ToolKind::ImageGeneration, counts(1, 1, 0)),
+            (ToolKind::CodeInterpreter, counts(1, 0, 1)),
+            (ToolKind::Function, counts(1, 0, 1)),
+            (ToolKind::Custom, counts(1, 1, 0)),
+            (ToolKind::Other, counts(1, 0, 0)),
+        ];
+        let tools = response.tool_usage();
+        for (kind, counts) in expected {
+            assert_eq!(tools.get(kind), counts, "{kind:?}");
+        }
+        assert_eq!(tools.total(), counts(6, 3, 3));
+        assert!(ToolUsageBreakdown::default().is_empty());
+
+        // The legacy counters only include completed built-in tool calls
+        assert_eq!(response.calculate_tool_usage(), (1, 0, 1, 1));
+        let usage = response.usage_with_tools().unwrap();
+        assert_eq!(
+            (usage.web_search, usage.file_search, usage.code_interpreter),
+            (Some(1), None, Some(1))
+        );
+        assert_eq!(usage.tool_usage, Some(tools));
+        assert_eq!(UsageAggregate::from_responses([&response]).tool_calls(), 3);
+
+        assert_eq!(
+            response.format_usage(),
+            "input tokens: 0\noutput tokens: 0\ntotal tokens: 0\n\
+             web search: 1 (1 failed)\nfile search: 0 (1 unfinished)\n\
+             image generation: 1 (1 failed)\ncode interpreter: 1 (1 unfinished)\n\
+             function calls: 1 (1 unfinished)\ncustom tools: 1 (1 failed)\nother tools: 1"
+        );
+    }
+}
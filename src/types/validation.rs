@@ -0,0 +1,156 @@
+/// Client-side validation errors, reported by
+/// [`RequestBuilder::build_validated`](crate::types::RequestBuilder::build_validated) and the
+/// checked setters it relies on
+#[derive(Debug, Clone, PartialEq, Eq, thiserror::Error)]
+pub enum ValidationError {
+    /// Metadata has more keys than the API accepts
+    #[error("metadata has {count} keys, exceeding the limit of {max}")]
+    MetadataTooManyKeys {
+        /// Number of keys present
+        count: usize,
+        /// Maximum number of keys allowed
+        max: usize,
+    },
+
+    /// A metadata key is longer than the API accepts
+    #[error("metadata key `{key}` is {len} characters, exceeding the limit of {max}")]
+    MetadataKeyTooLong {
+        /// The offending key
+        key: String,
+        /// Length of the key in characters
+        len: usize,
+        /// Maximum key length allowed
+        max: usize,
+    },
+
+    /// A metadata value is longer than the API accepts
+    #[error("metadata value for key `{key}` is {len} characters, exceeding the limit of {max}")]
+    MetadataValueTooLong {
+        /// Key whose value is too long
+        key: String,
+        /// Length of the value in characters
+        len: usize,
+        /// Maximum value length allowed
+        max: usize,
+    },
+
+    /// Several tools share a type and name (or MCP server label); see
+    /// [`Tool::identity_key`](crate::types::Tool::identity_key)
+    #[error("duplicate tools in request: {}", names.join(", "))]
+    DuplicateTools {
+        /// Function names, MCP server labels, or types of built-in tools, each listed once
+        names: Vec<String>,
+    },
+
+    /// An allowed-tools `tool_choice` lists tools the request does not define
+    #[error("tool_choice allows tools missing from the request: {}", names.join(", "))]
+    UnknownAllowedTools {
+        /// Labels of the missing tools, as [`ToolRef::label`](crate::types::ToolRef::label) gives them
+        names: Vec<String>,
+    },
+
+    /// `tool_choice` forces a tool the request does not define
+    #[error("tool_choice forces `{name}`, which is missing from the request's tools")]
+    UnknownToolChoice {
+        /// Label of the forced tool, as [`ToolRef::label`](crate::types::ToolRef::label) gives it
+        name: String,
+    },
+
+    /// An include adds to the output of a tool the request does not define, so it has
+    /// no effect; reported as a warning by
+    /// [`Request::consistency_warnings`](crate::types::Request::consistency_warnings)
+    #[error("include `{include}` has no effect without a `{tool_type}` tool")]
+    IncludeWithoutTool {
+        /// The include option
+        include: &'static str,
+        /// Type (or type prefix) of the tool it depends on
+        tool_type: &'static str,
+    },
+
+    /// `parallel_tool_calls` is set on a request without tools, so it has no effect;
+    /// reported as a warning by
+    /// [`Request::consistency_warnings`](crate::types::Request::consistency_warnings)
+    #[error("parallel_tool_calls has no effect without tools")]
+    ParallelToolCallsWithoutTools,
+
+    /// `previous_response_id` is combined with `store: false`, so the response that
+    /// continues the chain is not kept for the next turn
+    #[error("previous_response_id requires the response to be stored; remove `store: false`")]
+    PreviousResponseWithoutStore,
+
+    /// `max_output_tokens` is above what the model can generate
+    #[error("max_output_tokens {requested} exceeds the {max} tokens `{model}` can generate")]
+    MaxOutputTokensExceeded {
+        /// The model the request targets
+        model: String,
+        /// The requested `max_output_tokens`
+        requested: u32,
+        /// Largest output the model supports
+        max: u32,
+    },
+
+    /// A request with `stream: Some(true)` was passed to a non-streaming create
+    #[error("request has `stream` set to true; use `Responses::stream` for streaming responses")]
+    StreamingNotSupported,
+
+    /// A list `limit` is outside the range the API accepts
+    #[error("pagination limit {limit} is outside the allowed range {min}..={max}")]
+    PaginationLimitOutOfRange {
+        /// The requested limit
+        limit: u32,
+        /// Smallest allowed limit
+        min: u32,
+        /// Largest allowed limit
+        max: u32,
+    },
+
+    /// `text.stop` has more sequences than the API accepts
+    #[error("{count} stop sequences exceed the limit of {max}")]
+    TooManyStopSequences {
+        /// Number of stop sequences
+        count: usize,
+        /// Maximum number of stop sequences
+        max: usize,
+    },
+
+    /// A stop sequence is empty
+    #[error("stop sequence {index} is empty")]
+    EmptyStopSequence {
+        /// Position of the empty sequence in `text.stop`
+        index: usize,
+    },
+
+    /// The model is not a reasoning model, so it rejects a `reasoning` object
+    #[error("`{model}` is not a reasoning model and does not accept `reasoning`")]
+    ReasoningNotSupported {
+        /// The model the request targets
+        model: String,
+    },
+
+    /// The model rejects the requested `reasoning.effort`
+    #[error("`{model}` does not accept reasoning effort `{effort}`")]
+    UnsupportedReasoningEffort {
+        /// The model the request targets
+        model: String,
+        /// The requested effort
+        effort: String,
+    },
+
+    /// `reasoning.summary` and its deprecated alias `generate_summary` disagree
+    #[error("reasoning summary `{summary}` conflicts with generate_summary `{generate_summary}`")]
+    ConflictingReasoningSummary {
+        /// The `summary` setting
+        summary: String,
+        /// The `generate_summary` setting
+        generate_summary: String,
+    },
+
+    /// An inline base64 image is larger than the configured limit
+    #[error("inline image is {bytes} bytes, exceeding the limit of {max}")]
+    ImageTooLarge {
+        /// Decoded size of the image
+        bytes: usize,
+        /// Maximum image size allowed
+        max: usize,
+    },
+}
@@ -1,14 +1,28 @@
-use crate::error::{try_parse_api_error, Result};
-use crate::types::{PaginatedList, PaginationParams};
+use crate::endpoint::Endpoint;
+use crate::error::Result;
+use crate::files::File;
+use crate::http::Transport;
+use crate::retry::Retrying;
+use crate::types::{
+    DeleteConfirmation, DeleteOutcome, DeletionStatus, PaginatedList, PaginationParams,
+    RecoveryPolicy,
+};
 use chrono::{DateTime, Utc};
-use reqwest::Client as HttpClient;
+use futures_util::{StreamExt, TryStreamExt};
+use reqwest::StatusCode;
 use serde::{Deserialize, Serialize};
+use std::time::Duration;
+
+/// Maximum number of concurrent searches made by [`VectorStores::search_many`]
+const SEARCH_MANY_CONCURRENCY: usize = 4;
 
 /// Vector stores API endpoints
 #[derive(Debug, Clone)]
 pub struct VectorStores {
-    client: HttpClient,
-    base_url: String,
+    client: Transport,
+    endpoint: Endpoint,
+    recovery_policy: RecoveryPolicy,
+    strict_deletes: bool,
 }
 
 /// Vector store object
@@ -18,16 +32,18 @@ pub struct VectorStore {
     pub id: String,
 
     /// Type of object (always "vector_store")
+    #[cfg_attr(feature = "lenient", serde(default))]
     pub object: String,
 
     /// Name of the vector store
     pub name: String,
 
     /// Unix timestamp for when the vector store was created
-    #[serde(with = "chrono::serde::ts_seconds")]
+    #[serde(with = "crate::types::lenient::timestamp")]
     pub created_at: DateTime<Utc>,
 
     /// Status of the vector store
+    #[serde(deserialize_with = "crate::types::lenient::status")]
     pub status: String,
 
     /// Status details if the vector store is in an error state
@@ -58,6 +74,230 @@ pub struct AddFileToVectorStoreRequest {
     /// Optional attributes for the file
     #[serde(skip_serializing_if = "Option::is_none")]
     pub attributes: Option<serde_json::Value>,
+
+    /// How the file is split into chunks; the API uses [`ChunkingStrategy::Auto`] if unset
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub chunking_strategy: Option<ChunkingStrategy>,
+}
+
+/// How a file is split into chunks when it is added to a vector store
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(tag = "type", rename_all = "snake_case")]
+pub enum ChunkingStrategy {
+    /// Chunks of 800 tokens overlapping by 400
+    Auto,
+    /// Fixed-size chunks
+    Static {
+        /// Chunk size and overlap
+        #[serde(rename = "static")]
+        config: StaticChunking,
+    },
+    /// Reported for files chunked before strategies existed, or with a strategy this
+    /// crate does not know yet
+    #[serde(other)]
+    Other,
+}
+
+impl ChunkingStrategy {
+    /// Fixed-size chunks of `max_chunk_size_tokens` (100 to 4096), each overlapping the
+    /// previous one by `chunk_overlap_tokens` (at most half the chunk size)
+    #[must_use]
+    pub fn fixed(max_chunk_size_tokens: u32, chunk_overlap_tokens: u32) -> Self {
+        Self::Static {
+            config: StaticChunking {
+                max_chunk_size_tokens,
+                chunk_overlap_tokens,
+            },
+        }
+    }
+}
+
+/// Chunk size and overlap of a [`ChunkingStrategy::Static`]
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub struct StaticChunking {
+    /// Maximum number of tokens in a chunk
+    pub max_chunk_size_tokens: u32,
+
+    /// Number of tokens shared by consecutive chunks
+    pub chunk_overlap_tokens: u32,
+}
+
+/// Processing status of a file in a vector store
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum VectorStoreFileStatus {
+    /// The file is being chunked and embedded
+    InProgress,
+    /// The file is ready to be searched
+    Completed,
+    /// Processing was cancelled
+    Cancelled,
+    /// Processing failed; see [`VectorStoreFileAssociation::last_error`]
+    Failed,
+    /// A status this crate does not know yet
+    #[serde(other)]
+    Unknown,
+}
+
+impl VectorStoreFileStatus {
+    /// Whether processing has ended, successfully or not
+    #[must_use]
+    pub fn is_terminal(self) -> bool {
+        self != Self::InProgress
+    }
+}
+
+/// Error that stopped a file from being processed
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct VectorStoreFileError {
+    /// Error code, e.g. `unsupported_file`
+    pub code: String,
+
+    /// Human-readable description
+    pub message: String,
+}
+
+/// A file attached to a vector store (`vector_store.file`)
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct VectorStoreFileAssociation {
+    /// ID of the attached file
+    pub id: String,
+
+    /// Type of object (always "vector_store.file")
+    #[cfg_attr(feature = "lenient", serde(default))]
+    pub object: String,
+
+    /// Unix timestamp for when the file was attached
+    #[serde(with = "crate::types::lenient::timestamp")]
+    pub created_at: DateTime<Utc>,
+
+    /// ID of the vector store the file is attached to
+    pub vector_store_id: String,
+
+    /// Processing status
+    pub status: VectorStoreFileStatus,
+
+    /// Error that stopped processing, if the status is `failed`
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub last_error: Option<VectorStoreFileError>,
+
+    /// Storage used by the file in the vector store, in bytes
+    #[serde(default)]
+    pub usage_bytes: u64,
+
+    /// Attributes set on the file
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub attributes: Option<serde_json::Value>,
+
+    /// How the file was chunked
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub chunking_strategy: Option<ChunkingStrategy>,
+}
+
+/// Options for [`VectorStores::upload_and_attach`]
+#[derive(Debug, Clone, PartialEq)]
+pub struct AttachOptions {
+    /// Attributes set on the attached file
+    pub attributes: Option<serde_json::Value>,
+
+    /// How the file is chunked; the API's default if unset
+    pub chunking_strategy: Option<ChunkingStrategy>,
+
+    /// Wait until the file has been processed before returning
+    pub wait_until_ready: bool,
+
+    /// Delay between status checks while waiting
+    pub poll_interval: Duration,
+
+    /// Give up waiting once this much time has passed; `None` waits without a time limit
+    pub timeout: Option<Duration>,
+
+    /// Give up waiting after this many status checks; `None` checks without a limit
+    pub max_polls: Option<u32>,
+
+    /// Delete the uploaded file if attaching it fails
+    pub cleanup_on_failure: bool,
+}
+
+impl Default for AttachOptions {
+    fn default() -> Self {
+        Self {
+            attributes: None,
+            chunking_strategy: None,
+            wait_until_ready: false,
+            poll_interval: Duration::from_secs(1),
+            timeout: Some(Duration::from_secs(600)),
+            max_polls: None,
+            cleanup_on_failure: true,
+        }
+    }
+}
+
+impl AttachOptions {
+    /// Creates options that attach without waiting and clean up on failure
+    #[must_use]
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Sets the attributes of the attached file
+    #[must_use]
+    pub fn with_attributes(mut self, attributes: serde_json::Value) -> Self {
+        self.attributes = Some(attributes);
+        self
+    }
+
+    /// Sets how the file is chunked
+    #[must_use]
+    pub fn with_chunking_strategy(mut self, chunking_strategy: ChunkingStrategy) -> Self {
+        self.chunking_strategy = Some(chunking_strategy);
+        self
+    }
+
+    /// Sets whether to wait until the file has been processed
+    #[must_use]
+    pub fn with_wait_until_ready(mut self, wait: bool) -> Self {
+        self.wait_until_ready = wait;
+        self
+    }
+
+    /// Sets the delay between status checks while waiting
+    #[must_use]
+    pub fn with_poll_interval(mut self, poll_interval: Duration) -> Self {
+        self.poll_interval = poll_interval;
+        self
+    }
+
+    /// Sets how long to wait for processing before giving up (10 minutes by default)
+    #[must_use]
+    pub fn with_timeout(mut self, timeout: Option<Duration>) -> Self {
+        self.timeout = timeout;
+        self
+    }
+
+    /// Sets how many status checks to make before giving up (unlimited by default)
+    #[must_use]
+    pub fn with_max_polls(mut self, max_polls: Option<u32>) -> Self {
+        self.max_polls = max_polls;
+        self
+    }
+
+    /// Sets whether the uploaded file is deleted if attaching it fails
+    #[must_use]
+    pub fn with_cleanup_on_failure(mut self, cleanup: bool) -> Self {
+        self.cleanup_on_failure = cleanup;
+        self
+    }
+}
+
+/// A file uploaded and attached by [`VectorStores::upload_and_attach`]
+#[derive(Debug, Clone)]
+pub struct AttachedFile {
+    /// The uploaded file
+    pub file: File,
+
+    /// The file's attachment to the vector store, as last reported
+    pub association: VectorStoreFileAssociation,
 }
 
 /// Request to search a vector store
@@ -69,14 +309,53 @@ pub struct SearchVectorStoreRequest {
     /// Maximum number of results to return
     #[serde(skip_serializing_if = "Option::is_none")]
     pub max_num_results: Option<u32>,
+
+    /// Attribute filter applied to the files before ranking, e.g.
+    /// `{"type": "eq", "key": "lang", "value": "rust"}`
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub filters: Option<serde_json::Value>,
+}
+
+impl SearchVectorStoreRequest {
+    /// Creates a search request for `query`
+    #[must_use]
+    pub fn new(query: impl Into<String>) -> Self {
+        Self {
+            query: query.into(),
+            max_num_results: None,
+            filters: None,
+        }
+    }
+
+    /// Sets the maximum number of results to return
+    #[must_use]
+    pub fn with_max_num_results(mut self, max_num_results: u32) -> Self {
+        self.max_num_results = Some(max_num_results);
+        self
+    }
+
+    /// Sets the attribute filter, either a comparison or a compound `and`/`or` filter
+    #[must_use]
+    pub fn with_filters(mut self, filters: serde_json::Value) -> Self {
+        self.filters = Some(filters);
+        self
+    }
 }
 
 /// Result from searching a vector store
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct SearchVectorStoreResult {
+    /// ID of the file that matched the query
+    #[serde(default)]
+    pub file_id: String,
+
     /// Filename of the file that matched the query
     pub filename: String,
 
+    /// Attributes of the file that matched the query
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub attributes: Option<serde_json::Value>,
+
     /// Content that matched the query
     pub content: Vec<SearchContent>,
 
@@ -96,25 +375,67 @@ pub struct SearchContent {
 pub struct SearchVectorStoreResponse {
     /// Results from the search
     pub data: Vec<SearchVectorStoreResult>,
-}
 
-/// Response from deleting a file from a vector store
-#[derive(Debug, Clone, Serialize, Deserialize)]
-pub struct VectorStoreFileDeleteResponse {
-    /// ID of the deleted file
-    pub id: String,
+    /// Whether more results are available
+    #[serde(default)]
+    pub has_more: bool,
 
-    /// Object type (always "vector_store.file.deleted")
-    pub object: String,
+    /// Cursor for the next page of results, if any
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub next_page: Option<String>,
+}
 
-    /// Whether the file was successfully deleted
-    pub deleted: bool,
+/// Search request body with the page cursor used when following `next_page`
+#[derive(Serialize)]
+struct SearchPageRequest<'a> {
+    #[serde(flatten)]
+    request: &'a SearchVectorStoreRequest,
+
+    #[serde(skip_serializing_if = "Option::is_none")]
+    page: Option<&'a str>,
 }
 
+/// Response from deleting a file from a vector store
+pub type VectorStoreFileDeleteResponse = DeletionStatus;
+
 impl VectorStores {
-    /// Creates a new Vector Stores API client
-    pub(crate) fn new(client: HttpClient, base_url: String) -> Self {
-        Self { client, base_url }
+    /// Creates a new Vector Stores API client that retries failed requests as
+    /// `recovery_policy` allows
+    pub(crate) fn new(
+        client: impl Into<Transport>,
+        endpoint: impl Into<Endpoint>,
+        recovery_policy: RecoveryPolicy,
+    ) -> Self {
+        Self {
+            client: client.into(),
+            endpoint: endpoint.into(),
+            recovery_policy,
+            strict_deletes: false,
+        }
+    }
+
+    /// Returns a builder for a Vector Stores API client that is used on its own, with its
+    /// own base URL and recovery policy
+    pub fn builder() -> crate::EndpointBuilder<Self> {
+        crate::EndpointBuilder::default()
+    }
+
+    /// Sends this handle's requests with `auth` as the `Authorization` header
+    pub(crate) fn with_auth(mut self, auth: &reqwest::header::HeaderValue) -> Self {
+        self.client = self.client.with_auth(auth.clone());
+        self
+    }
+
+    fn retrying(&self) -> Retrying<'_> {
+        Retrying::new(&self.client, &self.recovery_policy)
+    }
+
+    /// Sets whether [`VectorStores::delete_file`] returns a 404 as an error instead of
+    /// [`DeleteOutcome::NotFound`]. Off by default.
+    #[must_use]
+    pub fn with_strict_deletes(mut self, enabled: bool) -> Self {
+        self.strict_deletes = enabled;
+        self
     }
 
     /// Creates a new vector store.
@@ -123,16 +444,10 @@ impl VectorStores {
     ///
     /// Returns an error if the request fails to send or has a non-200 status code.
     pub async fn create(&self, request: CreateVectorStoreRequest) -> Result<VectorStore> {
-        let response = self
-            .client
-            .post(format!("{}/vector_stores", self.base_url))
-            .json(&request)
-            .send()
+        let url = self.endpoint.url(&["vector_stores"])?;
+        self.retrying()
+            .send_json(|| Ok(self.client.post(url.clone()).json(&request)))
             .await
-            .map_err(crate::Error::Http)?;
-
-        let response = try_parse_api_error(response).await?;
-        response.json().await.map_err(crate::Error::Http)
     }
 
     /// Retrieves a vector store by ID.
@@ -141,39 +456,36 @@ impl VectorStores {
     ///
     /// Returns an error if the request fails to send or has a non-200 status code.
     pub async fn get(&self, vector_store_id: &str) -> Result<VectorStore> {
-        let response = self
-            .client
-            .get(format!(
-                "{}/vector_stores/{}",
-                self.base_url, vector_store_id
-            ))
-            .send()
+        let url = self.endpoint.url(&["vector_stores", vector_store_id])?;
+        self.retrying()
+            .send_json(|| Ok(self.client.get(url.clone())))
             .await
-            .map_err(crate::Error::Http)?;
-
-        let response = try_parse_api_error(response).await?;
-        response.json().await.map_err(crate::Error::Http)
     }
 
     /// Lists all vector stores.
     ///
     /// # Errors
     ///
-    /// Returns an error if the request fails to send or has a non-200 status code.
+    /// Returns an error if `params` has an out-of-range limit, or if the request fails to send
+    /// or has a non-200 status code.
     pub async fn list(
         &self,
         params: Option<PaginationParams>,
     ) -> Result<PaginatedList<VectorStore>> {
-        let mut request = self.client.get(format!("{}/vector_stores", self.base_url));
-
-        if let Some(params) = params {
-            request = request.query(&params);
+        if let Some(params) = &params {
+            params.validate()?;
         }
+        let url = self.endpoint.url(&["vector_stores"])?;
 
-        let response = request.send().await.map_err(crate::Error::Http)?;
-
-        let response = try_parse_api_error(response).await?;
-        response.json().await.map_err(crate::Error::Http)
+        self.retrying()
+            .send_json(|| {
+                let mut request = self.client.get(url.clone());
+                if let Some(params) = &params {
+                    request = request.query(params);
+                }
+                Ok(request)
+            })
+            .await
     }
 
     /// Deletes a vector store.
@@ -182,20 +494,27 @@ impl VectorStores {
     ///
     /// Returns an error if the request fails to send or has a non-200 status code.
     pub async fn delete(&self, vector_store_id: &str) -> Result<()> {
-        let response = self
-            .client
-            .delete(format!(
-                "{}/vector_stores/{}",
-                self.base_url, vector_store_id
-            ))
-            .send()
-            .await
-            .map_err(crate::Error::Http)?;
-
-        try_parse_api_error(response).await?;
+        let url = self.endpoint.url(&["vector_stores", vector_store_id])?;
+        self.retrying()
+            .send(|| Ok(self.client.delete(url.clone())))
+            .await?;
         Ok(())
     }
 
+    /// Deletes a vector store and returns the API's confirmation.
+    ///
+    /// A 404 is returned as [`DeleteConfirmation::NotFound`], or as an error with
+    /// [`VectorStores::with_strict_deletes`].
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the request fails to send, has a non-200 status code, or the
+    /// confirmation cannot be decoded.
+    pub async fn delete_confirmed(&self, vector_store_id: &str) -> Result<DeleteConfirmation> {
+        let url = self.endpoint.url(&["vector_stores", vector_store_id])?;
+        self.delete_url(url).await
+    }
+
     /// Adds a file to a vector store.
     ///
     /// # Errors
@@ -206,43 +525,185 @@ impl VectorStores {
         vector_store_id: &str,
         request: AddFileToVectorStoreRequest,
     ) -> Result<serde_json::Value> {
-        let response = self
-            .client
-            .post(format!(
-                "{}/vector_stores/{}/files",
-                self.base_url, vector_store_id
-            ))
-            .json(&request)
-            .send()
+        let url = self
+            .endpoint
+            .url(&["vector_stores", vector_store_id, "files"])?;
+        self.retrying()
+            .send_json(|| Ok(self.client.post(url.clone()).json(&request)))
+            .await
+    }
+
+    /// Retrieves a file's attachment to a vector store, including its processing status.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the request fails to send or has a non-200 status code.
+    pub async fn get_file(
+        &self,
+        vector_store_id: &str,
+        file_id: &str,
+    ) -> Result<VectorStoreFileAssociation> {
+        let url = self
+            .endpoint
+            .url(&["vector_stores", vector_store_id, "files", file_id])?;
+        self.retrying()
+            .send_json(|| Ok(self.client.get(url.clone())))
             .await
-            .map_err(crate::Error::Http)?;
+    }
 
-        let response = try_parse_api_error(response).await?;
-        response.json().await.map_err(crate::Error::Http)
+    /// Checks a file's status every [`AttachOptions::poll_interval`] until processing has
+    /// ended, and returns the last status reported.
+    ///
+    /// A `failed` or `cancelled` file is returned, not an error; check
+    /// [`VectorStoreFileAssociation::status`]. Only the waiting fields of `options` are
+    /// used.
+    ///
+    /// # Errors
+    ///
+    /// Returns [`Error::PollTimeout`](crate::Error::PollTimeout) if the file is still
+    /// processing after [`AttachOptions::timeout`] or [`AttachOptions::max_polls`], or an
+    /// error if a status check fails to send or has a non-200 status code.
+    pub async fn wait_for_file(
+        &self,
+        vector_store_id: &str,
+        file_id: &str,
+        options: &AttachOptions,
+    ) -> Result<VectorStoreFileAssociation> {
+        let clock = crate::runtime::Stopwatch::start();
+        let mut polls: u32 = 0;
+        loop {
+            let association = self.get_file(vector_store_id, file_id).await?;
+            polls = polls.saturating_add(1);
+            if association.status.is_terminal() {
+                return Ok(association);
+            }
+            let out_of_polls = options.max_polls.is_some_and(|max| polls >= max);
+            let out_of_time = options
+                .timeout
+                .is_some_and(|timeout| clock.elapsed() >= timeout);
+            if out_of_polls || out_of_time {
+                return Err(crate::Error::PollTimeout {
+                    resource: format!("file {file_id} in vector store {vector_store_id}"),
+                    polls,
+                });
+            }
+            crate::runtime::sleep(options.poll_interval).await;
+        }
+    }
+
+    /// Uploads a local file and attaches it to a vector store, optionally waiting until
+    /// it has been processed.
+    ///
+    /// The file is uploaded with the `assistants` purpose. If attaching it fails, the
+    /// uploaded file is deleted again unless [`AttachOptions::cleanup_on_failure`] is off;
+    /// the attach error is returned either way. Once attached, the file is never
+    /// deleted, including when processing fails or a status check errors.
+    ///
+    /// Not available on wasm32, which has no local filesystem.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the file cannot be read or uploaded, if attaching it fails, or
+    /// if a status check fails or the wait times out.
+    #[cfg(not(target_arch = "wasm32"))]
+    pub async fn upload_and_attach(
+        &self,
+        vector_store_id: &str,
+        path: impl AsRef<std::path::Path>,
+        mut options: AttachOptions,
+    ) -> Result<AttachedFile> {
+        let files = crate::files::Files::new(
+            self.client.clone(),
+            self.endpoint.clone(),
+            self.recovery_policy.clone(),
+        );
+        let file = files
+            .upload_file(path, crate::files::FilePurpose::Assistants, None)
+            .await?;
+
+        let url = self
+            .endpoint
+            .url(&["vector_stores", vector_store_id, "files"])?;
+        let request = AddFileToVectorStoreRequest {
+            file_id: file.id.clone(),
+            attributes: options.attributes.take(),
+            chunking_strategy: options.chunking_strategy.take(),
+        };
+        let attached: Result<VectorStoreFileAssociation> = self
+            .retrying()
+            .send_json(|| Ok(self.client.post(url.clone()).json(&request)))
+            .await;
+        let association = match attached {
+            Ok(association) => association,
+            Err(error) => {
+                if options.cleanup_on_failure {
+                    if let Err(cleanup_error) = files.delete_confirmed(&file.id).await {
+                        crate::diag::warn!(
+                            "Failed to delete file {} after attaching it failed: {cleanup_error}",
+                            file.id
+                        );
+                    }
+                }
+                return Err(error);
+            }
+        };
+
+        let association = if options.wait_until_ready && !association.status.is_terminal() {
+            self.wait_for_file(vector_store_id, &file.id, &options)
+                .await?
+        } else {
+            association
+        };
+        Ok(AttachedFile { file, association })
     }
 
     /// Removes a file from a vector store.
     ///
+    /// A 404, or a response reporting `deleted: false`, is returned as
+    /// [`DeleteOutcome::NotFound`]; with [`VectorStores::with_strict_deletes`] a 404 is
+    /// an error instead.
+    ///
     /// # Errors
     ///
     /// Returns an error if the request fails to send or has a non-200 status code.
-    pub async fn delete_file(
+    pub async fn delete_file(&self, vector_store_id: &str, file_id: &str) -> Result<DeleteOutcome> {
+        Ok(self
+            .delete_file_confirmed(vector_store_id, file_id)
+            .await?
+            .outcome())
+    }
+
+    /// Removes a file from a vector store and returns the API's confirmation.
+    ///
+    /// A 404 is returned as [`DeleteConfirmation::NotFound`], or as an error with
+    /// [`VectorStores::with_strict_deletes`].
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the request fails to send, has a non-200 status code, or the
+    /// confirmation cannot be decoded.
+    pub async fn delete_file_confirmed(
         &self,
         vector_store_id: &str,
         file_id: &str,
-    ) -> Result<VectorStoreFileDeleteResponse> {
-        let response = self
-            .client
-            .delete(format!(
-                "{}/vector_stores/{}/files/{}",
-                self.base_url, vector_store_id, file_id
-            ))
-            .send()
-            .await
-            .map_err(crate::Error::Http)?;
+    ) -> Result<DeleteConfirmation> {
+        let url = self
+            .endpoint
+            .url(&["vector_stores", vector_store_id, "files", file_id])?;
+        self.delete_url(url).await
+    }
 
-        let response = try_parse_api_error(response).await?;
-        response.json().await.map_err(crate::Error::Http)
+    async fn delete_url(&self, url: url::Url) -> Result<DeleteConfirmation> {
+        let allowed: &[StatusCode] = if self.strict_deletes {
+            &[]
+        } else {
+            &[StatusCode::NOT_FOUND]
+        };
+        let response = self
+            .retrying()
+            .send_allowing(|| Ok(self.client.delete(url.clone())), allowed)
+            .await?;
+        self.client.deletion(response).await
     }
 
     /// Searches a vector store.
@@ -255,18 +716,494 @@ impl VectorStores {
         vector_store_id: &str,
         request: SearchVectorStoreRequest,
     ) -> Result<SearchVectorStoreResponse> {
-        let response = self
-            .client
-            .post(format!(
-                "{}/vector_stores/{}/search",
-                self.base_url, vector_store_id
-            ))
-            .json(&request)
-            .send()
+        self.search_page(vector_store_id, &request, None).await
+    }
+
+    /// Searches a vector store and follows `next_page` until all results are collected.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if any page request fails to send or has a non-200 status code.
+    pub async fn search_all_pages(
+        &self,
+        vector_store_id: &str,
+        request: SearchVectorStoreRequest,
+    ) -> Result<Vec<SearchVectorStoreResult>> {
+        let mut results = Vec::new();
+        let mut page: Option<String> = None;
+
+        loop {
+            let response = self
+                .search_page(vector_store_id, &request, page.as_deref())
+                .await?;
+            results.extend(response.data);
+
+            match response.next_page {
+                // Stop if the server hands back the cursor we just used
+                Some(next) if response.has_more && page.as_deref() != Some(next.as_str()) => {
+                    page = Some(next);
+                }
+                _ => return Ok(results),
+            }
+        }
+    }
+
+    /// Searches several vector stores concurrently and merges the results.
+    ///
+    /// At most four searches run at once. Each store contributes its first page of
+    /// results, tagged with the store ID; the merged list is sorted by score, highest
+    /// first, with ties kept in `vector_store_ids` order.
+    ///
+    /// # Errors
+    ///
+    /// Returns the first error from any of the searches.
+    pub async fn search_many(
+        &self,
+        vector_store_ids: &[String],
+        request: SearchVectorStoreRequest,
+    ) -> Result<Vec<(String, SearchVectorStoreResult)>> {
+        let request = &request;
+        let responses: Vec<_> = futures_util::stream::iter(vector_store_ids)
+            .map(|id| async move {
+                let response = self.search_page(id, request, None).await?;
+                Ok::<_, crate::Error>((id, response))
+            })
+            .buffered(SEARCH_MANY_CONCURRENCY)
+            .try_collect()
+            .await?;
+
+        let mut results: Vec<_> = responses
+            .into_iter()
+            .flat_map(|(id, response)| {
+                response
+                    .data
+                    .into_iter()
+                    .map(move |result| (id.clone(), result))
+            })
+            .collect();
+        results.sort_by(|(_, a), (_, b)| b.score.total_cmp(&a.score));
+        Ok(results)
+    }
+
+    async fn search_page(
+        &self,
+        vector_store_id: &str,
+        request: &SearchVectorStoreRequest,
+        page: Option<&str>,
+    ) -> Result<SearchVectorStoreResponse> {
+        let url = self
+            .endpoint
+            .url(&["vector_stores", vector_store_id, "search"])?;
+        let body = SearchPageRequest { request, page };
+        self.retrying()
+            .send_json(|| Ok(self.client.post(url.clone()).json(&body)))
+            .await
+    }
+}
+
+impl crate::EndpointBuilder<VectorStores> {
+    /// Builds a standalone Vector Stores API client.
+    ///
+    /// # Errors
+    ///
+    /// Returns `CreateError::InvalidBaseUrl` if the base URL is not a valid http or https
+    /// URL, `CreateError::ApiKeyNotFound` if neither an API key nor an HTTP client was
+    /// given and `OPENAI_API_KEY` is not set, or `CreateError::InvalidApiKey` if the key
+    /// is invalid
+    pub fn build(self) -> std::result::Result<VectorStores, crate::CreateError> {
+        let (client, endpoint, recovery_policy) = self.into_parts()?;
+        Ok(VectorStores::new(client, endpoint, recovery_policy))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn result_json(filename: &str, score: f64) -> serde_json::Value {
+        serde_json::json!({
+            "filename": filename,
+            "content": [{"type": "text", "text": format!("from {filename}")}],
+            "score": score
+        })
+    }
+
+    fn query() -> SearchVectorStoreRequest {
+        SearchVectorStoreRequest {
+            query: "ownership".to_string(),
+            max_num_results: Some(2),
+            filters: None,
+        }
+    }
+
+    fn vector_store_file(status: &str) -> String {
+        serde_json::json!({
+            "id": "file_1",
+            "object": "vector_store.file",
+            "created_at": 1_700_000_000,
+            "vector_store_id": "vs_1",
+            "status": status,
+            "usage_bytes": if status == "completed" { 1024 } else { 0 },
+            "chunking_strategy": {
+                "type": "static",
+                "static": {"max_chunk_size_tokens": 400, "chunk_overlap_tokens": 100}
+            }
+        })
+        .to_string()
+    }
+
+    async fn mock_upload(server: &mut mockito::ServerGuard, hits: usize) -> mockito::Mock {
+        server
+            .mock("POST", "/files")
+            .with_status(200)
+            .with_body(
+                serde_json::json!({
+                    "id": "file_1",
+                    "object": "file",
+                    "bytes": 20,
+                    "created_at": 1_700_000_000,
+                    "filename": "guide.md",
+                    "purpose": "assistants",
+                    "status": "processed"
+                })
+                .to_string(),
+            )
+            .expect(hits)
+            .create_async()
+            .await
+    }
+
+    fn guide_path(name: &str) -> std::path::PathBuf {
+        let path = std::env::temp_dir().join(format!("{name}-{}-guide.md", std::process::id()));
+        std::fs::write(&path, "Keep functions small").unwrap();
+        path
+    }
+
+    #[tokio::test]
+    async fn upload_and_attach_waits_until_the_file_is_ready() {
+        let mut server = mockito::Server::new_async().await;
+        let upload = mock_upload(&mut server, 1).await;
+        let attach = server
+            .mock("POST", "/vector_stores/vs_1/files")
+            .match_body(mockito::Matcher::Json(serde_json::json!({
+                "file_id": "file_1",
+                "attributes": {"team": "docs"},
+                "chunking_strategy": {
+                    "type": "static",
+                    "static": {"max_chunk_size_tokens": 400, "chunk_overlap_tokens": 100}
+                }
+            })))
+            .with_status(200)
+            .with_body(vector_store_file("in_progress"))
+            .expect(1)
+            .create_async()
+            .await;
+        let status = server
+            .mock("GET", "/vector_stores/vs_1/files/file_1")
+            .with_status(200)
+            .with_body(vector_store_file("completed"))
+            .expect(1)
+            .create_async()
+            .await;
+
+        let stores = VectorStores::new(
+            reqwest::Client::new(),
+            server.url(),
+            RecoveryPolicy::default(),
+        );
+        let path = guide_path("attach-ready");
+        let attached = stores
+            .upload_and_attach(
+                "vs_1",
+                &path,
+                AttachOptions::new()
+                    .with_attributes(serde_json::json!({"team": "docs"}))
+                    .with_chunking_strategy(ChunkingStrategy::fixed(400, 100))
+                    .with_wait_until_ready(true)
+                    .with_poll_interval(Duration::from_millis(1)),
+            )
             .await
-            .map_err(crate::Error::Http)?;
+            .unwrap();
+        std::fs::remove_file(path).unwrap();
+
+        assert_eq!(attached.file.filename, "guide.md");
+        assert_eq!(
+            attached.association.status,
+            VectorStoreFileStatus::Completed
+        );
+        assert_eq!(attached.association.usage_bytes, 1024);
+        assert_eq!(
+            attached.association.chunking_strategy,
+            Some(ChunkingStrategy::fixed(400, 100))
+        );
+        upload.assert_async().await;
+        attach.assert_async().await;
+        status.assert_async().await;
+    }
+
+    #[tokio::test(start_paused = true)]
+    async fn waiting_for_a_stuck_file_times_out() {
+        let mut server = mockito::Server::new_async().await;
+        let status = server
+            .mock("GET", "/vector_stores/vs_1/files/file_1")
+            .with_status(200)
+            .with_body(vector_store_file("in_progress"))
+            .expect(3 + 6)
+            .create_async()
+            .await;
+        let stores = VectorStores::new(
+            reqwest::Client::new(),
+            server.url(),
+            RecoveryPolicy::default(),
+        );
+
+        let error = stores
+            .wait_for_file(
+                "vs_1",
+                "file_1",
+                &AttachOptions::new().with_max_polls(Some(3)),
+            )
+            .await
+            .unwrap_err();
+        assert!(
+            matches!(&error, crate::Error::PollTimeout { polls: 3, resource } if resource.contains("file_1")),
+            "{error:?}"
+        );
+
+        // Checks at 0s, 1s, ... 5s, then the 5s timeout is reached
+        let options = AttachOptions::new()
+            .with_poll_interval(Duration::from_secs(1))
+            .with_timeout(Some(Duration::from_secs(5)));
+        let error = stores
+            .wait_for_file("vs_1", "file_1", &options)
+            .await
+            .unwrap_err();
+        assert!(
+            matches!(error, crate::Error::PollTimeout { polls: 6, .. }),
+            "{error:?}"
+        );
+        status.assert_async().await;
+    }
+
+    #[tokio::test]
+    async fn upload_and_attach_deletes_the_file_when_attaching_fails() {
+        let mut server = mockito::Server::new_async().await;
+        let upload = mock_upload(&mut server, 2).await;
+        let attach = server
+            .mock("POST", "/vector_stores/vs_missing/files")
+            .with_status(404)
+            .with_body(
+                r#"{"error":{"message":"No vector store found","type":"invalid_request_error"}}"#,
+            )
+            .expect(2)
+            .create_async()
+            .await;
+        let cleanup = server
+            .mock("DELETE", "/files/file_1")
+            .with_status(200)
+            .with_body(r#"{"id":"file_1","object":"file","deleted":true}"#)
+            .expect(1)
+            .create_async()
+            .await;
+
+        let stores = VectorStores::new(
+            reqwest::Client::new(),
+            server.url(),
+            RecoveryPolicy::default(),
+        );
+        let path = guide_path("attach-cleanup");
+        let error = stores
+            .upload_and_attach("vs_missing", &path, AttachOptions::new())
+            .await
+            .unwrap_err();
+        assert!(
+            error.to_string().contains("No vector store found"),
+            "{error}"
+        );
+
+        // Without cleanup the uploaded file is left alone
+        let kept = stores
+            .upload_and_attach(
+                "vs_missing",
+                &path,
+                AttachOptions::new().with_cleanup_on_failure(false),
+            )
+            .await;
+        std::fs::remove_file(path).unwrap();
+        assert!(kept.is_err());
+
+        upload.assert_async().await;
+        attach.assert_async().await;
+        cleanup.assert_async().await;
+    }
+
+    #[tokio::test]
+    async fn search_all_pages_follows_next_page() {
+        let mut server = mockito::Server::new_async().await;
+        let first = server
+            .mock("POST", "/vector_stores/vs_1/search")
+            .match_body(mockito::Matcher::Json(serde_json::json!({
+                "query": "ownership",
+                "max_num_results": 2
+            })))
+            .with_status(200)
+            .with_body(
+                serde_json::json!({
+                    "object": "vector_store.search_results.page",
+                    "data": [result_json("a.md", 0.9), result_json("b.md", 0.8)],
+                    "has_more": true,
+                    "next_page": "cursor_2"
+                })
+                .to_string(),
+            )
+            .expect(1)
+            .create_async()
+            .await;
+        let second = server
+            .mock("POST", "/vector_stores/vs_1/search")
+            .match_body(mockito::Matcher::PartialJson(
+                serde_json::json!({"page": "cursor_2"}),
+            ))
+            .with_status(200)
+            .with_body(
+                serde_json::json!({
+                    "data": [result_json("c.md", 0.5)],
+                    "has_more": false,
+                    "next_page": null
+                })
+                .to_string(),
+            )
+            .expect(1)
+            .create_async()
+            .await;
+
+        let stores = VectorStores::new(
+            reqwest::Client::new(),
+            server.url(),
+            RecoveryPolicy::default(),
+        );
+        let results = stores.search_all_pages("vs_1", query()).await.unwrap();
+
+        let filenames: Vec<_> = results.iter().map(|r| r.filename.as_str()).collect();
+        assert_eq!(filenames, ["a.md", "b.md", "c.md"]);
+        first.assert_async().await;
+        second.assert_async().await;
+    }
+
+    #[tokio::test]
+    async fn search_many_merges_stores_by_score() {
+        let mut server = mockito::Server::new_async().await;
+        let mut mocks = Vec::new();
+        for (id, results) in [
+            (
+                "vs_docs",
+                vec![result_json("guide.md", 0.7), result_json("faq.md", 0.2)],
+            ),
+            (
+                "vs_code",
+                vec![result_json("lib.rs", 0.95), result_json("main.rs", 0.4)],
+            ),
+        ] {
+            mocks.push(
+                server
+                    .mock("POST", format!("/vector_stores/{id}/search").as_str())
+                    .with_status(200)
+                    .with_body(serde_json::json!({ "data": results }).to_string())
+                    .expect(1)
+                    .create_async()
+                    .await,
+            );
+        }
+
+        let stores = VectorStores::new(
+            reqwest::Client::new(),
+            server.url(),
+            RecoveryPolicy::default(),
+        );
+        let ids = vec!["vs_docs".to_string(), "vs_code".to_string()];
+        let merged = stores.search_many(&ids, query()).await.unwrap();
+
+        let ranked: Vec<_> = merged
+            .iter()
+            .map(|(id, r)| (id.as_str(), r.filename.as_str()))
+            .collect();
+        assert_eq!(
+            ranked,
+            [
+                ("vs_code", "lib.rs"),
+                ("vs_docs", "guide.md"),
+                ("vs_code", "main.rs"),
+                ("vs_docs", "faq.md"),
+            ]
+        );
+        for mock in mocks {
+            mock.assert_async().await;
+        }
+    }
+
+    #[tokio::test]
+    async fn search_many_fails_if_any_store_fails() {
+        let mut server = mockito::Server::new_async().await;
+        let _ok = server
+            .mock("POST", "/vector_stores/vs_ok/search")
+            .with_status(200)
+            .with_body(r#"{"data":[]}"#)
+            .create_async()
+            .await;
+        let _missing = server
+            .mock("POST", "/vector_stores/vs_missing/search")
+            .with_status(404)
+            .with_body(
+                r#"{"error":{"message":"No vector store found","type":"invalid_request_error"}}"#,
+            )
+            .create_async()
+            .await;
+
+        let stores = VectorStores::new(
+            reqwest::Client::new(),
+            server.url(),
+            RecoveryPolicy::default(),
+        );
+        let ids = vec!["vs_ok".to_string(), "vs_missing".to_string()];
+        assert!(stores.search_many(&ids, query()).await.is_err());
+    }
+
+    #[tokio::test]
+    async fn delete_file_maps_statuses_to_outcomes() {
+        let mut server = mockito::Server::new_async().await;
+        let deleted = server
+            .mock("DELETE", "/vector_stores/vs_1/files/file-1")
+            .with_status(200)
+            .with_body(r#"{"id":"file-1","object":"vector_store.file.deleted","deleted":true}"#)
+            .expect(1)
+            .create_async()
+            .await;
+        let missing = server
+            .mock("DELETE", "/vector_stores/vs_1/files/file-gone")
+            .with_status(404)
+            .with_body(r#"{"error":{"message":"No file found","type":"invalid_request_error"}}"#)
+            .expect(2)
+            .create_async()
+            .await;
+
+        let stores = VectorStores::new(
+            reqwest::Client::new(),
+            server.url(),
+            RecoveryPolicy::default(),
+        );
+        assert_eq!(
+            stores.delete_file("vs_1", "file-1").await.unwrap(),
+            DeleteOutcome::Deleted
+        );
+        assert_eq!(
+            stores.delete_file("vs_1", "file-gone").await.unwrap(),
+            DeleteOutcome::NotFound
+        );
 
-        let response = try_parse_api_error(response).await?;
-        response.json().await.map_err(crate::Error::Http)
+        let strict = stores.with_strict_deletes(true);
+        let error = strict.delete_file("vs_1", "file-gone").await.unwrap_err();
+        assert!(matches!(error, crate::Error::Api { .. }), "{error:?}");
+        deleted.assert_async().await;
+        missing.assert_async().await;
     }
 }
@@ -0,0 +1,321 @@
+//! Verifying and parsing webhook deliveries.
+//!
+//! With a webhook endpoint configured in the OpenAI dashboard, a response created
+//! with `background: true` is reported by a `POST` to your server when it finishes,
+//! instead of being polled for. Deliveries follow the Standard Webhooks scheme: the
+//! `webhook-signature` header holds one or more `v1,<base64>` HMAC-SHA256 signatures
+//! over `"{webhook-id}.{webhook-timestamp}.{body}"`, keyed with the endpoint's
+//! `whsec_` secret. [`Webhook`] checks a signature and the timestamp, then parses the
+//! body into a [`WebhookEvent`]. No HTTP calls are made.
+//!
+//! ```rust,no_run
+//! use open_ai_rust_responses_by_sshift::webhooks::{Webhook, WebhookEvent};
+//! # fn handle(body: &[u8], headers: &reqwest::header::HeaderMap) -> open_ai_rust_responses_by_sshift::Result<()> {
+//! let secret = std::env::var("OPENAI_WEBHOOK_SECRET").unwrap();
+//! match Webhook::verify_and_parse(body, headers, &secret)? {
+//!     WebhookEvent::ResponseCompleted(event) => println!("{} is ready", event.data.id),
+//!     event => println!("{event:?}"),
+//! }
+//! # Ok(())
+//! # }
+//! ```
+
+use crate::{Error, Result};
+use base64::Engine;
+use reqwest::header::HeaderMap;
+use serde::{Deserialize, Serialize};
+use std::fmt::Debug;
+use std::time::Duration;
+
+/// Default allowed difference between the delivery timestamp and the local clock
+pub const DEFAULT_TOLERANCE: Duration = Duration::from_secs(300);
+
+const ID_HEADER: &str = "webhook-id";
+const TIMESTAMP_HEADER: &str = "webhook-timestamp";
+const SIGNATURE_HEADER: &str = "webhook-signature";
+const SECRET_PREFIX: &str = "whsec_";
+
+/// A verified webhook delivery
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(tag = "type")]
+pub enum WebhookEvent {
+    /// A background response completed
+    #[serde(rename = "response.completed")]
+    ResponseCompleted(WebhookEventData),
+    /// A background response failed
+    #[serde(rename = "response.failed")]
+    ResponseFailed(WebhookEventData),
+    /// A background response was cancelled
+    #[serde(rename = "response.cancelled")]
+    ResponseCancelled(WebhookEventData),
+    /// A background response finished incomplete, e.g. at `max_output_tokens`
+    #[serde(rename = "response.incomplete")]
+    ResponseIncomplete(WebhookEventData),
+    /// An event type this version does not model (batches, fine-tuning jobs, evals)
+    #[serde(other)]
+    Unknown,
+}
+
+impl WebhookEvent {
+    /// Returns the event data, unless the event type is unknown
+    #[must_use]
+    pub fn data(&self) -> Option<&WebhookEventData> {
+        match self {
+            Self::ResponseCompleted(data)
+            | Self::ResponseFailed(data)
+            | Self::ResponseCancelled(data)
+            | Self::ResponseIncomplete(data) => Some(data),
+            Self::Unknown => None,
+        }
+    }
+
+    /// Returns the ID of the response the event is about, e.g. to retrieve it
+    #[must_use]
+    pub fn response_id(&self) -> Option<&str> {
+        self.data().map(|event| event.data.id.as_str())
+    }
+}
+
+/// Fields shared by the response webhook events
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct WebhookEventData {
+    /// Event ID, stable across redeliveries of the same event
+    pub id: String,
+    /// Unix timestamp of when the event was created
+    pub created_at: i64,
+    /// The response the event is about
+    pub data: WebhookResponseRef,
+}
+
+/// Reference to the response a webhook event is about
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct WebhookResponseRef {
+    /// Response ID
+    pub id: String,
+}
+
+/// Verifies webhook deliveries signed with an endpoint secret
+#[derive(Clone)]
+pub struct Webhook {
+    key: Vec<u8>,
+    tolerance: Duration,
+    clock: fn() -> i64,
+}
+
+impl Debug for Webhook {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("Webhook")
+            .field("key", &"<redacted>")
+            .field("tolerance", &self.tolerance)
+            .finish_non_exhaustive()
+    }
+}
+
+impl Webhook {
+    /// Creates a verifier from the endpoint's signing secret (`whsec_...`).
+    ///
+    /// # Errors
+    ///
+    /// Returns [`Error::WebhookVerification`] if the secret is not valid base64.
+    pub fn new(secret: &str) -> Result<Self> {
+        let encoded = secret.strip_prefix(SECRET_PREFIX).unwrap_or(secret);
+        let key = base64::engine::general_purpose::STANDARD
+            .decode(encoded)
+            .map_err(|error| Error::WebhookVerification(format!("invalid secret: {error}")))?;
+        Ok(Self {
+            key,
+            tolerance: DEFAULT_TOLERANCE,
+            clock: || chrono::Utc::now().timestamp(),
+        })
+    }
+
+    /// Sets how far the delivery timestamp may be from the local clock, in either
+    /// direction. Older deliveries are rejected as possible replays.
+    #[must_use]
+    pub fn with_tolerance(mut self, tolerance: Duration) -> Self {
+        self.tolerance = tolerance;
+        self
+    }
+
+    /// Replaces the clock supplying unix timestamps, e.g. with a fixed one in tests
+    #[must_use]
+    pub fn with_clock(mut self, clock: fn() -> i64) -> Self {
+        self.clock = clock;
+        self
+    }
+
+    /// Verifies a delivery with the default tolerance and parses its body.
+    ///
+    /// # Errors
+    ///
+    /// Returns [`Error::WebhookVerification`] if the secret is invalid, a header is
+    /// missing, the timestamp is outside [`DEFAULT_TOLERANCE`] or no signature
+    /// matches, and [`Error::Json`] if the verified body is not an event.
+    pub fn verify_and_parse(
+        payload: &[u8],
+        headers: &HeaderMap,
+        secret: &str,
+    ) -> Result<WebhookEvent> {
+        Self::new(secret)?.verify_event(payload, headers)
+    }
+
+    /// Verifies a delivery and parses its body.
+    ///
+    /// # Errors
+    ///
+    /// Returns [`Error::WebhookVerification`] if verification fails, and
+    /// [`Error::Json`] if the verified body is not an event.
+    pub fn verify_event(&self, payload: &[u8], headers: &HeaderMap) -> Result<WebhookEvent> {
+        self.verify(payload, headers)?;
+        Ok(serde_json::from_slice(payload)?)
+    }
+
+    /// Verifies a delivery's timestamp and signature without parsing its body.
+    ///
+    /// # Errors
+    ///
+    /// Returns [`Error::WebhookVerification`] if a header is missing or malformed,
+    /// the timestamp is outside the tolerance or no signature matches.
+    pub fn verify(&self, payload: &[u8], headers: &HeaderMap) -> Result<()> {
+        let id = header(headers, ID_HEADER)?;
+        let timestamp = header(headers, TIMESTAMP_HEADER)?;
+        let signatures = header(headers, SIGNATURE_HEADER)?;
+
+        let sent_at: i64 = timestamp.parse().map_err(|_| {
+            Error::WebhookVerification(format!("invalid {TIMESTAMP_HEADER}: {timestamp}"))
+        })?;
+        if (self.clock)().abs_diff(sent_at) > self.tolerance.as_secs() {
+            return Err(Error::WebhookVerification(format!(
+                "timestamp {sent_at} is outside the {}s tolerance",
+                self.tolerance.as_secs()
+            )));
+        }
+
+        let mut message = format!("{id}.{timestamp}.").into_bytes();
+        message.extend_from_slice(payload);
+        let expected = crate::signing::hmac_sha256(&self.key, &message);
+
+        let matched = signatures
+            .split(' ')
+            .filter_map(|entry| entry.strip_prefix("v1,"))
+            .filter_map(|encoded| {
+                base64::engine::general_purpose::STANDARD
+                    .decode(encoded)
+                    .ok()
+            })
+            .any(|signature| constant_time_eq(&signature, &expected));
+        if matched {
+            Ok(())
+        } else {
+            Err(Error::WebhookVerification(
+                "no matching v1 signature".to_string(),
+            ))
+        }
+    }
+}
+
+fn header<'a>(headers: &'a HeaderMap, name: &str) -> Result<&'a str> {
+    headers
+        .get(name)
+        .and_then(|value| value.to_str().ok())
+        .ok_or_else(|| Error::WebhookVerification(format!("missing {name} header")))
+}
+
+/// Compares without returning early, so timing reveals nothing about the expected value
+fn constant_time_eq(a: &[u8], b: &[u8]) -> bool {
+    a.len() == b.len() && a.iter().zip(b).fold(0, |diff, (x, y)| diff | (x ^ y)) == 0
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use reqwest::header::HeaderValue;
+
+    const FIXTURE: &str = include_str!("tests/fixtures/webhook_response_completed.json");
+    // base64("webhook-test-secret-key")
+    const SECRET: &str = "whsec_d2ViaG9vay10ZXN0LXNlY3JldC1rZXk=";
+    const TIMESTAMP: i64 = 1_750_287_078;
+    // HMAC-SHA256 of "msg_test_1.1750287078.{FIXTURE}" with the decoded secret
+    const SIGNATURE: &str = "v1,nojxxema5ZvlpvX1xHFtYTHd0ySJfTyZgY/jiNnHC/o=";
+
+    fn headers(signature: &str) -> HeaderMap {
+        let mut headers = HeaderMap::new();
+        headers.insert(ID_HEADER, HeaderValue::from_static("msg_test_1"));
+        headers.insert(
+            TIMESTAMP_HEADER,
+            HeaderValue::from_str(&TIMESTAMP.to_string()).unwrap(),
+        );
+        headers.insert(SIGNATURE_HEADER, HeaderValue::from_str(signature).unwrap());
+        headers
+    }
+
+    fn webhook() -> Webhook {
+        Webhook::new(SECRET).unwrap().with_clock(|| TIMESTAMP + 60)
+    }
+
+    #[test]
+    fn verifies_and_parses_a_signed_delivery() {
+        let event = webhook()
+            .verify_event(FIXTURE.as_bytes(), &headers(SIGNATURE))
+            .unwrap();
+        assert_eq!(event.response_id(), Some("resp_abc123"));
+        let WebhookEvent::ResponseCompleted(data) = event else {
+            panic!("expected response.completed, got {event:?}");
+        };
+        assert_eq!(data.id, "evt_685343a1381c819085d44c354e1b330e");
+        assert_eq!(data.created_at, TIMESTAMP);
+
+        // Any matching entry in a rotated signature list is accepted
+        let rotated = format!("v1,c3RhbGU= {SIGNATURE}");
+        assert!(webhook()
+            .verify(FIXTURE.as_bytes(), &headers(&rotated))
+            .is_ok());
+    }
+
+    #[test]
+    fn rejects_tampered_stale_and_unsigned_deliveries() {
+        let tampered = FIXTURE.replace("resp_abc123", "resp_evil");
+        let stale = Webhook::new(SECRET).unwrap().with_clock(|| TIMESTAMP + 301);
+        let mut unsigned = headers(SIGNATURE);
+        unsigned.remove(SIGNATURE_HEADER);
+
+        for result in [
+            webhook().verify(tampered.as_bytes(), &headers(SIGNATURE)),
+            stale.verify(FIXTURE.as_bytes(), &headers(SIGNATURE)),
+            webhook().verify(FIXTURE.as_bytes(), &unsigned),
+            webhook().verify(FIXTURE.as_bytes(), &headers("v0,whatever")),
+        ] {
+            assert!(
+                matches!(result, Err(Error::WebhookVerification(_))),
+                "{result:?}"
+            );
+        }
+        assert!(stale
+            .with_tolerance(Duration::from_secs(600))
+            .verify(FIXTURE.as_bytes(), &headers(SIGNATURE))
+            .is_ok());
+        assert!(matches!(
+            Webhook::new("whsec_not base64!"),
+            Err(Error::WebhookVerification(_))
+        ));
+    }
+
+    #[test]
+    fn response_event_types_are_typed() {
+        for (kind, expected) in [
+            ("response.failed", "ResponseFailed"),
+            ("response.cancelled", "ResponseCancelled"),
+            ("response.incomplete", "ResponseIncomplete"),
+        ] {
+            let body = FIXTURE.replace("response.completed", kind);
+            let event: WebhookEvent = serde_json::from_str(&body).unwrap();
+            assert!(format!("{event:?}").starts_with(expected), "{event:?}");
+            assert_eq!(event.response_id(), Some("resp_abc123"));
+        }
+
+        let batch = FIXTURE.replace("response.completed", "batch.completed");
+        let event: WebhookEvent = serde_json::from_str(&batch).unwrap();
+        assert_eq!(event, WebhookEvent::Unknown);
+        assert_eq!(event.response_id(), None);
+    }
+}
@@ -0,0 +1,169 @@
+//! End-to-end scenarios replayed from `tests/fixtures/`.
+//!
+//! These run offline by default. See `tests/support/mod.rs` for re-recording them
+//! against the live API.
+
+mod support;
+
+use open_ai_rust_responses_by_sshift::files::CreateFileRequest;
+use open_ai_rust_responses_by_sshift::types::{DeleteOutcome, ResponseStatus, Tool};
+use open_ai_rust_responses_by_sshift::vector_stores::{
+    AddFileToVectorStoreRequest, CreateVectorStoreRequest,
+};
+use open_ai_rust_responses_by_sshift::{Model, Request};
+use support::fixture_client;
+
+#[tokio::test]
+async fn basic_response() {
+    let (client, server) = fixture_client("basic_response").await;
+
+    let request = Request::builder()
+        .model(Model::GPT4o)
+        .input("Say hello in one short sentence.")
+        .build();
+    let response = client.responses.create(request).await.unwrap();
+
+    assert!(response.id().starts_with("resp_"));
+    assert_eq!(response.status, ResponseStatus::Completed);
+    assert!(!response.output_text().is_empty());
+    assert!(response.usage.is_some());
+    server.assert_replayed().await;
+}
+
+#[tokio::test]
+async fn function_calling_round_trip() {
+    let (client, server) = fixture_client("function_calling").await;
+
+    let weather = Tool::function(
+        "get_weather",
+        "Get the current weather for a city",
+        serde_json::json!({
+            "type": "object",
+            "properties": {"city": {"type": "string"}},
+            "required": ["city"]
+        }),
+    );
+    let request = Request::builder()
+        .model(Model::GPT4o)
+        .input("What is the weather in Paris? Use the tool.")
+        .tools(vec![weather.clone()])
+        .build();
+    let response = client.responses.create(request).await.unwrap();
+
+    let calls = response.tool_calls();
+    assert_eq!(calls.len(), 1);
+    assert_eq!(calls[0].name, "get_weather");
+    let arguments = calls[0].arguments_json().unwrap();
+    assert!(arguments["city"].as_str().unwrap().contains("Paris"));
+
+    let follow_up = Request::builder()
+        .model(Model::GPT4o)
+        .with_function_outputs(
+            response.id(),
+            vec![(
+                calls[0].call_id.clone(),
+                r#"{"temperature_c":18}"#.to_string(),
+            )],
+        )
+        .tools(vec![weather])
+        .build();
+    let answer = client.responses.create(follow_up).await.unwrap();
+
+    assert_eq!(answer.previous_response_id.as_deref(), Some(response.id()));
+    assert!(answer.tool_calls().is_empty());
+    assert!(answer.output_text().contains("18"));
+    server.assert_replayed().await;
+}
+
+#[cfg(feature = "stream")]
+#[tokio::test]
+async fn streaming_session() {
+    use futures::StreamExt;
+    use open_ai_rust_responses_by_sshift::types::{StreamCollector, StreamEvent};
+
+    let (client, server) = fixture_client("streaming_session").await;
+
+    let request = Request::builder()
+        .model(Model::GPT4o)
+        .input("Count from 1 to 5")
+        .build();
+    let mut stream = std::pin::pin!(client.responses.stream(request));
+    let mut collector = StreamCollector::new();
+    let mut response_id = None;
+    let mut deltas = 0;
+    while let Some(event) = stream.next().await {
+        let event = event.unwrap();
+        match &event {
+            StreamEvent::ResponseCreated { id } => response_id = Some(id.clone()),
+            StreamEvent::TextDelta { .. } => deltas += 1,
+            StreamEvent::Failed { error } => panic!("stream failed: {}", error.message),
+            _ => {}
+        }
+        collector.push_event(&event);
+    }
+
+    assert!(response_id.is_some_and(|id| id.starts_with("resp_")));
+    assert!(deltas > 1);
+    let text = collector.text();
+    for n in 1..=5 {
+        assert!(text.contains(&n.to_string()), "missing {n} in {text:?}");
+    }
+    server.assert_replayed().await;
+}
+
+#[tokio::test]
+async fn vector_store_lifecycle() {
+    let (client, server) = fixture_client("vector_store_lifecycle").await;
+
+    let file = client
+        .files
+        .create(CreateFileRequest {
+            purpose: "assistants".to_string(),
+            file: b"The fixture harness replays recorded API traffic.".to_vec(),
+            filename: "fixture-notes.txt".to_string(),
+            mime_type: Some("text/plain".to_string()),
+            expires_after: None,
+        })
+        .await
+        .unwrap();
+    assert!(file.id.starts_with("file-"));
+
+    let store = client
+        .vector_stores
+        .create(CreateVectorStoreRequest {
+            name: "fixture-lifecycle".to_string(),
+            file_ids: Vec::new(),
+        })
+        .await
+        .unwrap();
+    assert!(store.id.starts_with("vs_"));
+    assert_eq!(store.name, "fixture-lifecycle");
+
+    let attached = client
+        .vector_stores
+        .add_file(
+            &store.id,
+            AddFileToVectorStoreRequest {
+                file_id: file.id.clone(),
+                attributes: None,
+                chunking_strategy: None,
+            },
+        )
+        .await
+        .unwrap();
+    assert_eq!(attached["id"], file.id.as_str());
+
+    let fetched = client.vector_stores.get(&store.id).await.unwrap();
+    assert_eq!(fetched.id, store.id);
+
+    let detached = client
+        .vector_stores
+        .delete_file(&store.id, &file.id)
+        .await
+        .unwrap();
+    assert_eq!(detached, DeleteOutcome::Deleted);
+
+    client.vector_stores.delete(&store.id).await.unwrap();
+    client.files.delete(&file.id).await.unwrap();
+    server.assert_replayed().await;
+}
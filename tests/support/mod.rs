@@ -0,0 +1,451 @@
+//! Fixture harness for the integration tests.
+//!
+//! A scenario is a JSON file in `tests/fixtures/` listing the HTTP exchanges it makes, in
+//! order. [`fixture_client`] mounts every exchange on a mockito server and returns a client
+//! pointed at it, so the tests run offline and need no API key.
+//!
+//! With `RECORD_FIXTURES=1` and `OPENAI_API_KEY` set, the client talks to the live API
+//! (or `OPENAI_BASE_URL`) through a local recording proxy instead. When the test passes,
+//! the scenario file is rewritten from what was sent and received, with object IDs
+//! replaced by stable placeholders and API keys redacted:
+//!
+//! ```text
+//! RECORD_FIXTURES=1 OPENAI_API_KEY=sk-... cargo test --test integration basic_response
+//! ```
+
+use open_ai_rust_responses_by_sshift::Client;
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+use std::collections::HashMap;
+use std::path::PathBuf;
+use std::sync::{Arc, Mutex};
+
+const LIVE_BASE_URL: &str = "https://api.openai.com/v1";
+
+/// Prefixes of the object IDs replaced when a recording is saved
+const ID_PREFIXES: &[&str] = &[
+    "resp_", "msg_", "fc_", "call_", "rs_", "ws_", "fs_", "ig_", "ci_", "mcp_", "mcpl_", "vs_",
+    "file-", "cntr_", "cfile_", "ctc_",
+];
+
+/// Shortest ID suffix that is scrubbed, so field names like `call_id` are left alone
+const MIN_ID_SUFFIX_LEN: usize = 8;
+
+/// The exchanges of one scenario
+#[derive(Debug, Default, Serialize, Deserialize)]
+pub struct Scenario {
+    /// Exchanges in the order they were recorded
+    pub routes: Vec<Route>,
+}
+
+/// One recorded request and its response
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct Route {
+    /// HTTP method
+    pub method: String,
+    /// Path below the base URL, without the query string
+    pub path: String,
+    /// JSON request body; replayed requests must contain it (extra fields are ignored)
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub request: Option<Value>,
+    /// HTTP status of the response
+    pub status: u16,
+    /// Response body
+    #[serde(flatten)]
+    pub body: Body,
+}
+
+/// Response body of a [`Route`]
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum Body {
+    /// A JSON document
+    Json(Value),
+    /// Server-sent events, one `data:` payload each
+    Events(Vec<Value>),
+}
+
+/// Keeps the fixture server alive for the duration of a test
+pub struct FixtureServer {
+    mode: Mode,
+}
+
+enum Mode {
+    Replay {
+        _server: mockito::ServerGuard,
+        mocks: Vec<(String, mockito::Mock)>,
+    },
+    Record {
+        name: String,
+        routes: Arc<Mutex<Vec<Route>>>,
+        shutdown: Option<tokio::sync::oneshot::Sender<()>>,
+    },
+}
+
+impl FixtureServer {
+    /// Panics if a replayed exchange was never requested; does nothing while recording
+    pub async fn assert_replayed(&self) {
+        if let Mode::Replay { mocks, .. } = &self.mode {
+            for (route, mock) in mocks {
+                assert!(
+                    mock.matched_async().await,
+                    "fixture route never requested: {route}"
+                );
+            }
+        }
+    }
+}
+
+impl Drop for FixtureServer {
+    fn drop(&mut self) {
+        let Mode::Record {
+            name,
+            routes,
+            shutdown,
+        } = &mut self.mode
+        else {
+            return;
+        };
+        if let Some(shutdown) = shutdown.take() {
+            let _ = shutdown.send(());
+        }
+        if std::thread::panicking() {
+            eprintln!("Not saving fixture {name}: the test failed");
+            return;
+        }
+        let routes = std::mem::take(&mut *routes.lock().unwrap());
+        let scenario = Scrubber::default().scenario(Scenario { routes });
+        let json = serde_json::to_string_pretty(&scenario).expect("scenario serializes");
+        std::fs::write(fixture_path(name), json + "\n").expect("fixture is writable");
+        eprintln!("Recorded fixture {name}");
+    }
+}
+
+/// Returns a client for the named scenario and the server it talks to
+pub async fn fixture_client(name: &str) -> (Client, FixtureServer) {
+    match std::env::var("OPENAI_API_KEY") {
+        Ok(api_key) if std::env::var("RECORD_FIXTURES").as_deref() == Ok("1") => {
+            record(name, api_key).await
+        }
+        _ => replay(name).await,
+    }
+}
+
+fn fixture_path(name: &str) -> PathBuf {
+    PathBuf::from(env!("CARGO_MANIFEST_DIR"))
+        .join("tests/fixtures")
+        .join(format!("{name}.json"))
+}
+
+/// Loads a scenario from `tests/fixtures/{name}.json`
+pub fn load(name: &str) -> Scenario {
+    let path = fixture_path(name);
+    let json = std::fs::read_to_string(&path)
+        .unwrap_or_else(|error| panic!("cannot read {}: {error}", path.display()));
+    serde_json::from_str(&json)
+        .unwrap_or_else(|error| panic!("invalid fixture {}: {error}", path.display()))
+}
+
+async fn replay(name: &str) -> (Client, FixtureServer) {
+    let scenario = load(name);
+    let mut server = mockito::Server::new_async().await;
+    let mut mocks = Vec::with_capacity(scenario.routes.len());
+    for route in scenario.routes {
+        let mut mock = server
+            .mock(route.method.as_str(), route.path.as_str())
+            .match_query(mockito::Matcher::Any)
+            .with_status(route.status.into());
+        if let Some(request) = route.request {
+            mock = mock.match_body(mockito::Matcher::PartialJson(request));
+        }
+        mock = match route.body {
+            Body::Json(body) => mock
+                .with_header("content-type", "application/json")
+                .with_body(body.to_string()),
+            Body::Events(events) => mock
+                .with_header("content-type", "text/event-stream")
+                .with_body(render_events(&events)),
+        };
+        let label = format!("{} {}", route.method, route.path);
+        mocks.push((label, mock.create_async().await));
+    }
+    let client = Client::builder()
+        .api_key("sk-fixture")
+        .base_url(server.url())
+        .build()
+        .expect("fixture client");
+    let mode = Mode::Replay {
+        _server: server,
+        mocks,
+    };
+    (client, FixtureServer { mode })
+}
+
+/// Renders events the way the API streams them
+pub fn render_events(events: &[Value]) -> String {
+    events
+        .iter()
+        .map(|event| match event.get("type").and_then(Value::as_str) {
+            Some(kind) => format!("event: {kind}\ndata: {event}\n\n"),
+            None => format!("data: {event}\n\n"),
+        })
+        .collect()
+}
+
+struct Upstream {
+    http: reqwest::Client,
+    base_url: String,
+    api_key: String,
+    routes: Arc<Mutex<Vec<Route>>>,
+}
+
+async fn record(name: &str, api_key: String) -> (Client, FixtureServer) {
+    use hyper::service::{make_service_fn, service_fn};
+
+    let routes = Arc::new(Mutex::new(Vec::new()));
+    let upstream = Arc::new(Upstream {
+        http: reqwest::Client::new(),
+        base_url: std::env::var("OPENAI_BASE_URL").unwrap_or_else(|_| LIVE_BASE_URL.to_string()),
+        api_key: api_key.clone(),
+        routes: Arc::clone(&routes),
+    });
+    let make_service = make_service_fn(move |_| {
+        let upstream = Arc::clone(&upstream);
+        async move {
+            Ok::<_, std::convert::Infallible>(service_fn(move |request| {
+                forward(Arc::clone(&upstream), request)
+            }))
+        }
+    });
+    let server = hyper::Server::bind(&([127, 0, 0, 1], 0).into()).serve(make_service);
+    let address = server.local_addr();
+    let (shutdown, stopped) = tokio::sync::oneshot::channel::<()>();
+    tokio::spawn(server.with_graceful_shutdown(async {
+        let _ = stopped.await;
+    }));
+
+    let client = Client::builder()
+        .api_key(api_key)
+        .base_url(format!("http://{address}"))
+        .build()
+        .expect("recording client");
+    let mode = Mode::Record {
+        name: name.to_string(),
+        routes,
+        shutdown: Some(shutdown),
+    };
+    (client, FixtureServer { mode })
+}
+
+/// Sends a request on to the live API, records the exchange and relays the response
+async fn forward(
+    upstream: Arc<Upstream>,
+    request: hyper::Request<hyper::Body>,
+) -> Result<hyper::Response<hyper::Body>, std::convert::Infallible> {
+    let method = request.method().clone();
+    let path = request.uri().path().to_string();
+    let path_and_query = request
+        .uri()
+        .path_and_query()
+        .map_or_else(|| path.clone(), ToString::to_string);
+    let content_type = request.headers().get(hyper::header::CONTENT_TYPE).cloned();
+    let body = hyper::body::to_bytes(request.into_body())
+        .await
+        .unwrap_or_default();
+
+    let mut outgoing = upstream
+        .http
+        .request(
+            method.clone(),
+            format!("{}{path_and_query}", upstream.base_url),
+        )
+        .bearer_auth(&upstream.api_key)
+        .body(body.clone());
+    if let Some(content_type) = &content_type {
+        outgoing = outgoing.header(hyper::header::CONTENT_TYPE, content_type);
+    }
+    let response = match outgoing.send().await {
+        Ok(response) => response,
+        Err(error) => {
+            return Ok(hyper::Response::builder()
+                .status(502)
+                .body(hyper::Body::from(error.to_string()))
+                .expect("valid response"));
+        }
+    };
+    let status = response.status();
+    let response_type = response.headers().get(hyper::header::CONTENT_TYPE).cloned();
+    let response_body = response.bytes().await.unwrap_or_default();
+
+    let is_json = |value: Option<&hyper::header::HeaderValue>| {
+        value
+            .and_then(|v| v.to_str().ok())
+            .is_some_and(|v| v.starts_with("application/json"))
+    };
+    let is_stream = response_type
+        .as_ref()
+        .and_then(|v| v.to_str().ok())
+        .is_some_and(|v| v.starts_with("text/event-stream"));
+    let recorded_body = if is_stream {
+        Body::Events(parse_events(&response_body))
+    } else {
+        Body::Json(
+            serde_json::from_slice(&response_body)
+                .unwrap_or_else(|_| Value::String(String::from_utf8_lossy(&response_body).into())),
+        )
+    };
+    upstream.routes.lock().unwrap().push(Route {
+        method: method.to_string(),
+        path,
+        request: is_json(content_type.as_ref())
+            .then(|| serde_json::from_slice(&body).ok())
+            .flatten(),
+        status: status.as_u16(),
+        body: recorded_body,
+    });
+
+    let mut relayed = hyper::Response::builder().status(status);
+    if let Some(response_type) = response_type {
+        relayed = relayed.header(hyper::header::CONTENT_TYPE, response_type);
+    }
+    Ok(relayed
+        .body(hyper::Body::from(response_body))
+        .expect("valid response"))
+}
+
+/// Collects the JSON `data:` payloads of a buffered event stream
+fn parse_events(body: &[u8]) -> Vec<Value> {
+    String::from_utf8_lossy(body)
+        .lines()
+        .filter_map(|line| line.strip_prefix("data:"))
+        .filter_map(|data| serde_json::from_str(data.trim()).ok())
+        .collect()
+}
+
+/// Replaces object IDs with stable placeholders and redacts API keys
+#[derive(Default)]
+struct Scrubber {
+    ids: HashMap<String, String>,
+}
+
+impl Scrubber {
+    fn scenario(&mut self, scenario: Scenario) -> Scenario {
+        let routes = scenario
+            .routes
+            .into_iter()
+            .map(|route| Route {
+                path: self.text(&route.path),
+                request: route.request.map(|request| self.value(request)),
+                body: match route.body {
+                    Body::Json(body) => Body::Json(self.value(body)),
+                    Body::Events(events) => {
+                        Body::Events(events.into_iter().map(|e| self.value(e)).collect())
+                    }
+                },
+                ..route
+            })
+            .collect();
+        Scenario { routes }
+    }
+
+    fn value(&mut self, value: Value) -> Value {
+        match value {
+            Value::String(text) => Value::String(self.text(&text)),
+            Value::Array(items) => Value::Array(items.into_iter().map(|v| self.value(v)).collect()),
+            Value::Object(fields) => Value::Object(
+                fields
+                    .into_iter()
+                    .map(|(key, value)| (key, self.value(value)))
+                    .collect(),
+            ),
+            other => other,
+        }
+    }
+
+    fn text(&mut self, text: &str) -> String {
+        let mut out = String::with_capacity(text.len());
+        let mut rest = text;
+        while let Some(c) = rest.chars().next() {
+            let at_boundary = out
+                .chars()
+                .next_back()
+                .is_none_or(|prev| !(prev.is_ascii_alphanumeric() || prev == '_' || prev == '-'));
+            if at_boundary {
+                if let Some((token, len)) = self.token(rest) {
+                    out.push_str(&token);
+                    rest = &rest[len..];
+                    continue;
+                }
+            }
+            out.push(c);
+            rest = &rest[c.len_utf8()..];
+        }
+        out
+    }
+
+    /// Returns the replacement for an ID or key at the start of `text`, and its length
+    fn token(&mut self, text: &str) -> Option<(String, usize)> {
+        let run = |from: usize, allowed: fn(char) -> bool| {
+            text[from..]
+                .find(|c: char| !allowed(c))
+                .map_or(text.len(), |end| from + end)
+        };
+        if text.starts_with("sk-") {
+            let end = run(3, |c| c.is_ascii_alphanumeric() || c == '-' || c == '_');
+            return (end - 3 >= 20).then(|| ("sk-REDACTED".to_string(), end));
+        }
+        let prefix = ID_PREFIXES
+            .iter()
+            .find(|prefix| text.starts_with(**prefix))?;
+        let end = run(prefix.len(), |c| c.is_ascii_alphanumeric());
+        if end - prefix.len() < MIN_ID_SUFFIX_LEN {
+            return None;
+        }
+        let count = self.ids.len() + 1;
+        let replacement = self
+            .ids
+            .entry(text[..end].to_string())
+            .or_insert_with(|| format!("{prefix}fixture{count:03}"))
+            .clone();
+        Some((replacement, end))
+    }
+}
+
+#[test]
+fn scrubber_keeps_references_consistent() {
+    let mut scrubber = Scrubber::default();
+    let scenario = scrubber.scenario(Scenario {
+        routes: vec![
+            Route {
+                method: "POST".into(),
+                path: "/responses".into(),
+                request: Some(serde_json::json!({"input": "key sk-proj-abcdefghijklmnopqrstuvwx"})),
+                status: 200,
+                body: Body::Json(serde_json::json!({
+                    "id": "resp_68af0c3e1b5c8190", "output": [{"call_id": "call_Zx81Lk2Qp0sN"}]
+                })),
+            },
+            Route {
+                method: "GET".into(),
+                path: "/responses/resp_68af0c3e1b5c8190".into(),
+                request: None,
+                status: 200,
+                body: Body::Events(vec![
+                    serde_json::json!({"previous_response_id": "resp_68af0c3e1b5c8190"}),
+                ]),
+            },
+        ],
+    });
+    let json = serde_json::to_value(&scenario).unwrap();
+    assert_eq!(json["routes"][0]["request"]["input"], "key sk-REDACTED");
+    assert_eq!(json["routes"][0]["json"]["id"], "resp_fixture001");
+    assert_eq!(
+        json["routes"][0]["json"]["output"][0]["call_id"],
+        "call_fixture002"
+    );
+    assert_eq!(json["routes"][1]["path"], "/responses/resp_fixture001");
+    assert_eq!(
+        json["routes"][1]["events"][0]["previous_response_id"],
+        "resp_fixture001"
+    );
+}